@@ -0,0 +1,32 @@
+//! Compares generation time across algorithms, maze types and sizes with Criterion.
+//!
+//! Run with `cargo bench --features benchmark --bench algorithms`; for a quick table without Criterion's
+//! statistics, call `mazer::benchmark::run` instead.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+use mazer::algorithms::MazeAlgorithm;
+use mazer::benchmark::{empty_grid, BenchmarkConfig};
+
+fn generation(c: &mut Criterion) {
+    let config = BenchmarkConfig::default();
+    for &maze_type in &config.maze_types {
+        let mut group = c.benchmark_group(maze_type.name());
+        group.sample_size(10);
+        for &size in &config.sizes {
+            for &algorithm in MazeAlgorithm::all().iter().filter(|algorithm| algorithm.supports(maze_type)) {
+                group.bench_with_input(BenchmarkId::new(algorithm.name(), size), &size, |b, &size| {
+                    b.iter_batched(
+                        || empty_grid(maze_type, size).expect("Failed to construct grid"),
+                        |mut grid| algorithm.generate(&mut grid).map(|_| ()).expect("Failed to generate maze"),
+                        BatchSize::LargeInput,
+                    )
+                });
+            }
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, generation);
+criterion_main!(benches);
@@ -0,0 +1,64 @@
+//! Times grid construction, comparing the in-place neighbor assignment done by `Grid::new`
+//! against the previous approach of cloning each cell, updating it and writing it back with `Grid::set`.
+//!
+//! Run with `cargo bench --bench grid_construction`.
+
+use std::time::{Duration, Instant};
+
+use mazer::cell::{Coordinates, MazeType};
+use mazer::grid::Grid;
+
+const SIZES: [usize; 3] = [100, 250, 500];
+
+fn new_grid(maze_type: MazeType, size: usize) -> Grid {
+    let start = Coordinates { x: 0, y: 0 };
+    let goal = Coordinates { x: size - 1, y: size - 1 };
+    Grid::new(maze_type, size, size, start, goal, false).expect("Failed to construct grid")
+}
+
+/// Re-assigns every cell's neighbors the way construction used to: clone, mutate, then `set`.
+fn clone_and_set_neighbors(grid: &mut Grid) {
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            if let Ok(cell) = grid.get_by_coords(x, y) {
+                let mut cell = cell.clone();
+                let neighbors = cell.neighbors_by_direction.iter().collect();
+                cell.set_neighbors(neighbors);
+                grid.set(cell).expect("Failed to set cell");
+            }
+        }
+    }
+}
+
+/// Re-assigns every cell's neighbors in place, as `Grid::new` now does.
+fn in_place_neighbors(grid: &mut Grid) {
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            if let Ok(cell) = grid.get_mut_by_coords(x, y) {
+                let neighbors = cell.neighbors_by_direction.iter().collect();
+                cell.set_neighbors(neighbors);
+            }
+        }
+    }
+}
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let started = Instant::now();
+    f();
+    started.elapsed()
+}
+
+fn main() {
+    for maze_type in [MazeType::Orthogonal, MazeType::Sigma, MazeType::Delta] {
+        for size in SIZES {
+            let construction = time(|| { new_grid(maze_type, size); });
+            let mut grid = new_grid(maze_type, size);
+            let cloned = time(|| clone_and_set_neighbors(&mut grid));
+            let in_place = time(|| in_place_neighbors(&mut grid));
+            println!(
+                "{:<10} {:>4}x{:<4} Grid::new {:>10.2?} | neighbor pass: clone + set {:>10.2?}, in place {:>10.2?}",
+                maze_type.name(), size, size, construction, cloned, in_place
+            );
+        }
+    }
+}
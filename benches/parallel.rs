@@ -0,0 +1,33 @@
+//! Compares serial Kruskal's with `ParallelKruskals`, which a request's `parallel` flag selects, across maze
+//! sizes with Criterion.
+//!
+//! Run with `cargo bench --features benchmark,rayon --bench parallel`; without the `rayon` feature the strips
+//! of `ParallelKruskals` are carved one after another, so the comparison shows only its merging overhead.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+use mazer::algorithms::MazeAlgorithm;
+use mazer::benchmark::empty_grid;
+use mazer::cell::MazeType;
+
+const SIZES: [usize; 3] = [64, 128, 256];
+
+fn kruskals(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Kruskals");
+    group.sample_size(10);
+    for size in SIZES {
+        for algorithm in [MazeAlgorithm::Kruskals, MazeAlgorithm::ParallelKruskals] {
+            group.bench_with_input(BenchmarkId::new(algorithm.name(), size), &size, |b, &size| {
+                b.iter_batched(
+                    || empty_grid(MazeType::Orthogonal, size).expect("Failed to construct grid"),
+                    |mut grid| algorithm.generate(&mut grid).map(|_| ()).expect("Failed to generate maze"),
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, kruskals);
+criterion_main!(benches);
@@ -0,0 +1,57 @@
+package com.mazer;
+
+/**
+ * Native methods of the mazer library, built with its "jni" feature.
+ *
+ * A maze is held as an opaque long handle, 0 standing for no maze, which must be released with destroy.
+ * Requests, saved mazes and cells are passed as JSON strings, and the compact binary format as byte arrays.
+ */
+public final class Mazer {
+    static {
+        System.loadLibrary("mazer");
+    }
+
+    private Mazer() {}
+
+    /** Generates a maze from a JSON request, throwing an IllegalArgumentException if the request is invalid. */
+    public static native long generateMaze(String requestJson);
+
+    /** Releases a maze. Does nothing for the 0 handle. */
+    public static native void destroy(long maze);
+
+    /** The maze, including its links, visited state and move history, as JSON, or null if serialization fails. */
+    public static native String serializeMaze(long maze);
+
+    /** Restores a maze saved by serializeMaze, throwing an IllegalArgumentException if it cannot be restored. */
+    public static native long deserializeMaze(String savedJson);
+
+    /** The maze in the compact binary format, or null if it cannot be encoded. */
+    public static native byte[] toBytes(long maze);
+
+    /** Decodes a maze encoded by toBytes, throwing an IllegalArgumentException if the bytes are not valid. */
+    public static native long fromBytes(byte[] bytes);
+
+    /** The maze's cells as a JSON array, or null for the 0 handle. */
+    public static native String getCellsJson(long maze);
+
+    /** The number of generation steps captured, or 0 if capture_steps was not requested. */
+    public static native int getGenerationStepsCount(long maze);
+
+    /** The cells of a generation step as a JSON array, or null if the step index is out of range. */
+    public static native String getGenerationStepCellsJson(long maze, int stepIndex);
+
+    /** Moves the active cell in the named direction (e.g. "Up"), returning false if the move is unavailable. */
+    public static native boolean makeMove(long maze, String direction);
+
+    /** The numeric code of the next move toward the goal, or -1 if no hint is available. */
+    public static native int getHint(long maze);
+
+    /** Reverses the most recent move, returning false if there is none. */
+    public static native boolean undo(long maze);
+
+    /** Re-applies the most recently undone move, returning false if there is none. */
+    public static native boolean redo(long maze);
+
+    /** The maze's report as JSON, or null for the 0 handle. */
+    public static native String getStatsJson(long maze);
+}
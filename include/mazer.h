@@ -13,12 +13,22 @@ extern "C" {
  * The actual definitions of these types are hidden from the Swift side.
  */
 typedef struct Grid Grid;
+typedef struct BackgroundGeneration BackgroundGeneration;
+typedef struct StepPlayer StepPlayer;
+typedef struct StepCells StepCells;
+
+typedef struct FFICoordinates {
+    size_t x;
+    size_t y;
+} FFICoordinates;
+
+#define MAZER_FFI_CELL_VERSION 2  /* layout version of FFICell; version 2 added open_walls, linked_coords and version */
 
 typedef struct FFICell {
     size_t x;
     size_t y;
     const char* maze_type;
-    const char** linked;
+    const char** linked;      /* user-facing open wall directions, kept for existing clients; prefer open_walls */
     size_t linked_len;
     int32_t distance;
     bool is_start;
@@ -29,8 +39,56 @@ typedef struct FFICell {
     bool on_solution_path;
     const char* orientation;
     bool is_square;
+    int32_t distance_band;
+    float heat;
+    int32_t axial_q;
+    int32_t axial_r;
+    bool is_discovered;
+    const char** open_walls;  /* user-facing open wall directions, e.g. "North" */
+    size_t open_walls_len;
+    const FFICoordinates* linked_coords;  /* coordinates of the linked cells */
+    size_t linked_coords_len;
+    uint32_t version;         /* MAZER_FFI_CELL_VERSION */
 } FFICell;
 
+typedef struct FFIMoveRecord {
+    size_t from_x;
+    size_t from_y;
+    size_t to_x;
+    size_t to_y;
+    int32_t requested;
+    int32_t direction;
+    bool backtrack;
+} FFIMoveRecord;
+
+typedef struct FFIGridEvent {
+    int32_t kind;     /* one of the MAZER_EVENT_* constants */
+    size_t x;
+    size_t y;
+    size_t other_x;
+    size_t other_y;
+    bool is_visited;
+} FFIGridEvent;
+
+typedef struct FFIDifficultyReport {
+    size_t solution_length;
+    size_t dead_ends;
+    size_t junctions;
+    size_t decision_points;
+    double branching_factor;
+    double average_corridor_length;
+    double score;
+    int32_t level;
+} FFIDifficultyReport;
+
+typedef struct FFISessionStats {
+    uint64_t elapsed_ms;
+    size_t moves;
+    size_t wrong_turns;
+    size_t backtracks;
+    bool paused;
+} FFISessionStats;
+
 /**
  * Generates a maze from a JSON request.
  *
@@ -54,6 +112,62 @@ Grid* mazer_generate_maze(const char *request_json);
  */
 void mazer_destroy(Grid *maze);
 
+/**
+ * Serializes a maze, including its links, visited state and move history, to a JSON string.
+ *
+ * The returned string can later be passed to mazer_deserialize_maze to restore the maze.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return A null-terminated JSON string, or NULL if the grid pointer is NULL or serialization fails.
+ *         Release with mazer_free_string.
+ */
+char* mazer_serialize_maze(const Grid *grid);
+
+/**
+ * Restores a maze previously serialized with mazer_serialize_maze.
+ *
+ * @param saved_json A null-terminated C string containing the serialized maze.
+ * @return A pointer to the restored Grid (release with mazer_destroy), or NULL on failure.
+ */
+Grid* mazer_deserialize_maze(const char *saved_json);
+
+/**
+ * Frees a string previously returned by the library, such as from mazer_serialize_maze.
+ *
+ * @param ptr A pointer to the null-terminated C string to be freed.
+ */
+void mazer_free_string(char *ptr);
+
+/* Error categories reported by mazer_last_error_category. */
+#define MAZER_ERROR_INVALID_REQUEST 1  /* the request asked for something invalid, which the caller can correct */
+#define MAZER_ERROR_UNSUPPORTED 2      /* the request asked for something the library does not do */
+#define MAZER_ERROR_INTERNAL 3         /* the failure was not caused by the request */
+
+/**
+ * Retrieves the code of the most recent error on the calling thread, recorded when a function such as
+ * mazer_generate_maze fails. Codes are stable across releases. Successful calls do not clear the error, so
+ * check it only after a call has failed.
+ *
+ * @return The error code, or 0 if no error has been recorded on the calling thread.
+ */
+uint32_t mazer_last_error_code();
+
+/**
+ * Retrieves the category of the most recent error on the calling thread.
+ *
+ * @return MAZER_ERROR_INVALID_REQUEST, MAZER_ERROR_UNSUPPORTED or MAZER_ERROR_INTERNAL, or 0 if no error has
+ *         been recorded on the calling thread.
+ */
+uint32_t mazer_last_error_category();
+
+/**
+ * Retrieves a message describing the most recent error on the calling thread, suitable for showing users.
+ *
+ * @return A null-terminated string, or NULL if no error has been recorded on the calling thread. Release with
+ *         mazer_free_string.
+ */
+char* mazer_last_error_message();
+
 /**
  * Retrieves the cells of the maze.
  *
@@ -66,6 +180,40 @@ void mazer_destroy(Grid *maze);
  */
 FFICell* mazer_get_cells(Grid *maze, size_t *length);
 
+/**
+ * Retrieves the grid's revision, which increases whenever the state of any cell changes.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return The current revision, or 0 if the grid pointer is NULL.
+ */
+uint64_t mazer_get_revision(const Grid *grid);
+
+/**
+ * Retrieves only the cells whose state changed after the specified revision, so clients can redraw just
+ * those cells rather than the whole maze after every move. Clients record mazer_get_revision after each
+ * fetch and pass it as since_revision on the next.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param since_revision The revision as of the client's previous fetch.
+ * @param length A pointer to a size_t variable where the function will store the number of cells.
+ * @return A pointer to an array of FFICell structures, or NULL if either pointer is NULL.
+ *         Release with mazer_free_cells.
+ */
+FFICell* mazer_get_changed_cells(const Grid *grid, uint64_t since_revision, size_t *length);
+
+/**
+ * Shifts the maze's walls mid-game, opening count randomly chosen walls and closing another passage for each,
+ * while keeping every cell (and so the goal) reachable.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param count The number of walls to open, and of passages to close.
+ * @param seed Seed choosing the walls and passages, so the same seed always shifts the same walls.
+ * @param length A pointer to a size_t variable where the function will store the number of changed cells.
+ * @return A pointer to an array of FFICell structures for the cells changed by the shift (release with
+ *         mazer_free_cells), or NULL if either pointer is NULL or the walls could not be shifted.
+ */
+FFICell* mazer_shift_walls(Grid *grid, size_t count, uint64_t seed, size_t *length);
+
 /**
  * Frees an array of FFICell.
  *
@@ -102,9 +250,226 @@ size_t mazer_get_generation_steps_count(Grid *grid);
  * @param length A pointer to a size_t variable where the function will store the number of cells.
  * @return A pointer to an array of FFICell structures for the specified step, or NULL if the input
  *         pointers are invalid or the step index is out of range.
+ *
+ * Each call rebuilds and converts the whole step, so to animate generation use mazer_step_cells_new, which
+ * converts just the cells each step changed.
  */
 FFICell* mazer_get_generation_step_cells(Grid *grid, size_t step_index, size_t *length);
 
+/**
+ * Creates a cache of the cells of the maze's generation steps for animating generation. Unlike
+ * mazer_get_generation_step_cells, which converts every cell of the step requested on each call, the cache
+ * keeps the cells of the step last retrieved and converts only those changed since, so retrieving the steps
+ * in order allocates little more than the cells each step carved.
+ *
+ * @param grid A pointer to the Grid instance, which must outlive the cache.
+ * @return A pointer to the cache, or NULL if the grid pointer is NULL or no generation steps were captured.
+ *         Release with mazer_step_cells_destroy.
+ */
+StepCells* mazer_step_cells_new(const Grid *grid);
+
+/**
+ * Releases a cache created by mazer_step_cells_new, along with any cells retrieved from it. Does nothing if
+ * cells is NULL.
+ *
+ * @param cells A pointer to the cache, which is invalid after this call.
+ */
+void mazer_step_cells_destroy(StepCells *cells);
+
+/**
+ * Retrieves the cells of a generation step from a cache created by mazer_step_cells_new.
+ *
+ * @param cells A pointer to the cache.
+ * @param step_index The index of the generation step.
+ * @param length A pointer to store the number of cells.
+ * @return A pointer to an array of FFICell owned by the cache, valid only until the next call with the cache
+ *         or its release, or NULL if either pointer is NULL or the step index is out of range. Do not release
+ *         with mazer_free_cells.
+ */
+const FFICell* mazer_step_cells_get(StepCells *cells, size_t step_index, size_t *length);
+
+/**
+ * Plays out a solving strategy from the start, recording the maze after every step for animation.
+ *
+ * In each recorded step the solver's cell is active, its current trail is visited and every cell it has
+ * entered has been visited; for "DeadEndFilling", the cells filled in have been visited and the last step
+ * marks the route found as the solution path.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param strategy A null-terminated C string naming the strategy: "LeftHand", "RightHand", "Pledge",
+ *        "DeadEndFilling" or "Tremaux".
+ * @return The number of solving steps recorded, or 0 if either pointer is NULL, the strategy is not
+ *         recognized, or the maze's width or height exceeds 100.
+ */
+size_t mazer_capture_solver(Grid *grid, const char *strategy);
+
+/**
+ * Retrieves the number of solving steps recorded by mazer_capture_solver.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return The number of solving steps, or 0 if none have been recorded or the grid is invalid.
+ */
+size_t mazer_get_solving_steps_count(Grid *grid);
+
+/**
+ * Retrieves the cells for a specific solving step recorded by mazer_capture_solver.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param step_index The index of the solving step to retrieve.
+ * @param length A pointer to a size_t variable where the function will store the number of cells.
+ * @return A pointer to an array of FFICell structures for the specified step, or NULL if the input
+ *         pointers are invalid or the step index is out of range. Release with mazer_free_cells.
+ */
+FFICell* mazer_get_solving_step_cells(Grid *grid, size_t step_index, size_t *length);
+
+/**
+ * Retrieves the cells of the maze as a single JSON string, an alternative to mazer_get_cells.
+ *
+ * The string holds an array with an object per cell, with the same fields as FFICell except linked, version
+ * and the array lengths. linked_coords holds { "x", "y" } objects, and distance_band and heat are null when
+ * unassigned.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return A null-terminated JSON string, or NULL if the grid pointer is NULL or serialization fails.
+ *         Release with mazer_free_string.
+ */
+char* mazer_get_cells_json(const Grid *grid);
+
+/**
+ * Retrieves the cells of a generation step as a single JSON string, in the form of mazer_get_cells_json.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param step_index The index of the generation step.
+ * @return A null-terminated JSON string, or NULL if the grid pointer is NULL, no generation steps were
+ *         captured, the step index is out of range, or serialization fails. Release with mazer_free_string.
+ */
+char* mazer_get_generation_step_cells_json(const Grid *grid, size_t step_index);
+
+/**
+ * Retrieves what happened in a generation step as a JSON object: the algorithm's "phase" ("Start", "Carve",
+ * "Wall", "Kill" or "Hunt"), the "linked" and "unlinked" passages as pairs of coordinates, and the "frontier"
+ * cells the algorithm will carve from or into next.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param step_index The index of the generation step.
+ * @return A null-terminated JSON string, or NULL if the grid pointer is NULL, no generation steps were
+ *         captured, the step index is out of range, or serialization fails. Release with mazer_free_string.
+ */
+char* mazer_get_generation_step_details_json(const Grid *grid, size_t step_index);
+
+/**
+ * Creates a player of the maze's generation steps, which starts paused on the first step. Advance it with
+ * mazer_step_player_tick each frame while playing, and draw the cells of its current step.
+ *
+ * @param grid A pointer to the Grid instance, which must outlive the player.
+ * @return A pointer to the player, or NULL if the grid pointer is NULL or no generation steps were captured.
+ *         Release with mazer_step_player_destroy.
+ */
+StepPlayer* mazer_step_player_new(const Grid *grid);
+
+/**
+ * Releases a player created by mazer_step_player_new. Does nothing if player is NULL.
+ *
+ * @param player A pointer to the player, which is invalid after this call.
+ */
+void mazer_step_player_destroy(StepPlayer *player);
+
+/**
+ * Starts the player advancing as time passes, from the first step if the last has been reached.
+ *
+ * @param player A pointer to the player.
+ */
+void mazer_step_player_play(StepPlayer *player);
+
+/**
+ * Stops the player advancing, staying on the current step.
+ *
+ * @param player A pointer to the player.
+ */
+void mazer_step_player_pause(StepPlayer *player);
+
+/**
+ * Moves the player to a step.
+ *
+ * @param player A pointer to the player.
+ * @param step_index The index of the step, past the last step moving to the last.
+ * @return The index of the player's new current step, or 0 if player is NULL.
+ */
+size_t mazer_step_player_seek(StepPlayer *player, size_t step_index);
+
+/**
+ * Sets how many steps the player advances per second, 30 until set.
+ *
+ * @param player A pointer to the player.
+ * @param steps_per_second The speed, ignored unless positive.
+ */
+void mazer_step_player_set_speed(StepPlayer *player, double steps_per_second);
+
+/**
+ * Advances a playing player by as many steps as the time passed allows at its speed, pausing it on reaching
+ * the last step.
+ *
+ * @param player A pointer to the player.
+ * @param elapsed_millis The milliseconds passed since the previous tick.
+ * @return Whether the current step changed, so the maze needs redrawing.
+ */
+bool mazer_step_player_tick(StepPlayer *player, uint64_t elapsed_millis);
+
+/**
+ * Retrieves the index of the player's current step, or 0 if player is NULL.
+ */
+size_t mazer_step_player_index(const StepPlayer *player);
+
+/**
+ * Reports whether the player is advancing as time passes, which is false if player is NULL.
+ */
+bool mazer_step_player_is_playing(const StepPlayer *player);
+
+/**
+ * Retrieves the cells of the player's current step.
+ *
+ * @param player A pointer to the player.
+ * @param length A pointer to store the number of cells.
+ * @return A pointer to an array of FFICell, or NULL if either pointer is NULL. Release with mazer_free_cells.
+ */
+FFICell* mazer_step_player_cells(const StepPlayer *player, size_t *length);
+
+/**
+ * Retrieves what happened in the player's current step as JSON, in the form of
+ * mazer_get_generation_step_details_json.
+ *
+ * @param player A pointer to the player.
+ * @return A null-terminated JSON string, or NULL if player is NULL or serialization fails. Release with
+ *         mazer_free_string.
+ */
+char* mazer_step_player_details_json(const StepPlayer *player);
+
+/**
+ * Assigns distance bands to every cell of the maze.
+ *
+ * Groups the maze's cells into 'bands' bands by normalized distance from the start cell. Each cell's
+ * band id is then reported in the distance_band field of subsequently retrieved FFICells
+ * (-1 when no bands have been assigned).
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param bands The number of bands to split the cells into; must be at least 1.
+ * @return true if bands were assigned, or false if the grid pointer is NULL or bands is 0.
+ */
+bool mazer_assign_distance_bands(Grid *grid, size_t bands);
+
+/**
+ * Assigns heatmap intensities to every cell of the maze.
+ *
+ * Normalizes each cell's distance from the origin cell to a 0.0-1.0 intensity, which is then reported
+ * in the heat field of subsequently retrieved FFICells (-1.0 when no heatmap has been assigned).
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param origin_x The x-coordinate of the cell to measure distances from.
+ * @param origin_y The y-coordinate of the cell to measure distances from.
+ * @return true if the heatmap was assigned, or false if the grid pointer is NULL or the origin is not a cell of the maze.
+ */
+bool mazer_assign_heatmap(Grid *grid, size_t origin_x, size_t origin_y);
+
 /**
  * Updates the maze by performing a move in the specified direction.
  *
@@ -145,6 +510,492 @@ size_t mazer_get_generation_steps_count(Grid *grid);
  */
 FFICell* mazer_get_generation_step_cells(Grid *grid, size_t step_index, size_t *length);
 
+/**
+ * Moves to the open neighbor of the active cell nearest the specified cell, for touch interfaces where the
+ * user taps a cell, or swipes toward one, rather than choosing a direction.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param x The x-coordinate of the tapped cell.
+ * @param y The y-coordinate of the tapped cell.
+ * @return The numeric code of the direction taken (see mazer_get_hint), or -1 if the grid pointer is NULL,
+ *         the coordinates are not a cell of the maze, or no open passage leads toward the cell.
+ */
+int32_t mazer_move_toward(Grid *grid, size_t x, size_t y);
+
+/**
+ * Moves in a direction, then keeps following the passage until reaching a junction, a dead end or the goal,
+ * marking each cell passed through visited.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param direction A null-terminated C string indicating the direction of the first move.
+ * @param length A pointer to a size_t where the number of cells traversed will be stored.
+ * @return A pointer to an array of FFICoordinates listing the cells traversed, ending with the cell stopped at,
+ *         or NULL if any pointer is null, the direction is not recognized, or the first move is unavailable.
+ *         Release with mazer_free_coordinates.
+ */
+FFICoordinates* mazer_move_until_junction(Grid *grid, const char *direction, size_t *length);
+
+/**
+ * Sets how subsequent moves in a direction with no open passage are resolved.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param policy A null-terminated C string naming the policy, "Fallback" (the default, trying adjacent
+ *        directions) or "Strict" (only the exact direction), or a JSON policy such as
+ *        {"Custom": [["Left", ["UpperLeft"]]]} listing each direction's fallbacks.
+ * @return true if the policy was set, or false if either pointer is NULL or the policy is not recognized.
+ */
+bool mazer_set_move_policy(Grid *grid, const char *policy);
+
+/**
+ * Suggests the next move toward the goal from the currently active cell.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return The numeric code of the hinted direction (0 = Up, 1 = Right, 2 = Down, 3 = Left, 4 = UpperRight,
+ *         5 = LowerRight, 6 = LowerLeft, 7 = UpperLeft, 8 = Above, 9 = Below, 10 = Inward, 11 = Outward,
+ *         12 = Clockwise, 13 = CounterClockwise), or -1 if the grid pointer is NULL, the active cell is
+ *         already the goal, or no hint is available.
+ */
+int32_t mazer_get_hint(const Grid *grid);
+
+/**
+ * Reverses the most recent user move.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return true if a move was undone, or false if the grid pointer is NULL or there are no moves to undo.
+ */
+bool mazer_undo(Grid *grid);
+
+/**
+ * Re-applies the most recently undone user move.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return true if a move was redone, or false if the grid pointer is NULL or there are no undone moves.
+ */
+bool mazer_redo(Grid *grid);
+
+/**
+ * Retrieves the number of user moves in the maze's move history.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return The number of moves made (excluding undone moves), or 0 if the grid pointer is NULL.
+ */
+size_t mazer_move_count(const Grid *grid);
+
+/**
+ * Retrieves the user moves made so far, in the order they were taken.
+ *
+ * Directions are reported as numeric codes, using the same mapping as mazer_get_hint.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param length A pointer to a size_t variable where the function will store the number of moves.
+ * @return A pointer to an array of FFIMoveRecord, or NULL if either pointer is NULL. Release with mazer_free_moves.
+ */
+FFIMoveRecord* mazer_get_moves(const Grid *grid, size_t *length);
+
+/**
+ * Frees an array of FFIMoveRecord returned by mazer_get_moves.
+ *
+ * @param ptr A pointer to the array of FFIMoveRecord to be freed.
+ * @param length The number of FFIMoveRecord elements in the array.
+ */
+void mazer_free_moves(FFIMoveRecord *ptr, size_t length);
+
+#define MAZER_EVENT_CELL_LINKED 0      /* a passage was opened between (x, y) and (other_x, other_y) */
+#define MAZER_EVENT_CELL_UNLINKED 1    /* the passage between (x, y) and (other_x, other_y) was closed */
+#define MAZER_EVENT_ACTIVE_MOVED 2     /* the active cell moved from (x, y) to (other_x, other_y) */
+#define MAZER_EVENT_VISITED_CHANGED 3  /* (x, y) joined the user's trail, or left it when is_visited is false */
+
+/**
+ * Takes the changes made to the maze since the previous call, oldest first, so user interfaces can update
+ * just the cells affected rather than comparing every cell after each operation. Changes are logged from
+ * the first call on, which returns none.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param length A pointer to a size_t variable where the function will store the number of events.
+ * @return A pointer to an array of FFIGridEvent, or NULL if either pointer is NULL. Release with
+ *         mazer_free_events.
+ */
+FFIGridEvent* mazer_drain_events(Grid *grid, size_t *length);
+
+/**
+ * Frees an array of FFIGridEvent returned by mazer_drain_events.
+ *
+ * @param ptr A pointer to the array of FFIGridEvent to be freed.
+ * @param length The number of FFIGridEvent elements in the array.
+ */
+void mazer_free_events(FFIGridEvent *ptr, size_t length);
+
+/**
+ * Retrieves the cells of the solution path, in order from the start to the goal, so clients can animate the
+ * path once the user finishes.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param length A pointer to a size_t variable where the function will store the number of cells on the path.
+ * @return A pointer to an array of FFICoordinates, or NULL if either pointer is NULL. The array is empty when
+ *         the goal is unreachable. Release with mazer_free_coordinates.
+ */
+FFICoordinates* mazer_get_solution_path(const Grid *grid, size_t *length);
+
+/**
+ * Sets the cost of entering a cell in weighted pathfinding, such as a higher cost for mud or water.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param x The x-coordinate of the cell.
+ * @param y The y-coordinate of the cell.
+ * @param weight The cost of entering the cell, or 0 to return it to the default cost of 1.
+ * @return true if the weight was set, or false if the grid pointer is NULL or the coordinates are not a cell
+ *         of the maze.
+ */
+bool mazer_set_cell_weight(Grid *grid, size_t x, size_t y, uint32_t weight);
+
+/**
+ * Retrieves the cells of the lowest cost path from the start to the goal, where entering a cell costs its
+ * weight, in order from the start to the goal.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param length A pointer to a size_t variable where the function will store the number of cells on the path.
+ * @return A pointer to an array of FFICoordinates, or NULL if either pointer is NULL. The array is empty when
+ *         the goal is unreachable. Release with mazer_free_coordinates.
+ */
+FFICoordinates* mazer_get_weighted_solution_path(const Grid *grid, size_t *length);
+
+/**
+ * Frees an array of FFICoordinates returned by mazer_get_solution_path, mazer_get_weighted_solution_path
+ * or mazer_move_until_junction.
+ *
+ * @param ptr A pointer to the array of FFICoordinates to be freed.
+ * @param length The number of FFICoordinates elements in the array.
+ */
+void mazer_free_coordinates(FFICoordinates *ptr, size_t length);
+
+/**
+ * Computes the difficulty metrics of the maze.
+ *
+ * The report includes solution length, dead-end, junction and decision point counts, branching factor,
+ * average corridor length, a 0-100 score and a level (0 = Easy, 1 = Medium, 2 = Hard).
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param report A pointer to an FFIDifficultyReport which will be filled in with the maze's metrics.
+ * @return true if the report was written, or false if either pointer is NULL.
+ */
+bool mazer_get_difficulty_report(const Grid *grid, FFIDifficultyReport *report);
+
+/**
+ * Computes the maze's difficulty score.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return The score, from 0.0 (trivial) through 100.0 (hardest), or -1.0 if the grid pointer is NULL.
+ */
+double mazer_get_difficulty(const Grid *grid);
+
+/**
+ * Computes the maze's statistics and difficulty as JSON.
+ *
+ * The JSON object holds "par_moves" (the fewest moves from the start to the goal), "difficulty" (solution
+ * length, dead-end, junction and decision point counts, branching factor, average corridor length, score and
+ * a "level" label of "Easy", "Medium" or "Hard") and "stats" (dead-end ratio, longest path, passage
+ * directions and river factor).
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return A null-terminated JSON string to be released with mazer_free_string, or NULL if the grid pointer
+ *         is NULL or serialization fails.
+ */
+char* mazer_get_stats_json(const Grid *grid);
+
+/**
+ * Starts tracking elapsed time, moves, wrong turns and backtracks for the user's attempt at solving the maze,
+ * replacing any previous session.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return true if the session was started, or false if the grid pointer is NULL.
+ */
+bool mazer_start_session(Grid *grid);
+
+/**
+ * Pauses the session's clock, e.g. while the app is in the background.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return true if the clock was paused, or false if the grid pointer is NULL or no session has been started.
+ */
+bool mazer_pause_session(Grid *grid);
+
+/**
+ * Resumes the session's clock after mazer_pause_session.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return true if the clock was resumed, or false if the grid pointer is NULL or no session has been started.
+ */
+bool mazer_resume_session(Grid *grid);
+
+/**
+ * Retrieves the telemetry of the current session: elapsed milliseconds (excluding paused time), moves made,
+ * wrong turns (forward moves leading away from the goal), backtracks, and whether the clock is paused.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param stats A pointer to an FFISessionStats which will be filled in with the session's telemetry.
+ * @return true if the stats were written, or false if either pointer is NULL or no session has been started.
+ */
+bool mazer_get_session_stats(const Grid *grid, FFISessionStats *stats);
+
+/**
+ * Lists the names of all supported maze types.
+ *
+ * Names are returned in a stable order, with newly supported maze types appended to the end.
+ *
+ * @param length A pointer to a size_t variable where the function will store the number of names.
+ * @return An array of null-terminated C strings, or NULL if length is NULL. Release with mazer_free_string_list.
+ */
+const char** mazer_list_maze_types(size_t *length);
+
+/**
+ * Lists the names of all supported maze generation algorithms.
+ *
+ * Names are returned in a stable order, with newly supported algorithms appended to the end.
+ *
+ * @param length A pointer to a size_t variable where the function will store the number of names.
+ * @return An array of null-terminated C strings, or NULL if length is NULL. Release with mazer_free_string_list.
+ */
+const char** mazer_list_algorithms(size_t *length);
+
+/**
+ * Frees an array of C strings returned by mazer_list_maze_types or mazer_list_algorithms.
+ *
+ * @param ptr A pointer to the array of C strings to be freed.
+ * @param length The number of strings in the array.
+ */
+void mazer_free_string_list(const char **ptr, size_t length);
+
+/**
+ * Checks whether a maze generation algorithm can generate mazes of a maze type.
+ *
+ * Useful for greying out unsupported algorithm and maze type combinations before submitting a request.
+ *
+ * @param algorithm A null-terminated C string naming the algorithm, as returned by mazer_list_algorithms.
+ * @param maze_type A null-terminated C string naming the maze type, as returned by mazer_list_maze_types.
+ * @return true if the algorithm supports the maze type, or false if it does not, either pointer is NULL,
+ *         or either name is not recognized.
+ */
+bool mazer_algorithm_supports(const char *algorithm, const char *maze_type);
+
+/**
+ * Validates a JSON maze request without generating the maze.
+ *
+ * @param request_json A null-terminated C string containing the JSON request.
+ * @return true if the request is well formed and can be generated, or false otherwise.
+ */
+bool mazer_validate_request(const char *request_json);
+
+/**
+ * Retrieves the width and height of the maze, in cells.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param width A pointer to a size_t variable where the function will store the width.
+ * @param height A pointer to a size_t variable where the function will store the height.
+ * @return true if the dimensions were written, or false if any pointer is NULL.
+ */
+bool mazer_get_dimensions(const Grid *grid, size_t *width, size_t *height);
+
+/**
+ * Retrieves the name of the maze's type, as listed by mazer_list_maze_types.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return A static null-terminated C string, which must not be freed, or NULL if the grid pointer is NULL.
+ */
+const char* mazer_get_maze_type(const Grid *grid);
+
+/**
+ * Retrieves the random seed the maze was generated with.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return The maze's seed, or 0 if the grid pointer is NULL.
+ */
+uint64_t mazer_get_seed(const Grid *grid);
+
+/**
+ * Computes a hash of the maze's shape and passages, ignoring its start, goal and the user's progress, which is
+ * stable across runs and platforms, so that generated mazes can be deduplicated and referred to by id.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @return The maze's canonical hash, or 0 if the grid pointer is NULL.
+ */
+uint64_t mazer_canonical_hash(const Grid *grid);
+
+/**
+ * Retrieves the coordinates of the maze's start cell.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param start A pointer to an FFICoordinates which will be filled in with the start cell's coordinates.
+ * @return true if the coordinates were written, or false if either pointer is NULL.
+ */
+bool mazer_get_start(const Grid *grid, FFICoordinates *start);
+
+/**
+ * Retrieves the coordinates of the maze's goal cell.
+ *
+ * @param grid A pointer to the Grid instance.
+ * @param goal A pointer to an FFICoordinates which will be filled in with the goal cell's coordinates.
+ * @return true if the coordinates were written, or false if either pointer is NULL.
+ */
+bool mazer_get_goal(const Grid *grid, FFICoordinates *goal);
+
+/* Status codes returned by the handle-based functions. */
+#define MAZER_HANDLE_OK 0        /* the call succeeded */
+#define MAZER_HANDLE_INVALID -1  /* the handle was never issued */
+#define MAZER_HANDLE_STALE -2    /* the handle's maze has been destroyed */
+#define MAZER_HANDLE_FAILED -3   /* the handle is valid but the operation failed */
+
+/**
+ * Generates a maze from a JSON request, like mazer_generate_maze, but returns an integer handle rather than
+ * a raw pointer. Handle-based functions validate the handle on every call, so using a destroyed maze or
+ * destroying it twice reports an error instead of crashing the host app. They may also be called from any
+ * thread, as each maze is locked for the duration of a call; the pointer-based functions must not be called
+ * concurrently on the same maze.
+ *
+ * @param request_json A null-terminated C string containing the JSON request.
+ * @return A non-zero handle to the generated maze (release with mazer_handle_destroy), or 0 on failure.
+ */
+uint64_t mazer_handle_generate_maze(const char *request_json);
+
+/**
+ * Destroys the maze referred to by a handle, invalidating the handle.
+ *
+ * @param handle A handle returned by mazer_handle_generate_maze.
+ * @return MAZER_HANDLE_OK, MAZER_HANDLE_INVALID if the handle was never issued, or MAZER_HANDLE_STALE if the
+ *         maze was already destroyed.
+ */
+int32_t mazer_handle_destroy(uint64_t handle);
+
+/**
+ * Checks whether a handle refers to a maze which has not been destroyed.
+ *
+ * @param handle A handle returned by mazer_handle_generate_maze.
+ * @return true if the handle is valid, or false otherwise.
+ */
+bool mazer_handle_is_valid(uint64_t handle);
+
+/**
+ * Retrieves the cells of the maze referred to by a handle, like mazer_get_cells.
+ *
+ * @param handle A handle returned by mazer_handle_generate_maze.
+ * @param length A pointer to a size_t variable where the function will store the number of cells.
+ * @return A pointer to an array of FFICell structures (release with mazer_free_cells), or NULL if the handle
+ *         is not valid or length is NULL.
+ */
+FFICell* mazer_handle_get_cells(uint64_t handle, size_t *length);
+
+/**
+ * Performs a move in the maze referred to by a handle, like mazer_make_move.
+ *
+ * @param handle A handle returned by mazer_handle_generate_maze.
+ * @param direction A null-terminated C string indicating the move direction.
+ * @return MAZER_HANDLE_OK if the move was made, MAZER_HANDLE_INVALID or MAZER_HANDLE_STALE for a bad handle,
+ *         or MAZER_HANDLE_FAILED if the direction is unrecognized or the move is unavailable.
+ */
+int32_t mazer_handle_make_move(uint64_t handle, const char *direction);
+
+/**
+ * Reverses the most recent user move in the maze referred to by a handle, like mazer_undo.
+ *
+ * @param handle A handle returned by mazer_handle_generate_maze.
+ * @return MAZER_HANDLE_OK if a move was undone, MAZER_HANDLE_INVALID or MAZER_HANDLE_STALE for a bad handle,
+ *         or MAZER_HANDLE_FAILED if there are no moves to undo.
+ */
+int32_t mazer_handle_undo(uint64_t handle);
+
+/**
+ * Re-applies the most recently undone user move in the maze referred to by a handle, like mazer_redo.
+ *
+ * @param handle A handle returned by mazer_handle_generate_maze.
+ * @return MAZER_HANDLE_OK if a move was redone, MAZER_HANDLE_INVALID or MAZER_HANDLE_STALE for a bad handle,
+ *         or MAZER_HANDLE_FAILED if there are no undone moves.
+ */
+int32_t mazer_handle_redo(uint64_t handle);
+
+/**
+ * Suggests the next move toward the goal in the maze referred to by a handle, like mazer_get_hint.
+ *
+ * @param handle A handle returned by mazer_handle_generate_maze.
+ * @return The numeric code of the hinted direction (see mazer_get_hint), MAZER_HANDLE_INVALID or
+ *         MAZER_HANDLE_STALE for a bad handle, or MAZER_HANDLE_FAILED if no hint is available.
+ */
+int32_t mazer_handle_get_hint(uint64_t handle);
+
+/**
+ * Retrieves the solution path of the maze referred to by a handle, like mazer_get_solution_path.
+ *
+ * @param handle A handle returned by mazer_handle_generate_maze.
+ * @param length A pointer to a size_t variable where the function will store the number of cells on the path.
+ * @return A pointer to an array of FFICoordinates (release with mazer_free_coordinates), or NULL if the handle
+ *         is not valid or length is NULL. The array is empty when the goal is unreachable.
+ */
+FFICoordinates* mazer_handle_get_solution_path(uint64_t handle, size_t *length);
+
+/**
+ * Serializes the maze referred to by a handle to a JSON string, like mazer_serialize_maze.
+ *
+ * @param handle A handle returned by mazer_handle_generate_maze.
+ * @return A null-terminated JSON string (release with mazer_free_string), or NULL if the handle is not valid
+ *         or serialization fails.
+ */
+char* mazer_handle_serialize_maze(uint64_t handle);
+
+/**
+ * Returns the number of generation steps captured for the maze referred to by a handle, like
+ * mazer_get_generation_steps_count, or 0 if the handle is not valid or no steps were captured.
+ *
+ * @param handle A handle returned by mazer_handle_generate_maze.
+ */
+size_t mazer_handle_get_generation_steps_count(uint64_t handle);
+
+/**
+ * Retrieves the cells of a generation step of the maze referred to by a handle, like
+ * mazer_get_generation_step_cells.
+ *
+ * @param handle A handle returned by mazer_handle_generate_maze.
+ * @param step_index The index of the generation step.
+ * @param length A pointer to a size_t variable where the function will store the number of cells.
+ * @return A pointer to an array of FFICell structures (release with mazer_free_cells), or NULL if the handle
+ *         is not valid, length is NULL, or there is no such step.
+ */
+FFICell* mazer_handle_get_generation_step_cells(uint64_t handle, size_t step_index, size_t *length);
+
+/**
+ * Starts generating a maze from a JSON request on a background thread, so large mazes don't block the calling
+ * (e.g. UI) thread. Poll the generation with mazer_generation_progress, and collect the maze with
+ * mazer_generation_finish, which must be called exactly once for every generation started.
+ *
+ * @param request_json A null-terminated C string containing the JSON request.
+ * @return A pointer to the running generation, or NULL if the request is not valid JSON.
+ */
+BackgroundGeneration* mazer_generate_maze_async(const char *request_json);
+
+/**
+ * Retrieves the progress of a maze being generated in the background. May be called from any thread.
+ *
+ * @param generation A pointer returned by mazer_generate_maze_async.
+ * @return The fraction of the maze generated so far, from 0.0 through 1.0, which is 1.0 only once generation
+ *         has finished. Returns 0.0 if generation is NULL.
+ */
+double mazer_generation_progress(const BackgroundGeneration *generation);
+
+/**
+ * Asks a maze being generated in the background to stop. mazer_generation_finish must still be called to
+ * release the generation.
+ *
+ * @param generation A pointer returned by mazer_generate_maze_async.
+ */
+void mazer_generation_cancel(const BackgroundGeneration *generation);
+
+/**
+ * Waits for a maze being generated in the background to finish, and releases the generation. Returns
+ * immediately once mazer_generation_progress has reached 1.0.
+ *
+ * @param generation A pointer returned by mazer_generate_maze_async, which is invalid after this call.
+ * @return A pointer to the generated Grid (release with mazer_destroy), or NULL if generation failed or was
+ *         cancelled.
+ */
+Grid* mazer_generation_finish(BackgroundGeneration *generation);
+
 /**
  * To verify FFI connectivity, call verify this returns 42.
  */
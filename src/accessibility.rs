@@ -0,0 +1,258 @@
+use alloc::collections::BTreeSet;
+use rand::seq::SliceRandom;
+use rand::RngCore;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::algorithms::kruskals;
+use crate::cell::{Coordinates, MazeType};
+use crate::direction::Direction;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::prelude::*;
+
+/// Corridor metrics of a maze that bear on how easily it can be read and navigated, so level designers can
+/// check generated mazes against their guardrails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct AccessibilityReport {
+    /// Number of changes of direction along the solution path.
+    pub solution_turns: usize,
+    /// Number of passages along the longest straight corridor.
+    pub longest_straight: usize,
+    /// Number of cells with passages leading four or more ways.
+    pub four_way_junctions: usize,
+}
+
+impl AccessibilityReport {
+    /// Compute the corridor metrics of the specified maze.
+    pub fn from_grid(grid: &Grid) -> AccessibilityReport {
+        let path = grid.solution_path_order();
+        let headings: Vec<Direction> = path.windows(2).filter_map(|pair| heading_between(grid, pair[0], pair[1])).collect();
+        AccessibilityReport {
+            solution_turns: headings.windows(2).filter(|pair| pair[0] != pair[1]).count(),
+            longest_straight: corridors(grid).iter().map(Vec::len).max().unwrap_or(0),
+            four_way_junctions: four_way_junctions(grid).len(),
+        }
+    }
+}
+
+/// Limits on a generated maze's corridors, enforced by moving passages until the maze keeps within them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Guardrails {
+    /// The most passages any straight corridor may run, e.g. 6 for no straight corridor longer than 6.
+    pub max_straight: Option<usize>,
+    /// The most cells which may have passages leading four or more ways.
+    pub max_four_way_junctions: Option<usize>,
+}
+
+impl Guardrails {
+    /// Check that the guardrails can be kept: a straight corridor must be allowed at least one passage.
+    pub fn validate(self) -> Result<(), Error> {
+        if self.max_straight == Some(0) {
+            return Err(Error::InvalidGuardrails { reason: "straight corridors must be allowed at least 1 passage".to_string() });
+        }
+        Ok(())
+    }
+
+    /// Rework a generated maze until it keeps within the guardrails. Each passage breaking a guardrail is
+    /// closed, and the two parts of the maze it joined are rejoined through another wall, chosen to keep within
+    /// the guardrails where possible, so a perfect maze stays perfect. Fails if the maze still breaks a
+    /// guardrail after many such adjustments.
+    pub fn apply(self, grid: &mut Grid) -> Result<(), Error> {
+        self.validate()?;
+        let mut rng = grid.forked_rng();
+        let adjustments = 8 * grid.iter_cells().count();
+        for _ in 0..adjustments {
+            let Some((a, b)) = self.violation(grid, &mut rng) else {
+                return Ok(());
+            };
+            grid.unlink(a, b)?;
+            let side = grid.all_connected_cells(a);
+            if side.contains(&b) {
+                continue;
+            }
+            let mut bridges: Vec<(Coordinates, Coordinates)> = kruskals::edges(grid)
+                .into_iter()
+                .filter(|&(x, y)| side.contains(&x) != side.contains(&y) && (x, y) != (a.min(b), a.max(b)))
+                .collect();
+            bridges.shuffle(&mut rng);
+            let &(x, y) = bridges
+                .iter()
+                .find(|&&(x, y)| self.allows(grid, x, y))
+                .or(bridges.first())
+                .unwrap_or(&(a, b));
+            grid.link(x, y)?;
+        }
+        match self.violation(grid, &mut rng) {
+            None => Ok(()),
+            Some(_) => Err(Error::InvalidGuardrails {
+                reason: format!("the maze still breaks {:?} after {} adjustments", self, adjustments),
+            }),
+        }
+    }
+
+    // A passage to close toward keeping within the guardrails, if the maze breaks any of them
+    fn violation(self, grid: &Grid, rng: &mut impl RngCore) -> Option<(Coordinates, Coordinates)> {
+        if let Some(max) = self.max_straight {
+            let long: Vec<_> = corridors(grid).into_iter().filter(|corridor| corridor.len() > max).collect();
+            if let Some(corridor) = long.choose(rng) {
+                // cut so that neither part is too long, where a single cut can manage it
+                let first = (corridor.len() - 1).saturating_sub(max);
+                let last = max.min(corridor.len() - 1);
+                let cut = if first <= last { first + rng.next_u64() as usize % (last - first + 1) } else { max };
+                return Some(corridor[cut]);
+            }
+        }
+        if let Some(max) = self.max_four_way_junctions {
+            let junctions = four_way_junctions(grid);
+            if junctions.len() > max {
+                let &coords = junctions.choose(rng)?;
+                let linked: Vec<Coordinates> = grid.get(coords).ok()?.linked.iter().collect();
+                return linked.choose(rng).map(|&neighbor| (coords, neighbor));
+            }
+        }
+        None
+    }
+
+    // Whether opening a passage between the neighboring cells would keep within the guardrails
+    fn allows(self, grid: &Grid, a: Coordinates, b: Coordinates) -> bool {
+        let straight = self.max_straight.is_none_or(|max| {
+            heading_between(grid, a, b).is_none_or(|heading| {
+                run_length(grid, a, heading.opposite()) + 1 + run_length(grid, b, heading) <= max
+            })
+        });
+        let junctions = self.max_four_way_junctions.is_none_or(|max| {
+            let new = [a, b].iter().filter(|&&coords| grid.get(coords).is_ok_and(|cell| cell.linked.len() + 1 == 4)).count();
+            four_way_junctions(grid).len() + new <= max
+        });
+        straight && junctions
+    }
+}
+
+// The direction of travel through a passage, taking a Delta maze's alternating upper and lower sides as one
+fn heading(maze_type: MazeType, direction: Direction) -> Direction {
+    match (maze_type, direction) {
+        (MazeType::Delta, Direction::UpperLeft | Direction::LowerLeft) => Direction::Left,
+        (MazeType::Delta, Direction::UpperRight | Direction::LowerRight) => Direction::Right,
+        _ => direction,
+    }
+}
+
+// The direction of travel from a cell to its neighbor
+fn heading_between(grid: &Grid, from: Coordinates, to: Coordinates) -> Option<Direction> {
+    let cell = grid.get(from).ok()?;
+    cell.neighbors_by_direction
+        .iter()
+        .find(|&(_, neighbor)| neighbor == to)
+        .map(|(direction, _)| heading(grid.maze_type, direction))
+}
+
+// The cell reached from a cell through an open passage in the direction of travel, if any
+fn follow(grid: &Grid, from: Coordinates, toward: Direction) -> Option<Coordinates> {
+    let cell = grid.get(from).ok()?;
+    cell.neighbors_by_direction
+        .iter()
+        .find(|&(direction, neighbor)| heading(grid.maze_type, direction) == toward && cell.linked.contains(&neighbor))
+        .map(|(_, neighbor)| neighbor)
+}
+
+// Number of passages running straight on from a cell in the direction of travel
+fn run_length(grid: &Grid, from: Coordinates, toward: Direction) -> usize {
+    let mut length = 0;
+    let mut coords = from;
+    while let Some(next) = follow(grid, coords, toward) {
+        length += 1;
+        coords = next;
+    }
+    length
+}
+
+// The maze's passages grouped into straight corridors, each passage in exactly one
+fn corridors(grid: &Grid) -> Vec<Vec<(Coordinates, Coordinates)>> {
+    let mut seen = BTreeSet::new();
+    let mut corridors = Vec::new();
+    for (a, b) in kruskals::edges(grid) {
+        if seen.contains(&(a, b)) || !grid.get(a).is_ok_and(|cell| cell.linked.contains(&b)) {
+            continue;
+        }
+        let Some(toward) = heading_between(grid, a, b) else {
+            continue;
+        };
+        // back up to the corridor's first cell, then walk it to the end
+        let mut start = a;
+        while let Some(previous) = follow(grid, start, toward.opposite()) {
+            start = previous;
+        }
+        let mut corridor = Vec::new();
+        let mut coords = start;
+        while let Some(next) = follow(grid, coords, toward) {
+            let passage = (coords.min(next), coords.max(next));
+            seen.insert(passage);
+            corridor.push(passage);
+            coords = next;
+        }
+        corridors.push(corridor);
+    }
+    corridors
+}
+
+// Cells with passages leading four or more ways
+fn four_way_junctions(grid: &Grid) -> Vec<Coordinates> {
+    grid.iter_cells().filter(|cell| cell.linked.len() >= 4).map(|cell| cell.coords).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_of_a_winding_corridor() {
+        // a corridor along the top row, down the right side and back along the bottom row
+        let at = |x, y| Coordinates { x, y };
+        let mut grid = Grid::new(MazeType::Orthogonal, 4, 2, at(0, 0), at(0, 1), false).unwrap();
+        for (a, b) in [(at(0, 0), at(1, 0)), (at(1, 0), at(2, 0)), (at(2, 0), at(3, 0)), (at(3, 0), at(3, 1)), (at(3, 1), at(2, 1)), (at(2, 1), at(1, 1)), (at(1, 1), at(0, 1))] {
+            grid.link(a, b).unwrap();
+        }
+        grid.set_endpoints(at(0, 0), at(0, 1)).unwrap();
+        let report = AccessibilityReport::from_grid(&grid);
+        assert_eq!(report, AccessibilityReport { solution_turns: 2, longest_straight: 3, four_way_junctions: 0 });
+    }
+
+    #[test]
+    fn guardrails_are_kept() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 16,
+            "height": 16,
+            "algorithm": "Kruskals",
+            "guardrails": { "max_straight": 3, "max_four_way_junctions": 0 }
+        }
+        "#;
+        let grid = Grid::try_from(json).unwrap();
+        assert!(grid.is_perfect_maze().unwrap());
+        let report = AccessibilityReport::from_grid(&grid);
+        assert!(report.longest_straight <= 3, "{:?}", report);
+        assert_eq!(report.four_way_junctions, 0);
+        assert!(grid.iter_cells().all(|cell| cell.on_solution_path == grid.solution_path_order().contains(&cell.coords)));
+
+        let delta = r#"{ "maze_type": "Delta", "width": 12, "height": 8, "algorithm": "Prims", "guardrails": { "max_straight": 4 } }"#;
+        let grid = Grid::try_from(delta).unwrap();
+        assert!(grid.is_perfect_maze().unwrap());
+        assert!(AccessibilityReport::from_grid(&grid).longest_straight <= 4);
+
+        let invalid = r#"{ "maze_type": "Orthogonal", "width": 4, "height": 4, "algorithm": "Prims", "guardrails": { "max_straight": 0 } }"#;
+        assert!(matches!(Grid::try_from(invalid), Err(Error::InvalidGuardrails { .. })));
+    }
+
+    #[test]
+    fn guardrails_cannot_be_combined_with_symmetry_or_overlays() {
+        let guarded = r#""maze_type": "Orthogonal", "width": 10, "height": 8, "algorithm": "Prims", "guardrails": { "max_straight": 3 }"#;
+        let symmetric = format!(r#"{{ {}, "symmetry": "Horizontal" }}"#, guarded);
+        assert!(matches!(Grid::try_from(symmetric), Err(Error::InvalidGuardrails { .. })));
+        let overlaid = format!(r#"{{ {}, "overlay": {{ "factor": 4, "algorithm": "Kruskals" }} }}"#, guarded);
+        assert!(matches!(Grid::try_from(overlaid), Err(Error::InvalidGuardrails { .. })));
+    }
+}
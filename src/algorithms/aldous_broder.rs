@@ -2,8 +2,8 @@ use crate::behaviors::maze::MazeGeneration;
 use crate::grid::Grid;
 use crate::cell::Coordinates;
 use crate::error::Error;
+use crate::prelude::*;
 
-use std::collections::HashSet;
 
 pub struct AldousBroder;
 
@@ -22,70 +22,79 @@ impl MazeGeneration for AldousBroder {
                 break coords;
             }
         };
-        let mut current_coords = start_coords;
-        visited.insert(current_coords);
-        let mut visited_count = 1;
+        visited.insert(start_coords);
 
         // Capture initial state if capture_steps is true
         if grid.capture_steps {
-            let mut grid_clone = grid.clone();
-            grid_clone.capture_steps = false;
-            grid_clone.generation_steps = None;
-            grid.generation_steps.as_mut().unwrap().push(grid_clone);
+            let changed_cells = HashSet::new();
+            self.capture_step(grid, &changed_cells);
         }
 
-        // Step 3: Loop until all existing cells are visited
-        while visited_count < total_cells {
-            if let Ok(current_cell) = grid.get(current_coords) {
-                // Get neighbors that exist (i.e., have Some(Cell))
-                let neighbors: Vec<Coordinates> = current_cell
-                    .neighbors()
-                    .iter()
-                    .filter(|&&coords| grid.get(coords).is_ok())
-                    .cloned()
-                    .collect();
+        // Step 3: Walk until all existing cells are visited
+        random_walk(self, grid, &mut visited, start_coords, total_cells)
+    }
+}
 
-                if !neighbors.is_empty() {
-                    // Pick a random neighbor
-                    let random_index = grid.bounded_random_usize(neighbors.len());
-                    let random_neighbor = neighbors[random_index];
+/// Walk randomly from `from`, linking each cell the walk reaches for the first time to the cell it came from,
+/// until `target` cells have been visited. Shared with AldousBroderWilsons, which hands over to Wilson's part way.
+pub(crate) fn random_walk(
+    generator: &impl MazeGeneration,
+    grid: &mut Grid,
+    visited: &mut HashSet<Coordinates>,
+    from: Coordinates,
+    target: usize,
+) -> Result<(), Error> {
+    let mut current_coords = from;
+    while visited.len() < target {
+        grid.check_cancelled()?;
+        if let Ok(current_cell) = grid.get(current_coords) {
+            // Get neighbors that exist (i.e., have Some(Cell))
+            let neighbors: Vec<Coordinates> = current_cell
+                .neighbors()
+                .iter()
+                .filter(|&&coords| grid.get(coords).is_ok())
+                .cloned()
+                .collect();
 
-                    // If the neighbor hasn't been visited, link it and update visited
-                    if !visited.contains(&random_neighbor) {
-                        grid.link(current_coords, random_neighbor)?;
-                        visited.insert(random_neighbor);
-                        visited_count += 1;
+            if !neighbors.is_empty() {
+                // Pick a random neighbor
+                let random_index = grid.bounded_random_usize(neighbors.len());
+                let random_neighbor = neighbors[random_index];
 
-                        if grid.capture_steps {
-                            let mut changed_cells = HashSet::new();
-                            changed_cells.insert(current_coords);
-                            changed_cells.insert(random_neighbor);
-                            self.capture_step(grid, &changed_cells);
-                        }
-                    }
+                // If the neighbor hasn't been visited, link it and update visited
+                if !visited.contains(&random_neighbor) {
+                    grid.link(current_coords, random_neighbor)?;
+                    visited.insert(random_neighbor);
 
-                    // Move to the selected neighbor
-                    current_coords = random_neighbor;
-                } else {
-                    // If no unvisited neighbors, jump to another unvisited existing cell
-                    current_coords = loop {
-                        let rand_x = grid.bounded_random_usize(grid.width);
-                        let rand_y = grid.bounded_random_usize(grid.height);
-                        let coords = Coordinates { x: rand_x, y: rand_y };
-                        if grid.get(coords).is_ok() && !visited.contains(&coords) {
-                            break coords;
-                        }
-                    };
+                    if grid.capture_steps {
+                        let mut changed_cells = HashSet::new();
+                        changed_cells.insert(current_coords);
+                        changed_cells.insert(random_neighbor);
+                        generator.capture_step(grid, &changed_cells);
+                    }
                 }
+
+                // Move to the selected neighbor
+                current_coords = random_neighbor;
             } else {
-                // This should not occur with proper movement logic
-                return Err(Error::InvalidCellCoordinates {
-                    coordinates: current_coords,
-                });
+                // If no unvisited neighbors, jump to another unvisited existing cell
+                current_coords = loop {
+                    let rand_x = grid.bounded_random_usize(grid.width);
+                    let rand_y = grid.bounded_random_usize(grid.height);
+                    let coords = Coordinates { x: rand_x, y: rand_y };
+                    if grid.get(coords).is_ok() && !visited.contains(&coords) {
+                        break coords;
+                    }
+                };
             }
+        } else {
+            // This should not occur with proper movement logic
+            return Err(Error::InvalidCellCoordinates {
+                coordinates: current_coords,
+            });
         }
-        Ok(())
     }
+    Ok(())
 }
 
 // impl MazeGeneration for AldousBroder {
@@ -273,11 +282,11 @@ mod tests {
                 assert!(!steps.is_empty());
                 // Check if any cells become linked across all generation steps
                 let has_linked_cells = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.linked.is_empty())
+                    step.iter_cells().any(|cell| !cell.linked.is_empty())
                 });
                 assert!(has_linked_cells, "No cells were linked during maze generation");
                 let has_open_walls = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.open_walls.is_empty())
+                    step.iter_cells().any(|cell| !cell.open_walls.is_empty())
                 });
                 assert!(has_open_walls, "No cells have open walls in generation steps");
             }
@@ -0,0 +1,80 @@
+use crate::algorithms::aldous_broder::random_walk;
+use crate::algorithms::wilsons::loop_erased_walks;
+use crate::behaviors::maze::MazeGeneration;
+use crate::grid::Grid;
+use crate::cell::Coordinates;
+use crate::error::Error;
+use crate::prelude::*;
+
+/// Aldous-Broder until a fraction of the cells are visited, then Wilson's for the rest. Aldous-Broder is quick
+/// to visit its first cells but slow to find the last unvisited ones, while Wilson's is the reverse, so the
+/// hybrid is far faster than either on large grids. Both generate uniform spanning trees, as does the hybrid.
+pub struct AldousBroderWilsons {
+    /// Fraction of the cells (0.0 through 1.0) visited by Aldous-Broder before switching to Wilson's.
+    pub switch_threshold: f64,
+}
+
+impl AldousBroderWilsons {
+    pub const DEFAULT_SWITCH_THRESHOLD: f64 = 0.3;
+}
+
+impl MazeGeneration for AldousBroderWilsons {
+    fn generate(&self, grid: &mut Grid) -> Result<(), Error> {
+        let total_cells = grid.iter_cells().count();
+        let mut visited = HashSet::new();
+
+        let start_coords = loop {
+            let x = grid.bounded_random_usize(grid.width);
+            let y = grid.bounded_random_usize(grid.height);
+            let coords = Coordinates { x, y };
+            if grid.get(coords).is_ok() {
+                break coords;
+            }
+        };
+        visited.insert(start_coords);
+
+        // Capture initial state if capture_steps is true
+        if grid.capture_steps {
+            let changed_cells = HashSet::new();
+            self.capture_step(grid, &changed_cells);
+        }
+
+        let switch_at = ((total_cells as f64 * self.switch_threshold).ceil() as usize).clamp(1, total_cells);
+        random_walk(self, grid, &mut visited, start_coords, switch_at)?;
+        loop_erased_walks(self, grid, &mut visited, total_cells)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::{ MazeType, Coordinates };
+
+    fn hybrid() -> AldousBroderWilsons {
+        AldousBroderWilsons { switch_threshold: AldousBroderWilsons::DEFAULT_SWITCH_THRESHOLD }
+    }
+
+    #[test]
+    fn generate_and_print_12_x_6_orthogonal_maze() {
+        match Grid::new(MazeType::Orthogonal, 12, 6, Coordinates { x: 0, y: 0 }, Coordinates { x: 11, y: 5 }, false) {
+            Ok(mut grid) => {
+                assert!(!grid.is_perfect_maze().unwrap());
+                hybrid().generate(&mut grid).expect("AldousBroderWilsons maze generation failed");
+                println!("\n\nAldous Broder Wilsons\n\n{}\n\n", grid.to_asci());
+                assert!(grid.is_perfect_maze().unwrap());
+            }
+            Err(e) => panic!("Unexpected error running test: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn generate_every_maze_type() {
+        for &maze_type in MazeType::all() {
+            for switch_threshold in [0.0, 0.5, 1.0] {
+                let mut grid = Grid::new(maze_type, 10, 10, Coordinates { x: 0, y: 0 }, Coordinates { x: 8, y: 8 }, false).unwrap();
+                AldousBroderWilsons { switch_threshold }.generate(&mut grid).expect("AldousBroderWilsons maze generation failed");
+                assert!(grid.is_perfect_maze().unwrap(), "{:?} maze switching at {}", maze_type, switch_threshold);
+            }
+        }
+    }
+}
@@ -3,15 +3,17 @@ use crate::algorithms::MazeAlgorithm;
 use crate::grid::Grid;
 use crate::cell::{Coordinates, MazeType};
 use crate::error::Error;
+use crate::prelude::*;
 
-use std::collections::HashSet;
 
 pub struct BinaryTree;
 
 impl MazeGeneration for BinaryTree {
     fn generate(&self, grid: &mut Grid) -> Result<(), Error> {
         match grid.maze_type {
-            MazeType::Orthogonal => {} // proceed with maze generation for allowed Orthogonal (square) grid type
+            // rows of these grids are joined side by side, so every cell but the last has a neighbor to its right
+            // or in the row below
+            MazeType::Orthogonal | MazeType::Sigma | MazeType::Upsilon => {}
             maze_type => {
                 return Err(Error::AlgorithmUnavailableForMazeType {
                     algorithm: MazeAlgorithm::BinaryTree,
@@ -31,34 +33,32 @@ impl MazeGeneration for BinaryTree {
         for row in 0..rows {
             for col in 0..cols {
                 let current_coords = Coordinates { x: col, y: row };
-                let right_exists = col + 1 < cols;
-                let down_exists = row + 1 < rows;
-                let carve_down = if right_exists && down_exists {
+                let neighbors = grid.get(current_coords)?.neighbors();
+                let right_coords = Coordinates { x: col + 1, y: row };
+                let right_exists = neighbors.contains(&right_coords);
+                // cells below, of which Sigma and Upsilon cells can have several, in a stable order
+                let mut below: Vec<Coordinates> = neighbors.into_iter().filter(|neighbor| neighbor.y == row + 1).collect();
+                below.sort();
+                let carve_down = if right_exists && !below.is_empty() {
                     grid.random_bool() // Randomly decide between down and right
                 } else {
                     !right_exists
                 };
-                if carve_down {
-                    if down_exists {
-                        let down_coords = Coordinates { x: col, y: row + 1 };
-                        grid.link(current_coords, down_coords)?;
-                        if grid.capture_steps {
-                            let mut changed_cells = HashSet::new();
-                            changed_cells.insert(current_coords);
-                            changed_cells.insert(down_coords);
-                            self.capture_step(grid, &changed_cells);
-                        }
+                let next_coords = if carve_down {
+                    match below.len() {
+                        0 => None,
+                        len => Some(below[grid.bounded_random_usize(len)]),
                     }
                 } else {
-                    if right_exists {
-                        let right_coords = Coordinates { x: col + 1, y: row };
-                        grid.link(current_coords, right_coords)?;
-                        if grid.capture_steps {
-                            let mut changed_cells = HashSet::new();
-                            changed_cells.insert(current_coords);
-                            changed_cells.insert(right_coords);
-                            self.capture_step(grid, &changed_cells);
-                        }
+                    Some(right_coords)
+                };
+                if let Some(next_coords) = next_coords {
+                    grid.link(current_coords, next_coords)?;
+                    if grid.capture_steps {
+                        let mut changed_cells = HashSet::new();
+                        changed_cells.insert(current_coords);
+                        changed_cells.insert(next_coords);
+                        self.capture_step(grid, &changed_cells);
                     }
                 }
             }
@@ -145,30 +145,14 @@ mod tests {
     }
 
     #[test]
-    fn reject_5_x_5_sigma_binary_tree_maze() {
-        match Grid::new(
-            MazeType::Sigma,
-            4,
-            4,
-            Coordinates { x: 0, y: 0 },
-            Coordinates { x: 3, y: 3 },
-            false,
-        ) {
-            Ok(mut grid) => {
-                assert!(!grid.is_perfect_maze().unwrap());
-                match BinaryTree.generate(&mut grid) {
-                    Ok(()) => {
-                        panic!("Successfully generated a BinaryTree maze for a Sigma grid, which should have been rejected!");
-                    }
-                    Err(e) => {
-                        println!(
-                            "As expected, Sigma grid is rejected for BinaryTree maze generation: {:?}",
-                            e
-                        );
-                    }
-                }
+    fn generate_sigma_and_upsilon_mazes() {
+        for maze_type in [MazeType::Sigma, MazeType::Upsilon] {
+            for (width, height) in [(1, 1), (1, 6), (6, 1), (5, 5), (12, 7)] {
+                let goal = Coordinates { x: width - 1, y: height - 1 };
+                let mut grid = Grid::new(maze_type, width, height, Coordinates { x: 0, y: 0 }, goal, false).unwrap();
+                BinaryTree.generate(&mut grid).expect("BinaryTree maze generation failed");
+                assert!(grid.is_perfect_maze().unwrap(), "{:?} maze of {}x{}", maze_type, width, height);
             }
-            Err(e) => panic!("Unexpected error generating grid: {:?}", e),
         }
     }
 
@@ -186,11 +170,11 @@ mod tests {
                 assert!(!steps.is_empty());
                 // Check if any cells become linked across all generation steps
                 let has_linked_cells = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.linked.is_empty())
+                    step.iter_cells().any(|cell| !cell.linked.is_empty())
                 });
                 assert!(has_linked_cells, "No cells were linked during maze generation");
                 let has_open_walls = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.open_walls.is_empty())
+                    step.iter_cells().any(|cell| !cell.open_walls.is_empty())
                 });
                 assert!(has_open_walls, "No cells have open walls in generation steps");
             }
@@ -4,9 +4,9 @@ use crate::grid::Grid;
 use crate::cell::{Coordinates, MazeType};
 use crate::error::Error;
 
-use std::collections::{HashMap, HashSet};
 
 use rand::prelude::SliceRandom;
+use crate::prelude::*;
 
 pub struct Ellers;
 
@@ -85,7 +85,7 @@ impl MazeGeneration for Ellers {
                 // For each set, make at least one vertical connection
                 for (_set_id, cells) in cells_by_set {
                     let mut cells = cells;
-                    cells.shuffle(&mut rand::thread_rng());
+                    cells.shuffle(grid.rng());
                     let connect_count = 1 + grid.bounded_random_usize(cells.len());
                     for &cell_coords in cells.iter().take(connect_count) {
                         let down_coords = Coordinates {
@@ -208,11 +208,11 @@ mod tests {
                 assert!(!steps.is_empty());
                 // Check if any cells become linked across all generation steps
                 let has_linked_cells = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.linked.is_empty())
+                    step.iter_cells().any(|cell| !cell.linked.is_empty())
                 });
                 assert!(has_linked_cells, "No cells were linked during maze generation");
                 let has_open_walls = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.open_walls.is_empty())
+                    step.iter_cells().any(|cell| !cell.open_walls.is_empty())
                 });
                 assert!(has_open_walls, "No cells have open walls in generation steps");
             }
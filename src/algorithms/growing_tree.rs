@@ -4,14 +4,19 @@ use crate::grid::Grid;
 use crate::cell::{Coordinates, MazeType};
 use crate::error::Error;
 
-use std::collections::HashSet;
 
+#[cfg(feature = "serde")]
 use serde::{Serialize, Deserialize};
+use crate::prelude::*;
 
-#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SelectionStrategy {
     Random,
     Newest,
+    /// Select the newest active cell `newest_percent` percent of the time, and a random active cell otherwise.
+    /// Blends the long corridors of Newest with the short, branching passages of Random.
+    Mixed { newest_percent: u8 },
 }
 
 pub struct GrowingTree {
@@ -22,11 +27,11 @@ impl MazeGeneration for GrowingTree {
     fn generate(&self, grid: &mut Grid) -> Result<(), Error> {
         if grid.maze_type == MazeType::Rhombic {
             match self.strategy {
-                SelectionStrategy::Newest | SelectionStrategy::Random => {
+                SelectionStrategy::Newest | SelectionStrategy::Random | SelectionStrategy::Mixed { .. } => {
                     return Err(Error::AlgorithmUnavailableForMazeType {
                         algorithm: match self.strategy {
                             SelectionStrategy::Newest => MazeAlgorithm::GrowingTreeNewest,
-                            SelectionStrategy::Random => MazeAlgorithm::GrowingTreeRandom,
+                            SelectionStrategy::Random | SelectionStrategy::Mixed { .. } => MazeAlgorithm::GrowingTreeRandom,
                         },
                         maze_type: MazeType::Rhombic,
                     });
@@ -55,6 +60,13 @@ impl MazeGeneration for GrowingTree {
             let index = match self.strategy {
                 SelectionStrategy::Random => grid.bounded_random_usize(active.len()),
                 SelectionStrategy::Newest => active.len() - 1,
+                SelectionStrategy::Mixed { newest_percent } => {
+                    if grid.bounded_random_usize(100) < newest_percent as usize {
+                        active.len() - 1
+                    } else {
+                        grid.bounded_random_usize(active.len())
+                    }
+                }
             };
             let current_coords = active[index];
 
@@ -190,11 +202,11 @@ mod tests {
                 assert!(!steps.is_empty());
                 // Check if any cells become linked across all generation steps
                 let has_linked_cells = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.linked.is_empty())
+                    step.iter_cells().any(|cell| !cell.linked.is_empty())
                 });
                 assert!(has_linked_cells, "No cells were linked during maze generation");
                 let has_open_walls = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.open_walls.is_empty())
+                    step.iter_cells().any(|cell| !cell.open_walls.is_empty())
                 });
                 assert!(has_open_walls, "No cells have open walls in generation steps");
             }
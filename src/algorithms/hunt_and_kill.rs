@@ -3,8 +3,10 @@ use crate::algorithms::MazeAlgorithm;
 use crate::grid::Grid;
 use crate::cell::{Coordinates, MazeType};
 use crate::error::Error;
+use crate::events::GenerationPhase;
+use alloc::collections::BTreeSet;
+use crate::prelude::*;
 
-use std::collections::HashSet;
 
 pub struct HuntAndKill;
 
@@ -20,11 +22,14 @@ impl MazeGeneration for HuntAndKill {
             _ => {} // proceed with maze generation for all maze types other than Rhomicb
         }
         let mut visited = HashSet::new();
+        // unvisited cells with at least one visited neighbor, keyed by row then column so that hunts scan in
+        // row-major order
+        let mut frontier: BTreeSet<(usize, usize)> = BTreeSet::new();
         let mut current_coords = Coordinates {
             x: grid.bounded_random_usize(grid.width),
             y: grid.bounded_random_usize(grid.height),
         };
-        visited.insert(current_coords);
+        Self::visit(grid, current_coords, &mut visited, &mut frontier);
 
         // Capture initial state with no changed cells
         if grid.capture_steps {
@@ -37,7 +42,7 @@ impl MazeGeneration for HuntAndKill {
             while let Some(next_coords) = Self::random_unvisited_neighbor(grid, &current_coords, &visited) {
                 // Link the current cell with the chosen neighbor
                 grid.link(current_coords, next_coords)?;
-                visited.insert(next_coords);
+                Self::visit(grid, next_coords, &mut visited, &mut frontier);
                 current_coords = next_coords;
 
                 // Capture step with changed cells after linking
@@ -45,15 +50,15 @@ impl MazeGeneration for HuntAndKill {
                     let mut changed_cells = HashSet::new();
                     changed_cells.insert(current_coords);
                     changed_cells.insert(next_coords);
-                    self.capture_step(grid, &changed_cells);
+                    self.capture_phase(grid, &changed_cells, GenerationPhase::Kill, Self::frontier_cells(&frontier));
                 }
             }
 
             // Hunt Phase: Find the first unvisited cell with at least one visited neighbor
-            if let Some((new_coords, neighbor)) = Self::find_hunt_target(grid, &visited) {
+            if let Some((new_coords, neighbor)) = Self::find_hunt_target(grid, &visited, &frontier) {
                 // Link the new cell with one of its visited neighbors
                 grid.link(new_coords, neighbor)?;
-                visited.insert(new_coords);
+                Self::visit(grid, new_coords, &mut visited, &mut frontier);
                 current_coords = new_coords;
 
                 // Capture step with changed cells after linking
@@ -61,7 +66,7 @@ impl MazeGeneration for HuntAndKill {
                     let mut changed_cells = HashSet::new();
                     changed_cells.insert(new_coords);
                     changed_cells.insert(neighbor);
-                    self.capture_step(grid, &changed_cells);
+                    self.capture_phase(grid, &changed_cells, GenerationPhase::Hunt, Self::frontier_cells(&frontier));
                 }
             } else {
                 // No more unvisited cells, maze generation complete
@@ -73,6 +78,13 @@ impl MazeGeneration for HuntAndKill {
 }
 
 impl HuntAndKill {
+    // The frontier's cells, in order of coordinates
+    fn frontier_cells(frontier: &BTreeSet<(usize, usize)>) -> Vec<Coordinates> {
+        let mut cells: Vec<Coordinates> = frontier.iter().map(|&(y, x)| Coordinates { x, y }).collect();
+        cells.sort();
+        cells
+    }
+
     /// Finds a random unvisited neighbor of the current cell.
     fn random_unvisited_neighbor(
         grid: &mut Grid,
@@ -97,27 +109,38 @@ impl HuntAndKill {
         }
     }
 
-    /// Finds the first unvisited cell with at least one visited neighbor.
+    /// Marks a cell visited, moving it out of the frontier and its unvisited neighbors into it.
+    fn visit(
+        grid: &Grid,
+        coords: Coordinates,
+        visited: &mut HashSet<Coordinates>,
+        frontier: &mut BTreeSet<(usize, usize)>,
+    ) {
+        visited.insert(coords);
+        frontier.remove(&(coords.y, coords.x));
+        if let Ok(cell) = grid.get(coords) {
+            for neighbor in cell.neighbors() {
+                if !visited.contains(&neighbor) {
+                    frontier.insert((neighbor.y, neighbor.x));
+                }
+            }
+        }
+    }
+
+    /// Finds the first unvisited cell with at least one visited neighbor, which is the first cell of the frontier.
     fn find_hunt_target(
         grid: &Grid,
         visited: &HashSet<Coordinates>,
+        frontier: &BTreeSet<(usize, usize)>,
     ) -> Option<(Coordinates, Coordinates)> {
-        (0..grid.height)
-            .flat_map(|y| (0..grid.width).map(move |x| Coordinates { x, y }))
-            .find_map(|coords| {
-                if visited.contains(&coords) {
-                    None
-                } else {
-                    match grid.get(coords) {
-                        Ok(current_cell) => current_cell
-                            .neighbors()
-                            .into_iter()
-                            .find(|neighbor| visited.contains(neighbor))
-                            .map(|neighbor| (coords, neighbor)),
-                        Err(_) => None,
-                    }
-                }
-            })
+        let &(y, x) = frontier.first()?;
+        let coords = Coordinates { x, y };
+        grid.get(coords)
+            .ok()?
+            .neighbors()
+            .into_iter()
+            .find(|neighbor| visited.contains(neighbor))
+            .map(|neighbor| (coords, neighbor))
     }
 }
 
@@ -214,15 +237,41 @@ mod tests {
                 assert!(!steps.is_empty());
                 // Check if any cells become linked across all generation steps
                 let has_linked_cells = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.linked.is_empty())
+                    step.iter_cells().any(|cell| !cell.linked.is_empty())
                 });
                 assert!(has_linked_cells, "No cells were linked during maze generation");
                 let has_open_walls = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.open_walls.is_empty())
+                    step.iter_cells().any(|cell| !cell.open_walls.is_empty())
                 });
                 assert!(has_open_walls, "No cells have open walls in generation steps");
             }
             Err(e) => panic!("Unexpected error generating grid: {:?}", e),
         }
     }
+
+    #[test]
+    fn generation_steps_record_hunts_and_kills() {
+        let mut grid = Grid::new(MazeType::Orthogonal, 12, 12, Coordinates { x: 0, y: 0 }, Coordinates { x: 11, y: 11 }, true).unwrap();
+        HuntAndKill.generate(&mut grid).unwrap();
+        let details = grid.generation_step_details.as_ref().unwrap();
+        assert_eq!(details.len(), grid.generation_steps.as_ref().unwrap().len());
+        assert_eq!(details[0].phase, GenerationPhase::Start);
+        assert!(details.iter().any(|step| step.phase == GenerationPhase::Hunt));
+        for step in details.iter().skip(1) {
+            assert!(matches!(step.phase, GenerationPhase::Hunt | GenerationPhase::Kill));
+            assert_eq!(step.linked.len(), 1);
+            assert!(step.unlinked.is_empty());
+        }
+        // every cell is carved by the end, leaving no frontier
+        assert!(details.last().unwrap().frontier.is_empty());
+    }
+
+    #[test]
+    fn generate_500_x_500_orthogonal_maze() {
+        // hunting from the frontier rather than rescanning the grid keeps generation roughly linear in the
+        // number of cells; scanning took minutes at this size
+        let mut grid = Grid::new(MazeType::Orthogonal, 500, 500, Coordinates { x: 0, y: 0 }, Coordinates { x: 499, y: 499 }, false).unwrap();
+        HuntAndKill.generate(&mut grid).expect("HuntAndKill maze generation failed");
+        assert!(grid.is_perfect_maze().unwrap());
+    }
 }
@@ -3,30 +3,30 @@ use crate::grid::Grid;
 use crate::cell::Coordinates;
 use crate::error::Error;
 
-use std::collections::{HashMap, HashSet};
 use rand::seq::SliceRandom;
-use rand::Rng;
+use crate::prelude::*;
+use alloc::collections::BTreeSet;
 
 // Disjoint-set data structure for tracking cell sets
-struct DisjointSet {
+pub(crate) struct DisjointSet {
     parent: HashMap<Coordinates, Coordinates>,
     rank: HashMap<Coordinates, u32>,
 }
 
 impl DisjointSet {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         DisjointSet {
             parent: HashMap::new(),
             rank: HashMap::new(),
         }
     }
 
-    fn make_set(&mut self, coords: Coordinates) {
+    pub(crate) fn make_set(&mut self, coords: Coordinates) {
         self.parent.insert(coords, coords);
         self.rank.insert(coords, 0);
     }
 
-    fn find(&mut self, coords: Coordinates) -> Option<Coordinates> {
+    pub(crate) fn find(&mut self, coords: Coordinates) -> Option<Coordinates> {
         if let Some(&parent) = self.parent.get(&coords) {
             if parent != coords {
                 let root = self.find(parent)?;
@@ -40,7 +40,7 @@ impl DisjointSet {
         }
     }
 
-    fn union(&mut self, coords1: Coordinates, coords2: Coordinates) -> bool {
+    pub(crate) fn union(&mut self, coords1: Coordinates, coords2: Coordinates) -> bool {
         let root1 = self.find(coords1);
         let root2 = self.find(coords2);
 
@@ -69,11 +69,25 @@ impl DisjointSet {
 
 pub struct Kruskals;
 
+// Every pair of neighboring cells once, as the lesser coordinates then the greater, in order. Neighbors are
+// compared as a pair rather than by direction, since in Delta and Sigma grids a neighbor can be above and to
+// the right of a cell while the cell is below and to the left of it.
+pub(crate) fn edges(grid: &Grid) -> Vec<(Coordinates, Coordinates)> {
+    let mut edges = BTreeSet::new();
+    for cell in grid.iter_cells() {
+        for neighbor in cell.neighbors() {
+            if grid.get(neighbor).is_ok() {
+                edges.insert((cell.coords.min(neighbor), cell.coords.max(neighbor)));
+            }
+        }
+    }
+    edges.into_iter().collect()
+}
+
 impl MazeGeneration for Kruskals {
     fn generate(&self, grid: &mut Grid) -> Result<(), Error> {
-        let mut rng = rand::thread_rng();
+        let mut rng = grid.forked_rng();
         let mut disjoint_set = DisjointSet::new();
-        let mut edges: Vec<(Coordinates, Coordinates, u32)> = Vec::new();
 
         // Step 1: Initialize sets for each cell
         for y in 0..grid.height {
@@ -84,19 +98,7 @@ impl MazeGeneration for Kruskals {
         }
 
         // Step 2: Collect all possible edges
-        for y in 0..grid.height {
-            for x in 0..grid.width {
-                let coords = Coordinates { x, y };
-                if let Ok(cell) = grid.get(coords) {
-                    for &neighbor_coords in cell.neighbors().iter() {
-                        // Only add edges in one direction to avoid duplicates
-                        if neighbor_coords.x > coords.x || neighbor_coords.y > coords.y {
-                            edges.push((coords, neighbor_coords, rng.gen()));
-                        }
-                    }
-                }
-            }
-        }
+        let mut edges = edges(grid);
 
         // Step 3: Shuffle edges for random selection
         edges.shuffle(&mut rng);
@@ -108,7 +110,7 @@ impl MazeGeneration for Kruskals {
         }
 
         // Step 4: Process edges to build the maze
-        for (coords1, coords2, _weight) in edges {
+        for (coords1, coords2) in edges {
             if disjoint_set.union(coords1, coords2) {
                 grid.link(coords1, coords2)?;
                 // Capture step with changed cells after linking
@@ -216,6 +218,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn edges_of_every_maze_type() {
+        let grid = Grid::new(MazeType::Orthogonal, 12, 6, Coordinates { x: 0, y: 0 }, Coordinates { x: 11, y: 5 }, false).unwrap();
+        assert_eq!(edges(&grid).len(), 11 * 6 + 12 * 5);
+
+        for &maze_type in MazeType::all() {
+            let grid = Grid::new(maze_type, 12, 6, Coordinates { x: 0, y: 0 }, Coordinates { x: 11, y: 5 }, false).unwrap();
+            let edges = edges(&grid);
+            // each neighbor relationship is recorded by both cells, and becomes exactly one edge, even where a
+            // Sigma cell on the edge of the grid has the same neighbor in two directions
+            let directed: usize = grid
+                .iter_cells()
+                .map(|cell| {
+                    let distinct: HashSet<Coordinates> = cell.neighbors().into_iter().filter(|&coords| grid.get(coords).is_ok()).collect();
+                    distinct.len()
+                })
+                .sum();
+            assert_eq!(edges.len() * 2, directed, "{:?}", maze_type);
+            for &(a, b) in &edges {
+                assert!(a < b, "{:?}", maze_type);
+                assert!(grid.get(a).unwrap().neighbors().contains(&b), "{:?}", maze_type);
+                assert!(grid.get(b).unwrap().neighbors().contains(&a), "{:?}", maze_type);
+            }
+        }
+    }
+
     #[test]
     fn test_kruskals_with_capture_steps() {
         let start = Coordinates { x: 0, y: 0 };
@@ -230,11 +258,11 @@ mod tests {
                 assert!(!steps.is_empty());
                 // Check if any cells become linked across all generation steps
                 let has_linked_cells = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.linked.is_empty())
+                    step.iter_cells().any(|cell| !cell.linked.is_empty())
                 });
                 assert!(has_linked_cells, "No cells were linked during maze generation");
                 let has_open_walls = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.open_walls.is_empty())
+                    step.iter_cells().any(|cell| !cell.open_walls.is_empty())
                 });
                 assert!(has_open_walls, "No cells have open walls in generation steps");
             }
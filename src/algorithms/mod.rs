@@ -1,9 +1,14 @@
-use std::fmt;
+use core::fmt;
+use core::str::FromStr;
+#[cfg(feature = "serde")]
 use serde::{ Serialize, Deserialize };
+#[cfg(feature = "json")]
 use crate::behaviors::display::JsonDisplay;
 use crate::behaviors::maze::MazeGeneration;
+use crate::cell::MazeType;
 use crate::error::Error;
 use crate::grid::Grid;
+use crate::trace::{trace_span, trace_timed};
 use crate::algorithms::binary_tree::BinaryTree;
 use crate::algorithms::sidewinder::Sidewinder;
 use crate::algorithms::aldous_broder::AldousBroder;
@@ -16,6 +21,10 @@ use crate::algorithms::growing_tree::{GrowingTree, SelectionStrategy};
 use crate::algorithms::ellers::Ellers;
 use crate::algorithms::recursive_division::RecursiveDivision;
 use crate::algorithms::reverse_delete::ReverseDelete;
+use crate::algorithms::parallel_kruskals::ParallelKruskals;
+use crate::algorithms::weighted_prims::WeightedPrims;
+use crate::algorithms::aldous_broder_wilsons::AldousBroderWilsons;
+use crate::prelude::*;
 
 pub mod binary_tree;
 pub mod sidewinder;
@@ -29,8 +38,136 @@ pub mod growing_tree;
 pub mod ellers;
 pub mod recursive_division;
 pub mod reverse_delete;
+pub mod parallel_kruskals;
+pub mod weighted_prims;
+pub mod aldous_broder_wilsons;
+pub mod resumable;
+#[cfg(test)]
+mod stats;
+
+/// Optional algorithm-specific settings supplied with a maze request.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AlgorithmParams {
+    /// Active cell selection for the Growing Tree algorithms, overriding the algorithm's own strategy.
+    pub strategy: Option<SelectionStrategy>,
+    /// Per-cell weights for WeightedPrims, indexed by row then column (`weights[y][x]`). Passages prefer
+    /// low-weight cells; when omitted, passages are weighted randomly.
+    pub weights: Option<Vec<Vec<u32>>>,
+    /// For RecursiveDivision, regions with fewer cells than this may be left open as rooms.
+    pub min_region_size: Option<usize>,
+    /// For RecursiveDivision, the probability (0.0 through 1.0) that a region smaller than `min_region_size`
+    /// is left as a room. Defaults to 1.0 when `min_region_size` is specified.
+    pub room_probability: Option<f64>,
+    /// For AldousBroderWilsons, the fraction of cells (0.0 through 1.0) visited by Aldous-Broder before switching
+    /// to Wilson's. Defaults to 0.3.
+    pub switch_threshold: Option<f64>,
+    /// For RecursiveBacktracker, the probability (0.0 through 1.0) of carving along the current row rather than
+    /// to another row whenever either is possible, e.g. 0.7 for mostly horizontal corridors.
+    pub horizontal_bias: Option<f64>,
+    /// For RecursiveBacktracker, the most cells carved in a single run before branching from an earlier cell.
+    pub max_run_length: Option<usize>,
+}
+
+impl AlgorithmParams {
+    /// Check the parameters are meaningful for the specified algorithm and maze dimensions.
+    pub fn validate(&self, algorithm: MazeAlgorithm, width: usize, height: usize) -> Result<(), Error> {
+        if let Some(strategy) = self.strategy {
+            if !matches!(algorithm, MazeAlgorithm::GrowingTreeRandom | MazeAlgorithm::GrowingTreeNewest) {
+                return Err(Error::InvalidAlgorithmParams {
+                    reason: format!("a selection strategy cannot be applied to {}", algorithm.name()),
+                });
+            }
+            if let SelectionStrategy::Mixed { newest_percent } = strategy {
+                if newest_percent > 100 {
+                    return Err(Error::InvalidAlgorithmParams {
+                        reason: format!("newest_percent {} exceeds 100", newest_percent),
+                    });
+                }
+            }
+        }
+        if let Some(weights) = &self.weights {
+            if algorithm != MazeAlgorithm::WeightedPrims {
+                return Err(Error::InvalidAlgorithmParams {
+                    reason: format!("weights cannot be applied to {}", algorithm.name()),
+                });
+            }
+            if weights.len() != height || weights.iter().any(|row| row.len() != width) {
+                return Err(Error::InvalidAlgorithmParams {
+                    reason: format!("weights must have {} rows of {} columns", height, width),
+                });
+            }
+        }
+        if (self.min_region_size.is_some() || self.room_probability.is_some()) && algorithm != MazeAlgorithm::RecursiveDivision {
+            return Err(Error::InvalidAlgorithmParams {
+                reason: format!("rooms cannot be applied to {}", algorithm.name()),
+            });
+        }
+        if let Some(probability) = self.room_probability {
+            if !(0.0..=1.0).contains(&probability) {
+                return Err(Error::InvalidAlgorithmParams {
+                    reason: format!("room_probability {} is not between 0.0 and 1.0", probability),
+                });
+            }
+        }
+        if let Some(threshold) = self.switch_threshold {
+            if algorithm != MazeAlgorithm::AldousBroderWilsons {
+                return Err(Error::InvalidAlgorithmParams {
+                    reason: format!("a switch threshold cannot be applied to {}", algorithm.name()),
+                });
+            }
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(Error::InvalidAlgorithmParams {
+                    reason: format!("switch_threshold {} is not between 0.0 and 1.0", threshold),
+                });
+            }
+        }
+        if (self.horizontal_bias.is_some() || self.max_run_length.is_some()) && algorithm != MazeAlgorithm::RecursiveBacktracker {
+            return Err(Error::InvalidAlgorithmParams {
+                reason: format!("corridor bias cannot be applied to {}", algorithm.name()),
+            });
+        }
+        if let Some(bias) = self.horizontal_bias {
+            if !(0.0..=1.0).contains(&bias) {
+                return Err(Error::InvalidAlgorithmParams {
+                    reason: format!("horizontal_bias {} is not between 0.0 and 1.0", bias),
+                });
+            }
+        }
+        if self.max_run_length == Some(0) {
+            return Err(Error::InvalidAlgorithmParams {
+                reason: "max_run_length must be at least 1".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// How many cells an algorithm can generate in reasonable time, from `MazeAlgorithm::recommended_limits`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SizeLimits {
+    /// The most cells the algorithm generates quickly enough for an interactive app, in well under a second.
+    pub recommended_cells: usize,
+    /// The most cells a request may ask the algorithm for; larger requests are rejected rather than left
+    /// running for minutes.
+    pub max_cells: usize,
+}
+
+impl SizeLimits {
+    /// Whether a maze of `cells` cells is within the recommended size.
+    pub fn recommends(&self, cells: usize) -> bool {
+        cells <= self.recommended_cells
+    }
+
+    /// Whether a maze of `cells` cells may be requested.
+    pub fn allows(&self, cells: usize) -> bool {
+        cells <= self.max_cells
+    }
+}
 
-#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MazeAlgorithm {
     BinaryTree,
     Sidewinder,
@@ -46,34 +183,185 @@ pub enum MazeAlgorithm {
     Ellers,
     RecursiveDivision,
     ReverseDelete,
+    ParallelKruskals,
+    WeightedPrims,
+    AldousBroderWilsons,
 }
 
 impl MazeAlgorithm {
-    pub fn generate<'a>(&self, grid: &'a mut Grid) -> Result<&'a Grid, Error> {
+    /// Every supported algorithm. New algorithms are only ever appended, so this ordering is stable across releases.
+    pub fn all() -> &'static [MazeAlgorithm] {
+        &[
+            MazeAlgorithm::BinaryTree,
+            MazeAlgorithm::Sidewinder,
+            MazeAlgorithm::AldousBroder,
+            MazeAlgorithm::Wilsons,
+            MazeAlgorithm::HuntAndKill,
+            MazeAlgorithm::RecursiveBacktracker,
+            MazeAlgorithm::Prims,
+            MazeAlgorithm::Kruskals,
+            MazeAlgorithm::GrowingTreeRandom,
+            MazeAlgorithm::GrowingTreeNewest,
+            MazeAlgorithm::Ellers,
+            MazeAlgorithm::RecursiveDivision,
+            MazeAlgorithm::ReverseDelete,
+            MazeAlgorithm::ParallelKruskals,
+            MazeAlgorithm::WeightedPrims,
+            MazeAlgorithm::AldousBroderWilsons,
+        ]
+    }
+
+    /// The algorithm's name, as used in JSON requests
+    pub fn name(&self) -> &'static str {
+        match self {
+            MazeAlgorithm::BinaryTree => "BinaryTree",
+            MazeAlgorithm::Sidewinder => "Sidewinder",
+            MazeAlgorithm::AldousBroder => "AldousBroder",
+            MazeAlgorithm::Wilsons => "Wilsons",
+            MazeAlgorithm::HuntAndKill => "HuntAndKill",
+            MazeAlgorithm::RecursiveBacktracker => "RecursiveBacktracker",
+            MazeAlgorithm::Prims => "Prims",
+            MazeAlgorithm::Kruskals => "Kruskals",
+            MazeAlgorithm::GrowingTreeRandom => "GrowingTreeRandom",
+            MazeAlgorithm::GrowingTreeNewest => "GrowingTreeNewest",
+            MazeAlgorithm::Ellers => "Ellers",
+            MazeAlgorithm::RecursiveDivision => "RecursiveDivision",
+            MazeAlgorithm::ReverseDelete => "ReverseDelete",
+            MazeAlgorithm::ParallelKruskals => "ParallelKruskals",
+            MazeAlgorithm::WeightedPrims => "WeightedPrims",
+            MazeAlgorithm::AldousBroderWilsons => "AldousBroderWilsons",
+        }
+    }
+
+    /// Whether the algorithm can generate mazes of the specified maze type. Generating an unsupported
+    /// combination fails with `Error::AlgorithmUnavailableForMazeType`.
+    pub fn supports(&self, maze_type: MazeType) -> bool {
         match self {
+            MazeAlgorithm::BinaryTree | MazeAlgorithm::Sidewinder => {
+                matches!(maze_type, MazeType::Orthogonal | MazeType::Sigma | MazeType::Upsilon)
+            }
+            MazeAlgorithm::Ellers => maze_type == MazeType::Orthogonal,
+            MazeAlgorithm::RecursiveDivision => maze_type != MazeType::Upsilon,
+            MazeAlgorithm::HuntAndKill | MazeAlgorithm::GrowingTreeRandom | MazeAlgorithm::GrowingTreeNewest => {
+                maze_type != MazeType::Rhombic
+            }
+            MazeAlgorithm::AldousBroder
+            | MazeAlgorithm::Wilsons
+            | MazeAlgorithm::RecursiveBacktracker
+            | MazeAlgorithm::Prims
+            | MazeAlgorithm::Kruskals
+            | MazeAlgorithm::ReverseDelete
+            | MazeAlgorithm::ParallelKruskals
+            | MazeAlgorithm::WeightedPrims
+            | MazeAlgorithm::AldousBroderWilsons => true,
+        }
+    }
+
+    /// How many cells the algorithm can generate in reasonable time. Random walks slow down sharply as mazes
+    /// grow, Aldous-Broder's most of all, and Reverse Delete searches the whole maze for every passage it
+    /// considers removing, while the other algorithms take time in proportion to the number of cells.
+    pub fn recommended_limits(&self) -> SizeLimits {
+        let (recommended_cells, max_cells) = match self {
+            MazeAlgorithm::AldousBroder => (10_000, 250_000),
+            MazeAlgorithm::Wilsons | MazeAlgorithm::AldousBroderWilsons => (40_000, 1_000_000),
+            MazeAlgorithm::ReverseDelete => (2_500, 40_000),
+            _ => (250_000, 4_000_000),
+        };
+        SizeLimits { recommended_cells, max_cells }
+    }
+
+    /// Algorithms which can generate `cells` cells of the maze type, fastest first, to suggest in place of one
+    /// which cannot.
+    pub fn alternatives_for(maze_type: MazeType, cells: usize) -> Vec<MazeAlgorithm> {
+        let mut alternatives: Vec<MazeAlgorithm> = MazeAlgorithm::all()
+            .iter()
+            .copied()
+            .filter(|algorithm| algorithm.supports(maze_type) && algorithm.recommended_limits().allows(cells))
+            .collect();
+        alternatives.sort_by_key(|algorithm| core::cmp::Reverse(algorithm.recommended_limits().recommended_cells));
+        alternatives
+    }
+
+    /// Whether the algorithm can generate multi-layer mazes, carving stairs between layers as readily as
+    /// passages within a layer.
+    pub fn supports_layers(&self) -> bool {
+        matches!(self, MazeAlgorithm::RecursiveBacktracker | MazeAlgorithm::Prims | MazeAlgorithm::Kruskals)
+    }
+
+    /// Whether the algorithm can generate a maze around rooms, each contracted into a single cell with any number
+    /// of neighbors. Algorithms which work along rows and columns, or visit every position of the grid, cannot.
+    pub fn supports_rooms(&self) -> bool {
+        self.supports(MazeType::Rhombic) && *self != MazeAlgorithm::RecursiveDivision
+    }
+
+    pub fn generate<'a>(&self, grid: &'a mut Grid) -> Result<&'a Grid, Error> {
+        self.generate_with_params(grid, &AlgorithmParams::default())
+    }
+
+    /// Begin generating the maze, carving for up to `budget_ms` milliseconds and returning the generation's
+    /// state, to be resumed with `GenerationState::resume` until it is finished. Lets apps generate huge mazes
+    /// across many frames; only some algorithms can be generated in parts (see `GenerationState`).
+    #[cfg(feature = "std")]
+    pub fn generate_partial(&self, grid: &mut Grid, budget_ms: u64) -> Result<resumable::GenerationState, Error> {
+        let mut state = resumable::GenerationState::start(*self, grid)?;
+        state.resume(grid, budget_ms)?;
+        Ok(state)
+    }
+
+    /// Generate the maze, applying any algorithm-specific parameters.
+    pub fn generate_with_params<'a>(&self, grid: &'a mut Grid, params: &AlgorithmParams) -> Result<&'a Grid, Error> {
+        trace_span!("algorithm", algorithm = self.name(), maze_type = grid.maze_type.name(), width = grid.width, height = grid.height);
+        trace_timed!(match self {
             MazeAlgorithm::BinaryTree => BinaryTree.build(grid),
             MazeAlgorithm::Sidewinder => Sidewinder.build(grid),
             MazeAlgorithm::AldousBroder => AldousBroder.build(grid),
             MazeAlgorithm::Wilsons => Wilsons.build(grid),
             MazeAlgorithm::HuntAndKill => HuntAndKill.build(grid),
-            MazeAlgorithm::RecursiveBacktracker => RecursiveBacktracker.build(grid),
+            MazeAlgorithm::RecursiveBacktracker => {
+                let recursive_backtracker = RecursiveBacktracker {
+                    horizontal_bias: params.horizontal_bias,
+                    max_run_length: params.max_run_length,
+                };
+                recursive_backtracker.build(grid)
+            }
             MazeAlgorithm::Prims => Prims.build(grid),
             MazeAlgorithm::Kruskals => Kruskals.build(grid),
             MazeAlgorithm::GrowingTreeRandom => {
-                let growing_tree = GrowingTree { strategy: SelectionStrategy::Random };
+                let growing_tree = GrowingTree { strategy: params.strategy.unwrap_or(SelectionStrategy::Random) };
                 growing_tree.build(grid)
             }
             MazeAlgorithm::GrowingTreeNewest => {
-                let growing_tree = GrowingTree { strategy: SelectionStrategy::Newest};
+                let growing_tree = GrowingTree { strategy: params.strategy.unwrap_or(SelectionStrategy::Newest) };
                 growing_tree.build(grid)
             }
             MazeAlgorithm::Ellers => Ellers.build(grid),
-            MazeAlgorithm::RecursiveDivision => RecursiveDivision.build(grid),
+            MazeAlgorithm::RecursiveDivision => {
+                let recursive_division = RecursiveDivision {
+                    min_region_size: params.min_region_size.unwrap_or_default(),
+                    room_probability: params.room_probability.unwrap_or(1.0),
+                };
+                recursive_division.build(grid)
+            }
             MazeAlgorithm::ReverseDelete => ReverseDelete.build(grid),
-        }
+            MazeAlgorithm::ParallelKruskals => ParallelKruskals.build(grid),
+            MazeAlgorithm::WeightedPrims => {
+                let weighted_prims = match &params.weights {
+                    Some(weights) => WeightedPrims::from_cell_weights(weights.clone()),
+                    None => WeightedPrims::random(),
+                };
+                weighted_prims.build(grid)
+            }
+            MazeAlgorithm::AldousBroderWilsons => {
+                let hybrid = AldousBroderWilsons {
+                    switch_threshold: params.switch_threshold.unwrap_or(AldousBroderWilsons::DEFAULT_SWITCH_THRESHOLD),
+                };
+                hybrid.build(grid)
+            }
+        }, algorithm = self.name(), "maze generated")
     }
 }
 
+#[cfg(feature = "json")]
 impl fmt::Display for MazeAlgorithm {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.to_json() {
@@ -82,10 +370,87 @@ impl fmt::Display for MazeAlgorithm {
         }
     }
 }
+// the JSON representation, as far as it can be written without serde
+#[cfg(not(feature = "json"))]
+impl fmt::Display for MazeAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\"", self.name())
+    }
+}
+
+impl FromStr for MazeAlgorithm {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MazeAlgorithm::all()
+            .iter()
+            .find(|algorithm| algorithm.name() == s)
+            .copied()
+            .ok_or_else(|| Error::InvalidMazeAlgorithm { algorithm: s.to_string() })
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use crate::generate;
+    use super::*;
+
+    #[test]
+    fn test_algorithm_names_round_trip() {
+        for algorithm in MazeAlgorithm::all() {
+            assert_eq!(MazeAlgorithm::from_str(algorithm.name()).unwrap(), *algorithm);
+            // names must match the serde representation used by JSON requests
+            assert_eq!(algorithm.to_json().unwrap(), format!("\"{}\"", algorithm.name()));
+        }
+        assert!(MazeAlgorithm::from_str("Dijkstra").is_err());
+    }
+
+    #[test]
+    fn oversized_requests_are_rejected_with_alternatives() {
+        let json = r#"{ "maze_type": "Orthogonal", "width": 1000, "height": 1000, "algorithm": "AldousBroder" }"#;
+        match Grid::try_from(json) {
+            Err(Error::MazeTooLargeForAlgorithm { algorithm, cells, max_cells, alternatives }) => {
+                assert_eq!((algorithm, cells), (MazeAlgorithm::AldousBroder, 1_000_000));
+                assert_eq!(max_cells, MazeAlgorithm::AldousBroder.recommended_limits().max_cells);
+                assert!(alternatives.contains(&MazeAlgorithm::HuntAndKill) && !alternatives.contains(&MazeAlgorithm::AldousBroder));
+            }
+            other => panic!("Unexpected result: {:?}", other.map(|grid| grid.count_edges())),
+        }
+        for algorithm in MazeAlgorithm::all() {
+            let limits = algorithm.recommended_limits();
+            assert!(limits.recommends(limits.recommended_cells) && limits.allows(limits.recommended_cells));
+            assert!(!limits.allows(limits.max_cells + 1));
+        }
+        // layers count toward the size
+        let json = r#"{ "maze_type": "Orthogonal", "width": 1500, "height": 1500, "layers": 2, "algorithm": "Kruskals" }"#;
+        assert!(matches!(Grid::try_from(json), Err(Error::MazeTooLargeForAlgorithm { cells: 4_500_000, .. })));
+    }
+
+    #[test]
+    fn test_supports_agrees_with_generation() {
+        for maze_type in MazeType::all() {
+            for algorithm in MazeAlgorithm::all() {
+                let json = format!(r#"
+                {{
+                    "maze_type": "{}",
+                    "width": 6,
+                    "height": 6,
+                    "algorithm": "{}"
+                }}
+                "#, maze_type.name(), algorithm.name());
+                let mut grid = Grid::new(*maze_type, 6, 6, crate::cell::Coordinates { x: 0, y: 0 }, crate::cell::Coordinates { x: 5, y: 5 }, false).unwrap();
+                let generated = algorithm.generate(&mut grid).map(|_| ());
+                match generated {
+                    Ok(()) => assert!(algorithm.supports(*maze_type), "{} unexpectedly generated a {} maze", algorithm.name(), maze_type.name()),
+                    Err(Error::AlgorithmUnavailableForMazeType { .. }) => assert!(!algorithm.supports(*maze_type)),
+                    Err(e) => panic!("Unexpected error generating {} {} maze: {:?}", algorithm.name(), maze_type.name(), e),
+                }
+                // unsupported combinations are rejected by request validation, before generation begins
+                if !algorithm.supports(*maze_type) {
+                    assert!(matches!(Grid::try_from(json), Err(Error::AlgorithmUnavailableForMazeType { .. })));
+                }
+            }
+        }
+    }
 
     #[test]
     fn test_recursive_backtracker_orthogonal_12_x_12_maze_generation_from_json() {
@@ -128,6 +493,26 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_parallel_kruskals_orthogonal_300_x_300_maze_generation_from_json() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 300,
+            "height": 300,
+            "algorithm": "ParallelKruskals",
+            "start": { "x": 0, "y": 0 },
+            "goal": { "x": 299, "y": 299 }
+        }
+        "#;
+        match generate(json) {
+            Ok(maze) => {
+                assert!(maze.is_perfect_maze().unwrap());
+            }
+            Err(e) => panic!("Unexpected error running test: {:?}", e),
+        }
+    }
+
     #[test]
     fn test_recursive_backtracker_orthogonal_400_x_400_maze_generation_from_json() {
         let json = r#"
@@ -147,4 +532,126 @@ mod tests {
             Err(e) => panic!("Unexpected error running test: {:?}", e),
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_growing_tree_strategy_from_algorithm_params() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 12,
+            "height": 12,
+            "algorithm": "GrowingTreeRandom",
+            "algorithm_params": { "strategy": { "Mixed": { "newest_percent": 50 } } }
+        }
+        "#;
+        let request: crate::request::MazeRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.algorithm_params.as_ref().unwrap().strategy, Some(SelectionStrategy::Mixed { newest_percent: 50 }));
+        let maze = generate(json).expect("Growing Tree maze generation failed");
+        assert!(maze.is_perfect_maze().unwrap());
+
+        let newest = generate(&json.replace(r#"{ "Mixed": { "newest_percent": 50 } }"#, r#""Newest""#)).unwrap();
+        assert!(newest.is_perfect_maze().unwrap());
+
+        let too_many = json.replace("50", "150");
+        assert!(matches!(generate(&too_many), Err(Error::InvalidAlgorithmParams { .. })));
+        let not_growing_tree = json.replace("GrowingTreeRandom", "Prims");
+        assert!(matches!(generate(&not_growing_tree), Err(Error::InvalidAlgorithmParams { .. })));
+    }
+
+    #[test]
+    fn test_weighted_prims_weights_from_algorithm_params() {
+        let rows: Vec<String> = (0..4).map(|y| format!("[{}]", [if y == 0 { "1" } else { "50" }; 5].join(", "))).collect();
+        let json = format!(r#"
+        {{
+            "maze_type": "Orthogonal",
+            "width": 5,
+            "height": 4,
+            "algorithm": "WeightedPrims",
+            "algorithm_params": {{ "weights": [{}] }}
+        }}
+        "#, rows.join(", "));
+        let maze = generate(&json).expect("Weighted Prim's maze generation failed");
+        assert!(maze.is_perfect_maze().unwrap());
+        // the cheap top row is carved as a single corridor
+        for x in 0..4 {
+            assert!(maze.get(crate::cell::Coordinates { x, y: 0 }).unwrap().linked.contains(&crate::cell::Coordinates { x: x + 1, y: 0 }));
+        }
+
+        let mismatched = json.replace(r#""height": 4"#, r#""height": 5"#);
+        assert!(matches!(generate(&mismatched), Err(Error::InvalidAlgorithmParams { .. })));
+        let not_weighted = json.replace("WeightedPrims", "Prims");
+        assert!(matches!(generate(&not_weighted), Err(Error::InvalidAlgorithmParams { .. })));
+    }
+
+    #[test]
+    fn test_recursive_division_rooms_from_algorithm_params() {
+        let json = r#"
+        {
+            "maze_type": "Sigma",
+            "width": 10,
+            "height": 10,
+            "algorithm": "RecursiveDivision",
+            "algorithm_params": { "min_region_size": 200 }
+        }
+        "#;
+        let maze = generate(json).expect("RecursiveDivision maze generation failed");
+        // the whole grid is below the threshold and left as one open room
+        assert!(!maze.is_perfect_maze().unwrap());
+
+        let invalid_probability = json.replace(r#""min_region_size": 200"#, r#""min_region_size": 8, "room_probability": 1.5"#);
+        assert!(matches!(generate(&invalid_probability), Err(Error::InvalidAlgorithmParams { .. })));
+        let not_recursive_division = json.replace("RecursiveDivision", "Prims");
+        assert!(matches!(generate(&not_recursive_division), Err(Error::InvalidAlgorithmParams { .. })));
+    }
+
+    #[test]
+    fn test_aldous_broder_wilsons_switch_threshold_from_algorithm_params() {
+        let json = r#"
+        {
+            "maze_type": "Upsilon",
+            "width": 10,
+            "height": 10,
+            "algorithm": "AldousBroderWilsons",
+            "algorithm_params": { "switch_threshold": 0.6 }
+        }
+        "#;
+        let maze = generate(json).expect("AldousBroderWilsons maze generation failed");
+        assert!(maze.is_perfect_maze().unwrap());
+
+        let invalid_threshold = json.replace("0.6", "1.5");
+        assert!(matches!(generate(&invalid_threshold), Err(Error::InvalidAlgorithmParams { .. })));
+        let not_hybrid = json.replace("AldousBroderWilsons", "AldousBroder");
+        assert!(matches!(generate(&not_hybrid), Err(Error::InvalidAlgorithmParams { .. })));
+    }
+
+    #[test]
+    fn test_recursive_backtracker_bias_from_algorithm_params() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 20,
+            "height": 20,
+            "algorithm": "RecursiveBacktracker",
+            "algorithm_params": { "horizontal_bias": 1.0 }
+        }
+        "#;
+        let horizontal_passages = |maze: &Grid| maze.iter_linked_pairs().filter(|(a, b)| a.coords.y == b.coords.y).count();
+        let horizontal = generate(json).expect("RecursiveBacktracker maze generation failed");
+        assert!(horizontal.is_perfect_maze().unwrap());
+        let vertical = generate(&json.replace("1.0", "0.0")).unwrap();
+        assert!(vertical.is_perfect_maze().unwrap());
+        // 399 passages in all, about half of them horizontal without bias
+        assert!(horizontal_passages(&horizontal) > 300, "{}", horizontal_passages(&horizontal));
+        assert!(horizontal_passages(&vertical) < 100, "{}", horizontal_passages(&vertical));
+
+        let branching = generate(&json.replace(r#""horizontal_bias": 1.0"#, r#""max_run_length": 3"#)).unwrap();
+        assert!(branching.is_perfect_maze().unwrap());
+
+        let invalid_bias = json.replace("1.0", "1.5");
+        assert!(matches!(generate(&invalid_bias), Err(Error::InvalidAlgorithmParams { .. })));
+        let invalid_run = json.replace(r#""horizontal_bias": 1.0"#, r#""max_run_length": 0"#);
+        assert!(matches!(generate(&invalid_run), Err(Error::InvalidAlgorithmParams { .. })));
+        let not_backtracker = json.replace("RecursiveBacktracker", "Prims");
+        assert!(matches!(generate(&not_backtracker), Err(Error::InvalidAlgorithmParams { .. })));
+    }
+}
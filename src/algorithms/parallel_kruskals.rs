@@ -0,0 +1,244 @@
+use crate::behaviors::maze::MazeGeneration;
+use crate::grid::Grid;
+use crate::cell::Coordinates;
+use crate::error::Error;
+use crate::rng::GridRng;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use crate::prelude::*;
+
+/// Number of grid rows carved together as one independent strip.
+const ROWS_PER_STRIP: usize = 64;
+
+// Disjoint-set over flattened cell indices, cheaper than a coordinate-keyed map for huge grids
+struct IndexDisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+}
+
+impl IndexDisjointSet {
+    fn new(size: usize) -> Self {
+        IndexDisjointSet {
+            parent: (0..size).collect(),
+            rank: vec![0; size],
+        }
+    }
+
+    fn find(&mut self, index: usize) -> usize {
+        let mut root = index;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // Path compression
+        let mut current = index;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    fn union(&mut self, index1: usize, index2: usize) -> bool {
+        let root1 = self.find(index1);
+        let root2 = self.find(index2);
+        if root1 == root2 {
+            return false; // Already in the same set
+        }
+        // Union by rank
+        match self.rank[root1].cmp(&self.rank[root2]) {
+            core::cmp::Ordering::Less => self.parent[root1] = root2,
+            core::cmp::Ordering::Greater => self.parent[root2] = root1,
+            core::cmp::Ordering::Equal => {
+                self.parent[root2] = root1;
+                self.rank[root1] += 1;
+            }
+        }
+        true
+    }
+}
+
+/// Kruskal's algorithm split into horizontal strips which are carved independently (in parallel when the
+/// `rayon` feature is enabled) and then seamed together with a final Kruskal's pass over the edges crossing
+/// strip boundaries. Intended for very large grids where the single-threaded algorithms become slow.
+pub struct ParallelKruskals;
+
+impl MazeGeneration for ParallelKruskals {
+    fn generate(&self, grid: &mut Grid) -> Result<(), Error> {
+        let width = grid.width;
+        let strip_count = grid.height.div_ceil(ROWS_PER_STRIP);
+
+        // Step 1: Collect each undirected edge once, either within a single strip or crossing a seam
+        let mut strip_edges: Vec<Vec<(Coordinates, Coordinates)>> = vec![Vec::new(); strip_count];
+        let mut seam_edges: Vec<(Coordinates, Coordinates)> = Vec::new();
+        for cell in grid.iter_cells() {
+            let index = grid.get_flattened_index(cell.coords.x, cell.coords.y);
+            for neighbor in cell.neighbors() {
+                if grid.get_flattened_index(neighbor.x, neighbor.y) <= index {
+                    continue; // the neighbor owns this edge
+                }
+                let strip = cell.coords.y / ROWS_PER_STRIP;
+                if neighbor.y / ROWS_PER_STRIP == strip {
+                    strip_edges[strip].push((cell.coords, neighbor));
+                } else {
+                    seam_edges.push((cell.coords, neighbor));
+                }
+            }
+        }
+
+        // Step 2: Carve a spanning forest within each strip independently, each strip with its own seed drawn
+        // from the grid's random numbers
+        let seeds: Vec<u64> = (0..strip_count).map(|_| grid.rng().gen()).collect();
+        #[cfg(feature = "rayon")]
+        let carved: Vec<Vec<(Coordinates, Coordinates)>> = strip_edges
+            .into_par_iter()
+            .zip(seeds)
+            .enumerate()
+            .map(|(strip, (edges, seed))| Self::carve_strip(strip, width, edges, seed))
+            .collect();
+        #[cfg(not(feature = "rayon"))]
+        let carved: Vec<Vec<(Coordinates, Coordinates)>> = strip_edges
+            .into_iter()
+            .zip(seeds)
+            .enumerate()
+            .map(|(strip, (edges, seed))| Self::carve_strip(strip, width, edges, seed))
+            .collect();
+
+        // Step 3: Seam the strips together, only opening seam edges which join separate sets
+        let mut disjoint_set = IndexDisjointSet::new(width * grid.height);
+        let mut passages: Vec<(Coordinates, Coordinates)> = Vec::new();
+        for (c1, c2) in carved.into_iter().flatten() {
+            disjoint_set.union(grid.get_flattened_index(c1.x, c1.y), grid.get_flattened_index(c2.x, c2.y));
+            passages.push((c1, c2));
+        }
+        seam_edges.shuffle(grid.rng());
+        for (c1, c2) in seam_edges {
+            if disjoint_set.union(grid.get_flattened_index(c1.x, c1.y), grid.get_flattened_index(c2.x, c2.y)) {
+                passages.push((c1, c2));
+            }
+        }
+
+        // Capture initial state with no changed cells
+        if grid.capture_steps {
+            let changed_cells = HashSet::new();
+            self.capture_step(grid, &changed_cells);
+        }
+
+        // Step 4: Link the chosen passages into the grid
+        for (c1, c2) in passages {
+            grid.link(c1, c2)?;
+            if grid.capture_steps {
+                let mut changed_cells = HashSet::new();
+                changed_cells.insert(c1);
+                changed_cells.insert(c2);
+                self.capture_step(grid, &changed_cells);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ParallelKruskals {
+    /// Runs Kruskal's algorithm over the edges of a single strip, returning the edges to carve.
+    fn carve_strip(
+        strip: usize,
+        width: usize,
+        mut edges: Vec<(Coordinates, Coordinates)>,
+        seed: u64,
+    ) -> Vec<(Coordinates, Coordinates)> {
+        let first_row = strip * ROWS_PER_STRIP;
+        // index cells relative to the strip's first row so each strip only allocates its own sets
+        let local_index = |coords: Coordinates| (coords.y - first_row) * width + coords.x;
+        let mut disjoint_set = IndexDisjointSet::new(ROWS_PER_STRIP * width);
+        edges.shuffle(&mut GridRng::seed_from_u64(seed));
+        edges
+            .into_iter()
+            .filter(|&(c1, c2)| disjoint_set.union(local_index(c1), local_index(c2)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::{MazeType, Coordinates};
+
+    #[test]
+    fn generate_and_print_5_x_5_orthogonal_maze() {
+        match Grid::new(MazeType::Orthogonal, 5, 5, Coordinates { x: 0, y: 0 }, Coordinates { x: 4, y: 4 }, false) {
+            Ok(mut grid) => {
+                assert!(!grid.is_perfect_maze().unwrap());
+                ParallelKruskals.generate(&mut grid).expect("ParallelKruskals maze generation failed");
+                println!("\n\nParallel Kruskal's\n\n{}\n\n", grid.to_asci());
+                assert!(grid.is_perfect_maze().unwrap());
+            }
+            Err(e) => panic!("Unexpected error running test: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn generate_multi_strip_orthogonal_maze() {
+        // tall enough to span several strips and seams
+        match Grid::new(MazeType::Orthogonal, 40, 200, Coordinates { x: 0, y: 0 }, Coordinates { x: 39, y: 199 }, false) {
+            Ok(mut grid) => {
+                ParallelKruskals.generate(&mut grid).expect("ParallelKruskals maze generation failed");
+                assert!(grid.is_perfect_maze().unwrap());
+            }
+            Err(e) => panic!("Unexpected error running test: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn generate_multi_strip_delta_maze() {
+        match Grid::new(MazeType::Delta, 30, 150, Coordinates { x: 0, y: 0 }, Coordinates { x: 29, y: 149 }, false) {
+            Ok(mut grid) => {
+                ParallelKruskals.generate(&mut grid).expect("ParallelKruskals maze generation failed");
+                assert!(grid.is_perfect_maze().unwrap());
+            }
+            Err(e) => panic!("Unexpected error running test: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn generate_multi_strip_sigma_maze() {
+        match Grid::new(MazeType::Sigma, 30, 150, Coordinates { x: 0, y: 0 }, Coordinates { x: 29, y: 149 }, false) {
+            Ok(mut grid) => {
+                ParallelKruskals.generate(&mut grid).expect("ParallelKruskals maze generation failed");
+                assert!(grid.is_perfect_maze().unwrap());
+            }
+            Err(e) => panic!("Unexpected error running test: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn generate_multi_strip_rhombic_maze() {
+        match Grid::new(MazeType::Rhombic, 30, 150, Coordinates { x: 0, y: 0 }, Coordinates { x: 28, y: 148 }, false) {
+            Ok(mut grid) => {
+                ParallelKruskals.generate(&mut grid).expect("ParallelKruskals maze generation failed");
+                assert!(grid.is_perfect_maze().unwrap());
+            }
+            Err(e) => panic!("Unexpected error running test: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_parallel_kruskals_with_capture_steps() {
+        let start = Coordinates { x: 0, y: 0 };
+        let goal = Coordinates { x: 11, y: 11 };
+        match Grid::new(MazeType::Orthogonal, 12, 12, start, goal, true) {
+            Ok(mut grid) => {
+                ParallelKruskals.generate(&mut grid).expect("Maze generation failed");
+                assert!(grid.is_perfect_maze().unwrap());
+                let steps = grid.generation_steps.as_ref().unwrap();
+                // initial state plus one step per carved passage
+                assert_eq!(steps.len(), 12 * 12);
+            }
+            Err(e) => panic!("Unexpected error generating grid: {:?}", e),
+        }
+    }
+}
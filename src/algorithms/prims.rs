@@ -2,9 +2,11 @@ use crate::behaviors::maze::MazeGeneration;
 use crate::grid::Grid;
 use crate::cell::Coordinates;
 use crate::error::Error;
+use crate::events::GenerationPhase;
 
-use std::collections::{BinaryHeap, HashSet};
+use alloc::collections::BinaryHeap;
 use rand::Rng;
+use crate::prelude::*;
 
 // A structure to hold frontier cells with their weights for Prim's algorithm
 #[derive(Eq, PartialEq)]
@@ -14,14 +16,14 @@ struct FrontierCell {
 }
 
 impl Ord for FrontierCell {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         // Reverse ordering to make BinaryHeap a min-heap (lower weights first)
         other.weight.cmp(&self.weight)
     }
 }
 
 impl PartialOrd for FrontierCell {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
@@ -32,7 +34,7 @@ impl MazeGeneration for Prims {
     fn generate(&self, grid: &mut Grid) -> Result<(), Error> {
         let mut visited: HashSet<Coordinates> = HashSet::new();
         let mut frontier: BinaryHeap<FrontierCell> = BinaryHeap::new();
-        let mut rng = rand::thread_rng();
+        let mut rng = grid.forked_rng();
 
         // Step 1: Choose a random starting cell that exists in the grid
         let start_coords;
@@ -89,16 +91,17 @@ impl MazeGeneration for Prims {
             };
 
             // Link to a visited neighbor if available
+            let mut linked = None;
             if !visited_neighbors.is_empty() {
                 let neighbor_index = grid.bounded_random_usize(visited_neighbors.len());
                 let neighbor_coords = visited_neighbors[neighbor_index];
                 grid.link(coords, neighbor_coords)?;
-                // Capture state after each link is made
+                // Capture state after each link is made, once the frontier has grown
                 if grid.capture_steps {
                     let mut changed_cells = HashSet::new();
                     changed_cells.insert(coords);
                     changed_cells.insert(neighbor_coords);
-                    self.capture_step(grid, &changed_cells);
+                    linked = Some(changed_cells);
                 }
             }
 
@@ -109,6 +112,13 @@ impl MazeGeneration for Prims {
                     weight: rng.gen(), // Assign a random weight
                 });
             }
+
+            if let Some(changed_cells) = linked {
+                let mut frontier_cells: Vec<Coordinates> = frontier.iter().map(|cell| cell.coords).filter(|coords| !visited.contains(coords)).collect();
+                frontier_cells.sort();
+                frontier_cells.dedup();
+                self.capture_phase(grid, &changed_cells, GenerationPhase::Carve, frontier_cells);
+            }
         }
 
         Ok(())
@@ -207,11 +217,11 @@ mod tests {
                 let steps = grid.generation_steps.as_ref().unwrap(); assert!(!steps.is_empty());
                 // Check if any cells become linked across all generation steps
                 let has_linked_cells = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.linked.is_empty())
+                    step.iter_cells().any(|cell| !cell.linked.is_empty())
                 });
                 assert!(has_linked_cells, "No cells were linked during maze generation");
                 let has_open_walls = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.open_walls.is_empty())
+                    step.iter_cells().any(|cell| !cell.open_walls.is_empty())
                 });
                 assert!(has_open_walls, "No cells have open walls in generation steps");
             }
@@ -249,14 +259,14 @@ mod tests {
 // }
 
 // impl Ord for FrontierCell {
-//     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+//     fn cmp(&self, other: &Self) -> core::cmp::Ordering {
 //         // Reverse ordering to make BinaryHeap a min-heap (lower weights first)
 //         other.weight.cmp(&self.weight)
 //     }
 // }
 
 // impl PartialOrd for FrontierCell {
-//     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+//     fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
 //         Some(self.cmp(other))
 //     }
 // }
@@ -276,7 +286,7 @@ mod tests {
 //         }
 //         let mut visited: HashSet<Coordinates> = HashSet::new();
 //         let mut frontier: BinaryHeap<FrontierCell> = BinaryHeap::new();
-//         let mut rng = rand::thread_rng();
+//         let mut rng = grid.forked_rng();
 
 //         // Step 1: Choose a random starting cell
 //         let start_coords = Coordinates {
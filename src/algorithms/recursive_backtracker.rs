@@ -2,10 +2,20 @@ use crate::behaviors::maze::MazeGeneration;
 use crate::grid::Grid;
 use crate::cell::Coordinates;
 use crate::error::Error;
-
-use std::collections::HashSet;
-
-pub struct RecursiveBacktracker;
+use crate::events::GenerationPhase;
+use rand::Rng;
+use crate::prelude::*;
+
+
+#[derive(Default)]
+pub struct RecursiveBacktracker {
+    /// Probability (0.0 through 1.0) of carving along the current row, rather than to another row, whenever
+    /// either is possible. Neighbors are chosen uniformly when `None`.
+    pub horizontal_bias: Option<f64>,
+    /// Cells carved in a single run before branching from a random earlier cell of the path. Runs continue
+    /// until they reach a dead end when `None`.
+    pub max_run_length: Option<usize>,
+}
 
 impl MazeGeneration for RecursiveBacktracker {
     fn generate(&self, grid: &mut Grid) -> Result<(), Error> {
@@ -23,6 +33,9 @@ impl MazeGeneration for RecursiveBacktracker {
             self.capture_step(grid, &changed_cells);
         }
 
+        // cells carved since the last backtrack or branch
+        let mut run_length = 0;
+
         while let Some(current_coords) = stack.last().cloned() {
             // Get all unvisited neighbors
             let neighbors: Vec<Coordinates> = grid
@@ -35,34 +48,59 @@ impl MazeGeneration for RecursiveBacktracker {
             if neighbors.is_empty() {
                 // Backtrack if no unvisited neighbors
                 stack.pop();
+                run_length = 0;
+            } else if self.max_run_length.is_some_and(|max| run_length >= max) && stack.len() > 1 {
+                // Branch from a random earlier cell of the path, which stays on the stack (as does every other
+                // cell) until it has no unvisited neighbors left
+                let branch = grid.bounded_random_usize(stack.len() - 1);
+                let branch_coords = stack.remove(branch);
+                stack.push(branch_coords);
+                run_length = 0;
             } else {
-                // Choose a random unvisited neighbor
-                let random_index = {
-                    let upper_bound = neighbors.len();
-                    grid.bounded_random_usize(upper_bound)
-                };
-                let next_coords = neighbors[random_index];
+                let next_coords = self.choose(grid, current_coords, &neighbors);
+                run_length += 1;
 
                 // Link current cell to the chosen neighbor
                 grid.link(current_coords, next_coords)?;
 
-                // Capture state after linking if capture_steps is true
+                // Mark the neighbor as visited and push it onto the stack
+                visited.insert(next_coords);
+                stack.push(next_coords);
+
+                // Capture state after linking if capture_steps is true, with the path carved from
                 if grid.capture_steps {
                     let mut changed_cells = HashSet::new();
                     changed_cells.insert(current_coords);
                     changed_cells.insert(next_coords);
-                    self.capture_step(grid, &changed_cells);
+                    self.capture_phase(grid, &changed_cells, GenerationPhase::Carve, stack.clone());
                 }
-
-                // Mark the neighbor as visited and push it onto the stack
-                visited.insert(next_coords);
-                stack.push(next_coords);
             }
         }
         Ok(())
     }
 }
 
+impl RecursiveBacktracker {
+    // Choose a random unvisited neighbor, weighted by the horizontal bias if there is one
+    fn choose(&self, grid: &mut Grid, current: Coordinates, neighbors: &[Coordinates]) -> Coordinates {
+        let candidates: Vec<Coordinates> = match self.horizontal_bias {
+            Some(bias) => {
+                let (horizontal, vertical): (Vec<Coordinates>, Vec<Coordinates>) =
+                    neighbors.iter().partition(|neighbor| neighbor.y == current.y);
+                if horizontal.is_empty() || vertical.is_empty() {
+                    neighbors.to_vec()
+                } else if grid.rng().gen_bool(bias) {
+                    horizontal
+                } else {
+                    vertical
+                }
+            }
+            None => neighbors.to_vec(),
+        };
+        candidates[grid.bounded_random_usize(candidates.len())]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,7 +111,7 @@ mod tests {
         match Grid::new(MazeType::Orthogonal, 4, 4, Coordinates { x: 0, y: 0 }, Coordinates { x: 3, y: 3 }, false) {
             Ok(mut grid) => {
                 assert!(!grid.is_perfect_maze().unwrap());
-                RecursiveBacktracker.generate(&mut grid).expect("RecursiveBacktracker maze generation failed");
+                RecursiveBacktracker::default().generate(&mut grid).expect("RecursiveBacktracker maze generation failed");
                 println!("\n\nRecursive Backtracker\n\n{}\n\n", grid.to_asci());
                 assert!(grid.is_perfect_maze().unwrap());
             }
@@ -86,7 +124,7 @@ mod tests {
         match Grid::new(MazeType::Orthogonal, 12, 6, Coordinates { x: 0, y: 0 }, Coordinates { x: 11, y: 5 }, false) {
             Ok(mut grid) => {
                 assert!(!grid.is_perfect_maze().unwrap());
-                RecursiveBacktracker.generate(&mut grid).expect("RecursiveBacktracker maze generation failed");
+                RecursiveBacktracker::default().generate(&mut grid).expect("RecursiveBacktracker maze generation failed");
                 println!("\n\nRecursive Backtracker\n\n{}\n\n", grid.to_asci());
                 assert!(grid.is_perfect_maze().unwrap());
             }
@@ -99,7 +137,7 @@ mod tests {
         match Grid::new(MazeType::Delta, 4, 4, Coordinates { x: 0, y: 0 }, Coordinates { x: 3, y: 3 }, false) {
             Ok(mut grid) => {
                 assert!(!grid.is_perfect_maze().unwrap());
-                RecursiveBacktracker.generate(&mut grid).expect("RecursiveBacktracker maze generation failed");
+                RecursiveBacktracker::default().generate(&mut grid).expect("RecursiveBacktracker maze generation failed");
                 assert!(grid.is_perfect_maze().unwrap());
             }
             Err(e) => panic!("Unexpected error occurred running test: {:?}", e),
@@ -111,7 +149,7 @@ mod tests {
         match Grid::new(MazeType::Delta, 12, 6, Coordinates { x: 0, y: 0 }, Coordinates { x: 11, y: 5 }, false) {
             Ok(mut grid) => {
                 assert!(!grid.is_perfect_maze().unwrap());
-                RecursiveBacktracker.generate(&mut grid).expect("RecursiveBacktracker maze generation failed");
+                RecursiveBacktracker::default().generate(&mut grid).expect("RecursiveBacktracker maze generation failed");
                 assert!(grid.is_perfect_maze().unwrap());
             }
             Err(e) => panic!("Unexpected error running test: {:?}", e),
@@ -123,7 +161,7 @@ mod tests {
         match Grid::new(MazeType::Sigma, 4, 4, Coordinates { x: 0, y: 0 }, Coordinates { x: 3, y: 3 }, false) {
             Ok(mut grid) => {
                 assert!(!grid.is_perfect_maze().unwrap());
-                RecursiveBacktracker.generate(&mut grid).expect("RecursiveBacktracker maze generation failed");
+                RecursiveBacktracker::default().generate(&mut grid).expect("RecursiveBacktracker maze generation failed");
                 assert!(grid.is_perfect_maze().unwrap());
             }
             Err(e) => panic!("Unexpected error occurred running test: {:?}", e),
@@ -135,7 +173,7 @@ mod tests {
         match Grid::new(MazeType::Sigma, 12, 6, Coordinates { x: 0, y: 0 }, Coordinates { x: 11, y: 5 }, false) {
             Ok(mut grid) => {
                 assert!(!grid.is_perfect_maze().unwrap());
-                RecursiveBacktracker.generate(&mut grid).expect("RecursiveBacktracker maze generation failed");
+                RecursiveBacktracker::default().generate(&mut grid).expect("RecursiveBacktracker maze generation failed");
                 assert!(grid.is_perfect_maze().unwrap());
             }
             Err(e) => panic!("Unexpected error running test: {:?}", e),
@@ -147,7 +185,7 @@ mod tests {
         match Grid::new(MazeType::Rhombic, 12, 6, Coordinates { x: 0, y: 0 }, Coordinates { x: 11, y: 5 }, false) {
             Ok(mut grid) => {
                 assert!(!grid.is_perfect_maze().unwrap());
-                RecursiveBacktracker.generate(&mut grid).expect("RecursiveBacktracker maze generation failed");
+                RecursiveBacktracker::default().generate(&mut grid).expect("RecursiveBacktracker maze generation failed");
                 assert!(grid.is_perfect_maze().unwrap());
             }
             Err(e) => panic!("Unexpected error running test: {:?}", e),
@@ -161,17 +199,17 @@ mod tests {
         match Grid::new(MazeType::Orthogonal, 20, 20, start, goal, true) {
             Ok(mut grid) => {
                 assert!(!grid.is_perfect_maze().unwrap());
-                RecursiveBacktracker.generate(&mut grid).expect("Maze generation failed");
+                RecursiveBacktracker::default().generate(&mut grid).expect("Maze generation failed");
                 assert!(grid.is_perfect_maze().unwrap());
                 assert!(grid.generation_steps.is_some());
                 let steps = grid.generation_steps.as_ref().unwrap(); assert!(!steps.is_empty());
                 // Check if any cells become linked across all generation steps
                 let has_linked_cells = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.linked.is_empty())
+                    step.iter_cells().any(|cell| !cell.linked.is_empty())
                 });
                 assert!(has_linked_cells, "No cells were linked during maze generation");
                 let has_open_walls = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.open_walls.is_empty())
+                    step.iter_cells().any(|cell| !cell.open_walls.is_empty())
                 });
                 assert!(has_open_walls, "No cells have open walls in generation steps");
             }
@@ -1,17 +1,31 @@
 use crate::behaviors::maze::MazeGeneration;
 use crate::algorithms::MazeAlgorithm;
+use crate::algorithms::kruskals::DisjointSet;
 use crate::grid::Grid;
 use crate::cell::{Coordinates, MazeType};
 use crate::error::Error;
-use std::collections::{HashMap, HashSet};
+use rand::Rng;
+use crate::prelude::*;
 
-pub struct RecursiveDivision;
+pub struct RecursiveDivision {
+    /// Regions with fewer cells than this may be left undivided, as open rooms.
+    pub min_region_size: usize,
+    /// Probability (0.0 through 1.0) that a region smaller than `min_region_size` is left as a room.
+    pub room_probability: f64,
+}
+
+impl Default for RecursiveDivision {
+    /// Divide all the way down to single cells, producing a perfect maze.
+    fn default() -> Self {
+        RecursiveDivision { min_region_size: 0, room_probability: 0.0 }
+    }
+}
 
 impl MazeGeneration for RecursiveDivision {
     fn generate(&self, grid: &mut Grid) -> Result<(), Error> {
         // Check supported maze types
         match grid.maze_type {
-            MazeType::Orthogonal | MazeType::Rhombic => {},
+            MazeType::Orthogonal | MazeType::Rhombic | MazeType::Delta | MazeType::Sigma => {},
             maze_type => {
                 return Err(Error::AlgorithmUnavailableForMazeType {
                     algorithm: MazeAlgorithm::RecursiveDivision,
@@ -26,7 +40,7 @@ impl MazeGeneration for RecursiveDivision {
                 if grid.has_cell(x, y) {
                     let coords = Coordinates { x, y };
 
-                    let neighbors: Vec<Coordinates> = grid.get(coords).unwrap().neighbors_by_direction.values().copied().collect();
+                    let neighbors: Vec<Coordinates> = grid.get(coords).unwrap().neighbors_by_direction.values().collect();
                     for neighbor_coords in neighbors {
                         grid.link(coords, neighbor_coords)?;
                     }
@@ -56,6 +70,9 @@ impl RecursiveDivision {
         if region.len() <= 1 {
             return Ok(());
         }
+        if region.len() < self.min_region_size && grid.rng().gen_bool(self.room_probability.clamp(0.0, 1.0)) {
+            return Ok(()); // leave the region open as a room
+        }
 
         // Compute transformed coordinates based on maze type
         let u_v: HashMap<Coordinates, (isize, isize)> = region.iter().map(|&coords| {
@@ -64,7 +81,7 @@ impl RecursiveDivision {
             let (u, v) = match grid.maze_type {
                 MazeType::Orthogonal => (x, y),
                 MazeType::Rhombic => ((x + y) / 2, (x - y) / 2),
-                _ => unreachable!(), // Checked in generate
+                _ => (x, y), // Delta and Sigma rows and columns are connected, as long as Delta columns stay paired
             };
             (coords, (u, v))
         }).collect();
@@ -76,7 +93,14 @@ impl RecursiveDivision {
         let max_v = u_v.values().map(|&(_, v)| v).max().unwrap();
 
         // Choose division direction based on range
-        let divide_along_u = if (max_u - min_u) > (max_v - min_v) {
+        // A lone column of triangles is only connected every other row, so Delta regions spanning several rows
+        // are only divided into columns at least two cells wide
+        let paired_columns = grid.maze_type == MazeType::Delta && max_v > min_v;
+        let can_divide_u = if paired_columns { max_u - min_u >= 3 } else { max_u > min_u };
+
+        let divide_along_u = if !can_divide_u {
+            false
+        } else if (max_u - min_u) > (max_v - min_v) {
             true
         } else if (max_v - min_v) > (max_u - min_u) {
             false
@@ -84,15 +108,19 @@ impl RecursiveDivision {
             grid.random_bool()
         };
 
-        if divide_along_u && max_u > min_u {
-            let u_wall = min_u + (grid.bounded_random_usize((max_u - min_u) as usize) as isize);
+        if divide_along_u {
+            let u_wall = if paired_columns {
+                min_u + 1 + (grid.bounded_random_usize((max_u - min_u - 2) as usize) as isize)
+            } else {
+                min_u + (grid.bounded_random_usize((max_u - min_u) as usize) as isize)
+            };
             // Find connections between u = u_wall and u = u_wall + 1
             let mut wall_pairs = Vec::new();
             for &coords in region {
                 let (u, _) = u_v[&coords];
                 if u == u_wall {
                     let cell = grid.get(coords).unwrap();
-                    for &neighbor_coords in cell.linked.iter() {
+                    for neighbor_coords in cell.linked.iter() {
                         if region.contains(&neighbor_coords) {
                             let (neighbor_u, _) = u_v[&neighbor_coords];
                             if neighbor_u == u_wall + 1 {
@@ -103,24 +131,6 @@ impl RecursiveDivision {
                 }
             }
 
-            // Create wall with a passage
-            if !wall_pairs.is_empty() {
-                let passage_index = grid.bounded_random_usize(wall_pairs.len());
-                wall_pairs.remove(passage_index);
-                let mut changed_cells = HashSet::new();
-                for (c1, c2) in wall_pairs {
-                    grid.unlink(c1, c2)?;
-                    if grid.capture_steps {
-                        changed_cells.insert(c1);
-                        changed_cells.insert(c2);
-                    }
-                }
-                if grid.capture_steps && !changed_cells.is_empty() {
-                    self.capture_step(grid, &changed_cells);
-                }
-            }
-
-            // Split and recurse
             let left_region: HashSet<Coordinates> = region.iter()
                 .filter(|&&coords| u_v[&coords].0 <= u_wall)
                 .cloned()
@@ -129,8 +139,9 @@ impl RecursiveDivision {
                 .filter(|&&coords| u_v[&coords].0 >= u_wall + 1)
                 .cloned()
                 .collect();
-            self.divide(grid, &left_region)?;
-            self.divide(grid, &right_region)?;
+            for part in self.build_wall(grid, wall_pairs, &left_region, &right_region)? {
+                self.divide(grid, &part)?;
+            }
         } else if max_v > min_v {
             let v_wall = min_v + (grid.bounded_random_usize((max_v - min_v) as usize) as isize);
             // Find connections between v = v_wall and v = v_wall + 1
@@ -139,7 +150,7 @@ impl RecursiveDivision {
                 let (_, v) = u_v[&coords];
                 if v == v_wall {
                     let cell = grid.get(coords).unwrap();
-                    for &neighbor_coords in cell.linked.iter() {
+                    for neighbor_coords in cell.linked.iter() {
                         if region.contains(&neighbor_coords) {
                             let (_, neighbor_v) = u_v[&neighbor_coords];
                             if neighbor_v == v_wall + 1 {
@@ -150,24 +161,6 @@ impl RecursiveDivision {
                 }
             }
 
-            // Create wall with a passage
-            if !wall_pairs.is_empty() {
-                let passage_index = grid.bounded_random_usize(wall_pairs.len());
-                wall_pairs.remove(passage_index);
-                let mut changed_cells = HashSet::new();
-                for (c1, c2) in wall_pairs {
-                    grid.unlink(c1, c2)?;
-                    if grid.capture_steps {
-                        changed_cells.insert(c1);
-                        changed_cells.insert(c2);
-                    }
-                }
-                if grid.capture_steps && !changed_cells.is_empty() {
-                    self.capture_step(grid, &changed_cells);
-                }
-            }
-
-            // Split and recurse
             let bottom_region: HashSet<Coordinates> = region.iter()
                 .filter(|&&coords| u_v[&coords].1 <= v_wall)
                 .cloned()
@@ -176,18 +169,90 @@ impl RecursiveDivision {
                 .filter(|&&coords| u_v[&coords].1 >= v_wall + 1)
                 .cloned()
                 .collect();
-            self.divide(grid, &bottom_region)?;
-            self.divide(grid, &top_region)?;
+            for part in self.build_wall(grid, wall_pairs, &bottom_region, &top_region)? {
+                self.divide(grid, &part)?;
+            }
         }
 
         Ok(())
     }
+
+    // Wall off two halves of a region, leaving a passage through the wall at random. A region bounded by missing
+    // cells, as in a masked maze, can leave a half in separate parts, so further passages are left wherever they
+    // join parts not yet joined. Returns the parts, each to be divided in turn.
+    fn build_wall(
+        &self,
+        grid: &mut Grid,
+        mut wall_pairs: Vec<(Coordinates, Coordinates)>,
+        first: &HashSet<Coordinates>,
+        second: &HashSet<Coordinates>,
+    ) -> Result<Vec<HashSet<Coordinates>>, Error> {
+        let mut parts = Self::parts(grid, first);
+        parts.extend(Self::parts(grid, second));
+        if wall_pairs.is_empty() {
+            return Ok(parts);
+        }
+        // each part is known in the disjoint set by its least cell
+        let representatives: Vec<Coordinates> = parts.iter().filter_map(|part| part.iter().min().copied()).collect();
+        let part_of: HashMap<Coordinates, Coordinates> = parts.iter()
+            .zip(&representatives)
+            .flat_map(|(part, &representative)| part.iter().map(move |&coords| (coords, representative)))
+            .collect();
+        let mut joined = DisjointSet::new();
+        for &representative in &representatives {
+            joined.make_set(representative);
+        }
+        let (a, b) = wall_pairs.remove(grid.bounded_random_usize(wall_pairs.len()));
+        joined.union(part_of[&a], part_of[&b]);
+        let mut changed_cells = HashSet::new();
+        for (c1, c2) in wall_pairs {
+            if joined.union(part_of[&c1], part_of[&c2]) {
+                continue;
+            }
+            grid.unlink(c1, c2)?;
+            if grid.capture_steps {
+                changed_cells.insert(c1);
+                changed_cells.insert(c2);
+            }
+        }
+        if grid.capture_steps && !changed_cells.is_empty() {
+            self.capture_step(grid, &changed_cells);
+        }
+        Ok(parts)
+    }
+
+    // The parts of a half region joined by passages within it, in order of their least cell
+    fn parts(grid: &Grid, half: &HashSet<Coordinates>) -> Vec<HashSet<Coordinates>> {
+        let mut starts: Vec<Coordinates> = half.iter().copied().collect();
+        starts.sort();
+        let mut seen = HashSet::new();
+        let mut parts = Vec::new();
+        for start in starts {
+            if !seen.insert(start) {
+                continue;
+            }
+            let mut part = HashSet::new();
+            part.insert(start);
+            let mut frontier = vec![start];
+            while let Some(coords) = frontier.pop() {
+                for neighbor in grid.get(coords).map(|cell| &cell.linked).into_iter().flatten() {
+                    if half.contains(&neighbor) && seen.insert(neighbor) {
+                        part.insert(neighbor);
+                        frontier.push(neighbor);
+                    }
+                }
+            }
+            parts.push(part);
+        }
+        parts
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::cell::{MazeType, Coordinates};
+    use crate::events::GenerationPhase;
 
     #[test]
     fn generate_and_print_5_x_5_orthogonal_maze() {
@@ -199,7 +264,7 @@ mod tests {
             Coordinates { x: 4, y: 4 },
             false
         ).unwrap();
-        RecursiveDivision.generate(&mut grid).unwrap();
+        RecursiveDivision::default().generate(&mut grid).unwrap();
         assert!(grid.is_perfect_maze().unwrap());
         println!("\n\nOrthogonal 5x5\n\n{}\n\n", grid.to_asci());
     }
@@ -214,7 +279,7 @@ mod tests {
             Coordinates { x: 4, y: 4 },
             false
         ).unwrap();
-        RecursiveDivision.generate(&mut grid).unwrap();
+        RecursiveDivision::default().generate(&mut grid).unwrap();
         assert!(grid.is_perfect_maze().unwrap());
     }
 
@@ -228,8 +293,10 @@ mod tests {
             Coordinates { x: 4, y: 4 },
             true
         ).unwrap();
-        RecursiveDivision.generate(&mut grid).unwrap();
+        RecursiveDivision::default().generate(&mut grid).unwrap();
         assert!(grid.is_perfect_maze().unwrap());
+        let details = grid.generation_step_details.as_ref().unwrap();
+        assert!(details.iter().skip(1).all(|step| step.phase == GenerationPhase::Wall && step.linked.is_empty() && !step.unlinked.is_empty()));
         let steps = grid.generation_steps.unwrap();
         assert!(!steps.is_empty());
         assert!(steps.iter().any(|step| step.count_edges() > 0));
@@ -245,7 +312,7 @@ mod tests {
             Coordinates { x: 4, y: 4 },
             true
         ).unwrap();
-        RecursiveDivision.generate(&mut grid).unwrap();
+        RecursiveDivision::default().generate(&mut grid).unwrap();
         assert!(grid.is_perfect_maze().unwrap());
         let steps = grid.generation_steps.unwrap();
         assert!(!steps.is_empty());
@@ -255,14 +322,44 @@ mod tests {
     #[test]
     fn reject_unsupported_maze_type() {
         let mut grid = Grid::new(
-            MazeType::Delta,
+            MazeType::Upsilon,
             5,
             5,
             Coordinates { x: 0, y: 0 },
             Coordinates { x: 4, y: 4 },
             false
         ).unwrap();
-        assert!(RecursiveDivision.generate(&mut grid).is_err());
+        assert!(RecursiveDivision::default().generate(&mut grid).is_err());
+    }
+
+    #[test]
+    fn generate_delta_and_sigma_mazes() {
+        for maze_type in [MazeType::Delta, MazeType::Sigma] {
+            for (width, height) in [(5, 5), (12, 6), (3, 9), (1, 4)] {
+                let mut grid = Grid::new(maze_type, width, height, Coordinates { x: 0, y: 0 }, Coordinates { x: width - 1, y: height - 1 }, false).unwrap();
+                if maze_type == MazeType::Delta && width == 1 {
+                    continue; // a single column of triangles is not connected
+                }
+                RecursiveDivision::default().generate(&mut grid).expect("RecursiveDivision maze generation failed");
+                assert!(grid.is_perfect_maze().unwrap(), "{:?} {}x{} maze is not perfect", maze_type, width, height);
+            }
+        }
+    }
+
+    #[test]
+    fn small_regions_are_left_as_rooms() {
+        let start = Coordinates { x: 0, y: 0 };
+        let goal = Coordinates { x: 9, y: 9 };
+
+        // every region is below the threshold, so the whole grid is left as one room
+        let mut grid = Grid::new(MazeType::Orthogonal, 10, 10, start, goal, false).unwrap();
+        RecursiveDivision { min_region_size: 101, room_probability: 1.0 }.generate(&mut grid).unwrap();
+        assert_eq!(grid.count_edges(), 2 * 10 * 9);
+
+        let mut grid = Grid::new(MazeType::Orthogonal, 10, 10, start, goal, false).unwrap();
+        RecursiveDivision { min_region_size: 9, room_probability: 1.0 }.generate(&mut grid).unwrap();
+        assert!(grid.count_edges() > 99, "rooms should introduce loops");
+        assert_eq!(grid.distances(start).len(), 100, "every cell should remain reachable");
     }
 
     #[test]
@@ -270,7 +367,7 @@ mod tests {
         match Grid::new(MazeType::Rhombic, 12, 24, Coordinates { x: 0, y: 0 }, Coordinates { x: 5, y: 23 }, false) {
             Ok(mut grid) => {
                 assert!(!grid.is_perfect_maze().unwrap());
-                RecursiveDivision.generate(&mut grid).expect("RecursiveDivision maze generation failed");
+                RecursiveDivision::default().generate(&mut grid).expect("RecursiveDivision maze generation failed");
                 assert!(grid.is_perfect_maze().unwrap());
             }
             Err(e) => panic!("Unexpected error running test: {:?}", e),
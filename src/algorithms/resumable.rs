@@ -0,0 +1,230 @@
+use rand::seq::SliceRandom;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::algorithms::kruskals::{self, DisjointSet};
+use crate::algorithms::prims::Prims;
+use crate::algorithms::MazeAlgorithm;
+use crate::behaviors::maze::MazeGeneration;
+use crate::cell::Coordinates;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::prelude::*;
+
+/// A snapshot of a maze generation paused partway, holding the algorithm's frontier, stack or remaining edges
+/// so that generation can resume later, e.g. in the next frame of a mobile app generating a huge maze. The
+/// cells carved so far are held by the grid itself, which must be passed back unchanged to resume.
+///
+/// Only RecursiveBacktracker, Prims and Kruskals can be generated in parts, and generation steps are not
+/// captured. Mazes generated in parts are as random as, though not identical to, those generated at once
+/// from the same seed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GenerationState {
+    algorithm: MazeAlgorithm,
+    frontier: Frontier,
+    finished: bool,
+}
+
+// What remains for each algorithm to carve. Which cells have been carved into is read from the grid's links,
+// keeping snapshots small
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+enum Frontier {
+    // the current path, from the start
+    RecursiveBacktracker { stack: Vec<Coordinates> },
+    // the first cell carved, and the uncarved cells next to carved ones
+    Prims { seed: Coordinates, frontier: Vec<Coordinates> },
+    // neighboring pairs of cells yet to be considered, the next one last
+    Kruskals { edges: Vec<(Coordinates, Coordinates)> },
+}
+
+impl GenerationState {
+    /// Begin generating a maze on a grid without passages, carving nothing yet.
+    pub fn start(algorithm: MazeAlgorithm, grid: &mut Grid) -> Result<GenerationState, Error> {
+        let frontier = match algorithm {
+            MazeAlgorithm::RecursiveBacktracker => Frontier::RecursiveBacktracker { stack: vec![grid.start_coords] },
+            MazeAlgorithm::Prims => {
+                let cells: Vec<Coordinates> = grid.iter_cells().map(|cell| cell.coords).collect();
+                let seed = cells[grid.bounded_random_usize(cells.len())];
+                Frontier::Prims { seed, frontier: sorted_neighbors(grid, seed)? }
+            }
+            MazeAlgorithm::Kruskals => {
+                let mut edges = kruskals::edges(grid);
+                edges.shuffle(grid.rng());
+                Frontier::Kruskals { edges }
+            }
+            _ => return Err(Error::GenerationNotResumable { algorithm }),
+        };
+        Ok(GenerationState { algorithm, frontier, finished: false })
+    }
+
+    /// The algorithm generating the maze.
+    pub fn algorithm(&self) -> MazeAlgorithm {
+        self.algorithm
+    }
+
+    /// Whether the maze is finished, its distances and solution path set as for a maze generated at once.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Carve up to `max_steps` more steps of the maze, finishing it once nothing remains to carve. Returns
+    /// whether the maze is finished.
+    pub fn advance(&mut self, grid: &mut Grid, max_steps: usize) -> Result<bool, Error> {
+        self.advance_while(grid, max_steps, || true)
+    }
+
+    /// Carve for up to `budget_ms` milliseconds, finishing the maze once nothing remains to carve. Returns
+    /// whether the maze is finished.
+    #[cfg(feature = "std")]
+    pub fn resume(&mut self, grid: &mut Grid, budget_ms: u64) -> Result<bool, Error> {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(budget_ms);
+        self.advance_while(grid, usize::MAX, || std::time::Instant::now() < deadline)
+    }
+
+    // Carve until the maze is finished, `max_steps` steps are carved, or `more` returns false, checked every
+    // few steps so a clock can be read without slowing carving
+    fn advance_while(&mut self, grid: &mut Grid, max_steps: usize, mut more: impl FnMut() -> bool) -> Result<bool, Error> {
+        const STEPS_PER_CHECK: usize = 64;
+        let mut steps = 0;
+        // cells joined by the passages carved so far, rebuilt from the grid on each call
+        let mut sets = match self.frontier {
+            Frontier::Kruskals { .. } => Some(joined_sets(grid)),
+            _ => None,
+        };
+        while !self.finished && steps < max_steps && (steps % STEPS_PER_CHECK != 0 || more()) {
+            steps += 1;
+            let remaining = match &mut self.frontier {
+                Frontier::RecursiveBacktracker { stack } => backtracker_step(grid, stack)?,
+                Frontier::Prims { seed, frontier } => prims_step(grid, *seed, frontier)?,
+                Frontier::Kruskals { edges } => kruskals_step(grid, edges, sets.get_or_insert_with(DisjointSet::new))?,
+            };
+            if !remaining {
+                Prims.finalize(grid)?;
+                self.finished = true;
+            }
+        }
+        Ok(self.finished)
+    }
+}
+
+// A cell's neighbors in order of coordinates, so that a resumed generation draws from them as it would have
+fn sorted_neighbors(grid: &Grid, coords: Coordinates) -> Result<Vec<Coordinates>, Error> {
+    let mut neighbors: Vec<Coordinates> = grid.get(coords)?.neighbors().into_iter().collect();
+    neighbors.sort();
+    Ok(neighbors)
+}
+
+// Whether a cell has been carved into, given the cell generation began from
+fn carved(grid: &Grid, coords: Coordinates, seed: Coordinates) -> bool {
+    coords == seed || grid.get(coords).is_ok_and(|cell| !cell.linked.is_empty())
+}
+
+// Carve to a random uncarved neighbor of the cell atop the stack, or backtrack from it. Returns whether any
+// of the maze remains to be carved
+fn backtracker_step(grid: &mut Grid, stack: &mut Vec<Coordinates>) -> Result<bool, Error> {
+    let Some(&current) = stack.last() else {
+        return Ok(false);
+    };
+    let seed = stack[0];
+    let mut neighbors = sorted_neighbors(grid, current)?;
+    neighbors.retain(|&neighbor| !carved(grid, neighbor, seed));
+    if neighbors.is_empty() {
+        stack.pop();
+    } else {
+        let next = neighbors[grid.bounded_random_usize(neighbors.len())];
+        grid.link(current, next)?;
+        stack.push(next);
+    }
+    Ok(!stack.is_empty())
+}
+
+// Carve into a random frontier cell from a random carved neighbor, adding its uncarved neighbors which were
+// not already next to a carved cell to the frontier
+fn prims_step(grid: &mut Grid, seed: Coordinates, frontier: &mut Vec<Coordinates>) -> Result<bool, Error> {
+    if frontier.is_empty() {
+        return Ok(false);
+    }
+    let coords = frontier.swap_remove(grid.bounded_random_usize(frontier.len()));
+    let neighbors = sorted_neighbors(grid, coords)?;
+    let inside: Vec<Coordinates> = neighbors.iter().copied().filter(|&neighbor| carved(grid, neighbor, seed)).collect();
+    let from = inside[grid.bounded_random_usize(inside.len())];
+    grid.link(coords, from)?;
+    for neighbor in neighbors {
+        if carved(grid, neighbor, seed) {
+            continue;
+        }
+        let beside_carved = grid.get(neighbor)?.neighbors().into_iter().any(|other| other != coords && carved(grid, other, seed));
+        if !beside_carved {
+            frontier.push(neighbor);
+        }
+    }
+    Ok(!frontier.is_empty())
+}
+
+// Open the passage between the next pair of cells if they are not yet joined
+fn kruskals_step(grid: &mut Grid, edges: &mut Vec<(Coordinates, Coordinates)>, sets: &mut DisjointSet) -> Result<bool, Error> {
+    let Some((a, b)) = edges.pop() else {
+        return Ok(false);
+    };
+    if sets.union(a, b) {
+        grid.link(a, b)?;
+    }
+    Ok(!edges.is_empty())
+}
+
+// Cells joined by the grid's passages
+fn joined_sets(grid: &Grid) -> DisjointSet {
+    let mut sets = DisjointSet::new();
+    for cell in grid.iter_cells() {
+        sets.make_set(cell.coords);
+    }
+    for (a, b) in grid.iter_linked_pairs() {
+        sets.union(a.coords, b.coords);
+    }
+    sets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::behaviors::display::JsonDisplay;
+    use crate::cell::MazeType;
+
+    #[test]
+    fn generation_resumes_from_snapshots() {
+        for algorithm in [MazeAlgorithm::RecursiveBacktracker, MazeAlgorithm::Prims, MazeAlgorithm::Kruskals] {
+            for maze_type in [MazeType::Orthogonal, MazeType::Sigma] {
+                let mut grid = Grid::new(maze_type, 14, 11, Coordinates { x: 0, y: 0 }, Coordinates { x: 13, y: 10 }, false).unwrap();
+                let mut state = GenerationState::start(algorithm, &mut grid).unwrap();
+                let mut calls = 0;
+                while !state.advance(&mut grid, 25).unwrap() {
+                    // snapshot both and carry on from the copies, as an app would across frames
+                    let saved = serde_json::to_string(&state).unwrap();
+                    state = serde_json::from_str(&saved).unwrap();
+                    grid = Grid::from_saved_json(&grid.to_json().unwrap()).unwrap();
+                    calls += 1;
+                }
+                assert!(calls > 1, "{:?} finished at once", algorithm);
+                assert!(grid.is_perfect_maze().unwrap(), "{:?} {:?}", algorithm, maze_type);
+                assert!(grid.get(grid.goal_coords).unwrap().on_solution_path);
+                assert!(state.advance(&mut grid, 1).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn generation_runs_within_a_budget() {
+        let mut grid = Grid::new(MazeType::Orthogonal, 30, 30, Coordinates { x: 0, y: 0 }, Coordinates { x: 29, y: 29 }, false).unwrap();
+        let mut state = MazeAlgorithm::Prims.generate_partial(&mut grid, 1000).unwrap();
+        while !state.resume(&mut grid, 5).unwrap() {}
+        assert!(grid.is_perfect_maze().unwrap());
+
+        let mut grid = Grid::new(MazeType::Orthogonal, 4, 4, Coordinates { x: 0, y: 0 }, Coordinates { x: 3, y: 3 }, false).unwrap();
+        assert!(matches!(
+            GenerationState::start(MazeAlgorithm::Wilsons, &mut grid),
+            Err(Error::GenerationNotResumable { algorithm: MazeAlgorithm::Wilsons })
+        ));
+    }
+}
@@ -3,7 +3,7 @@ use crate::cell::Coordinates;
 use crate::error::Error;
 use crate::grid::Grid;
 use rand::seq::SliceRandom;
-use std::collections::HashSet;
+use crate::prelude::*;
 
 pub struct ReverseDelete;
 
@@ -16,9 +16,9 @@ impl MazeGeneration for ReverseDelete {
             .iter()
             .filter_map(|opt| opt.as_ref()) // Converts &Option<Cell> to Option<&Cell>, skipping None
             .flat_map(|cell| {
-                cell.neighbors_by_direction
-                    .values()
-                    .map(move |&neighbor| (cell.coords, neighbor))
+                cell.neighbors()
+                    .into_iter()
+                    .map(move |neighbor| (cell.coords, neighbor))
             })
             .collect();
 
@@ -30,17 +30,19 @@ impl MazeGeneration for ReverseDelete {
         let mut edges = collect_all_edges(grid);
 
         // Step 3: Shuffle edges randomly
-        let mut rng = rand::thread_rng();
+        let mut rng = grid.forked_rng();
         edges.shuffle(&mut rng);
 
         // Step 4: Process each edge, removing those that don't disconnect the graph
         for (u, v) in edges {
+            grid.check_cancelled()?;
             // Temporarily remove the link
             {
                 let cell_u = grid.get_mut(u)?;
                 cell_u.linked.remove(&v);
                 let cell_v = grid.get_mut(v)?;
                 cell_v.linked.remove(&u);
+                grid.invalidate_distances();
             }
 
             // Check if u and v are still connected without this edge
@@ -61,6 +63,7 @@ impl MazeGeneration for ReverseDelete {
                     cell_u.linked.insert(v);
                     let cell_v = grid.get_mut(v)?;
                     cell_v.linked.insert(u);
+                    grid.invalidate_distances();
                 }
             }
         }
@@ -74,7 +77,7 @@ fn collect_all_edges(grid: &Grid) -> Vec<(Coordinates, Coordinates)> {
     let mut edges = HashSet::new();
     for opt in grid.cells.iter() {
         if let Some(cell) = opt.as_ref() {
-            for &neighbor in cell.neighbors_by_direction.values() {
+            for neighbor in cell.neighbors() {
                 let mut pair = [cell.coords, neighbor];
                 pair.sort(); // Ensure consistent ordering: smaller coord first
                 edges.insert((pair[0], pair[1]));
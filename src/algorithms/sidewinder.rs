@@ -3,14 +3,16 @@ use crate::algorithms::MazeAlgorithm;
 use crate::grid::Grid;
 use crate::cell::{Coordinates, MazeType};
 use crate::error::Error;
-use std::collections::HashSet;
+use crate::prelude::*;
 
 pub struct Sidewinder;
 
 impl MazeGeneration for Sidewinder {
     fn generate(&self, grid: &mut Grid) -> Result<(), Error> {
         match grid.maze_type {
-            MazeType::Orthogonal => {} // proceed with maze generation for allowed Orthogonal (square) grid type
+            // rows of these grids are joined side by side, and every cell below the first row has a neighbor in
+            // the row above
+            MazeType::Orthogonal | MazeType::Sigma | MazeType::Upsilon => {}
             maze_type => {
                 return Err(Error::AlgorithmUnavailableForMazeType{algorithm:MazeAlgorithm::Sidewinder, maze_type:maze_type});
             }
@@ -31,7 +33,8 @@ impl MazeGeneration for Sidewinder {
                 let current_coords = Coordinates { x: col, y: row };
                 run.push(current_coords); // Add current cell to the run
 
-                let at_eastern_boundary = col + 1 == cols;
+                let east_coords = Coordinates { x: col + 1, y: row };
+                let at_eastern_boundary = !grid.get(current_coords)?.neighbors().contains(&east_coords);
                 let at_northern_boundary = row == 0;
 
                 let should_close_run = at_eastern_boundary || (!at_northern_boundary && grid.random_bool());
@@ -43,10 +46,15 @@ impl MazeGeneration for Sidewinder {
                         let random_index = grid.bounded_random_usize(run.len());
                         let random_cell = run[random_index];
 
-                        let above_coords = Coordinates {
-                            x: random_cell.x,
-                            y: random_cell.y - 1,
-                        };
+                        // cells above, of which Sigma and Upsilon cells can have several, in a stable order
+                        let mut above: Vec<Coordinates> = grid
+                            .get(random_cell)?
+                            .neighbors()
+                            .into_iter()
+                            .filter(|neighbor| neighbor.y + 1 == row)
+                            .collect();
+                        above.sort();
+                        let above_coords = above[grid.bounded_random_usize(above.len())];
 
                         // Link the selected cell upward
                         grid.link(random_cell, above_coords)?;
@@ -63,11 +71,6 @@ impl MazeGeneration for Sidewinder {
                     run.clear(); // Reset the run
                 } else if !at_eastern_boundary {
                     // Carve eastward
-                    let east_coords = Coordinates {
-                        x: col + 1,
-                        y: row,
-                    };
-
                     grid.link(current_coords, east_coords)?;
 
                     // Capture state after linking with changed cells
@@ -134,23 +137,17 @@ mod tests {
     }
 
     #[test]
-    fn reject_5_x_5_sigma_binary_tree_maze() {
-        match Grid::new(MazeType::Sigma, 4, 4, Coordinates { x: 0, y: 0 }, Coordinates { x: 3, y: 3 }, false) {
-            Ok(mut grid) => {
-                assert!(!grid.is_perfect_maze().unwrap());
-                match Sidewinder.generate(&mut grid) {
-                    Ok(()) => {
-                        panic!("Successfully generated a Sidewinder maze for a Sigma grid, which is should have been rejected!");
-                    }
-                    Err(e) => {
-                        println!("As expected, Sigma grid is rejected for Sidewinder maze generation: {:?}", e);
-                    }
-                }
-            }    
-            Err(e) => panic!("Unexpected error generating grid: {:?}", e),
+    fn generate_sigma_and_upsilon_mazes() {
+        for maze_type in [MazeType::Sigma, MazeType::Upsilon] {
+            for (width, height) in [(1, 1), (1, 6), (6, 1), (5, 5), (12, 7)] {
+                let goal = Coordinates { x: width - 1, y: height - 1 };
+                let mut grid = Grid::new(maze_type, width, height, Coordinates { x: 0, y: 0 }, goal, false).unwrap();
+                Sidewinder.generate(&mut grid).expect("Sidewinder maze generation failed");
+                assert!(grid.is_perfect_maze().unwrap(), "{:?} maze of {}x{}", maze_type, width, height);
+            }
         }
     }
-    
+
     #[test]
     fn test_sidewinder_with_capture_steps() {
         let start = Coordinates { x: 0, y: 0 };
@@ -164,11 +161,11 @@ mod tests {
                 let steps = grid.generation_steps.as_ref().unwrap(); assert!(!steps.is_empty());
                 // Check if any cells become linked across all generation steps
                 let has_linked_cells = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.linked.is_empty())
+                    step.iter_cells().any(|cell| !cell.linked.is_empty())
                 });
                 assert!(has_linked_cells, "No cells were linked during maze generation");
                 let has_open_walls = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.open_walls.is_empty())
+                    step.iter_cells().any(|cell| !cell.open_walls.is_empty())
                 });
                 assert!(has_open_walls, "No cells have open walls in generation steps");
             }
@@ -0,0 +1,94 @@
+//! Statistical checks of the mazes the algorithms generate, each over thousands of small mazes, to catch bias
+//! an optimization of an algorithm would otherwise introduce silently.
+
+use crate::algorithms::MazeAlgorithm;
+use crate::cell::{Coordinates, MazeType};
+use crate::grid::Grid;
+use crate::prelude::*;
+
+// Mazes generated by the algorithm, each with a different seed
+fn samples(algorithm: MazeAlgorithm, maze_type: MazeType, width: usize, height: usize, count: usize) -> impl Iterator<Item = Grid> {
+    (0..count).map(move |sample| {
+        let goal = Coordinates { x: width - 1, y: height - 1 };
+        let mut grid = Grid::new(maze_type, width, height, Coordinates { x: 0, y: 0 }, goal, false).unwrap();
+        // a grid's own seed is drawn from only width * height + 1 values, too few for thousands of mazes
+        grid.reseed(sample as u64);
+        algorithm.generate(&mut grid).unwrap();
+        grid
+    })
+}
+
+// How many times each spanning tree, identified by its passages, was generated
+fn tree_counts(algorithm: MazeAlgorithm, width: usize, height: usize, count: usize) -> HashMap<Vec<(Coordinates, Coordinates)>, usize> {
+    let mut counts = HashMap::new();
+    for grid in samples(algorithm, MazeType::Orthogonal, width, height, count) {
+        let mut passages: Vec<(Coordinates, Coordinates)> = grid.iter_linked_pairs().map(|(a, b)| (a.coords, b.coords)).collect();
+        passages.sort();
+        *counts.entry(passages).or_default() += 1;
+    }
+    counts
+}
+
+// Pearson's chi-squared statistic of the counts against every category being equally likely
+fn chi_squared(counts: &[usize], categories: usize) -> f64 {
+    let total: usize = counts.iter().sum();
+    let expected = total as f64 / categories as f64;
+    let observed: f64 = counts.iter().map(|&count| (count as f64 - expected).powi(2) / expected).sum();
+    // categories never observed each contribute their whole expectation
+    observed + (categories - counts.len()) as f64 * expected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uniform_spanning_tree_algorithms() {
+        // a 3 x 3 grid has 192 spanning trees, each of which should be generated about as often as any other
+        const TREES: usize = 192;
+        const SAMPLES: usize = TREES * 50;
+        for algorithm in [MazeAlgorithm::AldousBroder, MazeAlgorithm::Wilsons, MazeAlgorithm::AldousBroderWilsons] {
+            let counts: Vec<usize> = tree_counts(algorithm, 3, 3, SAMPLES).into_values().collect();
+            assert_eq!(counts.len(), TREES, "{}", algorithm.name());
+            // with 191 degrees of freedom the statistic has a mean of 191 and a standard deviation of about 20,
+            // so a uniform algorithm exceeds 300 far less than once in a million runs
+            let statistic = chi_squared(&counts, TREES);
+            assert!(statistic < 300.0, "{} is biased: chi-squared {:.1}", algorithm.name(), statistic);
+        }
+    }
+
+    #[test]
+    fn biased_algorithms_are_not_uniform() {
+        // the statistic must be able to tell: BinaryTree generates only 2 ^ 4 of the 192 trees
+        let counts: Vec<usize> = tree_counts(MazeAlgorithm::BinaryTree, 3, 3, 192 * 50).into_values().collect();
+        assert_eq!(counts.len(), 16);
+        assert!(chi_squared(&counts, 192) > 1000.0);
+    }
+
+    #[test]
+    fn binary_tree_diagonal_bias() {
+        const SIZE: usize = 5;
+        let (mut right, mut down) = (0, 0);
+        for grid in samples(MazeAlgorithm::BinaryTree, MazeType::Orthogonal, SIZE, SIZE, 2000) {
+            for cell in grid.iter_cells() {
+                let Coordinates { x, y } = cell.coords;
+                let carved_right = cell.linked.contains(&Coordinates { x: x + 1, y });
+                let carved_down = cell.linked.contains(&Coordinates { x, y: y + 1 });
+                // every cell but the bottom right one carves exactly one of the two, so the bottom row and the
+                // right column are unbroken corridors
+                match (x == SIZE - 1, y == SIZE - 1) {
+                    (true, true) => assert!(!carved_right && !carved_down),
+                    (false, true) => assert!(carved_right),
+                    (true, false) => assert!(carved_down),
+                    (false, false) => {
+                        assert!(carved_right != carved_down);
+                        if carved_right { right += 1 } else { down += 1 }
+                    }
+                }
+            }
+        }
+        // 32000 coin flips, with a standard deviation of under 0.3% from even
+        let fraction = right as f64 / (right + down) as f64;
+        assert!((fraction - 0.5).abs() < 0.02, "carved right {:.3} of the time", fraction);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::behaviors::maze::MazeGeneration;
+use crate::grid::Grid;
+use crate::cell::Coordinates;
+use crate::error::Error;
+
+use alloc::collections::BinaryHeap;
+use rand::Rng;
+use crate::prelude::*;
+
+// A candidate passage between a cell already in the maze and one not yet in it
+#[derive(Eq, PartialEq)]
+struct FrontierEdge {
+    weight: u32,
+    tiebreak: u32, // random, so equally weighted passages are carved in no particular order
+    from: Coordinates,
+    to: Coordinates,
+}
+
+impl Ord for FrontierEdge {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        // Reverse ordering to make BinaryHeap a min-heap (lower weights first)
+        other.weight.cmp(&self.weight).then_with(|| other.tiebreak.cmp(&self.tiebreak))
+    }
+}
+
+impl PartialOrd for FrontierEdge {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// True Prim's algorithm: grows a minimum spanning tree by always carving the cheapest passage leading out of
+/// the maze, so passages prefer low-weight terrain.
+pub struct WeightedPrims {
+    edge_weight: Box<dyn Fn(Coordinates, Coordinates) -> u32>,
+}
+
+impl WeightedPrims {
+    /// Weigh the passage from one cell to a neighboring cell with the specified function.
+    pub fn new(edge_weight: impl Fn(Coordinates, Coordinates) -> u32 + 'static) -> Self {
+        WeightedPrims { edge_weight: Box::new(edge_weight) }
+    }
+
+    /// Weigh every passage randomly, producing a classic Prim's maze. Every passage weighs the same, leaving
+    /// the order in which they're carved to the random tiebreak.
+    pub fn random() -> Self {
+        WeightedPrims::new(|_, _| 0)
+    }
+
+    /// Weigh each passage by the sum of the weights of the two cells it joins. Weights are indexed by row,
+    /// then column (`weights[y][x]`); cells missing from the weight map weigh 0.
+    pub fn from_cell_weights(weights: Vec<Vec<u32>>) -> Self {
+        let weight = move |coords: Coordinates| weights.get(coords.y).and_then(|row| row.get(coords.x)).copied().unwrap_or(0);
+        WeightedPrims::new(move |from, to| weight(from).saturating_add(weight(to)))
+    }
+
+    fn push_edges(&self, grid: &mut Grid, from: Coordinates, visited: &HashSet<Coordinates>, frontier: &mut BinaryHeap<FrontierEdge>) {
+        let neighbors = grid.get(from).map(|cell| cell.neighbors()).unwrap_or_default();
+        for to in neighbors {
+            if !visited.contains(&to) {
+                frontier.push(FrontierEdge { weight: (self.edge_weight)(from, to), tiebreak: grid.rng().gen(), from, to });
+            }
+        }
+    }
+}
+
+impl MazeGeneration for WeightedPrims {
+    fn generate(&self, grid: &mut Grid) -> Result<(), Error> {
+        let mut visited: HashSet<Coordinates> = HashSet::new();
+        let mut frontier: BinaryHeap<FrontierEdge> = BinaryHeap::new();
+
+        // Choose a random starting cell that exists in the grid
+        let start_coords;
+        loop {
+            let x = grid.bounded_random_usize(grid.width);
+            let y = grid.bounded_random_usize(grid.height);
+            if grid.has_cell(x, y) {
+                start_coords = Coordinates { x, y };
+                break;
+            }
+        }
+        visited.insert(start_coords);
+        self.push_edges(grid, start_coords, &visited, &mut frontier);
+
+        if grid.capture_steps {
+            let changed_cells = HashSet::new();
+            self.capture_step(grid, &changed_cells);
+        }
+
+        while let Some(FrontierEdge { from, to, .. }) = frontier.pop() {
+            if !visited.insert(to) {
+                continue; // a cheaper passage already reached this cell
+            }
+            grid.link(from, to)?;
+            self.push_edges(grid, to, &visited, &mut frontier);
+
+            if grid.capture_steps {
+                let mut changed_cells = HashSet::new();
+                changed_cells.insert(from);
+                changed_cells.insert(to);
+                self.capture_step(grid, &changed_cells);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::MazeType;
+
+    #[test]
+    fn generate_random_weighted_mazes() {
+        for maze_type in [MazeType::Orthogonal, MazeType::Delta, MazeType::Sigma, MazeType::Rhombic] {
+            let mut grid = Grid::new(maze_type, 12, 8, Coordinates { x: 0, y: 0 }, Coordinates { x: 10, y: 6 }, false).unwrap();
+            WeightedPrims::random().build(&mut grid).expect("Weighted Prim's maze generation failed");
+            assert!(grid.is_perfect_maze().unwrap());
+        }
+    }
+
+    #[test]
+    fn passages_prefer_low_weight_terrain() {
+        // a cheap corridor along the top row and a costly remainder: the whole top row is carved as one passage
+        let width = 10;
+        let weights: Vec<Vec<u32>> = (0..6).map(|y| vec![if y == 0 { 1 } else { 100 }; width]).collect();
+        let mut grid = Grid::new(MazeType::Orthogonal, width, 6, Coordinates { x: 0, y: 0 }, Coordinates { x: 9, y: 5 }, false).unwrap();
+        WeightedPrims::from_cell_weights(weights).build(&mut grid).expect("Weighted Prim's maze generation failed");
+        assert!(grid.is_perfect_maze().unwrap());
+        for x in 0..width - 1 {
+            let cell = grid.get(Coordinates { x, y: 0 }).unwrap();
+            assert!(cell.linked.contains(&Coordinates { x: x + 1, y: 0 }));
+        }
+    }
+}
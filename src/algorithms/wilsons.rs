@@ -2,8 +2,8 @@ use crate::behaviors::maze::MazeGeneration;
 use crate::grid::Grid;
 use crate::cell::Coordinates;
 use crate::error::Error;
+use crate::prelude::*;
 
-use std::collections::HashSet;
 
 pub struct Wilsons;
 
@@ -23,72 +23,96 @@ impl MazeGeneration for Wilsons {
         // Count only valid cells (Some(Cell)) in the grid
         let total_cells = grid.cells.iter().filter(|opt| opt.is_some()).count();
 
-        while visited.len() < total_cells {
-            // Choose a random unvisited cell that exists to start the walk
-            let walk_start = loop {
-                let x = grid.bounded_random_usize(grid.width);
-                let y = grid.bounded_random_usize(grid.height);
-                let coords = Coordinates { x, y };
-                if grid.get(coords).is_ok() && !visited.contains(&coords) {
-                    break coords;
-                }
-            };
-
-            // Perform a random walk
-            let mut walk: Vec<Coordinates> = vec![walk_start];
-            let mut walk_set: HashSet<Coordinates> = HashSet::new();
-            walk_set.insert(walk_start);
-
-            while let Some(&current) = walk.last() {
-                if visited.contains(&current) {
-                    break; // Path hit a visited cell, carve it
-                }
+        loop_erased_walks(self, grid, &mut visited, total_cells)
+    }
+}
 
-                // Get valid neighbors (in-bounds and existing)
-                let cell = grid.get(current)?;
-                let neighbors: Vec<Coordinates> = cell
-                    .neighbors()
-                    .into_iter()
-                    .filter(|&coords| grid.get(coords).is_ok())
-                    .collect();
+/// Join unvisited cells to the visited ones by loop-erased random walks, one walk at a time, until `target`
+/// cells have been visited. Shared with AldousBroderWilsons, which begins with cells visited by Aldous-Broder.
+pub(crate) fn loop_erased_walks(
+    generator: &impl MazeGeneration,
+    grid: &mut Grid,
+    visited: &mut HashSet<Coordinates>,
+    target: usize,
+) -> Result<(), Error> {
+    // Walks step through cells by their index in the grid's flattened cells, so each step is a lookup in a
+    // vector rather than a hash of coordinates
+    let width = grid.width;
+    let coords_at = |index: usize| Coordinates { x: index % width, y: index / width };
+    let mut in_tree: Vec<bool> = vec![false; grid.cells.len()];
+    // every cell's neighbors, looked up once rather than on each step of the walks
+    let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); grid.cells.len()];
+    for cell in grid.iter_cells() {
+        let index = grid.get_flattened_index(cell.coords.x, cell.coords.y);
+        in_tree[index] = visited.contains(&cell.coords);
+        neighbors[index] = cell
+            .neighbors()
+            .into_iter()
+            .filter(|&coords| grid.get(coords).is_ok())
+            .map(|coords| grid.get_flattened_index(coords.x, coords.y))
+            .collect();
+        neighbors[index].sort();
+    }
 
-                if neighbors.is_empty() {
-                    break; // No valid moves, end this walk
-                }
+    // unvisited cells, from which each walk starts, and the position of each in the list
+    let mut unvisited: Vec<usize> = grid
+        .iter_cells()
+        .map(|cell| grid.get_flattened_index(cell.coords.x, cell.coords.y))
+        .filter(|&index| !in_tree[index])
+        .collect();
+    let mut positions: Vec<usize> = vec![usize::MAX; grid.cells.len()];
+    for (position, &index) in unvisited.iter().enumerate() {
+        positions[index] = position;
+    }
 
-                // Pick a random neighbor
-                let index = grid.bounded_random_usize(neighbors.len());
-                let next = neighbors[index];
+    // the latest neighbor the walk moved to from each cell it passed through
+    let mut exits: Vec<usize> = vec![usize::MAX; grid.cells.len()];
 
-                if let Some(pos) = walk.iter().position(|&c| c == next) {
-                    // Loop detected: truncate the path
-                    walk.truncate(pos + 1);
-                    walk_set.clear();
-                    walk.iter().for_each(|&c| { walk_set.insert(c); });
-                } else {
-                    walk.push(next);
-                    walk_set.insert(next);
-                }
+    while visited.len() < target && !unvisited.is_empty() {
+        // Perform a random walk from a random unvisited cell until it reaches a visited one
+        let walk_start = unvisited[grid.bounded_random_usize(unvisited.len())];
+        let mut current = walk_start;
+        let mut steps: usize = 0;
+        while !in_tree[current] {
+            steps += 1;
+            if steps.is_multiple_of(4096) {
+                grid.check_cancelled()?;
+            }
+            let options = &neighbors[current];
+            if options.is_empty() {
+                return Err(Error::NoValidNeighbor { coordinates: coords_at(current) });
             }
+            let next = options[grid.bounded_random_usize(options.len())];
+            // overwriting an earlier exit erases the loop the walk made since leaving the cell
+            exits[current] = next;
+            current = next;
+        }
 
-            // Carve the path into the maze
-            for pair in walk.windows(2) {
-                let (current, next) = (pair[0], pair[1]);
-                grid.link(current, next)?;
-                visited.insert(current);
-                visited.insert(next);
+        // Carve the loop-erased path into the maze by following the exits from the start
+        let mut current = walk_start;
+        while !in_tree[current] {
+            let next = exits[current];
+            let (from, to) = (coords_at(current), coords_at(next));
+            grid.link(from, to)?;
+            in_tree[current] = true;
+            visited.insert(from);
+            let position = positions[current];
+            unvisited.swap_remove(position);
+            if let Some(&moved) = unvisited.get(position) {
+                positions[moved] = position;
+            }
 
-                if grid.capture_steps {
-                    let mut changed_cells = HashSet::new();
-                    changed_cells.insert(current);
-                    changed_cells.insert(next);
-                    self.capture_step(grid, &changed_cells);
-                }
+            if grid.capture_steps {
+                let mut changed_cells = HashSet::new();
+                changed_cells.insert(from);
+                changed_cells.insert(to);
+                generator.capture_step(grid, &changed_cells);
             }
+            current = next;
         }
-
-        Ok(())
     }
+
+    Ok(())
 }
 
 // impl MazeGeneration for Wilsons {
@@ -291,11 +315,11 @@ mod tests {
                 let steps = grid.generation_steps.as_ref().unwrap(); assert!(!steps.is_empty());
                 // Check if any cells become linked across all generation steps
                 let has_linked_cells = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.linked.is_empty())
+                    step.iter_cells().any(|cell| !cell.linked.is_empty())
                 });
                 assert!(has_linked_cells, "No cells were linked during maze generation");
                 let has_open_walls = steps.iter().any(|step| {
-                    step.cells.iter().filter_map(|opt| opt.as_ref()).any(|cell| !cell.open_walls.is_empty())
+                    step.iter_cells().any(|cell| !cell.open_walls.is_empty())
                 });
                 assert!(has_open_walls, "No cells have open walls in generation steps");
                 
@@ -303,4 +327,11 @@ mod tests {
             Err(e) => panic!("Unexpected error generating grid: {:?}", e),
         }
     }
+
+    #[test]
+    fn generate_500_x_500_orthogonal_maze() {
+        let mut grid = Grid::new(MazeType::Orthogonal, 500, 500, Coordinates { x: 0, y: 0 }, Coordinates { x: 499, y: 499 }, false).unwrap();
+        Wilsons.generate(&mut grid).expect("Wilsons maze generation failed");
+        assert!(grid.is_perfect_maze().unwrap());
+    }
 }
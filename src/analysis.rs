@@ -0,0 +1,239 @@
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::accessibility::AccessibilityReport;
+use crate::algorithms::MazeAlgorithm;
+use crate::cell::MazeType;
+use crate::difficulty::DifficultyReport;
+use crate::direction::Direction;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::request::MazeRequest;
+use crate::prelude::*;
+
+/// Structural statistics describing the "texture" of a single maze.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct MazeStats {
+    /// Number of cells in the maze.
+    pub total_cells: usize,
+    /// Number of cells with exactly one passage.
+    pub dead_ends: usize,
+    /// Dead ends as a fraction of all cells.
+    pub dead_end_ratio: f64,
+    /// Number of passages along the longest path between any two cells (the maze's diameter).
+    pub longest_path: usize,
+    /// Number of passages running left/right.
+    pub horizontal_passages: usize,
+    /// Number of passages running up/down.
+    pub vertical_passages: usize,
+    /// Number of passages running diagonally.
+    pub diagonal_passages: usize,
+    /// Horizontal versus vertical preference, from -1.0 (only vertical) through 1.0 (only horizontal).
+    pub directional_bias: f64,
+    /// Fraction of cells which simply continue a corridor (exactly two passages). Higher values mean
+    /// long, winding "rivers" with few branches; lower values mean short, bushy passages.
+    pub river_factor: f64,
+}
+
+impl MazeStats {
+    /// Compute the statistics of the specified maze.
+    pub fn from_grid(grid: &Grid) -> MazeStats {
+        let cells: Vec<_> = grid.iter_cells().collect();
+        let total_cells = cells.len();
+        let dead_ends = cells.iter().filter(|cell| cell.linked.len() == 1).count();
+        let corridors = cells.iter().filter(|cell| cell.linked.len() == 2).count();
+
+        // every passage is seen once from each of its two cells
+        let (mut horizontal, mut vertical, mut diagonal) = (0, 0, 0);
+        for cell in &cells {
+            for (direction, neighbor) in &cell.neighbors_by_direction {
+                if !cell.linked.contains(&neighbor) {
+                    continue;
+                }
+                match direction {
+                    Direction::Left | Direction::Right => horizontal += 1,
+                    Direction::Up | Direction::Down => vertical += 1,
+                    _ => diagonal += 1,
+                }
+            }
+        }
+        let (horizontal, vertical, diagonal) = (horizontal / 2, vertical / 2, diagonal / 2);
+        let directional_bias = if horizontal + vertical > 0 {
+            (horizontal as f64 - vertical as f64) / (horizontal + vertical) as f64
+        } else {
+            0.0
+        };
+
+        let ratio = |count: usize| if total_cells > 0 { count as f64 / total_cells as f64 } else { 0.0 };
+        MazeStats {
+            total_cells,
+            dead_ends,
+            dead_end_ratio: ratio(dead_ends),
+            longest_path: grid.longest_path().2.len().saturating_sub(1),
+            horizontal_passages: horizontal,
+            vertical_passages: vertical,
+            diagonal_passages: diagonal,
+            directional_bias,
+            river_factor: ratio(corridors),
+        }
+    }
+}
+
+/// A maze's statistics and difficulty together, for showing alongside the maze, e.g. in an app's level
+/// select screen.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct MazeReport {
+    /// The fewest moves from the start to the goal, par for the user to match.
+    pub par_moves: usize,
+    pub difficulty: DifficultyReport,
+    pub stats: MazeStats,
+    pub accessibility: AccessibilityReport,
+}
+
+impl MazeReport {
+    /// Compute the statistics and difficulty of the specified maze.
+    pub fn from_grid(grid: &Grid) -> MazeReport {
+        let difficulty = grid.difficulty();
+        MazeReport {
+            par_moves: difficulty.solution_length.saturating_sub(1),
+            difficulty,
+            stats: MazeStats::from_grid(grid),
+            accessibility: AccessibilityReport::from_grid(grid),
+        }
+    }
+}
+
+/// Summary of one statistic's values across many sampled mazes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Distribution {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl Distribution {
+    /// Summarize the specified values; all fields are 0.0 when there are no values.
+    pub fn from_values(values: &[f64]) -> Distribution {
+        if values.is_empty() {
+            return Distribution { min: 0.0, max: 0.0, mean: 0.0, std_dev: 0.0 };
+        }
+        let count = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / count;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count;
+        Distribution {
+            min: values.iter().cloned().fold(f64::INFINITY, f64::min),
+            max: values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+            mean,
+            std_dev: variance.sqrt(),
+        }
+    }
+}
+
+/// Distributions of maze statistics for one algorithm, gathered over many generated mazes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct AggregateStats {
+    pub algorithm: MazeAlgorithm,
+    pub maze_type: MazeType,
+    pub width: usize,
+    pub height: usize,
+    /// Number of mazes generated.
+    pub samples: usize,
+    pub dead_end_ratio: Distribution,
+    pub longest_path: Distribution,
+    pub directional_bias: Distribution,
+    pub river_factor: Distribution,
+}
+
+/// Generate `samples` mazes from the request and summarize the distribution of their statistics.
+pub fn aggregate(request: &MazeRequest, samples: usize) -> Result<AggregateStats, Error> {
+    let mut stats: Vec<MazeStats> = Vec::with_capacity(samples);
+    for _ in 0..samples {
+        let mut request = request.clone();
+        request.capture_steps = None;
+        stats.push(MazeStats::from_grid(&Grid::try_from(request)?));
+    }
+    let distribution = |value: fn(&MazeStats) -> f64| {
+        Distribution::from_values(&stats.iter().map(value).collect::<Vec<f64>>())
+    };
+    Ok(AggregateStats {
+        algorithm: request.algorithm,
+        maze_type: request.maze_type,
+        width: request.width,
+        height: request.height,
+        samples,
+        dead_end_ratio: distribution(|s| s.dead_end_ratio),
+        longest_path: distribution(|s| s.longest_path as f64),
+        directional_bias: distribution(|s| s.directional_bias),
+        river_factor: distribution(|s| s.river_factor),
+    })
+}
+
+/// Aggregate statistics for every algorithm supporting the maze type, to compare algorithms by texture.
+pub fn aggregate_all(maze_type: MazeType, width: usize, height: usize, samples: usize) -> Result<Vec<AggregateStats>, Error> {
+    let mut results = Vec::new();
+    for algorithm in MazeAlgorithm::all() {
+        let stats = MazeRequest::builder()
+            .maze_type(maze_type)
+            .size(width, height)
+            .algorithm(*algorithm)
+            .build()
+            .and_then(|request| aggregate(&request, samples));
+        match stats {
+            Ok(stats) => results.push(stats),
+            Err(Error::AlgorithmUnavailableForMazeType { .. }) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Coordinates;
+
+    #[test]
+    fn stats_of_straight_corridor() {
+        let mut grid = Grid::new(MazeType::Orthogonal, 5, 1, Coordinates { x: 0, y: 0 }, Coordinates { x: 4, y: 0 }, false).unwrap();
+        for x in 0..4 {
+            grid.link(Coordinates { x, y: 0 }, Coordinates { x: x + 1, y: 0 }).unwrap();
+        }
+        let stats = MazeStats::from_grid(&grid);
+        assert_eq!(stats.total_cells, 5);
+        assert_eq!(stats.horizontal_passages + stats.vertical_passages + stats.diagonal_passages, grid.count_edges());
+        assert_eq!(stats.dead_ends, 2);
+        assert_eq!(stats.longest_path, 4);
+        assert_eq!(stats.horizontal_passages, 4);
+        assert_eq!(stats.vertical_passages, 0);
+        assert_eq!(stats.directional_bias, 1.0);
+        assert_eq!(stats.river_factor, 0.6);
+    }
+
+    #[test]
+    fn aggregate_sidewinder_statistics() {
+        let request = MazeRequest::builder()
+            .maze_type(MazeType::Orthogonal)
+            .size(15, 15)
+            .algorithm(MazeAlgorithm::Sidewinder)
+            .build()
+            .unwrap();
+        let stats = aggregate(&request, 10).expect("aggregation failed");
+        assert_eq!(stats.samples, 10);
+        assert!(stats.directional_bias.min >= -1.0 && stats.directional_bias.max <= 1.0);
+        assert!(stats.dead_end_ratio.min <= stats.dead_end_ratio.mean && stats.dead_end_ratio.mean <= stats.dead_end_ratio.max);
+        assert!(stats.longest_path.min >= 28.0);
+    }
+
+    #[test]
+    fn aggregate_all_skips_unsupported_algorithms() {
+        let results = aggregate_all(MazeType::Delta, 6, 6, 2).expect("aggregation failed");
+        assert!(!results.is_empty());
+        assert!(results.iter().all(|stats| stats.maze_type == MazeType::Delta));
+        assert!(!results.iter().any(|stats| stats.algorithm == MazeAlgorithm::BinaryTree));
+    }
+}
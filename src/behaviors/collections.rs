@@ -1,5 +1,5 @@
-use std::collections::{HashMap, HashSet};
-use std::hash::Hash;
+use core::hash::Hash;
+use crate::prelude::*;
 
 pub trait SetDifference<T>
 where
@@ -35,7 +35,7 @@ pub trait FilterKeys<K, V> {
 // Implement for HashMap if you wish to return a Vec<K>
 impl<K, V> FilterKeys<K, V> for HashMap<K, V>
 where
-    K: Eq + std::hash::Hash + Clone,
+    K: Eq + core::hash::Hash + Clone,
 {
     fn filter_keys<F>(&self, predicate: F) -> Vec<K>
     where
@@ -1,5 +1,6 @@
 use serde::Serialize;
 use serde_json;
+use crate::prelude::*;
 
 /// A helper trait that provides JSON conversion methods.
 /// Types that implement `Serialize` (or themselves as JSON representations) automatically get these methods.
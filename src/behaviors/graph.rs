@@ -1,5 +1,7 @@
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::hash::Hash;
+use alloc::collections::{BinaryHeap, VecDeque};
+use core::cmp::Reverse;
+use core::hash::Hash;
+use crate::prelude::*;
 
 /// Perform a breadth-first search starting from `start`,
 /// returning a mapping of each reachable node to its distance from `start`.
@@ -84,6 +86,93 @@ where
     Some(path)
 }
 
+/// Perform Dijkstra's search starting from `start`, returning a mapping of each reachable node to the
+/// lowest total cost of reaching it from `start`.
+///
+/// # Arguments
+///
+/// * `start` - The node at which to begin the search.
+/// * `neighbors` - A closure that, given a node, returns a `Vec` of its neighboring nodes, each paired with
+///   the cost of moving to it.
+///
+/// # Returns
+///
+/// A `HashMap` mapping each node to its cost from the starting node.
+pub fn dijkstra_distances<Node, F>(start: Node, neighbors: F) -> HashMap<Node, u32>
+where
+    Node: Eq + Hash + Copy + Ord,
+    F: Fn(Node) -> Vec<(Node, u32)>,
+{
+    let mut distances: HashMap<Node, u32> = HashMap::new();
+    let mut queue: BinaryHeap<Reverse<(u32, Node)>> = BinaryHeap::new();
+    distances.insert(start, 0);
+    queue.push(Reverse((0, start)));
+
+    while let Some(Reverse((current_distance, current))) = queue.pop() {
+        if distances.get(&current).is_some_and(|&best| best < current_distance) {
+            continue;
+        }
+        for (neighbor, cost) in neighbors(current) {
+            let distance = current_distance.saturating_add(cost);
+            if distances.get(&neighbor).is_none_or(|&best| distance < best) {
+                distances.insert(neighbor, distance);
+                queue.push(Reverse((distance, neighbor)));
+            }
+        }
+    }
+    distances
+}
+
+/// Reconstructs a lowest cost path from `start` to `goal` given a precomputed `distances` map.
+///
+/// # Arguments
+///
+/// * `start` - The starting node.
+/// * `goal` - The destination node.
+/// * `distances` - A map from nodes to their cost from the start (usually computed by `dijkstra_distances`).
+/// * `neighbors` - The closure the distances were computed with. Every move must be possible in reverse and
+///   cost more than zero.
+///
+/// # Returns
+///
+/// An `Option<Vec<Node>>` containing the path from start to goal (inclusive) if one exists.
+pub fn get_weighted_path<Node, F>(
+    start: Node,
+    goal: Node,
+    distances: &HashMap<Node, u32>,
+    neighbors: F,
+) -> Option<Vec<Node>>
+where
+    Node: Eq + Hash + Copy + Ord,
+    F: Fn(Node) -> Vec<(Node, u32)>,
+{
+    // If goal wasn't reached, return None.
+    distances.get(&goal)?;
+
+    let mut path = vec![goal];
+    let mut current = goal;
+    while current != start {
+        let current_distance = distances[&current];
+        // Among the neighbors of current, choose the lowest one from which the move to current accounts for
+        // the difference in cost.
+        let prev = neighbors(current)
+            .into_iter()
+            .map(|(neighbor, _)| neighbor)
+            .filter(|neighbor| {
+                distances.get(neighbor).is_some_and(|&distance| {
+                    distance < current_distance
+                        && neighbors(*neighbor).iter().any(|&(next, cost)| next == current && distance.saturating_add(cost) == current_distance)
+                })
+            })
+            .min()?;
+        path.push(prev);
+        current = prev;
+    }
+
+    path.reverse();
+    Some(path)
+}
+
 /// Returns all nodes connected (reachable) from `start` using a BFS.
 ///
 /// # Arguments
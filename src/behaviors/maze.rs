@@ -1,7 +1,11 @@
 use crate::{Grid, Error};
+use alloc::collections::BTreeSet;
+
 use crate::cell::Coordinates;
+use crate::events::{GenerationPhase, GenerationStep};
+use crate::trace::trace_span;
+use crate::prelude::*;
 
-use std::collections::HashSet;
 
 pub trait MazeGeneration {
     /// Carve a maze on the provided grid.
@@ -12,8 +16,8 @@ pub trait MazeGeneration {
         let start = grid.start_coords;
         let goal = grid.goal_coords;
     
-        let all_distances = grid.distances(start);
-        for (coords, distance) in all_distances {
+        let all_distances = grid.distance_field(start);
+        for (&coords, &distance) in all_distances.iter() {
             if let Ok(cell) = grid.get_mut(coords) {
                 cell.distance = distance as i32;
             }
@@ -43,27 +47,73 @@ pub trait MazeGeneration {
         }
     }
 
-    // Capture a step with minimal overhead
+    // Capture a step with minimal overhead, its phase told from the passages changed: walls built if passages
+    // were only closed, passages carved otherwise
     fn capture_step(&self, grid: &mut Grid, changed_cells: &HashSet<Coordinates>) {
         if grid.capture_steps {
-            // Update open_walls only for changed cells
-            for coord in changed_cells {
-                if let Ok(cell) = grid.get_mut(*coord) {
-                    cell.set_open_walls();
-                }
-            }
-            // Clone the grid minimally for storage
-            let mut grid_clone = grid.clone();
-            grid_clone.capture_steps = false;
-            grid_clone.generation_steps = None; // Prevent recursive cloning
-            grid.generation_steps.as_mut().unwrap().push(grid_clone);
+            let (linked, unlinked) = step_passages(grid, changed_cells);
+            let phase = match grid.generation_steps.as_ref() {
+                Some(steps) if steps.is_empty() => GenerationPhase::Start,
+                _ if linked.is_empty() && !unlinked.is_empty() => GenerationPhase::Wall,
+                _ => GenerationPhase::Carve,
+            };
+            push_step(grid, changed_cells, GenerationStep { phase, linked, unlinked, frontier: Vec::new() });
+        }
+    }
+
+    // Capture a step in the given phase, along with the cells the algorithm will carve from or into next
+    fn capture_phase(&self, grid: &mut Grid, changed_cells: &HashSet<Coordinates>, phase: GenerationPhase, frontier: Vec<Coordinates>) {
+        if grid.capture_steps {
+            let (linked, unlinked) = step_passages(grid, changed_cells);
+            push_step(grid, changed_cells, GenerationStep { phase, linked, unlinked, frontier });
         }
     }
 
     fn build<'a>(&self, grid: &'a mut Grid) -> Result<&'a Grid, Error> {
-        self.generate(grid)?;
+        {
+            trace_span!("carve");
+            self.generate(grid)?;
+        }
+        trace_span!("finalize");
         self.finalize(grid)?;
         Ok(grid)
     }
 
 }
+
+type Passages = Vec<(Coordinates, Coordinates)>;
+
+// Passages opened and closed at the changed cells since the previous step was captured
+fn step_passages(grid: &Grid, changed_cells: &HashSet<Coordinates>) -> (Passages, Passages) {
+    let mut linked = BTreeSet::new();
+    let mut unlinked = BTreeSet::new();
+    let Some(previous) = grid.generation_steps.as_ref().filter(|steps| !steps.is_empty()) else {
+        return (Vec::new(), Vec::new());
+    };
+    for &coords in changed_cells {
+        let (Ok(cell), Some(before)) = (grid.get(coords), previous.latest_cell(grid, coords)) else {
+            continue;
+        };
+        let pair = |other: Coordinates| if coords < other { (coords, other) } else { (other, coords) };
+        linked.extend(cell.linked.iter().filter(|other| !before.linked.contains(other)).map(pair));
+        unlinked.extend(before.linked.iter().filter(|other| !cell.linked.contains(other)).map(pair));
+    }
+    (linked.into_iter().collect(), unlinked.into_iter().collect())
+}
+
+// Store the grid's changed cells as the next generation step, along with what happened in it
+fn push_step(grid: &mut Grid, changed_cells: &HashSet<Coordinates>, details: GenerationStep) {
+    // Update open_walls only for changed cells
+    for coord in changed_cells {
+        if let Ok(cell) = grid.get_mut(*coord) {
+            cell.set_open_walls();
+        }
+    }
+    if let Some(mut steps) = grid.generation_steps.take() {
+        steps.push(grid, changed_cells);
+        grid.generation_steps = Some(steps);
+    }
+    if let Some(step_details) = grid.generation_step_details.as_mut() {
+        step_details.push(details);
+    }
+}
@@ -1,5 +1,5 @@
-
 pub mod collections;
+#[cfg(feature = "json")]
 pub mod display;
 pub mod graph;
 pub mod maze;
@@ -0,0 +1,131 @@
+use std::time::{Duration, Instant};
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::algorithms::MazeAlgorithm;
+use crate::cell::MazeType;
+use crate::error::Error;
+use crate::grid::Grid;
+
+/// Which algorithms, maze types and sizes `run` measures, and how many mazes it generates for each.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BenchmarkConfig {
+    pub algorithms: Vec<MazeAlgorithm>,
+    pub maze_types: Vec<MazeType>,
+    /// Side lengths of the square mazes to generate.
+    pub sizes: Vec<usize>,
+    /// Number of mazes generated per combination, of which the mean, fastest and slowest times are reported.
+    pub iterations: usize,
+}
+
+impl Default for BenchmarkConfig {
+    /// Every algorithm and maze type, at 100x100, 500x500 and 1000x1000.
+    fn default() -> Self {
+        BenchmarkConfig {
+            algorithms: MazeAlgorithm::all().to_vec(),
+            maze_types: MazeType::all().to_vec(),
+            sizes: vec![100, 500, 1000],
+            iterations: 3,
+        }
+    }
+}
+
+/// Time taken to generate mazes of one algorithm, maze type and size.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Measurement {
+    pub algorithm: MazeAlgorithm,
+    pub maze_type: MazeType,
+    pub size: usize,
+    pub mean: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+/// Results of a benchmark run. Combinations of algorithm and maze type which can't be generated are skipped.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Report {
+    pub measurements: Vec<Measurement>,
+}
+
+impl Report {
+    /// The fastest algorithm, on average, for mazes of the specified type and size.
+    pub fn fastest(&self, maze_type: MazeType, size: usize) -> Option<&Measurement> {
+        self.measurements
+            .iter()
+            .filter(|measurement| measurement.maze_type == maze_type && measurement.size == size)
+            .min_by_key(|measurement| measurement.mean)
+    }
+}
+
+/// Generate a maze of each configured algorithm, maze type and size, timing generation alone (excluding grid
+/// construction), so callers can choose algorithms suited to their hardware.
+pub fn run(config: &BenchmarkConfig) -> Result<Report, Error> {
+    if config.iterations == 0 {
+        return Err(Error::InvalidAlgorithmParams { reason: "a benchmark needs at least 1 iteration".to_string() });
+    }
+    let mut report = Report::default();
+    for &maze_type in &config.maze_types {
+        for &size in &config.sizes {
+            for &algorithm in config.algorithms.iter().filter(|algorithm| algorithm.supports(maze_type)) {
+                let mut times = Vec::with_capacity(config.iterations);
+                for _ in 0..config.iterations {
+                    times.push(time_generation(algorithm, maze_type, size)?);
+                }
+                report.measurements.push(Measurement {
+                    algorithm,
+                    maze_type,
+                    size,
+                    mean: times.iter().sum::<Duration>() / times.len() as u32,
+                    min: times.iter().copied().min().unwrap_or_default(),
+                    max: times.iter().copied().max().unwrap_or_default(),
+                });
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Construct an empty square grid, ready for the benchmarked algorithm to carve.
+pub fn empty_grid(maze_type: MazeType, size: usize) -> Result<Grid, Error> {
+    let (start, goal) = Grid::default_endpoints(size, size, maze_type);
+    Grid::new(maze_type, size, size, start, goal, false)
+}
+
+fn time_generation(algorithm: MazeAlgorithm, maze_type: MazeType, size: usize) -> Result<Duration, Error> {
+    let mut grid = empty_grid(maze_type, size)?;
+    let started = Instant::now();
+    algorithm.generate(&mut grid)?;
+    Ok(started.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_supported_combinations() {
+        let config = BenchmarkConfig {
+            algorithms: vec![MazeAlgorithm::BinaryTree, MazeAlgorithm::Prims],
+            maze_types: vec![MazeType::Orthogonal, MazeType::Delta],
+            sizes: vec![8, 16],
+            iterations: 2,
+        };
+        let report = run(&config).unwrap();
+        // BinaryTree does not support Delta mazes
+        assert_eq!(report.measurements.len(), 6);
+        for measurement in &report.measurements {
+            assert!(measurement.min <= measurement.mean && measurement.mean <= measurement.max);
+        }
+        assert!(report.fastest(MazeType::Delta, 8).is_some_and(|measurement| measurement.algorithm == MazeAlgorithm::Prims));
+        assert!(report.fastest(MazeType::Sigma, 8).is_none());
+    }
+
+    #[test]
+    fn zero_iterations() {
+        let config = BenchmarkConfig { iterations: 0, ..Default::default() };
+        assert!(matches!(run(&config), Err(Error::InvalidAlgorithmParams { .. })));
+    }
+}
@@ -0,0 +1,160 @@
+//! Generate a maze from the command line, from a JSON request file and/or flags, e.g.
+//! `mazer-cli --type sigma --width 30 --algorithm prims --seed 7 --format svg -o maze.svg`.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, ValueEnum};
+use mazer::algorithms::MazeAlgorithm;
+use mazer::cell::MazeType;
+use mazer::grid::Grid;
+use mazer::render::png;
+use mazer::render::svg::{self, SvgOptions};
+use mazer::request::MazeRequest;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Ascii,
+    Svg,
+    Png,
+    Json,
+}
+
+/// Generate a maze and write it as ASCII art, SVG, PNG or JSON.
+///
+/// Flags override the corresponding fields of the request file, if one is given.
+#[derive(Debug, Parser)]
+#[command(name = "mazer-cli", version)]
+struct Args {
+    /// JSON maze request file, or `-` to read the request from standard input
+    request: Option<PathBuf>,
+
+    /// Maze type, e.g. orthogonal, delta, sigma, upsilon or rhombic [default: orthogonal]
+    #[arg(short = 't', long = "type", value_parser = parse_maze_type)]
+    maze_type: Option<MazeType>,
+
+    /// Number of columns [default: 10]
+    #[arg(short, long)]
+    width: Option<usize>,
+
+    /// Number of rows [default: the width, without a request file]
+    #[arg(short = 'H', long)]
+    height: Option<usize>,
+
+    /// Generation algorithm, e.g. recursive-backtracker, prims or wilsons [default: recursive-backtracker]
+    #[arg(short, long, value_parser = parse_algorithm)]
+    algorithm: Option<MazeAlgorithm>,
+
+    /// Seed for the maze's random choices
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = Format::Ascii)]
+    format: Format,
+
+    /// File to write, instead of standard output
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Draw the solution in SVG and PNG output
+    #[arg(long)]
+    solution: bool,
+
+    /// Size of each cell in SVG and PNG output
+    #[arg(long, default_value_t = SvgOptions::default().cell_size)]
+    cell_size: f64,
+}
+
+// Names are matched ignoring case and separators, so that `recursive-backtracker` selects RecursiveBacktracker
+fn normalized(name: &str) -> String {
+    name.chars().filter(|c| c.is_ascii_alphanumeric()).map(|c| c.to_ascii_lowercase()).collect()
+}
+
+fn parse_maze_type(name: &str) -> Result<MazeType, String> {
+    MazeType::all()
+        .iter()
+        .copied()
+        .find(|maze_type| normalized(maze_type.name()) == normalized(name))
+        .ok_or_else(|| format!("unknown maze type {:?}", name))
+}
+
+fn parse_algorithm(name: &str) -> Result<MazeAlgorithm, String> {
+    MazeAlgorithm::all()
+        .iter()
+        .copied()
+        .find(|algorithm| normalized(algorithm.name()) == normalized(name))
+        .ok_or_else(|| format!("unknown algorithm {:?}", name))
+}
+
+fn request(args: &Args) -> Result<MazeRequest, Box<dyn std::error::Error>> {
+    let mut request = match &args.request {
+        Some(path) => {
+            let mut json = String::new();
+            if path.as_os_str() == "-" {
+                io::stdin().read_to_string(&mut json)?;
+            } else {
+                json = fs::read_to_string(path)?;
+            }
+            serde_json::from_str(&json)?
+        }
+        None => {
+            let width = args.width.unwrap_or(10);
+            MazeRequest::builder()
+                .maze_type(MazeType::Orthogonal)
+                .size(width, args.height.unwrap_or(width))
+                .algorithm(MazeAlgorithm::RecursiveBacktracker)
+                .build()?
+        }
+    };
+    if let Some(maze_type) = args.maze_type {
+        request.maze_type = maze_type;
+    }
+    if let Some(width) = args.width {
+        request.width = width;
+    }
+    if let Some(height) = args.height {
+        request.height = height;
+    }
+    if let Some(algorithm) = args.algorithm {
+        request.algorithm = algorithm;
+    }
+    if args.seed.is_some() {
+        request.seed = args.seed;
+    }
+    request.validate()?;
+    Ok(request)
+}
+
+fn run(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let request = request(args)?;
+    if args.format == Format::Ascii && request.maze_type != MazeType::Orthogonal {
+        return Err(format!("ASCII output is only available for Orthogonal mazes, not {:?}; try --format svg", request.maze_type).into());
+    }
+    let grid = Grid::try_from(request)?;
+    let options = SvgOptions { cell_size: args.cell_size, show_solution: args.solution, ..Default::default() };
+    let bytes = match args.format {
+        Format::Ascii => grid.to_asci().into_bytes(),
+        Format::Svg => svg::to_svg(&grid, &options)?.into_bytes(),
+        Format::Png => png::to_png(&grid, &options)?,
+        Format::Json => serde_json::to_vec(&grid)?,
+    };
+    match &args.output {
+        Some(path) => fs::write(path, bytes)?,
+        None => io::stdout().write_all(&bytes)?,
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("mazer-cli: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
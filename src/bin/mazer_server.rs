@@ -0,0 +1,14 @@
+//! Serve mazes over HTTP, listening on the address given as the first argument, or in the `MAZER_ADDR`
+//! environment variable, and otherwise on port 8080 of every interface.
+
+use std::env;
+
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let addr = env::args().nth(1).or_else(|| env::var("MAZER_ADDR").ok()).unwrap_or_else(|| "0.0.0.0:8080".to_string());
+    let listener = TcpListener::bind(&addr).await?;
+    eprintln!("mazer-server: listening on {}", listener.local_addr()?);
+    mazer::server::serve(listener).await
+}
@@ -0,0 +1,154 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::difficulty::{generate_series, DifficultyCurve};
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::render::svg::{self, SvgOptions};
+use crate::request::MazeRequest;
+
+/// One page of a maze book: a numbered maze rendered as SVG.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    /// Number of the maze, from 1, shared by its puzzle page and its answer key page.
+    pub number: usize,
+    pub svg: String,
+}
+
+/// A printable set of numbered mazes followed by an answer key showing each maze's solution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Book {
+    pub puzzles: Vec<Page>,
+    pub answers: Vec<Page>,
+}
+
+// The maze for each page of a difficulty curve, generated as a rising series (see `generate_series`) and
+// reversed for a falling curve
+fn curve_grids(base: &MazeRequest, count: usize, from_score: f64, to_score: f64) -> Result<Vec<Grid>, Error> {
+    let falling = to_score < from_score;
+    let curve = if falling { DifficultyCurve::linear(to_score, from_score) } else { DifficultyCurve::linear(from_score, to_score) };
+    let mut grids: Vec<Grid> = generate_series(base, count, curve)?.into_iter().map(|(grid, _)| grid).collect();
+    if falling {
+        grids.reverse();
+    }
+    Ok(grids)
+}
+
+impl Book {
+    /// Generate a maze for each request, in order.
+    pub fn from_requests(requests: &[MazeRequest], options: &SvgOptions) -> Result<Book, Error> {
+        let grids = requests
+            .iter()
+            .map(|request| Grid::try_from(request.clone()))
+            .collect::<Result<Vec<Grid>, Error>>()?;
+        Self::from_grids(&grids, options)
+    }
+
+    /// Generate `count` mazes from the base request whose difficulty scores (see `DifficultyReport`) rise, or
+    /// fall, evenly from `from_score` to `to_score`, picking each page's maze as `generate_series` does. Fails
+    /// with `Error::InvalidDifficultyCurve` if the scores lie outside 0 through 100, or no maze differing in
+    /// difficulty from the previous page could be found.
+    pub fn with_difficulty_curve(
+        base: &MazeRequest,
+        count: usize,
+        from_score: f64,
+        to_score: f64,
+        options: &SvgOptions,
+    ) -> Result<Book, Error> {
+        Self::from_grids(&curve_grids(base, count, from_score, to_score)?, options)
+    }
+
+    /// Render already generated mazes, numbered in order.
+    pub fn from_grids(grids: &[Grid], options: &SvgOptions) -> Result<Book, Error> {
+        let mut book = Book { puzzles: Vec::with_capacity(grids.len()), answers: Vec::with_capacity(grids.len()) };
+        for (index, grid) in grids.iter().enumerate() {
+            let number = index + 1;
+            let puzzle = SvgOptions { show_solution: false, caption: Some(format!("Maze {}", number)), ..options.clone() };
+            let answer = SvgOptions { show_solution: true, caption: Some(format!("Answer {}", number)), ..options.clone() };
+            book.puzzles.push(Page { number, svg: svg::to_svg(grid, &puzzle)? });
+            book.answers.push(Page { number, svg: svg::to_svg(grid, &answer)? });
+        }
+        Ok(book)
+    }
+
+    /// Every page in print order: the puzzles, then the answer key.
+    pub fn pages(&self) -> impl Iterator<Item = &Page> {
+        self.puzzles.iter().chain(self.answers.iter())
+    }
+
+    /// Write each page to its own file in the directory, named `maze-NNN.svg` and `answer-NNN.svg`, returning
+    /// the paths written in print order.
+    pub fn write_svg_files(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+        let puzzles = self.puzzles.iter().map(|page| ("maze", page));
+        let answers = self.answers.iter().map(|page| ("answer", page));
+        let mut paths = Vec::with_capacity(self.puzzles.len() + self.answers.len());
+        for (prefix, page) in puzzles.chain(answers) {
+            let path = dir.join(format!("{}-{:03}.svg", prefix, page.number));
+            fs::write(&path, &page.svg)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::MazeAlgorithm;
+    use crate::difficulty::DifficultyReport;
+
+    fn request(maze_type: &str) -> MazeRequest {
+        serde_json::from_str(&format!(
+            r#"{{ "maze_type": "{}", "width": 6, "height": 6, "algorithm": "RecursiveBacktracker" }}"#,
+            maze_type
+        ))
+        .unwrap()
+    }
+
+    // a request whose mazes are repeatable and vary enough in difficulty for a short curve
+    fn seeded_request() -> MazeRequest {
+        MazeRequest { width: 12, height: 12, seed: Some(7), algorithm: MazeAlgorithm::Kruskals, ..request("Orthogonal") }
+    }
+
+    #[test]
+    fn numbered_puzzles_with_answer_key() {
+        let requests = [request("Orthogonal"), request("Sigma"), request("Delta")];
+        let book = Book::from_requests(&requests, &SvgOptions::default()).unwrap();
+        assert_eq!(book.puzzles.len(), 3);
+        assert_eq!(book.answers.len(), 3);
+        assert_eq!(book.pages().count(), 6);
+        for (index, (puzzle, answer)) in book.puzzles.iter().zip(&book.answers).enumerate() {
+            assert_eq!((puzzle.number, answer.number), (index + 1, index + 1));
+            assert!(puzzle.svg.contains(&format!("Maze {}", index + 1)) && !puzzle.svg.contains("<polyline"));
+            assert!(answer.svg.contains(&format!("Answer {}", index + 1)) && answer.svg.contains("<polyline"));
+        }
+    }
+
+    #[test]
+    fn difficulty_curve_and_files() {
+        let book = Book::with_difficulty_curve(&seeded_request(), 4, 41.0, 32.0, &SvgOptions::default()).unwrap();
+        assert_eq!(book.puzzles.len(), 4);
+
+        let dir = std::env::temp_dir().join(format!("mazer-book-{}", std::process::id()));
+        let paths = book.write_svg_files(&dir).unwrap();
+        assert_eq!(paths.len(), 8);
+        assert!(paths[0].ends_with("maze-001.svg") && paths[7].ends_with("answer-004.svg"));
+        assert!(paths.iter().all(|path| path.exists()));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn seeded_difficulty_curve_varies_pages() {
+        let grids = curve_grids(&seeded_request(), 4, 32.0, 41.0).unwrap();
+        let scores: Vec<f64> = grids.iter().map(|grid| DifficultyReport::from_grid(grid).score).collect();
+        // each page scores near its target, 32, 35, 38 and 41 in turn
+        for (page, score) in scores.iter().enumerate() {
+            assert!((score - (32.0 + 3.0 * page as f64)).abs() < 3.0, "{:?}", scores);
+        }
+        assert!(scores.windows(2).all(|pair| pair[0] <= pair[1]) && scores[0] < scores[3], "{:?}", scores);
+        let ids: std::collections::HashSet<u64> = grids.iter().map(|grid| grid.canonical_hash()).collect();
+        assert_eq!(ids.len(), 4);
+    }
+}
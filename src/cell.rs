@@ -1,18 +1,26 @@
-use std::collections::{HashMap, HashSet};
-use std::fmt;
+use core::fmt;
+use core::str::FromStr;
 
+#[cfg(feature = "serde")]
 use serde::{ Serialize, Deserialize };
+#[cfg(feature = "serde")]
 use serde::ser::{SerializeStruct, Serializer};
 
-use crate::behaviors::collections::FilterKeys;
+use crate::compact::{DirectionSet, Links, Neighbors};
+#[cfg(feature = "json")]
+#[cfg(feature = "json")]
+#[cfg(feature = "json")]
 use crate::behaviors::display::JsonDisplay;
 use crate::direction::Direction;
+use crate::prelude::*;
 
-#[derive(Copy, Debug, Clone, Eq, Hash, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[derive(Copy, Debug, Clone, Eq, Hash, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Coordinates {
     pub x: usize,
     pub y: usize
 }
+#[cfg(feature = "json")]
 impl fmt::Display for Coordinates {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.to_json() {
@@ -21,6 +29,13 @@ impl fmt::Display for Coordinates {
         }
     }
 }
+// the JSON representation, as far as it can be written without serde
+#[cfg(not(feature = "json"))]
+impl fmt::Display for Coordinates {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, r#"{{"x":{},"y":{}}}"#, self.x, self.y)
+    }
+}
 impl Default for Coordinates {
     fn default() -> Self {
         Self {
@@ -30,14 +45,73 @@ impl Default for Coordinates {
     }
 }
 
-#[derive(Copy, Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// A rectangular region of a grid, with its top-left cell at `x`, `y`.
+#[derive(Copy, Debug, Clone, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    /// Whether the coordinates lie within the region.
+    pub fn contains(&self, coords: Coordinates) -> bool {
+        coords.x >= self.x && coords.x < self.x + self.width && coords.y >= self.y && coords.y < self.y + self.height
+    }
+}
+
+/// Axial coordinates of a hexagonal (Sigma) cell. Sigma grids lay out flat-topped hexes in columns, with odd
+/// columns shifted half a cell down; axial coordinates instead slant the rows, so that neighbor offsets and
+/// distances are the same for every cell.
+#[derive(Copy, Debug, Clone, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AxialCoordinates {
+    pub q: isize,
+    pub r: isize,
+}
+
+impl AxialCoordinates {
+    /// Convert a Sigma cell's column and row to axial coordinates.
+    pub fn from_offset(coords: Coordinates) -> AxialCoordinates {
+        let (col, row) = (coords.x as isize, coords.y as isize);
+        AxialCoordinates { q: col, r: row - (col - (col & 1)) / 2 }
+    }
+
+    /// Convert back to a Sigma column and row, or `None` if the position lies left of or above the grid.
+    pub fn to_offset(&self) -> Option<Coordinates> {
+        let row = self.r + (self.q - (self.q & 1)) / 2;
+        if self.q < 0 || row < 0 {
+            return None;
+        }
+        Some(Coordinates { x: self.q as usize, y: row as usize })
+    }
+
+    /// Cube coordinates `(x, y, z)`, which always sum to 0.
+    pub fn to_cube(&self) -> (isize, isize, isize) {
+        (self.q, -self.q - self.r, self.r)
+    }
+
+    /// Number of steps between two hexes, ignoring walls.
+    pub fn distance(&self, other: &AxialCoordinates) -> usize {
+        let (dq, dr) = (self.q - other.q, self.r - other.r);
+        ((dq.abs() + dr.abs() + (dq + dr).abs()) / 2) as usize
+    }
+}
+
+#[derive(Copy, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MazeType {
     Orthogonal,
     Sigma,
     Delta,
     Upsilon,
+    /// Diamond-shaped cells, also known as a rhombille tiling; requests may spell it either way.
+    #[cfg_attr(feature = "serde", serde(alias = "Rhombille"))]
     Rhombic,
 }
+#[cfg(feature = "json")]
 impl fmt::Display for MazeType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.to_json() {
@@ -46,13 +120,87 @@ impl fmt::Display for MazeType {
         }
     }
 }
+// the JSON representation, as far as it can be written without serde
+#[cfg(not(feature = "json"))]
+impl fmt::Display for MazeType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\"", self.name())
+    }
+}
+
+impl MazeType {
+    /// Every supported maze type. New maze types are only ever appended, so this ordering is stable across releases.
+    pub fn all() -> &'static [MazeType] {
+        &[
+            MazeType::Orthogonal,
+            MazeType::Sigma,
+            MazeType::Delta,
+            MazeType::Upsilon,
+            MazeType::Rhombic,
+        ]
+    }
+
+    /// The maze type's name, as used in JSON requests
+    pub fn name(&self) -> &'static str {
+        match self {
+            MazeType::Orthogonal => "Orthogonal",
+            MazeType::Sigma      => "Sigma",
+            MazeType::Delta      => "Delta",
+            MazeType::Upsilon    => "Upsilon",
+            MazeType::Rhombic    => "Rhombic",
+        }
+    }
+}
+
+impl FromStr for MazeType {
+    type Err = crate::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "Rhombille" {
+            return Ok(MazeType::Rhombic);
+        }
+        MazeType::all()
+            .iter()
+            .find(|maze_type| maze_type.name() == s)
+            .copied()
+            .ok_or_else(|| crate::Error::InvalidMazeType { maze_type: s.to_string() })
+    }
+}
 
-#[derive(Copy, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CellOrientation {
+    #[default]
     Normal,
     Inverted
 }
 
+/// The outline of a Delta maze: a `Rectangle` filling every row, or a `Triangle` whose rows widen by one
+/// triangle on each side, pointing up when its first triangle is `Normal` and down when it is `Inverted`.
+/// A triangle `height` rows tall is `2 * height - 1` triangles wide.
+#[derive(Copy, Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DeltaShape {
+    #[default]
+    Rectangle,
+    Triangle,
+}
+
+impl DeltaShape {
+    /// Whether the shape covers the position, in a maze of `height` rows whose first triangle has the given
+    /// orientation. Positions outside the shape have no cell.
+    pub fn contains(&self, orientation: CellOrientation, height: usize, coords: Coordinates) -> bool {
+        match self {
+            DeltaShape::Rectangle => true,
+            // an upward triangle widens from its apex at the middle of the top row, a downward one narrows
+            // towards its tip at the middle of the bottom row
+            DeltaShape::Triangle => match orientation {
+                CellOrientation::Normal => coords.x + coords.y + 1 >= height && coords.x < height + coords.y,
+                CellOrientation::Inverted => coords.x >= coords.y && coords.x + coords.y + 1 < 2 * height,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 /// Representation of a single Cell of the maze Grid
 pub struct Cell {
@@ -60,10 +208,14 @@ pub struct Cell {
     pub coords: Coordinates,
     /// The maze type (e.g., Orthogonal, Delta, Sigma).
     pub maze_type: MazeType,
-    /// Maps directions to the coordinates of neighboring cells.
-    pub neighbors_by_direction: HashMap<Direction, Coordinates>,
-    /// Coordinates of neighboring cells that are linked to this cell (i.e., no walls in between).
-    pub linked: HashSet<Coordinates>,
+    /// Maps directions to the coordinates of neighboring cells, as offsets from this cell's `coords`.
+    pub neighbors_by_direction: Neighbors,
+    /// Every neighbor of a room while the room is contracted into this, its first cell, for the maze to be
+    /// generated around it (see `MazeRequest::rooms`); empty otherwise.
+    pub room_neighbors: Vec<Coordinates>,
+    /// Coordinates of neighboring cells that are linked to this cell (i.e., no walls in between), as offsets
+    /// from this cell's `coords`.
+    pub linked: Links,
     /// Distance to the goal cell.
     pub distance: i32,
     /// Whether this cell is the starting cell.
@@ -87,9 +239,21 @@ pub struct Cell {
     /// The orientation of the cell (Normal or Inverted); applicable only for delta cells.
     pub orientation: CellOrientation,
     /// The directions in which there are no walls restricting movement.
-    pub open_walls: Vec<Direction>,
+    pub open_walls: DirectionSet,
     /// Used primarily for Upsilon maze_type, to indicate whether cell's square or octagon
     pub is_square: bool,
+    /// Index of the distance band this cell falls into, when distance bands have been assigned to the grid.
+    pub distance_band: Option<usize>,
+    /// Normalized 0.0–1.0 distance from the heatmap origin, when a heatmap has been assigned to the grid.
+    pub heat: Option<f32>,
+    /// Whether the cell has come into view of the user, when fog of war is enabled on the grid.
+    pub is_discovered: bool,
+    /// The side of a start or goal cell on the maze's border whose outer wall is knocked out as an entrance
+    /// or exit, when the grid's exits are open.
+    pub border_opening: Option<Direction>,
+    /// The cost of entering the cell in weighted pathfinding, such as mud or water that is slow to cross, when
+    /// one has been set; unweighted cells cost 1.
+    pub weight: Option<u32>,
 }
 
 impl Default for Cell {
@@ -97,8 +261,9 @@ impl Default for Cell {
         Self {
             coords: Coordinates::default(),
             maze_type: MazeType::Orthogonal,
-            neighbors_by_direction: HashMap::new(),
-            linked: HashSet::new(),
+            neighbors_by_direction: Neighbors::new(Coordinates::default()),
+            room_neighbors: Vec::new(),
+            linked: Links::new(Coordinates::default()),
             distance: 0,
             is_start: false,
             is_goal: false,
@@ -107,18 +272,32 @@ impl Default for Cell {
             has_been_visited: false,
             on_solution_path: false,
             orientation: CellOrientation::Normal, // Assuming CellOrientation has a Normal variant
-            open_walls: Vec::new(),
+            open_walls: DirectionSet::new(),
             is_square: false,
+            distance_band: None,
+            heat: None,
+            is_discovered: false,
+            border_opening: None,
+            weight: None,
         }
     }
 }
 
+#[cfg(feature = "serde")]
 impl Serialize for Cell {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Cell", 10)?;
+        // formats which write the field count up front, such as MessagePack, need it to match exactly
+        let optional = [
+            self.distance_band.is_some(),
+            self.heat.is_some(),
+            self.to_axial().is_some(),
+            self.border_opening.is_some(),
+            self.weight.is_some(),
+        ];
+        let mut state = serializer.serialize_struct("Cell", 11 + optional.iter().filter(|&&present| present).count())?;
         state.serialize_field("coords", &self.coords)?;
         let linked_dirs: Vec<String> = self.get_user_facing_linked_directions()
             .iter()
@@ -133,10 +312,87 @@ impl Serialize for Cell {
         state.serialize_field("has_been_visited", &self.has_been_visited)?;
         state.serialize_field("on_solution_path", &self.on_solution_path)?;
         state.serialize_field("is_square", &self.is_square)?;
+        if let Some(band) = self.distance_band {
+            state.serialize_field("distance_band", &band)?;
+        }
+        if let Some(heat) = self.heat {
+            state.serialize_field("heat", &heat)?;
+        }
+        if let Some(axial) = self.to_axial() {
+            state.serialize_field("axial", &axial)?;
+        }
+        state.serialize_field("is_discovered", &self.is_discovered)?;
+        if let Some(opening) = self.border_opening {
+            state.serialize_field("border_opening", &opening)?;
+        }
+        if let Some(weight) = self.weight {
+            state.serialize_field("weight", &weight)?;
+        }
         state.end()
     } 
 }
 
+// Mirror of the serialized cell fields, used to deserialize a `Cell`
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct CellData {
+    coords: Coordinates,
+    linked: Vec<Direction>,
+    distance: i32,
+    is_start: bool,
+    is_goal: bool,
+    is_active: bool,
+    is_visited: bool,
+    has_been_visited: bool,
+    on_solution_path: bool,
+    #[serde(default)]
+    is_square: bool,
+    #[serde(default)]
+    distance_band: Option<usize>,
+    #[serde(default)]
+    heat: Option<f32>,
+    #[serde(default)]
+    is_discovered: bool,
+    #[serde(default)]
+    border_opening: Option<Direction>,
+    #[serde(default)]
+    weight: Option<u32>,
+}
+
+/// Deserializes a cell detached from its grid: the serialized (user-facing) linked directions are restored
+/// into `open_walls`, while `linked` coordinates and `neighbors_by_direction` are only restored when the
+/// cell is deserialized as part of a `Grid`.
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Cell {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let data = CellData::deserialize(deserializer)?;
+        Ok(Cell {
+            coords: data.coords,
+            distance: data.distance,
+            is_start: data.is_start,
+            is_goal: data.is_goal,
+            is_active: data.is_active,
+            is_visited: data.is_visited,
+            has_been_visited: data.has_been_visited,
+            on_solution_path: data.on_solution_path,
+            neighbors_by_direction: Neighbors::new(data.coords),
+            linked: Links::new(data.coords),
+            open_walls: data.linked.into_iter().collect(),
+            is_square: data.is_square,
+            distance_band: data.distance_band,
+            heat: data.heat,
+            is_discovered: data.is_discovered,
+            border_opening: data.border_opening,
+            weight: data.weight,
+            ..Cell::default()
+        })
+    }
+}
+
+#[cfg(feature = "json")]
 impl fmt::Display for Cell {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.to_json() {
@@ -145,8 +401,23 @@ impl fmt::Display for Cell {
         }
     }
 }
+// the JSON representation, as far as it can be written without serde
+#[cfg(not(feature = "json"))]
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
 
 impl Cell {
+    /// The cell's axial coordinates, for Sigma (hexagonal) cells only.
+    pub fn to_axial(&self) -> Option<AxialCoordinates> {
+        match self.maze_type {
+            MazeType::Sigma => Some(AxialCoordinates::from_offset(self.coords)),
+            _ => None,
+        }
+    }
+
     /// X coordinate (on horizontal axis)
     pub fn x(&self) -> usize {
         return self.coords.x;
@@ -159,29 +430,29 @@ impl Cell {
 
     /// Coordinates of neighboring Cells
     pub fn neighbors(&self) -> HashSet<Coordinates> {
-        return self.neighbors_by_direction.values().cloned().collect();        
+        return self.neighbors_by_direction.values().chain(self.room_neighbors.iter().copied()).collect();
     }
 
     /// Coordinates of linked neighboring Cells (linked indicating no walls separating these linked neighbors from this Cell)
     pub fn unlinked_neighbors(&self) -> HashSet<Coordinates> {
-        let all_neighbors = self.neighbors();
-        return all_neighbors.difference(&self.linked).cloned().collect();
+        return self.neighbors().into_iter().filter(|coords| !self.linked.contains(coords)).collect();
     }
 
     /// Directions from this Cell to linked neighboring Cells (linked indicating no walls separating these linked neighbors from this Cell)
     pub fn linked_directions(&self) -> HashSet<Direction> {
         // Assuming neighbors_by_direction provides the mapping
         self.neighbors_by_direction
-            .filter_keys(|coords| self.linked.contains(coords))
-            .into_iter()
-            .collect() 
+            .iter()
+            .filter(|(_, coords)| self.linked.contains(coords))
+            .map(|(direction, _)| direction)
+            .collect()
     }
 
     /// Whether neighbor in specified Direction is linked to this Cell
     pub fn is_linked_direction(&self, direction: Direction) -> bool {
         // Find the neighbor for the given direction
         if let Some(neighbor_coords) = self.neighbors_by_direction.get(&direction) {
-            self.linked.contains(neighbor_coords)
+            self.linked.contains(&neighbor_coords)
         } else {
             false
         }
@@ -219,7 +490,10 @@ impl Cell {
 
     /// Set linked to a specified set of Coordinates    
     pub fn set_linked(&mut self, linked: HashSet<Coordinates>) {
-        self.linked = linked;
+        self.linked.clear();
+        for coords in linked {
+            self.linked.insert(coords);
+        }
     }
 
     /// Set orientation
@@ -229,43 +503,71 @@ impl Cell {
     
     /// Set neighbors_by_direction 
     pub fn set_neighbors(&mut self, neighbors_by_direction: HashMap<Direction, Coordinates>) {
-        self.neighbors_by_direction = neighbors_by_direction;
+        self.neighbors_by_direction.clear();
+        for (direction, coords) in neighbors_by_direction {
+            self.neighbors_by_direction.insert(direction, coords);
+        }
     }
 
     /// Set open_walls based on neighbors_by_direction to indicate which walls are omitted (e.g. open walls)
     pub fn set_open_walls(&mut self) {
-        self.open_walls = self.linked.iter()
-            .filter_map(|coords| {
-                self.neighbors_by_direction
-                    .iter()
-                    .find(|(_, &v)| v == *coords)
-                    .map(|(direction, _)| direction.clone())
-            })
+        self.open_walls = self.neighbors_by_direction
+            .iter()
+            .filter(|(_, coords)| self.linked.contains(coords))
+            .map(|(direction, _)| direction)
             .collect()
     }
 
+    // Approximate number of bytes the cell takes on the heap: only neighbors and links beyond the eight surrounding
+    // positions, and the neighbors of a contracted room
+    pub(crate) fn heap_footprint(&self) -> usize {
+        use core::mem::size_of;
+        self.neighbors_by_direction.heap_footprint()
+            + self.linked.heap_footprint()
+            + self.room_neighbors.capacity() * size_of::<Coordinates>()
+    }
+
     /// Returns neighbors mapped to user-facing directions (diagonal for Rhombic).
     pub fn get_user_facing_neighbors(&self) -> HashMap<Direction, Coordinates> {
         if self.maze_type == MazeType::Rhombic {
             let mut mapped = HashMap::new();
-            if let Some(&coords) = self.neighbors_by_direction.get(&Direction::Up) {
+            if let Some(coords) = self.neighbors_by_direction.get(&Direction::Up) {
                 mapped.insert(Direction::UpperRight, coords);
             }
-            if let Some(&coords) = self.neighbors_by_direction.get(&Direction::Right) {
+            if let Some(coords) = self.neighbors_by_direction.get(&Direction::Right) {
                 mapped.insert(Direction::LowerRight, coords);
             }
-            if let Some(&coords) = self.neighbors_by_direction.get(&Direction::Down) {
+            if let Some(coords) = self.neighbors_by_direction.get(&Direction::Down) {
                 mapped.insert(Direction::LowerLeft, coords);
             }
-            if let Some(&coords) = self.neighbors_by_direction.get(&Direction::Left) {
+            if let Some(coords) = self.neighbors_by_direction.get(&Direction::Left) {
                 mapped.insert(Direction::UpperLeft, coords);
             }
             mapped
         } else {
-            self.neighbors_by_direction.clone()
+            self.neighbors_by_direction.iter().collect()
+        }
+    }
+
+    /// Every side of the cell, whether or not there is a neighbor beyond it, starting with its top and bottom.
+    pub fn sides(&self) -> &'static [Direction] {
+        use Direction::*;
+        match self.maze_type {
+            MazeType::Orthogonal => &[Up, Down, Left, Right],
+            MazeType::Delta if self.orientation == CellOrientation::Normal => &[Down, UpperLeft, UpperRight],
+            MazeType::Delta => &[Up, LowerLeft, LowerRight],
+            MazeType::Sigma => &[Up, Down, UpperLeft, UpperRight, LowerLeft, LowerRight],
+            MazeType::Upsilon if self.is_square => &[Up, Down, Left, Right],
+            MazeType::Upsilon => &[Up, Down, Left, Right, UpperLeft, UpperRight, LowerLeft, LowerRight],
+            MazeType::Rhombic => &[UpperLeft, UpperRight, LowerLeft, LowerRight],
         }
     }
 
+    /// Sides of the cell on the maze's outer border, with no neighbor beyond them.
+    pub fn border_sides(&self) -> Vec<Direction> {
+        self.sides().iter().copied().filter(|side| !self.neighbors_by_direction.contains_key(side)).collect()
+    }
+
     /// Returns linked directions mapped to user-facing directions (diagonal for Rhombic).
     pub fn get_user_facing_linked_directions(&self) -> Vec<Direction> {
         if self.maze_type == MazeType::Rhombic {
@@ -286,7 +588,7 @@ impl Cell {
 
     pub fn get_user_facing_open_walls(&self) -> Vec<Direction> {
         if self.maze_type == MazeType::Rhombic {
-            self.open_walls.iter().map(|&d| match d {
+            self.open_walls.iter().map(|d| match d {
                 Direction::Up => Direction::UpperRight,
                 Direction::Right => Direction::LowerRight,
                 Direction::Down => Direction::LowerLeft,
@@ -294,7 +596,7 @@ impl Cell {
                 d => d,
             }).collect()
         } else {
-            self.open_walls.clone()
+            self.open_walls.iter().collect()
         }
     }
 }
@@ -314,11 +616,13 @@ impl CellBuilder {
     }
 
     pub fn new(x: usize, y: usize, maze_type: MazeType) -> Self {
+        let coords = Coordinates { x, y };
         Self(Cell {
-            coords: Coordinates{x: x, y: y},
+            coords,
             maze_type,
-            neighbors_by_direction: HashMap::new(),
-            linked: HashSet::new(),
+            neighbors_by_direction: Neighbors::new(coords),
+            room_neighbors: Vec::new(),
+            linked: Links::new(coords),
             distance: 0,
             is_start: false,
             is_goal: false,
@@ -327,8 +631,13 @@ impl CellBuilder {
             is_active: false, 
             on_solution_path: false,
             orientation: CellOrientation::Normal,
-            open_walls: Vec::new(),
+            open_walls: DirectionSet::new(),
             is_square: false,
+            distance_band: None,
+            heat: None,
+            is_discovered: false,
+            border_opening: None,
+            weight: None,
         })
     }
 
@@ -358,7 +667,7 @@ impl CellBuilder {
     }
 
     pub fn linked(mut self, linked: HashSet<Coordinates>) -> Self {
-        self.0.linked = linked;
+        self.0.set_linked(linked);
         self
     }
 
@@ -368,7 +677,7 @@ impl CellBuilder {
     }
     
     pub fn neighbors(&mut self, neighbors_by_direction: HashMap<Direction, Coordinates>) -> &Self {
-        self.0.neighbors_by_direction = neighbors_by_direction;
+        self.0.set_neighbors(neighbors_by_direction);
         self
     }
 
@@ -391,19 +700,17 @@ mod tests {
         neighbors.insert(Direction::Right, Coordinates{ x: 2, y: 1});
         neighbors.insert(Direction::Down, Coordinates{ x: 1, y: 2});
         neighbors.insert(Direction::Left, Coordinates{ x: 0, y: 1});
-        let cell2 = Cell {
-            neighbors_by_direction: neighbors,
-            ..cell1
-        };
+        let mut cell2 = cell1;
+        cell2.set_neighbors(neighbors);
         assert!(cell2.neighbors().contains(&Coordinates{x: 1, y: 0}));
         assert!(cell2.neighbors().contains(&Coordinates{x: 2, y: 1}));
         assert!(cell2.neighbors().contains(&Coordinates{x: 1, y: 2}));
         assert!(cell2.neighbors().contains(&Coordinates{x: 0, y: 1}));
         assert!(cell2.neighbors().len() == 4);
-        assert!(*cell2.neighbors_by_direction.get(&Direction::Up).expect("Missing North neighbor") == Coordinates{x: 1, y: 0});
-        assert!(*cell2.neighbors_by_direction.get(&Direction::Right).expect("Missing East neighbor") == Coordinates{x: 2, y: 1});
-        assert!(*cell2.neighbors_by_direction.get(&Direction::Down).expect("Missing South neighbor") == Coordinates{x: 1, y: 2});
-        assert!(*cell2.neighbors_by_direction.get(&Direction::Left).expect("Missing West neighbor") == Coordinates{x: 0, y: 1});
+        assert!(cell2.neighbors_by_direction.get(&Direction::Up).expect("Missing North neighbor") == Coordinates{x: 1, y: 0});
+        assert!(cell2.neighbors_by_direction.get(&Direction::Right).expect("Missing East neighbor") == Coordinates{x: 2, y: 1});
+        assert!(cell2.neighbors_by_direction.get(&Direction::Down).expect("Missing South neighbor") == Coordinates{x: 1, y: 2});
+        assert!(cell2.neighbors_by_direction.get(&Direction::Left).expect("Missing West neighbor") == Coordinates{x: 0, y: 1});
 
         // cell with no neighbors assigned
         let cell3 = CellBuilder::new(1, 1, MazeType::Orthogonal).build();
@@ -428,11 +735,10 @@ mod tests {
         linked.insert(north.clone());
         linked.insert(south.clone());
         // Clone cell1 for use in cell2
-        let cell2 = Cell {
-            neighbors_by_direction: neighbors.clone(),
-            linked: linked.clone(),
-            ..cell1.clone()  // clone cell1 entirely
-        };
+        // Clone cell1 entirely
+        let mut cell2 = cell1.clone();
+        cell2.set_neighbors(neighbors.clone());
+        cell2.set_linked(linked.clone());
         assert!(cell2.linked.contains(&north));
         assert!(cell2.linked.contains(&south));
         assert!(cell2.linked.len() == 2);
@@ -450,10 +756,8 @@ mod tests {
         assert!(cell2.linked_directions().contains(&Direction::Up));
         assert!(cell2.linked_directions().contains(&Direction::Down));
         assert!(cell2.linked_directions().len() == 2);
-        let mut cell3 = Cell {
-            neighbors_by_direction: neighbors,
-            ..cell1.clone()
-        }; // nothing linked yet
+        let mut cell3 = cell1.clone();
+        cell3.set_neighbors(neighbors); // nothing linked yet
         assert!(cell3.linked.is_empty());
         cell3.set_linked(linked.clone());
         assert!(cell3.linked.contains(&north));
@@ -486,11 +790,12 @@ mod tests {
         linked.insert(Coordinates { x: 1, y: 0 });
         linked.insert(Coordinates { x: 0, y: 1 });
 
-        let cell = Cell {
+        let mut cell = Cell {
             coords: Coordinates { x: 1, y: 1 },
             maze_type: MazeType::Orthogonal,
-            neighbors_by_direction: neighbors,
-            linked,
+            neighbors_by_direction: Neighbors::new(Coordinates { x: 1, y: 1 }),
+            room_neighbors: Vec::new(),
+            linked: Links::new(Coordinates { x: 1, y: 1 }),
             distance: 10,
             is_start: true,
             is_goal: false,
@@ -499,9 +804,16 @@ mod tests {
             has_been_visited: false,
             on_solution_path: true,
             orientation: CellOrientation::Normal,
-            open_walls: Vec::new(),
+            open_walls: DirectionSet::new(),
             is_square: true,
+            distance_band: None,
+            heat: None,
+            is_discovered: false,
+            border_opening: None,
+            weight: None,
         };
+        cell.set_neighbors(neighbors);
+        cell.set_linked(linked);
 
         let json = cell.to_string();
         println!("Serialized JSON: {}", json);
@@ -512,6 +824,51 @@ mod tests {
         assert!(json.contains("\"Down\""));
         assert!(json.contains("\"on_solution_path\":true"));
         assert!(json.contains("\"is_square\":true"));
+        assert!(!json.contains("distance_band"));
+        assert!(!json.contains("heat"));
+        assert!(!json.contains("weight"));
+
+        let restored: Cell = serde_json::from_str(&json).expect("Failed to deserialize cell");
+        assert_eq!(restored.coords, cell.coords);
+        assert_eq!(restored.distance, 10);
+        assert!(restored.is_start && restored.on_solution_path && restored.is_square);
+        assert_eq!(restored.open_walls.len(), 2);
+        assert!(restored.open_walls.contains(&Direction::Right) && restored.open_walls.contains(&Direction::Down));
+    }
+
+    #[test]
+    fn maze_type_names_round_trip() {
+        for maze_type in MazeType::all() {
+            assert_eq!(MazeType::from_str(maze_type.name()).unwrap(), *maze_type);
+            // names must match the serde representation used by JSON requests
+            assert_eq!(maze_type.to_json().unwrap(), format!("\"{}\"", maze_type.name()));
+        }
+        assert!(MazeType::from_str("Hexagonal").is_err());
+    }
+
+    #[test]
+    fn rhombille_is_an_alias_for_rhombic() {
+        assert_eq!(MazeType::from_str("Rhombille").unwrap(), MazeType::Rhombic);
+        let maze_type: MazeType = serde_json::from_str("\"Rhombille\"").unwrap();
+        assert_eq!(maze_type, MazeType::Rhombic);
+        // the canonical spelling is always written
+        assert_eq!(maze_type.to_json().unwrap(), "\"Rhombic\"");
+    }
+
+    #[test]
+    fn axial_coordinates_round_trip() {
+        for x in 0..6 {
+            for y in 0..6 {
+                let coords = Coordinates { x, y };
+                let axial = AxialCoordinates::from_offset(coords);
+                assert_eq!(axial.to_offset(), Some(coords));
+                let (cube_x, cube_y, cube_z) = axial.to_cube();
+                assert_eq!(cube_x + cube_y + cube_z, 0);
+            }
+        }
+        assert_eq!(AxialCoordinates::from_offset(Coordinates { x: 3, y: 0 }), AxialCoordinates { q: 3, r: -1 });
+        assert_eq!(AxialCoordinates { q: 0, r: -1 }.to_offset(), None);
+        assert_eq!(AxialCoordinates { q: 0, r: 0 }.distance(&AxialCoordinates { q: 2, r: -1 }), 2);
     }
 
 
@@ -0,0 +1,847 @@
+use core::fmt;
+use core::mem::size_of;
+
+use crate::cell::{CellOrientation, Coordinates, DeltaShape, MazeType};
+use crate::direction::Direction;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::prelude::*;
+
+/// Magic header identifying the binary maze format produced by `CompactGrid::to_bytes`.
+pub const BINARY_MAGIC: [u8; 4] = *b"MAZR";
+/// Version of the binary maze format, incremented whenever the encoding changes.
+pub const BINARY_VERSION: u8 = 1;
+// magic, version, maze type, six u32 dimensions/coordinates and a u64 seed
+const BINARY_HEADER_LEN: usize = 4 + 1 + 1 + 6 * 4 + 8;
+
+const START: u8 = 1;
+const GOAL: u8 = 1 << 1;
+const ACTIVE: u8 = 1 << 2;
+const VISITED: u8 = 1 << 3;
+const HAS_BEEN_VISITED: u8 = 1 << 4;
+const ON_SOLUTION_PATH: u8 = 1 << 5;
+const INVERTED: u8 = 1 << 6;
+const SQUARE: u8 = 1 << 7;
+
+/// Bit representing a direction within a `CompactCell` wall mask. Layer transitions have no bit, as compact
+/// grids only describe a single layer, and neither do polar directions, as no maze type has polar cells.
+fn direction_bit(direction: Direction) -> u8 {
+    if direction.is_layer_transition() || direction.is_polar() { 0 } else { 1 << (direction as u32) }
+}
+
+/// Offset of the neighbor in the given direction, following the same geometry used to assign neighbors to a `Grid`.
+fn neighbor_offset(maze_type: MazeType, coords: Coordinates, direction: Direction) -> (isize, isize) {
+    use Direction::*;
+    match (maze_type, direction) {
+        (_, Up) => (0, -1),
+        (_, Down) => (0, 1),
+        (_, Right) => (1, 0),
+        (_, Left) => (-1, 0),
+        (MazeType::Delta, UpperLeft) | (MazeType::Delta, LowerLeft) => (-1, 0),
+        (MazeType::Delta, UpperRight) | (MazeType::Delta, LowerRight) => (1, 0),
+        (MazeType::Sigma, _) => direction.offset_delta(coords.x % 2 == 1),
+        (_, UpperRight) => (1, -1),
+        (_, LowerRight) => (1, 1),
+        (_, LowerLeft) => (-1, 1),
+        (_, UpperLeft) => (-1, -1),
+        (_, Above) | (_, Below) => (0, 0),
+        (_, Inward) | (_, Outward) | (_, Clockwise) | (_, CounterClockwise) => (0, 0),
+    }
+}
+
+// Offsets of the eight positions surrounding a cell, indexed by the bits of a `Links` mask and the codes of a
+// `Neighbors` map; a position anywhere else, such as a stairway's landing or a contracted room, is stored as is
+const AROUND: [(isize, isize); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+// Code of a `Neighbors` direction whose neighbor is stored rather than computed from `AROUND`
+const STORED: u64 = 0xF;
+
+// Coordinates are packed into 32 bits each, as no maze comes anywhere near that size
+fn pack(coords: Coordinates) -> (u32, u32) {
+    (coords.x as u32, coords.y as u32)
+}
+
+fn around_index(origin: (u32, u32), coords: Coordinates) -> Option<usize> {
+    let offset = (coords.x as isize - origin.0 as isize, coords.y as isize - origin.1 as isize);
+    AROUND.iter().position(|&around| around == offset)
+}
+
+fn around(origin: (u32, u32), index: usize) -> Coordinates {
+    let (dx, dy) = AROUND[index];
+    Coordinates { x: (origin.0 as isize + dx) as usize, y: (origin.1 as isize + dy) as usize }
+}
+
+/// A set of directions packed into a bitmask, such as the open walls of a `Cell`.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DirectionSet(u16);
+
+impl DirectionSet {
+    pub fn new() -> Self {
+        Self(0)
+    }
+
+    pub fn contains(&self, direction: &Direction) -> bool {
+        self.0 & (1 << *direction as u32) != 0
+    }
+
+    /// Add the direction, returning whether it was missing from the set.
+    pub fn insert(&mut self, direction: Direction) -> bool {
+        let missing = !self.contains(&direction);
+        self.0 |= 1 << direction as u32;
+        missing
+    }
+
+    /// Remove the direction, returning whether it was in the set.
+    pub fn remove(&mut self, direction: &Direction) -> bool {
+        let present = self.contains(direction);
+        self.0 &= !(1 << *direction as u32);
+        present
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.0 = 0;
+    }
+
+    /// The directions in the set, in the order of `Direction::all`.
+    pub fn iter(&self) -> DirectionSetIter {
+        DirectionSetIter { set: *self, index: 0 }
+    }
+}
+
+/// Iterator over the directions of a `DirectionSet`.
+pub struct DirectionSetIter {
+    set: DirectionSet,
+    index: usize,
+}
+
+impl Iterator for DirectionSetIter {
+    type Item = Direction;
+
+    fn next(&mut self) -> Option<Direction> {
+        let directions = Direction::all();
+        while let Some(&direction) = directions.get(self.index) {
+            self.index += 1;
+            if self.set.contains(&direction) {
+                return Some(direction);
+            }
+        }
+        None
+    }
+}
+
+impl IntoIterator for &DirectionSet {
+    type Item = Direction;
+    type IntoIter = DirectionSetIter;
+
+    fn into_iter(self) -> DirectionSetIter {
+        self.iter()
+    }
+}
+
+impl fmt::Debug for DirectionSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<Direction> for DirectionSet {
+    fn from_iter<I: IntoIterator<Item = Direction>>(directions: I) -> Self {
+        let mut set = DirectionSet::new();
+        for direction in directions {
+            set.insert(direction);
+        }
+        set
+    }
+}
+
+/// The coordinates of the cells a `Cell` is linked to (i.e., no walls in between). Links to any of the eight
+/// surrounding positions are kept as a bitmask of offsets from the cell, so that a cell's links take no memory
+/// beyond the set itself; only links further away, such as stairs between layers, are stored as coordinates.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Links {
+    origin: (u32, u32),
+    around: u8,
+    // sorted, so that equal sets compare equal
+    distant: Box<[Coordinates]>,
+}
+
+impl Links {
+    /// An empty set of links for the cell at `origin`.
+    pub fn new(origin: Coordinates) -> Self {
+        Self { origin: pack(origin), around: 0, distant: Box::default() }
+    }
+
+    pub fn contains(&self, coords: &Coordinates) -> bool {
+        match around_index(self.origin, *coords) {
+            Some(index) => self.around & (1 << index) != 0,
+            None => self.distant.binary_search(coords).is_ok(),
+        }
+    }
+
+    /// Add a link, returning whether it was missing from the set.
+    pub fn insert(&mut self, coords: Coordinates) -> bool {
+        if let Some(index) = around_index(self.origin, coords) {
+            let missing = self.around & (1 << index) == 0;
+            self.around |= 1 << index;
+            return missing;
+        }
+        let Err(position) = self.distant.binary_search(&coords) else {
+            return false;
+        };
+        let mut distant = self.distant.to_vec();
+        distant.insert(position, coords);
+        self.distant = distant.into_boxed_slice();
+        true
+    }
+
+    /// Remove a link, returning whether it was in the set.
+    pub fn remove(&mut self, coords: &Coordinates) -> bool {
+        if let Some(index) = around_index(self.origin, *coords) {
+            let present = self.around & (1 << index) != 0;
+            self.around &= !(1 << index);
+            return present;
+        }
+        let Ok(position) = self.distant.binary_search(coords) else {
+            return false;
+        };
+        let mut distant = self.distant.to_vec();
+        distant.remove(position);
+        self.distant = distant.into_boxed_slice();
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.around.count_ones() as usize + self.distant.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.around == 0 && self.distant.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.around = 0;
+        self.distant = Box::default();
+    }
+
+    /// The linked coordinates: surrounding positions clockwise from above, then any others in order.
+    pub fn iter(&self) -> LinksIter<'_> {
+        LinksIter { links: self, index: 0 }
+    }
+
+    // Bytes the set takes on the heap, beyond its own size
+    pub(crate) fn heap_footprint(&self) -> usize {
+        self.distant.len() * size_of::<Coordinates>()
+    }
+}
+
+/// Iterator over the coordinates of a `Links` set.
+pub struct LinksIter<'a> {
+    links: &'a Links,
+    index: usize,
+}
+
+impl Iterator for LinksIter<'_> {
+    type Item = Coordinates;
+
+    fn next(&mut self) -> Option<Coordinates> {
+        while self.index < AROUND.len() {
+            let index = self.index;
+            self.index += 1;
+            if self.links.around & (1 << index) != 0 {
+                return Some(around(self.links.origin, index));
+            }
+        }
+        let coords = self.links.distant.get(self.index - AROUND.len()).copied();
+        self.index += 1;
+        coords
+    }
+}
+
+impl<'a> IntoIterator for &'a Links {
+    type Item = Coordinates;
+    type IntoIter = LinksIter<'a>;
+
+    fn into_iter(self) -> LinksIter<'a> {
+        self.iter()
+    }
+}
+
+impl fmt::Debug for Links {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// The coordinates of a `Cell`'s neighbors, by direction. Like `Links`, a neighbor at any of the eight surrounding
+/// positions is kept as a 4-bit offset code per direction, with only neighbors further away stored as coordinates.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Neighbors {
+    origin: (u32, u32),
+    codes: u64,
+    // sorted by direction, so that equal maps compare equal
+    distant: Box<[(Direction, Coordinates)]>,
+}
+
+impl Neighbors {
+    /// An empty map of neighbors for the cell at `origin`.
+    pub fn new(origin: Coordinates) -> Self {
+        Self { origin: pack(origin), codes: 0, distant: Box::default() }
+    }
+
+    fn code(&self, direction: Direction) -> u64 {
+        (self.codes >> (4 * direction as u32)) & 0xF
+    }
+
+    fn set_code(&mut self, direction: Direction, code: u64) {
+        let shift = 4 * direction as u32;
+        self.codes = (self.codes & !(0xF << shift)) | (code << shift);
+    }
+
+    fn distant_position(&self, direction: Direction) -> Result<usize, usize> {
+        self.distant.binary_search_by_key(&(direction as u32), |&(d, _)| d as u32)
+    }
+
+    pub fn get(&self, direction: &Direction) -> Option<Coordinates> {
+        match self.code(*direction) {
+            0 => None,
+            STORED => self.distant_position(*direction).ok().map(|position| self.distant[position].1),
+            code => Some(around(self.origin, code as usize - 1)),
+        }
+    }
+
+    pub fn contains_key(&self, direction: &Direction) -> bool {
+        self.code(*direction) != 0
+    }
+
+    /// Set the neighbor in the direction, returning the neighbor it replaces.
+    pub fn insert(&mut self, direction: Direction, coords: Coordinates) -> Option<Coordinates> {
+        let previous = self.remove(&direction);
+        match around_index(self.origin, coords) {
+            Some(index) => self.set_code(direction, index as u64 + 1),
+            None => {
+                self.set_code(direction, STORED);
+                let position = self.distant_position(direction).unwrap_or_else(|position| position);
+                let mut distant = self.distant.to_vec();
+                distant.insert(position, (direction, coords));
+                self.distant = distant.into_boxed_slice();
+            }
+        }
+        previous
+    }
+
+    /// Remove the neighbor in the direction, returning it.
+    pub fn remove(&mut self, direction: &Direction) -> Option<Coordinates> {
+        let previous = self.get(direction);
+        if let Ok(position) = self.distant_position(*direction) {
+            let mut distant = self.distant.to_vec();
+            distant.remove(position);
+            self.distant = distant.into_boxed_slice();
+        }
+        self.set_code(*direction, 0);
+        previous
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.codes = 0;
+        self.distant = Box::default();
+    }
+
+    /// The directions which have a neighbor, in the order of `Direction::all`.
+    pub fn keys(&self) -> impl Iterator<Item = Direction> + '_ {
+        Direction::all().iter().copied().filter(|direction| self.contains_key(direction))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.iter().map(|(_, coords)| coords)
+    }
+
+    pub fn iter(&self) -> NeighborsIter<'_> {
+        NeighborsIter { neighbors: self, index: 0 }
+    }
+
+    // Bytes the map takes on the heap, beyond its own size
+    pub(crate) fn heap_footprint(&self) -> usize {
+        self.distant.len() * size_of::<(Direction, Coordinates)>()
+    }
+}
+
+/// Iterator over the directions and coordinates of a `Neighbors` map, in the order of `Direction::all`.
+pub struct NeighborsIter<'a> {
+    neighbors: &'a Neighbors,
+    index: usize,
+}
+
+impl Iterator for NeighborsIter<'_> {
+    type Item = (Direction, Coordinates);
+
+    fn next(&mut self) -> Option<(Direction, Coordinates)> {
+        let directions = Direction::all();
+        while let Some(&direction) = directions.get(self.index) {
+            self.index += 1;
+            if let Some(coords) = self.neighbors.get(&direction) {
+                return Some((direction, coords));
+            }
+        }
+        None
+    }
+}
+
+impl<'a> IntoIterator for &'a Neighbors {
+    type Item = (Direction, Coordinates);
+    type IntoIter = NeighborsIter<'a>;
+
+    fn into_iter(self) -> NeighborsIter<'a> {
+        self.iter()
+    }
+}
+
+impl fmt::Debug for Neighbors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// A single cell of a `CompactGrid`: state flags and wall bitmasks.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CompactCell {
+    /// Bitmask of directions in which a neighboring cell exists.
+    pub neighbor_mask: u8,
+    /// Bitmask of directions in which there is no wall between this cell and its neighbor.
+    pub open_mask: u8,
+    /// Packed start/goal/active/visited/solution/orientation/square flags.
+    pub flags: u8,
+    /// Distance from the start cell.
+    pub distance: i32,
+}
+
+impl CompactCell {
+    pub fn is_start(&self) -> bool { self.flags & START != 0 }
+    pub fn is_goal(&self) -> bool { self.flags & GOAL != 0 }
+    pub fn is_active(&self) -> bool { self.flags & ACTIVE != 0 }
+    pub fn is_visited(&self) -> bool { self.flags & VISITED != 0 }
+    pub fn has_been_visited(&self) -> bool { self.flags & HAS_BEEN_VISITED != 0 }
+    pub fn on_solution_path(&self) -> bool { self.flags & ON_SOLUTION_PATH != 0 }
+    pub fn is_square(&self) -> bool { self.flags & SQUARE != 0 }
+
+    pub fn orientation(&self) -> CellOrientation {
+        if self.flags & INVERTED != 0 { CellOrientation::Inverted } else { CellOrientation::Normal }
+    }
+
+    /// Whether there is no wall between this cell and its neighbor in the specified direction
+    pub fn is_linked_direction(&self, direction: Direction) -> bool {
+        self.open_mask & direction_bit(direction) != 0
+    }
+
+    /// Directions in which there are no walls restricting movement
+    pub fn linked_directions(&self) -> Vec<Direction> {
+        Direction::all().iter().copied().filter(|&d| self.is_linked_direction(d)).collect()
+    }
+}
+
+/// Packed snapshot of a maze `Grid`, from which its binary format is encoded.
+///
+/// Each cell is reduced to a pair of direction bitmasks and packed flags, leaving out the overlays a `Cell`
+/// carries (distance bands, heat, weights), so that it is smaller still than a `Grid` for large mazes. It
+/// converts back to a full `Grid` when needed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactGrid {
+    pub width: usize,
+    pub height: usize,
+    pub maze_type: MazeType,
+    pub start_coords: Coordinates,
+    pub goal_coords: Coordinates,
+    pub seed: u64,
+    /// Flattened cells in row-major order; `None` where the maze type has no cell.
+    pub cells: Vec<Option<CompactCell>>,
+}
+
+impl From<&Grid> for CompactGrid {
+    fn from(grid: &Grid) -> Self {
+        let cells = grid.cells.iter().map(|opt| {
+            opt.as_ref().map(|cell| {
+                let mut compact = CompactCell { neighbor_mask: 0, open_mask: 0, flags: 0, distance: cell.distance };
+                for &direction in Direction::all() {
+                    let expected = Self::offset_coords(grid.maze_type, grid.width, grid.height, cell.coords, direction);
+                    if let Some(neighbor) = expected.filter(|n| cell.neighbors_by_direction.get(&direction) == Some(*n)) {
+                        compact.neighbor_mask |= direction_bit(direction);
+                        if cell.linked.contains(&neighbor) {
+                            compact.open_mask |= direction_bit(direction);
+                        }
+                    }
+                }
+                let flag = |set: bool, bit: u8| if set { bit } else { 0 };
+                compact.flags = flag(cell.is_start, START)
+                    | flag(cell.is_goal, GOAL)
+                    | flag(cell.is_active, ACTIVE)
+                    | flag(cell.is_visited, VISITED)
+                    | flag(cell.has_been_visited, HAS_BEEN_VISITED)
+                    | flag(cell.on_solution_path, ON_SOLUTION_PATH)
+                    | flag(cell.orientation == CellOrientation::Inverted, INVERTED)
+                    | flag(cell.is_square, SQUARE);
+                compact
+            })
+        }).collect();
+
+        CompactGrid {
+            width: grid.width,
+            height: grid.height,
+            maze_type: grid.maze_type,
+            start_coords: grid.start_coords,
+            goal_coords: grid.goal_coords,
+            seed: grid.seed,
+            cells,
+        }
+    }
+}
+
+impl TryFrom<&CompactGrid> for Grid {
+    type Error = Error;
+
+    fn try_from(compact: &CompactGrid) -> Result<Self, Self::Error> {
+        let mut grid = Grid::new(compact.maze_type, compact.width, compact.height, compact.start_coords, compact.goal_coords, false)?;
+        let kept = |coords: Coordinates| compact.get(coords).is_some();
+        let first = compact.cells.iter().enumerate().find_map(|(index, opt)| opt.map(|cell| (index, cell)));
+        if let Some((index, first)) = first.filter(|_| compact.maze_type == MazeType::Delta) {
+            let (x, y) = (index % compact.width, index / compact.width);
+            let flipped = |orientation: CellOrientation| match orientation {
+                CellOrientation::Normal => CellOrientation::Inverted,
+                CellOrientation::Inverted => CellOrientation::Normal,
+            };
+            // orientations alternate across rows and down columns from the top-left position, or from the first
+            // position of a triangle's top row, which starts an upward triangle halfway across
+            let oriented_from = |origin: usize| if (x + y + origin).is_multiple_of(2) { first.orientation() } else { flipped(first.orientation()) };
+            let triangle = [CellOrientation::Normal, CellOrientation::Inverted]
+                .into_iter()
+                .filter(|_| compact.width + 1 == 2 * compact.height)
+                .find(|&orientation| {
+                    let origin = if orientation == CellOrientation::Normal { compact.height - 1 } else { 0 };
+                    oriented_from(origin) == orientation
+                        && grid.iter_cells().all(|cell| DeltaShape::Triangle.contains(orientation, compact.height, cell.coords) || !kept(cell.coords))
+                });
+            match triangle {
+                Some(orientation) => {
+                    grid.set_delta_orientation(orientation)?;
+                    grid.set_delta_shape(DeltaShape::Triangle)?;
+                }
+                None => grid.set_delta_orientation(oriented_from(0))?,
+            }
+        }
+        // any other position without a cell lies outside the maze's mask
+        if grid.iter_cells().any(|cell| !kept(cell.coords)) {
+            grid.set_mask(Some(compact.cells.iter().map(Option::is_some).collect()))?;
+        }
+        grid.seed = compact.seed;
+        for (index, opt) in compact.cells.iter().enumerate() {
+            if let Some(compact_cell) = opt {
+                let coords = Coordinates { x: index % compact.width, y: index / compact.width };
+                for neighbor in compact.linked(coords) {
+                    grid.link(coords, neighbor)?;
+                }
+                let cell = grid.get_mut(coords)?;
+                cell.distance = compact_cell.distance;
+                cell.is_start = compact_cell.is_start();
+                cell.is_goal = compact_cell.is_goal();
+                cell.is_active = compact_cell.is_active();
+                cell.is_visited = compact_cell.is_visited();
+                cell.has_been_visited = compact_cell.has_been_visited();
+                cell.on_solution_path = compact_cell.on_solution_path();
+            }
+        }
+        Ok(grid)
+    }
+}
+
+impl CompactGrid {
+    fn offset_coords(maze_type: MazeType, width: usize, height: usize, coords: Coordinates, direction: Direction) -> Option<Coordinates> {
+        let (dx, dy) = neighbor_offset(maze_type, coords, direction);
+        let x = coords.x.checked_add_signed(dx)?;
+        let y = coords.y.checked_add_signed(dy)?;
+        if x < width && y < height { Some(Coordinates { x, y }) } else { None }
+    }
+
+    /// Retrieve a compact cell by its coordinates
+    pub fn get(&self, coords: Coordinates) -> Option<&CompactCell> {
+        if coords.x >= self.width || coords.y >= self.height {
+            return None;
+        }
+        self.cells.get(coords.y * self.width + coords.x).and_then(|opt| opt.as_ref())
+    }
+
+    /// Coordinates of the neighbor in the given direction, if a neighbor exists there
+    pub fn neighbor(&self, coords: Coordinates, direction: Direction) -> Option<Coordinates> {
+        let cell = self.get(coords)?;
+        if cell.neighbor_mask & direction_bit(direction) == 0 {
+            return None;
+        }
+        Self::offset_coords(self.maze_type, self.width, self.height, coords, direction)
+    }
+
+    /// Coordinates of all neighboring cells
+    pub fn neighbors(&self, coords: Coordinates) -> Vec<Coordinates> {
+        Direction::all().iter().filter_map(|&d| self.neighbor(coords, d)).collect()
+    }
+
+    /// Coordinates of neighboring cells with no wall in between
+    pub fn linked(&self, coords: Coordinates) -> Vec<Coordinates> {
+        match self.get(coords) {
+            Some(cell) => cell
+                .linked_directions()
+                .into_iter()
+                .filter_map(|d| self.neighbor(coords, d))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Encode the grid in a compact binary format: a magic header and format version, followed by the maze
+    /// type, dimensions, endpoints and seed, then two bytes per cell (open wall bitmask and state flags).
+    /// Distances are not stored, as they can be recomputed from the walls.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let cell_count = self.cells.iter().flatten().count();
+        let mut bytes = Vec::with_capacity(BINARY_HEADER_LEN + 2 * cell_count);
+        bytes.extend_from_slice(&BINARY_MAGIC);
+        bytes.push(BINARY_VERSION);
+        let maze_type_index = MazeType::all().iter().position(|t| *t == self.maze_type).unwrap_or(0);
+        bytes.push(maze_type_index as u8);
+        for value in [
+            self.width, self.height,
+            self.start_coords.x, self.start_coords.y,
+            self.goal_coords.x, self.goal_coords.y,
+        ] {
+            bytes.extend_from_slice(&(value as u32).to_le_bytes());
+        }
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        for cell in self.cells.iter().flatten() {
+            bytes.push(cell.open_mask);
+            bytes.push(cell.flags);
+        }
+        bytes
+    }
+
+    /// Decode a grid from the binary format produced by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<CompactGrid, Error> {
+        let invalid = |reason: &str| Error::InvalidBinaryFormat { reason: reason.to_string() };
+        if bytes.len() < BINARY_HEADER_LEN {
+            return Err(invalid("truncated header"));
+        }
+        if bytes[0..4] != BINARY_MAGIC {
+            return Err(invalid("missing magic header"));
+        }
+        if bytes[4] != BINARY_VERSION {
+            return Err(Error::UnsupportedBinaryVersion { version: bytes[4] });
+        }
+        let maze_type = *MazeType::all().get(bytes[5] as usize).ok_or_else(|| invalid("unknown maze type"))?;
+        let read_u32 = |offset: usize| {
+            let mut word = [0u8; 4];
+            word.copy_from_slice(&bytes[offset..offset + 4]);
+            u32::from_le_bytes(word) as usize
+        };
+        let [width, height, start_x, start_y, goal_x, goal_y] = [6, 10, 14, 18, 22, 26].map(read_u32);
+        let mut seed = [0u8; 8];
+        seed.copy_from_slice(&bytes[30..38]);
+
+        // rebuild the maze geometry, then overlay the encoded walls and state
+        let mut template = Grid::new(
+            maze_type,
+            width,
+            height,
+            Coordinates { x: start_x, y: start_y },
+            Coordinates { x: goal_x, y: goal_y },
+            false,
+        )?;
+        // a Delta maze's first cell tells which way its triangles alternate
+        if maze_type == MazeType::Delta && bytes.get(BINARY_HEADER_LEN + 1).is_some_and(|flags| flags & INVERTED != 0) {
+            template.set_delta_orientation(CellOrientation::Inverted)?;
+        }
+        let mut compact = CompactGrid::from(&template);
+        compact.seed = u64::from_le_bytes(seed);
+        let mut body = bytes[BINARY_HEADER_LEN..].chunks_exact(2);
+        if body.len() != compact.cells.iter().flatten().count() || !body.remainder().is_empty() {
+            return Err(invalid("cell count does not match maze dimensions"));
+        }
+        for (cell, encoded) in compact.cells.iter_mut().flatten().zip(&mut body) {
+            if encoded[0] & !cell.neighbor_mask != 0 {
+                return Err(invalid("open wall without a neighboring cell"));
+            }
+            cell.open_mask = encoded[0];
+            cell.flags = encoded[1];
+            cell.distance = 0;
+        }
+        Ok(compact)
+    }
+
+    /// Approximate number of bytes used by this compact grid.
+    pub fn memory_footprint(&self) -> usize {
+        size_of::<CompactGrid>() + self.cells.capacity() * size_of::<Option<CompactCell>>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::behaviors::display::JsonDisplay;
+
+    fn generated(maze_type: MazeType, algorithm: &str) -> Grid {
+        let json = format!(r#"
+        {{
+            "maze_type": "{:?}",
+            "width": 20,
+            "height": 20,
+            "algorithm": "{}",
+            "start": {{ "x": 0, "y": 0 }},
+            "goal": {{ "x": 18, "y": 18 }}
+        }}
+        "#, maze_type, algorithm);
+        Grid::try_from(json).expect("Unexpected error constructing maze")
+    }
+
+    #[test]
+    fn compact_round_trip_preserves_links() {
+        for maze_type in MazeType::all() {
+            let grid = generated(*maze_type, "Kruskals");
+            let compact = CompactGrid::from(&grid);
+            let restored = Grid::try_from(&compact).expect("Failed to restore grid");
+            assert!(restored.is_perfect_maze().unwrap());
+            for (original, copy) in grid.cells.iter().zip(restored.cells.iter()) {
+                match (original, copy) {
+                    (Some(a), Some(b)) => {
+                        assert_eq!(a.linked, b.linked, "links differ at {:?} for {:?}", a.coords, maze_type);
+                        assert_eq!(a.on_solution_path, b.on_solution_path);
+                        assert_eq!(a.distance, b.distance);
+                        assert_eq!(a.is_active, b.is_active);
+                    }
+                    (None, None) => {}
+                    _ => panic!("cell presence differs for {:?}", maze_type),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn compact_grid_matches_cell_queries() {
+        let grid = generated(MazeType::Orthogonal, "RecursiveBacktracker");
+        let compact = CompactGrid::from(&grid);
+        for cell in grid.iter_cells() {
+            let compact_cell = compact.get(cell.coords).unwrap();
+            for &direction in grid.all_moves() {
+                assert_eq!(compact_cell.is_linked_direction(direction), cell.is_linked_direction(direction));
+            }
+            let mut neighbors = compact.neighbors(cell.coords);
+            neighbors.sort();
+            let mut expected: Vec<Coordinates> = cell.neighbors().into_iter().collect();
+            expected.sort();
+            assert_eq!(neighbors, expected);
+        }
+    }
+
+    #[test]
+    fn binary_round_trip_preserves_maze() {
+        for maze_type in MazeType::all() {
+            let mut grid = generated(*maze_type, "Wilsons");
+            if let Some(direction) = grid.hint() {
+                grid.make_move(direction).unwrap();
+            }
+            let bytes = grid.to_bytes().unwrap();
+            assert_eq!(&bytes[0..4], &BINARY_MAGIC);
+            let restored = Grid::from_bytes(&bytes).expect("Failed to decode grid");
+            assert_eq!((restored.start_coords, restored.goal_coords, restored.seed), (grid.start_coords, grid.goal_coords, grid.seed));
+            for (a, b) in grid.iter_cells().zip(restored.iter_cells()) {
+                assert_eq!(a.linked, b.linked, "links differ at {:?} for {:?}", a.coords, maze_type);
+                assert_eq!(
+                    (a.distance, a.is_active, a.is_visited, a.has_been_visited, a.on_solution_path),
+                    (b.distance, b.is_active, b.is_visited, b.has_been_visited, b.on_solution_path)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn binary_format_rejects_invalid_input() {
+        let bytes = generated(MazeType::Orthogonal, "BinaryTree").to_bytes().unwrap();
+        assert!(matches!(Grid::from_bytes(&bytes[..10]), Err(Error::InvalidBinaryFormat { .. })));
+        assert!(matches!(Grid::from_bytes(&bytes[..bytes.len() - 1]), Err(Error::InvalidBinaryFormat { .. })));
+        let mut wrong_magic = bytes.clone();
+        wrong_magic[0] = b'X';
+        assert!(matches!(Grid::from_bytes(&wrong_magic), Err(Error::InvalidBinaryFormat { .. })));
+        let mut wrong_version = bytes.clone();
+        wrong_version[4] = BINARY_VERSION + 1;
+        assert!(matches!(Grid::from_bytes(&wrong_version), Err(Error::UnsupportedBinaryVersion { .. })));
+        // top-left cell has no neighbor above it
+        let mut bad_wall = bytes.clone();
+        bad_wall[BINARY_HEADER_LEN] |= direction_bit(Direction::Up);
+        assert!(matches!(Grid::from_bytes(&bad_wall), Err(Error::InvalidBinaryFormat { .. })));
+    }
+
+    #[test]
+    fn binary_format_is_compact() {
+        let grid = generated(MazeType::Orthogonal, "Kruskals");
+        assert_eq!(grid.to_bytes().unwrap().len(), BINARY_HEADER_LEN + 2 * 20 * 20);
+        assert!(grid.to_bytes().unwrap().len() * 20 < grid.to_json().unwrap().len());
+    }
+
+    #[test]
+    fn links_and_neighbors_pack_surrounding_positions() {
+        let origin = Coordinates { x: 5, y: 5 };
+        let (above, left, stairs) = (Coordinates { x: 5, y: 4 }, Coordinates { x: 4, y: 5 }, Coordinates { x: 5, y: 15 });
+        let mut links = Links::new(origin);
+        assert!(links.insert(stairs) && links.insert(left) && links.insert(above));
+        assert!(!links.insert(stairs));
+        assert_eq!(links.iter().collect::<Vec<_>>(), vec![above, left, stairs]);
+        assert_eq!((links.len(), links.heap_footprint()), (3, size_of::<Coordinates>()));
+        assert!(links.remove(&stairs) && !links.remove(&stairs) && !links.contains(&stairs));
+        let mut reordered = Links::new(origin);
+        reordered.insert(above);
+        reordered.insert(left);
+        assert_eq!(links, reordered);
+
+        let mut neighbors = Neighbors::new(origin);
+        assert_eq!(neighbors.insert(Direction::Below, stairs), None);
+        assert_eq!(neighbors.insert(Direction::Left, left), None);
+        assert_eq!(neighbors.insert(Direction::Left, above), Some(left));
+        assert_eq!(neighbors.iter().collect::<Vec<_>>(), vec![(Direction::Left, above), (Direction::Below, stairs)]);
+        assert_eq!(neighbors.remove(&Direction::Below), Some(stairs));
+        assert_eq!((neighbors.len(), neighbors.heap_footprint()), (1, 0));
+
+        let walls: DirectionSet = [Direction::Left, Direction::Up, Direction::Left].into_iter().collect();
+        assert_eq!(walls.iter().collect::<Vec<_>>(), vec![Direction::Up, Direction::Left]);
+    }
+
+    #[test]
+    fn cells_store_neighbors_and_links_as_bitmasks() {
+        for maze_type in MazeType::all() {
+            let grid = generated(*maze_type, "Kruskals");
+            // every neighbor of a single layer maze lies around its cell, so nothing is stored on the heap
+            assert!(grid.iter_cells().all(|cell| cell.heap_footprint() == 0), "{:?}", maze_type);
+            for cell in grid.iter_cells() {
+                for (direction, neighbor) in &cell.neighbors_by_direction {
+                    assert_eq!(cell.is_linked(neighbor), cell.open_walls.contains(&direction));
+                }
+            }
+        }
+        // hashed neighbor maps and link sets would take more than their own size again on the heap
+        let hashed = size_of::<HashMap<Direction, Coordinates>>() + size_of::<HashSet<Coordinates>>() + size_of::<Vec<Direction>>();
+        assert!(size_of::<Neighbors>() + size_of::<Links>() + size_of::<DirectionSet>() < hashed);
+    }
+
+    #[test]
+    fn compact_grid_is_an_order_of_magnitude_smaller() {
+        let grid = generated(MazeType::Orthogonal, "RecursiveBacktracker");
+        let compact = CompactGrid::from(&grid);
+        assert!(compact.memory_footprint() * 10 <= grid.memory_footprint());
+    }
+}
@@ -0,0 +1,245 @@
+use alloc::collections::{BTreeSet, VecDeque};
+use rand::seq::SliceRandom;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::cell::Coordinates;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::prelude::*;
+
+/// Passages a generated maze must have and walls it must keep, whatever the algorithm, e.g. a guaranteed
+/// central corridor or a region sealed off from its surroundings. Each passage or wall lies between two
+/// neighboring cells, given in either order.
+///
+/// Rather than seeding each algorithm's own disjoint sets or visited cells, which would need a variant of
+/// every algorithm and has no counterpart in those that build walls, such as Recursive Division, the maze is
+/// generated freely and then reworked around the constraints (see `apply`). The algorithm's character is kept
+/// everywhere but next to the constrained edges, where a few passages are moved. `check` runs before
+/// generation and accepts exactly the constraints `apply` can satisfy, so an accepted request never fails
+/// once generated.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Constraints {
+    /// Pairs of neighboring cells which must be linked.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub passages: Vec<(Coordinates, Coordinates)>,
+    /// Pairs of neighboring cells which must stay walled off from each other.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub walls: Vec<(Coordinates, Coordinates)>,
+}
+
+// The edge between two cells, the same whichever order they are given in
+fn edge((a, b): (Coordinates, Coordinates)) -> (Coordinates, Coordinates) {
+    (a.min(b), a.max(b))
+}
+
+impl Constraints {
+    /// Check that a perfect maze on the grid can satisfy the constraints: every passage and wall lies between
+    /// neighboring cells, no edge is both a passage and a wall, the passages form no loop, and the walls
+    /// leave every cell reachable.
+    pub fn check(&self, grid: &Grid) -> Result<(), Error> {
+        let invalid = |reason: String| Err(Error::InvalidConstraints { reason });
+        for &(a, b) in self.passages.iter().chain(&self.walls) {
+            let adjacent = grid.get(a).is_ok_and(|cell| cell.neighbors_by_direction.values().any(|neighbor| neighbor == b));
+            if !adjacent || grid.get(b).is_err() {
+                return invalid(format!("{} and {} are not neighboring cells of the maze", a, b));
+            }
+        }
+        let walls: BTreeSet<_> = self.walls.iter().copied().map(edge).collect();
+        if let Some(&(a, b)) = self.passages.iter().find(|&&passage| walls.contains(&edge(passage))) {
+            return invalid(format!("the edge between {} and {} is both a passage and a wall", a, b));
+        }
+
+        // passages joining cells already joined by other passages would close a loop
+        let mut joined: Vec<BTreeSet<Coordinates>> = Vec::new();
+        for &(a, b) in &self.passages.iter().copied().map(edge).collect::<BTreeSet<_>>() {
+            let with_a = joined.iter().position(|cells| cells.contains(&a));
+            let with_b = joined.iter().position(|cells| cells.contains(&b));
+            match (with_a, with_b) {
+                (Some(i), Some(j)) if i == j => return invalid(format!("the passages form a loop through {} and {}", a, b)),
+                (Some(i), Some(j)) => {
+                    let merged = joined.swap_remove(i.max(j));
+                    joined[i.min(j)].extend(merged);
+                }
+                (Some(i), None) => { joined[i].insert(b); }
+                (None, Some(j)) => { joined[j].insert(a); }
+                (None, None) => joined.push(BTreeSet::from([a, b])),
+            }
+        }
+
+        let Some(first) = grid.iter_cells().next() else {
+            return Ok(());
+        };
+        let mut reached: HashSet<Coordinates> = HashSet::from([first.coords]);
+        let mut queue: VecDeque<Coordinates> = VecDeque::from([first.coords]);
+        while let Some(coords) = queue.pop_front() {
+            for neighbor in grid.get(coords)?.neighbors_by_direction.values() {
+                if grid.get(neighbor).is_ok() && !walls.contains(&edge((coords, neighbor))) && reached.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        if reached.len() < grid.iter_cells().count() {
+            return invalid("the walls seal some cells off from the rest of the maze".to_string());
+        }
+        Ok(())
+    }
+
+    /// Rework a generated maze to satisfy the constraints, which `check` has accepted, while keeping every cell
+    /// reachable. Each required wall found open is closed and, if that cuts the maze in two, another passage
+    /// is opened to rejoin the halves; each required passage found closed is opened and, to keep the maze
+    /// free of the loop that makes, another passage around the loop is closed. Replacement passages are
+    /// chosen with the grid's random numbers, so the same maze is always reworked the same way.
+    ///
+    /// Constraints accepted by `check` are always satisfied: the edges that are not walls join every cell, so
+    /// some edge across each cut can rejoin the halves, and the passages form no loop, so every loop a passage
+    /// closes has another passage to close.
+    pub fn apply(&self, grid: &mut Grid) -> Result<(), Error> {
+        let mut rng = grid.forked_rng();
+        let walls: BTreeSet<_> = self.walls.iter().copied().map(edge).collect();
+        let passages: BTreeSet<_> = self.passages.iter().copied().map(edge).collect();
+
+        for &(a, b) in &walls {
+            if !grid.get(a)?.linked.contains(&b) {
+                continue;
+            }
+            grid.unlink(a, b)?;
+            let side = grid.all_connected_cells(a);
+            if side.contains(&b) {
+                continue;
+            }
+            let bridges: Vec<(Coordinates, Coordinates)> = side
+                .iter()
+                .flat_map(|&coords| grid.get(coords).into_iter().flat_map(move |cell| cell.neighbors_by_direction.values().map(move |neighbor| (coords, neighbor))))
+                .filter(|&(coords, neighbor)| !side.contains(&neighbor) && grid.get(neighbor).is_ok() && !walls.contains(&edge((coords, neighbor))))
+                .map(edge)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            // there is always a bridge, since `check` found the edges which are not walls join every cell
+            if let Some(&(x, y)) = bridges.choose(&mut rng) {
+                grid.link(x, y)?;
+            }
+        }
+
+        for &(a, b) in &passages {
+            if grid.get(a)?.linked.contains(&b) {
+                continue;
+            }
+            // the route already joining the cells, which the new passage closes into a loop
+            let distances = grid.distance_field(b);
+            let mut route = Vec::new();
+            let mut coords = a;
+            while let Some(&distance) = distances.get(&coords).filter(|&&distance| distance > 0) {
+                let Some(next) = grid.get(coords)?.linked.iter().filter(|linked| distances.get(linked) == Some(&(distance - 1))).min() else {
+                    break;
+                };
+                route.push(edge((coords, next)));
+                coords = next;
+            }
+            grid.link(a, b)?;
+            let removable: Vec<(Coordinates, Coordinates)> = route.into_iter().filter(|passage| !passages.contains(passage)).collect();
+            if let Some(&(x, y)) = removable.choose(&mut rng) {
+                grid.unlink(x, y)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::MazeType;
+
+    fn at(x: usize, y: usize) -> Coordinates {
+        Coordinates { x, y }
+    }
+
+    #[test]
+    fn infeasible_constraints_are_rejected() {
+        let grid = Grid::new(MazeType::Orthogonal, 3, 3, at(0, 0), at(2, 2), false).unwrap();
+        let check = |passages: Vec<_>, walls: Vec<_>| Constraints { passages, walls }.check(&grid);
+        assert!(check(vec![(at(0, 0), at(1, 0))], vec![(at(1, 1), at(1, 2))]).is_ok());
+        assert!(matches!(check(vec![(at(0, 0), at(2, 0))], vec![]), Err(Error::InvalidConstraints { .. })));
+        assert!(matches!(check(vec![], vec![(at(0, 0), at(5, 0))]), Err(Error::InvalidConstraints { .. })));
+        assert!(matches!(check(vec![(at(0, 0), at(1, 0))], vec![(at(1, 0), at(0, 0))]), Err(Error::InvalidConstraints { .. })));
+        let square = vec![(at(0, 0), at(1, 0)), (at(1, 0), at(1, 1)), (at(1, 1), at(0, 1)), (at(0, 1), at(0, 0))];
+        assert!(matches!(check(square, vec![]), Err(Error::InvalidConstraints { .. })));
+        let corner = vec![(at(0, 0), at(1, 0)), (at(0, 0), at(0, 1))];
+        assert!(matches!(check(vec![], corner), Err(Error::InvalidConstraints { .. })));
+    }
+
+    #[test]
+    fn every_algorithm_respects_constraints() {
+        // a corridor along the middle row, with the top row walled off from it except at the far right
+        let passages: Vec<_> = (0..7).map(|x| (at(x, 3), at(x + 1, 3))).collect();
+        let walls: Vec<_> = (0..7).map(|x| (at(x, 0), at(x, 1))).collect();
+        let constraints = Constraints { passages: passages.clone(), walls: walls.clone() };
+        for &algorithm in crate::algorithms::MazeAlgorithm::all() {
+            if !algorithm.supports(MazeType::Orthogonal) {
+                continue;
+            }
+            let json = format!(
+                r#"{{ "maze_type": "Orthogonal", "width": 8, "height": 8, "algorithm": "{:?}", "constraints": {} }}"#,
+                algorithm,
+                serde_json::to_string(&constraints).unwrap(),
+            );
+            let grid = Grid::try_from(json).unwrap();
+            assert!(passages.iter().all(|&(a, b)| grid.get(a).unwrap().linked.contains(&b)), "{:?}", algorithm);
+            assert!(walls.iter().all(|&(a, b)| !grid.get(a).unwrap().linked.contains(&b)), "{:?}", algorithm);
+            assert!(grid.is_perfect_maze().unwrap(), "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn accepted_constraints_never_fail_after_generation() {
+        use crate::algorithms::kruskals::{self, DisjointSet};
+        use crate::algorithms::MazeAlgorithm;
+        use crate::rng::GridRng;
+        use rand::Rng;
+
+        let template = Grid::new(MazeType::Orthogonal, 7, 6, at(0, 0), at(6, 5), false).unwrap();
+        let edges = kruskals::edges(&template);
+        for seed in 0..20 {
+            // random passages and walls, pruned to the passages joining no loop and the walls leaving every cell
+            // reachable
+            let mut rng = GridRng::seed_from_u64(seed);
+            let mut constraints = Constraints::default();
+            for &edge in &edges {
+                match rng.gen_range(0..10) {
+                    0 | 1 => constraints.passages.push(edge),
+                    2..=4 => constraints.walls.push(edge),
+                    _ => {}
+                }
+            }
+            let mut joined = DisjointSet::new();
+            let mut open = DisjointSet::new();
+            for cell in template.iter_cells() {
+                joined.make_set(cell.coords);
+                open.make_set(cell.coords);
+            }
+            constraints.passages.retain(|&(a, b)| joined.union(a, b));
+            let walls: BTreeSet<_> = constraints.walls.iter().copied().collect();
+            for &(a, b) in edges.iter().filter(|edge| !walls.contains(edge)) {
+                open.union(a, b);
+            }
+            constraints.walls.retain(|&(a, b)| !open.union(a, b));
+            assert!(constraints.check(&template).is_ok() && !constraints.walls.is_empty());
+
+            for algorithm in [MazeAlgorithm::RecursiveDivision, MazeAlgorithm::BinaryTree, MazeAlgorithm::Wilsons] {
+                let json = format!(
+                    r#"{{ "maze_type": "Orthogonal", "width": 7, "height": 6, "algorithm": "{:?}", "seed": {}, "constraints": {} }}"#,
+                    algorithm,
+                    seed,
+                    serde_json::to_string(&constraints).unwrap(),
+                );
+                let grid = Grid::try_from(json).unwrap();
+                assert!(constraints.passages.iter().all(|&(a, b)| grid.get(a).unwrap().linked.contains(&b)), "{:?} {}", algorithm, seed);
+                assert!(constraints.walls.iter().all(|&(a, b)| !grid.get(a).unwrap().linked.contains(&b)), "{:?} {}", algorithm, seed);
+                assert!(grid.is_perfect_maze().unwrap(), "{:?} {}", algorithm, seed);
+            }
+        }
+    }
+}
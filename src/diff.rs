@@ -0,0 +1,177 @@
+use alloc::collections::BTreeSet;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::cell::{Cell, Coordinates, MazeType};
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::prelude::*;
+
+/// The state of a cell carried by a `GridDelta`: everything but its passages, which the delta carries as
+/// passages opened and closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CellState {
+    pub coords: Coordinates,
+    pub distance: i32,
+    pub is_start: bool,
+    pub is_goal: bool,
+    pub is_active: bool,
+    pub is_visited: bool,
+    pub has_been_visited: bool,
+    pub on_solution_path: bool,
+    pub is_discovered: bool,
+}
+
+impl From<&Cell> for CellState {
+    fn from(cell: &Cell) -> Self {
+        CellState {
+            coords: cell.coords,
+            distance: cell.distance,
+            is_start: cell.is_start,
+            is_goal: cell.is_goal,
+            is_active: cell.is_active,
+            is_visited: cell.is_visited,
+            has_been_visited: cell.has_been_visited,
+            on_solution_path: cell.on_solution_path,
+            is_discovered: cell.is_discovered,
+        }
+    }
+}
+
+impl CellState {
+    // Overwrite the cell's state with this one
+    pub(crate) fn write_to(&self, cell: &mut Cell) {
+        cell.distance = self.distance;
+        cell.is_start = self.is_start;
+        cell.is_goal = self.is_goal;
+        cell.is_active = self.is_active;
+        cell.is_visited = self.is_visited;
+        cell.has_been_visited = self.has_been_visited;
+        cell.on_solution_path = self.on_solution_path;
+        cell.is_discovered = self.is_discovered;
+    }
+}
+
+/// The changes turning one maze into another of the same type and dimensions, produced by `Grid::diff` and
+/// applied by `Grid::apply`: passages opened and closed, and the new state of each cell whose state changed.
+/// A server can send clients deltas each tick rather than whole mazes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GridDelta {
+    /// Type and dimensions of the mazes, checked when the delta is applied.
+    pub maze_type: MazeType,
+    pub width: usize,
+    pub height: usize,
+    pub layers: usize,
+    /// Passages to open, each pair in order of coordinates.
+    pub linked: Vec<(Coordinates, Coordinates)>,
+    /// Passages to close, each pair in order of coordinates.
+    pub unlinked: Vec<(Coordinates, Coordinates)>,
+    /// New state of each cell whose state changed, in order of coordinates.
+    pub cells: Vec<CellState>,
+}
+
+impl GridDelta {
+    /// Whether applying the delta would change nothing.
+    pub fn is_empty(&self) -> bool {
+        self.linked.is_empty() && self.unlinked.is_empty() && self.cells.is_empty()
+    }
+
+    // Check that the delta fits the grid and only opens passages between neighbors, before any of it is applied
+    pub(crate) fn validate(&self, grid: &Grid) -> Result<(), Error> {
+        check_shape(grid, self.maze_type, self.width, self.height, self.layers)?;
+        let passages = self.linked.iter().chain(&self.unlinked);
+        for &(a, b) in passages {
+            let neighbors = grid.get(a).is_ok_and(|cell| cell.neighbors_by_direction.values().any(|neighbor| neighbor == b));
+            if !neighbors {
+                return Err(Error::InvalidDelta { reason: format!("{:?} and {:?} are not neighboring cells", a, b) });
+            }
+        }
+        if let Some(state) = self.cells.iter().find(|state| !grid.has_cell(state.coords.x, state.coords.y)) {
+            return Err(Error::InvalidDelta { reason: format!("there is no cell at {:?}", state.coords) });
+        }
+        Ok(())
+    }
+}
+
+fn check_shape(grid: &Grid, maze_type: MazeType, width: usize, height: usize, layers: usize) -> Result<(), Error> {
+    if (grid.maze_type, grid.width, grid.height, grid.layers) != (maze_type, width, height, layers) {
+        return Err(Error::InvalidDelta {
+            reason: format!(
+                "a {:?} maze of {}x{}x{} does not fit a {:?} maze of {}x{}x{}",
+                maze_type, width, height, layers, grid.maze_type, grid.width, grid.height, grid.layers
+            ),
+        });
+    }
+    Ok(())
+}
+
+fn passages(grid: &Grid) -> BTreeSet<(Coordinates, Coordinates)> {
+    grid.iter_linked_pairs().map(|(a, b)| (a.coords, b.coords)).collect()
+}
+
+/// The changes turning `from` into `to`, which must be of the same type and dimensions.
+pub fn diff(from: &Grid, to: &Grid) -> Result<GridDelta, Error> {
+    check_shape(from, to.maze_type, to.width, to.height, to.layers)?;
+    let (before, after) = (passages(from), passages(to));
+    let mut cells: Vec<CellState> = to
+        .iter_cells()
+        .map(CellState::from)
+        .filter(|state| !from.get(state.coords).is_ok_and(|cell| CellState::from(cell) == *state))
+        .collect();
+    cells.sort_by_key(|state| state.coords);
+    Ok(GridDelta {
+        maze_type: to.maze_type,
+        width: to.width,
+        height: to.height,
+        layers: to.layers,
+        linked: after.difference(&before).copied().collect(),
+        unlinked: before.difference(&after).copied().collect(),
+        cells,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delta_turns_one_maze_into_another() {
+        let json = r#"{ "maze_type": "Sigma", "width": 12, "height": 10, "algorithm": "Prims", "seed": 1 }"#;
+        let server = Grid::try_from(json).unwrap();
+        let mut client = server.clone();
+        let mut updated = server.clone();
+        let direction = updated.hint().unwrap();
+        updated.make_move(direction).unwrap();
+        // a move changes just the cells moved from and to
+        let moved = server.diff(&updated).unwrap();
+        assert!(moved.linked.is_empty() && moved.unlinked.is_empty());
+        assert_eq!(moved.cells.len(), 2);
+
+        updated.shift_walls(5, 7).unwrap();
+        let delta = server.diff(&updated).unwrap();
+        assert!(!delta.linked.is_empty() && delta.linked.len() == delta.unlinked.len());
+        let delta: GridDelta = serde_json::from_str(&serde_json::to_string(&delta).unwrap()).unwrap();
+
+        let revision = client.revision;
+        client.apply(&delta).unwrap();
+        assert!(client.diff(&updated).unwrap().is_empty());
+        assert_eq!(client.get_active_cell().unwrap().coords, updated.get_active_cell().unwrap().coords);
+        assert!(!client.changed_cells_since(revision).is_empty());
+        assert!(updated.diff(&updated).unwrap().is_empty());
+    }
+
+    #[test]
+    fn mismatched_deltas_are_rejected() {
+        let grid = Grid::try_from(r#"{ "maze_type": "Orthogonal", "width": 4, "height": 4, "algorithm": "Prims" }"#).unwrap();
+        let other = Grid::try_from(r#"{ "maze_type": "Orthogonal", "width": 5, "height": 4, "algorithm": "Prims" }"#).unwrap();
+        assert!(matches!(grid.diff(&other), Err(Error::InvalidDelta { .. })));
+
+        let mut client = grid.clone();
+        let mut delta = grid.diff(&grid).unwrap();
+        delta.linked.push((Coordinates { x: 0, y: 0 }, Coordinates { x: 2, y: 2 }));
+        assert!(matches!(client.apply(&delta), Err(Error::InvalidDelta { .. })));
+        assert_eq!(client.count_edges(), grid.count_edges());
+    }
+}
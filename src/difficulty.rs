@@ -0,0 +1,278 @@
+use core::fmt;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::cell::Coordinates;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::request::MazeRequest;
+use crate::prelude::*;
+
+// Mazes generated for each maze of a series, of which the one scoring closest to its target is kept
+const CANDIDATES_PER_MAZE: usize = 16;
+
+/// Coarse difficulty label derived from a `DifficultyReport` score.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum DifficultyLevel {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl fmt::Display for DifficultyLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            DifficultyLevel::Easy => "Easy",
+            DifficultyLevel::Medium => "Medium",
+            DifficultyLevel::Hard => "Hard",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Structural metrics describing how hard a maze is to solve, along with a normalized 0–100 score.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DifficultyReport {
+    /// Number of cells on the shortest path from start to goal, inclusive of both.
+    pub solution_length: usize,
+    /// Number of cells with exactly one passage.
+    pub dead_ends: usize,
+    /// Number of cells with three or more passages.
+    pub junctions: usize,
+    /// Number of junctions lying on the solution path, i.e. places where a solver must choose.
+    pub decision_points: usize,
+    /// Average number of passages leading off the solution path per solution cell.
+    pub branching_factor: f64,
+    /// Average number of cells in a corridor, a maximal run of cells with exactly two passages.
+    pub average_corridor_length: f64,
+    /// Difficulty normalized to 0 (trivial) through 100 (hardest).
+    pub score: f64,
+    /// Label for the score: Easy below 34, Medium below 67, and Hard otherwise.
+    pub level: DifficultyLevel,
+}
+
+impl DifficultyReport {
+    /// Compute the difficulty metrics of the specified maze.
+    pub fn from_grid(grid: &Grid) -> DifficultyReport {
+        let degree = |coords: &Coordinates| grid.get(*coords).map(|cell| cell.linked.len()).unwrap_or(0);
+        let cells: Vec<Coordinates> = grid.iter_cells().map(|cell| cell.coords).collect();
+        let total_cells = cells.len();
+
+        let solution: HashSet<Coordinates> = grid
+            .get_path_to(grid.start_coords.x, grid.start_coords.y, grid.goal_coords.x, grid.goal_coords.y)
+            .map(|path| path.into_keys().collect())
+            .unwrap_or_default();
+        let solution_length = solution.len();
+
+        let dead_ends = cells.iter().filter(|c| degree(c) == 1).count();
+        let junctions = cells.iter().filter(|c| degree(c) >= 3).count();
+        let decision_points = solution.iter().filter(|c| degree(c) >= 3).count();
+
+        // passages from solution cells into cells off the solution path
+        let branches: usize = solution
+            .iter()
+            .filter_map(|c| grid.get(*c).ok())
+            .map(|cell| cell.linked.iter().filter(|n| !solution.contains(n)).count())
+            .sum();
+        let branching_factor = if solution_length > 0 { branches as f64 / solution_length as f64 } else { 0.0 };
+
+        // group cells with exactly two passages into connected corridors
+        let corridor_cells: HashSet<Coordinates> = cells.iter().copied().filter(|c| degree(c) == 2).collect();
+        let mut seen: HashSet<Coordinates> = HashSet::new();
+        let mut corridors = 0;
+        for &coords in &corridor_cells {
+            if !seen.insert(coords) {
+                continue;
+            }
+            corridors += 1;
+            let mut stack = vec![coords];
+            while let Some(current) = stack.pop() {
+                if let Ok(cell) = grid.get(current) {
+                    for neighbor in &cell.linked {
+                        if corridor_cells.contains(&neighbor) && seen.insert(neighbor) {
+                            stack.push(neighbor);
+                        }
+                    }
+                }
+            }
+        }
+        let average_corridor_length = if corridors > 0 { corridor_cells.len() as f64 / corridors as f64 } else { 0.0 };
+
+        let score = Self::score(total_cells, solution_length, decision_points);
+        let level = match score {
+            s if s < 34.0 => DifficultyLevel::Easy,
+            s if s < 67.0 => DifficultyLevel::Medium,
+            _ => DifficultyLevel::Hard,
+        };
+
+        DifficultyReport {
+            solution_length,
+            dead_ends,
+            junctions,
+            decision_points,
+            branching_factor,
+            average_corridor_length,
+            score,
+            level,
+        }
+    }
+
+    // Scales by absolute solution length, then weighs the share of the maze the solution winds through and
+    // how often the solver must choose between passages along the way.
+    fn score(total_cells: usize, solution_length: usize, decision_points: usize) -> f64 {
+        if total_cells == 0 || solution_length < 2 {
+            return 0.0;
+        }
+        let length_factor = ((solution_length as f64).ln() / 1000f64.ln()).min(1.0);
+        let coverage_factor = (2.0 * solution_length as f64 / total_cells as f64).min(1.0);
+        let decision_factor = (2.0 * decision_points as f64 / solution_length as f64).min(1.0);
+        let score = 100.0 * length_factor * (0.4 + 0.3 * coverage_factor + 0.3 * decision_factor);
+        (score * 10.0).round() / 10.0
+    }
+}
+
+/// How the difficulty scores (see `DifficultyReport`) of a series of mazes ramp up from the first maze to
+/// the last.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DifficultyCurve {
+    /// Target score of the first maze, from 0 through 100.
+    pub from: f64,
+    /// Target score of the last maze, no lower than `from`.
+    pub to: f64,
+    /// Shape of the ramp: 1 rises evenly, above 1 starts gently and steepens toward the end, and below 1
+    /// rises steeply at first.
+    pub exponent: f64,
+}
+
+impl DifficultyCurve {
+    /// A ramp rising evenly from one score to another.
+    pub fn linear(from: f64, to: f64) -> Self {
+        DifficultyCurve { from, to, exponent: 1.0 }
+    }
+
+    /// The score targeted for the maze at the index of a series of `count` mazes.
+    pub fn target(&self, index: usize, count: usize) -> f64 {
+        if count < 2 {
+            return self.from;
+        }
+        let progress = index as f64 / (count - 1) as f64;
+        self.from + (self.to - self.from) * progress.powf(self.exponent)
+    }
+}
+
+/// Generate `count` mazes from the base request whose difficulty scores strictly increase, each as close to
+/// its target on the curve as could be found, returning each maze with its metrics. Since scores depend on the
+/// maze's size and algorithm as well as chance, each maze is chosen from several candidates, re-rolling the
+/// seed for each: when the base request has a seed, candidates are seeded from it in turn, so the series is
+/// repeatable, e.g. as a daily sequence.
+pub fn generate_series(base: &MazeRequest, count: usize, curve: DifficultyCurve) -> Result<Vec<(Grid, DifficultyReport)>, Error> {
+    if !(0.0..=100.0).contains(&curve.from) || !(curve.from..=100.0).contains(&curve.to) || curve.exponent <= 0.0 {
+        return Err(Error::InvalidDifficultyCurve {
+            reason: format!("scores must rise within 0 through 100 with a positive exponent, not {:?}", curve),
+        });
+    }
+    let mut series: Vec<(Grid, DifficultyReport)> = Vec::with_capacity(count);
+    let mut seed = base.seed;
+    for index in 0..count {
+        let target = curve.target(index, count);
+        let previous = series.last().map(|(_, report)| report.score);
+        let mut best: Option<(f64, Grid, DifficultyReport)> = None;
+        for _ in 0..CANDIDATES_PER_MAZE {
+            let request = MazeRequest { seed, ..base.clone() };
+            seed = seed.map(|seed| seed.wrapping_add(1));
+            let grid = Grid::try_from(request)?;
+            let report = DifficultyReport::from_grid(&grid);
+            if previous.is_some_and(|previous| report.score <= previous) {
+                continue;
+            }
+            let distance = (report.score - target).abs();
+            if best.as_ref().is_none_or(|(closest, _, _)| distance < *closest) {
+                best = Some((distance, grid, report));
+            }
+        }
+        let Some((_, grid, report)) = best else {
+            return Err(Error::InvalidDifficultyCurve {
+                reason: format!("no maze scoring above {} was found for maze {} of the series", previous.unwrap_or_default(), index + 1),
+            });
+        };
+        series.push((grid, report));
+    }
+    Ok(series)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::MazeType;
+
+    #[test]
+    fn difficulty_of_straight_corridor() {
+        // a 1-wide corridor has no choices along the way
+        let mut grid = Grid::new(MazeType::Orthogonal, 6, 1, Coordinates { x: 0, y: 0 }, Coordinates { x: 5, y: 0 }, false).unwrap();
+        for x in 0..5 {
+            grid.link(Coordinates { x, y: 0 }, Coordinates { x: x + 1, y: 0 }).unwrap();
+        }
+        let report = DifficultyReport::from_grid(&grid);
+        assert_eq!(report.solution_length, 6);
+        assert_eq!(report.dead_ends, 2);
+        assert_eq!(report.junctions, 0);
+        assert_eq!(report.decision_points, 0);
+        assert_eq!(report.branching_factor, 0.0);
+        assert_eq!(report.average_corridor_length, 4.0);
+        assert_eq!(report.level, DifficultyLevel::Easy);
+    }
+
+    #[test]
+    fn larger_mazes_score_harder() {
+        let request = |size: usize| format!(r#"
+        {{
+            "maze_type": "Orthogonal",
+            "width": {size},
+            "height": {size},
+            "algorithm": "RecursiveBacktracker",
+            "start": {{ "x": 0, "y": 0 }},
+            "goal": {{ "x": {last}, "y": {last} }}
+        }}
+        "#, size = size, last = size - 1);
+        let small = Grid::try_from(request(3)).unwrap().difficulty();
+        let grid = Grid::try_from(request(40)).unwrap();
+        let large = grid.difficulty();
+        let json = serde_json::to_value(&grid).unwrap();
+        assert_eq!(json["difficulty"]["level"], large.level.to_string());
+        assert!(small.score < large.score);
+        assert!((0.0..=100.0).contains(&large.score));
+        assert!(large.dead_ends > 0);
+        assert!(large.average_corridor_length >= 1.0);
+    }
+
+    #[test]
+    fn series_ramp_up() {
+        let base = MazeRequest::builder()
+            .maze_type(MazeType::Orthogonal)
+            .size(12, 12)
+            .algorithm(crate::algorithms::MazeAlgorithm::RecursiveBacktracker)
+            .seed(11)
+            .build()
+            .unwrap();
+        let curve = DifficultyCurve { from: 20.0, to: 60.0, exponent: 2.0 };
+        assert_eq!(curve.target(0, 5), 20.0);
+        assert_eq!(curve.target(2, 5), 30.0);
+        assert_eq!(curve.target(4, 5), 60.0);
+        let series = generate_series(&base, 4, curve).unwrap();
+        assert_eq!(series.len(), 4);
+        for pair in series.windows(2) {
+            assert!(pair[0].1.score < pair[1].1.score);
+        }
+        for (grid, report) in &series {
+            assert_eq!(*report, grid.difficulty());
+        }
+
+        assert!(matches!(generate_series(&base, 3, DifficultyCurve::linear(60.0, 20.0)), Err(Error::InvalidDifficultyCurve { .. })));
+        // a 2x2 maze can't get any harder
+        let tiny = MazeRequest { width: 2, height: 2, ..base };
+        assert!(matches!(generate_series(&tiny, 6, DifficultyCurve::linear(0.0, 100.0)), Err(Error::InvalidDifficultyCurve { .. })));
+    }
+}
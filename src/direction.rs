@@ -1,14 +1,21 @@
+#[cfg(feature = "serde")]
 use serde::{Serialize, Deserialize};
-use std::fmt;
-use std::convert::TryFrom;
+use core::fmt;
+use core::convert::TryFrom;
 use crate::cell::MazeType;
+use crate::prelude::*;
 
 #[repr(u32)]
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Direction {
     // Orthogonal & intercardinal
     Up, Right, Down, Left,
     UpperRight, LowerRight, LowerLeft, UpperLeft,
+    // Layer transitions (stairs) in multi-layer mazes
+    Above, Below,
+    // Polar (circular) mazes: toward and away from the center, and around a ring
+    Inward, Outward, Clockwise, CounterClockwise,
 }
 
 impl fmt::Display for Direction {
@@ -22,6 +29,12 @@ impl fmt::Display for Direction {
             Direction::LowerRight       => "LowerRight",
             Direction::LowerLeft        => "LowerLeft",
             Direction::UpperLeft        => "UpperLeft",
+            Direction::Above            => "Above",
+            Direction::Below            => "Below",
+            Direction::Inward           => "Inward",
+            Direction::Outward          => "Outward",
+            Direction::Clockwise        => "Clockwise",
+            Direction::CounterClockwise => "CounterClockwise",
         };
         write!(f, "{}", s)
     }
@@ -39,6 +52,12 @@ impl TryFrom<&str> for Direction {
             "LowerRight"       => Direction::LowerRight,
             "LowerLeft"        => Direction::LowerLeft,
             "UpperLeft"        => Direction::UpperLeft,
+            "Above"            => Direction::Above,
+            "Below"            => Direction::Below,
+            "Inward"           => Direction::Inward,
+            "Outward"          => Direction::Outward,
+            "Clockwise"        => Direction::Clockwise,
+            "CounterClockwise" => Direction::CounterClockwise,
             other =>
                 return Err(crate::Error::InvalidDirection { direction: other.to_string() }),
         })
@@ -58,6 +77,12 @@ impl TryFrom<u32> for Direction {
             5  => LowerRight,
             6  => LowerLeft,
             7  => UpperLeft,
+            8  => Above,
+            9  => Below,
+            10 => Inward,
+            11 => Outward,
+            12 => Clockwise,
+            13 => CounterClockwise,
             _  => return Err(crate::Error::InvalidDirection { direction: code.to_string() }),
         })
     }
@@ -68,7 +93,7 @@ impl Direction {
     pub fn valid_for(&self, maze_type: MazeType) -> bool {
         use Direction::*;
         match maze_type {
-            MazeType::Orthogonal => matches!(self, Up | Right | Down | Left),
+            MazeType::Orthogonal => matches!(self, Up | Right | Down | Left | Above | Below),
             MazeType::Sigma      => matches!(self, Up | Right | Down | Left | UpperRight | LowerRight | LowerLeft | UpperLeft),
             MazeType::Delta      => matches!(self, Up | Down | UpperLeft | UpperRight | LowerLeft | LowerRight),
             MazeType::Upsilon    => matches!(self, Up | Right | Down | Left | UpperRight | LowerRight | LowerLeft | UpperLeft),
@@ -81,9 +106,22 @@ impl Direction {
         &[
             Up, Right, Down, Left,
             UpperRight, LowerRight, LowerLeft, UpperLeft,
+            Above, Below,
+            Inward, Outward, Clockwise, CounterClockwise,
         ]
     }
 
+    /// Whether the direction leads to another layer of a multi-layer maze, rather than across the current one.
+    pub fn is_layer_transition(&self) -> bool {
+        matches!(self, Direction::Above | Direction::Below)
+    }
+
+    /// Whether the direction is one of a polar maze's, measured from its center rather than across the page.
+    /// No maze type has polar cells yet, so these are valid for none of them.
+    pub fn is_polar(&self) -> bool {
+        matches!(self, Direction::Inward | Direction::Outward | Direction::Clockwise | Direction::CounterClockwise)
+    }
+
     /// Only the six flat-top neighbors for a Sigma (hex) maze.
     pub fn sigma_neighbors() -> &'static [Direction] {
         use Direction::*;
@@ -123,6 +161,12 @@ impl Direction {
             Direction::UpperLeft        => Direction::LowerRight,
             Direction::Right            => Direction::Left,
             Direction::Left             => Direction::Right,
+            Direction::Above            => Direction::Below,
+            Direction::Below            => Direction::Above,
+            Direction::Inward           => Direction::Outward,
+            Direction::Outward          => Direction::Inward,
+            Direction::Clockwise        => Direction::CounterClockwise,
+            Direction::CounterClockwise => Direction::Clockwise,
         }
     }
 
@@ -141,3 +185,74 @@ impl Direction {
     }
 
 }
+
+/// How `Grid::make_move` resolves a move in a direction with no open passage.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MovePolicy {
+    /// Fall back to the adjacent directions, e.g. Left to UpperLeft then LowerLeft, so that Delta and Sigma
+    /// mazes can be played with four arrow keys.
+    #[default]
+    Fallback,
+    /// Move only in exactly the direction requested.
+    Strict,
+    /// Fall back to the listed directions, in order, for each direction listed; other directions are strict.
+    Custom(Vec<(Direction, Vec<Direction>)>),
+}
+
+impl MovePolicy {
+    /// The directions tried, in order, after the requested direction itself.
+    pub fn fallbacks(&self, direction: Direction) -> &[Direction] {
+        use Direction::*;
+        match self {
+            MovePolicy::Fallback => match direction {
+                Left       => &[UpperLeft, LowerLeft],
+                Right      => &[UpperRight, LowerRight],
+                UpperLeft  => &[Up, Left],
+                LowerLeft  => &[Down, Left],
+                UpperRight => &[Up, Right],
+                LowerRight => &[Down, Right],
+                Up         => &[UpperLeft, UpperRight],
+                Down       => &[LowerLeft, LowerRight],
+                _          => &[],
+            },
+            MovePolicy::Strict => &[],
+            MovePolicy::Custom(fallbacks) => fallbacks
+                .iter()
+                .find(|(from, _)| *from == direction)
+                .map(|(_, to)| to.as_slice())
+                .unwrap_or(&[]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_direction_round_trips() {
+        for (code, &direction) in Direction::all().iter().enumerate() {
+            assert_eq!(Direction::try_from(direction.to_string().as_str()).unwrap(), direction);
+            assert_eq!(Direction::try_from(code as u32).unwrap(), direction);
+            assert_eq!(direction as u32, code as u32);
+            assert_eq!(direction.opposite().opposite(), direction);
+            #[cfg(feature = "json")]
+            {
+                let json = serde_json::to_string(&direction).unwrap();
+                assert_eq!(json, format!("\"{}\"", direction));
+                assert_eq!(serde_json::from_str::<Direction>(&json).unwrap(), direction);
+            }
+        }
+        assert!(Direction::try_from(Direction::all().len() as u32).is_err());
+    }
+
+    #[test]
+    fn polar_directions_are_valid_for_no_maze_type() {
+        for &maze_type in MazeType::all() {
+            assert!(Direction::all().iter().filter(|direction| direction.is_polar()).all(|direction| !direction.valid_for(maze_type)));
+        }
+        assert_eq!(Direction::Clockwise.opposite(), Direction::CounterClockwise);
+        assert_eq!(Direction::Inward.opposite(), Direction::Outward);
+    }
+}
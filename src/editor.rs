@@ -0,0 +1,143 @@
+use crate::cell::Coordinates;
+use crate::direction::Direction;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::prelude::*;
+
+/// A wall opened or closed by an edit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WallEdit {
+    /// The cells on either side of the wall.
+    pub between: (Coordinates, Coordinates),
+    /// Whether the wall was opened into a passage, rather than closed.
+    pub opened: bool,
+}
+
+/// A batch of wall edits which takes effect only when committed. Dropping a transaction without committing
+/// it rolls back its edits.
+pub struct EditTransaction<'a> {
+    grid: &'a mut Grid,
+    keep_connected: bool,
+    edits: Vec<WallEdit>,
+    committed: bool,
+}
+
+impl<'a> EditTransaction<'a> {
+    pub(crate) fn new(grid: &'a mut Grid, keep_connected: bool) -> Self {
+        EditTransaction { grid, keep_connected, edits: Vec::new(), committed: false }
+    }
+
+    /// The grid being edited, reflecting the edits made so far.
+    pub fn grid(&self) -> &Grid {
+        self.grid
+    }
+
+    /// Open the wall on the specified side of a cell if it is closed, or close it if it is open, returning
+    /// whether the wall is now open.
+    pub fn toggle_wall(&mut self, coords: Coordinates, direction: Direction) -> Result<bool, Error> {
+        let edit = toggle(self.grid, coords, direction)?;
+        self.edits.push(edit);
+        Ok(edit.opened)
+    }
+
+    /// Apply the edits, failing and rolling them back if the transaction keeps the maze connected and some
+    /// cell could no longer be reached from the start.
+    pub fn commit(mut self) -> Result<Vec<WallEdit>, Error> {
+        if self.keep_connected {
+            let reachable = self.grid.all_connected_cells(self.grid.start_coords).len();
+            let total = self.grid.iter_cells().count();
+            if reachable < total {
+                return Err(Error::InvalidEdit {
+                    reason: format!("the edits would leave {} cells unreachable from the start", total - reachable),
+                });
+            }
+        }
+        self.committed = true;
+        self.grid.refresh_solution();
+        Ok(core::mem::take(&mut self.edits))
+    }
+
+    /// Undo the edits made so far.
+    pub fn rollback(self) {}
+}
+
+impl Drop for EditTransaction<'_> {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+        for edit in self.edits.drain(..).rev() {
+            let (a, b) = edit.between;
+            // reverting an edit which succeeded cannot fail
+            let _ = if edit.opened { self.grid.unlink(a, b) } else { self.grid.link(a, b) };
+        }
+    }
+}
+
+// Toggle the wall between a cell and its neighbor in the specified direction
+fn toggle(grid: &mut Grid, coords: Coordinates, direction: Direction) -> Result<WallEdit, Error> {
+    let cell = grid.get(coords)?;
+    let neighbor = cell.neighbors_by_direction.get(&direction).ok_or_else(|| Error::InvalidEdit {
+        reason: format!("cell {} has no neighbor {:?}", coords, direction),
+    })?;
+    let opened = !cell.linked.contains(&neighbor);
+    if opened {
+        grid.link(coords, neighbor)?;
+    } else {
+        grid.unlink(coords, neighbor)?;
+    }
+    Ok(WallEdit { between: (coords, neighbor), opened })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::MazeType;
+
+    fn generated() -> Grid {
+        Grid::try_from(r#"{ "maze_type": "Orthogonal", "width": 6, "height": 6, "algorithm": "RecursiveBacktracker" }"#).unwrap()
+    }
+
+    #[test]
+    fn toggling_keeps_links_bidirectional() {
+        let mut grid = Grid::new(MazeType::Orthogonal, 2, 2, Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 1 }, false).unwrap();
+        let origin = Coordinates { x: 0, y: 0 };
+        let right = Coordinates { x: 1, y: 0 };
+        assert!(grid.toggle_wall(origin, Direction::Right).unwrap());
+        assert!(grid.get(origin).unwrap().linked.contains(&right));
+        assert!(grid.get(right).unwrap().linked.contains(&origin));
+        assert!(!grid.toggle_wall(right, Direction::Left).unwrap());
+        assert!(grid.get(origin).unwrap().linked.is_empty());
+        assert!(grid.get(right).unwrap().linked.is_empty());
+
+        assert!(matches!(grid.toggle_wall(origin, Direction::Up), Err(Error::InvalidEdit { .. })));
+    }
+
+    #[test]
+    fn disconnecting_transactions_roll_back() {
+        let mut grid = generated();
+        let edges = grid.count_edges();
+        let start = grid.start_coords;
+        let passage = grid.get(start).unwrap().open_walls.iter().next().unwrap();
+
+        let mut edit = grid.edit(true);
+        edit.toggle_wall(start, passage).unwrap();
+        assert!(matches!(edit.commit(), Err(Error::InvalidEdit { .. })));
+        assert_eq!(grid.count_edges(), edges);
+        assert!(grid.get(start).unwrap().open_walls.contains(&passage));
+
+        let mut edit = grid.edit(false);
+        edit.toggle_wall(start, passage).unwrap();
+        assert!(!edit.grid().get(start).unwrap().open_walls.contains(&passage));
+        edit.rollback();
+        assert!(grid.get(start).unwrap().open_walls.contains(&passage));
+
+        // without the connectivity requirement, the edit commits and the solution path is recomputed
+        let mut edit = grid.edit(false);
+        edit.toggle_wall(start, passage).unwrap();
+        assert_eq!(edit.commit().unwrap().len(), 1);
+        assert!(!grid.get(start).unwrap().open_walls.contains(&passage));
+        let solution = grid.solution_path_order();
+        assert!(grid.iter_cells().all(|cell| cell.on_solution_path == solution.contains(&cell.coords)));
+    }
+}
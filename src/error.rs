@@ -1,8 +1,10 @@
-use std::fmt;
+use core::fmt;
+#[cfg(feature = "json")]
 use serde_json;
-use crate::cell::{ Coordinates, MazeType };
+use crate::cell::{ AxialCoordinates, Coordinates, MazeType };
 use crate::direction::Direction;
 use crate::algorithms::MazeAlgorithm;
+use crate::prelude::*;
 
 #[derive(Debug)]
 pub enum Error {
@@ -15,6 +17,8 @@ pub enum Error {
     NoValidNeighbor { coordinates: Coordinates },
     MultipleActiveCells { count: usize },
     NoActiveCells,
+    NoMovesToUndo,
+    NoMovesToRedo,
     InvalidDirection { direction: String },
     MoveUnavailable { attempted_move: Direction, available_moves: Vec<Direction>},
     GridDimensionsExceedLimitForCaptureSteps { width: usize, height: usize },
@@ -22,80 +26,412 @@ pub enum Error {
     InvalidCellCoordinates { coordinates: Coordinates },
     InvalidStartCoordinates { coordinates: Coordinates },
     InvalidGoalCoordinates { coordinates: Coordinates },
+    #[cfg(feature = "json")]
     SerializationError(serde_json::Error),
     EmptyList,
+    InvalidDistanceBandCount { bands: usize },
+    InvalidMazeType { maze_type: String },
+    InvalidMazeAlgorithm { algorithm: String },
+    InvalidBinaryFormat { reason: String },
+    UnsupportedBinaryVersion { version: u8 },
+    InvalidGridDimensions { width: usize, height: usize },
+    InvalidAlgorithmParams { reason: String },
+    InvalidCropRegion { reason: String },
+    InvalidStitch { reason: String },
+    AxialCoordinatesUnavailable { maze_type: MazeType },
+    InvalidAxialCoordinates { coordinates: AxialCoordinates },
+    GeometryUnavailableForMazeType { maze_type: MazeType },
+    InvalidLayers { reason: String },
+    InsufficientFeatureCells { requested: usize, available: usize },
+    InvalidPuzzle { reason: String },
+    DoorLocked { key: Coordinates },
+    InvalidTargetCoordinates { coordinates: Coordinates },
+    InvalidHandle { handle: u64 },
+    StaleHandle { handle: u64 },
+    Cancelled,
+    InvalidEdit { reason: String },
+    InvalidMorph { reason: String },
+    IncompleteRequest { field: &'static str },
+    InvalidMessagePack { reason: String },
+    ImageEncoding { reason: String },
+    NoMoveToward { target: Coordinates },
+    DuplicatePlayer { id: u32 },
+    UnknownPlayer { id: u32 },
+    InvalidConstraints { reason: String },
+    InvalidSymmetry { reason: String },
+    InvalidBorderEdge { reason: String },
+    InvalidWeight { reason: String },
+    InvalidOverlay { reason: String },
+    InvalidDifficultyCurve { reason: String },
+    InvalidRoom { reason: String },
+    InvalidGuardrails { reason: String },
+    InvalidMeshDimensions { reason: String },
+    InvalidTileset { reason: String },
+    InvalidDelta { reason: String },
+    GenerationNotResumable { algorithm: MazeAlgorithm },
+    MazeTooLargeForAlgorithm { algorithm: MazeAlgorithm, cells: usize, max_cells: usize, alternatives: Vec<MazeAlgorithm> },
+    ParallelGenerationUnavailable { algorithm: MazeAlgorithm },
+    InvalidDeltaShape { reason: String },
+    InvalidMask { reason: String },
+}
+
+/// The broad kind of an `Error`, for mapping errors to user interface strings and analytics without matching
+/// every variant.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// The request or operation asked for something invalid, such as coordinates outside the maze or a move
+    /// into a wall; the caller can correct it.
+    InvalidRequest,
+    /// The request is well formed but asks for something the crate does not do, such as an algorithm which
+    /// cannot generate the maze type.
+    Unsupported,
+    /// The maze's state was inconsistent, output could not be produced, or generation was cancelled; nothing
+    /// the caller asked for was wrong.
+    Internal,
+}
+
+impl ErrorCategory {
+    /// A stable number for the category, for FFI clients: 1 for InvalidRequest, 2 for Unsupported and 3 for
+    /// Internal.
+    pub fn code(&self) -> u32 {
+        match self {
+            ErrorCategory::InvalidRequest => 1,
+            ErrorCategory::Unsupported => 2,
+            ErrorCategory::Internal => 3,
+        }
+    }
+}
+
+impl Error {
+    /// A stable number identifying the kind of error, for FFI clients and analytics. Codes are never reused or
+    /// renumbered; new errors take the next unused code.
+    pub fn code(&self) -> u32 {
+        match self {
+            Error::InvalidCellForDeltaMaze { .. } => 1,
+            Error::InvalidCellForNonDeltaMaze { .. } => 2,
+            Error::AlgorithmUnavailableForMazeType { .. } => 3,
+            Error::FlattenedVectorDimensionsMismatch { .. } => 4,
+            Error::OutOfBoundsCoordinates { .. } => 5,
+            Error::MissingCoordinates { .. } => 6,
+            Error::NoValidNeighbor { .. } => 7,
+            Error::MultipleActiveCells { .. } => 8,
+            Error::NoActiveCells => 9,
+            Error::NoMovesToUndo => 10,
+            Error::NoMovesToRedo => 11,
+            Error::InvalidDirection { .. } => 12,
+            Error::MoveUnavailable { .. } => 13,
+            Error::GridDimensionsExceedLimitForCaptureSteps { .. } => 14,
+            Error::NoCellAtCoordinates { .. } => 15,
+            Error::InvalidCellCoordinates { .. } => 16,
+            Error::InvalidStartCoordinates { .. } => 17,
+            Error::InvalidGoalCoordinates { .. } => 18,
+            #[cfg(feature = "json")]
+            Error::SerializationError(_) => 19,
+            Error::EmptyList => 20,
+            Error::InvalidDistanceBandCount { .. } => 21,
+            Error::InvalidMazeType { .. } => 22,
+            Error::InvalidMazeAlgorithm { .. } => 23,
+            Error::InvalidBinaryFormat { .. } => 24,
+            Error::UnsupportedBinaryVersion { .. } => 25,
+            Error::InvalidGridDimensions { .. } => 26,
+            Error::InvalidAlgorithmParams { .. } => 27,
+            Error::InvalidCropRegion { .. } => 28,
+            Error::InvalidStitch { .. } => 29,
+            Error::AxialCoordinatesUnavailable { .. } => 30,
+            Error::InvalidAxialCoordinates { .. } => 31,
+            Error::GeometryUnavailableForMazeType { .. } => 32,
+            Error::InvalidLayers { .. } => 33,
+            Error::InsufficientFeatureCells { .. } => 34,
+            Error::InvalidPuzzle { .. } => 35,
+            Error::DoorLocked { .. } => 36,
+            Error::InvalidTargetCoordinates { .. } => 37,
+            Error::InvalidHandle { .. } => 38,
+            Error::StaleHandle { .. } => 39,
+            Error::Cancelled => 40,
+            Error::InvalidEdit { .. } => 41,
+            Error::InvalidMorph { .. } => 42,
+            Error::IncompleteRequest { .. } => 43,
+            Error::InvalidMessagePack { .. } => 44,
+            Error::ImageEncoding { .. } => 45,
+            Error::NoMoveToward { .. } => 46,
+            Error::DuplicatePlayer { .. } => 47,
+            Error::UnknownPlayer { .. } => 48,
+            Error::InvalidConstraints { .. } => 49,
+            Error::InvalidSymmetry { .. } => 50,
+            Error::InvalidBorderEdge { .. } => 51,
+            Error::InvalidWeight { .. } => 52,
+            Error::InvalidOverlay { .. } => 53,
+            Error::InvalidDifficultyCurve { .. } => 54,
+            Error::InvalidRoom { .. } => 55,
+            Error::InvalidGuardrails { .. } => 56,
+            Error::InvalidMeshDimensions { .. } => 57,
+            Error::InvalidTileset { .. } => 58,
+            Error::InvalidDelta { .. } => 59,
+            Error::GenerationNotResumable { .. } => 60,
+            Error::MazeTooLargeForAlgorithm { .. } => 61,
+            Error::ParallelGenerationUnavailable { .. } => 62,
+            Error::InvalidDeltaShape { .. } => 63,
+            Error::InvalidMask { .. } => 64,
+        }
+    }
+
+    /// The broad kind of error.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Error::AlgorithmUnavailableForMazeType { .. }
+            | Error::GridDimensionsExceedLimitForCaptureSteps { .. }
+            | Error::UnsupportedBinaryVersion { .. }
+            | Error::AxialCoordinatesUnavailable { .. }
+            | Error::GeometryUnavailableForMazeType { .. }
+            | Error::GenerationNotResumable { .. }
+            | Error::MazeTooLargeForAlgorithm { .. }
+            | Error::ParallelGenerationUnavailable { .. } => ErrorCategory::Unsupported,
+            Error::InvalidCellForDeltaMaze { .. }
+            | Error::InvalidCellForNonDeltaMaze { .. }
+            | Error::FlattenedVectorDimensionsMismatch { .. }
+            | Error::NoValidNeighbor { .. }
+            | Error::MultipleActiveCells { .. }
+            | Error::NoActiveCells
+            | Error::EmptyList
+            | Error::Cancelled
+            | Error::ImageEncoding { .. } => ErrorCategory::Internal,
+            // malformed input is the caller's to correct, while failing to write output is not
+            #[cfg(feature = "json")]
+            Error::SerializationError(e) if e.is_io() => ErrorCategory::Internal,
+            _ => ErrorCategory::InvalidRequest,
+        }
+    }
+}
+
+// Coordinates as users read them, "(x, y)"
+struct At<'a>(&'a Coordinates);
+
+impl fmt::Display for At<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.0.x, self.0.y)
+    }
+}
+
+fn names<T>(items: &[T], name: impl Fn(&T) -> String) -> String {
+    items.iter().map(name).collect::<Vec<_>>().join(", ")
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::InvalidCellForDeltaMaze { cell_maze_type } => {
-                write!(f, "Cannot generate non-triangle cells for Delta maze_type {:?}", cell_maze_type)
+                write!(f, "A Delta maze can only hold triangle cells, not {} cells", cell_maze_type.name())
             }
             Error::InvalidCellForNonDeltaMaze { cell_maze_type } => {
-                write!(f, "Cannot generate triangle cells for non-Delta maze_type {:?}", cell_maze_type)
+                write!(f, "Only Delta mazes can hold triangle cells or a triangle orientation, not {} mazes", cell_maze_type.name())
             }
-            Error::AlgorithmUnavailableForMazeType{ algorithm, maze_type } => {
-                write!(f, "MazeAlgorithm {:?} is unavailable for MazeType {:?}", algorithm, maze_type)
+            Error::AlgorithmUnavailableForMazeType { algorithm, maze_type } => {
+                write!(f, "{} cannot generate {} mazes", algorithm.name(), maze_type.name())
             }
             Error::FlattenedVectorDimensionsMismatch { vector_size, maze_width, maze_height } => {
-                write!(f, "Flattened vector size mismatch: expected size {} ({} x {}), but got {}", maze_width * maze_height, maze_width, maze_height, vector_size)
+                write!(f, "Expected {} cells for a {} x {} maze, but found {}", maze_width * maze_height, maze_width, maze_height, vector_size)
             }
             Error::OutOfBoundsCoordinates { coordinates, maze_width, maze_height } => {
-                write!(f, "Out of bounds coordinates: coordinates {:?} exceed maze dimensions {} x {}", coordinates, maze_width, maze_height)
+                write!(f, "Cell {} lies outside the {} x {} maze", At(coordinates), maze_width, maze_height)
             }
             Error::MissingCoordinates { coordinates } => {
-                write!(f, "Missing coordinates: {:?}", coordinates )
+                write!(f, "Cell {} is missing from the maze", At(coordinates))
             }
             Error::NoValidNeighbor { coordinates } => {
-                write!(f, "No valid neighbors: {:?}", coordinates )
+                write!(f, "Cell {} has no neighbor to connect to", At(coordinates))
             }
             Error::MultipleActiveCells { count } => {
-                write!(f, "{:?} active cells, there should only ever be exactly 1 active cell", count )
+                write!(f, "The maze has {} active cells, but should have exactly 1", count)
             }
             Error::NoActiveCells => {
-                write!(f, "No active cells, there should always be exactly 1 active cell" )
+                write!(f, "The maze has no active cell, but should have exactly 1")
+            }
+            Error::NoMovesToUndo => {
+                write!(f, "No moves have been made which can be undone")
+            }
+            Error::NoMovesToRedo => {
+                write!(f, "No undone moves are available to redo")
             }
             Error::MoveUnavailable { attempted_move, available_moves } => {
-                write!(f, "Cannot make move {:?} because it is unavailable. Available moves are: {:?}", attempted_move, available_moves.into_iter().map(|dir| dir.to_string()).collect::<Vec<_>>().join(", ") )
+                write!(f, "Cannot move {}; available moves are: {}", attempted_move, names(available_moves, |direction| direction.to_string()))
             }
             Error::InvalidDirection { direction } => {
-                write!(f, "Invalid Direction: {:?}", direction )
+                write!(f, "\"{}\" is not a direction", direction)
             }
-            Error::GridDimensionsExceedLimitForCaptureSteps  { width, height } => {
-                write!(f, "Grid dimensions {:?},{:?} exceed limit for enabling capture_steps. Neither width nor height can exceed 100 for this feature to be enabled", width, height )
+            Error::GridDimensionsExceedLimitForCaptureSteps { width, height } => {
+                write!(f, "Cannot capture generation steps of a {} x {} maze; neither width nor height can exceed 100", width, height)
             }
-            Error::NoCellAtCoordinates{ coordinates } => {
-                write!(f, "No cell exists at coordinates {:?}", coordinates )
+            Error::NoCellAtCoordinates { coordinates } => {
+                write!(f, "There is no cell at {}", At(coordinates))
             }
-            Error::InvalidCellCoordinates{ coordinates } => {
-                write!(f, "Invalid cell coordinates {:?}", coordinates )
+            Error::InvalidCellCoordinates { coordinates } => {
+                write!(f, "{} is not a cell of the maze", At(coordinates))
             }
-            Error::InvalidStartCoordinates{ coordinates } => {
-                write!(f, "Invalid start coordinates {:?}", coordinates )
+            Error::InvalidStartCoordinates { coordinates } => {
+                write!(f, "The start {} is not a cell of the maze", At(coordinates))
             }
-            Error::InvalidGoalCoordinates{ coordinates } => {
-                write!(f, "Invalid goal coordinates {:?}", coordinates )
+            Error::InvalidGoalCoordinates { coordinates } => {
+                write!(f, "The goal {} is not a cell of the maze", At(coordinates))
             }
+            #[cfg(feature = "json")]
             Error::SerializationError(e) => {
-                write!(f, "Serialization error: {}", e)
+                write!(f, "Invalid JSON: {}", e)
             }
             Error::EmptyList => {
                 write!(f, "Attempted operation on an empty list")
-            } 
+            }
+            Error::InvalidDistanceBandCount { bands } => {
+                write!(f, "Cannot split the maze into {} distance bands; at least 1 band is required", bands)
+            }
+            Error::InvalidMazeType { maze_type } => {
+                write!(f, "\"{}\" is not a maze type", maze_type)
+            }
+            Error::InvalidMazeAlgorithm { algorithm } => {
+                write!(f, "\"{}\" is not a maze algorithm", algorithm)
+            }
+            Error::InvalidBinaryFormat { reason } => {
+                write!(f, "Invalid binary maze data: {}", reason)
+            }
+            Error::UnsupportedBinaryVersion { version } => {
+                write!(f, "Binary maze format version {} is not supported", version)
+            }
+            Error::InvalidGridDimensions { width, height } => {
+                write!(f, "A maze cannot be {} x {}; both width and height must be at least 1", width, height)
+            }
+            Error::InvalidAlgorithmParams { reason } => {
+                write!(f, "Invalid algorithm parameters: {}", reason)
+            }
+            Error::InvalidCropRegion { reason } => {
+                write!(f, "Invalid crop region: {}", reason)
+            }
+            Error::InvalidStitch { reason } => {
+                write!(f, "Cannot stitch mazes: {}", reason)
+            }
+            Error::AxialCoordinatesUnavailable { maze_type } => {
+                write!(f, "Axial coordinates are only available for Sigma mazes, not {} mazes", maze_type.name())
+            }
+            Error::InvalidAxialCoordinates { coordinates } => {
+                write!(f, "There is no cell at axial coordinates ({}, {})", coordinates.q, coordinates.r)
+            }
+            Error::GeometryUnavailableForMazeType { maze_type } => {
+                write!(f, "Render geometry is not available for {} mazes", maze_type.name())
+            }
+            Error::InvalidLayers { reason } => {
+                write!(f, "Invalid maze layers: {}", reason)
+            }
+            Error::InsufficientFeatureCells { requested, available } => {
+                write!(f, "Cannot place {} features; only {} cells satisfy the constraints", requested, available)
+            }
+            Error::InvalidPuzzle { reason } => {
+                write!(f, "Cannot create puzzle: {}", reason)
+            }
+            Error::DoorLocked { key } => {
+                write!(f, "The door is locked until the key at {} is collected", At(key))
+            }
+            Error::InvalidTargetCoordinates { coordinates } => {
+                write!(f, "The target {} is not a cell of the maze", At(coordinates))
+            }
+            Error::InvalidHandle { handle } => {
+                write!(f, "Maze handle {} was never issued", handle)
+            }
+            Error::StaleHandle { handle } => {
+                write!(f, "Maze handle {} refers to a maze which has been destroyed", handle)
+            }
+            Error::Cancelled => {
+                write!(f, "Maze generation was cancelled")
+            }
+            Error::InvalidEdit { reason } => {
+                write!(f, "Invalid maze edit: {}", reason)
+            }
+            Error::InvalidMorph { reason } => {
+                write!(f, "Cannot morph maze: {}", reason)
+            }
+            Error::IncompleteRequest { field } => {
+                write!(f, "Maze request is missing its {}", field)
+            }
+            Error::InvalidMessagePack { reason } => {
+                write!(f, "Invalid MessagePack data: {}", reason)
+            }
+            Error::ImageEncoding { reason } => {
+                write!(f, "Cannot encode image: {}", reason)
+            }
+            Error::NoMoveToward { target } => {
+                write!(f, "No open passage from the active cell leads toward {}", At(target))
+            }
+            Error::DuplicatePlayer { id } => {
+                write!(f, "Player {} is already in the maze", id)
+            }
+            Error::UnknownPlayer { id } => {
+                write!(f, "Player {} is not in the maze", id)
+            }
+            Error::InvalidConstraints { reason } => {
+                write!(f, "Invalid maze constraints: {}", reason)
+            }
+            Error::InvalidSymmetry { reason } => {
+                write!(f, "Invalid maze symmetry: {}", reason)
+            }
+            Error::InvalidBorderEdge { reason } => {
+                write!(f, "Invalid border edge: {}", reason)
+            }
+            Error::InvalidWeight { reason } => {
+                write!(f, "Invalid weight: {}", reason)
+            }
+            Error::InvalidOverlay { reason } => {
+                write!(f, "Invalid maze overlay: {}", reason)
+            }
+            Error::InvalidDifficultyCurve { reason } => {
+                write!(f, "Invalid difficulty curve: {}", reason)
+            }
+            Error::InvalidRoom { reason } => {
+                write!(f, "Invalid room: {}", reason)
+            }
+            Error::InvalidGuardrails { reason } => {
+                write!(f, "Invalid guardrails: {}", reason)
+            }
+            Error::InvalidMeshDimensions { reason } => {
+                write!(f, "Invalid mesh dimensions: {}", reason)
+            }
+            Error::InvalidTileset { reason } => {
+                write!(f, "Invalid tileset mapping: {}", reason)
+            }
+            Error::InvalidDelta { reason } => {
+                write!(f, "Invalid grid delta: {}", reason)
+            }
+            Error::GenerationNotResumable { algorithm } => {
+                write!(f, "{} cannot be generated in parts", algorithm.name())
+            }
+            Error::MazeTooLargeForAlgorithm { algorithm, cells, max_cells, alternatives } => {
+                write!(f, "{} cannot generate {} cells in reasonable time (at most {})", algorithm.name(), cells, max_cells)?;
+                if alternatives.is_empty() {
+                    write!(f, "; request a smaller maze")
+                } else {
+                    write!(f, "; try {} or request a smaller maze", names(alternatives, |algorithm| algorithm.name().to_string()))
+                }
+            }
+            Error::ParallelGenerationUnavailable { algorithm } => {
+                write!(f, "{} cannot be generated in parallel; request Kruskals instead", algorithm.name())
+            }
+            Error::InvalidDeltaShape { reason } => {
+                write!(f, "Invalid Delta shape: {}", reason)
+            }
+            Error::InvalidMask { reason } => {
+                write!(f, "Invalid mask: {}", reason)
+            }
         }
     }
 }
 
-impl std::error::Error for Error {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
         match self {
+            #[cfg(feature = "json")]
             Error::SerializationError(e) => Some(e), // Return a reference to the error
             _ => None,
         }
     }
 }
 
+#[cfg(feature = "json")]
 impl From<serde_json::Error> for Error {
     fn from(e: serde_json::Error) -> Self {
         Error::SerializationError(e)
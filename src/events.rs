@@ -0,0 +1,52 @@
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::cell::Coordinates;
+use crate::prelude::*;
+
+/// A change to the maze's passages or to the user's progress through it, logged for `Grid::drain_events` so
+/// that user interfaces can update just what changed rather than comparing every cell after each operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GridEvent {
+    /// A passage was opened between two neighboring cells.
+    CellLinked { a: Coordinates, b: Coordinates },
+    /// The passage between two neighboring cells was closed.
+    CellUnlinked { a: Coordinates, b: Coordinates },
+    /// The user's active cell moved from one cell to another.
+    ActiveMoved { from: Coordinates, to: Coordinates },
+    /// A cell joined the user's current trail, or left it by backtracking (see `Cell::is_visited`).
+    VisitedChanged { coords: Coordinates, is_visited: bool },
+}
+
+/// What a generation algorithm was doing when a generation step was captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GenerationPhase {
+    /// The grid before the algorithm carved or walled anything.
+    Start,
+    /// Passages were carved, as by most algorithms.
+    Carve,
+    /// Walls were built across passages, as by Recursive Division.
+    Wall,
+    /// Hunt and Kill carved onward from the cell it last carved into.
+    Kill,
+    /// Hunt and Kill found an uncarved cell beside the carved ones and carved into it.
+    Hunt,
+}
+
+/// What happened in a captured generation step, recorded alongside the grid in `Grid::generation_steps` at the
+/// same index in `Grid::generation_step_details`, so that animations can highlight the passages changed and
+/// the cells the algorithm is still working from rather than comparing whole grids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GenerationStep {
+    pub phase: GenerationPhase,
+    /// Passages opened since the previous step, each pair in order of coordinates.
+    pub linked: Vec<(Coordinates, Coordinates)>,
+    /// Passages closed since the previous step, each pair in order of coordinates.
+    pub unlinked: Vec<(Coordinates, Coordinates)>,
+    /// The cells the algorithm will carve from or into next: Recursive Backtracker's path from its first cell,
+    /// or the frontier of Prim's and Hunt and Kill in order of coordinates. Empty for other algorithms.
+    pub frontier: Vec<Coordinates>,
+}
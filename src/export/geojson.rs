@@ -0,0 +1,76 @@
+use core::fmt::Write;
+
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::render::{self, svg, Point};
+use crate::prelude::*;
+
+// A point as a GeoJSON position. Render space has y increasing downward, so y is negated to keep the maze's
+// top row at the top of a map (and subtracted from zero so the top edge is not written as -0)
+fn position(point: Point) -> String {
+    format!("[{},{}]", point.x, 0.0 - point.y)
+}
+
+/// GeoJSON FeatureCollection of the maze: a Polygon feature for each cell, with the cell's coordinates and
+/// state as properties, and a LineString feature for each wall segment. Features carry a `kind` property of
+/// `"cell"` or `"wall"` to tell them apart. `cell_size` is interpreted as by the render module for the grid's
+/// maze type, and y is negated so that the maze keeps its orientation in mapping tools, where y increases
+/// upward.
+pub fn geojson(grid: &Grid, cell_size: f64) -> Result<String, Error> {
+    let polygons = render::polygons(grid, cell_size)?;
+    let mut features = Vec::new();
+    for polygon in &polygons {
+        let cell = grid.get(polygon.coords)?;
+        let ring: Vec<String> = polygon.vertices.iter().chain(polygon.vertices.first()).copied().map(position).collect();
+        let mut properties = format!(r#""kind":"cell","x":{},"y":{}"#, cell.coords.x, cell.coords.y);
+        // writing to a String cannot fail
+        let _ = write!(
+            properties,
+            r#","is_start":{},"is_goal":{},"is_visited":{},"has_been_visited":{},"on_solution_path":{},"distance":{}"#,
+            cell.is_start, cell.is_goal, cell.is_visited, cell.has_been_visited, cell.on_solution_path, cell.distance,
+        );
+        features.push(format!(
+            r#"{{"type":"Feature","geometry":{{"type":"Polygon","coordinates":[[{}]]}},"properties":{{{}}}}}"#,
+            ring.join(","),
+            properties,
+        ));
+    }
+    for (a, b) in svg::walls(grid, &polygons) {
+        features.push(format!(
+            r#"{{"type":"Feature","geometry":{{"type":"LineString","coordinates":[{},{}]}},"properties":{{"kind":"wall"}}}}"#,
+            position(a),
+            position(b),
+        ));
+    }
+    Ok(format!(r#"{{"type":"FeatureCollection","features":[{}]}}"#, features.join(",")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn features_for_every_cell_and_wall() {
+        for maze_type in ["Orthogonal", "Delta", "Sigma"] {
+            let json = format!(r#"{{ "maze_type": "{}", "width": 6, "height": 5, "algorithm": "Prims" }}"#, maze_type);
+            let grid = Grid::try_from(json).unwrap();
+            let collection: serde_json::Value = serde_json::from_str(&geojson(&grid, 10.0).unwrap()).unwrap();
+            assert_eq!(collection["type"], "FeatureCollection");
+            let features = collection["features"].as_array().unwrap();
+            let kind = |kind: &str| features.iter().filter(|feature| feature["properties"]["kind"] == kind).collect::<Vec<_>>();
+
+            let cells = kind("cell");
+            assert_eq!(cells.len(), grid.iter_cells().count(), "{}", maze_type);
+            for cell in &cells {
+                let ring = cell["geometry"]["coordinates"][0].as_array().unwrap();
+                assert_eq!(ring.first(), ring.last(), "rings are closed");
+            }
+            let solution = cells.iter().filter(|cell| cell["properties"]["on_solution_path"] == true).count();
+            assert_eq!(solution, grid.solution_path_order().len());
+            assert_eq!(cells.iter().filter(|cell| cell["properties"]["is_start"] == true).count(), 1);
+
+            let polygons = render::polygons(&grid, 10.0).unwrap();
+            assert_eq!(kind("wall").len(), svg::walls(&grid, &polygons).len(), "{}", maze_type);
+        }
+    }
+}
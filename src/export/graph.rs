@@ -0,0 +1,132 @@
+use core::fmt::Write;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::cell::Coordinates;
+use crate::grid::Grid;
+use crate::prelude::*;
+
+/// A cell of the maze as a node of its passage graph, with the cells it is linked to.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct GraphNode {
+    pub coords: Coordinates,
+    pub is_start: bool,
+    pub is_goal: bool,
+    pub on_solution_path: bool,
+    /// Distance from the start cell.
+    pub distance: i32,
+    /// Linked (passable) neighbors, sorted by coordinates.
+    pub neighbors: Vec<Coordinates>,
+}
+
+fn node_id(coords: Coordinates) -> String {
+    format!("\"{},{}\"", coords.x, coords.y)
+}
+
+/// Every cell of the maze with its linked neighbors, sorted by coordinates.
+pub fn adjacency_list(grid: &Grid) -> Vec<GraphNode> {
+    let mut nodes: Vec<GraphNode> = grid
+        .cells
+        .iter()
+        .flatten()
+        .map(|cell| {
+            let mut neighbors: Vec<Coordinates> = cell.linked.iter().collect();
+            neighbors.sort();
+            GraphNode {
+                coords: cell.coords,
+                is_start: cell.is_start,
+                is_goal: cell.is_goal,
+                on_solution_path: cell.on_solution_path,
+                distance: cell.distance,
+                neighbors,
+            }
+        })
+        .collect();
+    nodes.sort_by_key(|node| node.coords);
+    nodes
+}
+
+/// Graphviz DOT description of the maze's passage graph. Nodes are named "x,y" and carry `start`, `goal`,
+/// `solution` and `distance` attributes, plus a `pos` so that `neato -n` lays them out like the grid.
+pub fn dot(grid: &Grid) -> String {
+    let nodes = adjacency_list(grid);
+    let mut dot = String::from("graph maze {\n    node [shape=box];\n");
+    for node in &nodes {
+        let mut attributes = vec![
+            format!("pos=\"{},{}!\"", node.coords.x, grid.height - 1 - node.coords.y),
+            format!("distance={}", node.distance),
+        ];
+        if node.is_start {
+            attributes.push("start=true".to_string());
+            attributes.push("color=green".to_string());
+        }
+        if node.is_goal {
+            attributes.push("goal=true".to_string());
+            attributes.push("color=red".to_string());
+        }
+        if node.on_solution_path {
+            attributes.push("solution=true".to_string());
+            attributes.push("style=filled".to_string());
+        }
+        let _ = writeln!(dot, "    {} [{}];", node_id(node.coords), attributes.join(", "));
+    }
+    for node in &nodes {
+        // each passage is listed once, from its lesser cell
+        for neighbor in node.neighbors.iter().filter(|n| **n > node.coords) {
+            let _ = writeln!(dot, "    {} -- {};", node_id(node.coords), node_id(*neighbor));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::MazeType;
+
+    fn corridor() -> Grid {
+        let mut grid = Grid::new(MazeType::Orthogonal, 3, 1, Coordinates { x: 0, y: 0 }, Coordinates { x: 2, y: 0 }, false).unwrap();
+        grid.link(Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 0 }).unwrap();
+        grid.link(Coordinates { x: 1, y: 0 }, Coordinates { x: 2, y: 0 }).unwrap();
+        grid
+    }
+
+    #[test]
+    fn adjacency_list_of_corridor() {
+        let nodes = adjacency_list(&corridor());
+        assert_eq!(nodes.len(), 3);
+        assert!(nodes[0].is_start && nodes[2].is_goal);
+        assert_eq!(nodes[1].neighbors, vec![Coordinates { x: 0, y: 0 }, Coordinates { x: 2, y: 0 }]);
+    }
+
+    #[test]
+    fn dot_lists_each_passage_once() {
+        let dot = dot(&corridor());
+        assert!(dot.starts_with("graph maze {"));
+        assert!(dot.contains("\"0,0\" -- \"1,0\";"));
+        assert!(dot.contains("\"1,0\" -- \"2,0\";"));
+        assert!(!dot.contains("\"1,0\" -- \"0,0\";"));
+        assert_eq!(dot.matches(" -- ").count(), 2);
+        assert!(dot.contains("start=true"));
+        assert!(dot.contains("goal=true"));
+    }
+
+    #[test]
+    fn generated_maze_exports_every_edge() {
+        let json = r#"
+        {
+            "maze_type": "Sigma",
+            "width": 8,
+            "height": 8,
+            "algorithm": "Prims"
+        }
+        "#;
+        let grid = Grid::try_from(json).unwrap();
+        assert_eq!(dot(&grid).matches(" -- ").count(), grid.count_edges());
+        assert_eq!(dot(&grid).matches("solution=true").count(), grid.iter_cells().filter(|c| c.on_solution_path).count());
+        let adjacency = adjacency_list(&grid);
+        assert_eq!(adjacency.iter().map(|node| node.neighbors.len()).sum::<usize>(), 2 * grid.count_edges());
+    }
+}
@@ -0,0 +1,200 @@
+use core::fmt::Write;
+
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::render::{self, svg};
+use crate::prelude::*;
+
+/// Dimensions of the walls extruded by `extrude_walls`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshOptions {
+    /// Size of each cell, as interpreted by the render module for the maze's type.
+    pub cell_size: f64,
+    pub wall_height: f64,
+    pub wall_thickness: f64,
+}
+
+impl Default for MeshOptions {
+    fn default() -> Self {
+        MeshOptions { cell_size: 1.0, wall_height: 1.0, wall_thickness: 0.1 }
+    }
+}
+
+/// A triangle mesh, with y up and the maze lying on the x-z plane, its first row toward -z.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Mesh {
+    pub vertices: Vec<[f64; 3]>,
+    /// Indices into `vertices`, counterclockwise when seen from outside the mesh.
+    pub triangles: Vec<[u32; 3]>,
+}
+
+impl Mesh {
+    // Add a quad as two triangles, wound to face away from the center of the solid it bounds
+    fn push_quad(&mut self, corners: [[f64; 3]; 4], center: [f64; 3]) {
+        let sub = |a: [f64; 3], b: [f64; 3]| [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+        let (u, v) = (sub(corners[1], corners[0]), sub(corners[2], corners[0]));
+        let normal = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+        let middle = [0, 1, 2].map(|axis| corners.iter().map(|corner| corner[axis]).sum::<f64>() / 4.0);
+        let outward = sub(middle, center);
+        let mut corners = corners;
+        if normal[0] * outward[0] + normal[1] * outward[1] + normal[2] * outward[2] < 0.0 {
+            corners.reverse();
+        }
+        let first = self.vertices.len() as u32;
+        self.vertices.extend(corners);
+        self.triangles.push([first, first + 1, first + 2]);
+        self.triangles.push([first, first + 2, first + 3]);
+    }
+
+    /// Wavefront OBJ text of the mesh.
+    pub fn to_obj(&self) -> String {
+        let mut obj = String::from("# maze walls\no maze\n");
+        // writing to a String cannot fail
+        for [x, y, z] in &self.vertices {
+            let _ = writeln!(obj, "v {} {} {}", x, y, z);
+        }
+        for [a, b, c] in &self.triangles {
+            // OBJ indices count from 1
+            let _ = writeln!(obj, "f {} {} {}", a + 1, b + 1, c + 1);
+        }
+        obj
+    }
+
+    /// Self-contained glTF 2.0 JSON of the mesh, with its vertices and indices embedded as a base64 buffer.
+    pub fn to_gltf(&self) -> String {
+        let mut buffer = Vec::with_capacity(12 * (self.vertices.len() + self.triangles.len()));
+        for vertex in &self.vertices {
+            for coordinate in vertex {
+                buffer.extend_from_slice(&(*coordinate as f32).to_le_bytes());
+            }
+        }
+        let positions_length = buffer.len();
+        for index in self.triangles.iter().flatten() {
+            buffer.extend_from_slice(&index.to_le_bytes());
+        }
+        let bound = |pick: fn(f32, f32) -> f32, start: f32| {
+            [0, 1, 2].map(|axis| self.vertices.iter().map(|vertex| vertex[axis] as f32).fold(start, pick))
+        };
+        let (min, max) = (bound(f32::min, f32::INFINITY), bound(f32::max, f32::NEG_INFINITY));
+        format!(
+            concat!(
+                r#"{{"asset":{{"version":"2.0","generator":"mazer"}},"scene":0,"scenes":[{{"nodes":[0]}}],"nodes":[{{"mesh":0}}],"#,
+                r#""meshes":[{{"primitives":[{{"attributes":{{"POSITION":0}},"indices":1}}]}}],"#,
+                r#""accessors":[{{"bufferView":0,"componentType":5126,"count":{},"type":"VEC3","min":[{},{},{}],"max":[{},{},{}]}},"#,
+                r#"{{"bufferView":1,"componentType":5125,"count":{},"type":"SCALAR"}}],"#,
+                r#""bufferViews":[{{"buffer":0,"byteOffset":0,"byteLength":{},"target":34962}},"#,
+                r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":34963}}],"#,
+                r#""buffers":[{{"byteLength":{},"uri":"data:application/octet-stream;base64,{}"}}]}}"#,
+            ),
+            self.vertices.len(), min[0], min[1], min[2], max[0], max[1], max[2],
+            3 * self.triangles.len(),
+            positions_length,
+            positions_length, buffer.len() - positions_length,
+            buffer.len(), base64(&buffer),
+        )
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let word = chunk.iter().enumerate().fold(0u32, |word, (i, &byte)| word | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(word >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Extrude every wall of the maze (see `svg::walls`) into a box standing on the x-z plane, for dropping the
+/// maze into a game engine or 3D modelling tool. Each box runs half the wall thickness past both ends of its
+/// wall, so that walls meeting at a corner overlap rather than leave a gap.
+pub fn extrude_walls(grid: &Grid, options: &MeshOptions) -> Result<Mesh, Error> {
+    if options.wall_height <= 0.0 || options.wall_thickness <= 0.0 {
+        return Err(Error::InvalidMeshDimensions {
+            reason: format!("walls must have positive height and thickness, not {} and {}", options.wall_height, options.wall_thickness),
+        });
+    }
+    let polygons = render::polygons(grid, options.cell_size)?;
+    let half = options.wall_thickness / 2.0;
+    let mut mesh = Mesh::default();
+    for (a, b) in svg::walls(grid, &polygons) {
+        let length = ((b.x - a.x).powi(2) + (b.y - a.y).powi(2)).sqrt();
+        let (dx, dy) = ((b.x - a.x) / length * half, (b.y - a.y) / length * half);
+        let footprint = [
+            (a.x - dx - dy, a.y - dy + dx),
+            (b.x + dx - dy, b.y + dy + dx),
+            (b.x + dx + dy, b.y + dy - dx),
+            (a.x - dx + dy, a.y - dy - dx),
+        ];
+        let bottom = footprint.map(|(x, z)| [x, 0.0, z]);
+        let top = footprint.map(|(x, z)| [x, options.wall_height, z]);
+        let center = [(a.x + b.x) / 2.0, options.wall_height / 2.0, (a.y + b.y) / 2.0];
+        mesh.push_quad(bottom, center);
+        mesh.push_quad(top, center);
+        for side in 0..4 {
+            let next = (side + 1) % 4;
+            mesh.push_quad([bottom[side], bottom[next], top[next], top[side]], center);
+        }
+    }
+    Ok(mesh)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Volume enclosed by the mesh, positive when every triangle faces outward
+    fn signed_volume(mesh: &Mesh) -> f64 {
+        mesh.triangles
+            .iter()
+            .map(|&[a, b, c]| {
+                let [a, b, c] = [a, b, c].map(|index| mesh.vertices[index as usize]);
+                (a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0]) + a[2] * (b[0] * c[1] - b[1] * c[0])) / 6.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn walls_extrude_to_closed_boxes() {
+        let grid = Grid::try_from(r#"{ "maze_type": "Orthogonal", "width": 5, "height": 4, "algorithm": "Prims" }"#).unwrap();
+        let options = MeshOptions { cell_size: 2.0, wall_height: 3.0, wall_thickness: 0.5 };
+        let mesh = extrude_walls(&grid, &options).unwrap();
+        let walls = svg::walls(&grid, &render::polygons(&grid, 2.0).unwrap()).len();
+        assert_eq!(mesh.triangles.len(), 12 * walls);
+        // every box is 2.5 long with the overhang at both ends, 0.5 thick and 3 high
+        let expected = walls as f64 * 2.5 * 0.5 * 3.0;
+        assert!((signed_volume(&mesh) - expected).abs() < 1e-6, "{} != {}", signed_volume(&mesh), expected);
+
+        let obj = mesh.to_obj();
+        assert_eq!(obj.lines().filter(|line| line.starts_with("v ")).count(), mesh.vertices.len());
+        assert_eq!(obj.lines().filter(|line| line.starts_with("f ")).count(), mesh.triangles.len());
+
+        let gltf: serde_json::Value = serde_json::from_str(&mesh.to_gltf()).unwrap();
+        assert_eq!(gltf["accessors"][0]["count"], mesh.vertices.len());
+        assert_eq!(gltf["accessors"][0]["max"][1], 3.0);
+        let byte_length = 12 * (mesh.vertices.len() + mesh.triangles.len());
+        assert_eq!(gltf["buffers"][0]["byteLength"], byte_length);
+        let uri = gltf["buffers"][0]["uri"].as_str().unwrap();
+        assert_eq!(uri.trim_start_matches("data:application/octet-stream;base64,").len(), byte_length.div_ceil(3) * 4);
+
+        let sigma = Grid::try_from(r#"{ "maze_type": "Sigma", "width": 5, "height": 4, "algorithm": "Prims" }"#).unwrap();
+        assert!(signed_volume(&extrude_walls(&sigma, &MeshOptions::default()).unwrap()) > 0.0);
+        let flat = MeshOptions { wall_height: 0.0, ..MeshOptions::default() };
+        assert!(matches!(extrude_walls(&grid, &flat), Err(Error::InvalidMeshDimensions { .. })));
+    }
+
+    #[test]
+    fn base64_pads_partial_chunks() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+}
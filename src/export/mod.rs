@@ -0,0 +1,4 @@
+pub mod geojson;
+pub mod graph;
+pub mod mesh;
+pub mod tilemap;
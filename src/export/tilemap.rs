@@ -0,0 +1,176 @@
+use core::fmt::Write;
+
+use crate::cell::MazeType;
+use crate::direction::Direction;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::prelude::*;
+
+/// The tile IDs a tilemap is drawn with, and how many tiles each cell of the maze spans.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TilesetMapping {
+    /// Number of tiles along each side of the square block drawn for a cell: a ring of wall tiles, opened
+    /// where the cell has a passage, around floor tiles. At least 3.
+    pub block_size: usize,
+    pub wall: u32,
+    pub floor: u32,
+    /// Floor tile of the start cell, when it should stand out.
+    pub start: Option<u32>,
+    /// Floor tile of the goal cell, when it should stand out.
+    pub goal: Option<u32>,
+    /// Floor tile of the cells and passages along the solution path, e.g. for an answer key.
+    pub solution: Option<u32>,
+    /// Width and height of each tile in pixels, as recorded in Tiled maps.
+    pub tile_size: u32,
+    /// Path of the Tiled tileset (`.tsx`) the IDs refer to, referenced from Tiled maps.
+    pub tileset: Option<String>,
+}
+
+impl Default for TilesetMapping {
+    fn default() -> Self {
+        // Tiled reserves 0 for an empty tile, so the first tile of a tileset is 1
+        TilesetMapping { block_size: 3, wall: 1, floor: 2, start: None, goal: None, solution: None, tile_size: 16, tileset: None }
+    }
+}
+
+/// A grid of tile IDs in row-major order, drawn from a maze by `tilemap`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tilemap {
+    pub width: usize,
+    pub height: usize,
+    pub tiles: Vec<u32>,
+    // tile size and tileset, carried over from the mapping for Tiled maps
+    tile_size: u32,
+    tileset: Option<String>,
+}
+
+impl Tilemap {
+    /// The tile ID at the specified column and row.
+    pub fn tile(&self, x: usize, y: usize) -> Option<u32> {
+        (x < self.width).then(|| self.tiles.get(y * self.width + x).copied()).flatten()
+    }
+
+    /// The tiles as CSV, one line per row, as Tiled and most engines import.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        for row in self.tiles.chunks(self.width) {
+            let ids: Vec<String> = row.iter().map(|id| id.to_string()).collect();
+            csv.push_str(&ids.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// The tiles as a Tiled JSON map with a single tile layer.
+    pub fn to_tiled_json(&self) -> String {
+        let ids: Vec<String> = self.tiles.iter().map(|id| id.to_string()).collect();
+        let mut tilesets = String::new();
+        if let Some(source) = &self.tileset {
+            let escaped = source.replace('\\', "\\\\").replace('"', "\\\"");
+            // writing to a String cannot fail
+            let _ = write!(tilesets, r#"{{"firstgid":1,"source":"{}"}}"#, escaped);
+        }
+        format!(
+            concat!(
+                r#"{{"type":"map","version":"1.10","orientation":"orthogonal","renderorder":"right-down","infinite":false,"#,
+                r#""width":{},"height":{},"tilewidth":{},"tileheight":{},"nextlayerid":2,"nextobjectid":1,"#,
+                r#""layers":[{{"type":"tilelayer","id":1,"name":"maze","x":0,"y":0,"width":{},"height":{},"opacity":1,"visible":true,"data":[{}]}}],"#,
+                r#""tilesets":[{}]}}"#,
+            ),
+            self.width, self.height, self.tile_size, self.tile_size,
+            self.width, self.height, ids.join(","),
+            tilesets,
+        )
+    }
+}
+
+/// Draw an Orthogonal maze as a tilemap, each cell a square block of `mapping.block_size` tiles: wall tiles
+/// around its border, opened toward each passage and entrance or exit opening, and floor tiles within.
+pub fn tilemap(grid: &Grid, mapping: &TilesetMapping) -> Result<Tilemap, Error> {
+    if grid.maze_type != MazeType::Orthogonal {
+        return Err(Error::GeometryUnavailableForMazeType { maze_type: grid.maze_type });
+    }
+    let n = mapping.block_size;
+    if n < 3 {
+        return Err(Error::InvalidTileset { reason: format!("blocks must be at least 3 tiles wide, not {}", n) });
+    }
+    let (width, height) = (grid.width * n, grid.height * n);
+    let mut tiles = vec![mapping.wall; width * height];
+    let mut fill = |xs: core::ops::Range<usize>, ys: core::ops::Range<usize>, id: u32| {
+        for y in ys {
+            for x in xs.clone() {
+                tiles[y * width + x] = id;
+            }
+        }
+    };
+    for cell in grid.iter_cells() {
+        let solution = mapping.solution.filter(|_| cell.on_solution_path);
+        let floor = match (cell.is_start, cell.is_goal) {
+            (true, _) => mapping.start.or(solution).unwrap_or(mapping.floor),
+            (_, true) => mapping.goal.or(solution).unwrap_or(mapping.floor),
+            _ => solution.unwrap_or(mapping.floor),
+        };
+        let (left, top) = (cell.coords.x * n, cell.coords.y * n);
+        fill(left + 1..left + n - 1, top + 1..top + n - 1, floor);
+
+        let mut openings: Vec<(Direction, u32)> = cell.neighbors_by_direction
+            .iter()
+            .filter(|(_, neighbor)| cell.linked.contains(neighbor))
+            .map(|(direction, neighbor)| {
+                let on_path = cell.on_solution_path && grid.get(neighbor).is_ok_and(|neighbor| neighbor.on_solution_path);
+                (direction, mapping.solution.filter(|_| on_path).unwrap_or(mapping.floor))
+            })
+            .collect();
+        openings.extend(cell.border_opening.map(|direction| (direction, mapping.floor)));
+        for (direction, id) in openings {
+            match direction {
+                Direction::Up => fill(left + 1..left + n - 1, top..top + 1, id),
+                Direction::Down => fill(left + 1..left + n - 1, top + n - 1..top + n, id),
+                Direction::Left => fill(left..left + 1, top + 1..top + n - 1, id),
+                Direction::Right => fill(left + n - 1..left + n, top + 1..top + n - 1, id),
+                // stairs between layers have no place on a flat map
+                _ => {}
+            }
+        }
+    }
+    Ok(Tilemap { width, height, tiles, tile_size: mapping.tile_size, tileset: mapping.tileset.clone() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Coordinates;
+
+    #[test]
+    fn corridor_tiles() {
+        let mut grid = Grid::new(MazeType::Orthogonal, 2, 1, Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 0 }, false).unwrap();
+        grid.link(Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 0 }).unwrap();
+        let mapping = TilesetMapping { start: Some(3), goal: Some(4), ..TilesetMapping::default() };
+        let tilemap = tilemap(&grid, &mapping).unwrap();
+        assert_eq!((tilemap.width, tilemap.height), (6, 3));
+        assert_eq!(tilemap.to_csv(), "1,1,1,1,1,1\n1,3,2,2,4,1\n1,1,1,1,1,1\n");
+        assert_eq!(tilemap.tile(4, 1), Some(4));
+        assert_eq!(tilemap.tile(6, 0), None);
+    }
+
+    #[test]
+    fn generated_maze_tiles() {
+        let json = r#"{ "maze_type": "Orthogonal", "width": 6, "height": 5, "algorithm": "Prims", "open_exits": true }"#;
+        let grid = Grid::try_from(json).unwrap();
+        let mapping = TilesetMapping { block_size: 4, tileset: Some("maze.tsx".to_string()), ..TilesetMapping::default() };
+        let tilemap = tilemap(&grid, &mapping).unwrap();
+        // four floor tiles within each cell, and two on each side of every passage and opening
+        let floors = tilemap.tiles.iter().filter(|&&id| id == mapping.floor).count();
+        assert_eq!(floors, 4 * grid.iter_cells().count() + 4 * grid.count_edges() + 2 * 2);
+
+        let map: serde_json::Value = serde_json::from_str(&tilemap.to_tiled_json()).unwrap();
+        assert_eq!(map["width"], 24);
+        assert_eq!(map["layers"][0]["data"].as_array().unwrap().len(), 24 * 20);
+        assert_eq!(map["tilesets"][0]["source"], "maze.tsx");
+
+        let sigma = Grid::try_from(r#"{ "maze_type": "Sigma", "width": 4, "height": 4, "algorithm": "Prims" }"#).unwrap();
+        assert!(matches!(super::tilemap(&sigma, &mapping), Err(Error::GeometryUnavailableForMazeType { .. })));
+        let thin = TilesetMapping { block_size: 2, ..TilesetMapping::default() };
+        assert!(matches!(super::tilemap(&grid, &thin), Err(Error::InvalidTileset { .. })));
+    }
+}
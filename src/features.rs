@@ -0,0 +1,119 @@
+use rand::seq::SliceRandom;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::cell::Coordinates;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::rng::GridRng;
+use crate::prelude::*;
+
+/// Constraints on where `Grid::place_features` may place features such as keys, enemies or treasure.
+/// The start and goal cells never hold features.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FeatureSpec {
+    /// Number of features to place, each on a different cell.
+    pub count: usize,
+    /// Minimum number of steps from the start cell to any feature.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub min_distance_from_start: u32,
+    /// Place features only in dead ends (cells with a single passage).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dead_ends_only: bool,
+    /// Place features only on cells off the solution path between start and goal.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub off_solution_path: bool,
+    /// Cells which must not hold a feature, such as those holding previously placed features.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub exclude: Vec<Coordinates>,
+    /// Seed for choosing among eligible cells; defaults to the grid's seed, so placement is reproducible.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub seed: Option<u64>,
+}
+
+impl FeatureSpec {
+    /// Place `count` features anywhere reachable from the start.
+    pub fn new(count: usize) -> Self {
+        FeatureSpec { count, ..Default::default() }
+    }
+}
+
+/// Cells of the maze satisfying the specification's constraints, in row-major order.
+pub fn eligible_cells(grid: &Grid, spec: &FeatureSpec) -> Vec<Coordinates> {
+    let distances = grid.distance_field(grid.start_coords);
+    let solution: HashSet<Coordinates> = if spec.off_solution_path {
+        grid.get_path_to(grid.start_coords.x, grid.start_coords.y, grid.goal_coords.x, grid.goal_coords.y)
+            .map(|path| path.into_keys().collect())
+            .unwrap_or_default()
+    } else {
+        HashSet::new()
+    };
+    grid.iter_cells()
+        .filter(|cell| !cell.is_start && !cell.is_goal)
+        .filter(|cell| !spec.exclude.contains(&cell.coords))
+        .filter(|cell| distances.get(&cell.coords).is_some_and(|&distance| distance >= spec.min_distance_from_start))
+        .filter(|cell| !spec.dead_ends_only || cell.linked.len() == 1)
+        .filter(|cell| !solution.contains(&cell.coords))
+        .map(|cell| cell.coords)
+        .collect()
+}
+
+/// Randomly choose `spec.count` distinct cells satisfying the specification's constraints.
+pub fn place(grid: &Grid, spec: &FeatureSpec) -> Result<Vec<Coordinates>, Error> {
+    let candidates = eligible_cells(grid, spec);
+    if candidates.len() < spec.count {
+        return Err(Error::InsufficientFeatureCells { requested: spec.count, available: candidates.len() });
+    }
+    let mut rng = GridRng::seed_from_u64(spec.seed.unwrap_or(grid.seed));
+    Ok(candidates.choose_multiple(&mut rng, spec.count).copied().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::MazeType;
+
+    fn generated(maze_type: MazeType) -> Grid {
+        let json = format!(r#"{{ "maze_type": "{:?}", "width": 12, "height": 12, "algorithm": "RecursiveBacktracker" }}"#, maze_type);
+        Grid::try_from(json).unwrap()
+    }
+
+    #[test]
+    fn features_respect_constraints() {
+        for maze_type in [MazeType::Orthogonal, MazeType::Delta, MazeType::Sigma] {
+            let grid = generated(maze_type);
+            let spec = FeatureSpec { count: 3, min_distance_from_start: 4, dead_ends_only: true, off_solution_path: true, ..Default::default() };
+            let distances = grid.distances(grid.start_coords);
+            let solution = grid.get_path_to(grid.start_coords.x, grid.start_coords.y, grid.goal_coords.x, grid.goal_coords.y).unwrap();
+            let placed = grid.place_features(&spec).unwrap();
+            assert_eq!(placed.len(), 3);
+            assert_eq!(placed.iter().collect::<HashSet<_>>().len(), 3);
+            for coords in placed {
+                let cell = grid.get(coords).unwrap();
+                assert_eq!(cell.linked.len(), 1);
+                assert!(distances[&coords] >= 4);
+                assert!(!solution.contains_key(&coords));
+                assert!(!cell.is_start && !cell.is_goal);
+            }
+        }
+    }
+
+    #[test]
+    fn placement_is_reproducible() {
+        let grid = generated(MazeType::Orthogonal);
+        let spec = FeatureSpec::new(5);
+        assert_eq!(grid.place_features(&spec).unwrap(), grid.place_features(&spec).unwrap());
+
+        let first = grid.place_features(&spec).unwrap();
+        let others = grid.place_features(&FeatureSpec { exclude: first.clone(), ..FeatureSpec::new(5) }).unwrap();
+        assert!(others.iter().all(|coords| !first.contains(coords)));
+    }
+
+    #[test]
+    fn too_few_eligible_cells() {
+        let grid = generated(MazeType::Orthogonal);
+        let spec = FeatureSpec { count: 200, dead_ends_only: true, ..Default::default() };
+        assert!(matches!(grid.place_features(&spec), Err(Error::InsufficientFeatureCells { requested: 200, .. })));
+    }
+}
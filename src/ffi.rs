@@ -2,8 +2,23 @@ use std::ptr;
 use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_void};
 use crate::Grid;
-use crate::cell::Cell;
-use crate::direction::Direction;
+use crate::cell::{AxialCoordinates, Cell, CellOrientation, Coordinates, MazeType};
+use crate::analysis::MazeReport;
+use crate::difficulty::DifficultyReport;
+use crate::direction::{Direction, MovePolicy};
+use crate::events::GridEvent;
+use crate::history::MoveRecord;
+use crate::playback::StepPlayer;
+use crate::algorithms::MazeAlgorithm;
+use crate::request::MazeRequest;
+use crate::session::SessionStats;
+use crate::solver::SolverStrategy;
+use crate::steps::GenerationSteps;
+use crate::error::Error;
+use crate::generation::BackgroundGeneration;
+use crate::handle::{self, Handle};
+use crate::behaviors::display::JsonDisplay;
+use crate::trace::trace_span;
 
 /// Representation of a cell for the FFI layer.
 ///
@@ -13,7 +28,8 @@ use crate::direction::Direction;
 /// - `x`: The x-coordinate of the cell.
 /// - `y`: The y-coordinate of the cell.
 /// - `maze_type`: A pointer to a null-terminated C string identifying the maze type.
-/// - `linked`: A pointer to an array of null-terminated C strings represe_ting linked cells.
+/// - `linked`: A pointer to an array of null-terminated C strings naming the cell's user-facing open wall
+///   directions, kept for existing clients; prefer `open_walls`, which holds the same directions.
 /// - `linked_len`: The number of elements in the `linked` array.
 /// - `distance`: An integer metric (e.g., the distance from the start).
 /// - `is_start`: Indicates if this cell is the starting cell.
@@ -23,6 +39,18 @@ use crate::direction::Direction;
 /// - `has_been_visited`: Indicates if this cell has ever been visited.
 /// - `on_solution_path`: Indicates if this cell is part of the solution path.
 /// - `orientation`: A pointer to a null-terminated C string indicating the cell's orientation.
+/// - `is_square`: Indicates if this cell is square (used by the Upsilon maze type).
+/// - `distance_band`: The cell's distance band id, or -1 if distance bands have not been assigned.
+/// - `heat`: The cell's normalized 0.0–1.0 distance from the heatmap origin, or -1.0 if no heatmap has been assigned.
+/// - `is_discovered`: Indicates if this cell has come into view, when fog of war is enabled.
+/// - `open_walls`: A pointer to an array of null-terminated C strings naming the cell's user-facing open wall
+///   directions (e.g., "North", "East").
+/// - `open_walls_len`: The number of elements in the `open_walls` array.
+/// - `linked_coords`: A pointer to an array of the coordinates of the cells linked to this cell, in order of
+///   their coordinates.
+/// - `linked_coords_len`: The number of elements in the `linked_coords` array.
+/// - `version`: The layout version of the struct, `MAZER_FFI_CELL_VERSION`, so clients can tell which fields
+///   are present.
 #[repr(C)]
 pub struct FFICell {
     pub x: usize,
@@ -51,32 +79,78 @@ pub struct FFICell {
     pub orientation: *const c_char,
 
     pub is_square: bool,
+
+    // Distance band id, or -1 when no bands have been assigned
+    pub distance_band: i32,
+
+    // Normalized heatmap intensity, or -1.0 when no heatmap has been assigned
+    pub heat: f32,
+
+    // Axial coordinates of Sigma (hexagonal) cells; for other maze types these equal x and y
+    pub axial_q: i32,
+    pub axial_r: i32,
+
+    pub is_discovered: bool,
+
+    // The same directions as `linked`, under a name matching what they hold
+    pub open_walls: *const *const c_char,
+    pub open_walls_len: usize,
+
+    // Coordinates of the linked cells
+    pub linked_coords: *const FFICoordinates,
+    pub linked_coords_len: usize,
+
+    pub version: u32,
+}
+
+/// Layout version of `FFICell`, carried in its `version` field. Version 2 added `open_walls`,
+/// `linked_coords` and `version`.
+pub const MAZER_FFI_CELL_VERSION: u32 = 2;
+
+// Leak an array of C strings naming the directions, returning its pointer and length
+fn leak_directions(directions: &[Direction]) -> (*const *const c_char, usize) {
+    let raw: Vec<*const c_char> = directions
+        .iter()
+        .map(|&direction| CString::new(direction.to_string()).unwrap().into_raw() as *const c_char)
+        .collect();
+    let len = raw.len();
+    (Box::leak(raw.into_boxed_slice()).as_ptr(), len)
+}
+
+// Reclaim an array of C strings leaked by `leak_directions`
+unsafe fn free_directions(ptr: *const *const c_char, len: usize) {
+    if ptr.is_null() {
+        return;
+    }
+    for &string in std::slice::from_raw_parts(ptr, len) {
+        if !string.is_null() {
+            let _ = CString::from_raw(string as *mut c_char);
+        }
+    }
+    let _ = Vec::from_raw_parts(ptr as *mut *const c_char, len, len);
 }
 
 impl From<&Cell> for FFICell {
     fn from(cell: &Cell) -> Self {
         // Get the user-facing open walls, adjusted for Rhombic maze if applicable
         let open_walls = cell.get_user_facing_open_walls();
-        
-        // Convert each direction to a C-compatible string
-        let open_walls_raw: Vec<*const c_char> = open_walls
-            .iter()
-            .map(|&direction| {
-                CString::new(direction.to_string()).unwrap().into_raw() as *const c_char
-            })
-            .collect();
-        
-        // Leak the vector into a boxed slice and get its pointer and length
-        let open_walls_len = open_walls_raw.len();
-        let open_walls_ptr = Box::leak(open_walls_raw.into_boxed_slice()).as_ptr();
-        
+        let (linked_ptr, linked_len) = leak_directions(&open_walls);
+        let (open_walls_ptr, open_walls_len) = leak_directions(&open_walls);
+
+        let mut linked_coords: Vec<FFICoordinates> = cell.linked.iter().map(|coords| FFICoordinates { x: coords.x, y: coords.y }).collect();
+        linked_coords.sort_by_key(|coords| (coords.y, coords.x));
+        let linked_coords_len = linked_coords.len();
+        let linked_coords_ptr = Box::leak(linked_coords.into_boxed_slice()).as_ptr();
+
+        let axial = cell.to_axial().unwrap_or(AxialCoordinates { q: cell.coords.x as isize, r: cell.coords.y as isize });
+
         // Construct the FFICell with all fields
         FFICell {
             x: cell.coords.x,
             y: cell.coords.y,
             maze_type: CString::new(format!("{:?}", cell.maze_type)).unwrap().into_raw(),
-            linked: open_walls_ptr,
-            linked_len: open_walls_len,
+            linked: linked_ptr,
+            linked_len,
             distance: cell.distance,
             is_start: cell.is_start,
             is_goal: cell.is_goal,
@@ -86,6 +160,16 @@ impl From<&Cell> for FFICell {
             on_solution_path: cell.on_solution_path,
             orientation: CString::new(format!("{:?}", cell.orientation)).unwrap().into_raw(),
             is_square: cell.is_square,
+            distance_band: cell.distance_band.map_or(-1, |band| band as i32),
+            heat: cell.heat.unwrap_or(-1.0),
+            axial_q: axial.q as i32,
+            axial_r: axial.r as i32,
+            is_discovered: cell.is_discovered,
+            open_walls: open_walls_ptr,
+            open_walls_len,
+            linked_coords: linked_coords_ptr,
+            linked_coords_len,
+            version: MAZER_FFI_CELL_VERSION,
         }
     }
 }
@@ -103,16 +187,15 @@ impl Drop for FFICell {
                 let _ = CString::from_raw(self.orientation as *mut c_char);
             }
             
-            // Reclaim each of the linked C strings.
-            let linked_slice = std::slice::from_raw_parts(self.linked, self.linked_len);
-            for &ptr in linked_slice {
-                if !ptr.is_null() {
-                    let _ = CString::from_raw(ptr as *mut c_char);
-                }
+            // Reclaim the linked and open_walls C strings and their pointer arrays.
+            free_directions(self.linked, self.linked_len);
+            free_directions(self.open_walls, self.open_walls_len);
+
+            // Reclaim the linked coordinates.
+            if !self.linked_coords.is_null() {
+                let slice: *mut [FFICoordinates] = std::ptr::slice_from_raw_parts_mut(self.linked_coords as *mut FFICoordinates, self.linked_coords_len);
+                drop(Box::from_raw(slice));
             }
-            
-            // Reclaim and free the leaked pointer array.
-            let _ = Vec::from_raw_parts(self.linked as *mut *const c_char, self.linked_len, self.linked_len);
         }
     }
 }
@@ -133,6 +216,7 @@ impl Drop for FFICell {
 /// A pointer to the generated `Grid` if successful, or a null pointer on failure.
 #[no_mangle]
 pub extern "C" fn mazer_generate_maze(request_json: *const c_char) -> *mut Grid {
+    trace_span!("ffi", function = "mazer_generate_maze");
     // Check for null pointer.
     if request_json.is_null() {
         eprintln!("mazer_generate_maze: request_json is null");
@@ -153,6 +237,7 @@ pub extern "C" fn mazer_generate_maze(request_json: *const c_char) -> *mut Grid
         Ok(m) => m,
         Err(err) => {
             eprintln!("mazer_generate_maze: Maze generation failed: {:?}", err);
+            record_error(&err);
             return std::ptr::null_mut();
         }
     };
@@ -183,6 +268,133 @@ pub extern "C" fn mazer_destroy(maze: *mut Grid) {
     }
 }
 
+/// Serializes a maze, including its links, visited state and move history, to a JSON string.
+///
+/// The returned string can later be passed to `mazer_deserialize_maze` to restore the maze.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+///
+/// # Returns
+///
+/// A null-terminated JSON string to be released with `mazer_free_string`, or a null pointer if the grid
+/// pointer is null or serialization fails.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_serialize_maze(grid: *const Grid) -> *mut c_char {
+    trace_span!("ffi", function = "mazer_serialize_maze");
+    if grid.is_null() {
+        return ptr::null_mut();
+    }
+    let grid = unsafe { &*grid };
+    match grid.to_json() {
+        Ok(json) => CString::new(json).map(CString::into_raw).unwrap_or(ptr::null_mut()),
+        Err(err) => {
+            eprintln!("mazer_serialize_maze: Serialization failed: {:?}", err);
+            record_error(&Error::from(err));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Restores a maze previously serialized with `mazer_serialize_maze`.
+///
+/// # Parameters
+///
+/// - `saved_json`: A null-terminated C string containing the serialized maze.
+///
+/// # Returns
+///
+/// A pointer to the restored `Grid` (to be released with `mazer_destroy`), or a null pointer on failure.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_deserialize_maze(saved_json: *const c_char) -> *mut Grid {
+    trace_span!("ffi", function = "mazer_deserialize_maze");
+    if saved_json.is_null() {
+        return ptr::null_mut();
+    }
+    let json = match unsafe { CStr::from_ptr(saved_json) }.to_str() {
+        Ok(s) => s,
+        Err(_) => return ptr::null_mut(),
+    };
+    match Grid::from_saved_json(json) {
+        Ok(grid) => Box::into_raw(Box::new(grid)),
+        Err(err) => {
+            eprintln!("mazer_deserialize_maze: Failed to restore maze: {:?}", err);
+            record_error(&err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Frees a string previously returned by the library, such as from `mazer_serialize_maze`.
+///
+/// # Parameters
+///
+/// - `ptr`: A pointer to the null-terminated C string to be freed.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_free_string(ptr: *mut c_char) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        drop(CString::from_raw(ptr));
+    }
+}
+
+/// Error category reported by `mazer_last_error_category` when the request asked for something invalid.
+pub const MAZER_ERROR_INVALID_REQUEST: u32 = 1;
+/// Error category reported by `mazer_last_error_category` when the request asked for something unsupported.
+pub const MAZER_ERROR_UNSUPPORTED: u32 = 2;
+/// Error category reported by `mazer_last_error_category` when the failure was not caused by the request.
+pub const MAZER_ERROR_INTERNAL: u32 = 3;
+
+// The code, category code and message of the most recent failure on each thread
+thread_local! {
+    static LAST_ERROR: std::cell::RefCell<Option<(u32, u32, String)>> = const { std::cell::RefCell::new(None) };
+}
+
+// Keep an error for the `mazer_last_error_*` functions
+fn record_error(error: &Error) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some((error.code(), error.category().code(), error.to_string())));
+}
+
+/// Retrieves the code of the most recent error on the calling thread, recorded when a function such as
+/// `mazer_generate_maze` fails. Codes are stable across releases (see `Error::code`). Successful calls do not
+/// clear the error, so check it only after a call has failed.
+///
+/// # Returns
+///
+/// The error code, or 0 if no error has been recorded on the calling thread.
+#[no_mangle]
+pub extern "C" fn mazer_last_error_code() -> u32 {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(0, |(code, _, _)| *code))
+}
+
+/// Retrieves the category of the most recent error on the calling thread.
+///
+/// # Returns
+///
+/// `MAZER_ERROR_INVALID_REQUEST`, `MAZER_ERROR_UNSUPPORTED` or `MAZER_ERROR_INTERNAL`, or 0 if no error has
+/// been recorded on the calling thread.
+#[no_mangle]
+pub extern "C" fn mazer_last_error_category() -> u32 {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(0, |(_, category, _)| *category))
+}
+
+/// Retrieves a message describing the most recent error on the calling thread, suitable for showing users.
+///
+/// # Returns
+///
+/// A null-terminated string to be released with `mazer_free_string`, or a null pointer if no error has been
+/// recorded on the calling thread.
+#[no_mangle]
+pub extern "C" fn mazer_last_error_message() -> *mut c_char {
+    into_c_string(LAST_ERROR.with(|last| last.borrow().as_ref().map(|(_, _, message)| message.clone())))
+}
+
 /// Retrieves the cells of the maze.
 ///
 /// This function returns an array of `FFICell` structures that represent the individual cells
@@ -198,6 +410,7 @@ pub extern "C" fn mazer_destroy(maze: *mut Grid) {
 /// A pointer to an array of `FFICell` structures, or a null pointer if the input pointers are invalid.
 #[no_mangle]
 pub extern "C" fn mazer_get_cells(maze: *mut Grid, length: *mut usize) -> *mut FFICell {
+    trace_span!("ffi", function = "mazer_get_cells");
     // Validate input pointers.
     if maze.is_null() || length.is_null() {
         return std::ptr::null_mut();
@@ -207,7 +420,7 @@ pub extern "C" fn mazer_get_cells(maze: *mut Grid, length: *mut usize) -> *mut F
     let grid = unsafe { &*maze };
 
     // Convert each Cell into an FFICell.
-    let ffi_cells: Vec<FFICell> = grid.cells.iter().filter_map(|opt| opt.as_ref().map(FFICell::from)).collect();
+    let ffi_cells: Vec<FFICell> = grid.iter_cells().map(FFICell::from).collect();
 
     // Write the number of FFICells into the provided length pointer.
     let len = ffi_cells.len();
@@ -220,6 +433,86 @@ pub extern "C" fn mazer_get_cells(maze: *mut Grid, length: *mut usize) -> *mut F
     Box::into_raw(ffi_cells.into_boxed_slice()) as *mut FFICell
 }
 
+/// Retrieves the grid's revision, which increases whenever the state of any cell changes.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+///
+/// # Returns
+///
+/// The current revision, or 0 if the grid pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_revision(grid: *const Grid) -> u64 {
+    if grid.is_null() {
+        return 0;
+    }
+    let grid = unsafe { &*grid };
+    grid.revision
+}
+
+/// Retrieves only the cells whose state changed after the specified revision, so clients can redraw just
+/// those cells rather than the whole maze after every move.
+///
+/// Clients record `mazer_get_revision` after each fetch and pass it as `since_revision` on the next.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `since_revision`: The revision as of the client's previous fetch.
+/// - `length`: A pointer to a `usize` variable where the number of cells will be stored.
+///
+/// # Returns
+///
+/// A pointer to an array of `FFICell` structures to be released with `mazer_free_cells`, or a null pointer
+/// if either pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_changed_cells(grid: *const Grid, since_revision: u64, length: *mut usize) -> *mut FFICell {
+    if grid.is_null() || length.is_null() {
+        return ptr::null_mut();
+    }
+    let grid = unsafe { &*grid };
+    let ffi_cells: Vec<FFICell> = grid.changed_cells_since(since_revision).into_iter().map(FFICell::from).collect();
+    unsafe {
+        *length = ffi_cells.len();
+    }
+    Box::into_raw(ffi_cells.into_boxed_slice()) as *mut FFICell
+}
+
+/// Shifts the maze's walls mid-game, opening `count` randomly chosen walls and closing another passage for
+/// each, while keeping every cell (and so the goal) reachable. Same as `Grid::shift_walls`.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the mutable `Grid` instance.
+/// - `count`: The number of walls to open, and of passages to close.
+/// - `seed`: Seed choosing the walls and passages, so the same seed always shifts the same walls.
+/// - `length`: A pointer to a `usize` variable where the number of changed cells will be stored.
+///
+/// # Returns
+///
+/// A pointer to an array of `FFICell` structures for the cells changed by the shift, to be released with
+/// `mazer_free_cells`, or a null pointer if either pointer is null or the walls could not be shifted.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_shift_walls(grid: *mut Grid, count: usize, seed: u64, length: *mut usize) -> *mut FFICell {
+    if grid.is_null() || length.is_null() {
+        return ptr::null_mut();
+    }
+    let grid = unsafe { &mut *grid };
+    let revision = grid.revision;
+    if grid.shift_walls(count, seed).is_err() {
+        return ptr::null_mut();
+    }
+    let ffi_cells: Vec<FFICell> = grid.changed_cells_since(revision).into_iter().map(FFICell::from).collect();
+    unsafe {
+        *length = ffi_cells.len();
+    }
+    Box::into_raw(ffi_cells.into_boxed_slice()) as *mut FFICell
+}
+
 /// Frees an array of `FFICell` structures.
 ///
 /// This function deallocates the memory allocated for an array of `FFICell` structures that was
@@ -259,21 +552,22 @@ pub extern "C" fn mazer_get_generation_steps_count(grid: *mut Grid) -> usize {
     }
 }
 
-/// Returns the cells for a specific generation step.
+/// Returns the cells for a specific generation step. Each call rebuilds and converts the whole step, so to
+/// animate generation use `mazer_step_cells_new`, which converts just the cells each step changed.
 #[no_mangle]
 pub extern "C" fn mazer_get_generation_step_cells(
     grid: *mut Grid,
     step_index: usize,
     length: *mut usize,
 ) -> *mut FFICell {
+    trace_span!("ffi", function = "mazer_get_generation_step_cells");
     if grid.is_null() || length.is_null() {
         return std::ptr::null_mut();
     }
     let grid = unsafe { &*grid };
     if let Some(steps) = &grid.generation_steps {
-        if step_index < steps.len() {
-            let step_grid = &steps[step_index];
-            let ffi_cells: Vec<FFICell> = step_grid.cells.iter().filter_map(|opt| opt.as_ref().map(FFICell::from)).collect(); 
+        if let Some(step_grid) = steps.get(step_index) {
+            let ffi_cells: Vec<FFICell> = step_grid.iter_cells().map(FFICell::from).collect(); 
             let len = ffi_cells.len();
             unsafe {
                 *length = len;
@@ -287,49 +581,1874 @@ pub extern "C" fn mazer_get_generation_step_cells(
     }
 }
 
-/// Performs a move on the maze grid based on the provided direction.
-///
-/// This function takes an opaque pointer to a mutable `Grid` instance and a null-terminated C string
-/// representing the direction in which to move. It then calls the internal `make_move` function on the
-/// `Grid` and returns a pointer to the updated `Grid` instance.
+/// The cells of a maze's generation steps converted for the FFI layer and kept between calls, so that moving
+/// on through the steps converts only the cells each step changed rather than allocating every cell anew.
+pub struct StepCells {
+    steps: &'static GenerationSteps,
+    index: usize,
+    cells: Vec<FFICell>,
+    // position in `cells` of the cell at each index of the maze's cells
+    positions: Vec<Option<usize>>,
+}
+
+impl StepCells {
+    // The cells of the first step, or None if no steps were captured
+    fn new(steps: &'static GenerationSteps) -> Option<Self> {
+        let first = steps.first()?;
+        let positions = first.cells.iter().scan(0, |next, cell| {
+            Some(cell.as_ref().map(|_| {
+                *next += 1;
+                *next - 1
+            }))
+        }).collect();
+        let cells = first.iter_cells().map(FFICell::from).collect();
+        Some(StepCells { steps, index: 0, cells, positions })
+    }
+
+    // Move to the step at `index`, converting the cells changed since the current step, or starting over from
+    // the first step to move back. Returns false if there is no such step
+    fn seek(&mut self, index: usize) -> bool {
+        if index >= self.steps.len() {
+            return false;
+        }
+        let (steps, Some(first)) = (self.steps, self.steps.first()) else {
+            return false;
+        };
+        if index < self.index {
+            self.cells = first.iter_cells().map(FFICell::from).collect();
+            self.index = 0;
+        }
+        for step in self.index + 1..=index {
+            for cell in steps.changed_cells(step) {
+                if let Some(&Some(position)) = self.positions.get(first.get_flattened_index(cell.coords.x, cell.coords.y)) {
+                    self.cells[position] = FFICell::from(cell);
+                }
+            }
+        }
+        self.index = index;
+        true
+    }
+}
+
+/// Creates a cache of the cells of the maze's generation steps for animating generation. Unlike
+/// `mazer_get_generation_step_cells`, which converts every cell of the step requested on each call, the cache
+/// keeps the cells of the step last retrieved and converts only those changed since, so retrieving the steps
+/// in order allocates little more than the cells each step carved.
 ///
 /// # Parameters
 ///
-/// - `grid_ptr`: An opaque pointer (`*mut c_void`) to a mutable `Grid`.
-/// - `direction`: A null-terminated C string (`*const c_char`) indicating the move direction.
+/// - `grid`: A pointer to the `Grid` instance, which must outlive the cache.
 ///
 /// # Returns
 ///
-/// A pointer to the updated `Grid` instance if successful, or a null pointer if an error occurs.
+/// A pointer to the cache (to be released with `mazer_step_cells_destroy`), or a null pointer if the grid
+/// pointer is null or no generation steps were captured.
 #[no_mangle]
-pub extern "C" fn mazer_make_move(grid_ptr: *mut c_void, direction: *const c_char) -> *mut c_void {
-    // Safety: Ensure that both pointers are non-null.
-    if grid_ptr.is_null() || direction.is_null() {
-        // bad inputs -> null
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_step_cells_new(grid: *const Grid) -> *mut StepCells {
+    trace_span!("ffi", function = "mazer_step_cells_new");
+    let Some(steps) = unsafe { grid.as_ref() }.and_then(|grid| grid.generation_steps.as_ref()) else {
         return ptr::null_mut();
-    }
+    };
+    StepCells::new(steps).map_or(ptr::null_mut(), |cells| Box::into_raw(Box::new(cells)))
+}
 
-    // reclaim the grid: convert the opaque pointer back to a mutable reference to Grid.
-    #[allow(unused_unsafe)]
-    let grid: &mut Grid = unsafe { &mut *(grid_ptr as *mut Grid) };
+/// Releases a cache created by `mazer_step_cells_new`, along with any cells retrieved from it. Does nothing if
+/// `cells` is null.
+///
+/// # Parameters
+///
+/// - `cells`: A pointer to the cache, which is invalid after this call.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_step_cells_destroy(cells: *mut StepCells) {
+    if !cells.is_null() {
+        drop(unsafe { Box::from_raw(cells) });
+    }
+}
 
-    // convert the C string to a Rust &str.
-    let dir_str = match unsafe { CStr::from_ptr(direction) }.to_str() {
-        Ok(s) => s,
-        Err(_) => return std::ptr::null_mut(),
+/// Retrieves the cells of a generation step from a cache created by `mazer_step_cells_new`.
+///
+/// # Parameters
+///
+/// - `cells`: A pointer to the cache.
+/// - `step_index`: The index of the generation step.
+/// - `length`: A pointer to store the number of cells.
+///
+/// # Returns
+///
+/// A pointer to an array of `FFICell` owned by the cache, which must not be released with `mazer_free_cells`
+/// and is valid only until the next call with the cache or its release, or a null pointer if either pointer is
+/// null or the step index is out of range.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_step_cells_get(cells: *mut StepCells, step_index: usize, length: *mut usize) -> *const FFICell {
+    if length.is_null() {
+        return ptr::null();
+    }
+    let Some(cells) = (unsafe { cells.as_mut() }) else {
+        return ptr::null();
     };
+    if !cells.seek(step_index) {
+        return ptr::null();
+    }
+    unsafe {
+        *length = cells.cells.len();
+    }
+    cells.cells.as_ptr()
+}
 
-    let dir_enum = match Direction::try_from(dir_str) {
-        Ok(d) => d,
-        Err(_) => return std::ptr::null_mut(),
+/// Plays out a solving strategy from the start, recording the maze after every step for animation.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the Grid instance.
+/// - `strategy`: A null-terminated C string naming the strategy: "LeftHand", "RightHand", "Pledge",
+///   "DeadEndFilling" or "Tremaux".
+///
+/// # Returns
+///
+/// The number of solving steps recorded, or 0 if either pointer is null, the strategy is not recognized, or
+/// the maze is too large to capture.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_capture_solver(grid: *mut Grid, strategy: *const c_char) -> usize {
+    trace_span!("ffi", function = "mazer_capture_solver");
+    if grid.is_null() || strategy.is_null() {
+        return 0;
+    }
+    let grid = unsafe { &mut *grid };
+    let Ok(strategy) = unsafe { CStr::from_ptr(strategy) }.to_str() else {
+        return 0;
     };
+    let Ok(strategy) = serde_json::from_value::<SolverStrategy>(serde_json::Value::String(strategy.to_string())) else {
+        return 0;
+    };
+    match grid.capture_solver(strategy) {
+        Ok(_) => grid.solving_steps.as_ref().map_or(0, |steps| steps.len()),
+        Err(_) => 0,
+    }
+}
 
-    // attempt the move
-    if grid.make_move(dir_enum).is_ok() {
-        // on successful move, return the same pointer to the grid.
-        grid_ptr
-    } else {
-        std::ptr::null_mut()
+/// Returns the number of solving steps recorded by mazer_capture_solver.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_solving_steps_count(grid: *mut Grid) -> usize {
+    if grid.is_null() {
+        return 0;
+    }
+    let grid = unsafe { &*grid };
+    grid.solving_steps.as_ref().map_or(0, |steps| steps.len())
+}
+
+/// Returns the cells for a specific solving step.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_solving_step_cells(
+    grid: *mut Grid,
+    step_index: usize,
+    length: *mut usize,
+) -> *mut FFICell {
+    if grid.is_null() || length.is_null() {
+        return std::ptr::null_mut();
+    }
+    let grid = unsafe { &*grid };
+    match grid.solving_steps.as_ref().and_then(|steps| steps.get(step_index)) {
+        Some(step_grid) => {
+            let ffi_cells: Vec<FFICell> = step_grid.iter_cells().map(FFICell::from).collect();
+            unsafe {
+                *length = ffi_cells.len();
+            }
+            Box::into_raw(ffi_cells.into_boxed_slice()) as *mut FFICell
+        }
+        None => std::ptr::null_mut(),
+    }
+}
+
+// JSON mirror of `FFICell`, for clients which would rather parse one string than walk an array of structs
+#[derive(serde::Serialize)]
+struct JsonCell {
+    x: usize,
+    y: usize,
+    maze_type: MazeType,
+    open_walls: Vec<String>,
+    linked_coords: Vec<Coordinates>,
+    distance: i32,
+    is_start: bool,
+    is_goal: bool,
+    is_active: bool,
+    is_visited: bool,
+    has_been_visited: bool,
+    on_solution_path: bool,
+    orientation: CellOrientation,
+    is_square: bool,
+    distance_band: Option<usize>,
+    heat: Option<f32>,
+    axial_q: isize,
+    axial_r: isize,
+    is_discovered: bool,
+}
+
+impl From<&Cell> for JsonCell {
+    fn from(cell: &Cell) -> Self {
+        let mut linked_coords: Vec<Coordinates> = cell.linked.iter().collect();
+        linked_coords.sort_by_key(|coords| (coords.y, coords.x));
+        let axial = cell.to_axial().unwrap_or(AxialCoordinates { q: cell.coords.x as isize, r: cell.coords.y as isize });
+        JsonCell {
+            x: cell.coords.x,
+            y: cell.coords.y,
+            maze_type: cell.maze_type,
+            open_walls: cell.get_user_facing_open_walls().iter().map(|direction| direction.to_string()).collect(),
+            linked_coords,
+            distance: cell.distance,
+            is_start: cell.is_start,
+            is_goal: cell.is_goal,
+            is_active: cell.is_active,
+            is_visited: cell.is_visited,
+            has_been_visited: cell.has_been_visited,
+            on_solution_path: cell.on_solution_path,
+            orientation: cell.orientation,
+            is_square: cell.is_square,
+            distance_band: cell.distance_band,
+            heat: cell.heat,
+            axial_q: axial.q,
+            axial_r: axial.r,
+            is_discovered: cell.is_discovered,
+        }
+    }
+}
+
+// The grid's cells as a JSON array, as returned by `mazer_get_cells_json`
+pub(crate) fn cells_json(grid: &Grid) -> Option<String> {
+    let cells: Vec<JsonCell> = grid.iter_cells().map(JsonCell::from).collect();
+    serde_json::to_string(&cells).ok()
+}
+
+// A JSON string as a C string, or a null pointer if there is none
+fn into_c_string(json: Option<String>) -> *mut c_char {
+    json.and_then(|json| CString::new(json).ok()).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Retrieves the cells of the maze as a single JSON string, an alternative to `mazer_get_cells` for clients
+/// which find parsing JSON simpler than reading an array of `FFICell`.
+///
+/// The string holds an array with an object per cell, with the same fields as `FFICell` except `linked`,
+/// `version` and the array lengths. `linked_coords` holds `{ "x", "y" }` objects, and `distance_band` and
+/// `heat` are null when unassigned.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+///
+/// # Returns
+///
+/// A null-terminated JSON string to be released with `mazer_free_string`, or a null pointer if the grid
+/// pointer is null or serialization fails.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_cells_json(grid: *const Grid) -> *mut c_char {
+    if grid.is_null() {
+        return ptr::null_mut();
+    }
+    into_c_string(cells_json(unsafe { &*grid }))
+}
+
+/// Retrieves the cells of a generation step as a single JSON string, in the form of `mazer_get_cells_json`.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `step_index`: The index of the generation step.
+///
+/// # Returns
+///
+/// A null-terminated JSON string to be released with `mazer_free_string`, or a null pointer if the grid
+/// pointer is null, no generation steps were captured, the step index is out of range, or serialization fails.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_generation_step_cells_json(grid: *const Grid, step_index: usize) -> *mut c_char {
+    if grid.is_null() {
+        return ptr::null_mut();
+    }
+    let grid = unsafe { &*grid };
+    match grid.generation_steps.as_ref().and_then(|steps| steps.get(step_index)) {
+        Some(step_grid) => into_c_string(cells_json(&step_grid)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Retrieves what happened in a generation step as a JSON object: the algorithm's `phase` ("Start", "Carve",
+/// "Wall", "Kill" or "Hunt"), the `linked` and `unlinked` passages as pairs of coordinates, and the `frontier`
+/// cells the algorithm will carve from or into next.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `step_index`: The index of the generation step.
+///
+/// # Returns
+///
+/// A null-terminated JSON string to be released with `mazer_free_string`, or a null pointer if the grid
+/// pointer is null, no generation steps were captured, the step index is out of range, or serialization fails.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_generation_step_details_json(grid: *const Grid, step_index: usize) -> *mut c_char {
+    if grid.is_null() {
+        return ptr::null_mut();
+    }
+    let grid = unsafe { &*grid };
+    match grid.generation_step_details.as_ref().and_then(|details| details.get(step_index)) {
+        Some(details) => into_c_string(serde_json::to_string(details).ok()),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Creates a player of the maze's generation steps, which starts paused on the first step. Advance it with
+/// `mazer_step_player_tick` each frame while playing, and draw the cells of its current step.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance, which must outlive the player.
+///
+/// # Returns
+///
+/// A pointer to the player (to be released with `mazer_step_player_destroy`), or a null pointer if the grid
+/// pointer is null or no generation steps were captured.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_step_player_new(grid: *const Grid) -> *mut StepPlayer<'static> {
+    if grid.is_null() {
+        return ptr::null_mut();
+    }
+    let player = StepPlayer::new(unsafe { &*grid });
+    if player.is_empty() {
+        return ptr::null_mut();
+    }
+    Box::into_raw(Box::new(player))
+}
+
+/// Releases a player created by `mazer_step_player_new`. Does nothing if `player` is null.
+///
+/// # Parameters
+///
+/// - `player`: A pointer to the player, which is invalid after this call.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_step_player_destroy(player: *mut StepPlayer<'static>) {
+    if !player.is_null() {
+        drop(unsafe { Box::from_raw(player) });
+    }
+}
+
+/// Starts the player advancing as time passes, from the first step if the last has been reached.
+///
+/// # Parameters
+///
+/// - `player`: A pointer to the player.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_step_player_play(player: *mut StepPlayer<'static>) {
+    if let Some(player) = unsafe { player.as_mut() } {
+        player.play();
+    }
+}
+
+/// Stops the player advancing, staying on the current step.
+///
+/// # Parameters
+///
+/// - `player`: A pointer to the player.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_step_player_pause(player: *mut StepPlayer<'static>) {
+    if let Some(player) = unsafe { player.as_mut() } {
+        player.pause();
+    }
+}
+
+/// Moves the player to a step.
+///
+/// # Parameters
+///
+/// - `player`: A pointer to the player.
+/// - `step_index`: The index of the step, past the last step moving to the last.
+///
+/// # Returns
+///
+/// The index of the player's new current step, or 0 if `player` is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_step_player_seek(player: *mut StepPlayer<'static>, step_index: usize) -> usize {
+    unsafe { player.as_mut() }.map_or(0, |player| player.seek(step_index))
+}
+
+/// Sets how many steps the player advances per second, 30 until set.
+///
+/// # Parameters
+///
+/// - `player`: A pointer to the player.
+/// - `steps_per_second`: The speed, ignored unless positive.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_step_player_set_speed(player: *mut StepPlayer<'static>, steps_per_second: f64) {
+    if let Some(player) = unsafe { player.as_mut() } {
+        player.set_speed(steps_per_second);
+    }
+}
+
+/// Advances a playing player by as many steps as the time passed allows at its speed, pausing it on reaching
+/// the last step.
+///
+/// # Parameters
+///
+/// - `player`: A pointer to the player.
+/// - `elapsed_millis`: The milliseconds passed since the previous tick.
+///
+/// # Returns
+///
+/// Whether the current step changed, so the maze needs redrawing.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_step_player_tick(player: *mut StepPlayer<'static>, elapsed_millis: u64) -> bool {
+    unsafe { player.as_mut() }.is_some_and(|player| player.tick(elapsed_millis))
+}
+
+/// Retrieves the index of the player's current step, or 0 if `player` is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_step_player_index(player: *const StepPlayer<'static>) -> usize {
+    unsafe { player.as_ref() }.map_or(0, |player| player.index())
+}
+
+/// Reports whether the player is advancing as time passes, which is false if `player` is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_step_player_is_playing(player: *const StepPlayer<'static>) -> bool {
+    unsafe { player.as_ref() }.is_some_and(|player| player.is_playing())
+}
+
+/// Retrieves the cells of the player's current step.
+///
+/// # Parameters
+///
+/// - `player`: A pointer to the player.
+/// - `length`: A pointer to store the number of cells.
+///
+/// # Returns
+///
+/// A pointer to an array of `FFICell` (to be released with `mazer_free_cells`), or a null pointer if either
+/// pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_step_player_cells(player: *const StepPlayer<'static>, length: *mut usize) -> *mut FFICell {
+    if length.is_null() {
+        return ptr::null_mut();
+    }
+    let Some(player) = (unsafe { player.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    let ffi_cells: Vec<FFICell> = player.current_cells().map(FFICell::from).collect();
+    unsafe {
+        *length = ffi_cells.len();
+    }
+    Box::into_raw(ffi_cells.into_boxed_slice()) as *mut FFICell
+}
+
+/// Retrieves what happened in the player's current step as JSON, in the form of
+/// `mazer_get_generation_step_details_json`.
+///
+/// # Parameters
+///
+/// - `player`: A pointer to the player.
+///
+/// # Returns
+///
+/// A null-terminated JSON string to be released with `mazer_free_string`, or a null pointer if `player` is
+/// null or serialization fails.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_step_player_details_json(player: *const StepPlayer<'static>) -> *mut c_char {
+    let details = unsafe { player.as_ref() }.and_then(|player| player.current_details());
+    into_c_string(details.and_then(|details| serde_json::to_string(details).ok()))
+}
+
+/// Assigns distance bands to every cell of the maze.
+///
+/// Groups the maze's cells into `bands` bands by normalized distance from the start cell and stores
+/// each cell's band id, which is then reported in the `distance_band` field of subsequently
+/// retrieved `FFICell`s.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `bands`: The number of bands to split the cells into; must be at least 1.
+///
+/// # Returns
+///
+/// `true` if bands were assigned, or `false` if the grid pointer is null or `bands` is 0.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_assign_distance_bands(grid: *mut Grid, bands: usize) -> bool {
+    if grid.is_null() {
+        return false;
+    }
+    let grid = unsafe { &mut *grid };
+    grid.assign_distance_bands(bands).is_ok()
+}
+
+/// Assigns heatmap intensities to every cell of the maze.
+///
+/// Normalizes each cell's distance from the origin cell to a 0.0–1.0 intensity, which is then reported
+/// in the `heat` field of subsequently retrieved `FFICell`s.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `origin_x`: The x-coordinate of the cell to measure distances from.
+/// - `origin_y`: The y-coordinate of the cell to measure distances from.
+///
+/// # Returns
+///
+/// `true` if the heatmap was assigned, or `false` if the grid pointer is null or the origin is not a cell of the maze.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_assign_heatmap(grid: *mut Grid, origin_x: usize, origin_y: usize) -> bool {
+    if grid.is_null() {
+        return false;
+    }
+    let grid = unsafe { &mut *grid };
+    grid.assign_heatmap(Coordinates { x: origin_x, y: origin_y }).is_ok()
+}
+
+/// Performs a move on the maze grid based on the provided direction.
+///
+/// This function takes an opaque pointer to a mutable `Grid` instance and a null-terminated C string
+/// representing the direction in which to move. It then calls the internal `make_move` function on the
+/// `Grid` and returns a pointer to the updated `Grid` instance.
+///
+/// # Parameters
+///
+/// - `grid_ptr`: An opaque pointer (`*mut c_void`) to a mutable `Grid`.
+/// - `direction`: A null-terminated C string (`*const c_char`) indicating the move direction.
+///
+/// # Returns
+///
+/// A pointer to the updated `Grid` instance if successful, or a null pointer if an error occurs.
+#[no_mangle]
+pub extern "C" fn mazer_make_move(grid_ptr: *mut c_void, direction: *const c_char) -> *mut c_void {
+    trace_span!("ffi", function = "mazer_make_move");
+    // Safety: Ensure that both pointers are non-null.
+    if grid_ptr.is_null() || direction.is_null() {
+        // bad inputs -> null
+        return ptr::null_mut();
+    }
+
+    // reclaim the grid: convert the opaque pointer back to a mutable reference to Grid.
+    #[allow(unused_unsafe)]
+    let grid: &mut Grid = unsafe { &mut *(grid_ptr as *mut Grid) };
+
+    // convert the C string to a Rust &str.
+    let dir_str = match unsafe { CStr::from_ptr(direction) }.to_str() {
+        Ok(s) => s,
+        Err(_) => return std::ptr::null_mut(),
+    };
+
+    let dir_enum = match Direction::try_from(dir_str) {
+        Ok(d) => d,
+        Err(_) => return std::ptr::null_mut(),
+    };
+
+    // attempt the move
+    match grid.make_move(dir_enum) {
+        // on successful move, return the same pointer to the grid.
+        Ok(_) => grid_ptr,
+        Err(err) => {
+            record_error(&err);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Moves to the open neighbor of the active cell nearest the specified cell, for touch interfaces where the
+/// user taps a cell, or swipes toward one, rather than choosing a direction.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `x`: The x-coordinate of the tapped cell.
+/// - `y`: The y-coordinate of the tapped cell.
+///
+/// # Returns
+///
+/// The numeric code of the direction taken (see `mazer_get_hint`), or -1 if the grid pointer is null, the
+/// coordinates are not a cell of the maze, or no open passage leads toward the cell.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_move_toward(grid: *mut Grid, x: usize, y: usize) -> i32 {
+    if grid.is_null() {
+        return -1;
+    }
+    let grid = unsafe { &mut *grid };
+    grid.make_move_toward(Coordinates { x, y }).map(|direction| direction as i32).unwrap_or(-1)
+}
+
+/// Moves in a direction, then keeps following the passage until reaching a junction, a dead end or the goal,
+/// marking each cell passed through visited.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `direction`: A null-terminated C string indicating the direction of the first move.
+/// - `length`: A pointer to a `usize` where the number of cells traversed will be stored.
+///
+/// # Returns
+///
+/// A pointer to an array of `FFICoordinates` listing the cells traversed in order, ending with the cell
+/// stopped at, to be released with `mazer_free_coordinates`, or a null pointer if any pointer is null, the
+/// direction is not recognized, or the first move is unavailable.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_move_until_junction(grid: *mut Grid, direction: *const c_char, length: *mut usize) -> *mut FFICoordinates {
+    if grid.is_null() || direction.is_null() || length.is_null() {
+        return ptr::null_mut();
+    }
+    let grid = unsafe { &mut *grid };
+    let Some(direction) = unsafe { CStr::from_ptr(direction) }.to_str().ok().and_then(|d| Direction::try_from(d).ok()) else {
+        return ptr::null_mut();
+    };
+    let Ok(traversed) = grid.move_until_junction(direction) else {
+        return ptr::null_mut();
+    };
+    let traversed: Vec<FFICoordinates> = traversed.into_iter().map(|coords| FFICoordinates { x: coords.x, y: coords.y }).collect();
+    unsafe {
+        *length = traversed.len();
+    }
+    Box::into_raw(traversed.into_boxed_slice()) as *mut FFICoordinates
+}
+
+/// Sets how subsequent moves in a direction with no open passage are resolved.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `policy`: A null-terminated C string naming the policy, `Fallback` (the default, trying adjacent
+///   directions) or `Strict` (only the exact direction), or a JSON policy such as
+///   `{"Custom": [["Left", ["UpperLeft"]]]}` listing each direction's fallbacks.
+///
+/// # Returns
+///
+/// `true` if the policy was set, or `false` if either pointer is null or the policy is not recognized.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_set_move_policy(grid: *mut Grid, policy: *const c_char) -> bool {
+    if grid.is_null() || policy.is_null() {
+        return false;
+    }
+    let grid = unsafe { &mut *grid };
+    let Ok(policy) = unsafe { CStr::from_ptr(policy) }.to_str() else {
+        return false;
+    };
+    // a bare name is accepted as well as the JSON string holding it
+    let parsed = serde_json::from_str::<MovePolicy>(policy)
+        .or_else(|_| serde_json::from_value::<MovePolicy>(serde_json::Value::String(policy.to_string())));
+    match parsed {
+        Ok(policy) => {
+            grid.move_policy = policy;
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Suggests the next move toward the goal from the currently active cell.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+///
+/// # Returns
+///
+/// The numeric code of the hinted direction (0 = Up, 1 = Right, 2 = Down, 3 = Left, 4 = UpperRight,
+/// 5 = LowerRight, 6 = LowerLeft, 7 = UpperLeft, 8 = Above, 9 = Below, 10 = Inward, 11 = Outward, 12 = Clockwise,
+/// 13 = CounterClockwise), or -1 if the grid pointer is null, the active cell is already the goal, or no hint is
+/// available.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_hint(grid: *const Grid) -> i32 {
+    if grid.is_null() {
+        return -1;
+    }
+    let grid = unsafe { &*grid };
+    grid.hint().map(|direction| direction as i32).unwrap_or(-1)
+}
+
+/// Representation of a recorded user move for the FFI layer.
+///
+/// Fields:
+/// - `from_x`, `from_y`: The coordinates of the cell moved from.
+/// - `to_x`, `to_y`: The coordinates of the cell moved to.
+/// - `requested`: The numeric code of the direction requested by the user (see `mazer_get_hint`).
+/// - `direction`: The numeric code of the direction actually taken.
+/// - `backtrack`: Indicates if the move retraced the path back into an already visited cell.
+#[repr(C)]
+pub struct FFIMoveRecord {
+    pub from_x: usize,
+    pub from_y: usize,
+    pub to_x: usize,
+    pub to_y: usize,
+    pub requested: i32,
+    pub direction: i32,
+    pub backtrack: bool,
+}
+
+impl From<&MoveRecord> for FFIMoveRecord {
+    fn from(record: &MoveRecord) -> Self {
+        FFIMoveRecord {
+            from_x: record.from.x,
+            from_y: record.from.y,
+            to_x: record.to.x,
+            to_y: record.to.y,
+            requested: record.requested as i32,
+            direction: record.direction as i32,
+            backtrack: record.backtrack,
+        }
+    }
+}
+
+/// Reverses the most recent user move.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+///
+/// # Returns
+///
+/// `true` if a move was undone, or `false` if the grid pointer is null or there are no moves to undo.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_undo(grid: *mut Grid) -> bool {
+    if grid.is_null() {
+        return false;
+    }
+    let grid = unsafe { &mut *grid };
+    grid.undo().is_ok()
+}
+
+/// Re-applies the most recently undone user move.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+///
+/// # Returns
+///
+/// `true` if a move was redone, or `false` if the grid pointer is null or there are no undone moves.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_redo(grid: *mut Grid) -> bool {
+    if grid.is_null() {
+        return false;
+    }
+    let grid = unsafe { &mut *grid };
+    grid.redo().is_ok()
+}
+
+/// Returns the number of user moves in the grid's move history, or 0 if the grid pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_move_count(grid: *const Grid) -> usize {
+    if grid.is_null() {
+        return 0;
+    }
+    let grid = unsafe { &*grid };
+    grid.move_history().len()
+}
+
+/// Retrieves the user moves made so far, in the order they were taken.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `length`: A pointer to a `usize` where the number of moves will be stored.
+///
+/// # Returns
+///
+/// A pointer to an array of `FFIMoveRecord` to be released with `mazer_free_moves`, or a null pointer
+/// if either pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_moves(grid: *const Grid, length: *mut usize) -> *mut FFIMoveRecord {
+    if grid.is_null() || length.is_null() {
+        return ptr::null_mut();
+    }
+    let grid = unsafe { &*grid };
+    let moves: Vec<FFIMoveRecord> = grid.move_history().iter().map(FFIMoveRecord::from).collect();
+    unsafe {
+        *length = moves.len();
+    }
+    Box::into_raw(moves.into_boxed_slice()) as *mut FFIMoveRecord
+}
+
+/// Frees an array of `FFIMoveRecord` previously returned by `mazer_get_moves`.
+///
+/// # Parameters
+///
+/// - `ptr`: A pointer to the array of `FFIMoveRecord` to be freed.
+/// - `length`: The number of `FFIMoveRecord` elements in the array.
+#[no_mangle]
+pub extern "C" fn mazer_free_moves(ptr: *mut FFIMoveRecord, length: usize) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let slice: *mut [FFIMoveRecord] = std::ptr::slice_from_raw_parts_mut(ptr, length);
+        drop(Box::from_raw(slice));
+    }
+}
+
+/// Kind of an `FFIGridEvent` reporting that a passage was opened between two cells.
+pub const MAZER_EVENT_CELL_LINKED: i32 = 0;
+/// Kind of an `FFIGridEvent` reporting that the passage between two cells was closed.
+pub const MAZER_EVENT_CELL_UNLINKED: i32 = 1;
+/// Kind of an `FFIGridEvent` reporting that the active cell moved.
+pub const MAZER_EVENT_ACTIVE_MOVED: i32 = 2;
+/// Kind of an `FFIGridEvent` reporting that a cell joined or left the user's trail.
+pub const MAZER_EVENT_VISITED_CHANGED: i32 = 3;
+
+/// Representation of a change to the maze for the FFI layer, as logged by `Grid::drain_events`.
+///
+/// Fields:
+/// - `kind`: One of the `MAZER_EVENT_*` constants.
+/// - `x`, `y`: The coordinates of the first cell linked or unlinked, the cell moved from, or the cell whose
+///   visited state changed.
+/// - `other_x`, `other_y`: The coordinates of the second cell linked or unlinked, or the cell moved to; the
+///   same as `x`, `y` for a visited change.
+/// - `is_visited`: For a visited change, whether the cell is now on the user's trail.
+#[repr(C)]
+pub struct FFIGridEvent {
+    pub kind: i32,
+    pub x: usize,
+    pub y: usize,
+    pub other_x: usize,
+    pub other_y: usize,
+    pub is_visited: bool,
+}
+
+impl From<&GridEvent> for FFIGridEvent {
+    fn from(event: &GridEvent) -> Self {
+        let (kind, first, second, is_visited) = match *event {
+            GridEvent::CellLinked { a, b } => (MAZER_EVENT_CELL_LINKED, a, b, false),
+            GridEvent::CellUnlinked { a, b } => (MAZER_EVENT_CELL_UNLINKED, a, b, false),
+            GridEvent::ActiveMoved { from, to } => (MAZER_EVENT_ACTIVE_MOVED, from, to, false),
+            GridEvent::VisitedChanged { coords, is_visited } => (MAZER_EVENT_VISITED_CHANGED, coords, coords, is_visited),
+        };
+        FFIGridEvent { kind, x: first.x, y: first.y, other_x: second.x, other_y: second.y, is_visited }
+    }
+}
+
+/// Takes the changes made to the maze since the previous call, oldest first, so user interfaces can update
+/// just the cells affected. Changes are logged from the first call on, which returns none.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `length`: A pointer to a `usize` where the number of events will be stored.
+///
+/// # Returns
+///
+/// A pointer to an array of `FFIGridEvent` to be released with `mazer_free_events`, or a null pointer if
+/// either pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_drain_events(grid: *mut Grid, length: *mut usize) -> *mut FFIGridEvent {
+    if grid.is_null() || length.is_null() {
+        return ptr::null_mut();
+    }
+    let grid = unsafe { &mut *grid };
+    let events: Vec<FFIGridEvent> = grid.drain_events().iter().map(FFIGridEvent::from).collect();
+    unsafe {
+        *length = events.len();
+    }
+    Box::into_raw(events.into_boxed_slice()) as *mut FFIGridEvent
+}
+
+/// Frees an array of `FFIGridEvent` previously returned by `mazer_drain_events`.
+///
+/// # Parameters
+///
+/// - `ptr`: A pointer to the array of `FFIGridEvent` to be freed.
+/// - `length`: The number of `FFIGridEvent` elements in the array.
+#[no_mangle]
+pub extern "C" fn mazer_free_events(ptr: *mut FFIGridEvent, length: usize) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let slice: *mut [FFIGridEvent] = std::ptr::slice_from_raw_parts_mut(ptr, length);
+        drop(Box::from_raw(slice));
+    }
+}
+
+/// Representation of a cell's coordinates for the FFI layer.
+#[repr(C)]
+pub struct FFICoordinates {
+    pub x: usize,
+    pub y: usize,
+}
+
+/// Retrieves the cells of the solution path, in order from the start to the goal, so clients can animate
+/// the path once the user finishes.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `length`: A pointer to a `usize` where the number of cells on the path will be stored.
+///
+/// # Returns
+///
+/// A pointer to an array of `FFICoordinates` to be released with `mazer_free_coordinates`, or a null
+/// pointer if either pointer is null. The array is empty when the goal is unreachable.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_solution_path(grid: *const Grid, length: *mut usize) -> *mut FFICoordinates {
+    if grid.is_null() || length.is_null() {
+        return ptr::null_mut();
+    }
+    let grid = unsafe { &*grid };
+    let path: Vec<FFICoordinates> = grid
+        .solution_path_order()
+        .into_iter()
+        .map(|coords| FFICoordinates { x: coords.x, y: coords.y })
+        .collect();
+    unsafe {
+        *length = path.len();
+    }
+    Box::into_raw(path.into_boxed_slice()) as *mut FFICoordinates
+}
+
+/// Sets the cost of entering a cell in weighted pathfinding, such as a higher cost for mud or water.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `x`, `y`: The coordinates of the cell.
+/// - `weight`: The cost of entering the cell, or 0 to return it to the default cost of 1.
+///
+/// # Returns
+///
+/// `true` if the weight was set, or `false` if the grid pointer is null or the coordinates are not a cell of
+/// the maze.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_set_cell_weight(grid: *mut Grid, x: usize, y: usize, weight: u32) -> bool {
+    if grid.is_null() {
+        return false;
+    }
+    let grid = unsafe { &mut *grid };
+    grid.set_weight(Coordinates { x, y }, Some(weight).filter(|&weight| weight > 0)).is_ok()
+}
+
+/// Retrieves the cells of the lowest cost path from the start to the goal, where entering a cell costs its
+/// weight, in order from the start to the goal.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `length`: A pointer to a `usize` where the number of cells on the path will be stored.
+///
+/// # Returns
+///
+/// A pointer to an array of `FFICoordinates` to be released with `mazer_free_coordinates`, or a null
+/// pointer if either pointer is null. The array is empty when the goal is unreachable.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_weighted_solution_path(grid: *const Grid, length: *mut usize) -> *mut FFICoordinates {
+    if grid.is_null() || length.is_null() {
+        return ptr::null_mut();
+    }
+    let grid = unsafe { &*grid };
+    let mut path: Vec<(Coordinates, u32)> = grid
+        .get_path_to_weighted(grid.start_coords.x, grid.start_coords.y, grid.goal_coords.x, grid.goal_coords.y)
+        .map(|path| path.into_iter().collect())
+        .unwrap_or_default();
+    path.sort_by_key(|&(_, cost)| cost);
+    let path: Vec<FFICoordinates> = path
+        .into_iter()
+        .map(|(coords, _)| FFICoordinates { x: coords.x, y: coords.y })
+        .collect();
+    unsafe {
+        *length = path.len();
+    }
+    Box::into_raw(path.into_boxed_slice()) as *mut FFICoordinates
+}
+
+/// Frees an array of `FFICoordinates` previously returned by `mazer_get_solution_path`,
+/// `mazer_get_weighted_solution_path` or `mazer_move_until_junction`.
+///
+/// # Parameters
+///
+/// - `ptr`: A pointer to the array of `FFICoordinates` to be freed.
+/// - `length`: The number of `FFICoordinates` elements in the array.
+#[no_mangle]
+pub extern "C" fn mazer_free_coordinates(ptr: *mut FFICoordinates, length: usize) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let slice: *mut [FFICoordinates] = std::ptr::slice_from_raw_parts_mut(ptr, length);
+        drop(Box::from_raw(slice));
+    }
+}
+
+/// Representation of a maze difficulty report for the FFI layer.
+///
+/// Fields mirror `DifficultyReport`, with `level` reported as 0 = Easy, 1 = Medium, 2 = Hard.
+#[repr(C)]
+pub struct FFIDifficultyReport {
+    pub solution_length: usize,
+    pub dead_ends: usize,
+    pub junctions: usize,
+    pub decision_points: usize,
+    pub branching_factor: f64,
+    pub average_corridor_length: f64,
+    pub score: f64,
+    pub level: i32,
+}
+
+impl From<&DifficultyReport> for FFIDifficultyReport {
+    fn from(report: &DifficultyReport) -> Self {
+        FFIDifficultyReport {
+            solution_length: report.solution_length,
+            dead_ends: report.dead_ends,
+            junctions: report.junctions,
+            decision_points: report.decision_points,
+            branching_factor: report.branching_factor,
+            average_corridor_length: report.average_corridor_length,
+            score: report.score,
+            level: report.level as i32,
+        }
+    }
+}
+
+/// Computes the difficulty metrics of the maze.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `report`: A pointer to an `FFIDifficultyReport` which will be filled in with the maze's metrics.
+///
+/// # Returns
+///
+/// `true` if the report was written, or `false` if either pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_difficulty_report(grid: *const Grid, report: *mut FFIDifficultyReport) -> bool {
+    if grid.is_null() || report.is_null() {
+        return false;
+    }
+    let grid = unsafe { &*grid };
+    unsafe {
+        *report = FFIDifficultyReport::from(&grid.difficulty());
+    }
+    true
+}
+
+/// Computes the maze's difficulty score.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+///
+/// # Returns
+///
+/// The score, from 0.0 (trivial) through 100.0 (hardest), or -1.0 if the grid pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_difficulty(grid: *const Grid) -> f64 {
+    if grid.is_null() {
+        return -1.0;
+    }
+    let grid = unsafe { &*grid };
+    grid.difficulty().score
+}
+
+/// Computes the maze's statistics and difficulty as JSON, an object holding `par_moves` (the fewest moves
+/// from the start to the goal), `difficulty` (the `DifficultyReport`, with its `level` label) and `stats`
+/// (the `MazeStats`).
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+///
+/// # Returns
+///
+/// A null-terminated JSON string to be released with `mazer_free_string`, or a null pointer if the grid
+/// pointer is null or serialization fails.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_stats_json(grid: *const Grid) -> *mut c_char {
+    if grid.is_null() {
+        return ptr::null_mut();
+    }
+    let grid = unsafe { &*grid };
+    match serde_json::to_string(&MazeReport::from_grid(grid)) {
+        Ok(json) => CString::new(json).map(CString::into_raw).unwrap_or(ptr::null_mut()),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// C-compatible snapshot of a maze solving session's telemetry.
+///
+/// Fields mirror `SessionStats`, with the elapsed time reported in milliseconds.
+#[repr(C)]
+pub struct FFISessionStats {
+    pub elapsed_ms: u64,
+    pub moves: usize,
+    pub wrong_turns: usize,
+    pub backtracks: usize,
+    pub paused: bool,
+}
+
+impl From<&SessionStats> for FFISessionStats {
+    fn from(stats: &SessionStats) -> Self {
+        FFISessionStats {
+            elapsed_ms: stats.elapsed.as_millis() as u64,
+            moves: stats.moves,
+            wrong_turns: stats.wrong_turns,
+            backtracks: stats.backtracks,
+            paused: stats.paused,
+        }
+    }
+}
+
+/// Starts tracking elapsed time, moves, wrong turns and backtracks for the user's attempt at solving the
+/// maze, replacing any previous session.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+///
+/// # Returns
+///
+/// `true` if the session was started, or `false` if the grid pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_start_session(grid: *mut Grid) -> bool {
+    if grid.is_null() {
+        return false;
+    }
+    let grid = unsafe { &mut *grid };
+    grid.start_session();
+    true
+}
+
+/// Pauses the session's clock, e.g. while the app is in the background.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+///
+/// # Returns
+///
+/// `true` if the clock was paused, or `false` if the grid pointer is null or no session has been started.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_pause_session(grid: *mut Grid) -> bool {
+    if grid.is_null() {
+        return false;
+    }
+    let grid = unsafe { &mut *grid };
+    grid.pause();
+    grid.session.is_some()
+}
+
+/// Resumes the session's clock after mazer_pause_session.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+///
+/// # Returns
+///
+/// `true` if the clock was resumed, or `false` if the grid pointer is null or no session has been started.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_resume_session(grid: *mut Grid) -> bool {
+    if grid.is_null() {
+        return false;
+    }
+    let grid = unsafe { &mut *grid };
+    grid.resume();
+    grid.session.is_some()
+}
+
+/// Retrieves the telemetry of the current session.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `stats`: A pointer to an `FFISessionStats` which will be filled in with the session's telemetry.
+///
+/// # Returns
+///
+/// `true` if the stats were written, or `false` if either pointer is null or no session has been started.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_session_stats(grid: *const Grid, stats: *mut FFISessionStats) -> bool {
+    if grid.is_null() || stats.is_null() {
+        return false;
+    }
+    let grid = unsafe { &*grid };
+    match grid.stats() {
+        Some(session_stats) => {
+            unsafe {
+                *stats = FFISessionStats::from(&session_stats);
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+// Leaks a list of names as an array of null-terminated C strings, writing the array's length into `length`.
+fn into_c_string_list(names: Vec<&str>, length: *mut usize) -> *mut *const c_char {
+    let raw: Vec<*const c_char> = names
+        .into_iter()
+        .map(|name| CString::new(name).unwrap().into_raw() as *const c_char)
+        .collect();
+    unsafe {
+        *length = raw.len();
+    }
+    Box::into_raw(raw.into_boxed_slice()) as *mut *const c_char
+}
+
+/// Lists the names of all supported maze types.
+///
+/// Names are returned in a stable order, with newly supported maze types appended to the end,
+/// so client UIs can populate selection lists without hardcoding maze types.
+///
+/// # Parameters
+///
+/// - `length`: A pointer to a `usize` variable where the number of names will be stored.
+///
+/// # Returns
+///
+/// A pointer to an array of null-terminated C strings, or a null pointer if `length` is null.
+/// The array must be released with `mazer_free_string_list`.
+#[no_mangle]
+pub extern "C" fn mazer_list_maze_types(length: *mut usize) -> *mut *const c_char {
+    if length.is_null() {
+        return ptr::null_mut();
+    }
+    into_c_string_list(MazeType::all().iter().map(|maze_type| maze_type.name()).collect(), length)
+}
+
+/// Lists the names of all supported maze generation algorithms.
+///
+/// Names are returned in a stable order, with newly supported algorithms appended to the end,
+/// so client UIs can populate selection lists without hardcoding algorithms.
+///
+/// # Parameters
+///
+/// - `length`: A pointer to a `usize` variable where the number of names will be stored.
+///
+/// # Returns
+///
+/// A pointer to an array of null-terminated C strings, or a null pointer if `length` is null.
+/// The array must be released with `mazer_free_string_list`.
+#[no_mangle]
+pub extern "C" fn mazer_list_algorithms(length: *mut usize) -> *mut *const c_char {
+    if length.is_null() {
+        return ptr::null_mut();
+    }
+    into_c_string_list(MazeAlgorithm::all().iter().map(|algorithm| algorithm.name()).collect(), length)
+}
+
+/// Frees an array of C strings previously returned by `mazer_list_maze_types` or `mazer_list_algorithms`.
+///
+/// # Parameters
+///
+/// - `ptr`: A pointer to the array of C strings to be freed.
+/// - `length`: The number of strings in the array.
+#[no_mangle]
+pub extern "C" fn mazer_free_string_list(ptr: *mut *const c_char, length: usize) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let slice: *mut [*const c_char] = std::ptr::slice_from_raw_parts_mut(ptr, length);
+        let strings = Box::from_raw(slice);
+        for &string in strings.iter() {
+            if !string.is_null() {
+                let _ = CString::from_raw(string as *mut c_char);
+            }
+        }
+    }
+}
+
+/// Checks whether a maze generation algorithm can generate mazes of a maze type.
+///
+/// Lets client UIs grey out unsupported algorithm and maze type combinations before submitting a request.
+///
+/// # Parameters
+///
+/// - `algorithm`: A null-terminated C string naming the algorithm, as returned by `mazer_list_algorithms`.
+/// - `maze_type`: A null-terminated C string naming the maze type, as returned by `mazer_list_maze_types`.
+///
+/// # Returns
+///
+/// `true` if the algorithm supports the maze type, or `false` if it does not, either pointer is null,
+/// or either name is not recognized.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_algorithm_supports(algorithm: *const c_char, maze_type: *const c_char) -> bool {
+    if algorithm.is_null() || maze_type.is_null() {
+        return false;
+    }
+    let (algorithm, maze_type) = unsafe { (CStr::from_ptr(algorithm), CStr::from_ptr(maze_type)) };
+    match (algorithm.to_str(), maze_type.to_str()) {
+        (Ok(algorithm), Ok(maze_type)) => match (algorithm.parse::<MazeAlgorithm>(), maze_type.parse::<MazeType>()) {
+            (Ok(algorithm), Ok(maze_type)) => algorithm.supports(maze_type),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Validates a JSON maze request without generating the maze.
+///
+/// # Parameters
+///
+/// - `request_json`: A null-terminated C string containing the JSON request.
+///
+/// # Returns
+///
+/// `true` if the request is well formed and can be generated, such as its algorithm supporting its maze type
+/// and its start and goal lying within the maze, or `false` otherwise.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_validate_request(request_json: *const c_char) -> bool {
+    if request_json.is_null() {
+        return false;
+    }
+    let c_str = unsafe { CStr::from_ptr(request_json) };
+    match c_str.to_str() {
+        Ok(json) => serde_json::from_str::<MazeRequest>(json).map(|request| request.validate().is_ok()).unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
+/// Retrieves the width and height of the maze, in cells.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `width`: A pointer to a `usize` where the width will be stored.
+/// - `height`: A pointer to a `usize` where the height will be stored.
+///
+/// # Returns
+///
+/// `true` if the dimensions were written, or `false` if any pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_dimensions(grid: *const Grid, width: *mut usize, height: *mut usize) -> bool {
+    if grid.is_null() || width.is_null() || height.is_null() {
+        return false;
+    }
+    let grid = unsafe { &*grid };
+    unsafe {
+        *width = grid.width;
+        *height = grid.height;
+    }
+    true
+}
+
+/// Retrieves the name of the maze's type, as listed by `mazer_list_maze_types`.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+///
+/// # Returns
+///
+/// A pointer to a static null-terminated C string, which must not be freed, or a null pointer if the grid
+/// pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_maze_type(grid: *const Grid) -> *const c_char {
+    if grid.is_null() {
+        return ptr::null();
+    }
+    let grid = unsafe { &*grid };
+    let name: &'static CStr = match grid.maze_type {
+        MazeType::Orthogonal => c"Orthogonal",
+        MazeType::Sigma => c"Sigma",
+        MazeType::Delta => c"Delta",
+        MazeType::Upsilon => c"Upsilon",
+        MazeType::Rhombic => c"Rhombic",
+    };
+    name.as_ptr()
+}
+
+/// Retrieves the random seed the maze was generated with.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+///
+/// # Returns
+///
+/// The maze's seed, or 0 if the grid pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_seed(grid: *const Grid) -> u64 {
+    if grid.is_null() {
+        return 0;
+    }
+    let grid = unsafe { &*grid };
+    grid.seed
+}
+
+/// Computes a hash of the maze's shape and passages, ignoring its start, goal and the user's progress, which is
+/// stable across runs and platforms, so that generated mazes can be deduplicated and referred to by id.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+///
+/// # Returns
+///
+/// The maze's canonical hash, or 0 if the grid pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_canonical_hash(grid: *const Grid) -> u64 {
+    if grid.is_null() {
+        return 0;
+    }
+    let grid = unsafe { &*grid };
+    grid.canonical_hash()
+}
+
+/// Retrieves the coordinates of the maze's start cell.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `start`: A pointer to an `FFICoordinates` which will be filled in with the start cell's coordinates.
+///
+/// # Returns
+///
+/// `true` if the coordinates were written, or `false` if either pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_start(grid: *const Grid, start: *mut FFICoordinates) -> bool {
+    if grid.is_null() || start.is_null() {
+        return false;
+    }
+    let grid = unsafe { &*grid };
+    unsafe {
+        *start = FFICoordinates { x: grid.start_coords.x, y: grid.start_coords.y };
+    }
+    true
+}
+
+/// Retrieves the coordinates of the maze's goal cell.
+///
+/// # Parameters
+///
+/// - `grid`: A pointer to the `Grid` instance.
+/// - `goal`: A pointer to an `FFICoordinates` which will be filled in with the goal cell's coordinates.
+///
+/// # Returns
+///
+/// `true` if the coordinates were written, or `false` if either pointer is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_get_goal(grid: *const Grid, goal: *mut FFICoordinates) -> bool {
+    if grid.is_null() || goal.is_null() {
+        return false;
+    }
+    let grid = unsafe { &*grid };
+    unsafe {
+        *goal = FFICoordinates { x: grid.goal_coords.x, y: grid.goal_coords.y };
+    }
+    true
+}
+
+/// Status returned by handle-based functions when the call succeeded.
+pub const MAZER_HANDLE_OK: i32 = 0;
+/// Status returned by handle-based functions when the handle was never issued.
+pub const MAZER_HANDLE_INVALID: i32 = -1;
+/// Status returned by handle-based functions when the handle's maze has been destroyed.
+pub const MAZER_HANDLE_STALE: i32 = -2;
+/// Status returned by handle-based functions when the handle is valid but the operation failed.
+pub const MAZER_HANDLE_FAILED: i32 = -3;
+
+fn handle_status(error: &Error) -> i32 {
+    record_error(error);
+    match error {
+        Error::InvalidHandle { .. } => MAZER_HANDLE_INVALID,
+        Error::StaleHandle { .. } => MAZER_HANDLE_STALE,
+        _ => MAZER_HANDLE_FAILED,
+    }
+}
+
+/// Generates a maze from a JSON request, like `mazer_generate_maze`, but returns an integer handle rather
+/// than a raw pointer.
+///
+/// Handle-based functions validate the handle on every call, so using a destroyed maze or destroying it
+/// twice reports an error instead of crashing the host app. They may also be called from any thread, as
+/// each maze is locked for the duration of a call; the pointer-based functions, by contrast, must not be
+/// called concurrently on the same maze.
+///
+/// # Parameters
+///
+/// - `request_json`: A null-terminated C string containing the JSON request.
+///
+/// # Returns
+///
+/// A non-zero handle to the generated maze, to be released with `mazer_handle_destroy`, or 0 on failure.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_handle_generate_maze(request_json: *const c_char) -> Handle {
+    trace_span!("ffi", function = "mazer_handle_generate_maze");
+    if request_json.is_null() {
+        return 0;
+    }
+    let c_str = unsafe { CStr::from_ptr(request_json) };
+    let Ok(json) = c_str.to_str() else {
+        return 0;
+    };
+    match Grid::try_from(json) {
+        Ok(grid) => handle::registry().insert(grid),
+        Err(err) => {
+            record_error(&err);
+            0
+        }
+    }
+}
+
+/// Destroys the maze referred to by a handle, invalidating the handle.
+///
+/// # Parameters
+///
+/// - `handle`: A handle returned by `mazer_handle_generate_maze`.
+///
+/// # Returns
+///
+/// `MAZER_HANDLE_OK`, `MAZER_HANDLE_INVALID` if the handle was never issued, or `MAZER_HANDLE_STALE` if the
+/// maze was already destroyed.
+#[no_mangle]
+pub extern "C" fn mazer_handle_destroy(handle: Handle) -> i32 {
+    match handle::registry().remove(handle) {
+        Ok(_) => MAZER_HANDLE_OK,
+        Err(error) => handle_status(&error),
+    }
+}
+
+/// Checks whether a handle refers to a maze which has not been destroyed.
+///
+/// # Parameters
+///
+/// - `handle`: A handle returned by `mazer_handle_generate_maze`.
+///
+/// # Returns
+///
+/// `true` if the handle is valid, or `false` otherwise.
+#[no_mangle]
+pub extern "C" fn mazer_handle_is_valid(handle: Handle) -> bool {
+    handle::registry().get(handle).is_ok()
+}
+
+/// Retrieves the cells of the maze referred to by a handle, like `mazer_get_cells`.
+///
+/// # Parameters
+///
+/// - `handle`: A handle returned by `mazer_handle_generate_maze`.
+/// - `length`: A pointer to a `usize` variable where the number of cells will be stored.
+///
+/// # Returns
+///
+/// A pointer to an array of `FFICell` structures to be released with `mazer_free_cells`, or a null pointer
+/// if the handle is not valid or `length` is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_handle_get_cells(handle: Handle, length: *mut usize) -> *mut FFICell {
+    if length.is_null() {
+        return ptr::null_mut();
+    }
+    let Ok(ffi_cells) = handle::with_grid(handle, |grid| grid.iter_cells().map(FFICell::from).collect::<Vec<FFICell>>()) else {
+        return ptr::null_mut();
+    };
+    unsafe {
+        *length = ffi_cells.len();
+    }
+    Box::into_raw(ffi_cells.into_boxed_slice()) as *mut FFICell
+}
+
+/// Performs a move in the maze referred to by a handle, like `mazer_make_move`.
+///
+/// # Parameters
+///
+/// - `handle`: A handle returned by `mazer_handle_generate_maze`.
+/// - `direction`: A null-terminated C string indicating the move direction.
+///
+/// # Returns
+///
+/// `MAZER_HANDLE_OK` if the move was made, `MAZER_HANDLE_INVALID` or `MAZER_HANDLE_STALE` for a bad handle,
+/// or `MAZER_HANDLE_FAILED` if the direction is unrecognized or the move is unavailable.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_handle_make_move(handle: Handle, direction: *const c_char) -> i32 {
+    trace_span!("ffi", function = "mazer_handle_make_move");
+    let direction = if direction.is_null() {
+        None
+    } else {
+        unsafe { CStr::from_ptr(direction) }.to_str().ok().and_then(|name| Direction::try_from(name).ok())
+    };
+    match handle::with_grid(handle, |grid| direction.map(|direction| grid.make_move(direction))) {
+        Ok(Some(Ok(_))) => MAZER_HANDLE_OK,
+        Ok(Some(Err(error))) => handle_status(&error),
+        Ok(None) => MAZER_HANDLE_FAILED,
+        Err(error) => handle_status(&error),
+    }
+}
+
+/// Reverses the most recent user move in the maze referred to by a handle, like `mazer_undo`.
+///
+/// # Parameters
+///
+/// - `handle`: A handle returned by `mazer_handle_generate_maze`.
+///
+/// # Returns
+///
+/// `MAZER_HANDLE_OK` if a move was undone, `MAZER_HANDLE_INVALID` or `MAZER_HANDLE_STALE` for a bad handle,
+/// or `MAZER_HANDLE_FAILED` if there are no moves to undo.
+#[no_mangle]
+pub extern "C" fn mazer_handle_undo(handle: Handle) -> i32 {
+    match handle::with_grid(handle, |grid| grid.undo()) {
+        Ok(Ok(_)) => MAZER_HANDLE_OK,
+        Ok(Err(error)) | Err(error) => handle_status(&error),
+    }
+}
+
+/// Re-applies the most recently undone user move in the maze referred to by a handle, like `mazer_redo`.
+///
+/// # Parameters
+///
+/// - `handle`: A handle returned by `mazer_handle_generate_maze`.
+///
+/// # Returns
+///
+/// `MAZER_HANDLE_OK` if a move was redone, `MAZER_HANDLE_INVALID` or `MAZER_HANDLE_STALE` for a bad handle,
+/// or `MAZER_HANDLE_FAILED` if there are no undone moves.
+#[no_mangle]
+pub extern "C" fn mazer_handle_redo(handle: Handle) -> i32 {
+    match handle::with_grid(handle, |grid| grid.redo()) {
+        Ok(Ok(_)) => MAZER_HANDLE_OK,
+        Ok(Err(error)) | Err(error) => handle_status(&error),
+    }
+}
+
+/// Suggests the next move toward the goal in the maze referred to by a handle, like `mazer_get_hint`.
+///
+/// # Parameters
+///
+/// - `handle`: A handle returned by `mazer_handle_generate_maze`.
+///
+/// # Returns
+///
+/// The numeric code of the hinted direction (see `mazer_get_hint`), `MAZER_HANDLE_INVALID` or
+/// `MAZER_HANDLE_STALE` for a bad handle, or `MAZER_HANDLE_FAILED` if no hint is available.
+#[no_mangle]
+pub extern "C" fn mazer_handle_get_hint(handle: Handle) -> i32 {
+    match handle::with_grid(handle, |grid| grid.hint()) {
+        Ok(Some(direction)) => direction as i32,
+        Ok(None) => MAZER_HANDLE_FAILED,
+        Err(error) => handle_status(&error),
+    }
+}
+
+/// Retrieves the solution path of the maze referred to by a handle, like `mazer_get_solution_path`.
+///
+/// # Parameters
+///
+/// - `handle`: A handle returned by `mazer_handle_generate_maze`.
+/// - `length`: A pointer to a `usize` where the number of cells on the path will be stored.
+///
+/// # Returns
+///
+/// A pointer to an array of `FFICoordinates` to be released with `mazer_free_coordinates`, or a null
+/// pointer if the handle is not valid or `length` is null. The array is empty when the goal is unreachable.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_handle_get_solution_path(handle: Handle, length: *mut usize) -> *mut FFICoordinates {
+    if length.is_null() {
+        return ptr::null_mut();
+    }
+    let path = handle::with_grid(handle, |grid| {
+        grid.solution_path_order().into_iter().map(|coords| FFICoordinates { x: coords.x, y: coords.y }).collect::<Vec<FFICoordinates>>()
+    });
+    let path = match path {
+        Ok(path) => path,
+        Err(error) => {
+            record_error(&error);
+            return ptr::null_mut();
+        }
+    };
+    unsafe {
+        *length = path.len();
+    }
+    Box::into_raw(path.into_boxed_slice()) as *mut FFICoordinates
+}
+
+/// Serializes the maze referred to by a handle to a JSON string, like `mazer_serialize_maze`.
+///
+/// # Parameters
+///
+/// - `handle`: A handle returned by `mazer_handle_generate_maze`.
+///
+/// # Returns
+///
+/// A null-terminated JSON string to be released with `mazer_free_string`, or a null pointer if the handle is
+/// not valid or serialization fails.
+#[no_mangle]
+pub extern "C" fn mazer_handle_serialize_maze(handle: Handle) -> *mut c_char {
+    trace_span!("ffi", function = "mazer_handle_serialize_maze");
+    match handle::with_grid(handle, |grid| grid.to_json().map_err(Error::from)) {
+        Ok(Ok(json)) => CString::new(json).map(CString::into_raw).unwrap_or(ptr::null_mut()),
+        Ok(Err(error)) | Err(error) => {
+            record_error(&error);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the number of generation steps captured for the maze referred to by a handle, like
+/// `mazer_get_generation_steps_count`, or 0 if the handle is not valid or no steps were captured.
+#[no_mangle]
+pub extern "C" fn mazer_handle_get_generation_steps_count(handle: Handle) -> usize {
+    handle::with_grid(handle, |grid| grid.generation_steps.as_ref().map_or(0, |steps| steps.len())).unwrap_or(0)
+}
+
+/// Retrieves the cells of a generation step of the maze referred to by a handle, like
+/// `mazer_get_generation_step_cells`.
+///
+/// # Parameters
+///
+/// - `handle`: A handle returned by `mazer_handle_generate_maze`.
+/// - `step_index`: The index of the generation step.
+/// - `length`: A pointer to a `usize` where the number of cells will be stored.
+///
+/// # Returns
+///
+/// A pointer to an array of `FFICell` structures to be released with `mazer_free_cells`, or a null pointer
+/// if the handle is not valid, `length` is null, or there is no such step.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_handle_get_generation_step_cells(handle: Handle, step_index: usize, length: *mut usize) -> *mut FFICell {
+    trace_span!("ffi", function = "mazer_handle_get_generation_step_cells");
+    if length.is_null() {
+        return ptr::null_mut();
+    }
+    let step = handle::with_grid(handle, |grid| {
+        let step = grid.generation_steps.as_ref()?.get(step_index)?;
+        Some(step.iter_cells().map(FFICell::from).collect::<Vec<FFICell>>())
+    });
+    let Ok(Some(ffi_cells)) = step else {
+        return ptr::null_mut();
+    };
+    unsafe {
+        *length = ffi_cells.len();
+    }
+    Box::into_raw(ffi_cells.into_boxed_slice()) as *mut FFICell
+}
+
+/// Starts generating a maze from a JSON request on a background thread, so large mazes don't block the
+/// calling (e.g. UI) thread. Poll the generation with `mazer_generation_progress`, and collect the maze
+/// with `mazer_generation_finish`, which must be called exactly once for every generation started.
+///
+/// # Parameters
+///
+/// - `request_json`: A null-terminated C string containing the JSON request.
+///
+/// # Returns
+///
+/// A pointer to the running generation, or a null pointer if the request is not valid JSON.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_generate_maze_async(request_json: *const c_char) -> *mut BackgroundGeneration {
+    trace_span!("ffi", function = "mazer_generate_maze_async");
+    if request_json.is_null() {
+        return ptr::null_mut();
+    }
+    let c_str = unsafe { CStr::from_ptr(request_json) };
+    match c_str.to_str().ok().and_then(|json| serde_json::from_str::<MazeRequest>(json).ok()) {
+        Some(request) => Box::into_raw(Box::new(BackgroundGeneration::spawn(request))),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Retrieves the progress of a maze being generated in the background. May be called from any thread.
+///
+/// # Parameters
+///
+/// - `generation`: A pointer returned by `mazer_generate_maze_async`.
+///
+/// # Returns
+///
+/// The fraction of the maze generated so far, from 0.0 through 1.0, which is 1.0 only once generation has
+/// finished. Returns 0.0 if `generation` is null.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_generation_progress(generation: *const BackgroundGeneration) -> f64 {
+    if generation.is_null() {
+        return 0.0;
+    }
+    unsafe { &*generation }.progress().fraction()
+}
+
+/// Asks a maze being generated in the background to stop. `mazer_generation_finish` must still be called
+/// to release the generation.
+///
+/// # Parameters
+///
+/// - `generation`: A pointer returned by `mazer_generate_maze_async`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_generation_cancel(generation: *const BackgroundGeneration) {
+    if generation.is_null() {
+        return;
+    }
+    unsafe { &*generation }.cancel();
+}
+
+/// Waits for a maze being generated in the background to finish, and releases the generation. Returns
+/// immediately once `mazer_generation_progress` has reached 1.0.
+///
+/// # Parameters
+///
+/// - `generation`: A pointer returned by `mazer_generate_maze_async`, which is invalid after this call.
+///
+/// # Returns
+///
+/// A pointer to the generated `Grid` (to be released with `mazer_destroy`), or a null pointer if generation
+/// failed or was cancelled.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn mazer_generation_finish(generation: *mut BackgroundGeneration) -> *mut Grid {
+    trace_span!("ffi", function = "mazer_generation_finish");
+    if generation.is_null() {
+        return ptr::null_mut();
+    }
+    let generation = unsafe { Box::from_raw(generation) };
+    match generation.wait() {
+        Ok(grid) => Box::into_raw(Box::new(grid)),
+        Err(err) => {
+            eprintln!("mazer_generation_finish: Maze generation failed: {:?}", err);
+            record_error(&err);
+            ptr::null_mut()
+        }
     }
 }
 
@@ -350,6 +2469,7 @@ mod tests {
     use super::*;
     use std::collections::{HashSet, HashMap};
     use crate::cell::{CellOrientation, MazeType, Cell, Coordinates};
+    use crate::compact::{Links, Neighbors};
 
     // Helper function to parse a C string to Direction
     fn parse_direction(ptr: *const c_char) -> Direction {
@@ -459,11 +2579,12 @@ mod tests {
         open_walls.push(Direction::Right);
         open_walls.push(Direction::Down);
 
-        let cell = Cell {
+        let mut cell = Cell {
             coords: Coordinates { x: 1, y: 2 },
             maze_type: MazeType::Orthogonal,
-            neighbors_by_direction: neighbors,
-            linked,
+            neighbors_by_direction: Neighbors::new(Coordinates { x: 1, y: 2 }),
+            room_neighbors: Vec::new(),
+            linked: Links::new(Coordinates { x: 1, y: 2 }),
             distance: 10,
             is_start: true,
             is_goal: false,
@@ -472,9 +2593,16 @@ mod tests {
             has_been_visited: false,
             on_solution_path: true,
             orientation: CellOrientation::Normal,
-            open_walls: open_walls,
+            open_walls: open_walls.into_iter().collect(),
             is_square: false,
+            distance_band: None,
+            heat: None,
+            is_discovered: false,
+            border_opening: None,
+            weight: None,
         };
+        cell.set_neighbors(neighbors);
+        cell.set_linked(linked);
 
         let ffi_cell: FFICell = (&cell).into();
 
@@ -498,7 +2626,7 @@ mod tests {
         let expected_linked: HashSet<String> = cell
             .neighbors_by_direction
             .iter()
-            .filter_map(|(k, &v)| {
+            .filter_map(|(k, v)| {
                 if cell.linked.contains(&v) {
                     Some(k.to_string().clone())
                 } else {
@@ -508,6 +2636,22 @@ mod tests {
             .collect();
         assert_eq!(linked_rust, expected_linked);
 
+        let open_walls_rust: HashSet<String> = unsafe {
+            std::slice::from_raw_parts(ffi_cell.open_walls, ffi_cell.open_walls_len)
+                .iter()
+                .map(|&ptr| CStr::from_ptr(ptr).to_string_lossy().into_owned())
+                .collect()
+        };
+        assert_eq!(open_walls_rust, expected_linked);
+        let linked_coords: Vec<(usize, usize)> = unsafe {
+            std::slice::from_raw_parts(ffi_cell.linked_coords, ffi_cell.linked_coords_len)
+                .iter()
+                .map(|coords| (coords.x, coords.y))
+                .collect()
+        };
+        assert_eq!(linked_coords, vec![(2, 2), (1, 3)]);
+        assert_eq!(ffi_cell.version, MAZER_FFI_CELL_VERSION);
+
         // No manual cleanup is necessary.
         // The Drop implementation for FFICell will automatically free all allocated memory.
     }
@@ -608,6 +2752,489 @@ mod tests {
         mazer_destroy(maze_ptr);
     }
 
+    #[test]
+    fn test_mazer_get_hint() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 8,
+            "height": 8,
+            "algorithm": "RecursiveBacktracker",
+            "start": { "x": 0, "y": 0 },
+            "goal": { "x": 7, "y": 7 }
+        }
+        "#;
+        let maze_ptr = mazer_generate_maze(CString::new(json).unwrap().as_ptr());
+        assert!(!maze_ptr.is_null());
+        let code = mazer_get_hint(maze_ptr);
+        let direction = Direction::try_from(code as u32).expect("hint should be a valid direction code");
+        let grid = unsafe { &mut *maze_ptr };
+        grid.make_move(direction).expect("hinted move should succeed");
+        assert!(grid.get_active_cell().unwrap().on_solution_path);
+        assert_eq!(mazer_get_hint(ptr::null()), -1);
+        mazer_destroy(maze_ptr);
+    }
+
+    #[test]
+    fn test_mazer_move_history() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 8,
+            "height": 8,
+            "algorithm": "RecursiveBacktracker",
+            "start": { "x": 0, "y": 0 },
+            "goal": { "x": 7, "y": 7 }
+        }
+        "#;
+        let maze_ptr = mazer_generate_maze(CString::new(json).unwrap().as_ptr());
+        assert!(!maze_ptr.is_null());
+        assert!(!mazer_undo(maze_ptr));
+        for _ in 0..2 {
+            let direction = Direction::try_from(mazer_get_hint(maze_ptr) as u32).unwrap();
+            let direction = CString::new(direction.to_string()).unwrap();
+            assert!(!mazer_make_move(maze_ptr as *mut c_void, direction.as_ptr()).is_null());
+        }
+        assert_eq!(mazer_move_count(maze_ptr), 2);
+
+        let mut length: usize = 0;
+        let moves_ptr = mazer_get_moves(maze_ptr, &mut length);
+        assert_eq!(length, 2);
+        let moves = unsafe { std::slice::from_raw_parts(moves_ptr, length) };
+        assert_eq!((moves[0].from_x, moves[0].from_y), (0, 0));
+        assert_eq!((moves[0].to_x, moves[0].to_y), (moves[1].from_x, moves[1].from_y));
+        mazer_free_moves(moves_ptr, length);
+
+        assert!(mazer_undo(maze_ptr));
+        assert_eq!(mazer_move_count(maze_ptr), 1);
+        assert!(mazer_redo(maze_ptr));
+        assert_eq!(mazer_move_count(maze_ptr), 2);
+        assert!(!mazer_redo(maze_ptr));
+        mazer_destroy(maze_ptr);
+    }
+
+    #[test]
+    fn test_mazer_drain_events() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 8,
+            "height": 8,
+            "algorithm": "RecursiveBacktracker",
+            "start": { "x": 0, "y": 0 },
+            "goal": { "x": 7, "y": 7 }
+        }
+        "#;
+        let maze_ptr = mazer_generate_maze(CString::new(json).unwrap().as_ptr());
+        assert!(!maze_ptr.is_null());
+        let mut length: usize = 1;
+        let events_ptr = mazer_drain_events(maze_ptr, &mut length);
+        assert_eq!(length, 0);
+        mazer_free_events(events_ptr, length);
+
+        let direction = Direction::try_from(mazer_get_hint(maze_ptr) as u32).unwrap();
+        let direction = CString::new(direction.to_string()).unwrap();
+        assert!(!mazer_make_move(maze_ptr as *mut c_void, direction.as_ptr()).is_null());
+        let events_ptr = mazer_drain_events(maze_ptr, &mut length);
+        assert_eq!(length, 2);
+        let events = unsafe { std::slice::from_raw_parts(events_ptr, length) };
+        assert_eq!((events[0].kind, events[0].x, events[0].y), (MAZER_EVENT_ACTIVE_MOVED, 0, 0));
+        assert_eq!(events[1].kind, MAZER_EVENT_VISITED_CHANGED);
+        assert_eq!((events[1].x, events[1].y), (events[0].other_x, events[0].other_y));
+        assert!(events[1].is_visited);
+        mazer_free_events(events_ptr, length);
+
+        assert!(mazer_drain_events(ptr::null_mut(), &mut length).is_null());
+        mazer_destroy(maze_ptr);
+    }
+
+    #[test]
+    fn test_mazer_get_difficulty_report() {
+        let json = r#"
+        {
+            "maze_type": "Delta",
+            "width": 12,
+            "height": 12,
+            "algorithm": "Prims",
+            "start": { "x": 0, "y": 0 },
+            "goal": { "x": 11, "y": 11 }
+        }
+        "#;
+        let maze_ptr = mazer_generate_maze(CString::new(json).unwrap().as_ptr());
+        assert!(!maze_ptr.is_null());
+        let mut report = std::mem::MaybeUninit::<FFIDifficultyReport>::uninit();
+        assert!(mazer_get_difficulty_report(maze_ptr, report.as_mut_ptr()));
+        let report = unsafe { report.assume_init() };
+        let expected = unsafe { &*maze_ptr }.difficulty();
+        assert_eq!(report.solution_length, expected.solution_length);
+        assert_eq!(report.dead_ends, expected.dead_ends);
+        assert_eq!(report.score, expected.score);
+        assert_eq!(report.level, expected.level as i32);
+        assert!(!mazer_get_difficulty_report(maze_ptr, ptr::null_mut()));
+        assert_eq!(mazer_get_difficulty(maze_ptr), expected.score);
+        assert_eq!(mazer_get_difficulty(ptr::null()), -1.0);
+
+        let stats_ptr = mazer_get_stats_json(maze_ptr);
+        assert!(!stats_ptr.is_null());
+        let stats: serde_json::Value = serde_json::from_str(unsafe { CStr::from_ptr(stats_ptr) }.to_str().unwrap()).unwrap();
+        assert_eq!(stats["par_moves"], expected.solution_length - 1);
+        assert_eq!(stats["difficulty"]["dead_ends"], expected.dead_ends);
+        assert_eq!(stats["difficulty"]["level"], expected.level.to_string());
+        assert_eq!(stats["stats"]["total_cells"], 144);
+        mazer_free_string(stats_ptr);
+        assert!(mazer_get_stats_json(ptr::null()).is_null());
+        mazer_destroy(maze_ptr);
+    }
+
+    #[test]
+    fn test_mazer_handles() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 5,
+            "height": 5,
+            "algorithm": "Prims"
+        }
+        "#;
+        let handle = mazer_handle_generate_maze(CString::new(json).unwrap().as_ptr());
+        assert_ne!(handle, 0);
+        assert!(mazer_handle_is_valid(handle));
+        let mut length: usize = 0;
+        let cells_ptr = mazer_handle_get_cells(handle, &mut length);
+        assert_eq!(length, 25);
+        mazer_free_cells(cells_ptr, length);
+
+        let direction = handle::with_grid(handle, |grid| grid.hint()).unwrap().unwrap().to_string();
+        assert_eq!(mazer_handle_make_move(handle, CString::new(direction).unwrap().as_ptr()), MAZER_HANDLE_OK);
+        assert_eq!(mazer_handle_make_move(handle, CString::new("Sideways").unwrap().as_ptr()), MAZER_HANDLE_FAILED);
+        assert_eq!(mazer_handle_undo(handle), MAZER_HANDLE_OK);
+        assert_eq!(mazer_handle_undo(handle), MAZER_HANDLE_FAILED);
+        assert_eq!(mazer_handle_redo(handle), MAZER_HANDLE_OK);
+        assert!(mazer_handle_get_hint(handle) >= 0);
+
+        let path_ptr = mazer_handle_get_solution_path(handle, &mut length);
+        assert!(!path_ptr.is_null() && length > 1);
+        mazer_free_coordinates(path_ptr, length);
+
+        let json_ptr = mazer_handle_serialize_maze(handle);
+        let saved = unsafe { CStr::from_ptr(json_ptr) }.to_str().unwrap().to_string();
+        mazer_free_string(json_ptr);
+        let restored = Grid::from_saved_json(&saved).unwrap();
+        assert_eq!(restored.move_history().len(), 1);
+        assert_eq!(restored.count_edges(), handle::with_grid(handle, |grid| grid.count_edges()).unwrap());
+
+        // no steps were captured for this maze
+        assert_eq!(mazer_handle_get_generation_steps_count(handle), 0);
+        assert!(mazer_handle_get_generation_step_cells(handle, 0, &mut length).is_null());
+        let json = r#"{ "maze_type": "Orthogonal", "width": 4, "height": 4, "algorithm": "Prims", "capture_steps": true }"#;
+        let stepped = mazer_handle_generate_maze(CString::new(json).unwrap().as_ptr());
+        let steps = mazer_handle_get_generation_steps_count(stepped);
+        assert!(steps > 0);
+        let cells_ptr = mazer_handle_get_generation_step_cells(stepped, steps - 1, &mut length);
+        assert_eq!(length, 16);
+        mazer_free_cells(cells_ptr, length);
+        assert!(mazer_handle_get_generation_step_cells(stepped, steps, &mut length).is_null());
+        assert_eq!(mazer_handle_destroy(stepped), MAZER_HANDLE_OK);
+
+        assert_eq!(mazer_handle_destroy(handle), MAZER_HANDLE_OK);
+        assert!(!mazer_handle_is_valid(handle));
+        assert_eq!(mazer_handle_destroy(handle), MAZER_HANDLE_STALE);
+        assert_eq!(mazer_handle_make_move(handle, CString::new("Up").unwrap().as_ptr()), MAZER_HANDLE_STALE);
+        assert!(mazer_handle_get_cells(handle, &mut length).is_null());
+        assert_eq!(mazer_handle_undo(handle), MAZER_HANDLE_STALE);
+        assert_eq!(mazer_handle_get_hint(handle), MAZER_HANDLE_STALE);
+        assert!(mazer_handle_serialize_maze(handle).is_null());
+        assert_eq!(mazer_handle_get_generation_steps_count(handle), 0);
+        assert_eq!(mazer_handle_destroy(0), MAZER_HANDLE_INVALID);
+        assert_eq!(mazer_handle_generate_maze(ptr::null()), 0);
+    }
+
+    #[test]
+    fn test_mazer_last_error() {
+        let json = r#"{ "maze_type": "Orthogonal", "width": 1000, "height": 1000, "algorithm": "AldousBroder" }"#;
+        assert!(mazer_generate_maze(CString::new(json).unwrap().as_ptr()).is_null());
+        assert_eq!(mazer_last_error_code(), 61);
+        assert_eq!(mazer_last_error_category(), MAZER_ERROR_UNSUPPORTED);
+        let message_ptr = mazer_last_error_message();
+        let message = unsafe { CStr::from_ptr(message_ptr) }.to_str().unwrap().to_string();
+        mazer_free_string(message_ptr);
+        assert!(message.starts_with("AldousBroder cannot generate 1000000 cells"), "{}", message);
+
+        let json = r#"{ "maze_type": "Orthogonal", "width": 4, "height": 4, "algorithm": "Prims", "start": { "x": 9, "y": 0 } }"#;
+        assert_eq!(mazer_handle_generate_maze(CString::new(json).unwrap().as_ptr()), 0);
+        assert_eq!(mazer_last_error_category(), MAZER_ERROR_INVALID_REQUEST);
+        let message_ptr = mazer_last_error_message();
+        assert_eq!(unsafe { CStr::from_ptr(message_ptr) }.to_str().unwrap(), "The start (9, 0) is not a cell of the maze");
+        mazer_free_string(message_ptr);
+
+        // errors are kept per thread
+        std::thread::spawn(|| assert_eq!(mazer_last_error_code(), 0)).join().unwrap();
+    }
+
+    #[test]
+    fn test_mazer_generate_maze_async() {
+        let request = CString::new(r#"{ "maze_type": "Orthogonal", "width": 40, "height": 40, "algorithm": "Prims" }"#).unwrap();
+        let generation = mazer_generate_maze_async(request.as_ptr());
+        assert!(!generation.is_null());
+        let progress = mazer_generation_progress(generation);
+        assert!((0.0..=1.0).contains(&progress));
+        let grid = mazer_generation_finish(generation);
+        assert!(!grid.is_null());
+        assert!(unsafe { &*grid }.is_perfect_maze().unwrap());
+        mazer_destroy(grid);
+
+        let generation = mazer_generate_maze_async(request.as_ptr());
+        mazer_generation_cancel(generation);
+        // generation may have finished before it was cancelled
+        let grid = mazer_generation_finish(generation);
+        if !grid.is_null() {
+            mazer_destroy(grid);
+        }
+
+        assert!(mazer_generate_maze_async(CString::new("not json").unwrap().as_ptr()).is_null());
+        assert!(mazer_generation_finish(ptr::null_mut()).is_null());
+    }
+
+    #[test]
+    fn test_mazer_shift_walls() {
+        let request = CString::new(r#"{ "maze_type": "Orthogonal", "width": 8, "height": 8, "algorithm": "Prims" }"#).unwrap();
+        let grid = mazer_generate_maze(request.as_ptr());
+        let mut length: usize = 0;
+        let cells = mazer_shift_walls(grid, 3, 7, &mut length);
+        assert!(!cells.is_null());
+        assert!(length >= 4);
+        mazer_free_cells(cells, length);
+        assert!(unsafe { &*grid }.is_perfect_maze().unwrap());
+        assert!(mazer_shift_walls(ptr::null_mut(), 3, 7, &mut length).is_null());
+        mazer_destroy(grid);
+    }
+
+    #[test]
+    fn test_mazer_get_changed_cells() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 10,
+            "height": 10,
+            "algorithm": "RecursiveBacktracker"
+        }
+        "#;
+        let grid_ptr = mazer_generate_maze(CString::new(json).unwrap().as_ptr());
+        let mut length: usize = 0;
+        let cells_ptr = mazer_get_changed_cells(grid_ptr, 0, &mut length);
+        assert_eq!(length, 100);
+        mazer_free_cells(cells_ptr, length);
+
+        let revision = mazer_get_revision(grid_ptr);
+        let cells_ptr = mazer_get_changed_cells(grid_ptr, revision, &mut length);
+        assert_eq!(length, 0);
+        mazer_free_cells(cells_ptr, length);
+
+        let hint = mazer_get_hint(grid_ptr);
+        let direction = Direction::try_from(hint as u32).unwrap().to_string();
+        mazer_make_move(grid_ptr as *mut c_void, CString::new(direction).unwrap().as_ptr());
+        assert!(mazer_get_revision(grid_ptr) > revision);
+        let cells_ptr = mazer_get_changed_cells(grid_ptr, revision, &mut length);
+        let cells = unsafe { std::slice::from_raw_parts(cells_ptr, length) };
+        assert_eq!(length, 2);
+        assert_eq!(cells.iter().filter(|cell| cell.is_active).count(), 1);
+        mazer_free_cells(cells_ptr, length);
+        mazer_destroy(grid_ptr);
+    }
+
+    #[test]
+    fn test_mazer_grid_metadata() {
+        let json = r#"
+        {
+            "maze_type": "Sigma",
+            "width": 7,
+            "height": 5,
+            "algorithm": "Wilsons",
+            "start": { "x": 0, "y": 0 },
+            "goal": { "x": 6, "y": 4 }
+        }
+        "#;
+        let grid_ptr = mazer_generate_maze(CString::new(json).unwrap().as_ptr());
+        let (mut width, mut height) = (0, 0);
+        assert!(mazer_get_dimensions(grid_ptr, &mut width, &mut height));
+        assert_eq!((width, height), (7, 5));
+        let maze_type = unsafe { CStr::from_ptr(mazer_get_maze_type(grid_ptr)) };
+        assert_eq!(maze_type.to_str().unwrap(), "Sigma");
+        assert_eq!(mazer_get_seed(grid_ptr), unsafe { &*grid_ptr }.seed);
+        assert_eq!(mazer_canonical_hash(grid_ptr), unsafe { &*grid_ptr }.canonical_hash());
+        assert_eq!(mazer_canonical_hash(std::ptr::null()), 0);
+        let mut coords = FFICoordinates { x: 0, y: 0 };
+        assert!(mazer_get_goal(grid_ptr, &mut coords));
+        assert_eq!((coords.x, coords.y), (6, 4));
+        assert!(mazer_get_start(grid_ptr, &mut coords));
+        assert_eq!((coords.x, coords.y), (0, 0));
+
+        assert!(!mazer_get_dimensions(ptr::null(), &mut width, &mut height));
+        assert!(mazer_get_maze_type(ptr::null()).is_null());
+        assert!(!mazer_get_start(grid_ptr, ptr::null_mut()));
+        mazer_destroy(grid_ptr);
+    }
+
+    #[test]
+    fn test_mazer_get_solution_path() {
+        let json = r#"
+        {
+            "maze_type": "Delta",
+            "width": 8,
+            "height": 8,
+            "algorithm": "Prims"
+        }
+        "#;
+        let grid_ptr = mazer_generate_maze(CString::new(json).unwrap().as_ptr());
+        let mut length: usize = 0;
+        let path_ptr = mazer_get_solution_path(grid_ptr, &mut length);
+        assert!(!path_ptr.is_null());
+        let path = unsafe { std::slice::from_raw_parts(path_ptr, length) };
+        let grid = unsafe { &*grid_ptr };
+        assert_eq!(length, grid.iter_cells().filter(|cell| cell.on_solution_path).count());
+        assert_eq!((path[0].x, path[0].y), (grid.start_coords.x, grid.start_coords.y));
+        assert_eq!((path[length - 1].x, path[length - 1].y), (grid.goal_coords.x, grid.goal_coords.y));
+        for step in path.windows(2) {
+            let cell = grid.get(Coordinates { x: step[0].x, y: step[0].y }).unwrap();
+            assert!(cell.linked.contains(&Coordinates { x: step[1].x, y: step[1].y }));
+        }
+        mazer_free_coordinates(path_ptr, length);
+        assert!(mazer_get_solution_path(grid_ptr, ptr::null_mut()).is_null());
+        mazer_destroy(grid_ptr);
+    }
+
+    #[test]
+    fn test_mazer_weighted_solution_path() {
+        let json = r#"{ "maze_type": "Orthogonal", "width": 3, "height": 1, "algorithm": "BinaryTree", "start": { "x": 0, "y": 0 }, "goal": { "x": 2, "y": 0 } }"#;
+        let grid_ptr = mazer_generate_maze(CString::new(json).unwrap().as_ptr());
+        assert!(mazer_set_cell_weight(grid_ptr, 1, 0, 4));
+        assert!(!mazer_set_cell_weight(grid_ptr, 5, 0, 4));
+        assert_eq!(unsafe { &*grid_ptr }.get(Coordinates { x: 1, y: 0 }).unwrap().weight, Some(4));
+        let mut length: usize = 0;
+        let path_ptr = mazer_get_weighted_solution_path(grid_ptr, &mut length);
+        let path = unsafe { std::slice::from_raw_parts(path_ptr, length) };
+        assert_eq!(path.iter().map(|coords| (coords.x, coords.y)).collect::<Vec<_>>(), vec![(0, 0), (1, 0), (2, 0)]);
+        mazer_free_coordinates(path_ptr, length);
+        assert!(mazer_set_cell_weight(grid_ptr, 1, 0, 0));
+        assert_eq!(unsafe { &*grid_ptr }.get(Coordinates { x: 1, y: 0 }).unwrap().weight, None);
+        mazer_destroy(grid_ptr);
+    }
+
+    #[test]
+    fn test_mazer_session_stats() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 6,
+            "height": 6,
+            "algorithm": "RecursiveBacktracker"
+        }
+        "#;
+        let grid_ptr = mazer_generate_maze(CString::new(json).unwrap().as_ptr());
+        let mut stats = std::mem::MaybeUninit::<FFISessionStats>::uninit();
+        assert!(!mazer_get_session_stats(grid_ptr, stats.as_mut_ptr()));
+        assert!(!mazer_pause_session(grid_ptr));
+
+        assert!(mazer_start_session(grid_ptr));
+        let hint = mazer_get_hint(grid_ptr);
+        let direction = Direction::try_from(hint as u32).unwrap().to_string();
+        assert!(!mazer_make_move(grid_ptr as *mut c_void, CString::new(direction).unwrap().as_ptr()).is_null());
+        assert!(mazer_pause_session(grid_ptr));
+        assert!(mazer_get_session_stats(grid_ptr, stats.as_mut_ptr()));
+        let stats = unsafe { stats.assume_init() };
+        assert_eq!((stats.moves, stats.wrong_turns, stats.backtracks, stats.paused), (1, 0, 0, true));
+        assert!(mazer_resume_session(grid_ptr));
+        mazer_destroy(grid_ptr);
+    }
+
+    #[test]
+    fn test_mazer_assign_heatmap() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 6,
+            "height": 6,
+            "algorithm": "HuntAndKill",
+            "start": { "x": 0, "y": 0 },
+            "goal": { "x": 5, "y": 5 }
+        }
+        "#;
+        let grid_ptr = mazer_generate_maze(CString::new(json).unwrap().as_ptr());
+        assert!(!grid_ptr.is_null());
+        let mut length: usize = 0;
+        let cells_ptr = mazer_get_cells(grid_ptr, &mut length);
+        let cells = unsafe { std::slice::from_raw_parts(cells_ptr, length) };
+        assert!(cells.iter().all(|cell| cell.heat == -1.0), "Heat should be unset before assignment");
+        mazer_free_cells(cells_ptr, length);
+
+        assert!(!mazer_assign_heatmap(grid_ptr, 6, 6), "Origin outside the maze should be rejected");
+        assert!(mazer_assign_heatmap(grid_ptr, 5, 5));
+        let cells_ptr = mazer_get_cells(grid_ptr, &mut length);
+        let cells = unsafe { std::slice::from_raw_parts(cells_ptr, length) };
+        assert!(cells.iter().all(|cell| (0.0..=1.0).contains(&cell.heat)));
+        assert!(cells.iter().any(|cell| cell.heat == 1.0));
+        assert_eq!(cells.iter().find(|cell| cell.x == 5 && cell.y == 5).unwrap().heat, 0.0);
+        mazer_free_cells(cells_ptr, length);
+        mazer_destroy(grid_ptr);
+    }
+
+    #[test]
+    fn test_ffi_cell_axial_coordinates() {
+        let json = r#"
+        {
+            "maze_type": "Sigma",
+            "width": 5,
+            "height": 5,
+            "algorithm": "Prims"
+        }
+        "#;
+        let grid_ptr = mazer_generate_maze(CString::new(json).unwrap().as_ptr());
+        assert!(!grid_ptr.is_null());
+        let mut length: usize = 0;
+        let cells_ptr = mazer_get_cells(grid_ptr, &mut length);
+        let cells = unsafe { std::slice::from_raw_parts(cells_ptr, length) };
+        for cell in cells {
+            let axial = AxialCoordinates::from_offset(Coordinates { x: cell.x, y: cell.y });
+            assert_eq!((cell.axial_q, cell.axial_r), (axial.q as i32, axial.r as i32));
+        }
+        mazer_free_cells(cells_ptr, length);
+        mazer_destroy(grid_ptr);
+    }
+
+    #[test]
+    fn test_mazer_serialize_and_deserialize_maze() {
+        let json = r#"
+        {
+            "maze_type": "Sigma",
+            "width": 7,
+            "height": 7,
+            "algorithm": "Wilsons",
+            "start": { "x": 0, "y": 0 },
+            "goal": { "x": 6, "y": 6 }
+        }
+        "#;
+        let maze_ptr = mazer_generate_maze(CString::new(json).unwrap().as_ptr());
+        assert!(!maze_ptr.is_null());
+        let direction = Direction::try_from(mazer_get_hint(maze_ptr) as u32).unwrap();
+        unsafe { &mut *maze_ptr }.make_move(direction).unwrap();
+
+        let saved = mazer_serialize_maze(maze_ptr);
+        assert!(!saved.is_null());
+        let restored_ptr = mazer_deserialize_maze(saved);
+        mazer_free_string(saved);
+        assert!(!restored_ptr.is_null());
+
+        let (original, restored) = unsafe { (&mut *maze_ptr, &mut *restored_ptr) };
+        assert_eq!(restored.move_history(), original.move_history());
+        assert_eq!(restored.get_active_cell().unwrap().coords, original.get_active_cell().unwrap().coords);
+        assert_eq!(restored.count_edges(), original.count_edges());
+
+        let invalid = CString::new("{}").unwrap();
+        assert!(mazer_deserialize_maze(invalid.as_ptr()).is_null());
+        mazer_destroy(maze_ptr);
+        mazer_destroy(restored_ptr);
+    }
+
     #[test]
     fn test_mazer_make_move() {
         let json = r#"
@@ -644,13 +3271,13 @@ mod tests {
                 println!("\n\nMaze:\n\n{}\n\n", maze.to_asci());
                 
                 assert_eq!(
-                    maze.cells.iter().filter_map(|opt| opt.as_ref()).filter(|cell| cell.is_visited).count(),
+                    maze.iter_cells().filter(|cell| cell.is_visited).count(),
                     1,
                     "There should be 1 visited cell on dynamic path at the beginning"
                 );
                 
                 assert_eq!(
-                    maze.cells.iter().filter_map(|opt| opt.as_ref()).filter(|cell| cell.has_been_visited).count(),
+                    maze.iter_cells().filter(|cell| cell.has_been_visited).count(),
                     1,
                     "There should be 1 visited cell on permenant path at the beginning"
                 );
@@ -667,7 +3294,7 @@ mod tests {
                 
                     // 3) Collect its open_walls directly as a Vec<Direction>
                     //    (assuming open_walls: HashSet<Direction> or Vec<Direction>)
-                    let available_moves: Vec<Direction> = active.open_walls.iter().cloned().collect();
+                    let available_moves: Vec<Direction> = active.open_walls.iter().collect();
                 
                     // 4) Anything in `all_moves` not in `available_moves` is “unavailable”
                     let unavailable_moves: Vec<Direction> = maze.all_moves()
@@ -695,13 +3322,13 @@ mod tests {
                 );
 
                 assert_eq!(
-                    copied_maze.cells.iter().filter_map(|opt| opt.as_ref()).filter(|cell| cell.is_visited).count(),
+                    copied_maze.iter_cells().filter(|cell| cell.is_visited).count(),
                     1,
                     "There should be 1 visited cell on dynamic path before a successful move is made"
                 );
                 
                 assert_eq!(
-                    copied_maze.cells.iter().filter_map(|opt| opt.as_ref()).filter(|cell| cell.has_been_visited).count(),
+                    copied_maze.iter_cells().filter(|cell| cell.has_been_visited).count(),
                     1,
                     "There should be 1 visited cell on permenant path before a successful move is made"
                 );
@@ -712,19 +3339,19 @@ mod tests {
 
                 // Verify that exactly one cell is active.
                 assert_eq!(
-                    maze.cells.iter().filter_map(|opt| opt.as_ref()).filter(|cell| cell.is_active).count(),
+                    maze.iter_cells().filter(|cell| cell.is_active).count(),
                     1,
                     "There should be exactly one active cell"
                 );
 
                 assert_eq!(
-                    maze.cells.iter().filter_map(|opt| opt.as_ref()).filter(|cell| cell.is_visited).count(),
+                    maze.iter_cells().filter(|cell| cell.is_visited).count(),
                     2,
                     "There should be 2 visited cells on dynamic path after first successful move (start cell and current)"
                 );
                 
                 assert_eq!(
-                    maze.cells.iter().filter_map(|opt| opt.as_ref()).filter(|cell| cell.has_been_visited).count(),
+                    maze.iter_cells().filter(|cell| cell.has_been_visited).count(),
                     2,
                     "There should be 2 visited cells on permenant path after first successful move (start cell and current)"
                 );
@@ -747,6 +3374,109 @@ mod tests {
         }       
     }
 
+    #[test]
+    fn test_mazer_capture_solver() {
+        let request_json = r#"{ "maze_type": "Orthogonal", "width": 6, "height": 6, "algorithm": "RecursiveBacktracker" }"#;
+        let request = CString::new(request_json).unwrap();
+        let grid_ptr = mazer_generate_maze(request.as_ptr());
+        assert!(!grid_ptr.is_null());
+        assert_eq!(mazer_get_solving_steps_count(grid_ptr), 0);
+
+        let unknown = CString::new("Teleport").unwrap();
+        assert_eq!(mazer_capture_solver(grid_ptr, unknown.as_ptr()), 0);
+        let strategy = CString::new("Tremaux").unwrap();
+        let count = mazer_capture_solver(grid_ptr, strategy.as_ptr());
+        assert!(count > 0);
+        assert_eq!(mazer_get_solving_steps_count(grid_ptr), count);
+
+        let mut length: usize = 0;
+        let cells_ptr = mazer_get_solving_step_cells(grid_ptr, count - 1, &mut length);
+        assert!(!cells_ptr.is_null());
+        let cells: &[FFICell] = unsafe { std::slice::from_raw_parts(cells_ptr, length) };
+        assert!(cells.iter().any(|cell| cell.is_goal && cell.is_active));
+        mazer_free_cells(cells_ptr, length);
+        assert!(mazer_get_solving_step_cells(grid_ptr, count, &mut length).is_null());
+
+        mazer_destroy(grid_ptr);
+    }
+
+    #[test]
+    fn test_mazer_get_cells_json() {
+        let json = r#"{ "maze_type": "Orthogonal", "width": 4, "height": 3, "algorithm": "Prims", "capture_steps": true }"#;
+        let maze_ptr = mazer_generate_maze(CString::new(json).unwrap().as_ptr());
+        assert!(!maze_ptr.is_null());
+        let grid = unsafe { &*maze_ptr };
+
+        let json_ptr = mazer_get_cells_json(maze_ptr);
+        assert!(!json_ptr.is_null());
+        let cells: serde_json::Value = serde_json::from_str(unsafe { CStr::from_ptr(json_ptr) }.to_str().unwrap()).unwrap();
+        mazer_free_string(json_ptr);
+        let cells = cells.as_array().unwrap();
+        assert_eq!(cells.len(), 12);
+        for cell in cells {
+            let coords = Coordinates { x: cell["x"].as_u64().unwrap() as usize, y: cell["y"].as_u64().unwrap() as usize };
+            let original = grid.get(coords).unwrap();
+            assert_eq!(cell["maze_type"], "Orthogonal");
+            assert_eq!(cell["open_walls"].as_array().unwrap().len(), original.linked.len());
+            for linked in cell["linked_coords"].as_array().unwrap() {
+                let linked = Coordinates { x: linked["x"].as_u64().unwrap() as usize, y: linked["y"].as_u64().unwrap() as usize };
+                assert!(original.linked.contains(&linked));
+            }
+            assert_eq!(cell["on_solution_path"], original.on_solution_path);
+            assert!(cell["heat"].is_null());
+        }
+
+        let steps = mazer_get_generation_steps_count(maze_ptr);
+        let step_ptr = mazer_get_generation_step_cells_json(maze_ptr, steps - 1);
+        assert!(!step_ptr.is_null());
+        mazer_free_string(step_ptr);
+        assert!(mazer_get_generation_step_cells_json(maze_ptr, steps).is_null());
+        let details_ptr = mazer_get_generation_step_details_json(maze_ptr, steps - 1);
+        let details: serde_json::Value = serde_json::from_str(unsafe { CStr::from_ptr(details_ptr) }.to_str().unwrap()).unwrap();
+        mazer_free_string(details_ptr);
+        assert_eq!(details["phase"], "Carve");
+        assert_eq!(details["linked"].as_array().unwrap().len(), 1);
+        assert!(mazer_get_generation_step_details_json(maze_ptr, steps).is_null());
+
+        let player = mazer_step_player_new(maze_ptr);
+        assert!(!player.is_null());
+        mazer_step_player_set_speed(player, 1000.0);
+        mazer_step_player_play(player);
+        assert!(mazer_step_player_is_playing(player));
+        assert!(mazer_step_player_tick(player, 2));
+        assert_eq!(mazer_step_player_index(player), 2);
+        let mut length = 0;
+        let cells_ptr = mazer_step_player_cells(player, &mut length);
+        assert_eq!(length, 12);
+        mazer_free_cells(cells_ptr, length);
+        assert_eq!(mazer_step_player_seek(player, steps), steps - 1);
+        assert!(!mazer_step_player_is_playing(player));
+        let details_ptr = mazer_step_player_details_json(player);
+        assert!(!details_ptr.is_null());
+        mazer_free_string(details_ptr);
+        mazer_step_player_destroy(player);
+
+        // cached step cells match those converted anew, moving forward and back
+        let cache = mazer_step_cells_new(maze_ptr);
+        assert!(!cache.is_null());
+        for step in (0..steps).chain([1, steps - 1]) {
+            let mut cached_length = 0;
+            let cached = mazer_step_cells_get(cache, step, &mut cached_length);
+            let mut length = 0;
+            let converted = mazer_get_generation_step_cells(maze_ptr, step, &mut length);
+            assert_eq!(cached_length, length);
+            let (cached, converted) = unsafe { (std::slice::from_raw_parts(cached, cached_length), std::slice::from_raw_parts(converted, length)) };
+            for (a, b) in cached.iter().zip(converted) {
+                assert_eq!((a.x, a.y, a.open_walls_len, a.linked_coords_len), (b.x, b.y, b.open_walls_len, b.linked_coords_len));
+            }
+            mazer_free_cells(converted.as_ptr() as *mut FFICell, length);
+        }
+        assert!(mazer_step_cells_get(cache, steps, &mut length).is_null());
+        mazer_step_cells_destroy(cache);
+        assert!(mazer_get_cells_json(std::ptr::null()).is_null());
+        mazer_destroy(maze_ptr);
+    }
+
     #[test]
     fn test_hunt_and_kill_orthogonal_bidirectional_links_in_steps_ffi() {
         let json_request = r#"
@@ -841,6 +3571,132 @@ mod tests {
         assert!(!ptr.is_null(), "Maze generation failed for Rhombic maze");
     }
 
+    #[test]
+    fn test_mazer_assign_distance_bands() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 8,
+            "height": 8,
+            "algorithm": "HuntAndKill"
+        }
+        "#;
+        let grid_ptr = Box::into_raw(Box::new(Grid::try_from(json).expect("Failed to create Grid from JSON")));
+
+        let mut length: usize = 0;
+        let cells_ptr = mazer_get_cells(grid_ptr, &mut length);
+        let cells: &[FFICell] = unsafe { std::slice::from_raw_parts(cells_ptr, length) };
+        assert!(cells.iter().all(|cell| cell.distance_band == -1), "Bands should be unset before assignment");
+        mazer_free_cells(cells_ptr, length);
+
+        assert!(!mazer_assign_distance_bands(grid_ptr, 0), "Zero bands should be rejected");
+        assert!(mazer_assign_distance_bands(grid_ptr, 3));
+
+        let cells_ptr = mazer_get_cells(grid_ptr, &mut length);
+        let cells: &[FFICell] = unsafe { std::slice::from_raw_parts(cells_ptr, length) };
+        assert!(cells.iter().all(|cell| (0..3).contains(&cell.distance_band)));
+        mazer_free_cells(cells_ptr, length);
+
+        mazer_destroy(grid_ptr);
+    }
+
+    #[test]
+    fn test_mazer_list_maze_types_and_algorithms() {
+        let mut length: usize = 0;
+        let types_ptr = mazer_list_maze_types(&mut length);
+        assert!(!types_ptr.is_null());
+        let maze_types: Vec<String> = unsafe {
+            std::slice::from_raw_parts(types_ptr, length)
+                .iter()
+                .map(|&ptr| CStr::from_ptr(ptr).to_string_lossy().into_owned())
+                .collect()
+        };
+        assert_eq!(maze_types.len(), MazeType::all().len());
+        assert_eq!(maze_types[0], "Orthogonal");
+        mazer_free_string_list(types_ptr, length);
+
+        let algorithms_ptr = mazer_list_algorithms(&mut length);
+        assert!(!algorithms_ptr.is_null());
+        let algorithms: Vec<String> = unsafe {
+            std::slice::from_raw_parts(algorithms_ptr, length)
+                .iter()
+                .map(|&ptr| CStr::from_ptr(ptr).to_string_lossy().into_owned())
+                .collect()
+        };
+        assert_eq!(algorithms.len(), MazeAlgorithm::all().len());
+        assert!(algorithms.contains(&"RecursiveBacktracker".to_string()));
+        mazer_free_string_list(algorithms_ptr, length);
+
+        assert!(mazer_list_algorithms(std::ptr::null_mut()).is_null());
+    }
+
+    #[test]
+    fn test_mazer_algorithm_supports_and_validate_request() {
+        let name = |s: &str| CString::new(s).unwrap();
+        assert!(mazer_algorithm_supports(name("Prims").as_ptr(), name("Sigma").as_ptr()));
+        assert!(!mazer_algorithm_supports(name("Ellers").as_ptr(), name("Sigma").as_ptr()));
+        assert!(!mazer_algorithm_supports(name("Dijkstra").as_ptr(), name("Orthogonal").as_ptr()));
+        assert!(!mazer_algorithm_supports(std::ptr::null(), name("Orthogonal").as_ptr()));
+
+        let valid = name(r#"{ "maze_type": "Sigma", "width": 8, "height": 8, "algorithm": "Prims" }"#);
+        assert!(mazer_validate_request(valid.as_ptr()));
+        let unsupported = name(r#"{ "maze_type": "Sigma", "width": 8, "height": 8, "algorithm": "Ellers" }"#);
+        assert!(!mazer_validate_request(unsupported.as_ptr()));
+        let malformed = name(r#"{ "maze_type": "Sigma" }"#);
+        assert!(!mazer_validate_request(malformed.as_ptr()));
+        assert!(!mazer_validate_request(std::ptr::null()));
+    }
+
+    #[test]
+    fn test_mazer_move_toward() {
+        let request = CString::new(r#"{ "maze_type": "Orthogonal", "width": 5, "height": 1, "algorithm": "Prims", "start": { "x": 0, "y": 0 } }"#).unwrap();
+        let grid = mazer_generate_maze(request.as_ptr());
+        assert!(!grid.is_null());
+        assert_eq!(mazer_move_toward(grid, 4, 0), Direction::Right as i32);
+        assert_eq!(mazer_move_toward(grid, 1, 0), -1);
+        assert_eq!(mazer_move_toward(grid, 9, 9), -1);
+        assert_eq!(mazer_move_toward(std::ptr::null_mut(), 0, 0), -1);
+        mazer_destroy(grid);
+    }
+
+    #[test]
+    fn test_mazer_move_until_junction() {
+        let request = CString::new(r#"{ "maze_type": "Orthogonal", "width": 5, "height": 1, "algorithm": "Prims", "start": { "x": 0, "y": 0 }, "goal": { "x": 4, "y": 0 } }"#).unwrap();
+        let grid = mazer_generate_maze(request.as_ptr());
+        assert!(!grid.is_null());
+        let mut length = 0;
+        let left = CString::new("Left").unwrap();
+        assert!(mazer_move_until_junction(grid, left.as_ptr(), &mut length).is_null());
+        let right = CString::new("Right").unwrap();
+        let traversed = mazer_move_until_junction(grid, right.as_ptr(), &mut length);
+        assert!(!traversed.is_null());
+        assert_eq!(length, 4);
+        let last = unsafe { &*traversed.add(length - 1) };
+        assert_eq!((last.x, last.y), (4, 0));
+        mazer_free_coordinates(traversed, length);
+        assert!(mazer_move_until_junction(grid, std::ptr::null(), &mut length).is_null());
+        mazer_destroy(grid);
+    }
+
+    #[test]
+    fn test_mazer_set_move_policy() {
+        let name = |s: &str| CString::new(s).unwrap();
+        let request = name(r#"{ "maze_type": "Delta", "width": 6, "height": 6, "algorithm": "Prims" }"#);
+        let grid = mazer_generate_maze(request.as_ptr());
+        assert!(!grid.is_null());
+        assert!(mazer_set_move_policy(grid, name("Strict").as_ptr()));
+        assert_eq!(unsafe { &*grid }.move_policy, MovePolicy::Strict);
+        assert!(mazer_set_move_policy(grid, name(r#"{ "Custom": [["Left", ["UpperLeft"]]] }"#).as_ptr()));
+        assert_eq!(unsafe { &*grid }.move_policy, MovePolicy::Custom(vec![(Direction::Left, vec![Direction::UpperLeft])]));
+        assert!(mazer_set_move_policy(grid, name(r#""Fallback""#).as_ptr()));
+        assert_eq!(unsafe { &*grid }.move_policy, MovePolicy::Fallback);
+
+        assert!(!mazer_set_move_policy(grid, name("Lenient").as_ptr()));
+        assert!(!mazer_set_move_policy(grid, std::ptr::null()));
+        assert!(!mazer_set_move_policy(std::ptr::null_mut(), name("Strict").as_ptr()));
+        mazer_destroy(grid);
+    }
+
     #[test]
     fn test_ffi_integration_returns_42() {
         let result = mazer_ffi_integration_test();
@@ -0,0 +1,281 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+#[cfg(feature = "std")]
+use std::thread::{self, JoinHandle};
+
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+#[cfg(feature = "std")]
+use crate::algorithms::MazeAlgorithm;
+use crate::error::Error;
+#[cfg(feature = "std")]
+use crate::grid::Grid;
+#[cfg(feature = "std")]
+use crate::request::MazeRequest;
+
+/// A flag for stopping a long-running generation from another thread, e.g. when a server request times out or
+/// a user navigates away. Generation checks the token periodically and fails with `Error::Cancelled` once it
+/// has been cancelled. Cloning yields another handle to the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    // when the token cancels itself, if ever
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A token which cancels itself once the deadline passes, if not cancelled sooner.
+    #[cfg(feature = "std")]
+    pub fn with_deadline(deadline: Instant) -> Self {
+        CancellationToken { deadline: Some(deadline), ..Self::default() }
+    }
+
+    /// Cancel every generation using this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Whether `cancel` has been called, or the token's deadline has passed.
+    pub fn is_cancelled(&self) -> bool {
+        #[cfg(feature = "std")]
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return true;
+        }
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Fail with `Error::Cancelled` once the token has been cancelled.
+    pub fn check(&self) -> Result<(), Error> {
+        if self.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+        Ok(())
+    }
+}
+
+/// Progress of a maze being generated, shared between the generating thread and any threads polling it.
+/// Cloning yields another view of the same progress.
+#[derive(Debug, Clone, Default)]
+pub struct GenerationProgress {
+    inner: Arc<ProgressState>,
+    cancellation: CancellationToken,
+}
+
+#[derive(Debug, Default)]
+struct ProgressState {
+    // passages carved so far
+    carved: AtomicUsize,
+    // passages in a perfect maze of the grid's size, i.e. one fewer than its cells
+    total: AtomicUsize,
+    finished: AtomicBool,
+}
+
+impl GenerationProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track the progress of a generation which stops once the token is cancelled.
+    pub fn with_cancellation(cancellation: CancellationToken) -> Self {
+        GenerationProgress { inner: Arc::default(), cancellation }
+    }
+
+    /// Fraction of the maze generated so far, from 0.0 through 1.0. Progress is estimated from the passages
+    /// carved, and reaches 1.0 only once the maze is finished.
+    pub fn fraction(&self) -> f64 {
+        if self.is_finished() {
+            return 1.0;
+        }
+        let total = self.inner.total.load(Ordering::Relaxed);
+        if total == 0 {
+            return 0.0;
+        }
+        (self.inner.carved.load(Ordering::Relaxed) as f64 / total as f64).min(0.99)
+    }
+
+    /// Whether generation has finished, successfully or not.
+    pub fn is_finished(&self) -> bool {
+        self.inner.finished.load(Ordering::Acquire)
+    }
+
+    /// Ask for generation to stop, which then fails with `Error::Cancelled`.
+    pub fn cancel(&self) {
+        self.cancellation.cancel();
+    }
+
+    /// Whether `cancel` has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// The token which cancels this generation.
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    pub(crate) fn start(&self, total: usize) {
+        self.inner.total.store(total, Ordering::Relaxed);
+    }
+
+    // Count a carved passage, failing once generation has been cancelled
+    pub(crate) fn advance(&self) -> Result<(), Error> {
+        self.cancellation.check()?;
+        self.inner.carved.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub(crate) fn finish(&self) {
+        self.inner.finished.store(true, Ordering::Release);
+    }
+}
+
+/// A maze generated within a time budget by `Grid::generate_with_budget`, with the algorithm which generated it.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct BudgetedGeneration {
+    pub grid: Grid,
+    /// The algorithm which generated the maze: the one requested, or the fallback it was abandoned for.
+    pub algorithm: MazeAlgorithm,
+    /// Algorithms abandoned for running over their share of the budget, in the order they were tried.
+    pub abandoned: Vec<MazeAlgorithm>,
+}
+
+/// Algorithms to fall back on when generation runs over its budget, from slowest to fastest. The last can
+/// generate every maze type, with any number of layers.
+#[cfg(feature = "std")]
+pub const FALLBACK_ALGORITHMS: [MazeAlgorithm; 3] = [MazeAlgorithm::HuntAndKill, MazeAlgorithm::Sidewinder, MazeAlgorithm::RecursiveBacktracker];
+
+/// A maze being generated on a background thread, so that large mazes don't block the caller.
+#[cfg(feature = "std")]
+pub struct BackgroundGeneration {
+    progress: GenerationProgress,
+    worker: JoinHandle<Result<Grid, Error>>,
+}
+
+#[cfg(feature = "std")]
+impl BackgroundGeneration {
+    /// Start generating the requested maze on a new thread.
+    pub fn spawn(request: MazeRequest) -> Self {
+        let progress = GenerationProgress::new();
+        let worker = {
+            let progress = progress.clone();
+            thread::spawn(move || Grid::generate_with_progress(request, &progress))
+        };
+        BackgroundGeneration { progress, worker }
+    }
+
+    /// Progress of the generation, which may be polled from any thread.
+    pub fn progress(&self) -> &GenerationProgress {
+        &self.progress
+    }
+
+    /// Whether the maze is finished, so `wait` will return without blocking.
+    pub fn is_finished(&self) -> bool {
+        self.worker.is_finished()
+    }
+
+    /// Ask for generation to stop; `wait` then returns `Error::Cancelled` unless the maze was already finished.
+    pub fn cancel(&self) {
+        self.progress.cancel();
+    }
+
+    /// Block until generation finishes, returning the maze.
+    pub fn wait(self) -> Result<Grid, Error> {
+        match self.worker.join() {
+            Ok(result) => result,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::MazeAlgorithm;
+
+    fn request(width: usize, height: usize) -> MazeRequest {
+        serde_json::from_value(serde_json::json!({
+            "maze_type": "Orthogonal",
+            "width": width,
+            "height": height,
+            "algorithm": MazeAlgorithm::RecursiveBacktracker,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn progress_reaches_completion() {
+        let progress = GenerationProgress::new();
+        assert_eq!(progress.fraction(), 0.0);
+        let grid = Grid::generate_with_progress(request(20, 20), &progress).unwrap();
+        assert!(grid.is_perfect_maze().unwrap());
+        assert!(progress.is_finished());
+        assert_eq!(progress.fraction(), 1.0);
+    }
+
+    #[test]
+    fn background_generation() {
+        let generation = BackgroundGeneration::spawn(request(30, 30));
+        let grid = generation.wait().unwrap();
+        assert_eq!((grid.width, grid.height), (30, 30));
+        assert!(grid.is_perfect_maze().unwrap());
+    }
+
+    #[test]
+    fn cancelled_generation_fails() {
+        let progress = GenerationProgress::new();
+        progress.cancel();
+        assert!(matches!(Grid::generate_with_progress(request(20, 20), &progress), Err(Error::Cancelled)));
+        assert!(progress.is_finished());
+        assert_eq!(progress.fraction(), 1.0);
+    }
+
+    #[test]
+    fn cancellation_stops_every_algorithm() {
+        let token = CancellationToken::new();
+        token.cancel();
+        for &algorithm in MazeAlgorithm::all() {
+            let mut request = request(12, 12);
+            request.algorithm = algorithm;
+            assert!(matches!(Grid::generate_with_cancel(request, &token), Err(Error::Cancelled)), "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn budget_falls_back_to_faster_algorithms() {
+        let mut slow = request(200, 200);
+        slow.algorithm = MazeAlgorithm::AldousBroder;
+        slow.seed = Some(3);
+        let generated = Grid::generate_with_budget(slow, 0).unwrap();
+        assert_eq!(generated.abandoned, vec![MazeAlgorithm::AldousBroder, MazeAlgorithm::HuntAndKill, MazeAlgorithm::Sidewinder]);
+        assert_eq!(generated.algorithm, MazeAlgorithm::RecursiveBacktracker);
+        assert!(generated.grid.is_perfect_maze().unwrap());
+
+        let mut delta = request(10, 10);
+        delta.maze_type = crate::cell::MazeType::Delta;
+        delta.algorithm = MazeAlgorithm::Wilsons;
+        let generated = Grid::generate_with_budget(delta, 60_000).unwrap();
+        assert_eq!(generated.algorithm, MazeAlgorithm::Wilsons);
+        assert!(generated.abandoned.is_empty());
+    }
+
+    #[test]
+    fn cancelling_from_another_thread() {
+        let token = CancellationToken::new();
+        let mut request = request(300, 300);
+        request.algorithm = MazeAlgorithm::AldousBroder;
+        let worker = {
+            let token = token.clone();
+            thread::spawn(move || Grid::generate_with_cancel(request, &token))
+        };
+        token.cancel();
+        assert!(matches!(worker.join().unwrap(), Err(Error::Cancelled)));
+        assert!(CancellationToken::new().check().is_ok());
+    }
+}
@@ -1,13 +1,49 @@
-use std::fmt;
-use std::collections::{HashMap, HashSet};
-use rand::{ thread_rng, Rng };
+use alloc::collections::BTreeSet;
+use alloc::sync::Arc;
+use core::fmt;
+use crate::rng::GridRng;
+#[cfg(feature = "std")]
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{ Deserialize, Deserializer };
+#[cfg(feature = "serde")]
 use serde::ser::{ Serialize, Serializer, SerializeStruct };
+#[cfg(feature = "json")]
 use crate::behaviors::display::JsonDisplay;
 use crate::behaviors::graph;
-use crate::cell::{CellOrientation, MazeType, Cell, CellBuilder, Coordinates};
-use crate::direction::Direction;
+use crate::cell::{AxialCoordinates, CellOrientation, DeltaShape, MazeType, Cell, CellBuilder, Coordinates, Rect};
+use crate::compact::CompactGrid;
+use crate::diff::{self, GridDelta};
+use crate::difficulty::DifficultyReport;
+use crate::events::{GenerationStep, GridEvent};
+use crate::direction::{Direction, MovePolicy};
+use crate::editor::EditTransaction;
 use crate::error::Error;
-use crate::request::MazeRequest;
+use crate::steps::GenerationSteps;
+use crate::export;
+use crate::export::graph::GraphNode;
+use crate::export::mesh::{Mesh, MeshOptions};
+use crate::export::tilemap::{Tilemap, TilesetMapping};
+use crate::features::{self, FeatureSpec};
+use crate::generation::{CancellationToken, GenerationProgress};
+#[cfg(feature = "std")]
+use crate::algorithms::MazeAlgorithm;
+#[cfg(feature = "std")]
+use crate::generation::{BudgetedGeneration, FALLBACK_ALGORITHMS};
+use crate::history::{MoveRecord, Replay};
+use crate::mask;
+use crate::morph::{self, MorphStep};
+use crate::player::Player;
+use crate::puzzle::{self, Door};
+use crate::render::{self, heatmap};
+use crate::regions::{self, Region};
+use crate::request::{BorderEdge, Collect, Endpoints, MazeRequest};
+use crate::rooms;
+#[cfg(feature = "std")]
+use crate::session::{Session, SessionStats};
+use crate::solver::{self, SolverRun, SolverStrategy};
+use crate::trace::trace_span;
+use crate::prelude::*;
 
 #[derive(Debug, Clone)]
 /// Represents a grid of maze cells, encapsulating both the cells and their spatial relationships.
@@ -19,8 +55,11 @@ use crate::request::MazeRequest;
 pub struct Grid {
     /// The width of the grid.
     pub width: usize,
-    /// The height of the grid.
+    /// The height of the grid, counting the rows of every layer.
     pub height: usize,
+    /// The number of stacked layers, stored one below the other, each spanning `height / layers` rows.
+    /// Only Orthogonal mazes can have more than one layer.
+    pub layers: usize,
     /// The maze type, which determines the style of the maze (e.g., Orthogonal, Delta, Sigma).
     pub maze_type: MazeType,
     /// A flattened array of cells that make up the maze.
@@ -33,22 +72,263 @@ pub struct Grid {
     pub goal_coords: Coordinates,
     /// Enables intermediate grid states to be recorded during maze generation, for education purposes to the user.
     pub capture_steps: bool,
-    /// When capture_steps is true, contains a vector of `Grid` states representing each significant step of the maze generation process
-    pub generation_steps: Option<Vec<Grid>>,
+    /// When capture_steps is true, the `Grid` states at each significant step of the maze generation process
+    pub generation_steps: Option<GenerationSteps>,
+    /// When capture_steps is true, what happened in each step of `generation_steps`, at the same index
+    pub generation_step_details: Option<Vec<GenerationStep>>,
+    /// After `capture_solver`, contains a vector of `Grid` states representing each step of the solving strategy
+    pub solving_steps: Option<Vec<Grid>>,
+    /// The user moves made so far, most recent last.
+    pub move_history: Vec<MoveRecord>,
+    /// Moves reversed by `undo`, most recently undone last, which `redo` re-applies.
+    pub undone_moves: Vec<MoveRecord>,
+    /// Locked doors across passages of the maze, each opened by visiting its key cell.
+    pub doors: Vec<Door>,
+    /// Telemetry for the user's current attempt at solving the maze, once `start_session` is called.
+    #[cfg(feature = "std")]
+    pub session: Option<Session>,
+    /// Cells the user must visit to complete the maze, in "collect all" mode; when empty, reaching the goal
+    /// completes the maze.
+    pub targets: Vec<Coordinates>,
+    /// Whether the user has completed the maze, updated as moves are made, undone and redone.
+    pub completed: bool,
+    /// How many cells along each open corridor the user can see, when fog of war is enabled.
+    pub visibility_radius: Option<usize>,
+    /// How `make_move` resolves a move in a direction with no open passage.
+    pub move_policy: MovePolicy,
+    /// Additional players racing through the maze, each with its own position and trail, added with
+    /// `add_player` and moved with `make_move_for`. The user's own position and trail stay in the cells.
+    pub players: Vec<Player>,
+    /// Rectangular blocks of cells merged into open chambers (see `merge_rooms`).
+    pub rooms: Vec<Rect>,
+    /// The orientation of a Delta maze's top-left triangle, from which the other triangles alternate across
+    /// each row and down each column (see `set_delta_orientation`).
+    pub delta_orientation: CellOrientation,
+    /// The outline of a Delta maze, where a `Triangle` leaves the positions outside it without cells (see
+    /// `set_delta_shape`).
+    pub delta_shape: DeltaShape,
+    /// Which positions keep their cells, in row-major order, when the maze is masked to an outline such as a
+    /// cave (see `set_mask`); the others have no cell.
+    pub mask: Option<Vec<bool>>,
+    /// Incremented whenever the state of any cell changes, so clients can fetch only the cells changed since a
+    /// previous revision (see `changed_cells_since`).
+    pub revision: u64,
+    // Revision at which each cell last changed, indexed like `cells`
+    cell_revisions: Vec<u64>,
+    // Progress reported as passages are carved, while generating with `generate_with_progress`
+    progress: Option<GenerationProgress>,
+    // Random numbers drawn by the generation algorithms, seeded from `seed`
+    rng: GridRng,
+    // Distances from the origins most recently searched from, and the maze's difficulty, until the passages change
+    distance_cache: DistanceCache,
+    // Changes not yet taken by `drain_events`, once it has first been called
+    pub(crate) event_log: Option<Vec<GridEvent>>,
 }
 
+// How many origins' distances are kept, enough for the start, the goal and a couple of game pieces
+#[cfg(feature = "std")]
+const CACHED_DISTANCE_FIELDS: usize = 4;
+
+type DistanceField = Arc<HashMap<Coordinates, u32>>;
+
+// Distance fields, and the difficulty report searched from them, shared by every `&Grid`, so behind a lock.
+// Without `std` there is no lock to share them between threads with, so nothing is cached.
+#[derive(Debug, Default)]
+struct DistanceCache {
+    // least recently used first
+    #[cfg(feature = "std")]
+    fields: std::sync::Mutex<Vec<(Coordinates, DistanceField)>>,
+    // the difficulty of the maze between the start and goal it was measured for, serialized with every grid
+    #[cfg(feature = "std")]
+    difficulty: std::sync::Mutex<Option<(Coordinates, Coordinates, DifficultyReport)>>,
+}
+
+#[cfg(feature = "std")]
+impl DistanceCache {
+    fn fields(&self) -> std::sync::MutexGuard<'_, Vec<(Coordinates, DistanceField)>> {
+        // the fields are only replaced whole, so are as valid after a panic as before it
+        self.fields.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn get_or_insert_with(&self, origin: Coordinates, compute: impl FnOnce() -> HashMap<Coordinates, u32>) -> DistanceField {
+        {
+            let mut fields = self.fields();
+            if let Some(position) = fields.iter().position(|(cached, _)| *cached == origin) {
+                let entry = fields.remove(position);
+                let field = entry.1.clone();
+                fields.push(entry);
+                return field;
+            }
+        }
+        // searched without holding the lock, so other threads can read their fields meanwhile
+        let field = Arc::new(compute());
+        let mut fields = self.fields();
+        if fields.len() >= CACHED_DISTANCE_FIELDS {
+            fields.remove(0);
+        }
+        fields.push((origin, field.clone()));
+        field
+    }
+
+    fn difficulty(&self) -> std::sync::MutexGuard<'_, Option<(Coordinates, Coordinates, DifficultyReport)>> {
+        self.difficulty.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn difficulty_or_insert_with(&self, start: Coordinates, goal: Coordinates, compute: impl FnOnce() -> DifficultyReport) -> DifficultyReport {
+        if let Some((_, _, report)) = self.difficulty().as_ref().filter(|(from, to, _)| (*from, *to) == (start, goal)) {
+            return report.clone();
+        }
+        let report = compute();
+        *self.difficulty() = Some((start, goal, report.clone()));
+        report
+    }
+
+    fn clear(&mut self) {
+        self.fields.get_mut().unwrap_or_else(|poisoned| poisoned.into_inner()).clear();
+        *self.difficulty.get_mut().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl DistanceCache {
+    fn get_or_insert_with(&self, _origin: Coordinates, compute: impl FnOnce() -> HashMap<Coordinates, u32>) -> DistanceField {
+        Arc::new(compute())
+    }
+
+    fn difficulty_or_insert_with(&self, _start: Coordinates, _goal: Coordinates, compute: impl FnOnce() -> DifficultyReport) -> DifficultyReport {
+        compute()
+    }
+
+    fn clear(&mut self) {}
+}
+
+impl Clone for DistanceCache {
+    fn clone(&self) -> Self {
+        DistanceCache {
+            #[cfg(feature = "std")]
+            fields: std::sync::Mutex::new(self.fields().clone()),
+            #[cfg(feature = "std")]
+            difficulty: std::sync::Mutex::new(self.difficulty().clone()),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
 impl Serialize for Grid {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut grid_map = serializer.serialize_struct("Grid", 1)?;
-        let cells: Vec<&Cell> = self.cells.iter().filter_map(|opt| opt.as_ref()).collect();
+        // formats which write the field count up front, such as MessagePack, need it to match exactly
+        let optional = [
+            self.layers > 1,
+            !self.doors.is_empty(),
+            !self.targets.is_empty(),
+            self.visibility_radius.is_some(),
+            self.move_policy != MovePolicy::default(),
+            !self.players.is_empty(),
+            !self.rooms.is_empty(),
+            self.delta_orientation != CellOrientation::default(),
+            self.delta_shape != DeltaShape::default(),
+            self.mask.is_some(),
+        ];
+        let mut grid_map = serializer.serialize_struct("Grid", 11 + optional.iter().filter(|&&present| present).count())?;
+        let cells: Vec<&Cell> = self.iter_cells().collect();
         grid_map.serialize_field("rows", &cells)?;
+        grid_map.serialize_field("difficulty", &self.difficulty())?;
+        grid_map.serialize_field("maze_type", &self.maze_type)?;
+        grid_map.serialize_field("width", &self.width)?;
+        grid_map.serialize_field("height", &self.height)?;
+        if self.layers > 1 {
+            grid_map.serialize_field("layers", &self.layers)?;
+        }
+        grid_map.serialize_field("seed", &self.seed)?;
+        grid_map.serialize_field("start", &self.start_coords)?;
+        grid_map.serialize_field("goal", &self.goal_coords)?;
+        grid_map.serialize_field("move_history", &self.move_history)?;
+        grid_map.serialize_field("undone_moves", &self.undone_moves)?;
+        if !self.doors.is_empty() {
+            grid_map.serialize_field("doors", &self.doors)?;
+        }
+        if !self.targets.is_empty() {
+            grid_map.serialize_field("targets", &self.targets)?;
+        }
+        grid_map.serialize_field("completed", &self.completed)?;
+        if let Some(radius) = self.visibility_radius {
+            grid_map.serialize_field("visibility_radius", &radius)?;
+        }
+        if self.move_policy != MovePolicy::default() {
+            grid_map.serialize_field("move_policy", &self.move_policy)?;
+        }
+        if !self.players.is_empty() {
+            grid_map.serialize_field("players", &self.players)?;
+        }
+        if !self.rooms.is_empty() {
+            grid_map.serialize_field("rooms", &self.rooms)?;
+        }
+        if self.delta_orientation != CellOrientation::default() {
+            grid_map.serialize_field("delta_orientation", &self.delta_orientation)?;
+        }
+        if self.delta_shape != DeltaShape::default() {
+            grid_map.serialize_field("delta_shape", &self.delta_shape)?;
+        }
+        if let Some(mask) = &self.mask {
+            grid_map.serialize_field("mask", mask)?;
+        }
         grid_map.end()
     }
 }
 
+// Mirror of the serialized grid fields, used to deserialize a `Grid`
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct GridData {
+    rows: Vec<Cell>,
+    maze_type: MazeType,
+    width: usize,
+    height: usize,
+    #[serde(default)]
+    layers: usize,
+    #[serde(default)]
+    seed: u64,
+    start: Coordinates,
+    goal: Coordinates,
+    #[serde(default)]
+    move_history: Vec<MoveRecord>,
+    #[serde(default)]
+    undone_moves: Vec<MoveRecord>,
+    #[serde(default)]
+    doors: Vec<Door>,
+    #[serde(default)]
+    targets: Vec<Coordinates>,
+    #[serde(default)]
+    visibility_radius: Option<usize>,
+    #[serde(default)]
+    move_policy: MovePolicy,
+    #[serde(default)]
+    players: Vec<Player>,
+    #[serde(default)]
+    rooms: Vec<Rect>,
+    #[serde(default)]
+    delta_orientation: CellOrientation,
+    #[serde(default)]
+    delta_shape: DeltaShape,
+    #[serde(default)]
+    mask: Option<Vec<bool>>,
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Grid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let data = GridData::deserialize(deserializer)?;
+        Grid::from_data(data).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "json")]
 impl fmt::Display for Grid {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.to_json() {
@@ -57,31 +337,188 @@ impl fmt::Display for Grid {
         }
     }
 }
+// the JSON representation, as far as it can be written without serde
+#[cfg(not(feature = "json"))]
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_asci())
+    }
+}
 
 impl TryFrom<MazeRequest> for Grid {
     type Error = crate::Error;
 
     fn try_from(request: MazeRequest) -> Result<Self, Self::Error> {
+        Grid::from_request(request, None)
+    }
+}
+
+impl Grid {
+    /// Generate the requested maze, reporting progress as it is generated and stopping with
+    /// `Error::Cancelled` if the progress is cancelled. The progress is marked finished on return.
+    pub fn generate_with_progress(request: MazeRequest, progress: &GenerationProgress) -> Result<Grid, Error> {
+        let result = Grid::from_request(request, Some(progress));
+        progress.finish();
+        result
+    }
+
+    /// Generate the requested maze, stopping with `Error::Cancelled` once the token is cancelled.
+    pub fn generate_with_cancel(request: MazeRequest, cancellation: &CancellationToken) -> Result<Grid, Error> {
+        Grid::generate_with_progress(request, &GenerationProgress::with_cancellation(cancellation.clone()))
+    }
+
+    /// Generate the requested maze within about `max_millis` milliseconds, for servers which need bounded
+    /// latency. The requested algorithm gets half the budget; if it runs over, generation falls back to each
+    /// faster algorithm of `FALLBACK_ALGORITHMS` supporting the maze in turn, each getting half the time
+    /// remaining, except the last, which runs to completion. Algorithm parameters are dropped on falling back.
+    #[cfg(feature = "std")]
+    pub fn generate_with_budget(request: MazeRequest, max_millis: u64) -> Result<BudgetedGeneration, Error> {
+        request.validate()?;
+        let layers = request.layers.unwrap_or(1);
+        let position = FALLBACK_ALGORITHMS.iter().position(|&algorithm| algorithm == request.algorithm);
+        let fallbacks: Vec<MazeAlgorithm> = FALLBACK_ALGORITHMS[position.map_or(0, |position| position + 1)..]
+            .iter()
+            .copied()
+            .filter(|algorithm| algorithm.supports(request.maze_type) && (layers == 1 || algorithm.supports_layers()))
+            .collect();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(max_millis);
+        let mut abandoned = Vec::new();
+        let mut request = request;
+        for fallback in fallbacks {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            let token = CancellationToken::with_deadline(std::time::Instant::now() + remaining / 2);
+            match Grid::generate_with_cancel(request.clone(), &token) {
+                Ok(grid) => return Ok(BudgetedGeneration { grid, algorithm: request.algorithm, abandoned }),
+                Err(Error::Cancelled) => {
+                    abandoned.push(request.algorithm);
+                    request.algorithm = fallback;
+                    request.algorithm_params = None;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        let algorithm = request.algorithm;
+        Grid::from_request(request, None).map(|grid| BudgetedGeneration { grid, algorithm, abandoned })
+    }
+
+    fn from_request(request: MazeRequest, progress: Option<&GenerationProgress>) -> Result<Grid, Error> {
+        trace_span!("generate", maze_type = request.maze_type.name(), width = request.width, height = request.height, algorithm = request.algorithm.name());
+        request.validate()?;
+
         // decide start/goal, falling back to sensible defaults
         let (start_coords, goal_coords) = match (request.start, request.goal) {
             (Some(s), Some(g)) => (s, g),
-            _ => Grid::default_endpoints(request.width, request.height, request.maze_type),
+            _ if request.delta_shape == Some(DeltaShape::Triangle) => {
+                Grid::default_triangle_endpoints(request.height, request.delta_orientation.unwrap_or_default())
+            }
+            _ => Grid::default_endpoints(request.width, request.rows(), request.maze_type),
         };
 
-        let mut grid = Grid::new(
+        let mut grid = Grid::new_layered(
             request.maze_type,
             request.width,
             request.height,
+            request.layers.unwrap_or(1),
             start_coords,
             goal_coords,
             request.capture_steps.unwrap_or_default(),
         )?;
 
-        request.algorithm.generate(&mut grid)?;
+        if let Some(orientation) = request.delta_orientation {
+            grid.set_delta_orientation(orientation)?;
+        }
+        if let Some(shape) = request.delta_shape {
+            grid.set_delta_shape(shape)?;
+        }
+        if let Some(mask) = request.mask {
+            let keep = mask.generate(&grid)?;
+            if request.start.is_none() || request.goal.is_none() {
+                // the default endpoints may lie outside the mask, so move them to the nearest kept cells
+                let start = mask::nearest(&grid, &keep, grid.start_coords, None);
+                let goal = start.and_then(|start| mask::nearest(&grid, &keep, grid.goal_coords, Some(start)));
+                if let (Some(start), Some(goal)) = (start, goal) {
+                    grid.start_coords = start;
+                    grid.goal_coords = goal;
+                }
+            }
+            grid.set_mask(Some(keep))?;
+        }
+        if let Some(seed) = request.seed {
+            grid.reseed(seed);
+        }
+        let rooms = request.rooms.as_ref().filter(|rooms| !rooms.is_empty());
+        let endpoints = rooms.map(|rooms| rooms::contract(&mut grid, rooms)).transpose()?;
+        if let Some(progress) = progress {
+            progress.start(grid.iter_cells().count().saturating_sub(1));
+            grid.progress = Some(progress.clone());
+        }
+        if let Some(constraints) = &request.constraints {
+            constraints.check(&grid)?;
+        }
+        request.generation_algorithm().generate_with_params(&mut grid, &request.algorithm_params.clone().unwrap_or_default())?;
+        grid.progress = None;
+        if let (Some(rooms), Some(endpoints)) = (rooms, endpoints) {
+            rooms::expand(&mut grid, rooms, endpoints)?;
+            grid.rooms = rooms.clone();
+        }
+        if let Some(symmetry) = request.symmetry {
+            symmetry.apply(&mut grid)?;
+        }
+        if let Some(overlay) = request.overlay {
+            overlay.apply(&mut grid)?;
+        }
+        if let Some(constraints) = &request.constraints {
+            constraints.apply(&mut grid)?;
+        }
+        if let Some(guardrails) = request.guardrails {
+            guardrails.apply(&mut grid)?;
+        }
+        // the solution found while generating no longer holds once the passages are reworked
+        if request.symmetry.is_some() || request.overlay.is_some() || request.constraints.is_some() || request.guardrails.is_some() || rooms.is_some() {
+            grid.refresh_solution();
+        }
+
+        if request.endpoints == Some(Endpoints::LongestPath) {
+            let (start, goal, _) = grid.longest_path();
+            grid.set_endpoints(start, goal)?;
+        }
+
+        if request.start_edge.is_some() || request.goal_edge.is_some() {
+            let start = request.start_edge.map(|edge| grid.border_cell(edge)).transpose()?;
+            let goal = request.goal_edge.map(|edge| grid.border_cell(edge)).transpose()?;
+            grid.set_endpoints(start.map_or(grid.start_coords, |(coords, _)| coords), goal.map_or(grid.goal_coords, |(coords, _)| coords))?;
+            for edge in [request.start_edge, request.goal_edge].into_iter().flatten() {
+                grid.open_border(edge)?;
+            }
+        }
+        if let Some(max_weight) = request.weight_noise {
+            grid.assign_random_weights(max_weight)?;
+        }
+        if let Some(bands) = request.distance_bands {
+            grid.assign_distance_bands(bands)?;
+        }
+        if request.heatmap.unwrap_or_default() {
+            grid.assign_heatmap(grid.start_coords)?;
+        }
+        if request.open_exits.unwrap_or_default() {
+            grid.open_exits();
+        }
+        if let Some(radius) = request.visibility_radius {
+            grid.enable_fog_of_war(radius)?;
+        }
+        if let Some(policy) = &request.move_policy {
+            grid.move_policy = policy.clone();
+        }
+        match &request.collect {
+            Some(Collect::AllCells) => grid.set_targets(grid.iter_cells().map(|cell| cell.coords).collect())?,
+            Some(Collect::Cells(targets)) => grid.set_targets(targets.clone())?,
+            None => {}
+        }
         Ok(grid)
     }
 }
 
+#[cfg(feature = "json")]
 impl TryFrom<&str> for Grid {
     type Error = crate::Error; // explicitly reference our custom Error type
 
@@ -91,6 +528,7 @@ impl TryFrom<&str> for Grid {
     }
 }
 
+#[cfg(feature = "json")]
 impl TryFrom<String> for Grid {
     type Error = crate::Error;
 
@@ -145,6 +583,102 @@ impl Grid {
         (Coordinates { x: start_x, y: start_y }, Coordinates { x: goal_x, y: goal_y })
     }
 
+    /// Restore a maze previously serialized to JSON (e.g. with `to_json`), including its links, the user's
+    /// visited and active cells, and move history, so a partially solved maze can be persisted and resumed.
+    #[cfg(feature = "json")]
+    pub fn from_saved_json(json: &str) -> Result<Grid, Error> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Encode the maze in a compact binary format (see `CompactGrid::to_bytes`), far smaller than its JSON
+    /// for storage or network transfer of large mazes.
+    /// Multi-layer, masked and triangle-shaped Delta mazes cannot be encoded, as the format has no room for stairs
+    /// between layers or for the maze's outline.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        if self.layers > 1 {
+            return Err(Error::InvalidBinaryFormat { reason: "multi-layer mazes cannot be encoded".to_string() });
+        }
+        if self.delta_shape != DeltaShape::default() {
+            return Err(Error::InvalidBinaryFormat { reason: "triangle-shaped Delta mazes cannot be encoded".to_string() });
+        }
+        if self.mask.is_some() {
+            return Err(Error::InvalidBinaryFormat { reason: "masked mazes cannot be encoded".to_string() });
+        }
+        Ok(CompactGrid::from(self).to_bytes())
+    }
+
+    /// Decode a maze from the binary format produced by `to_bytes`, recomputing each cell's distance from the start.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Grid, Error> {
+        let mut grid = Grid::try_from(&CompactGrid::from_bytes(bytes)?)?;
+        for (&coords, &distance) in grid.distance_field(grid.start_coords).iter() {
+            grid.get_mut(coords)?.distance = distance as i32;
+        }
+        for cell in grid.cells.iter_mut().flatten() {
+            cell.set_open_walls();
+        }
+        Ok(grid)
+    }
+
+    // Rebuild a grid's geometry from its dimensions, then re-apply the saved links and cell state
+    #[cfg(feature = "serde")]
+    fn from_data(data: GridData) -> Result<Grid, Error> {
+        let layers = data.layers.max(1);
+        let mut grid = Grid::new_layered(data.maze_type, data.width, data.height / layers, layers, data.start, data.goal, false)?;
+        grid.reseed(data.seed);
+        if data.delta_orientation != CellOrientation::default() {
+            grid.set_delta_orientation(data.delta_orientation)?;
+        }
+        if data.delta_shape != DeltaShape::default() {
+            grid.set_delta_shape(data.delta_shape)?;
+        }
+        if data.mask.is_some() {
+            grid.set_mask(data.mask)?;
+        }
+        for saved in &data.rows {
+            let cell = grid.get(saved.coords)?;
+            let user_facing = cell.get_user_facing_neighbors();
+            let neighbors: Vec<Coordinates> = saved.open_walls
+                .iter()
+                .map(|direction| {
+                    cell.neighbors_by_direction.get(&direction)
+                        .or_else(|| user_facing.get(&direction).copied())
+                        .ok_or(Error::InvalidDirection { direction: direction.to_string() })
+                })
+                .collect::<Result<_, _>>()?;
+            for neighbor in neighbors {
+                grid.link(saved.coords, neighbor)?;
+            }
+        }
+        for saved in data.rows {
+            let cell = grid.get_mut(saved.coords)?;
+            cell.distance = saved.distance;
+            cell.is_start = saved.is_start;
+            cell.is_goal = saved.is_goal;
+            cell.is_active = saved.is_active;
+            cell.is_visited = saved.is_visited;
+            cell.has_been_visited = saved.has_been_visited;
+            cell.on_solution_path = saved.on_solution_path;
+            cell.distance_band = saved.distance_band;
+            cell.heat = saved.heat;
+            cell.is_discovered = saved.is_discovered;
+            cell.border_opening = saved.border_opening;
+            cell.weight = saved.weight;
+        }
+        for cell in grid.cells.iter_mut().flatten() {
+            cell.set_open_walls();
+        }
+        grid.move_history = data.move_history;
+        grid.undone_moves = data.undone_moves;
+        grid.doors = data.doors;
+        grid.targets = data.targets;
+        grid.visibility_radius = data.visibility_radius;
+        grid.move_policy = data.move_policy;
+        grid.players = data.players;
+        grid.rooms = data.rooms;
+        grid.update_completion();
+        Ok(grid)
+    }
+
     /// Get x,y coordinate's index in the flattened 1D vector
     pub fn get_flattened_index(&self, x: usize, y: usize) -> usize {
         // when unflattened to become a 2D vector, cells are stored in row-major order 
@@ -159,16 +693,36 @@ impl Grid {
     // }
 
     pub fn has_cell(&self, x: usize, y: usize) -> bool {
-        if x >= self.width || y >= self.height {
+        if x >= self.width || y >= self.height || self.mask.as_ref().is_some_and(|mask| !mask[self.get_flattened_index(x, y)]) {
             false
         } else {
             match self.maze_type {
                 MazeType::Rhombic => (x + y) % 2 == 0,
+                MazeType::Delta => self.delta_shape.contains(self.delta_orientation, self.height, Coordinates { x, y }),
                 _ => true,
             }
         }
     }
     
+    /// Axial coordinates of the cell at the specified column and row of a Sigma maze.
+    pub fn to_axial(&self, coords: Coordinates) -> Result<AxialCoordinates, Error> {
+        if self.maze_type != MazeType::Sigma {
+            return Err(Error::AxialCoordinatesUnavailable { maze_type: self.maze_type });
+        }
+        Ok(self.get(coords)?.to_axial().unwrap_or_else(|| AxialCoordinates::from_offset(coords)))
+    }
+
+    /// Column and row of the cell at the specified axial coordinates of a Sigma maze.
+    pub fn from_axial(&self, axial: AxialCoordinates) -> Result<Coordinates, Error> {
+        if self.maze_type != MazeType::Sigma {
+            return Err(Error::AxialCoordinatesUnavailable { maze_type: self.maze_type });
+        }
+        match axial.to_offset() {
+            Some(coords) if self.has_cell(coords.x, coords.y) => Ok(coords),
+            _ => Err(Error::InvalidAxialCoordinates { coordinates: axial }),
+        }
+    }
+
     /// Retrieve a cell by its coordinates
     pub fn get(&self, coords: Coordinates) -> Result<&Cell, Error> {
         let index = self.get_flattened_index(coords.x, coords.y);
@@ -196,8 +750,48 @@ impl Grid {
             }),
         }
     }
+
+    /// Every cell of the maze in row-major order, skipping positions without a cell (e.g. in Rhombic mazes).
+    pub fn iter_cells(&self) -> impl Iterator<Item = &Cell> + '_ {
+        self.cells.iter().filter_map(Option::as_ref)
+    }
+
+    /// Mutable access to every cell of the maze in row-major order.
+    pub fn iter_cells_mut(&mut self) -> impl Iterator<Item = &mut Cell> + '_ {
+        self.cells.iter_mut().filter_map(Option::as_mut)
+    }
+
+    /// Cells of the specified row from left to right, which is empty if the row is out of bounds.
+    pub fn iter_row(&self, y: usize) -> impl Iterator<Item = &Cell> + '_ {
+        let row = if y < self.height { &self.cells[y * self.width..(y + 1) * self.width] } else { &[] };
+        row.iter().filter_map(Option::as_ref)
+    }
+
+    /// Cells of the specified column from top to bottom, which is empty if the column is out of bounds.
+    pub fn iter_column(&self, x: usize) -> impl Iterator<Item = &Cell> + '_ {
+        let column = if x < self.width { &self.cells[x..] } else { &[] };
+        column.iter().step_by(self.width.max(1)).filter_map(Option::as_ref)
+    }
+
+    /// Both cells of every passage in the maze, each passage yielded once with the lesser coordinates first.
+    pub fn iter_linked_pairs(&self) -> impl Iterator<Item = (&Cell, &Cell)> + '_ {
+        self.iter_cells().flat_map(move |cell| {
+            cell.linked
+                .iter()
+                .filter(move |&neighbor| cell.coords < neighbor)
+                .filter_map(move |neighbor| self.get(neighbor).ok())
+                .map(move |neighbor| (cell, neighbor))
+        })
+    }
+
     /// Get the currently active Cell
     pub fn get_active_cell(&mut self) -> Result<&mut Cell, Error> {
+        let active_coords = self.active_coords()?;
+        self.get_mut(active_coords)
+    }
+
+    /// Coordinates of the single currently active Cell
+    fn active_coords(&self) -> Result<Coordinates, Error> {
         let active_coords: Vec<Coordinates> = self.cells.iter()
             .enumerate()
             .filter_map(|(index, opt)| {
@@ -213,7 +807,7 @@ impl Grid {
 
         match active_coords.len() {
             0 => Err(Error::NoActiveCells),
-            1 => self.get_mut(active_coords[0]), // Assumes get_mut returns Result<&mut Cell, Error>
+            1 => Ok(active_coords[0]),
             count => Err(Error::MultipleActiveCells { count }),
         }
     }
@@ -228,6 +822,7 @@ impl Grid {
     pub fn all_moves(&self) -> &'static [Direction] {
         use Direction::*;
         match self.maze_type {
+            MazeType::Orthogonal if self.layers > 1 => &[Up, Right, Down, Left, Above, Below],
             MazeType::Orthogonal => &[Up, Right, Down, Left],
             MazeType::Sigma      => &[Up, UpperRight, Right, LowerRight, Down, LowerLeft, Left, UpperLeft],
             MazeType::Delta      => &[Up, UpperLeft, UpperRight, Down, LowerLeft, LowerRight],
@@ -236,15 +831,24 @@ impl Grid {
         }
     }
 
+    /// Whether make_move would accept the specified direction *right now*, without mutating the grid.
+    pub fn can_move(&self, direction: Direction) -> bool {
+        self.active_coords()
+            .and_then(|coords| self.get(coords))
+            .map(|cell| {
+                Self::resolve_move(&self.move_policy, cell, direction)
+                    .and_then(|resolved| cell.neighbors_by_direction.get(&resolved))
+                    .is_some_and(|next| self.locked_door(cell.coords, next).is_none())
+            })
+            .unwrap_or(false)
+    }
+
     /// Which directions would make_move reject *right now*?
     pub fn unavailable_moves(&self) -> Vec<Direction> {
         self.all_moves()
             .iter()
             .cloned()
-            .filter(|d| {
-                let mut copy = self.clone();
-                copy.make_move(*d).is_err()
-            })
+            .filter(|d| !self.can_move(*d))
             .collect()
     }
 
@@ -253,85 +857,30 @@ impl Grid {
         self.all_moves()
             .iter()
             .cloned()
-            .filter(|d| {
-                let mut copy = self.clone();
-                copy.make_move(*d).is_ok()
-            })
+            .filter(|d| self.can_move(*d))
             .collect()
     }
 
+    /// Resolve a requested move from the given cell to the open direction actually taken, accounting for
+    /// fallback to adjacent directions (e.g. "Up" falling back to "UpperLeft" or "UpperRight") as the policy
+    /// allows.
+    fn resolve_move(policy: &MovePolicy, cell: &Cell, direction: Direction) -> Option<Direction> {
+        // a candidate move must be both open (in open_walls) and valid (exists in neighbors_by_direction)
+        let open = |candidate: &Direction| cell.open_walls.contains(candidate) && cell.neighbors_by_direction.contains_key(candidate);
+        core::iter::once(&direction).chain(policy.fallbacks(direction)).find(|candidate| open(candidate)).copied()
+    }
+
     /// Manually make a user move to a specified direction.
     pub fn make_move(&mut self, direction: Direction) -> Result<Direction, Error> {
         // Store the original direction for error reporting.
         let original_direction = direction;
 
         // Get the current active cell and record its coordinates.
-        let active_cell = self.get_active_cell()?;
+        let active_cell = self.get(self.active_coords()?)?;
         let original_coords = active_cell.coords;
 
         // Determine the effective direction to use, accounting for fallback logic.
-        let picked: Option<Direction> = {
-            // Define a helper closure: it checks whether a candidate move is both open (in open_walls)
-            // and valid (exists in neighbors_by_direction).
-            let try_direction = |cell: &Cell, cand: &Direction| -> Option<Direction> {
-                if cell.open_walls.contains(cand) && cell.neighbors_by_direction.contains_key(cand) {
-                    Some(*cand)
-                } else {
-                    None
-                }
-            };
-
-            match direction {
-                Direction::Left => {
-                    // For "Left", try Left then UpperLeft then LowerLeft.
-                    try_direction(active_cell, &Direction::Left)
-                        .or_else(|| try_direction(active_cell, &Direction::UpperLeft))
-                        .or_else(|| try_direction(active_cell, &Direction::LowerLeft))
-                },
-                Direction::Right => {
-                    // For "Right", try Right then UpperRight then LowerRight.
-                    try_direction(active_cell, &Direction::Right)
-                        .or_else(|| try_direction(active_cell, &Direction::UpperRight))
-                        .or_else(|| try_direction(active_cell, &Direction::LowerRight))
-                },
-                Direction::UpperLeft => {
-                    // For "UpperLeft", try UpperLeft then Up then Left.
-                    try_direction(active_cell, &Direction::UpperLeft)
-                        .or_else(|| try_direction(active_cell, &Direction::Up))
-                        .or_else(|| try_direction(active_cell, &Direction::Left))
-                },
-                Direction::LowerLeft => {
-                    // For "LowerLeft", try LowerLeft then Down then Left.
-                    try_direction(active_cell, &Direction::LowerLeft)
-                        .or_else(|| try_direction(active_cell, &Direction::Down))
-                        .or_else(|| try_direction(active_cell, &Direction::Left))
-                },
-                Direction::UpperRight => {
-                    // For "UpperRight", try UpperRight then Up then Right.
-                    try_direction(active_cell, &Direction::UpperRight)
-                        .or_else(|| try_direction(active_cell, &Direction::Up))
-                        .or_else(|| try_direction(active_cell, &Direction::Right))
-                },
-                Direction::LowerRight => {
-                    // For "LowerRight", try LowerRight then Down then Right.
-                    try_direction(active_cell, &Direction::LowerRight)
-                        .or_else(|| try_direction(active_cell, &Direction::Down))
-                        .or_else(|| try_direction(active_cell, &Direction::Right))
-                },
-                Direction::Up => {
-                    // For Up, try Up first then fall back to UpperLeft, then fail back to UpperRight.
-                    try_direction(active_cell, &Direction::Up)
-                        .or_else(|| try_direction(active_cell, &Direction::UpperLeft))
-                        .or_else(|| try_direction(active_cell, &Direction::UpperRight))
-                },
-                Direction::Down => {
-                    // For Down, try Down first then fall back to LowerLeft, then fail back to LowerRight.
-                    try_direction(active_cell, &Direction::Down)
-                        .or_else(|| try_direction(active_cell, &Direction::LowerLeft))
-                        .or_else(|| try_direction(active_cell, &Direction::LowerRight))
-                },
-            }
-        };
+        let picked = Self::resolve_move(&self.move_policy, active_cell, direction);
 
         // If no valid direction is picked, return an error with the original direction and user-facing available moves.
         let effective_direction = picked.ok_or_else(|| Error::MoveUnavailable {
@@ -348,15 +897,155 @@ impl Grid {
         }
 
         // Get the neighbor coordinate based on the effective direction.
-        let neighbor_coords = *active_cell.neighbors_by_direction.get(&effective_direction)
+        let neighbor_coords = active_cell.neighbors_by_direction.get(&effective_direction)
             .ok_or(Error::InvalidDirection { direction: effective_direction.to_string() })?;
 
+        if let Some(door) = self.locked_door(original_coords, neighbor_coords) {
+            return Err(Error::DoorLocked { key: door.key });
+        }
+
+        let record = self.apply_move(original_coords, neighbor_coords, original_direction, effective_direction)?;
+        #[cfg(feature = "std")]
+        self.record_in_session(&record);
+        self.move_history.push(record);
+        self.update_completion();
+        // a new move invalidates any moves which were undone
+        self.undone_moves.clear();
+
+        Ok(effective_direction)
+    }
+
+    /// Move to the open neighbor of the active cell nearest the target cell, measured between the cells' centers
+    /// as rendered, for touch interfaces where the user taps a cell, or swipes toward one, rather than choosing
+    /// a direction. Returns the direction taken, or fails with `Error::NoMoveToward` when no open neighbor lies
+    /// nearer the target than the active cell itself, as when the active cell is tapped.
+    pub fn make_move_toward(&mut self, target: Coordinates) -> Result<Direction, Error> {
+        let active = self.get(self.active_coords()?)?;
+        let aim = render::center(self, target, 1.0)?;
+        let distance_squared = |coords: Coordinates| {
+            render::center(self, coords, 1.0).map(|point| (point.x - aim.x).powi(2) + (point.y - aim.y).powi(2))
+        };
+        let mut nearest = None;
+        let mut nearest_distance = distance_squared(active.coords)?;
+        // directions are tried in a fixed order, so that ties resolve the same way every time
+        for &direction in self.all_moves() {
+            if let Some(neighbor) = active.neighbors_by_direction.get(&direction).filter(|neighbor| active.linked.contains(neighbor)) {
+                let distance = distance_squared(neighbor)?;
+                if distance < nearest_distance {
+                    nearest = Some(direction);
+                    nearest_distance = distance;
+                }
+            }
+        }
+        // the exact direction of an open passage is taken whatever the move policy
+        self.make_move(nearest.ok_or(Error::NoMoveToward { target })?)
+    }
+
+    /// Move in a direction, then keep following the passage, around any bends, until reaching a junction, a dead
+    /// end or the goal, as in the "tunnel" movement of many maze games. Each step is an ordinary move, recorded
+    /// in the move history and marking its cell visited. Returns the cells traversed in order, ending with the
+    /// cell stopped at, or fails as make_move does when the first move is unavailable. A locked door along the
+    /// way ends the movement early.
+    pub fn move_until_junction(&mut self, direction: Direction) -> Result<Vec<Coordinates>, Error> {
+        let origin = self.active_coords()?;
+        self.make_move(direction)?;
+        let mut previous = origin;
+        let mut traversed = vec![self.active_coords()?];
+        loop {
+            let current = self.get(self.active_coords()?)?;
+            if current.coords == self.goal_coords || current.linked.len() != 2 {
+                break;
+            }
+            let Some(next) = current.linked.iter().find(|&linked| linked != previous) else {
+                break;
+            };
+            // a corridor closing on itself would otherwise be followed forever
+            if next == origin {
+                break;
+            }
+            let toward = self.all_moves().iter().find(|&d| current.open_walls.contains(d) && current.neighbors_by_direction.get(d) == Some(next));
+            let Some(&toward) = toward else {
+                break;
+            };
+            previous = current.coords;
+            if self.make_move(toward).is_err() {
+                break;
+            }
+            traversed.push(next);
+        }
+        Ok(traversed)
+    }
+
+    /// Add a player to race through the maze from the start cell given, failing if a player with the same id
+    /// has already been added or the start is not a cell of the maze.
+    pub fn add_player(&mut self, id: u32, start: Coordinates) -> Result<&Player, Error> {
+        if self.player(id).is_some() {
+            return Err(Error::DuplicatePlayer { id });
+        }
+        self.get(start).map_err(|_| Error::InvalidStartCoordinates { coordinates: start })?;
+        let mut player = Player::new(id, start);
+        player.completed = self.player_completed(&player);
+        self.players.push(player);
+        Ok(&self.players[self.players.len() - 1])
+    }
+
+    /// Remove a player from the maze, returning its final state.
+    pub fn remove_player(&mut self, id: u32) -> Result<Player, Error> {
+        let index = self.players.iter().position(|player| player.id == id).ok_or(Error::UnknownPlayer { id })?;
+        Ok(self.players.remove(index))
+    }
+
+    /// The player with the specified id, if it has been added.
+    pub fn player(&self, id: u32) -> Option<&Player> {
+        self.players.iter().find(|player| player.id == id)
+    }
+
+    /// Move a player in the specified direction, as `make_move` moves the user: falling back to adjacent
+    /// directions as the move policy allows, and stopped by doors whose keys the player has not collected.
+    /// Returns the direction actually taken.
+    pub fn make_move_for(&mut self, id: u32, direction: Direction) -> Result<Direction, Error> {
+        let index = self.players.iter().position(|player| player.id == id).ok_or(Error::UnknownPlayer { id })?;
+        let from = self.players[index].coords;
+        let cell = self.get(from)?;
+        let taken = Self::resolve_move(&self.move_policy, cell, direction).ok_or_else(|| Error::MoveUnavailable {
+            attempted_move: direction,
+            available_moves: cell.get_user_facing_open_walls(),
+        })?;
+        let to = cell.neighbors_by_direction.get(&taken).ok_or(Error::InvalidDirection { direction: taken.to_string() })?;
+        let player = &self.players[index];
+        if let Some(door) = self.doors.iter().find(|door| door.joins(from, to) && !player.has_been_visited.contains(&door.key)) {
+            return Err(Error::DoorLocked { key: door.key });
+        }
+        self.players[index].apply_move(to, direction, taken);
+        self.players[index].completed = self.player_completed(&self.players[index]);
+        Ok(taken)
+    }
+
+    // Whether the player has visited every target or, without targets, is on the goal
+    fn player_completed(&self, player: &Player) -> bool {
+        if self.targets.is_empty() {
+            player.coords == self.goal_coords
+        } else {
+            self.targets.iter().all(|target| player.has_been_visited.contains(target))
+        }
+    }
+
+    // Move the active cell from one cell to its neighbor, returning a record sufficient to reverse the move.
+    fn apply_move(
+        &mut self,
+        from: Coordinates,
+        to: Coordinates,
+        requested: Direction,
+        direction: Direction,
+    ) -> Result<MoveRecord, Error> {
         // Determine whether this move is a backtracking move by checking if the neighbor is already visited.
         let going_back: bool;
+        let to_had_been_visited: bool;
         {
             // Mutably borrow the next cell.
-            let next_cell = self.get_mut(neighbor_coords)?;
+            let next_cell = self.get_mut(to)?;
             going_back = next_cell.is_visited;  // If already visited, then we're going backward.
+            to_had_been_visited = next_cell.has_been_visited;
             if !going_back {
                 // For a forward move: mark the new cell as visited.
                 next_cell.set_visited(true);
@@ -366,7 +1055,7 @@ impl Grid {
         }
         {
             // Now handle the previously active cell.
-            let previous_cell = self.get_mut(original_coords)?;
+            let previous_cell = self.get_mut(from)?;
             if going_back {
                 // For a backtracking move: unvisit the cell that we are leaving.
                 previous_cell.set_visited(false);
@@ -374,8 +1063,327 @@ impl Grid {
             // Mark the previous cell as no longer active.
             previous_cell.set_active(false);
         }
+        self.mark_changed(&[from, to]);
+        self.log_event(GridEvent::ActiveMoved { from, to });
+        if going_back {
+            self.log_event(GridEvent::VisitedChanged { coords: from, is_visited: false });
+        } else {
+            self.log_event(GridEvent::VisitedChanged { coords: to, is_visited: true });
+        }
+        if let Some(radius) = self.visibility_radius {
+            self.discover_from(to, radius)?;
+        }
 
-        Ok(effective_direction)
+        Ok(MoveRecord { from, to, requested, direction, backtrack: going_back, to_had_been_visited })
+    }
+
+    /// Cells visible from the specified cell: the cell itself, and the cells seen looking straight down each
+    /// open corridor leading from it, up to `radius` cells away or until a wall blocks the view.
+    pub fn visible_cells_from(&self, coords: Coordinates, radius: usize) -> HashSet<Coordinates> {
+        let mut visible = HashSet::new();
+        let Ok(origin) = self.get(coords) else {
+            return visible;
+        };
+        visible.insert(coords);
+        for direction in origin.neighbors_by_direction.keys() {
+            let mut cell = origin;
+            let mut heading = direction;
+            for _ in 0..radius {
+                let Some(next) = cell.neighbors_by_direction.get(&heading).filter(|next| cell.linked.contains(next)) else {
+                    break;
+                };
+                visible.insert(next);
+                let Ok(next_cell) = self.get(next) else {
+                    break;
+                };
+                // Delta corridors along a row alternate between upright and inverted triangles
+                heading = match (self.maze_type, heading) {
+                    (MazeType::Delta, Direction::UpperLeft) => Direction::LowerLeft,
+                    (MazeType::Delta, Direction::LowerLeft) => Direction::UpperLeft,
+                    (MazeType::Delta, Direction::UpperRight) => Direction::LowerRight,
+                    (MazeType::Delta, Direction::LowerRight) => Direction::UpperRight,
+                    _ => heading,
+                };
+                cell = next_cell;
+            }
+        }
+        visible
+    }
+
+    /// Enable fog of war: cells start out undiscovered, and those visible from the active cell (see
+    /// `visible_cells_from`) are discovered as the user moves.
+    pub fn enable_fog_of_war(&mut self, radius: usize) -> Result<(), Error> {
+        self.visibility_radius = Some(radius);
+        for cell in self.cells.iter_mut().flatten() {
+            cell.is_discovered = false;
+        }
+        self.mark_all_changed();
+        let active = self.active_coords()?;
+        self.discover_from(active, radius)
+    }
+
+    // Mark the cells visible from the specified cell as discovered
+    fn discover_from(&mut self, coords: Coordinates, radius: usize) -> Result<(), Error> {
+        let mut discovered = Vec::new();
+        for visible in self.visible_cells_from(coords, radius) {
+            let cell = self.get_mut(visible)?;
+            if !cell.is_discovered {
+                cell.is_discovered = true;
+                discovered.push(visible);
+            }
+        }
+        self.mark_changed(&discovered);
+        Ok(())
+    }
+
+    /// Cells whose state changed after the specified revision, in row-major order, so a client which last
+    /// fetched cells at that revision need only redraw these. Changes made by mutating cells directly (e.g.
+    /// through `get_mut`) are not tracked.
+    pub fn changed_cells_since(&self, revision: u64) -> Vec<&Cell> {
+        self.cells
+            .iter()
+            .zip(&self.cell_revisions)
+            .filter(|(_, &changed_at)| changed_at > revision)
+            .filter_map(|(cell, _)| cell.as_ref())
+            .collect()
+    }
+
+    // Advance the revision, recording that the specified cells changed in it
+    fn mark_changed(&mut self, changed: &[Coordinates]) {
+        self.revision += 1;
+        for coords in changed {
+            let index = self.get_flattened_index(coords.x, coords.y);
+            if let Some(changed_at) = self.cell_revisions.get_mut(index) {
+                *changed_at = self.revision;
+            }
+        }
+    }
+
+    // Advance the revision, recording that every cell changed in it
+    fn mark_all_changed(&mut self) {
+        self.revision += 1;
+        self.cell_revisions.fill(self.revision);
+    }
+
+    /// Take the changes to passages, the active cell and the user's trail made since the previous call, oldest
+    /// first, so that a user interface can update just what changed. Changes are logged from the first call
+    /// on, which returns none; changes made by mutating cells directly (e.g. through `get_mut`) are not logged.
+    pub fn drain_events(&mut self) -> Vec<GridEvent> {
+        core::mem::take(self.event_log.get_or_insert_with(Vec::new))
+    }
+
+    // Log a change, once `drain_events` has started the log
+    fn log_event(&mut self, event: GridEvent) {
+        if let Some(log) = &mut self.event_log {
+            log.push(event);
+        }
+    }
+
+    /// Switch the maze to "collect all" mode, where it is completed by visiting every target cell rather
+    /// than by reaching the goal. An empty list of targets restores the usual reach-the-goal mode.
+    pub fn set_targets(&mut self, targets: Vec<Coordinates>) -> Result<(), Error> {
+        if let Some(&coordinates) = targets.iter().find(|coords| !self.has_cell(coords.x, coords.y)) {
+            return Err(Error::InvalidTargetCoordinates { coordinates });
+        }
+        self.targets = targets;
+        self.update_completion();
+        Ok(())
+    }
+
+    /// Target cells the user has yet to visit, in "collect all" mode.
+    pub fn remaining_targets(&self) -> Vec<Coordinates> {
+        self.targets
+            .iter()
+            .copied()
+            .filter(|coords| !self.get(*coords).map(|cell| cell.has_been_visited).unwrap_or(false))
+            .collect()
+    }
+
+    // Whether every target has been visited or, without targets, the user is on the goal
+    fn update_completion(&mut self) {
+        self.completed = if self.targets.is_empty() {
+            self.active_coords().is_ok_and(|coords| coords == self.goal_coords)
+        } else {
+            self.remaining_targets().is_empty()
+        };
+    }
+
+    /// Begin tracking elapsed time, moves, wrong turns and backtracks for the user's attempt at solving
+    /// the maze, and recording a replay of it, replacing any previous session.
+    #[cfg(feature = "std")]
+    pub fn start_session(&mut self) {
+        self.session = Some(self.active_coords().map(Session::recording).unwrap_or_default());
+    }
+
+    /// Pause the session's clock, e.g. while the app is in the background.
+    #[cfg(feature = "std")]
+    pub fn pause(&mut self) {
+        if let Some(session) = &mut self.session {
+            session.pause();
+        }
+    }
+
+    /// Resume the session's clock after a `pause`.
+    #[cfg(feature = "std")]
+    pub fn resume(&mut self) {
+        if let Some(session) = &mut self.session {
+            session.resume();
+        }
+    }
+
+    /// Telemetry for the current session, or `None` when no session has been started.
+    #[cfg(feature = "std")]
+    pub fn stats(&self) -> Option<SessionStats> {
+        self.session.as_ref().map(Session::stats)
+    }
+
+    /// The replay of the user's moves recorded during the current session, for racing against later, or `None`
+    /// when no session has been started.
+    #[cfg(feature = "std")]
+    pub fn recorded_replay(&self) -> Option<&Replay> {
+        self.session.as_ref().and_then(Session::replay)
+    }
+
+    /// The cell a replay's user was on at the specified tick, in milliseconds from the start of the recording,
+    /// for drawing the ghost of an earlier run alongside the user. Fails if the replay does not fit this maze.
+    pub fn replay(&self, replay: &Replay, tick: u64) -> Result<Coordinates, Error> {
+        let coords = replay.position_at(tick);
+        self.get(coords)?;
+        Ok(coords)
+    }
+
+    // Count a move in the current session, if any
+    #[cfg(feature = "std")]
+    fn record_in_session(&mut self, record: &MoveRecord) {
+        if self.session.is_none() {
+            return;
+        }
+        let distances = self.distance_field(self.goal_coords);
+        let away_from_goal = match (distances.get(&record.from), distances.get(&record.to)) {
+            (Some(from), Some(to)) => to >= from,
+            _ => true,
+        };
+        if let Some(session) = &mut self.session {
+            session.record(record, away_from_goal);
+        }
+    }
+
+    /// The locked door across the passage between the specified cells, if any.
+    pub fn locked_door(&self, a: Coordinates, b: Coordinates) -> Option<&Door> {
+        self.doors.iter().find(|door| door.joins(a, b) && door.is_locked(self))
+    }
+
+    /// Reverse the most recent user move, restoring the active and visited state of both cells involved.
+    pub fn undo(&mut self) -> Result<MoveRecord, Error> {
+        let record = self.move_history.pop().ok_or(Error::NoMovesToUndo)?;
+        {
+            let to_cell = self.get_mut(record.to)?;
+            to_cell.set_active(false);
+            if !record.backtrack {
+                to_cell.set_visited(false);
+            }
+            to_cell.has_been_visited = record.to_had_been_visited;
+        }
+        {
+            let from_cell = self.get_mut(record.from)?;
+            from_cell.set_active(true);
+            if record.backtrack {
+                from_cell.set_visited(true);
+            }
+        }
+        self.mark_changed(&[record.from, record.to]);
+        self.log_event(GridEvent::ActiveMoved { from: record.to, to: record.from });
+        if record.backtrack {
+            self.log_event(GridEvent::VisitedChanged { coords: record.from, is_visited: true });
+        } else {
+            self.log_event(GridEvent::VisitedChanged { coords: record.to, is_visited: false });
+        }
+        #[cfg(feature = "std")]
+        if let Some(session) = &mut self.session {
+            session.record_position(record.from);
+        }
+        self.undone_moves.push(record.clone());
+        self.update_completion();
+        Ok(record)
+    }
+
+    /// Re-apply the most recently undone user move.
+    pub fn redo(&mut self) -> Result<MoveRecord, Error> {
+        let undone = self.undone_moves.pop().ok_or(Error::NoMovesToRedo)?;
+        let record = self.apply_move(undone.from, undone.to, undone.requested, undone.direction)?;
+        #[cfg(feature = "std")]
+        self.record_in_session(&record);
+        self.move_history.push(record.clone());
+        self.update_completion();
+        Ok(record)
+    }
+
+    /// The user moves made so far, in the order they were taken (excluding undone moves).
+    pub fn move_history(&self) -> &[MoveRecord] {
+        &self.move_history
+    }
+
+    /// The next direction to move from the currently active cell to stay on the shortest path to the goal.
+    ///
+    /// The returned direction is one that `make_move` accepts, so clients can offer a "nudge" without
+    /// revealing the whole solution. Returns `None` when there is no single active cell, the active cell
+    /// is already the goal, or the goal is unreachable.
+    pub fn hint(&self) -> Option<Direction> {
+        let active = self.get(self.active_coords().ok()?).ok()?;
+        if active.coords == self.goal_coords {
+            return None;
+        }
+        let distances = self.distance_field(self.goal_coords);
+        let remaining = *distances.get(&active.coords)?;
+        let next = active
+            .linked
+            .iter()
+            .find(|coords| distances.get(coords) == Some(&(remaining - 1)))?;
+        let leads_to_next = |direction: &Direction| {
+            Self::resolve_move(&self.move_policy, active, *direction)
+                .and_then(|resolved| active.neighbors_by_direction.get(&resolved))
+                == Some(next)
+        };
+        // prefer a direction which needs no fallback, then any direction make_move resolves to the next cell
+        self.all_moves()
+            .iter()
+            .find(|d| Self::resolve_move(&self.move_policy, active, **d) == Some(**d) && leads_to_next(d))
+            .or_else(|| self.all_moves().iter().find(|d| leads_to_next(d)))
+            .copied()
+    }
+
+    /// The direction to step from `from` along the shortest path through the maze's passages to `to`, so a game
+    /// AI can chase a target around walls. Returns `None` when the cells are the same, either is not a cell of
+    /// the maze, or `to` is unreachable from `from`.
+    pub fn next_step_towards(&self, from: Coordinates, to: Coordinates) -> Option<Direction> {
+        let cell = self.get(from).ok()?;
+        let distances = self.distance_field(to);
+        let remaining = *distances.get(&from)?;
+        let next = cell.linked.iter().find(|coords| remaining > 0 && distances.get(coords) == Some(&(remaining - 1)))?;
+        Self::direction_to(cell, next)
+    }
+
+    /// The direction to step from `from` which leads farthest from `threat` through the maze's passages, so a
+    /// game AI can flee around walls. Returns `None` when `from` is not a cell of the maze, or no open
+    /// passage leads farther from the threat (the fleeing cell is cornered).
+    pub fn flee_direction(&self, from: Coordinates, threat: Coordinates) -> Option<Direction> {
+        let cell = self.get(from).ok()?;
+        let distances = self.distance_field(threat);
+        // unreachable cells are as far from the threat as it gets
+        let distance = |coords: &Coordinates| distances.get(coords).copied().unwrap_or(u32::MAX);
+        let current = distance(&from);
+        let mut neighbors: Vec<Coordinates> = cell.linked.iter().filter(|coords| distance(coords) > current).collect();
+        neighbors.sort();
+        let next = neighbors.into_iter().max_by_key(|coords| distance(coords))?;
+        Self::direction_to(cell, next)
+    }
+
+    // The direction from a cell to one of its neighbors
+    fn direction_to(cell: &Cell, neighbor: Coordinates) -> Option<Direction> {
+        cell.neighbors_by_direction
+            .iter()
+            .find(|&(_, coords)| coords == neighbor)
+            .map(|(direction, _)| direction)
     }
 
     /// Retrieve a cell by its coordinates
@@ -403,15 +1411,30 @@ impl Grid {
         }
         let index = self.get_flattened_index(coords.x, coords.y);
         self.cells[index] = Some(cell);
+        self.invalidate_distances();
         Ok(())
     }
 
     /// Random unsigned integer within bounds of an upper boundary
     pub fn bounded_random_usize(&mut self, upper_bound: usize) -> usize {
-        let mut rng = thread_rng();
-        let seed= rng.gen_range(0..upper_bound);
-        self.seed = seed as u64;
-        return seed;
+        self.rng.bounded_usize(upper_bound)
+    }
+
+    /// The random number generator the generation algorithms draw from, seeded from the grid's seed, for
+    /// custom algorithms which need more than `bounded_random_usize` and `random_bool`.
+    pub fn rng(&mut self) -> &mut GridRng {
+        &mut self.rng
+    }
+
+    /// Seed the grid's random numbers, so generating again with the same seed makes the same random choices.
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = GridRng::seed_from_u64(seed);
+    }
+
+    // An independent generator seeded from the grid's, for use while the grid itself is borrowed
+    pub(crate) fn forked_rng(&mut self) -> GridRng {
+        self.rng.fork()
     }
 
     /// Random boolean
@@ -433,7 +1456,15 @@ impl Grid {
         if self.maze_type != MazeType::Delta {
             return Err(Error::InvalidCellForNonDeltaMaze { cell_maze_type: self.maze_type } );
         }
-        let mut row_starts_with_upright = true;
+        let mut row_starts_with_upright = self.delta_orientation == CellOrientation::Normal;
+        // a triangle-shaped maze's orientation is that of its first triangle, which starts an upward triangle's
+        // top row halfway across the grid
+        let first_column = (0..self.width)
+            .find(|&x| self.delta_shape.contains(self.delta_orientation, self.height, Coordinates { x, y: 0 }))
+            .unwrap_or_default();
+        if first_column % 2 == 1 {
+            row_starts_with_upright = !row_starts_with_upright;
+        }
 
         let triangle_orientation = |upward: bool| {
             if upward {
@@ -449,6 +1480,11 @@ impl Grid {
 
             for col in 0..self.width {
                 upright = !upright;
+                if !self.has_cell(col, row) {
+                    let index = self.get_flattened_index(col, row);
+                    self.cells[index] = None;
+                    continue;
+                }
                 let coords = Coordinates { x: col, y: row };
                 let is_start = coords == self.start_coords;
                 let is_goal = coords == self.goal_coords;
@@ -465,12 +1501,103 @@ impl Grid {
                 .orientation(triangle_orientation(upright))
                 .build();
 
-                self.set(cell)?;
+                self.set(cell)?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Rebuild a Delta maze's cells so that its top-left triangle has the specified orientation: `Normal` for a
+    /// triangle pointing up, as in a newly created grid, or `Inverted` for one pointing down. The cells are
+    /// rebuilt without passages, so set the orientation before generating the maze.
+    pub fn set_delta_orientation(&mut self, orientation: CellOrientation) -> Result<(), Error> {
+        if self.maze_type != MazeType::Delta {
+            return Err(Error::InvalidCellForNonDeltaMaze { cell_maze_type: self.maze_type });
+        }
+        self.delta_orientation = orientation;
+        self.initialize_triangle_cells()?;
+        self.assign_neighbors()?;
+        self.distance_cache.clear();
+        self.mark_all_changed();
+        Ok(())
+    }
+
+    /// Rebuild a Delta maze's cells in the specified outline. A `Triangle` must be `2 * height - 1` triangles
+    /// wide and contain the start and goal; it points up or down as set by `set_delta_orientation`. The cells
+    /// are rebuilt without passages, so set the shape before generating the maze.
+    pub fn set_delta_shape(&mut self, shape: DeltaShape) -> Result<(), Error> {
+        if self.maze_type != MazeType::Delta {
+            return Err(Error::InvalidCellForNonDeltaMaze { cell_maze_type: self.maze_type });
+        }
+        if shape == DeltaShape::Triangle && self.width + 1 != 2 * self.height {
+            return Err(Error::InvalidDeltaShape {
+                reason: format!("a triangle {} rows tall must be {} triangles wide, not {}", self.height, 2 * self.height - 1, self.width),
+            });
+        }
+        let previous = self.delta_shape;
+        self.delta_shape = shape;
+        if let Err(error) = self.validate_endpoints() {
+            self.delta_shape = previous;
+            return Err(error);
+        }
+        self.initialize_triangle_cells()?;
+        self.assign_neighbors()?;
+        self.distance_cache.clear();
+        self.mark_all_changed();
+        Ok(())
+    }
+
+    /// Keep only the positions the mask marks `true`, in row-major order, leaving the others without cells, or
+    /// restore a cell at every position with `None`. The kept cells must include the start and goal and all be
+    /// joined through their neighbors. The cells are rebuilt without passages, so set the mask before generating
+    /// the maze, with an algorithm which can generate Rhombic mazes, as the others expect a cell at every position.
+    pub fn set_mask(&mut self, mask: Option<Vec<bool>>) -> Result<(), Error> {
+        if let Some(mask) = &mask {
+            if self.layers > 1 {
+                return Err(Error::InvalidMask { reason: "multi-layer mazes cannot be masked".to_string() });
             }
+            if mask.len() != self.width * self.height {
+                return Err(Error::InvalidMask {
+                    reason: format!("a {}x{} maze needs a mask of {} positions, not {}", self.width, self.height, self.width * self.height, mask.len()),
+                });
+            }
+        }
+        let previous = core::mem::replace(&mut self.mask, mask);
+        if let Err(error) = self.validate_endpoints() {
+            self.mask = previous;
+            return Err(error);
+        }
+        self.rebuild_cells()?;
+        if mask::regions(self, |_| true).len() > 1 {
+            self.mask = previous;
+            self.rebuild_cells()?;
+            return Err(Error::InvalidMask { reason: "the kept cells must all be joined through their neighbors".to_string() });
         }
         Ok(())
     }
-    
+
+    // Rebuild every cell without passages, once the positions which have cells change
+    pub(crate) fn rebuild_cells(&mut self) -> Result<(), Error> {
+        match self.maze_type {
+            MazeType::Delta => self.initialize_triangle_cells()?,
+            _ => self.initialize_non_triangle_cells()?,
+        }
+        self.assign_neighbors()?;
+        self.distance_cache.clear();
+        self.mark_all_changed();
+        Ok(())
+    }
+
+    /// Default start and goal for a triangle-shaped Delta maze of `height` rows: the bottom-left corner to the
+    /// apex of an upward triangle, or the top-left corner to the tip of a downward one.
+    pub fn default_triangle_endpoints(height: usize, orientation: CellOrientation) -> (Coordinates, Coordinates) {
+        let last = height.saturating_sub(1);
+        match orientation {
+            CellOrientation::Normal => (Coordinates { x: 0, y: last }, Coordinates { x: last, y: 0 }),
+            CellOrientation::Inverted => (Coordinates { x: 0, y: 0 }, Coordinates { x: last, y: last }),
+        }
+    }
+
     /// Prepare grid for non-Delta maze type by initialzing cells as non-triangular (e.g. do not have any Inverted)
     pub fn initialize_non_triangle_cells(&mut self) -> Result<(), Error> {
         if self.maze_type == MazeType::Delta {
@@ -482,7 +1609,9 @@ impl Grid {
             .flat_map(|row| (0..grid_width).map(move |col| (row, col)))
             .for_each(|(row, col)| {
                 if !self.has_cell(col, row) {
-                    return; // Skip positions where no cell should exist (e.g., Rhombic)
+                    let index = self.get_flattened_index(col, row);
+                    self.cells[index] = None; // No cell should exist here (e.g., Rhombic)
+                    return;
                 }
                 let coords = Coordinates { x: col, y: row };
                 let is_start = coords == self.start_coords;
@@ -572,6 +1701,29 @@ impl Grid {
         goal: Coordinates,
         capture_steps: bool,
     ) -> Result<Self, Error> {
+        Self::new_layered(maze_type, width, height, 1, start, goal, capture_steps)
+    }
+
+    /// Create a new grid of `layers` stacked layers, each `width` by `layer_height`, where each cell leads
+    /// `Above` and `Below` to the cell at the same position on the neighboring layers. Layers are stored one
+    /// below the other, so layer `n` occupies rows `n * layer_height` through `(n + 1) * layer_height - 1`,
+    /// and start and goal coordinates are given in those rows. Only Orthogonal mazes can have multiple layers.
+    pub fn new_layered(
+        maze_type: MazeType,
+        width: usize,
+        layer_height: usize,
+        layers: usize,
+        start: Coordinates,
+        goal: Coordinates,
+        capture_steps: bool,
+    ) -> Result<Self, Error> {
+        if layers == 0 {
+            return Err(Error::InvalidLayers { reason: "a maze needs at least 1 layer".to_string() });
+        }
+        if layers > 1 && maze_type != MazeType::Orthogonal {
+            return Err(Error::InvalidLayers { reason: format!("only Orthogonal mazes can have multiple layers, not {:?}", maze_type) });
+        }
+        let height = layer_height * layers;
 
         if capture_steps && (width > 100 || height > 100) {
             return Err(Error::GridDimensionsExceedLimitForCaptureSteps { width, height });
@@ -581,13 +1733,36 @@ impl Grid {
         let mut grid = Grid {
             width,
             height,
+            layers,
             maze_type,
             cells: vec![None; width * height],  // Initialize with None instead of CellBuilder
             seed,
             start_coords: start,
             goal_coords: goal,
             capture_steps,
-            generation_steps: if capture_steps { Some(Vec::new()) } else { None },
+            generation_steps: if capture_steps { Some(GenerationSteps::new()) } else { None },
+            generation_step_details: if capture_steps { Some(Vec::new()) } else { None },
+            solving_steps: None,
+            move_history: Vec::new(),
+            undone_moves: Vec::new(),
+            doors: Vec::new(),
+            #[cfg(feature = "std")]
+            session: None,
+            targets: Vec::new(),
+            completed: false,
+            visibility_radius: None,
+            move_policy: MovePolicy::default(),
+            players: Vec::new(),
+            rooms: Vec::new(),
+            delta_orientation: CellOrientation::default(),
+            delta_shape: DeltaShape::default(),
+            mask: None,
+            revision: 0,
+            cell_revisions: vec![0; width * height],
+            progress: None,
+            rng: GridRng::seed_from_u64(seed),
+            distance_cache: DistanceCache::default(),
+            event_log: None,
         };
 
         // Generate different types of cells based on maze_type
@@ -598,6 +1773,9 @@ impl Grid {
 
         // Assign neighbor information based on maze type
         grid.assign_neighbors()?;
+        if layers > 1 {
+            grid.assign_layer_neighbors()?;
+        }
 
         // Validate start and goal coordinates
         grid.validate_endpoints()?;
@@ -606,10 +1784,16 @@ impl Grid {
     }
 
     /// Generate a seed based on the grid dimensions.
+    #[cfg(feature = "std")]
+    fn generate_seed(width: usize, height: usize) -> u64 {
+        rand::thread_rng().gen_range(0..(width * height + 1)) as u64
+    }
+
+    /// Without `std` there is no source of entropy, so every grid of the same size starts from the same seed;
+    /// request a seed to vary them.
+    #[cfg(not(feature = "std"))]
     fn generate_seed(width: usize, height: usize) -> u64 {
-        use rand::{thread_rng, Rng};
-        let mut rng = thread_rng();
-        rng.gen_range(0..(width * height + 1)) as u64
+        (width * height) as u64
     }
 
     /// Assign neighbor relationships for each cell based on the maze type.
@@ -630,7 +1814,6 @@ impl Grid {
                 if !self.has_cell(x, y) {
                     continue;
                 }
-                let mut cell = self.get_mut_by_coords(x, y)?.clone();
                 let mut neighbors: HashMap<Direction, Coordinates> = HashMap::new();
                 if y > 0 && self.has_cell(x, y - 1) {
                     neighbors.insert(Direction::Up, Coordinates { x, y: y - 1 });
@@ -644,8 +1827,42 @@ impl Grid {
                 if x > 0 && self.has_cell(x - 1, y) {
                     neighbors.insert(Direction::Left, Coordinates { x: x - 1, y });
                 }
-                cell.set_neighbors(neighbors);
-                self.set(cell)?;
+                self.get_mut_by_coords(x, y)?.set_neighbors(neighbors);
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of rows in each layer of the grid.
+    pub fn layer_height(&self) -> usize {
+        self.height / self.layers
+    }
+
+    /// The layer containing the specified coordinates, layer 0 being the topmost rows of the grid.
+    pub fn layer_of(&self, coords: Coordinates) -> usize {
+        coords.y / self.layer_height()
+    }
+
+    // Replace the Up and Down neighbors across layer boundaries with stairs to the cell at the same
+    // position on the layers Above and Below
+    fn assign_layer_neighbors(&mut self) -> Result<(), Error> {
+        let layer_height = self.layer_height();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let height = self.height;
+                let neighbors = &mut self.get_mut_by_coords(x, y)?.neighbors_by_direction;
+                if y % layer_height == 0 {
+                    neighbors.remove(&Direction::Up);
+                }
+                if y % layer_height == layer_height - 1 {
+                    neighbors.remove(&Direction::Down);
+                }
+                if y + layer_height < height {
+                    neighbors.insert(Direction::Above, Coordinates { x, y: y + layer_height });
+                }
+                if y >= layer_height {
+                    neighbors.insert(Direction::Below, Coordinates { x, y: y - layer_height });
+                }
             }
         }
         Ok(())
@@ -655,7 +1872,10 @@ impl Grid {
     fn assign_neighbors_delta(&mut self) -> Result<(), Error> {
         for row in 0..self.height {
             for col in 0..self.width {
-                let mut cell = self.get_mut_by_coords(col, row)?.clone();
+                if !self.has_cell(col, row) {
+                    continue;
+                }
+                let orientation = self.get_by_coords(col, row)?.orientation;
                 let mut neighbors: HashMap<Direction, Coordinates> = HashMap::new();
                 
                 // Left and right neighbors
@@ -663,7 +1883,7 @@ impl Grid {
                 let right = if col < self.width - 1 { Some(Coordinates { x: col + 1, y: row }) } else { None };
                 
                 if let Some(left_coords) = left {
-                    let key = if cell.orientation == CellOrientation::Normal {
+                    let key = if orientation == CellOrientation::Normal {
                         Direction::UpperLeft
                     } else {
                         Direction::LowerLeft
@@ -671,7 +1891,7 @@ impl Grid {
                     neighbors.insert(key, left_coords);
                 }
                 if let Some(right_coords) = right {
-                    let key = if cell.orientation == CellOrientation::Normal {
+                    let key = if orientation == CellOrientation::Normal {
                         Direction::UpperRight
                     } else {
                         Direction::LowerRight
@@ -680,12 +1900,12 @@ impl Grid {
                 }
                 
                 // Up and down neighbors based on orientation.
-                let up = if cell.orientation == CellOrientation::Inverted && row > 0 { 
+                let up = if orientation == CellOrientation::Inverted && row > 0 { 
                     Some(Coordinates { x: col, y: row - 1 })
                 } else { 
                     None 
                 };
-                let down = if cell.orientation == CellOrientation::Normal && row < self.height - 1 {
+                let down = if orientation == CellOrientation::Normal && row < self.height - 1 {
                     Some(Coordinates { x: col, y: row + 1 })
                 } else {
                     None
@@ -696,8 +1916,8 @@ impl Grid {
                 if let Some(down_coords) = down {
                     neighbors.insert(Direction::Down, down_coords);
                 }
-                cell.set_neighbors(neighbors);
-                self.set(cell)?;
+                neighbors.retain(|_, coords| self.has_cell(coords.x, coords.y));
+                self.get_mut_by_coords(col, row)?.set_neighbors(neighbors);
             }
         }
         Ok(())
@@ -710,7 +1930,9 @@ impl Grid {
         
         for row in 0..self.height {
             for col in 0..self.width {
-                let mut cell = self.get_mut_by_coords(col, row)?.clone();
+                if !self.has_cell(col, row) {
+                    continue;
+                }
                 let mut neighbors: HashMap<Direction, Coordinates> = HashMap::new();
 
                 let (north_diagonal, south_diagonal) = match is_even(col) {
@@ -722,41 +1944,41 @@ impl Grid {
                 if col > 0 && north_diagonal < self.height {
                     neighbors.insert(
                         Direction::UpperLeft,
-                        self.get_by_coords(col - 1, north_diagonal)?.coords,
+                        Coordinates { x: col - 1, y: north_diagonal },
                     );
                 }
                 if col < self.width && row > 0 {
                     neighbors.insert(
                         Direction::Up,
-                        self.get_by_coords(col, row - 1)?.coords,
+                        Coordinates { x: col, y: row - 1 },
                     );
                 }
                 if col < self.width - 1 && north_diagonal < self.height {
                     neighbors.insert(
                         Direction::UpperRight,
-                        self.get_by_coords(col + 1, north_diagonal)?.coords,
+                        Coordinates { x: col + 1, y: north_diagonal },
                     );
                 }
                 if col > 0 && south_diagonal < self.height {
                     neighbors.insert(
                         Direction::LowerLeft,
-                        self.get_by_coords(col - 1, south_diagonal)?.coords,
+                        Coordinates { x: col - 1, y: south_diagonal },
                     );
                 }
                 if row < self.height - 1 && col < self.width {
                     neighbors.insert(
                         Direction::Down,
-                        self.get_by_coords(col, row + 1)?.coords,
+                        Coordinates { x: col, y: row + 1 },
                     );
                 }
                 if col < self.width - 1 && south_diagonal < self.height {
                     neighbors.insert(
                         Direction::LowerRight,
-                        self.get_by_coords(col + 1, south_diagonal)?.coords,
+                        Coordinates { x: col + 1, y: south_diagonal },
                     );
                 }
-                cell.set_neighbors(neighbors);
-                self.set(cell)?;
+                neighbors.retain(|_, coords| self.has_cell(coords.x, coords.y));
+                self.get_mut_by_coords(col, row)?.set_neighbors(neighbors);
             }
         }
         Ok(())
@@ -765,9 +1987,12 @@ impl Grid {
     fn assign_neighbors_upsilon(&mut self) -> Result<(), Error> {
         for y in 0..self.height {
             for x in 0..self.width {
-                let mut cell = self.get_mut(Coordinates { x, y })?.clone();
+                if !self.has_cell(x, y) {
+                    continue;
+                }
+                let is_square = self.get_by_coords(x, y)?.is_square;
                 let mut neighbors = HashMap::new();
-                if cell.is_square {
+                if is_square {
                     if y > 0 { neighbors.insert(Direction::Up, Coordinates { x, y: y - 1 }); }
                     if x < self.width - 1 { neighbors.insert(Direction::Right, Coordinates { x: x + 1, y }); }
                     if y < self.height - 1 { neighbors.insert(Direction::Down, Coordinates { x, y: y + 1 }); }
@@ -782,8 +2007,8 @@ impl Grid {
                     if x > 0 && y < self.height - 1 { neighbors.insert(Direction::LowerLeft, Coordinates { x: x - 1, y: y + 1 }); }
                     if x > 0 && y > 0 { neighbors.insert(Direction::UpperLeft, Coordinates { x: x - 1, y: y - 1 }); }
                 }
-                cell.set_neighbors(neighbors);
-                self.set(cell)?;
+                neighbors.retain(|_, coords| self.has_cell(coords.x, coords.y));
+                self.get_mut_by_coords(x, y)?.set_neighbors(neighbors);
             }
         }
         Ok(())
@@ -795,7 +2020,6 @@ impl Grid {
                 if !self.has_cell(x, y) {
                     continue;
                 }
-                let mut cell = self.get_mut_by_coords(x, y)?.clone();
                 let mut neighbors: HashMap<Direction, Coordinates> = HashMap::new();
 
                 // UpperRight neighbor
@@ -814,9 +2038,9 @@ impl Grid {
                 if x > 0 && y > 0 {
                     neighbors.insert(Direction::UpperLeft, Coordinates { x: x - 1, y: y - 1 });
                 }
+                neighbors.retain(|_, coords| self.has_cell(coords.x, coords.y));
 
-                cell.set_neighbors(neighbors);
-                self.set(cell)?;
+                self.get_mut_by_coords(x, y)?.set_neighbors(neighbors);
             }
         }
         Ok(())
@@ -824,13 +2048,17 @@ impl Grid {
 
     /// Link two cells together by their coordinates.
     pub fn link(&mut self, coord1: Coordinates, coord2: Coordinates) -> Result<(), Error> {
+        if let Some(progress) = &self.progress {
+            progress.advance()?;
+        }
         let (row1, col1) = (coord1.y, coord1.x);
         let (row2, col2) = (coord2.y, coord2.x);
 
         // Link cell at coord1 to cell at coord2 and update open_walls.
+        let newly_linked;
         {
             let cell1 = self.get_mut_by_coords(col1, row1)?;
-            cell1.linked.insert(coord2);
+            newly_linked = cell1.linked.insert(coord2);
             cell1.set_open_walls();
         }
         // Link cell at coord2 to cell at coord1 and update open_walls.
@@ -839,18 +2067,34 @@ impl Grid {
             cell2.linked.insert(coord1);
             cell2.set_open_walls();
         }
+        self.invalidate_distances();
+        self.mark_changed(&[coord1, coord2]);
+        if newly_linked {
+            self.log_event(GridEvent::CellLinked { a: coord1, b: coord2 });
+        }
         Ok(())
     }
 
+    /// Fail with `Error::Cancelled` if the generation in progress has been cancelled. Algorithms which carve
+    /// passages infrequently, such as those performing random walks, call this periodically; every other
+    /// algorithm is checked as it links cells.
+    pub fn check_cancelled(&self) -> Result<(), Error> {
+        match &self.progress {
+            Some(progress) => progress.cancellation().check(),
+            None => Ok(()),
+        }
+    }
+
     /// Unlink two cells by their coordinates, removing the connection between them.
     pub fn unlink(&mut self, coord1: Coordinates, coord2: Coordinates) -> Result<(), Error> {
         let (row1, col1) = (coord1.y, coord1.x);
         let (row2, col2) = (coord2.y, coord2.x);
 
         // Unlink cell at coord1 from cell at coord2 and update open_walls.
+        let was_linked;
         {
             let cell1 = self.get_mut_by_coords(col1, row1)?;
-            cell1.linked.remove(&coord2);
+            was_linked = cell1.linked.remove(&coord2);
             cell1.set_open_walls();
         }
         // Unlink cell at coord2 from cell at coord1 and update open_walls.
@@ -859,6 +2103,11 @@ impl Grid {
             cell2.linked.remove(&coord1);
             cell2.set_open_walls();
         }
+        self.invalidate_distances();
+        self.mark_changed(&[coord1, coord2]);
+        if was_linked {
+            self.log_event(GridEvent::CellUnlinked { a: coord1, b: coord2 });
+        }
         Ok(())
     }
 
@@ -900,19 +2149,29 @@ impl Grid {
 
     /// Get a map of distances from the start coordinate to all other connected coordinates.
     pub fn distances(&self, start: Coordinates) -> HashMap<Coordinates, u32> {
-        // Define a closure that returns the linked (neighbor) coordinates for a given coordinate.
-        let neighbor_fn = |coords: Coordinates| -> Vec<Coordinates> {
-            // Retrieve the cell at `coords`
-            if let Ok(cell) = self.get(coords) {
-                // Return its linked neighbors (assuming cell.linked is a HashSet<Coordinates>).
-                cell.linked.iter().copied().collect()
-            } else {
-                Vec::new()
-            }
-        };
+        self.distance_field(start).as_ref().clone()
+    }
+
+    /// Distances from the origin to every cell connected to it, as `distances` returns them, but shared with
+    /// the grid's cache: with `std`, the distances from the last few origins searched from are kept until a
+    /// passage is linked or unlinked, so the pathfinding, hints and heatmaps asked for after every move don't
+    /// search the whole maze again.
+    pub fn distance_field(&self, origin: Coordinates) -> Arc<HashMap<Coordinates, u32>> {
+        self.distance_cache.get_or_insert_with(origin, || {
+            let neighbor_fn = |coords: Coordinates| -> Vec<Coordinates> {
+                self.get(coords)
+                    .map(|cell| cell.linked.iter().collect())
+                    .unwrap_or_else(|_| Vec::new())
+            };
+            graph::bfs_distances(origin, neighbor_fn)
+        })
+    }
 
-        graph::bfs_distances(start, neighbor_fn)
-    }    
+    // Forget the cached distances, which changing the passages makes stale. Cells whose `linked` coordinates
+    // are changed other than by `link` and `unlink` must call this too.
+    pub(crate) fn invalidate_distances(&mut self) {
+        self.distance_cache.clear();
+    }
 
     /// Compute a path from the given start coordinates to the goal coordinates within the maze grid.
     /// 
@@ -930,14 +2189,14 @@ impl Grid {
         let start = Coordinates { x: start_x, y: start_y };
         let goal = Coordinates { x: goal_x, y: goal_y };
 
-        // Compute distances from start using your existing method.
-        let distances = self.distances(start);
+        // Distances from the start, reused from the cache when the start was searched from before.
+        let distances = self.distance_field(start);
 
         // Define the neighbor function inline.
         // Given a coordinate, return its linked neighbors (or an empty vec on error).
         let neighbor_fn = |coords: Coordinates| -> Vec<Coordinates> {
             self.get(coords)
-                .map(|cell| cell.linked.iter().copied().collect())
+                .map(|cell| cell.linked.iter().collect())
                 .unwrap_or_else(|_| Vec::new())
         };
 
@@ -956,28 +2215,666 @@ impl Grid {
         }
     }
 
+    /// The cells of the shortest path from the start to the goal, in order from start to goal inclusive, or an
+    /// empty list when the goal is unreachable. Useful for animating the solution.
+    pub fn solution_path_order(&self) -> Vec<Coordinates> {
+        let mut path: Vec<(Coordinates, u32)> = self
+            .get_path_to(self.start_coords.x, self.start_coords.y, self.goal_coords.x, self.goal_coords.y)
+            .map(|path| path.into_iter().collect())
+            .unwrap_or_default();
+        path.sort_by_key(|&(_, distance)| distance);
+        path.into_iter().map(|(coords, _)| coords).collect()
+    }
+
+    /// Set the cost of entering a cell in weighted pathfinding, such as a higher cost for mud or water, or
+    /// with `None` return the cell to the default cost of 1. Weights must be at least 1.
+    pub fn set_weight(&mut self, coords: Coordinates, weight: Option<u32>) -> Result<(), Error> {
+        if weight == Some(0) {
+            return Err(Error::InvalidWeight { reason: format!("the weight of {} must be at least 1", coords) });
+        }
+        self.get_mut(coords)?.weight = weight;
+        Ok(())
+    }
+
+    /// Give every cell a random weight from 1 up to `max_weight` inclusive, drawn with the grid's random
+    /// numbers, as noise for terrain of varying cost.
+    pub fn assign_random_weights(&mut self, max_weight: u32) -> Result<(), Error> {
+        if max_weight == 0 {
+            return Err(Error::InvalidWeight { reason: "the maximum weight must be at least 1".to_string() });
+        }
+        let mut rng = self.forked_rng();
+        for cell in self.cells.iter_mut().flatten() {
+            cell.weight = Some(rng.bounded_usize(max_weight as usize) as u32 + 1);
+        }
+        Ok(())
+    }
+
+    /// The lowest total cost of reaching every cell connected to the origin, where entering a cell costs its
+    /// weight, found by Dijkstra's search. Without weights these are the same as `distances`.
+    pub fn weighted_distances(&self, origin: Coordinates) -> HashMap<Coordinates, u32> {
+        graph::dijkstra_distances(origin, |coords| self.weighted_neighbors(coords))
+    }
+
+    // The cells linked to a cell, each with the cost of entering it
+    fn weighted_neighbors(&self, coords: Coordinates) -> Vec<(Coordinates, u32)> {
+        self.get(coords)
+            .map(|cell| {
+                cell.linked
+                    .iter()
+                    .map(|linked| (linked, self.get(linked).ok().and_then(|cell| cell.weight).unwrap_or(1)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Compute the lowest cost path from the given start coordinates to the goal coordinates, where entering
+    /// a cell costs its weight, rather than the path of fewest moves that `get_path_to` finds. It returns a
+    /// `HashMap` mapping each coordinate along the found path to its total cost from the start. If no path
+    /// exists, an empty map is returned.
+    pub fn get_path_to_weighted(
+        &self,
+        start_x: usize,
+        start_y: usize,
+        goal_x: usize,
+        goal_y: usize,
+    ) -> Result<HashMap<Coordinates, u32>, Error> {
+        let start = Coordinates { x: start_x, y: start_y };
+        let goal = Coordinates { x: goal_x, y: goal_y };
+        self.get(start)?;
+        self.get(goal)?;
+
+        let distances = self.weighted_distances(start);
+        let path = graph::get_weighted_path(start, goal, &distances, |coords| self.weighted_neighbors(coords)).unwrap_or_default();
+        Ok(path
+            .into_iter()
+            .filter_map(|coords| distances.get(&coords).map(|&distance| (coords, distance)))
+            .collect())
+    }
+
+    /// The longest path between any two cells of the maze (its diameter), found by double breadth-first
+    /// search: the cell farthest from an arbitrary cell is one end of a longest path, and the cell farthest
+    /// from that end is the other. Returns both ends and the cells of the path between them, inclusive.
+    pub fn longest_path(&self) -> (Coordinates, Coordinates, Vec<Coordinates>) {
+        let neighbor_fn = |coords: Coordinates| -> Vec<Coordinates> {
+            self.get(coords)
+                .map(|cell| cell.linked.iter().collect())
+                .unwrap_or_else(|_| Vec::new())
+        };
+        // break distance ties by coordinates so the result is deterministic
+        let farthest = |from: Coordinates| -> (Coordinates, HashMap<Coordinates, u32>) {
+            let distances = graph::bfs_distances(from, neighbor_fn);
+            let end = distances
+                .iter()
+                .max_by_key(|&(coords, distance)| (*distance, *coords))
+                .map(|(coords, _)| *coords)
+                .unwrap_or(from);
+            (end, distances)
+        };
+        let origin = self.iter_cells().next().map(|cell| cell.coords).unwrap_or(self.start_coords);
+        let (first, _) = farthest(origin);
+        let (second, distances) = farthest(first);
+        let path = graph::get_path(first, second, &distances, neighbor_fn).unwrap_or_else(|| vec![first]);
+        (first, second, path)
+    }
+
+    /// Knock out the outer wall of the start and goal cells, as entrance and exit openings, where they lie on
+    /// the maze's border. Openings stay with the start and goal when they move, and an opening already on the
+    /// start or goal, such as one placed with `open_border`, is kept.
+    pub fn open_exits(&mut self) {
+        let (start, goal) = (self.start_coords, self.goal_coords);
+        let mut changed = Vec::new();
+        for cell in self.cells.iter_mut().flatten() {
+            let opening = if cell.coords == start || cell.coords == goal {
+                cell.border_opening.or_else(|| cell.border_sides().first().copied())
+            } else {
+                None
+            };
+            if cell.border_opening != opening {
+                cell.border_opening = opening;
+                changed.push(cell.coords);
+            }
+        }
+        self.mark_changed(&changed);
+    }
+
+    /// The cell at a place on the maze's border, the outermost cell in that row or column with a side facing
+    /// out of the maze that way, and the side facing out. A side directly facing out is preferred to a slanted
+    /// one, such as the upper left side of a hexagon along the left of the maze.
+    pub fn border_cell(&self, edge: BorderEdge) -> Result<(Coordinates, Direction), Error> {
+        use Direction::*;
+        let faces = |direction: Direction| match edge.side {
+            Up => matches!(direction, Up | UpperLeft | UpperRight),
+            Down => matches!(direction, Down | LowerLeft | LowerRight),
+            Left => matches!(direction, Left | UpperLeft | LowerLeft),
+            Right => matches!(direction, Right | UpperRight | LowerRight),
+            _ => false,
+        };
+        let line: Vec<Coordinates> = match edge.side {
+            Up => (0..self.height).map(|y| Coordinates { x: edge.offset, y }).collect(),
+            Down => (0..self.height).rev().map(|y| Coordinates { x: edge.offset, y }).collect(),
+            Left => (0..self.width).map(|x| Coordinates { x, y: edge.offset }).collect(),
+            Right => (0..self.width).rev().map(|x| Coordinates { x, y: edge.offset }).collect(),
+            side => return Err(Error::InvalidBorderEdge { reason: format!("{} is not a side of the maze", side) }),
+        };
+        line.into_iter()
+            .filter_map(|coords| self.get(coords).ok())
+            .find_map(|cell| {
+                let sides = cell.border_sides();
+                let opening = sides.iter().find(|&&side| side == edge.side).or_else(|| sides.iter().find(|&&side| faces(side)));
+                opening.map(|&opening| (cell.coords, opening))
+            })
+            .ok_or_else(|| Error::InvalidBorderEdge { reason: format!("no cell at offset {} faces out of the {} side", edge.offset, edge.side) })
+    }
+
+    /// Knock out the outer wall at a place on the maze's border (see `border_cell`), returning the cell opened.
+    /// Openings elsewhere are left as they are.
+    pub fn open_border(&mut self, edge: BorderEdge) -> Result<Coordinates, Error> {
+        let (coords, opening) = self.border_cell(edge)?;
+        self.get_mut(coords)?.border_opening = Some(opening);
+        self.mark_changed(&[coords]);
+        Ok(coords)
+    }
+
+    /// Move the maze's start and goal to new cells, resetting the user's progress and recomputing distances
+    /// and the solution path for the new endpoints.
+    pub fn set_endpoints(&mut self, start: Coordinates, goal: Coordinates) -> Result<(), Error> {
+        if !self.has_cell(start.x, start.y) {
+            return Err(Error::InvalidStartCoordinates { coordinates: start });
+        }
+        if !self.has_cell(goal.x, goal.y) {
+            return Err(Error::InvalidGoalCoordinates { coordinates: goal });
+        }
+        let exits_open = self.iter_cells().any(|cell| cell.border_opening.is_some());
+        self.start_coords = start;
+        self.goal_coords = goal;
+        if exits_open {
+            self.open_exits();
+        }
+        let distances = self.distance_field(start);
+        let solution = self.get_path_to(start.x, start.y, goal.x, goal.y)?;
+        let mut events = Vec::new();
+        for cell in self.cells.iter_mut().flatten() {
+            let is_start = cell.coords == start;
+            if cell.is_active && !is_start {
+                events.push(GridEvent::ActiveMoved { from: cell.coords, to: start });
+            }
+            if cell.is_visited != is_start {
+                events.push(GridEvent::VisitedChanged { coords: cell.coords, is_visited: is_start });
+            }
+            cell.is_start = is_start;
+            cell.is_goal = cell.coords == goal;
+            cell.is_active = is_start;
+            cell.is_visited = is_start;
+            cell.has_been_visited = is_start;
+            cell.on_solution_path = solution.contains_key(&cell.coords);
+            cell.distance = distances.get(&cell.coords).map(|&d| d as i32).unwrap_or(cell.distance);
+        }
+        self.mark_all_changed();
+        for event in events {
+            self.log_event(event);
+        }
+        self.move_history.clear();
+        self.undone_moves.clear();
+        self.update_completion();
+        Ok(())
+    }
+
+    /// Open the wall on the specified side of a cell if it is closed, or close it if it is open, returning
+    /// whether the wall is now open. Use `edit` to batch edits, roll them back, or keep the maze connected.
+    pub fn toggle_wall(&mut self, coords: Coordinates, direction: Direction) -> Result<bool, Error> {
+        let mut transaction = self.edit(false);
+        let opened = transaction.toggle_wall(coords, direction)?;
+        transaction.commit()?;
+        Ok(opened)
+    }
+
+    /// Begin a batch of wall edits, which take effect when the transaction is committed and are rolled back
+    /// otherwise. When `keep_connected` is true, committing fails if any cell would become unreachable from
+    /// the start.
+    pub fn edit(&mut self, keep_connected: bool) -> EditTransaction<'_> {
+        EditTransaction::new(self, keep_connected)
+    }
+
+    /// Single passage changes transforming this maze into another of the same type and dimensions, such that
+    /// no cell becomes unreachable partway through. Useful for shifting mazes and animated transitions.
+    pub fn morph_to(&self, other: &Grid) -> Result<Vec<MorphStep>, Error> {
+        morph::morph(self, other)
+    }
+
+    /// The passage and cell state changes turning this maze into another of the same type and dimensions (see
+    /// `diff::diff`), compact enough to send to multiplayer clients every tick in place of the whole maze.
+    pub fn diff(&self, other: &Grid) -> Result<GridDelta, Error> {
+        diff::diff(self, other)
+    }
+
+    /// Apply a delta produced by `diff`, opening and closing its passages and overwriting the state of its
+    /// cells. The delta is checked against the maze before any of it is applied. The changed cells are
+    /// reported by `changed_cells_since`, and the active cell's moves and trail changes are logged for
+    /// `drain_events`; the move history is left as it was.
+    pub fn apply(&mut self, delta: &GridDelta) -> Result<(), Error> {
+        delta.validate(self)?;
+        for &(a, b) in &delta.unlinked {
+            self.unlink(a, b)?;
+        }
+        for &(a, b) in &delta.linked {
+            self.link(a, b)?;
+        }
+        let previously_active = self.active_coords().ok();
+        let mut events = Vec::new();
+        let mut changed = Vec::new();
+        for state in &delta.cells {
+            let cell = self.get_mut(state.coords)?;
+            if cell.is_visited != state.is_visited {
+                events.push(GridEvent::VisitedChanged { coords: state.coords, is_visited: state.is_visited });
+            }
+            state.write_to(cell);
+            changed.push(state.coords);
+            if state.is_start {
+                self.start_coords = state.coords;
+            }
+            if state.is_goal {
+                self.goal_coords = state.coords;
+            }
+        }
+        if let (Some(from), Ok(to)) = (previously_active, self.active_coords()) {
+            if from != to {
+                events.insert(0, GridEvent::ActiveMoved { from, to });
+            }
+        }
+        self.mark_changed(&changed);
+        for event in events {
+            self.log_event(event);
+        }
+        self.update_completion();
+        Ok(())
+    }
+
+    /// Shift the maze's walls mid-game by opening `count` randomly chosen walls, closing for each another
+    /// passage from the loop it forms. Every cell stays reachable, so the goal remains reachable from wherever
+    /// the user stands; passages with doors are never closed. The same seed always shifts the same walls.
+    /// Returns the passage changes made, whose cells are reported by `changed_cells_since`.
+    pub fn shift_walls(&mut self, count: usize, seed: u64) -> Result<Vec<MorphStep>, Error> {
+        let steps = morph::shift_walls(self, count, seed)?;
+        self.refresh_solution();
+        Ok(steps)
+    }
+
+    // Recompute distances and the solution path after the maze's passages change, keeping the user's progress
+    pub(crate) fn refresh_solution(&mut self) {
+        let distances = self.distance_field(self.start_coords);
+        let solution: HashSet<Coordinates> = self.solution_path_order().into_iter().collect();
+        let mut changed = Vec::new();
+        for cell in self.cells.iter_mut().flatten() {
+            let on_solution_path = solution.contains(&cell.coords);
+            let distance = distances.get(&cell.coords).map(|&d| d as i32).unwrap_or(cell.distance);
+            if (cell.on_solution_path, cell.distance) != (on_solution_path, distance) {
+                cell.on_solution_path = on_solution_path;
+                cell.distance = distance;
+                changed.push(cell.coords);
+            }
+        }
+        self.mark_changed(&changed);
+    }
+
+    /// Label the maze's loops, chambers and articulation points (see `regions::regions`), e.g. to place the
+    /// boss of a braided or dungeon-style maze in its largest chamber.
+    pub fn regions(&self) -> Vec<Region> {
+        regions::regions(self)
+    }
+
+    /// Merge rectangular blocks of cells into open chambers, each joined to the rest of the maze as though it
+    /// were a single cell, so a perfect maze stays perfect with its rooms taken as cells (see `rooms::merge`).
+    /// Only single layer Orthogonal mazes can have rooms, which must not overlap each other or earlier rooms.
+    pub fn merge_rooms(&mut self, rooms: Vec<Rect>) -> Result<(), Error> {
+        let mut all = self.rooms.clone();
+        all.extend(rooms.iter().copied());
+        rooms::validate(&all, self.maze_type, self.width, self.height, self.layers)?;
+        rooms::merge(self, &rooms)?;
+        self.rooms = all;
+        self.refresh_solution();
+        Ok(())
+    }
+
+    /// The room containing the cell, if it has been merged into one.
+    pub fn room_at(&self, coords: Coordinates) -> Option<Rect> {
+        self.rooms.iter().copied().find(|room| room.contains(coords))
+    }
+
+    /// Extract a region of the maze as a maze of its own, keeping the passages between cells inside the region.
+    /// The start and goal carry over when they lie inside the region and otherwise fall back to defaults.
+    /// Because passages leaving the region are dropped, the cropped maze may contain unreachable cells.
+    ///
+    /// Cell shapes alternate across Delta, Rhombic and Upsilon grids and Sigma columns, so the region must start
+    /// on a cell of the same shape as the grid's top-left cell.
+    pub fn crop(&self, rect: Rect) -> Result<Grid, Error> {
+        if self.layers > 1 {
+            return Err(Error::InvalidLayers { reason: "multi-layer mazes cannot be cropped".to_string() });
+        }
+        if self.delta_shape != DeltaShape::default() {
+            return Err(Error::InvalidCropRegion { reason: "triangle-shaped Delta mazes cannot be cropped".to_string() });
+        }
+        if self.mask.is_some() {
+            return Err(Error::InvalidCropRegion { reason: "masked mazes cannot be cropped".to_string() });
+        }
+        if rect.width == 0 || rect.height == 0 || rect.x + rect.width > self.width || rect.y + rect.height > self.height {
+            return Err(Error::InvalidCropRegion {
+                reason: format!("{:?} does not fit within the {}x{} grid", rect, self.width, self.height),
+            });
+        }
+        let origin = Coordinates { x: rect.x, y: rect.y };
+        if !Self::preserves_geometry(self.maze_type, origin) {
+            return Err(Error::InvalidCropRegion {
+                reason: format!("{:?} cells at {} differ in shape from the grid's first cell", self.maze_type, origin),
+            });
+        }
+        let translate = |coords: Coordinates| Coordinates { x: coords.x - rect.x, y: coords.y - rect.y };
+        let (default_start, default_goal) = Grid::default_endpoints(rect.width, rect.height, self.maze_type);
+        let start = if rect.contains(self.start_coords) { translate(self.start_coords) } else { default_start };
+        let goal = if rect.contains(self.goal_coords) { translate(self.goal_coords) } else { default_goal };
+
+        let mut cropped = Grid::new(self.maze_type, rect.width, rect.height, start, goal, false)?;
+        if self.delta_orientation != CellOrientation::default() {
+            cropped.set_delta_orientation(self.delta_orientation)?;
+        }
+        for cell in self.iter_cells().filter(|cell| rect.contains(cell.coords)) {
+            for neighbor in cell.linked.iter().filter(|&neighbor| rect.contains(neighbor)) {
+                cropped.link(translate(cell.coords), translate(neighbor))?;
+            }
+        }
+        cropped.rooms = self.rooms
+            .iter()
+            .filter(|room| {
+                rect.contains(Coordinates { x: room.x, y: room.y })
+                    && rect.contains(Coordinates { x: room.x + room.width - 1, y: room.y + room.height - 1 })
+            })
+            .map(|room| Rect { x: room.x - rect.x, y: room.y - rect.y, ..*room })
+            .collect();
+        cropped.set_endpoints(start, goal)?;
+        Ok(cropped)
+    }
+
+    /// Join another maze of the same type onto this one, producing a larger maze. The other maze is placed at
+    /// `offset`, which must sit directly right of this maze (at this maze's width, with the same height) or
+    /// directly below it (at this maze's height, with the same width). `connector_count` passages are then
+    /// opened at random along the seam; a single connector keeps two perfect mazes perfect, while more
+    /// introduce loops. The joined maze starts at this maze's start and ends at the other maze's goal.
+    pub fn stitch(&self, other: &Grid, offset: Coordinates, connector_count: usize) -> Result<Grid, Error> {
+        if self.layers > 1 || other.layers > 1 {
+            return Err(Error::InvalidLayers { reason: "multi-layer mazes cannot be stitched".to_string() });
+        }
+        if self.delta_shape != DeltaShape::default() || other.delta_shape != DeltaShape::default() {
+            return Err(Error::InvalidStitch { reason: "triangle-shaped Delta mazes cannot be stitched".to_string() });
+        }
+        if self.mask.is_some() || other.mask.is_some() {
+            return Err(Error::InvalidStitch { reason: "masked mazes cannot be stitched".to_string() });
+        }
+        if other.maze_type != self.maze_type {
+            return Err(Error::InvalidStitch {
+                reason: format!("cannot join a {:?} maze onto a {:?} maze", other.maze_type, self.maze_type),
+            });
+        }
+        let (width, height) = if offset == (Coordinates { x: self.width, y: 0 }) && other.height == self.height {
+            (self.width + other.width, self.height)
+        } else if offset == (Coordinates { x: 0, y: self.height }) && other.width == self.width {
+            (self.width, self.height + other.height)
+        } else {
+            return Err(Error::InvalidStitch {
+                reason: format!("a {}x{} maze at {} does not share a full edge with the {}x{} maze", other.width, other.height, offset, self.width, self.height),
+            });
+        };
+        if !Self::preserves_geometry(self.maze_type, offset) {
+            return Err(Error::InvalidStitch {
+                reason: format!("{:?} cells at {} differ in shape from the maze's first cell", self.maze_type, offset),
+            });
+        }
+        if connector_count == 0 {
+            return Err(Error::InvalidStitch { reason: "at least one connecting passage is required".to_string() });
+        }
+
+        let translate = |coords: Coordinates| Coordinates { x: coords.x + offset.x, y: coords.y + offset.y };
+        let start = self.start_coords;
+        let goal = translate(other.goal_coords);
+        let mut stitched = Grid::new(self.maze_type, width, height, start, goal, false)?;
+        if self.delta_orientation != CellOrientation::default() {
+            stitched.set_delta_orientation(self.delta_orientation)?;
+        }
+        for cell in self.iter_cells() {
+            for neighbor in &cell.linked {
+                stitched.link(cell.coords, neighbor)?;
+            }
+        }
+        for cell in other.iter_cells() {
+            for neighbor in &cell.linked {
+                stitched.link(translate(cell.coords), translate(neighbor))?;
+            }
+        }
+
+        // candidate connectors are pairs of neighboring cells on either side of the seam
+        let own = Rect { x: 0, y: 0, width: self.width, height: self.height };
+        let mut seam: Vec<(Coordinates, Coordinates)> = stitched.iter_cells()
+            .filter(|cell| own.contains(cell.coords))
+            .flat_map(|cell| cell.neighbors().into_iter().filter(|n| !own.contains(*n)).map(move |n| (cell.coords, n)))
+            .collect();
+        seam.sort();
+        seam.dedup();
+        if seam.len() < connector_count {
+            return Err(Error::InvalidStitch {
+                reason: format!("the seam has room for only {} connecting passages, {} requested", seam.len(), connector_count),
+            });
+        }
+        for _ in 0..connector_count {
+            let index = stitched.bounded_random_usize(seam.len());
+            let (from, to) = seam.swap_remove(index);
+            stitched.link(from, to)?;
+        }
+        stitched.rooms = self.rooms.clone();
+        stitched.rooms.extend(other.rooms.iter().map(|room| Rect { x: room.x + offset.x, y: room.y + offset.y, ..*room }));
+        stitched.set_endpoints(start, goal)?;
+        Ok(stitched)
+    }
+
+    // Whether cells offset by the specified amount have the same shape and neighbors as unshifted cells
+    fn preserves_geometry(maze_type: MazeType, offset: Coordinates) -> bool {
+        match maze_type {
+            MazeType::Orthogonal => true,
+            MazeType::Sigma => offset.x.is_multiple_of(2),
+            MazeType::Delta | MazeType::Rhombic | MazeType::Upsilon => (offset.x + offset.y).is_multiple_of(2),
+        }
+    }
+
+    /// Group cells into `bands` bands by their distance from the start cell, normalized against the
+    /// farthest reachable cell. Band 0 holds the cells nearest the start and band `bands - 1` the farthest,
+    /// which is useful for rainbow-style renderings or visualizing how deep into the maze a region lies.
+    /// Cells unreachable from the start are omitted from the returned map.
+    pub fn distance_bands(&self, bands: usize) -> Result<HashMap<Coordinates, usize>, Error> {
+        if bands == 0 {
+            return Err(Error::InvalidDistanceBandCount { bands });
+        }
+        let distances = self.distance_field(self.start_coords);
+        let max_distance = distances.values().copied().max().unwrap_or(0);
+        let band_ids = distances
+            .iter()
+            .map(|(&coords, &distance)| {
+                let band = if max_distance == 0 {
+                    0
+                } else {
+                    // scale distance into 0..bands, keeping the farthest cell(s) in the last band
+                    ((distance as usize * bands) / (max_distance as usize + 1)).min(bands - 1)
+                };
+                (coords, band)
+            })
+            .collect();
+        Ok(band_ids)
+    }
+
+    /// Compute distance bands (see `distance_bands`) and store each cell's band id on the cell itself,
+    /// so that it is included when the grid is serialized or handed across the FFI layer.
+    pub fn assign_distance_bands(&mut self, bands: usize) -> Result<(), Error> {
+        let band_ids = self.distance_bands(bands)?;
+        for cell in self.cells.iter_mut().flatten() {
+            cell.distance_band = band_ids.get(&cell.coords).copied();
+        }
+        self.mark_all_changed();
+        Ok(())
+    }
+
+    /// Normalized 0.0–1.0 distance of every cell reachable from the start cell, sorted by coordinates,
+    /// so UIs can color cells by how far they lie from the start.
+    pub fn distance_heatmap(&self) -> Vec<(Coordinates, f32)> {
+        self.distance_heatmap_from(self.start_coords)
+    }
+
+    /// Normalized 0.0–1.0 distance of every cell reachable from the specified origin, sorted by coordinates.
+    pub fn distance_heatmap_from(&self, origin: Coordinates) -> Vec<(Coordinates, f32)> {
+        heatmap::normalize(&self.distance_field(origin))
+    }
+
+    /// Compute a heatmap from the specified origin (see `distance_heatmap_from`) and store each cell's
+    /// intensity on the cell itself, so that it is included when serialized or handed across the FFI layer.
+    pub fn assign_heatmap(&mut self, origin: Coordinates) -> Result<(), Error> {
+        self.get(origin)?;
+        let heat: HashMap<Coordinates, f32> = self.distance_heatmap_from(origin).into_iter().collect();
+        for cell in self.cells.iter_mut().flatten() {
+            cell.heat = heat.get(&cell.coords).copied();
+        }
+        self.mark_all_changed();
+        Ok(())
+    }
+
     /// Return all cells reachable from the given start coordinates
     /// Get all connected cells from a starting coordinate.
     pub fn all_connected_cells(&self, start: Coordinates) -> HashSet<Coordinates> {
         let neighbor_fn = |coords: Coordinates| -> Vec<Coordinates> {
             if let Ok(cell) = self.get(coords) {
-                cell.linked.iter().copied().collect()
+                cell.linked.iter().collect()
             } else {
                 Vec::new()
             }
-        };
+        };
+
+        graph::all_connected(start, neighbor_fn)
+    }
+    
+    /// Count the number of edges in the maze
+    pub fn count_edges(&self) -> usize {
+        self.iter_cells()                   // Yields &Cell, skipping positions without a cell
+            .map(|cell| cell.linked.len())  // Access linked field on &Cell and get its length
+            .sum::<usize>()                 // Sum the total number of linked connections
+            / 2                             // Divide by 2 since each edge is counted twice
+    }
+
+    /// Approximate number of bytes used by this grid, including the neighbors and links each cell stores beyond
+    /// the eight positions around it (see `Links`) and any captured generation and solving steps. Compare against `CompactGrid::memory_footprint` for the packed representation.
+    pub fn memory_footprint(&self) -> usize {
+        use core::mem::size_of;
+        let cells: usize = self.iter_cells().map(Cell::heap_footprint).sum();
+        let steps: usize = self.generation_steps.as_ref().map_or(0, GenerationSteps::memory_footprint)
+            + self.solving_steps.iter().flatten().map(Grid::memory_footprint).sum::<usize>();
+        size_of::<Grid>() + self.cells.capacity() * size_of::<Option<Cell>>() + cells + steps
+    }
+
+    /// A hash of the maze's shape and passages, ignoring its start, goal and the user's progress, so that
+    /// generated mazes can be deduplicated and referred to by a stable id. The hash is computed with FNV-1a
+    /// over the maze type, dimensions and each cell's passages in a fixed order, so it is the same across
+    /// runs, platforms and releases.
+    pub fn canonical_hash(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+        let mut hash = FNV_OFFSET_BASIS;
+        let mut write = |value: u64| {
+            for byte in value.to_le_bytes() {
+                hash = (hash ^ byte as u64).wrapping_mul(FNV_PRIME);
+            }
+        };
+        write(self.maze_type as u64);
+        write(self.width as u64);
+        write(self.height as u64);
+        write(self.layers as u64);
+        for (index, cell) in self.cells.iter().enumerate() {
+            let Some(cell) = cell else {
+                // masked out positions are marked, so that masking a cell changes the hash
+                write(u64::MAX);
+                continue;
+            };
+            // each passage is written once, from its lower indexed cell
+            let mut passages: Vec<usize> = cell.linked
+                .iter()
+                .map(|linked| self.get_flattened_index(linked.x, linked.y))
+                .filter(|&linked| linked > index)
+                .collect();
+            passages.sort_unstable();
+            write(passages.len() as u64);
+            for linked in passages {
+                write(linked as u64);
+            }
+        }
+        hash
+    }
 
-        graph::all_connected(start, neighbor_fn)
+    /// How alike two mazes' passages are, from 0.0 (no passage in common) through 1.0 (identical passages):
+    /// the number of passages the mazes share over the number found in either. Mazes of different types or
+    /// dimensions share no passages. See `similarity_with_symmetry` to also catch a rotated or mirrored copy.
+    pub fn similarity(&self, other: &Grid) -> f32 {
+        if self.maze_type != other.maze_type || (self.width, self.height, self.layers) != (other.width, other.height, other.layers) {
+            return 0.0;
+        }
+        Self::passage_overlap(&self.passages(), &other.passages())
     }
-    
-    /// Count the number of edges in the maze
-    pub fn count_edges(&self) -> usize {
-        self.cells
-            .iter()                         // Yields &Option<Cell>
-            .filter_map(|opt| opt.as_ref()) // Converts to Option<&Cell>, filters out None, yields &Cell
-            .map(|cell| cell.linked.len())  // Access linked field on &Cell and get its length
-            .sum::<usize>()                 // Sum the total number of linked connections
-            / 2                             // Divide by 2 since each edge is counted twice
+
+    /// The greatest `similarity` between this maze and the other maze rotated or mirrored, so that a maze
+    /// turned on its side still counts as a near copy. Only single layer Orthogonal mazes are rotated and
+    /// mirrored; for others this is the same as `similarity`.
+    pub fn similarity_with_symmetry(&self, other: &Grid) -> f32 {
+        if self.maze_type != MazeType::Orthogonal || other.maze_type != MazeType::Orthogonal || self.layers > 1 || other.layers > 1 {
+            return self.similarity(other);
+        }
+        let (w, h) = (other.width, other.height);
+        // each symmetry of a rectangle, mapping the other maze's coordinates onto a grid of the given size
+        type Symmetry = fn(Coordinates, usize, usize) -> Coordinates;
+        let symmetries: [(usize, usize, Symmetry); 8] = [
+            (w, h, |c, _, _| c),
+            (w, h, |c, w, _| Coordinates { x: w - 1 - c.x, y: c.y }),
+            (w, h, |c, _, h| Coordinates { x: c.x, y: h - 1 - c.y }),
+            (w, h, |c, w, h| Coordinates { x: w - 1 - c.x, y: h - 1 - c.y }),
+            (h, w, |c, _, _| Coordinates { x: c.y, y: c.x }),
+            (h, w, |c, _, h| Coordinates { x: h - 1 - c.y, y: c.x }),
+            (h, w, |c, w, _| Coordinates { x: c.y, y: w - 1 - c.x }),
+            (h, w, |c, w, h| Coordinates { x: h - 1 - c.y, y: w - 1 - c.x }),
+        ];
+        let passages = self.passages();
+        let other_passages = other.passages();
+        symmetries
+            .iter()
+            .filter(|(width, height, _)| (*width, *height) == (self.width, self.height))
+            .map(|(_, _, map)| {
+                let mapped: BTreeSet<(Coordinates, Coordinates)> = other_passages
+                    .iter()
+                    .map(|&(a, b)| {
+                        let (a, b) = (map(a, w, h), map(b, w, h));
+                        (a.min(b), a.max(b))
+                    })
+                    .collect();
+                Self::passage_overlap(&passages, &mapped)
+            })
+            .fold(0.0, f32::max)
+    }
+
+    // Every passage of the maze, each as its pair of cells in order
+    fn passages(&self) -> BTreeSet<(Coordinates, Coordinates)> {
+        self.iter_cells()
+            .flat_map(|cell| cell.linked.iter().map(move |linked| (cell.coords.min(linked), cell.coords.max(linked))))
+            .collect()
+    }
+
+    // Passages in both sets over passages in either, counting two mazes without passages as identical
+    fn passage_overlap(a: &BTreeSet<(Coordinates, Coordinates)>, b: &BTreeSet<(Coordinates, Coordinates)>) -> f32 {
+        let shared = a.intersection(b).count();
+        let either = a.len() + b.len() - shared;
+        if either == 0 {
+            1.0
+        } else {
+            shared as f32 / either as f32
+        }
     }
 
     /// Whether the maze is perfect
@@ -997,28 +2894,109 @@ impl Grid {
         Ok(total_edges == total_cells - 1)
     }
 
-    /// ASCI display, only applicable to Orthogonal (square cell) mazes
+    /// Play out a classic solving strategy, such as following the wall with one hand, from the start until it
+    /// reaches the goal, for animating how each strategy fares against different mazes.
+    pub fn simulate_solver(&self, strategy: SolverStrategy) -> Result<SolverRun, Error> {
+        solver::simulate(self, strategy)
+    }
+
+    /// Play out a solving strategy as `simulate_solver` does, recording the maze after every step in
+    /// `solving_steps`, so that solving techniques can be animated the same way as generation. As with
+    /// `capture_steps`, neither width nor height can exceed 100.
+    pub fn capture_solver(&mut self, strategy: SolverStrategy) -> Result<SolverRun, Error> {
+        if self.width > 100 || self.height > 100 {
+            return Err(Error::GridDimensionsExceedLimitForCaptureSteps { width: self.width, height: self.height });
+        }
+        let (run, steps) = solver::capture(self, strategy)?;
+        self.solving_steps = Some(steps);
+        Ok(run)
+    }
+
+    /// Structural difficulty metrics of the maze (solution length, dead ends, decision points, etc.) with
+    /// a normalized 0–100 score and an Easy/Medium/Hard label. The report is kept until the passages or
+    /// endpoints change, so serializing the grid after each move does not measure the maze again.
+    pub fn difficulty(&self) -> DifficultyReport {
+        self.distance_cache.difficulty_or_insert_with(self.start_coords, self.goal_coords, || DifficultyReport::from_grid(self))
+    }
+
+    /// Randomly choose distinct cells for features such as keys, enemies or treasure, honoring the spec's
+    /// constraints (distance from start, dead ends only, off the solution path). Placement is seeded, by
+    /// default from the grid's seed, so the same maze and spec always yield the same cells.
+    pub fn place_features(&self, spec: &FeatureSpec) -> Result<Vec<Coordinates>, Error> {
+        features::place(self, spec)
+    }
+
+    /// Turn the maze into a key-and-door puzzle by locking `count` doors along the solution path, each opened
+    /// by visiting a key cell reachable before it (see `puzzle::add_doors`). Replaces any existing doors.
+    pub fn add_doors(&mut self, count: usize) -> Result<Vec<Door>, Error> {
+        puzzle::add_doors(self, count)
+    }
+
+    /// Graphviz DOT export of the maze's passage graph, annotated with start, goal and solution path
+    pub fn to_dot(&self) -> String {
+        export::graph::dot(self)
+    }
+
+    /// Adjacency list of the maze's passage graph, annotated with start, goal and solution path
+    pub fn to_adjacency_list(&self) -> Vec<GraphNode> {
+        export::graph::adjacency_list(self)
+    }
+
+    /// GeoJSON export of the maze, with each cell as a Polygon and each wall as a LineString (see
+    /// `export::geojson::geojson`), for mapping and visualization tools and game map editors.
+    pub fn to_geojson(&self, cell_size: f64) -> Result<String, Error> {
+        export::geojson::geojson(self, cell_size)
+    }
+
+    /// 3D mesh of the maze's walls, extruded to the specified height and thickness (see
+    /// `export::mesh::extrude_walls`), to write as OBJ or glTF for game engines and modelling tools.
+    pub fn to_mesh(&self, options: &MeshOptions) -> Result<Mesh, Error> {
+        export::mesh::extrude_walls(self, options)
+    }
+
+    /// Tilemap of an Orthogonal maze, each cell drawn as a block of wall and floor tiles (see
+    /// `export::tilemap::tilemap`), to write as CSV or a Tiled map for game engines.
+    pub fn to_tilemap(&self, mapping: &TilesetMapping) -> Result<Tilemap, Error> {
+        export::tilemap::tilemap(self, mapping)
+    }
+
+    /// ASCI display, only applicable to Orthogonal (square cell) mazes. Layers are drawn one below the other,
+    /// with stairs marked `^` (Above), `v` (Below) or `x` (both).
     pub fn to_asci(&self) -> String {
         assert!(self.maze_type == MazeType::Orthogonal, "Rejecting displaying ASCI for MazeType {}! ASCI display behavior is only applicable to the Orthogonal MazeType", self.maze_type.to_string());
-        let mut output = format!("+{}\n", "---+".repeat(self.width));
+        let top_row: String = (0..self.width)
+            .map(|x| match self.get_by_coords(x, 0).map(|cell| cell.border_opening) {
+                Ok(Some(Direction::Up)) => "   +",
+                _ => "---+",
+            })
+            .collect();
+        let mut output = format!("+{}\n", top_row);
         // For orthogonal mazes, all cells should be Some(Cell), so unwrapping is safe
         let unflattened: Vec<Vec<Cell>> = self.unflatten()
             .into_iter()
             .map(|row| row.into_iter().map(|opt| opt.unwrap()).collect())
             .collect();
         for row in unflattened {
-            let mut top = String::from("|");
+            let west_boundary = if row[0].border_opening == Some(Direction::Left) { " " } else { "|" };
+            let mut top = String::from(west_boundary);
             let mut bottom = String::from("+");
             for cell in row {
-                let body = "   ";
+                let body = match (cell.is_linked_direction(Direction::Above), cell.is_linked_direction(Direction::Below)) {
+                    (true, true) => " x ",
+                    (true, false) => " ^ ",
+                    (false, true) => " v ",
+                    (false, false) => "   ",
+                };
                 let east_boundary = match cell.neighbors_by_direction.get(&Direction::Right).is_some() {
                     true if cell.is_linked_direction(Direction::Right) => " ",
+                    false if cell.border_opening == Some(Direction::Right) => " ",
                     _ => "|",
                 };
                 top.push_str(body);
                 top.push_str(east_boundary);
                 let south_boundary = match cell.neighbors_by_direction.get(&Direction::Down).is_some() {
                     true if cell.is_linked_direction(Direction::Down) => "   ",
+                    false if cell.border_opening == Some(Direction::Down) => "   ",
                     _ => "---",
                 };
                 let corner = "+";
@@ -1039,6 +3017,7 @@ impl Grid {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::algorithms::MazeAlgorithm;
     use crate::algorithms::hunt_and_kill::HuntAndKill;
     use crate::behaviors::maze::MazeGeneration;
 
@@ -1313,6 +3292,246 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_distance_bands() {
+        match Grid::new(MazeType::Orthogonal, 4, 1, Coordinates { x: 0, y: 0 }, Coordinates { x: 3, y: 0 }, false) {
+            Ok(mut grid) => {
+                let _ = grid.link(Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 0 });
+                let _ = grid.link(Coordinates { x: 1, y: 0 }, Coordinates { x: 2, y: 0 });
+                let _ = grid.link(Coordinates { x: 2, y: 0 }, Coordinates { x: 3, y: 0 });
+                let bands = grid.distance_bands(2).expect("Failed to compute distance bands");
+                assert_eq!(bands[&Coordinates { x: 0, y: 0 }], 0);
+                assert_eq!(bands[&Coordinates { x: 1, y: 0 }], 0);
+                assert_eq!(bands[&Coordinates { x: 2, y: 0 }], 1);
+                assert_eq!(bands[&Coordinates { x: 3, y: 0 }], 1);
+                assert!(grid.distance_bands(0).is_err());
+
+                grid.assign_distance_bands(4).expect("Failed to assign distance bands");
+                for x in 0..4 {
+                    assert_eq!(grid.get_by_coords(x, 0).unwrap().distance_band, Some(x));
+                }
+                assert!(grid.to_string().contains("\"distance_band\":3"));
+            }
+            Err(e) => panic!("Unexpected error occurred running Grid test test_distance_bands: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_distance_bands_from_json_request() {
+        let json = r#"
+        {
+            "maze_type": "Sigma",
+            "width": 10,
+            "height": 10,
+            "algorithm": "RecursiveBacktracker",
+            "distance_bands": 5
+        }
+        "#;
+        let maze = Grid::try_from(json).expect("Unexpected error constructing maze");
+        let cells: Vec<&Cell> = maze.iter_cells().collect();
+        assert!(cells.iter().all(|cell| matches!(cell.distance_band, Some(band) if band < 5)));
+        assert!(cells.iter().any(|cell| cell.distance_band == Some(0)));
+        assert!(cells.iter().any(|cell| cell.distance_band == Some(4)));
+    }
+
+    #[test]
+    fn test_longest_path() {
+        let json = r#"
+        {
+            "maze_type": "Sigma",
+            "width": 8,
+            "height": 8,
+            "algorithm": "Kruskals"
+        }
+        "#;
+        let maze = Grid::try_from(json).expect("Unexpected error constructing maze");
+        let (first, second, path) = maze.longest_path();
+        assert_eq!(path.first(), Some(&first));
+        assert_eq!(path.last(), Some(&second));
+        for pair in path.windows(2) {
+            assert!(maze.get(pair[0]).unwrap().is_linked(pair[1]));
+        }
+        // compare against the brute-force diameter
+        let diameter = maze.iter_cells()
+            .map(|cell| *maze.distances(cell.coords).values().max().unwrap())
+            .max()
+            .unwrap();
+        assert_eq!(path.len() - 1, diameter as usize);
+    }
+
+    #[test]
+    fn test_longest_path_endpoints_from_json_request() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 10,
+            "height": 10,
+            "algorithm": "Wilsons",
+            "start": { "x": 0, "y": 0 },
+            "goal": { "x": 9, "y": 9 },
+            "endpoints": "LongestPath"
+        }
+        "#;
+        let mut maze = Grid::try_from(json).expect("Unexpected error constructing maze");
+        let (first, second, path) = maze.longest_path();
+        assert_eq!((maze.start_coords, maze.goal_coords), (first, second));
+        assert!(maze.get(first).unwrap().is_start && maze.get(second).unwrap().is_goal);
+        assert_eq!(maze.iter_cells().filter(|c| c.is_start || c.is_goal).count(), 2);
+        assert_eq!(maze.iter_cells().filter(|c| c.on_solution_path).count(), path.len());
+        assert_eq!(maze.get(second).unwrap().distance as usize, path.len() - 1);
+        assert_eq!(maze.get_active_cell().unwrap().coords, first);
+    }
+
+    #[test]
+    fn test_crop_keeps_passages_inside_region() {
+        for maze_type in MazeType::all() {
+            let json = format!(r#"
+            {{
+                "maze_type": "{}",
+                "width": 10,
+                "height": 8,
+                "algorithm": "RecursiveBacktracker"
+            }}
+            "#, maze_type.name());
+            let maze = Grid::try_from(json).expect("Unexpected error constructing maze");
+            let rect = Rect { x: 2, y: 2, width: 6, height: 4 };
+            let cropped = maze.crop(rect).expect("Unexpected error cropping maze");
+            assert_eq!((cropped.width, cropped.height), (6, 4));
+            for cell in cropped.iter_cells() {
+                let original = maze.get(Coordinates { x: cell.coords.x + 2, y: cell.coords.y + 2 }).unwrap();
+                let kept: HashSet<Coordinates> = original.linked.iter()
+                    .filter(|&n| rect.contains(n))
+                    .map(|n| Coordinates { x: n.x - 2, y: n.y - 2 })
+                    .collect();
+                assert_eq!(cell.linked.iter().collect::<HashSet<_>>(), kept, "{:?} cell {:?}", maze_type, cell.coords);
+                assert!(cell.linked.iter().all(|n| cell.neighbors().contains(&n)), "{:?} links must join neighbors", maze_type);
+            }
+        }
+        let maze = Grid::try_from(r#"{ "maze_type": "Delta", "width": 6, "height": 6, "algorithm": "Prims" }"#).unwrap();
+        assert!(matches!(maze.crop(Rect { x: 1, y: 0, width: 2, height: 2 }), Err(Error::InvalidCropRegion { .. })));
+        assert!(matches!(maze.crop(Rect { x: 2, y: 2, width: 6, height: 2 }), Err(Error::InvalidCropRegion { .. })));
+    }
+
+    #[test]
+    fn test_stitch_joins_mazes_along_seam() {
+        for maze_type in MazeType::all() {
+            let json = format!(r#"
+            {{
+                "maze_type": "{}",
+                "width": 6,
+                "height": 6,
+                "algorithm": "Kruskals"
+            }}
+            "#, maze_type.name());
+            let left = Grid::try_from(json.as_str()).expect("Unexpected error constructing maze");
+            let right = Grid::try_from(json.as_str()).expect("Unexpected error constructing maze");
+
+            let wide = left.stitch(&right, Coordinates { x: 6, y: 0 }, 1).expect("Unexpected error stitching mazes");
+            assert_eq!((wide.width, wide.height), (12, 6));
+            assert!(wide.is_perfect_maze().unwrap(), "{:?} stitched with one connector should be perfect", maze_type);
+            assert_eq!(wide.start_coords, left.start_coords);
+            assert_eq!(wide.goal_coords, Coordinates { x: right.goal_coords.x + 6, y: right.goal_coords.y });
+            assert!(wide.iter_cells().any(|cell| cell.on_solution_path && cell.coords.x >= 6));
+
+            let tall = left.stitch(&right, Coordinates { x: 0, y: 6 }, 3).expect("Unexpected error stitching mazes");
+            assert_eq!(tall.count_edges(), left.count_edges() + right.count_edges() + 3);
+        }
+        let maze = Grid::try_from(r#"{ "maze_type": "Orthogonal", "width": 4, "height": 4, "algorithm": "Prims" }"#).unwrap();
+        assert!(matches!(maze.stitch(&maze, Coordinates { x: 4, y: 4 }, 1), Err(Error::InvalidStitch { .. })));
+        assert!(matches!(maze.stitch(&maze, Coordinates { x: 4, y: 0 }, 5), Err(Error::InvalidStitch { .. })));
+        assert!(matches!(maze.stitch(&maze, Coordinates { x: 4, y: 0 }, 0), Err(Error::InvalidStitch { .. })));
+    }
+
+    #[test]
+    fn test_sigma_axial_coordinates() {
+        let maze = Grid::try_from(r#"{ "maze_type": "Sigma", "width": 7, "height": 5, "algorithm": "Wilsons" }"#).unwrap();
+        for cell in maze.iter_cells() {
+            let axial = maze.to_axial(cell.coords).unwrap();
+            assert_eq!(maze.from_axial(axial).unwrap(), cell.coords);
+            // in axial coordinates every hex neighbor is a single step away
+            for neighbor in cell.neighbors() {
+                assert_eq!(axial.distance(&maze.to_axial(neighbor).unwrap()), 1);
+            }
+        }
+        let json = serde_json::to_value(maze.get(Coordinates { x: 3, y: 2 }).unwrap()).unwrap();
+        assert_eq!(json["axial"]["q"], 3);
+        assert_eq!(json["axial"]["r"], 1);
+        assert!(matches!(maze.from_axial(AxialCoordinates { q: 7, r: 0 }), Err(Error::InvalidAxialCoordinates { .. })));
+
+        let orthogonal = Grid::try_from(r#"{ "maze_type": "Orthogonal", "width": 4, "height": 4, "algorithm": "Wilsons" }"#).unwrap();
+        assert!(matches!(orthogonal.to_axial(Coordinates { x: 0, y: 0 }), Err(Error::AxialCoordinatesUnavailable { .. })));
+        assert!(serde_json::to_value(orthogonal.get(Coordinates { x: 0, y: 0 }).unwrap()).unwrap().get("axial").is_none());
+    }
+
+    #[test]
+    fn test_distance_heatmap() {
+        let json = r#"
+        {
+            "maze_type": "Delta",
+            "width": 10,
+            "height": 10,
+            "algorithm": "AldousBroder",
+            "heatmap": true
+        }
+        "#;
+        let maze = Grid::try_from(json).expect("Unexpected error constructing maze");
+        let heat = maze.distance_heatmap();
+        assert_eq!(heat.len(), maze.iter_cells().count());
+        assert!(heat.iter().all(|&(_, h)| (0.0..=1.0).contains(&h)));
+        assert!(heat.iter().any(|&(_, h)| h == 1.0));
+        for (coords, h) in &heat {
+            assert_eq!(maze.get(*coords).unwrap().heat, Some(*h));
+        }
+        assert_eq!(maze.get(maze.start_coords).unwrap().heat, Some(0.0));
+        assert!(maze.to_string().contains("\"heat\":"));
+
+        let from_goal = maze.distance_heatmap_from(maze.goal_coords);
+        assert_eq!(from_goal.iter().find(|(c, _)| *c == maze.goal_coords).unwrap().1, 0.0);
+    }
+
+    #[test]
+    fn test_saved_json_round_trip() {
+        for maze_type in MazeType::all() {
+            let json = format!(r#"
+            {{
+                "maze_type": "{:?}",
+                "width": 9,
+                "height": 9,
+                "algorithm": "Kruskals",
+                "distance_bands": 3
+            }}
+            "#, maze_type);
+            let mut maze = Grid::try_from(json).expect("Unexpected error constructing maze");
+            // make some progress so visited state and history are persisted too
+            for _ in 0..3 {
+                if let Some(direction) = maze.hint() {
+                    maze.make_move(direction).unwrap();
+                }
+            }
+            maze.undo().unwrap();
+
+            let saved = maze.to_json().expect("Failed to serialize maze");
+            let restored = Grid::from_saved_json(&saved).expect("Failed to restore maze");
+            assert_eq!((restored.maze_type, restored.width, restored.height), (maze.maze_type, maze.width, maze.height));
+            assert_eq!((restored.start_coords, restored.goal_coords, restored.seed), (maze.start_coords, maze.goal_coords, maze.seed));
+            assert_eq!(restored.move_history(), maze.move_history());
+            assert_eq!(restored.undone_moves, maze.undone_moves);
+            for (a, b) in restored.iter_cells().zip(maze.iter_cells()) {
+                assert_eq!(a.coords, b.coords);
+                assert_eq!(a.linked, b.linked, "{:?} links differ at {:?}", maze_type, a.coords);
+                let open_neighbors = |cell: &Cell| -> HashSet<Coordinates> {
+                    cell.open_walls.iter().filter_map(|d| cell.neighbors_by_direction.get(&d)).collect()
+                };
+                assert_eq!(open_neighbors(a), open_neighbors(b));
+                assert_eq!(
+                    (a.is_active, a.is_visited, a.has_been_visited, a.on_solution_path, a.distance, a.distance_band),
+                    (b.is_active, b.is_visited, b.has_been_visited, b.on_solution_path, b.distance, b.distance_band)
+                );
+            }
+        }
+        assert!(Grid::from_saved_json(r#"{ "rows": [] }"#).is_err());
+    }
+
     #[test]
     fn test_recursive_backtracker_orthogonal_12_x_12_maze_generation_from_json() {
         let json = r#"
@@ -1517,9 +3736,7 @@ mod tests {
 
         // ensure the cell we backtracked from is no longer visited but still permanent
         let back_cell = maze
-            .cells
-            .iter()
-            .filter_map(|opt| opt.as_ref())
+            .iter_cells()
             .find(|cell| cell.coords == cell1_coords)
             .expect("Backtracked cell not found");
         assert!(!back_cell.is_visited, "Backtracked cell should no longer be marked visited");
@@ -1527,9 +3744,7 @@ mod tests {
 
         // ensure start cell remains visited and permanent
         let start_cell = maze
-            .cells
-            .iter()
-            .filter_map(|opt| opt.as_ref())
+            .iter_cells()
             .find(|c| c.coords == original_coords)
             .unwrap();
         assert!(start_cell.is_visited, "Start cell should remain is_visited");
@@ -1573,8 +3788,8 @@ mod tests {
 
         // sanity: only one visited
         
-        assert_eq!(maze.cells.iter().filter_map(|opt| opt.as_ref()).filter(|c| c.is_visited).count(), 1);
-        assert_eq!(maze.cells.iter().filter_map(|opt| opt.as_ref()).filter(|c| c.has_been_visited).count(), 1);
+        assert_eq!(maze.iter_cells().filter(|c| c.is_visited).count(), 1);
+        assert_eq!(maze.iter_cells().filter(|c| c.has_been_visited).count(), 1);
     
         // unavailable move must error
         {
@@ -1606,15 +3821,14 @@ mod tests {
             let back_to_start: Direction = first_cell
                 .neighbors_by_direction
                 .iter()
-                .find_map(|(dir, &coords)| {
+                .find_map(|(dir, coords)| {
                     if coords == original_coords { Some(dir.clone()) } else { None }
                 })
                 .expect("Expected a reverse link back to the start cell");
     
             // b) pick *any* other open wall (if there is one)
             if let Some(requested2) = first_cell.open_walls.iter()
-                .find(|dir| *dir != &back_to_start)
-                .cloned()
+                .find(|dir| *dir != back_to_start)
             {
                 // 2c) make the second forward move
                 let _ = maze
@@ -1628,7 +3842,7 @@ mod tests {
                 let back2: Direction = second_cell
                     .neighbors_by_direction
                     .iter()
-                    .find_map(|(dir, &coords)| {
+                    .find_map(|(dir, coords)| {
                         if coords == cell_after_first { Some(dir.clone()) } else { None }
                     })
                     .expect("Expected a neighbor mapping back to the first cell");
@@ -1644,7 +3858,7 @@ mod tests {
                 let back1_again: Direction = first_again
                     .neighbors_by_direction
                     .iter()
-                    .find_map(|(dir, &coords)| {
+                    .find_map(|(dir, coords)| {
                         if coords == original_coords { Some(dir.clone()) } else { None }
                     })
                     .expect("Expected a neighbor mapping back to the start cell");
@@ -1715,6 +3929,302 @@ mod tests {
         run_make_move_delta_test("Wilsons");
     }
 
+    #[test]
+    fn test_can_move_agrees_with_make_move() {
+        for maze_type in MazeType::all() {
+            let json = format!(r#"
+            {{
+                "maze_type": "{:?}",
+                "width": 10,
+                "height": 10,
+                "algorithm": "RecursiveBacktracker"
+            }}
+            "#, maze_type);
+            let mut maze = Grid::try_from(json).expect("Unexpected error constructing maze");
+            // walk a few steps so the check covers cells other than the start
+            for _ in 0..5 {
+                for &direction in maze.all_moves() {
+                    let mut copy = maze.clone();
+                    assert_eq!(maze.can_move(direction), copy.make_move(direction).is_ok(), "{:?} {:?}", maze_type, direction);
+                }
+                let next = maze.effective_moves()[0];
+                maze.make_move(next).expect("effective move should succeed");
+            }
+            let all: HashSet<Direction> = maze.all_moves().iter().cloned().collect();
+            let effective: HashSet<Direction> = maze.effective_moves().into_iter().collect();
+            let unavailable: HashSet<Direction> = maze.unavailable_moves().into_iter().collect();
+            assert!(effective.is_disjoint(&unavailable));
+            assert_eq!(effective.union(&unavailable).cloned().collect::<HashSet<_>>(), all);
+        }
+    }
+
+    #[test]
+    fn test_move_policy() {
+        let json = r#"{ "maze_type": "Delta", "width": 12, "height": 12, "algorithm": "RecursiveBacktracker", "seed": 3 }"#;
+        let mut maze = Grid::try_from(json).unwrap();
+        // walk until a move falls back to a direction other than the one requested
+        let (requested, taken) = loop {
+            let fallback = maze.all_moves().iter().find_map(|&direction| {
+                let taken = maze.clone().make_move(direction).ok()?;
+                (taken != direction).then_some((direction, taken))
+            });
+            match fallback {
+                Some(fallback) => break fallback,
+                None => {
+                    let next = maze.effective_moves()[0];
+                    maze.make_move(next).unwrap();
+                }
+            }
+        };
+
+        maze.move_policy = MovePolicy::Strict;
+        assert!(!maze.can_move(requested));
+        assert!(matches!(maze.clone().make_move(requested), Err(Error::MoveUnavailable { .. })));
+        assert!(maze.clone().make_move(taken).is_ok());
+
+        maze.move_policy = MovePolicy::Custom(vec![(requested, vec![taken])]);
+        assert_eq!(maze.clone().make_move(requested).unwrap(), taken);
+        let saved: Grid = serde_json::from_str(&serde_json::to_string(&maze).unwrap()).unwrap();
+        assert_eq!(saved.move_policy, maze.move_policy);
+
+        let strict = Grid::try_from(json.replace(r#""seed": 3"#, r#""seed": 3, "move_policy": "Strict""#).as_str()).unwrap();
+        assert_eq!(strict.move_policy, MovePolicy::Strict);
+    }
+
+    #[test]
+    fn test_make_move_toward() {
+        for &maze_type in MazeType::all() {
+            let json = format!(r#"{{ "maze_type": "{:?}", "width": 8, "height": 8, "algorithm": "Prims" }}"#, maze_type);
+            let mut maze = Grid::try_from(json.as_str()).unwrap();
+            let active = maze.get_active_cell().unwrap().coords;
+            assert!(matches!(maze.make_move_toward(active), Err(Error::NoMoveToward { .. })), "{:?}", maze_type);
+            // tapping an open neighbor moves to it
+            let neighbor = maze.get(active).unwrap().linked.iter().min().unwrap();
+            let direction = maze.make_move_toward(neighbor).unwrap();
+            assert_eq!(maze.get_active_cell().unwrap().coords, neighbor, "{:?}", maze_type);
+            assert_eq!(maze.move_history().last().unwrap().direction, direction);
+        }
+
+        // tapping far away steps toward the tap
+        let json = r#"{ "maze_type": "Orthogonal", "width": 5, "height": 1, "algorithm": "Prims", "start": { "x": 0, "y": 0 } }"#;
+        let mut corridor = Grid::try_from(json).unwrap();
+        assert_eq!(corridor.make_move_toward(Coordinates { x: 4, y: 0 }).unwrap(), Direction::Right);
+        assert!(corridor.make_move_toward(Coordinates { x: 5, y: 0 }).is_err());
+    }
+
+    #[test]
+    fn test_move_until_junction() {
+        let json = r#"{ "maze_type": "Orthogonal", "width": 5, "height": 1, "algorithm": "Prims", "start": { "x": 0, "y": 0 }, "goal": { "x": 4, "y": 0 } }"#;
+        let mut corridor = Grid::try_from(json).unwrap();
+        assert!(matches!(corridor.move_until_junction(Direction::Left), Err(Error::MoveUnavailable { .. })));
+        let traversed = corridor.move_until_junction(Direction::Right).unwrap();
+        assert_eq!(traversed, (1..5).map(|x| Coordinates { x, y: 0 }).collect::<Vec<_>>());
+        assert_eq!(corridor.move_history().len(), 4);
+        assert!(traversed.iter().all(|&coords| corridor.get(coords).unwrap().is_visited));
+
+        for &maze_type in MazeType::all() {
+            let json = format!(r#"{{ "maze_type": "{:?}", "width": 8, "height": 8, "algorithm": "Wilsons" }}"#, maze_type);
+            let mut maze = Grid::try_from(json.as_str()).unwrap();
+            let direction = maze.get_active_cell().unwrap().get_user_facing_open_walls()[0];
+            let traversed = maze.move_until_junction(direction).unwrap();
+            let stopped = maze.get(maze.active_coords().unwrap()).unwrap();
+            assert_eq!(traversed.last(), Some(&stopped.coords), "{:?}", maze_type);
+            assert!(stopped.coords == maze.goal_coords || stopped.linked.len() != 2, "{:?} stopped mid-corridor", maze_type);
+            // every cell passed through along the way is part of the corridor
+            for coords in &traversed[..traversed.len() - 1] {
+                assert_eq!(maze.get(*coords).unwrap().linked.len(), 2, "{:?}", maze_type);
+            }
+        }
+    }
+
+    #[test]
+    fn test_canonical_hash() {
+        let json = |seed: u64| format!(r#"{{ "maze_type": "Orthogonal", "width": 6, "height": 6, "algorithm": "Wilsons", "seed": {} }}"#, seed);
+        let mut maze = Grid::try_from(json(7).as_str()).unwrap();
+        let hash = maze.canonical_hash();
+        assert_eq!(Grid::try_from(json(7).as_str()).unwrap().canonical_hash(), hash);
+        // the user's progress, and the endpoints, are not part of the maze's identity
+        let direction = maze.get_active_cell().unwrap().get_user_facing_open_walls()[0];
+        maze.make_move(direction).unwrap();
+        maze.goal_coords = maze.start_coords;
+        assert_eq!(maze.canonical_hash(), hash);
+        let saved: Grid = serde_json::from_str(&serde_json::to_string(&maze).unwrap()).unwrap();
+        assert_eq!(saved.canonical_hash(), hash);
+
+        let passage = (Coordinates { x: 0, y: 0 }, maze.get(Coordinates { x: 0, y: 0 }).unwrap().linked.iter().next().unwrap());
+        maze.unlink(passage.0, passage.1).unwrap();
+        assert_ne!(maze.canonical_hash(), hash);
+
+        // mazes share a hash exactly when they share their passages
+        let mut passages_by_hash: HashMap<u64, Vec<(usize, usize, usize, usize)>> = HashMap::new();
+        for seed in 0..500 {
+            let maze = Grid::try_from(json(seed).as_str()).unwrap();
+            let mut passages: Vec<_> = maze.iter_cells()
+                .flat_map(|cell| cell.linked.iter().map(move |linked| (cell.coords.x, cell.coords.y, linked.x, linked.y)))
+                .collect();
+            passages.sort_unstable();
+            let known = passages_by_hash.entry(maze.canonical_hash()).or_insert_with(|| passages.clone());
+            assert_eq!(*known, passages, "hash collision at seed {}", seed);
+        }
+
+        // the hash is stable across releases
+        let mut corridor = Grid::new(MazeType::Orthogonal, 2, 1, Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 0 }, false).unwrap();
+        corridor.link(Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 0 }).unwrap();
+        assert_eq!(corridor.canonical_hash(), 12378020113335102151);
+    }
+
+    #[test]
+    fn test_similarity() {
+        let json = |seed: u64| format!(r#"{{ "maze_type": "Orthogonal", "width": 6, "height": 4, "algorithm": "Wilsons", "seed": {} }}"#, seed);
+        let maze = Grid::try_from(json(1).as_str()).unwrap();
+        assert_eq!(maze.similarity(&maze.clone()), 1.0);
+        let other = Grid::try_from(json(2).as_str()).unwrap();
+        let similarity = maze.similarity(&other);
+        assert!(similarity > 0.0 && similarity < 1.0, "{}", similarity);
+        assert_eq!(other.similarity(&maze), similarity);
+        let sigma = Grid::try_from(json(1).replace("Orthogonal", "Sigma").as_str()).unwrap();
+        assert_eq!(maze.similarity(&sigma), 0.0);
+        assert_eq!(maze.similarity_with_symmetry(&sigma), 0.0);
+
+        // the same maze mirrored, and turned on its side
+        let start = Coordinates { x: 0, y: 0 };
+        let mut mirrored = Grid::new(MazeType::Orthogonal, 6, 4, start, Coordinates { x: 5, y: 3 }, false).unwrap();
+        let mut rotated = Grid::new(MazeType::Orthogonal, 4, 6, start, Coordinates { x: 3, y: 5 }, false).unwrap();
+        for (a, b) in maze.passages() {
+            mirrored.link(Coordinates { x: 5 - a.x, y: a.y }, Coordinates { x: 5 - b.x, y: b.y }).unwrap();
+            rotated.link(Coordinates { x: 3 - a.y, y: a.x }, Coordinates { x: 3 - b.y, y: b.x }).unwrap();
+        }
+        assert!(maze.similarity(&mirrored) < 1.0);
+        assert_eq!(maze.similarity_with_symmetry(&mirrored), 1.0);
+        assert_eq!(maze.similarity(&rotated), 0.0);
+        assert_eq!(maze.similarity_with_symmetry(&rotated), 1.0);
+        assert!(maze.similarity_with_symmetry(&other) >= similarity);
+    }
+
+    #[test]
+    fn test_players() {
+        let json = r#"{ "maze_type": "Orthogonal", "width": 5, "height": 1, "algorithm": "Prims", "start": { "x": 0, "y": 0 }, "goal": { "x": 4, "y": 0 } }"#;
+        let mut race = Grid::try_from(json).unwrap();
+        let start = race.start_coords;
+        race.add_player(1, start).unwrap();
+        race.add_player(2, start).unwrap();
+        assert!(matches!(race.add_player(1, start), Err(Error::DuplicatePlayer { id: 1 })));
+        assert!(matches!(race.add_player(3, Coordinates { x: 9, y: 9 }), Err(Error::InvalidStartCoordinates { .. })));
+        assert!(matches!(race.make_move_for(3, Direction::Right), Err(Error::UnknownPlayer { id: 3 })));
+        assert!(matches!(race.make_move_for(1, Direction::Left), Err(Error::MoveUnavailable { .. })));
+
+        for _ in 0..4 {
+            assert_eq!(race.make_move_for(1, Direction::Right).unwrap(), Direction::Right);
+        }
+        let winner = race.player(1).unwrap();
+        assert!(winner.completed);
+        assert_eq!(winner.visited.len(), 5);
+        assert_eq!(winner.move_history.len(), 4);
+        // the other player, and the user, are unaffected
+        assert!(!race.player(2).unwrap().completed);
+        assert_eq!(race.get_active_cell().unwrap().coords, start);
+        assert!(race.move_history.is_empty());
+        assert!(!race.completed);
+
+        // backtracking retracts the player's trail, but not what it has seen
+        race.make_move_for(2, Direction::Right).unwrap();
+        race.make_move_for(2, Direction::Left).unwrap();
+        let player = race.player(2).unwrap();
+        assert!(player.is_visited(start) && !player.is_visited(Coordinates { x: 1, y: 0 }));
+        assert_eq!(player.has_been_visited.len(), 2);
+        assert!(player.move_history[1].backtrack);
+
+        let saved: Grid = serde_json::from_str(&serde_json::to_string(&race).unwrap()).unwrap();
+        assert_eq!(saved.players, race.players);
+        assert_eq!(race.remove_player(1).unwrap().id, 1);
+        assert!(race.player(1).is_none());
+    }
+
+    #[test]
+    fn test_hint_follows_solution_to_goal() {
+        for maze_type in MazeType::all() {
+            let json = format!(r#"
+            {{
+                "maze_type": "{:?}",
+                "width": 12,
+                "height": 12,
+                "algorithm": "Wilsons"
+            }}
+            "#, maze_type);
+            let mut maze = Grid::try_from(json).expect("Unexpected error constructing maze");
+            let solution_length = maze.iter_cells().filter(|c| c.on_solution_path).count();
+            let mut moves = 0;
+            while let Some(direction) = maze.hint() {
+                maze.make_move(direction).expect("hinted move should succeed");
+                assert!(maze.get_active_cell().unwrap().on_solution_path, "{:?} hint left the solution path", maze_type);
+                moves += 1;
+                assert!(moves < solution_length, "{:?} hints did not reach the goal", maze_type);
+            }
+            let goal = maze.goal_coords;
+            assert_eq!(maze.get_active_cell().unwrap().coords, goal);
+            assert_eq!(moves, solution_length - 1);
+        }
+    }
+
+    #[test]
+    fn test_undo_and_redo_moves() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 10,
+            "height": 10,
+            "algorithm": "RecursiveBacktracker",
+            "start": { "x": 0, "y": 0 },
+            "goal": { "x": 9, "y": 9 }
+        }
+        "#;
+        let mut maze = Grid::try_from(json).expect("Unexpected error constructing maze");
+        assert!(matches!(maze.undo(), Err(Error::NoMovesToUndo)));
+        let initial = maze.clone();
+
+        // walk forward along hints, then backtrack one step
+        let mut snapshots = vec![maze.clone()];
+        for _ in 0..3 {
+            let direction = maze.hint().expect("Expected a hint");
+            maze.make_move(direction).expect("hinted move should succeed");
+            snapshots.push(maze.clone());
+        }
+        let back = maze.move_history().last().unwrap().direction.opposite();
+        maze.make_move(back).expect("backtracking should succeed");
+        assert!(maze.move_history().last().unwrap().backtrack);
+        assert_eq!(maze.move_history().len(), 4);
+
+        // undo everything, checking each intermediate state is restored exactly
+        let after_moves = maze.clone();
+        maze.undo().expect("undo should succeed");
+        for snapshot in snapshots.iter().rev() {
+            for (a, b) in maze.iter_cells().zip(snapshot.iter_cells()) {
+                assert_eq!((a.is_active, a.is_visited, a.has_been_visited), (b.is_active, b.is_visited, b.has_been_visited));
+            }
+            if maze.move_history().is_empty() {
+                break;
+            }
+            maze.undo().expect("undo should succeed");
+        }
+        assert!(maze.move_history().is_empty());
+        for (a, b) in maze.iter_cells().zip(initial.iter_cells()) {
+            assert_eq!((a.is_active, a.is_visited, a.has_been_visited), (b.is_active, b.is_visited, b.has_been_visited));
+        }
+
+        // redo everything to arrive back at the same state
+        while maze.redo().is_ok() {}
+        assert_eq!(maze.move_history(), after_moves.move_history());
+        for (a, b) in maze.iter_cells().zip(after_moves.iter_cells()) {
+            assert_eq!((a.is_active, a.is_visited, a.has_been_visited), (b.is_active, b.is_visited, b.has_been_visited));
+        }
+
+        // a fresh move discards the redo stack
+        maze.undo().expect("undo should succeed");
+        let direction = maze.effective_moves()[0];
+        maze.make_move(direction).expect("move should succeed");
+        assert!(matches!(maze.redo(), Err(Error::NoMovesToRedo)));
+    }
+
     /// Manually linking two cells should produce a bidirectional link.
     #[test]
     fn test_manual_link_is_bidirectional() {
@@ -1765,11 +4275,9 @@ mod tests {
         "#;
         let maze = Grid::try_from(json).unwrap();
 
-        maze.cells
-            .iter()
-            .filter_map(|opt| opt.as_ref())
+        maze.iter_cells()
             .for_each(|cell| {
-                for &neighbor_coords in &cell.linked {
+                for neighbor_coords in &cell.linked {
                     let neighbor = maze.get(neighbor_coords).unwrap();
                     assert!(
                         neighbor.linked.contains(&cell.coords),
@@ -1787,7 +4295,7 @@ mod tests {
     fn check_bidirectional_links(grid: &Grid, step_index: usize) {
         for opt in grid.cells.iter() {
             if let Some(cell) = opt.as_ref() {
-                for &neighbor_coords in &cell.linked {
+                for neighbor_coords in &cell.linked {
                     let neighbor = grid.get(neighbor_coords).unwrap();
                     assert!(
                         neighbor.linked.contains(&cell.coords),
@@ -1814,7 +4322,7 @@ mod tests {
         assert!(!steps.is_empty(), "Expected some generation steps");
         
         for (i, step) in steps.iter().enumerate() {
-            check_bidirectional_links(step, i);
+            check_bidirectional_links(&step, i);
         }
     }
 
@@ -1831,8 +4339,424 @@ mod tests {
         assert!(!steps.is_empty(), "Expected some generation steps");
         
         for (i, step) in steps.iter().enumerate() {
-            check_bidirectional_links(step, i);
+            check_bidirectional_links(&step, i);
+        }
+    }
+
+    #[test]
+    fn test_layered_mazes_join_layers_with_stairs() {
+        for algorithm in ["RecursiveBacktracker", "Prims", "Kruskals"] {
+            let json = format!(r#"{{ "maze_type": "Orthogonal", "width": 6, "height": 5, "layers": 3, "algorithm": "{}" }}"#, algorithm);
+            let grid = Grid::try_from(json).unwrap();
+            assert_eq!((grid.height, grid.layers, grid.layer_height()), (15, 3, 5));
+            assert!(grid.is_perfect_maze().unwrap(), "{} layered maze should be perfect", algorithm);
+
+            let mut stairs = 0;
+            for cell in grid.iter_cells() {
+                for neighbor in &cell.linked {
+                    let direction = cell.neighbors_by_direction.iter().find(|&(_, n)| n == neighbor).map(|(d, _)| d).unwrap();
+                    if grid.layer_of(neighbor) != grid.layer_of(cell.coords) {
+                        // only stairs lead between layers, and always to the same position on the next layer
+                        assert!(direction.is_layer_transition());
+                        assert_eq!(neighbor.x, cell.coords.x);
+                        assert_eq!(grid.layer_of(neighbor).abs_diff(grid.layer_of(cell.coords)), 1);
+                        stairs += 1;
+                    }
+                }
+            }
+            assert!(stairs >= 2 * (grid.layers - 1), "every layer should be reachable by stairs");
+
+            let restored = Grid::from_saved_json(&grid.to_json().unwrap()).unwrap();
+            assert_eq!(restored.layers, 3);
+            for (original, cell) in grid.iter_cells().zip(restored.iter_cells()) {
+                assert_eq!(original.linked, cell.linked);
+            }
+            assert!(grid.to_bytes().is_err());
+        }
+    }
+
+    #[test]
+    fn test_move_between_layers() {
+        let start = Coordinates { x: 0, y: 0 };
+        let goal = Coordinates { x: 2, y: 5 };
+        let mut grid = Grid::new_layered(MazeType::Orthogonal, 3, 3, 2, start, goal, false).unwrap();
+        assert!(!grid.get(Coordinates { x: 0, y: 2 }).unwrap().neighbors_by_direction.contains_key(&Direction::Down));
+        grid.link(start, Coordinates { x: 0, y: 3 }).unwrap();
+
+        assert_eq!(grid.effective_moves(), vec![Direction::Above]);
+        assert_eq!(grid.make_move(Direction::Above).unwrap(), Direction::Above);
+        assert_eq!(grid.active_coords().unwrap(), Coordinates { x: 0, y: 3 });
+        assert_eq!(grid.make_move(Direction::Below).unwrap(), Direction::Below);
+        assert_eq!(grid.active_coords().unwrap(), start);
+
+        assert!(matches!(
+            Grid::new_layered(MazeType::Sigma, 3, 3, 2, start, goal, false),
+            Err(Error::InvalidLayers { .. })
+        ));
+    }
+
+    #[test]
+    fn test_collect_all_targets() {
+        // a corridor from (0, 0) through (1, 0) to (2, 0)
+        let json = r#"{ "maze_type": "Orthogonal", "width": 3, "height": 1, "algorithm": "Prims", "start": { "x": 1, "y": 0 }, "goal": { "x": 2, "y": 0 }, "collect": "AllCells" }"#;
+        let mut grid = Grid::try_from(json).unwrap();
+        assert_eq!(grid.targets.len(), 3);
+        assert_eq!(grid.remaining_targets(), vec![Coordinates { x: 0, y: 0 }, Coordinates { x: 2, y: 0 }]);
+
+        // reaching the goal leaves a target behind
+        grid.make_move(Direction::Right).unwrap();
+        assert!(!grid.completed);
+        grid.make_move(Direction::Left).unwrap();
+        grid.make_move(Direction::Left).unwrap();
+        assert!(grid.remaining_targets().is_empty());
+        assert!(grid.completed);
+
+        let restored = Grid::from_saved_json(&grid.to_json().unwrap()).unwrap();
+        assert_eq!(restored.targets, grid.targets);
+        assert!(restored.completed);
+
+        grid.undo().unwrap();
+        assert!(!grid.completed);
+        grid.set_targets(Vec::new()).unwrap();
+        assert!(!grid.completed);
+        grid.make_move(Direction::Right).unwrap();
+        assert!(grid.completed);
+    }
+
+    #[test]
+    fn test_chase_and_flee_follow_passages() {
+        // a U-shaped corridor: (0, 0) down to (0, 1), across to (1, 1), then up to (1, 0), with a wall between (0, 0) and (1, 0)
+        let mut grid = Grid::new(MazeType::Orthogonal, 2, 2, Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 0 }, false).unwrap();
+        grid.link(Coordinates { x: 0, y: 0 }, Coordinates { x: 0, y: 1 }).unwrap();
+        grid.link(Coordinates { x: 0, y: 1 }, Coordinates { x: 1, y: 1 }).unwrap();
+        grid.link(Coordinates { x: 1, y: 1 }, Coordinates { x: 1, y: 0 }).unwrap();
+
+        assert_eq!(grid.next_step_towards(Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 0 }), Some(Direction::Down));
+        assert_eq!(grid.next_step_towards(Coordinates { x: 1, y: 1 }, Coordinates { x: 0, y: 0 }), Some(Direction::Left));
+        assert_eq!(grid.next_step_towards(Coordinates { x: 1, y: 1 }, Coordinates { x: 1, y: 1 }), None);
+
+        assert_eq!(grid.flee_direction(Coordinates { x: 0, y: 1 }, Coordinates { x: 1, y: 0 }), Some(Direction::Up));
+        assert_eq!(grid.flee_direction(Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 0 }), None);
+
+        let maze = Grid::try_from(r#"{ "maze_type": "Sigma", "width": 8, "height": 8, "algorithm": "Wilsons" }"#).unwrap();
+        let (mut chaser, target) = (maze.start_coords, maze.goal_coords);
+        while chaser != target {
+            let direction = maze.next_step_towards(chaser, target).unwrap();
+            let next = maze.get(chaser).unwrap().neighbors_by_direction.get(&direction).unwrap();
+            assert!(maze.get(chaser).unwrap().linked.contains(&next));
+            chaser = next;
+        }
+    }
+
+    #[test]
+    fn test_fog_of_war_discovers_cells_along_corridors() {
+        // a corridor along the top row, with a branch down from (1, 0) to (1, 1)
+        let mut grid = Grid::new(MazeType::Orthogonal, 4, 2, Coordinates { x: 0, y: 0 }, Coordinates { x: 3, y: 0 }, false).unwrap();
+        for (a, b) in [((0, 0), (1, 0)), ((1, 0), (2, 0)), ((2, 0), (3, 0)), ((1, 0), (1, 1))] {
+            grid.link(Coordinates { x: a.0, y: a.1 }, Coordinates { x: b.0, y: b.1 }).unwrap();
+        }
+        let row = |xs: &[usize]| xs.iter().map(|&x| Coordinates { x, y: 0 }).collect::<HashSet<_>>();
+        assert_eq!(grid.visible_cells_from(Coordinates { x: 0, y: 0 }, 2), row(&[0, 1, 2]));
+        // the branch is out of sight around the corner
+        assert!(!grid.visible_cells_from(Coordinates { x: 0, y: 0 }, 5).contains(&Coordinates { x: 1, y: 1 }));
+
+        grid.enable_fog_of_war(1).unwrap();
+        let discovered = |grid: &Grid| grid.iter_cells().filter(|cell| cell.is_discovered).map(|cell| cell.coords).collect::<HashSet<_>>();
+        assert_eq!(discovered(&grid), row(&[0, 1]));
+        grid.make_move(Direction::Right).unwrap();
+        let mut expected = row(&[0, 1, 2]);
+        expected.insert(Coordinates { x: 1, y: 1 });
+        assert_eq!(discovered(&grid), expected);
+
+        let restored = Grid::from_saved_json(&grid.to_json().unwrap()).unwrap();
+        assert_eq!(discovered(&restored), expected);
+        assert_eq!(restored.visibility_radius, Some(1));
+    }
+
+    #[test]
+    fn test_open_exits_on_border() {
+        let json = r#"{ "maze_type": "Orthogonal", "width": 5, "height": 5, "algorithm": "Prims", "open_exits": true }"#;
+        let mut grid = Grid::try_from(json).unwrap();
+        assert_eq!(grid.get(grid.start_coords).unwrap().border_opening, Some(Direction::Down));
+        assert_eq!(grid.get(grid.goal_coords).unwrap().border_opening, Some(Direction::Up));
+        assert_eq!(grid.iter_cells().filter(|cell| cell.border_opening.is_some()).count(), 2);
+        let ascii = grid.to_asci();
+        assert!(ascii.starts_with("+---+---+   +"));
+        assert!(ascii.trim_end().ends_with("+---+---+   +---+---+"));
+
+        let restored = Grid::from_saved_json(&grid.to_json().unwrap()).unwrap();
+        assert_eq!(restored.get(restored.start_coords).unwrap().border_opening, Some(Direction::Down));
+
+        // openings follow the endpoints, and interior cells have none
+        grid.set_endpoints(Coordinates { x: 0, y: 2 }, Coordinates { x: 2, y: 2 }).unwrap();
+        assert_eq!(grid.get(Coordinates { x: 0, y: 2 }).unwrap().border_opening, Some(Direction::Left));
+        assert_eq!(grid.iter_cells().filter(|cell| cell.border_opening.is_some()).count(), 1);
+
+        let json = r#"{ "maze_type": "Sigma", "width": 6, "height": 6, "algorithm": "Prims", "open_exits": true }"#;
+        let grid = Grid::try_from(json).unwrap();
+        for coords in [grid.start_coords, grid.goal_coords] {
+            let cell = grid.get(coords).unwrap();
+            let opening = cell.border_opening.unwrap();
+            assert!(!cell.neighbors_by_direction.contains_key(&opening));
+        }
+    }
+
+    #[test]
+    fn test_border_edges() {
+        let json = r#"{ "maze_type": "Orthogonal", "width": 6, "height": 5, "algorithm": "Prims",
+            "start_edge": { "side": "Left", "offset": 3 }, "goal_edge": { "side": "Up", "offset": 4 }, "open_exits": true }"#;
+        let grid = Grid::try_from(json).unwrap();
+        assert_eq!(grid.start_coords, Coordinates { x: 0, y: 3 });
+        assert_eq!(grid.goal_coords, Coordinates { x: 4, y: 0 });
+        assert_eq!(grid.get(grid.start_coords).unwrap().border_opening, Some(Direction::Left));
+        assert_eq!(grid.get(grid.goal_coords).unwrap().border_opening, Some(Direction::Up));
+        assert!(grid.get(grid.start_coords).unwrap().is_active);
+        assert!(grid.to_asci().lines().nth(7).unwrap().starts_with(' '));
+
+        // sides of other cell shapes open where they face out that way
+        for &maze_type in MazeType::all() {
+            let json = format!(r#"{{ "maze_type": "{:?}", "width": 8, "height": 8, "algorithm": "Prims", "start_edge": {{ "side": "Right", "offset": 3 }} }}"#, maze_type);
+            let grid = Grid::try_from(json.as_str()).unwrap_or_else(|e| panic!("{:?}: {}", maze_type, e));
+            let start = grid.get(grid.start_coords).unwrap();
+            let opening = start.border_opening.unwrap();
+            assert!(!start.neighbors_by_direction.contains_key(&opening), "{:?}", maze_type);
+            assert!(matches!(opening, Direction::Right | Direction::UpperRight | Direction::LowerRight), "{:?}", maze_type);
+        }
+
+        let invalid = |json: &str| Grid::try_from(json).map(|_| ()).unwrap_err();
+        assert!(matches!(invalid(r#"{ "maze_type": "Orthogonal", "width": 6, "height": 5, "algorithm": "Prims", "start_edge": { "side": "Left", "offset": 5 } }"#), Error::InvalidBorderEdge { .. }));
+        assert!(matches!(invalid(r#"{ "maze_type": "Orthogonal", "width": 6, "height": 5, "algorithm": "Prims", "start_edge": { "side": "UpperLeft", "offset": 0 } }"#), Error::InvalidBorderEdge { .. }));
+        assert!(matches!(
+            invalid(r#"{ "maze_type": "Orthogonal", "width": 6, "height": 5, "algorithm": "Prims", "start": { "x": 0, "y": 0 }, "start_edge": { "side": "Down", "offset": 0 } }"#),
+            Error::InvalidBorderEdge { .. }
+        ));
+    }
+
+    #[test]
+    fn test_cell_iterators() {
+        let json = r#"{ "maze_type": "Rhombic", "width": 6, "height": 4, "algorithm": "Prims" }"#;
+        let grid = Grid::try_from(json).unwrap();
+        // Rhombic mazes only have cells where x + y is even
+        assert_eq!(grid.iter_cells().count(), 12);
+        assert!(grid.iter_row(1).map(|cell| cell.coords).eq([1, 3, 5].map(|x| Coordinates { x, y: 1 })));
+        assert!(grid.iter_column(2).map(|cell| cell.coords).eq([0, 2].map(|y| Coordinates { x: 2, y })));
+        assert_eq!(grid.iter_row(4).count() + grid.iter_column(6).count(), 0);
+
+        let pairs: Vec<(&Cell, &Cell)> = grid.iter_linked_pairs().collect();
+        assert_eq!(pairs.len(), grid.count_edges());
+        assert!(pairs.iter().all(|(a, b)| a.coords < b.coords && a.linked.contains(&b.coords)));
+    }
+
+    #[test]
+    fn test_generation_leaves_seed_unchanged() {
+        for &algorithm in MazeAlgorithm::all() {
+            let maze_type = MazeType::all().iter().copied().find(|&maze_type| algorithm.supports(maze_type)).unwrap();
+            let json = format!(r#"{{ "maze_type": "{:?}", "width": 8, "height": 8, "algorithm": "{:?}", "seed": 7 }}"#, maze_type, algorithm);
+            let grid = Grid::try_from(json.as_str()).unwrap();
+            assert_eq!(grid.seed, 7, "{}", algorithm.name());
+        }
+    }
+
+    #[test]
+    fn test_distance_field_cache() {
+        let json = r#"{ "maze_type": "Orthogonal", "width": 6, "height": 6, "algorithm": "RecursiveBacktracker" }"#;
+        let mut grid = Grid::try_from(json).unwrap();
+        let start = grid.start_coords;
+        // the distances from the start found while generating are reused
+        let field = grid.distance_field(start);
+        assert!(Arc::ptr_eq(&field, &grid.distance_field(start)));
+        assert_eq!(*field, grid.distances(start));
+
+        // cutting a passage on the solution path makes the cached distances stale
+        let path = grid.solution_path_order();
+        grid.unlink(path[0], path[1]).unwrap();
+        let cut = grid.distance_field(start);
+        assert!(!Arc::ptr_eq(&field, &cut));
+        assert!(!cut.contains_key(&grid.goal_coords));
+        assert_eq!(grid.hint(), None);
+
+        // only the most recently used origins are kept
+        let origins: Vec<Coordinates> = grid.iter_cells().map(|cell| cell.coords).take(CACHED_DISTANCE_FIELDS).collect();
+        for &origin in &origins {
+            grid.distance_field(origin);
+        }
+        assert!(!Arc::ptr_eq(&cut, &grid.distance_field(start)));
+        assert_eq!(grid.distance_cache.fields().len(), CACHED_DISTANCE_FIELDS);
+    }
+
+    #[test]
+    fn test_difficulty_cache() {
+        let json = r#"{ "maze_type": "Orthogonal", "width": 8, "height": 8, "algorithm": "RecursiveBacktracker" }"#;
+        let mut grid = Grid::try_from(json).unwrap();
+        // serializing measures the maze once, and moves leave the measurement standing
+        grid.to_json().unwrap();
+        assert!(grid.distance_cache.difficulty().is_some());
+        if let Some(direction) = grid.hint() {
+            grid.make_move(direction).unwrap();
+        }
+        assert!(grid.distance_cache.difficulty().is_some());
+        assert_eq!(grid.difficulty(), DifficultyReport::from_grid(&grid));
+
+        // moving the endpoints or cutting a passage measures it again
+        let corner = Coordinates { x: 7, y: 0 };
+        grid.set_endpoints(grid.start_coords, corner).unwrap();
+        assert_eq!(grid.difficulty(), DifficultyReport::from_grid(&grid));
+        let path = grid.solution_path_order();
+        grid.unlink(path[0], path[1]).unwrap();
+        assert!(grid.distance_cache.difficulty().is_none());
+        assert_eq!(grid.difficulty(), DifficultyReport::from_grid(&grid));
+    }
+
+    #[test]
+    fn test_weighted_path() {
+        // an open 3x3 room, with mud on the direct route along the top row
+        let at = |x, y| Coordinates { x, y };
+        let mut grid = Grid::new(MazeType::Orthogonal, 3, 3, at(0, 0), at(2, 0), false).unwrap();
+        for y in 0..3 {
+            for x in 0..3 {
+                if x < 2 {
+                    grid.link(at(x, y), at(x + 1, y)).unwrap();
+                }
+                if y < 2 {
+                    grid.link(at(x, y), at(x, y + 1)).unwrap();
+                }
+            }
+        }
+        assert_eq!(grid.get_path_to_weighted(0, 0, 2, 0).unwrap(), HashMap::from([(at(0, 0), 0), (at(1, 0), 1), (at(2, 0), 2)]));
+        grid.set_weight(at(1, 0), Some(10)).unwrap();
+        assert_eq!(grid.get_path_to(0, 0, 2, 0).unwrap().len(), 3);
+        let around = HashMap::from([(at(0, 0), 0), (at(0, 1), 1), (at(1, 1), 2), (at(2, 1), 3), (at(2, 0), 4)]);
+        assert_eq!(grid.get_path_to_weighted(0, 0, 2, 0).unwrap(), around);
+        assert_eq!(grid.weighted_distances(at(0, 0))[&at(1, 0)], 10);
+        assert!(matches!(grid.set_weight(at(1, 1), Some(0)), Err(Error::InvalidWeight { .. })));
+
+        let restored: Grid = serde_json::from_str(&serde_json::to_string(&grid).unwrap()).unwrap();
+        assert_eq!(restored.get(at(1, 0)).unwrap().weight, Some(10));
+        grid.set_weight(at(1, 0), None).unwrap();
+        assert_eq!(grid.get_path_to_weighted(0, 0, 2, 0).unwrap().len(), 3);
+
+        let json = r#"{ "maze_type": "Orthogonal", "width": 8, "height": 8, "algorithm": "RecursiveBacktracker", "seed": 3, "weight_noise": 5 }"#;
+        let noisy = Grid::try_from(json).unwrap();
+        assert!(noisy.iter_cells().all(|cell| cell.weight.is_some_and(|weight| (1..=5).contains(&weight))));
+        assert!(noisy.iter_cells().any(|cell| cell.weight != Some(1)));
+        // a perfect maze has a single route, however its cells are weighted
+        let (start, goal) = (noisy.start_coords, noisy.goal_coords);
+        let weighted = noisy.get_path_to_weighted(start.x, start.y, goal.x, goal.y).unwrap();
+        assert_eq!(weighted.keys().collect::<HashSet<_>>(), noisy.get_path_to(start.x, start.y, goal.x, goal.y).unwrap().keys().collect());
+        let invalid = r#"{ "maze_type": "Orthogonal", "width": 4, "height": 4, "algorithm": "Prims", "weight_noise": 0 }"#;
+        assert!(matches!(Grid::try_from(invalid), Err(Error::InvalidWeight { .. })));
+    }
+
+    #[test]
+    fn test_drain_events() {
+        let at = |x, y| Coordinates { x, y };
+        let mut grid = Grid::new(MazeType::Orthogonal, 3, 1, at(0, 0), at(2, 0), false).unwrap();
+        grid.link(at(0, 0), at(1, 0)).unwrap();
+        assert!(grid.drain_events().is_empty(), "changes before the first call are not logged");
+
+        grid.link(at(1, 0), at(2, 0)).unwrap();
+        grid.link(at(1, 0), at(2, 0)).unwrap();
+        assert_eq!(grid.drain_events(), vec![GridEvent::CellLinked { a: at(1, 0), b: at(2, 0) }]);
+
+        grid.make_move(Direction::Right).unwrap();
+        assert_eq!(grid.drain_events(), vec![
+            GridEvent::ActiveMoved { from: at(0, 0), to: at(1, 0) },
+            GridEvent::VisitedChanged { coords: at(1, 0), is_visited: true },
+        ]);
+        grid.make_move(Direction::Left).unwrap();
+        assert_eq!(grid.drain_events(), vec![
+            GridEvent::ActiveMoved { from: at(1, 0), to: at(0, 0) },
+            GridEvent::VisitedChanged { coords: at(1, 0), is_visited: false },
+        ]);
+        grid.undo().unwrap();
+        assert_eq!(grid.drain_events(), vec![
+            GridEvent::ActiveMoved { from: at(0, 0), to: at(1, 0) },
+            GridEvent::VisitedChanged { coords: at(1, 0), is_visited: true },
+        ]);
+
+        grid.unlink(at(1, 0), at(2, 0)).unwrap();
+        assert_eq!(grid.drain_events(), vec![GridEvent::CellUnlinked { a: at(1, 0), b: at(2, 0) }]);
+        assert!(grid.drain_events().is_empty());
+    }
+
+    #[test]
+    fn test_inverted_delta_orientation() {
+        let json = r#"{ "maze_type": "Delta", "width": 9, "height": 6, "algorithm": "RecursiveBacktracker", "delta_orientation": "Inverted" }"#;
+        let grid = Grid::try_from(json).unwrap();
+        assert!(grid.is_perfect_maze().unwrap());
+        assert_eq!(grid.delta_orientation, CellOrientation::Inverted);
+        for cell in grid.iter_cells() {
+            let upright = (cell.coords.x + cell.coords.y) % 2 == 1;
+            assert_eq!(cell.orientation == CellOrientation::Normal, upright, "{}", cell.coords);
+            // inverted triangles lead up, upright triangles lead down
+            let vertical = if upright { Direction::Down } else { Direction::Up };
+            assert!(cell.neighbors_by_direction.keys().all(|direction| direction != vertical.opposite()));
         }
+        assert!(!grid.get(Coordinates { x: 0, y: 0 }).unwrap().neighbors_by_direction.contains_key(&Direction::Up));
+
+        let restored = Grid::from_saved_json(&grid.to_json().unwrap()).unwrap();
+        assert_eq!(restored.delta_orientation, CellOrientation::Inverted);
+        assert!(restored.is_perfect_maze().unwrap());
+        let decoded = Grid::from_bytes(&grid.to_bytes().unwrap()).unwrap();
+        assert!(decoded.iter_cells().zip(grid.iter_cells()).all(|(a, b)| a.orientation == b.orientation && a.linked == b.linked));
+        let cropped = grid.crop(Rect { x: 2, y: 2, width: 4, height: 3 }).unwrap();
+        assert_eq!(cropped.get(Coordinates { x: 0, y: 0 }).unwrap().orientation, CellOrientation::Inverted);
+
+        let orthogonal = r#"{ "maze_type": "Orthogonal", "width": 4, "height": 4, "algorithm": "Prims", "delta_orientation": "Inverted" }"#;
+        assert!(matches!(Grid::try_from(orthogonal), Err(Error::InvalidCellForNonDeltaMaze { .. })));
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_triangle_delta_shape() {
+        let algorithms = MazeAlgorithm::all().iter().filter(|algorithm| algorithm.supports(MazeType::Rhombic) && algorithm.supports(MazeType::Delta));
+        for (&algorithm, orientation) in algorithms.flat_map(|algorithm| [(algorithm, CellOrientation::Normal), (algorithm, CellOrientation::Inverted)]) {
+            let request = MazeRequest::builder()
+                .maze_type(MazeType::Delta)
+                .size(11, 6)
+                .algorithm(algorithm)
+                .delta_orientation(orientation)
+                .delta_shape(DeltaShape::Triangle)
+                .build()
+                .unwrap();
+            let grid = Grid::try_from(request).unwrap();
+            assert!(grid.is_perfect_maze().unwrap(), "{:?} {:?}", algorithm, orientation);
+            // rows widen by a triangle on each side, 1, 3, 5 ... 11 triangles
+            assert_eq!(grid.iter_cells().count(), 36);
+            assert!(grid.iter_cells().all(|cell| cell.neighbors().iter().all(|n| grid.has_cell(n.x, n.y))));
+            let apex = if orientation == CellOrientation::Normal { Coordinates { x: 5, y: 0 } } else { Coordinates { x: 5, y: 5 } };
+            assert_eq!(grid.get(apex).unwrap().orientation, orientation);
+            assert_eq!(grid.goal_coords, apex);
+            assert!(grid.get(Coordinates { x: 0, y: 0 }).is_ok() == (orientation == CellOrientation::Inverted));
+            assert!(grid.solution_path_order().contains(&apex));
+
+            let restored = Grid::from_saved_json(&grid.to_json().unwrap()).unwrap();
+            assert_eq!(restored.delta_shape, DeltaShape::Triangle);
+            assert_eq!(restored.iter_cells().count(), 36);
+            assert!(restored.is_perfect_maze().unwrap());
+            let compact = Grid::try_from(&CompactGrid::from(&grid)).unwrap();
+            assert_eq!(compact.delta_shape, DeltaShape::Triangle);
+            assert!(compact.iter_cells().zip(grid.iter_cells()).all(|(a, b)| a.orientation == b.orientation && a.linked == b.linked));
+        }
+    }
+
+    #[test]
+    fn test_triangle_delta_shape_rejections() {
+        let triangle = |width: usize, algorithm: &str, extra: &str| {
+            let json = format!(r#"{{ "maze_type": "Delta", "width": {}, "height": 6, "algorithm": "{}", "delta_shape": "Triangle"{} }}"#, width, algorithm, extra);
+            Grid::try_from(json.as_str())
+        };
+        assert!(matches!(triangle(9, "Kruskals", ""), Err(Error::InvalidDeltaShape { .. })));
+        // Hunt and Kill visits every position, so cannot skip those outside the triangle
+        assert!(matches!(triangle(11, "HuntAndKill", ""), Err(Error::InvalidDeltaShape { .. })));
+        assert!(matches!(
+            triangle(11, "Kruskals", r#", "start": { "x": 0, "y": 0 }, "goal": { "x": 5, "y": 0 }"#),
+            Err(Error::InvalidStartCoordinates { .. })
+        ));
+        let orthogonal = r#"{ "maze_type": "Orthogonal", "width": 11, "height": 6, "algorithm": "Kruskals", "delta_shape": "Triangle" }"#;
+        assert!(matches!(Grid::try_from(orthogonal), Err(Error::InvalidCellForNonDeltaMaze { .. })));
+
+        let grid = triangle(11, "Kruskals", "").unwrap();
+        assert!(matches!(grid.to_bytes(), Err(Error::InvalidBinaryFormat { .. })));
+        assert!(matches!(grid.crop(Rect { x: 0, y: 0, width: 4, height: 4 }), Err(Error::InvalidCropRegion { .. })));
+    }
+}
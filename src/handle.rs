@@ -0,0 +1,152 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::error::Error;
+use crate::grid::Grid;
+
+/// An integer standing in for a `Grid` owned by a `HandleRegistry`. The low 32 bits index the registry's
+/// slot holding the grid and the high 32 bits hold the slot's generation when the handle was issued, so a
+/// handle to a destroyed grid is detected even after its slot is reused. 0 is never a valid handle.
+pub type Handle = u64;
+
+/// A registered grid, locked by whichever thread is operating on it.
+pub type SharedGrid = Arc<Mutex<Grid>>;
+
+struct Slot {
+    // incremented each time the slot's grid is removed, invalidating handles issued for it
+    generation: u32,
+    grid: Option<SharedGrid>,
+}
+
+/// Owns grids on behalf of FFI callers, who refer to them by `Handle` rather than by raw pointer, so that
+/// using a destroyed grid or destroying it twice reports an error instead of crashing the host app.
+///
+/// Each grid sits behind its own lock, so handles may be used from any thread: calls on the same grid are
+/// serialized, while calls on different grids proceed in parallel. A grid destroyed while another thread is
+/// operating on it is dropped once that operation completes.
+#[derive(Default)]
+pub struct HandleRegistry {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+}
+
+impl HandleRegistry {
+    pub const fn new() -> Self {
+        HandleRegistry { slots: Vec::new(), free: Vec::new() }
+    }
+
+    /// Take ownership of a grid, returning the handle to refer to it by.
+    pub fn insert(&mut self, grid: Grid) -> Handle {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.slots.push(Slot { generation: 1, grid: None });
+                (self.slots.len() - 1) as u32
+            }
+        };
+        let slot = &mut self.slots[index as usize];
+        slot.grid = Some(Arc::new(Mutex::new(grid)));
+        ((slot.generation as u64) << 32) | (index as u64 + 1)
+    }
+
+    /// The grid a handle refers to.
+    pub fn get(&self, handle: Handle) -> Result<SharedGrid, Error> {
+        let index = self.slot_index(handle)?;
+        self.slots[index].grid.clone().ok_or(Error::StaleHandle { handle })
+    }
+
+    /// Release ownership of a grid, invalidating its handle.
+    pub fn remove(&mut self, handle: Handle) -> Result<SharedGrid, Error> {
+        let index = self.slot_index(handle)?;
+        let slot = &mut self.slots[index];
+        let grid = slot.grid.take().ok_or(Error::StaleHandle { handle })?;
+        slot.generation = slot.generation.wrapping_add(1).max(1);
+        self.free.push(index as u32);
+        Ok(grid)
+    }
+
+    // Index of the slot a handle refers to, failing for handles never issued or issued for a removed grid
+    fn slot_index(&self, handle: Handle) -> Result<usize, Error> {
+        let generation = (handle >> 32) as u32;
+        let index = (handle & u32::MAX as u64) as usize;
+        if index == 0 || index > self.slots.len() || generation == 0 {
+            return Err(Error::InvalidHandle { handle });
+        }
+        let slot = &self.slots[index - 1];
+        if generation > slot.generation {
+            return Err(Error::InvalidHandle { handle });
+        }
+        if generation < slot.generation {
+            return Err(Error::StaleHandle { handle });
+        }
+        Ok(index - 1)
+    }
+}
+
+static REGISTRY: Mutex<HandleRegistry> = Mutex::new(HandleRegistry::new());
+
+/// The process-wide registry backing the handle-based FFI functions. Hold it only long enough to look up,
+/// insert or remove a grid; operate on grids with `with_grid`, which releases the registry first.
+pub fn registry() -> MutexGuard<'static, HandleRegistry> {
+    // a panic while the registry was held leaves it consistent, as every update is a single assignment
+    REGISTRY.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Run an operation on the grid a handle refers to, holding the grid's lock (but not the registry's) for its
+/// duration.
+pub fn with_grid<R>(handle: Handle, operation: impl FnOnce(&mut Grid) -> R) -> Result<R, Error> {
+    let grid = registry().get(handle)?;
+    let mut guard = grid.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    Ok(operation(&mut guard))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::{Coordinates, MazeType};
+
+    fn grid() -> Grid {
+        Grid::new(MazeType::Orthogonal, 3, 3, Coordinates { x: 0, y: 0 }, Coordinates { x: 2, y: 2 }, false).unwrap()
+    }
+
+    #[test]
+    fn stale_and_unknown_handles_are_rejected() {
+        let mut registry = HandleRegistry::new();
+        let handle = registry.insert(grid());
+        assert_ne!(handle, 0);
+        assert_eq!(registry.get(handle).unwrap().lock().unwrap().width, 3);
+        registry.get(handle).unwrap().lock().unwrap().seed = 7;
+
+        assert_eq!(registry.remove(handle).unwrap().lock().unwrap().seed, 7);
+        assert!(matches!(registry.get(handle), Err(Error::StaleHandle { .. })));
+        assert!(matches!(registry.remove(handle), Err(Error::StaleHandle { .. })));
+
+        // the slot is reused, but the old handle stays stale
+        let reused = registry.insert(grid());
+        assert_ne!(reused, handle);
+        assert!(registry.get(reused).is_ok());
+        assert!(matches!(registry.get(handle), Err(Error::StaleHandle { .. })));
+
+        assert!(matches!(registry.get(0), Err(Error::InvalidHandle { .. })));
+        assert!(matches!(registry.get(reused + 1), Err(Error::InvalidHandle { .. })));
+        assert!(matches!(registry.get(reused + (1 << 32)), Err(Error::InvalidHandle { .. })));
+    }
+
+    #[test]
+    fn grids_are_shared_across_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Grid>();
+        assert_send_sync::<SharedGrid>();
+
+        let handle = registry().insert(grid());
+        let initial_seed = with_grid(handle, |grid| grid.seed).unwrap();
+        let workers: Vec<_> = (0..8)
+            .map(|_| std::thread::spawn(move || with_grid(handle, |grid| grid.seed += 1).unwrap()))
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert_eq!(with_grid(handle, |grid| grid.seed).unwrap(), initial_seed + 8);
+        registry().remove(handle).unwrap();
+        assert!(matches!(with_grid(handle, |_| ()), Err(Error::StaleHandle { .. })));
+    }
+}
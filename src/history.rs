@@ -0,0 +1,68 @@
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::cell::Coordinates;
+use crate::direction::Direction;
+use crate::prelude::*;
+
+/// A single user move through the maze, as recorded by `Grid::make_move`.
+///
+/// Records keep enough detail to replay the exact sequence of moves taken, and to reverse each move
+/// with `Grid::undo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MoveRecord {
+    /// Coordinates of the cell moved from.
+    pub from: Coordinates,
+    /// Coordinates of the cell moved to.
+    pub to: Coordinates,
+    /// The direction requested by the user.
+    pub requested: Direction,
+    /// The direction actually taken, which differs from `requested` when the move fell back to an adjacent direction.
+    pub direction: Direction,
+    /// Whether the move retraced the path back into an already visited cell.
+    pub backtrack: bool,
+    /// Whether the destination cell had been visited at any point before this move.
+    pub to_had_been_visited: bool,
+}
+
+/// A recording of the user's path through a maze, with the time of each move, so that a later attempt can
+/// race against the earlier run's "ghost". Recorded while a session runs (see `Grid::start_session`) and
+/// played back with `Grid::replay`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Replay {
+    /// The cell the recording began on.
+    pub start: Coordinates,
+    /// The cell moved to at each point of the recording, in the order the moves were made.
+    pub moves: Vec<ReplayMove>,
+}
+
+/// A move within a `Replay`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReplayMove {
+    /// Milliseconds of session time, excluding time spent paused, from the start of the recording to the move.
+    pub tick: u64,
+    /// The cell moved to, including by undoing a move.
+    pub to: Coordinates,
+}
+
+impl Replay {
+    /// An empty recording beginning on the specified cell.
+    pub fn new(start: Coordinates) -> Self {
+        Replay { start, moves: Vec::new() }
+    }
+
+    /// The cell the recorded user was on at the specified tick.
+    pub fn position_at(&self, tick: u64) -> Coordinates {
+        // moves are recorded in order, so their ticks never decrease
+        let made = self.moves.partition_point(|recorded| recorded.tick <= tick);
+        made.checked_sub(1).map(|last| self.moves[last].to).unwrap_or(self.start)
+    }
+
+    /// The tick of the last move, after which the recorded user stays put.
+    pub fn duration(&self) -> u64 {
+        self.moves.last().map(|last| last.tick).unwrap_or(0)
+    }
+}
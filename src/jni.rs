@@ -0,0 +1,200 @@
+//! JNI bindings for Android and other JVM clients, exposing the operations of the C FFI as the native methods
+//! of a `com.mazer.Mazer` class (see `include/java/com/mazer/Mazer.java`), so apps need not wrap the C ABI
+//! themselves.
+//!
+//! A maze is held as an opaque `long` handle, 0 standing for no maze, which must be released with `destroy`.
+//! Requests, saved mazes and cells are passed as JSON strings, and the compact binary format as byte arrays.
+//! Requests and saved mazes which cannot be turned into a maze throw an `IllegalArgumentException` carrying
+//! the reason.
+
+use ::jni::objects::{JByteArray, JClass, JString};
+use ::jni::sys::{jboolean, jbyteArray, jint, jlong, jstring, JNI_FALSE, JNI_TRUE};
+use ::jni::JNIEnv;
+
+use crate::analysis::MazeReport;
+use crate::behaviors::display::JsonDisplay;
+use crate::direction::Direction;
+use crate::ffi::cells_json;
+use crate::grid::Grid;
+
+// Move a maze to the heap, returning its handle
+fn into_handle(grid: Grid) -> jlong {
+    Box::into_raw(Box::new(grid)) as jlong
+}
+
+// The maze behind a handle, or None for the 0 handle
+fn grid_mut<'a>(handle: jlong) -> Option<&'a mut Grid> {
+    if handle == 0 {
+        return None;
+    }
+    Some(unsafe { &mut *(handle as *mut Grid) })
+}
+
+// The handle of a maze, or 0 after throwing an IllegalArgumentException with the reason it is missing
+fn handle_or_throw(env: &mut JNIEnv, grid: Result<Grid, String>) -> jlong {
+    match grid {
+        Ok(grid) => into_handle(grid),
+        Err(reason) => {
+            let _ = env.throw_new("java/lang/IllegalArgumentException", reason);
+            0
+        }
+    }
+}
+
+// A Java string, or null if there is no string or it cannot be created
+fn to_jstring(env: &mut JNIEnv, string: Option<String>) -> jstring {
+    string.and_then(|string| env.new_string(string).ok()).map_or(std::ptr::null_mut(), |string| string.into_raw())
+}
+
+// The contents of a Java string, or None if it is null or cannot be read
+fn from_jstring(env: &mut JNIEnv, string: &JString) -> Option<String> {
+    if string.is_null() {
+        return None;
+    }
+    env.get_string(string).ok().map(String::from)
+}
+
+fn to_jboolean(value: bool) -> jboolean {
+    if value { JNI_TRUE } else { JNI_FALSE }
+}
+
+/// `static native long generateMaze(String requestJson)`: generates a maze from a JSON request, throwing an
+/// `IllegalArgumentException` if the request is invalid.
+#[no_mangle]
+pub extern "system" fn Java_com_mazer_Mazer_generateMaze<'local>(mut env: JNIEnv<'local>, _class: JClass<'local>, request_json: JString<'local>) -> jlong {
+    let grid = match from_jstring(&mut env, &request_json) {
+        Some(json) => Grid::try_from(json.as_str()).map_err(|err| err.to_string()),
+        None => Err("the request is null or unreadable".to_string()),
+    };
+    handle_or_throw(&mut env, grid)
+}
+
+/// `static native void destroy(long maze)`: releases a maze. Does nothing for the 0 handle.
+#[no_mangle]
+pub extern "system" fn Java_com_mazer_Mazer_destroy<'local>(_env: JNIEnv<'local>, _class: JClass<'local>, maze: jlong) {
+    if maze != 0 {
+        drop(unsafe { Box::from_raw(maze as *mut Grid) });
+    }
+}
+
+/// `static native String serializeMaze(long maze)`: the maze, including its links, visited state and move
+/// history, as JSON for `deserializeMaze`, or null if serialization fails.
+#[no_mangle]
+pub extern "system" fn Java_com_mazer_Mazer_serializeMaze<'local>(mut env: JNIEnv<'local>, _class: JClass<'local>, maze: jlong) -> jstring {
+    let json = grid_mut(maze).and_then(|grid| grid.to_json().ok());
+    to_jstring(&mut env, json)
+}
+
+/// `static native long deserializeMaze(String savedJson)`: restores a maze saved by `serializeMaze`, throwing
+/// an `IllegalArgumentException` if it cannot be restored.
+#[no_mangle]
+pub extern "system" fn Java_com_mazer_Mazer_deserializeMaze<'local>(mut env: JNIEnv<'local>, _class: JClass<'local>, saved_json: JString<'local>) -> jlong {
+    let grid = match from_jstring(&mut env, &saved_json) {
+        Some(json) => Grid::from_saved_json(&json).map_err(|err| err.to_string()),
+        None => Err("the saved maze is null or unreadable".to_string()),
+    };
+    handle_or_throw(&mut env, grid)
+}
+
+/// `static native byte[] toBytes(long maze)`: the maze in the compact binary format, or null if it cannot be
+/// encoded.
+#[no_mangle]
+pub extern "system" fn Java_com_mazer_Mazer_toBytes<'local>(env: JNIEnv<'local>, _class: JClass<'local>, maze: jlong) -> jbyteArray {
+    grid_mut(maze)
+        .and_then(|grid| grid.to_bytes().ok())
+        .and_then(|bytes| env.byte_array_from_slice(&bytes).ok())
+        .map_or(std::ptr::null_mut(), |array| array.into_raw())
+}
+
+/// `static native long fromBytes(byte[] bytes)`: decodes a maze encoded by `toBytes`, throwing an
+/// `IllegalArgumentException` if the bytes are not a valid encoding.
+#[no_mangle]
+pub extern "system" fn Java_com_mazer_Mazer_fromBytes<'local>(mut env: JNIEnv<'local>, _class: JClass<'local>, bytes: JByteArray<'local>) -> jlong {
+    let grid = match env.convert_byte_array(&bytes) {
+        Ok(bytes) => Grid::from_bytes(&bytes).map_err(|err| err.to_string()),
+        Err(_) => Err("the bytes are null or unreadable".to_string()),
+    };
+    handle_or_throw(&mut env, grid)
+}
+
+/// `static native String getCellsJson(long maze)`: the maze's cells as JSON, in the form of the C FFI's
+/// `mazer_get_cells_json`, or null for the 0 handle.
+#[no_mangle]
+pub extern "system" fn Java_com_mazer_Mazer_getCellsJson<'local>(mut env: JNIEnv<'local>, _class: JClass<'local>, maze: jlong) -> jstring {
+    let json = grid_mut(maze).and_then(|grid| cells_json(grid));
+    to_jstring(&mut env, json)
+}
+
+/// `static native int getGenerationStepsCount(long maze)`: the number of generation steps captured, or 0 if
+/// `capture_steps` was not requested.
+#[no_mangle]
+pub extern "system" fn Java_com_mazer_Mazer_getGenerationStepsCount<'local>(_env: JNIEnv<'local>, _class: JClass<'local>, maze: jlong) -> jint {
+    grid_mut(maze).and_then(|grid| grid.generation_steps.as_ref()).map_or(0, |steps| steps.len() as jint)
+}
+
+/// `static native String getGenerationStepCellsJson(long maze, int stepIndex)`: the cells of a generation step
+/// as JSON, or null if the step index is out of range.
+#[no_mangle]
+pub extern "system" fn Java_com_mazer_Mazer_getGenerationStepCellsJson<'local>(mut env: JNIEnv<'local>, _class: JClass<'local>, maze: jlong, step_index: jint) -> jstring {
+    let json = grid_mut(maze)
+        .and_then(|grid| grid.generation_steps.as_ref())
+        .and_then(|steps| usize::try_from(step_index).ok().and_then(|index| steps.get(index)))
+        .and_then(|step| cells_json(&step));
+    to_jstring(&mut env, json)
+}
+
+/// `static native boolean makeMove(long maze, String direction)`: moves the active cell in the named direction
+/// (e.g. "Up"), returning false if the direction is not recognized or no open passage leads that way.
+#[no_mangle]
+pub extern "system" fn Java_com_mazer_Mazer_makeMove<'local>(mut env: JNIEnv<'local>, _class: JClass<'local>, maze: jlong, direction: JString<'local>) -> jboolean {
+    let direction = from_jstring(&mut env, &direction).and_then(|direction| Direction::try_from(direction.as_str()).ok());
+    let moved = match (grid_mut(maze), direction) {
+        (Some(grid), Some(direction)) => grid.make_move(direction).is_ok(),
+        _ => false,
+    };
+    to_jboolean(moved)
+}
+
+/// `static native int getHint(long maze)`: the numeric code of the next move toward the goal, as returned by
+/// the C FFI's `mazer_get_hint`, or -1 if no hint is available.
+#[no_mangle]
+pub extern "system" fn Java_com_mazer_Mazer_getHint<'local>(_env: JNIEnv<'local>, _class: JClass<'local>, maze: jlong) -> jint {
+    grid_mut(maze).and_then(|grid| grid.hint()).map_or(-1, |direction| direction as jint)
+}
+
+/// `static native boolean undo(long maze)`: reverses the most recent move, returning false if there is none.
+#[no_mangle]
+pub extern "system" fn Java_com_mazer_Mazer_undo<'local>(_env: JNIEnv<'local>, _class: JClass<'local>, maze: jlong) -> jboolean {
+    to_jboolean(grid_mut(maze).is_some_and(|grid| grid.undo().is_ok()))
+}
+
+/// `static native boolean redo(long maze)`: re-applies the most recently undone move, returning false if there
+/// is none.
+#[no_mangle]
+pub extern "system" fn Java_com_mazer_Mazer_redo<'local>(_env: JNIEnv<'local>, _class: JClass<'local>, maze: jlong) -> jboolean {
+    to_jboolean(grid_mut(maze).is_some_and(|grid| grid.redo().is_ok()))
+}
+
+/// `static native String getStatsJson(long maze)`: the maze's report as JSON, in the form of the C FFI's
+/// `mazer_get_stats_json`, or null for the 0 handle.
+#[no_mangle]
+pub extern "system" fn Java_com_mazer_Mazer_getStatsJson<'local>(mut env: JNIEnv<'local>, _class: JClass<'local>, maze: jlong) -> jstring {
+    let json = grid_mut(maze).and_then(|grid| serde_json::to_string(&MazeReport::from_grid(grid)).ok());
+    to_jstring(&mut env, json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handles_round_trip() {
+        let grid = Grid::try_from(r#"{ "maze_type": "Orthogonal", "width": 4, "height": 4, "algorithm": "Prims" }"#).unwrap();
+        let edges = grid.count_edges();
+        let handle = into_handle(grid);
+        assert_ne!(handle, 0);
+        assert_eq!(grid_mut(handle).map(|grid| grid.count_edges()), Some(edges));
+        assert!(grid_mut(0).is_none());
+        drop(unsafe { Box::from_raw(handle as *mut Grid) });
+    }
+}
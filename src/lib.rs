@@ -1,17 +1,64 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 use crate::grid::Grid;
 use crate::error::Error;
 
+#[cfg(feature = "json")]
 #[allow(unused_imports)]
 pub use crate::ffi::*;
 
+mod prelude;
+mod trace;
 pub mod cell;
+pub mod compact;
+pub mod constraints;
 pub mod grid;
+#[cfg(feature = "std")]
+pub mod handle;
+pub mod diff;
+pub mod difficulty;
 pub mod direction;
+pub mod history;
+pub mod mask;
+pub mod overlay;
+pub mod regions;
+pub mod rooms;
+pub mod player;
+pub mod playback;
+pub mod render;
+pub mod puzzle;
 pub mod request;
+pub mod rng;
+#[cfg(feature = "std")]
+pub mod session;
+pub mod solver;
+pub mod steps;
+pub mod symmetry;
 pub mod algorithms;
+#[cfg(feature = "benchmark")]
+pub mod benchmark;
+#[cfg(feature = "std")]
+pub mod book;
+pub mod accessibility;
+pub mod analysis;
 pub mod behaviors;
+pub mod editor;
 pub mod error;
+pub mod events;
+pub mod export;
+pub mod features;
+pub mod morph;
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+pub mod generation;
+#[cfg(feature = "json")]
 pub mod ffi;
+#[cfg(feature = "jni")]
+pub mod jni;
+#[cfg(feature = "server")]
+pub mod server;
 
 // algorithms: BinaryTree, Sidewinder, AldousBroder, HuntAndKill, RecursiveBacktracker
 // maze_types: Orthogonal, Delta, Hex
@@ -25,6 +72,7 @@ pub mod ffi;
 //     "goal": { "x": 11, "y": 11 }
 // }
 
+#[cfg(feature = "json")]
 pub fn generate(request_json: &str) -> Result<Grid, Error> {
     return Grid::try_from(request_json);
 }
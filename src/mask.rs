@@ -0,0 +1,179 @@
+use alloc::collections::BTreeMap;
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::algorithms::kruskals::{self, DisjointSet};
+use crate::cell::Coordinates;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::rng::GridRng;
+use crate::prelude::*;
+
+// Rounds of smoothing which turn the initial noise into caves
+const SMOOTHING_ROUNDS: usize = 4;
+
+/// A generator of a maze's outline, choosing which positions keep their cells and which are left without
+/// (see `Grid::set_mask`), e.g. `{ "type": "CaveNoise", "fill": 0.55, "seed": 3 }`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+pub enum Mask {
+    /// An organic, cave-like outline grown by a cellular automaton. Each position starts open with probability
+    /// `fill`, then over several rounds becomes open where at least 5 of the 9 positions around and including it
+    /// are open, and closed otherwise. Only the largest cave is kept, so that every cell is reachable.
+    CaveNoise { fill: f64, seed: u64 },
+}
+
+impl Mask {
+    /// Check that the mask can be generated: a cave's fill must be above 0 and at most 1.
+    pub fn validate(&self) -> Result<(), Error> {
+        match *self {
+            Mask::CaveNoise { fill, .. } if !(fill > 0.0 && fill <= 1.0) => {
+                Err(Error::InvalidMask { reason: format!("a cave's fill must be above 0 and at most 1, not {}", fill) })
+            }
+            Mask::CaveNoise { .. } => Ok(()),
+        }
+    }
+
+    /// The positions of the grid the mask keeps, in row-major order, all joined through the grid's neighbors.
+    pub fn generate(&self, grid: &Grid) -> Result<Vec<bool>, Error> {
+        self.validate()?;
+        let Mask::CaveNoise { fill, seed } = *self;
+        let (width, height) = (grid.width, grid.height);
+        let mut rng = GridRng::seed_from_u64(seed);
+        let mut open: Vec<bool> = (0..width * height).map(|_| rng.gen_bool(fill)).collect();
+        for _ in 0..SMOOTHING_ROUNDS {
+            // positions beyond the edges count as closed, so caves pull away from the maze's border
+            let open_around = |x: usize, y: usize| {
+                (y.saturating_sub(1)..=(y + 1).min(height - 1))
+                    .flat_map(|ny| (x.saturating_sub(1)..=(x + 1).min(width - 1)).map(move |nx| (nx, ny)))
+                    .filter(|&(nx, ny)| open[ny * width + nx])
+                    .count()
+            };
+            open = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).map(|(x, y)| open_around(x, y) >= 5).collect();
+        }
+
+        let caves = regions(grid, |coords| open[grid.get_flattened_index(coords.x, coords.y)]);
+        let Some(cave) = caves.first().filter(|cave| cave.len() >= 2) else {
+            return Err(Error::InvalidMask { reason: format!("a fill of {} leaves no cave of at least 2 cells", fill) });
+        };
+        let mut keep = vec![false; width * height];
+        for coords in cave {
+            keep[grid.get_flattened_index(coords.x, coords.y)] = true;
+        }
+        Ok(keep)
+    }
+}
+
+/// The groups of the grid's cells which `keep` accepts and which are joined through the cells' neighbors, largest
+/// first, each sorted by coordinates.
+pub(crate) fn regions(grid: &Grid, keep: impl Fn(Coordinates) -> bool) -> Vec<Vec<Coordinates>> {
+    let mut sets = DisjointSet::new();
+    let kept: Vec<Coordinates> = grid.iter_cells().map(|cell| cell.coords).filter(|&coords| keep(coords)).collect();
+    for &coords in &kept {
+        sets.make_set(coords);
+    }
+    for (a, b) in kruskals::edges(grid) {
+        if keep(a) && keep(b) {
+            sets.union(a, b);
+        }
+    }
+    let mut groups: BTreeMap<Coordinates, Vec<Coordinates>> = BTreeMap::new();
+    for coords in kept {
+        let root = sets.find(coords).unwrap_or(coords);
+        groups.entry(root).or_default().push(coords);
+    }
+    let mut groups: Vec<Vec<Coordinates>> = groups.into_values().collect();
+    for group in &mut groups {
+        group.sort();
+    }
+    groups.sort_by_key(|group| core::cmp::Reverse(group.len()));
+    groups
+}
+
+/// The cell the mask keeps which lies nearest the position, other than `except`, preferring the first in
+/// row-major order among equally near cells.
+pub(crate) fn nearest(grid: &Grid, keep: &[bool], position: Coordinates, except: Option<Coordinates>) -> Option<Coordinates> {
+    grid.iter_cells()
+        .map(|cell| cell.coords)
+        .filter(|&coords| keep[grid.get_flattened_index(coords.x, coords.y)] && Some(coords) != except)
+        .min_by_key(|coords| coords.x.abs_diff(position.x).pow(2) + coords.y.abs_diff(position.y).pow(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::MazeAlgorithm;
+    use crate::cell::MazeType;
+    use crate::behaviors::display::JsonDisplay;
+    use crate::compact::CompactGrid;
+
+    fn cave(maze_type: MazeType, algorithm: &str) -> Result<Grid, Error> {
+        let json = format!(r#"
+        {{
+            "maze_type": "{:?}",
+            "width": 24,
+            "height": 20,
+            "algorithm": "{}",
+            "mask": {{ "type": "CaveNoise", "fill": 0.55, "seed": 3 }}
+        }}
+        "#, maze_type, algorithm);
+        Grid::try_from(json.as_str())
+    }
+
+    #[test]
+    fn cave_masks_leave_organic_perfect_mazes() {
+        for maze_type in MazeType::all() {
+            for algorithm in MazeAlgorithm::all().iter().filter(|algorithm| algorithm.supports(MazeType::Rhombic) && algorithm.supports(*maze_type)) {
+                let grid = cave(*maze_type, algorithm.name()).unwrap();
+                let mask = grid.mask.as_ref().unwrap();
+                let kept = mask.iter().filter(|&&kept| kept).count();
+                assert!(kept > 40 && kept < 24 * 20, "{:?} kept {} positions", maze_type, kept);
+                assert_eq!(grid.iter_cells().count(), (0..20).flat_map(|y| (0..24).map(move |x| (x, y))).filter(|&(x, y)| grid.has_cell(x, y)).count());
+                assert!(grid.iter_cells().all(|cell| cell.neighbors().iter().all(|n| grid.has_cell(n.x, n.y))));
+                assert!(grid.is_perfect_maze().unwrap(), "{:?} {:?}", maze_type, algorithm);
+                assert!(grid.has_cell(grid.start_coords.x, grid.start_coords.y) && grid.has_cell(grid.goal_coords.x, grid.goal_coords.y));
+                assert_ne!(grid.start_coords, grid.goal_coords);
+
+                let restored = Grid::from_saved_json(&grid.to_json().unwrap()).unwrap();
+                assert_eq!(restored.mask, grid.mask);
+                assert!(restored.is_perfect_maze().unwrap());
+                let compact = Grid::try_from(&CompactGrid::from(&grid)).unwrap();
+                assert!((0..20).all(|y| (0..24).all(|x| compact.has_cell(x, y) == grid.has_cell(x, y))));
+                assert!(compact.iter_cells().zip(grid.iter_cells()).all(|(a, b)| a.orientation == b.orientation && a.linked == b.linked), "{:?} {:?}", maze_type, algorithm);
+            }
+        }
+        // the same seed grows the same cave
+        assert_eq!(cave(MazeType::Orthogonal, "Kruskals").unwrap().mask, cave(MazeType::Orthogonal, "Prims").unwrap().mask);
+    }
+
+    #[test]
+    fn masks_need_algorithms_which_skip_missing_cells() {
+        assert!(matches!(cave(MazeType::Orthogonal, "BinaryTree"), Err(Error::InvalidMask { .. })));
+        assert!(matches!(cave(MazeType::Orthogonal, "HuntAndKill"), Err(Error::InvalidMask { .. })));
+        let empty = r#"{ "maze_type": "Orthogonal", "width": 8, "height": 8, "algorithm": "Kruskals", "mask": { "type": "CaveNoise", "fill": 0.0, "seed": 3 } }"#;
+        assert!(matches!(Grid::try_from(empty), Err(Error::InvalidMask { .. })));
+        let mirrored = r#"{ "maze_type": "Orthogonal", "width": 8, "height": 8, "algorithm": "Kruskals", "symmetry": "Horizontal", "mask": { "type": "CaveNoise", "fill": 0.55, "seed": 3 } }"#;
+        assert!(matches!(Grid::try_from(mirrored), Err(Error::InvalidMask { .. })));
+    }
+
+    #[test]
+    fn masks_must_keep_one_connected_region() {
+        let mut grid = Grid::new(MazeType::Orthogonal, 4, 3, Coordinates { x: 0, y: 0 }, Coordinates { x: 3, y: 0 }, false).unwrap();
+        // the middle column splits the maze in two
+        let split: Vec<bool> = (0..12).map(|index| index % 4 != 1).collect();
+        assert!(matches!(grid.set_mask(Some(split)), Err(Error::InvalidMask { .. })));
+        assert!(grid.mask.is_none());
+        assert_eq!(grid.iter_cells().count(), 12);
+
+        let corner: Vec<bool> = (0..12).map(|index| index != 11).collect();
+        grid.set_mask(Some(corner)).unwrap();
+        assert!(!grid.has_cell(3, 2));
+        assert!(grid.get(Coordinates { x: 3, y: 1 }).unwrap().neighbors_by_direction.get(&crate::direction::Direction::Down).is_none());
+        let without_start: Vec<bool> = (0..12).map(|index| index != 0).collect();
+        assert!(matches!(grid.set_mask(Some(without_start)), Err(Error::InvalidStartCoordinates { .. })));
+        grid.set_mask(None).unwrap();
+        assert_eq!(grid.iter_cells().count(), 12);
+    }
+}
@@ -0,0 +1,198 @@
+use alloc::collections::{BTreeSet, VecDeque};
+use rand::seq::SliceRandom;
+
+use crate::cell::Coordinates;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::rng::GridRng;
+use crate::prelude::*;
+
+/// A single change to a maze's passages, one of a sequence produced by `Grid::morph_to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MorphStep {
+    /// Open a passage between two neighboring cells.
+    Link(Coordinates, Coordinates),
+    /// Close the passage between two neighboring cells.
+    Unlink(Coordinates, Coordinates),
+}
+
+impl MorphStep {
+    /// Apply the step to a grid.
+    pub fn apply(&self, grid: &mut Grid) -> Result<(), Error> {
+        match *self {
+            MorphStep::Link(a, b) => grid.link(a, b),
+            MorphStep::Unlink(a, b) => grid.unlink(a, b),
+        }
+    }
+}
+
+type Edge = (Coordinates, Coordinates);
+
+/// Steps transforming the passages of `from` into those of `to`, such that every cell reachable before a step
+/// is still reachable after it. Each passage only in `to` is opened, closing in turn a passage only in `from`
+/// from the loop it forms, and any passages only in `from` remaining at the end are closed.
+pub fn morph(from: &Grid, to: &Grid) -> Result<Vec<MorphStep>, Error> {
+    if from.maze_type != to.maze_type || from.width != to.width || from.height != to.height || from.layers != to.layers {
+        return Err(Error::InvalidMorph {
+            reason: format!(
+                "a {:?} maze of {}x{} cannot morph into a {:?} maze of {}x{}",
+                from.maze_type, from.width, from.height, to.maze_type, to.width, to.height
+            ),
+        });
+    }
+    let target = edges(to);
+    let mut current: HashMap<Coordinates, HashSet<Coordinates>> = from
+        .cells
+        .iter()
+        .flatten()
+        .map(|cell| (cell.coords, cell.linked.iter().collect()))
+        .collect();
+    let mut remaining: BTreeSet<Edge> = edges(from).difference(&target).copied().collect();
+    let mut steps = Vec::new();
+
+    for &(a, b) in target.difference(&edges(from)) {
+        // the passages joining a and b, if any, form a loop with the new passage; close one not in the target
+        let removable = path(a, b, |coords| current.get(&coords).into_iter().flatten().copied().collect()).and_then(|path| {
+            path.windows(2).map(|pair| ordered(pair[0], pair[1])).find(|edge| remaining.contains(edge))
+        });
+        steps.push(MorphStep::Link(a, b));
+        current.entry(a).or_default().insert(b);
+        current.entry(b).or_default().insert(a);
+        if let Some((c, d)) = removable {
+            steps.push(MorphStep::Unlink(c, d));
+            current.entry(c).or_default().remove(&d);
+            current.entry(d).or_default().remove(&c);
+            remaining.remove(&(c, d));
+        }
+    }
+    // every passage of the target is now open, so closing the rest leaves target-connected cells connected
+    steps.extend(remaining.into_iter().map(|(a, b)| MorphStep::Unlink(a, b)));
+    Ok(steps)
+}
+
+/// Open `count` randomly chosen walls, each followed by closing a random passage from the loop it forms, and
+/// return the changes made.
+pub fn shift_walls(grid: &mut Grid, count: usize, seed: u64) -> Result<Vec<MorphStep>, Error> {
+    let mut rng = GridRng::seed_from_u64(seed);
+    let mut steps = Vec::new();
+    for _ in 0..count {
+        let walls: Vec<Edge> = grid
+            .cells
+            .iter()
+            .flatten()
+            .flat_map(|cell| {
+                cell.neighbors().into_iter().filter(|neighbor| !cell.linked.contains(neighbor)).map(|neighbor| ordered(cell.coords, neighbor))
+            })
+            .collect::<BTreeSet<Edge>>()
+            .into_iter()
+            .collect();
+        let Some(&(a, b)) = walls.choose(&mut rng) else {
+            break;
+        };
+        // passages on the loop formed by opening the wall, excluding those with doors
+        let closable: Vec<Edge> = path(a, b, |coords| grid.get(coords).map(|cell| cell.linked.iter().collect()).unwrap_or_default())
+            .map(|path| {
+                path.windows(2)
+                    .map(|pair| ordered(pair[0], pair[1]))
+                    .filter(|&(c, d)| !grid.doors.iter().any(|door| door.joins(c, d)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let Some(&(c, d)) = closable.choose(&mut rng) else {
+            continue;
+        };
+        grid.link(a, b)?;
+        grid.unlink(c, d)?;
+        steps.push(MorphStep::Link(a, b));
+        steps.push(MorphStep::Unlink(c, d));
+    }
+    Ok(steps)
+}
+
+fn ordered(a: Coordinates, b: Coordinates) -> Edge {
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+// Every passage of the maze, each listed once, in a stable order
+fn edges(grid: &Grid) -> BTreeSet<Edge> {
+    grid.iter_linked_pairs().map(|(a, b)| (a.coords, b.coords)).collect()
+}
+
+// Cells of a path from one cell to another through open passages, inclusive, if there is one
+fn path(from: Coordinates, to: Coordinates, passages: impl Fn(Coordinates) -> Vec<Coordinates>) -> Option<Vec<Coordinates>> {
+    let mut previous: HashMap<Coordinates, Coordinates> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(coords) = queue.pop_front() {
+        if coords == to {
+            let mut path = vec![to];
+            while let Some(&step) = previous.get(path.last()?) {
+                path.push(step);
+            }
+            return Some(path);
+        }
+        for neighbor in passages(coords) {
+            if neighbor != from && !previous.contains_key(&neighbor) {
+                previous.insert(neighbor, coords);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::MazeType;
+
+    fn generated(maze_type: MazeType, algorithm: &str) -> Grid {
+        let json = format!(r#"{{ "maze_type": "{:?}", "width": 8, "height": 8, "algorithm": "{}" }}"#, maze_type, algorithm);
+        Grid::try_from(json).unwrap()
+    }
+
+    #[test]
+    fn morphing_stays_connected() {
+        for maze_type in [MazeType::Orthogonal, MazeType::Delta, MazeType::Sigma] {
+            let from = generated(maze_type, "RecursiveBacktracker");
+            let to = generated(maze_type, "Prims");
+            let total = from.iter_cells().count();
+            let mut grid = from.clone();
+            for step in from.morph_to(&to).unwrap() {
+                step.apply(&mut grid).unwrap();
+                assert_eq!(grid.all_connected_cells(grid.start_coords).len(), total);
+            }
+            assert_eq!(edges(&grid), edges(&to));
+        }
+    }
+
+    #[test]
+    fn mismatched_grids() {
+        let from = generated(MazeType::Orthogonal, "Prims");
+        let to = generated(MazeType::Sigma, "Prims");
+        assert!(matches!(from.morph_to(&to), Err(Error::InvalidMorph { .. })));
+        assert!(from.morph_to(&from).unwrap().is_empty());
+    }
+
+    #[test]
+    fn shifted_walls_keep_the_maze_connected() {
+        for maze_type in [MazeType::Orthogonal, MazeType::Delta, MazeType::Upsilon] {
+            let mut grid = generated(maze_type, "Kruskals");
+            let original = grid.clone();
+            let revision = grid.revision;
+            let steps = grid.shift_walls(5, 42).unwrap();
+            assert_eq!(steps.len(), 10);
+            assert!(grid.is_perfect_maze().unwrap());
+            assert_ne!(edges(&grid), edges(&original));
+            assert!(grid.solution_path_order().contains(&grid.goal_coords));
+
+            let changed: HashSet<Coordinates> = grid.changed_cells_since(revision).iter().map(|cell| cell.coords).collect();
+            for step in &steps {
+                let (MorphStep::Link(a, b) | MorphStep::Unlink(a, b)) = *step;
+                assert!(changed.contains(&a) && changed.contains(&b));
+            }
+
+            let mut again = original.clone();
+            assert_eq!(again.shift_walls(5, 42).unwrap(), steps);
+        }
+    }
+}
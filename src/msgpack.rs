@@ -0,0 +1,55 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::request::MazeRequest;
+
+/// Encode a value, such as a `MazeRequest`, `Grid` or report, as MessagePack. Fields are encoded by name, so
+/// payloads decode the same way their JSON counterparts do while being considerably smaller.
+pub fn to_msgpack<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    rmp_serde::to_vec_named(value).map_err(|e| Error::InvalidMessagePack { reason: e.to_string() })
+}
+
+/// Decode a value encoded with `to_msgpack`.
+pub fn from_msgpack<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+    rmp_serde::from_slice(bytes).map_err(|e| Error::InvalidMessagePack { reason: e.to_string() })
+}
+
+/// Generate the maze described by a MessagePack-encoded `MazeRequest`, the MessagePack counterpart of
+/// `mazer::generate`.
+pub fn generate(request: &[u8]) -> Result<Grid, Error> {
+    Grid::try_from(from_msgpack::<MazeRequest>(request)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::MazeType;
+    use crate::algorithms::MazeAlgorithm;
+
+    #[test]
+    fn request_and_maze_round_trip() {
+        let request = MazeRequest::builder()
+            .maze_type(MazeType::Sigma)
+            .size(12, 12)
+            .algorithm(MazeAlgorithm::Prims)
+            .open_exits(true)
+            .build()
+            .unwrap();
+        let encoded = to_msgpack(&request).unwrap();
+        assert_eq!(from_msgpack::<MazeRequest>(&encoded).unwrap(), request);
+
+        let grid = generate(&encoded).unwrap();
+        let saved = to_msgpack(&grid).unwrap();
+        let restored: Grid = from_msgpack(&saved).unwrap();
+        assert_eq!(restored.count_edges(), grid.count_edges());
+        assert_eq!((restored.start_coords, restored.goal_coords), (grid.start_coords, grid.goal_coords));
+        assert!(restored.iter_cells().zip(grid.iter_cells()).all(|(a, b)| a.linked == b.linked));
+    }
+
+    #[test]
+    fn invalid_data() {
+        assert!(matches!(from_msgpack::<MazeRequest>(&[0xc1]), Err(Error::InvalidMessagePack { .. })));
+    }
+}
@@ -0,0 +1,173 @@
+use alloc::collections::BTreeMap;
+use rand::seq::SliceRandom;
+use rand::RngCore;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::algorithms::kruskals::{self, DisjointSet};
+use crate::algorithms::MazeAlgorithm;
+use crate::cell::{Coordinates, MazeType};
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::prelude::*;
+
+/// A coarse maze of zones laid over the fine maze, giving it district structure: each zone is a square block
+/// of `factor` by `factor` cells, walls between zones of the coarse maze become unbroken barriers along the
+/// zones' shared edge, and each coarse passage becomes a single doorway between its zones. For example, a
+/// factor of 10 lays a 5x5 maze of zones over a 50x50 maze.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Overlay {
+    /// The width and height of each zone, in cells of the fine maze. Zones along the right and bottom edges are
+    /// cut short when the maze's dimensions are not a multiple of the factor.
+    pub factor: usize,
+    /// The algorithm generating the coarse maze of zones.
+    pub algorithm: MazeAlgorithm,
+}
+
+impl Overlay {
+    // The zone of the coarse maze containing a cell of the fine maze
+    fn zone(self, coords: Coordinates) -> Coordinates {
+        Coordinates { x: coords.x / self.factor, y: coords.y / self.factor }
+    }
+
+    /// Check that the overlay can be laid over a maze of the given type and dimensions: only single layer
+    /// Orthogonal mazes can be overlaid, the algorithm must generate Orthogonal mazes, and the maze must span at
+    /// least two zones.
+    pub fn validate(self, maze_type: MazeType, width: usize, height: usize, layers: usize) -> Result<(), Error> {
+        let invalid = |reason: String| Err(Error::InvalidOverlay { reason });
+        if maze_type != MazeType::Orthogonal || layers > 1 {
+            return invalid(format!("only single layer Orthogonal mazes can be overlaid, not {:?}", maze_type));
+        }
+        if !self.algorithm.supports(MazeType::Orthogonal) {
+            return invalid(format!("{} cannot generate the Orthogonal maze of zones", self.algorithm.name()));
+        }
+        if self.factor < 2 {
+            return invalid("zones must be at least 2 cells wide".to_string());
+        }
+        if width.div_ceil(self.factor) * height.div_ceil(self.factor) < 2 {
+            return invalid(format!("a factor of {} leaves a {}x{} maze a single zone", self.factor, width, height));
+        }
+        Ok(())
+    }
+
+    /// Rebuild a generated maze under the overlay. The maze's own passages within each zone are kept, and
+    /// joined by as few others as make each zone a single tree, before the zones are joined by one doorway for
+    /// each passage of the coarse maze, leaving the maze perfect.
+    pub fn apply(self, grid: &mut Grid) -> Result<(), Error> {
+        self.validate(grid.maze_type, grid.width, grid.height, grid.layers)?;
+        let mut rng = grid.forked_rng();
+        let (zones_wide, zones_high) = (grid.width.div_ceil(self.factor), grid.height.div_ceil(self.factor));
+        let mut zones = Grid::new(
+            MazeType::Orthogonal,
+            zones_wide,
+            zones_high,
+            Coordinates { x: 0, y: 0 },
+            Coordinates { x: zones_wide - 1, y: zones_high - 1 },
+            false,
+        )?;
+        zones.reseed(rng.next_u64());
+        self.algorithm.generate(&mut zones)?;
+
+        let edges = kruskals::edges(grid);
+        let within = |&(a, b): &(Coordinates, Coordinates)| self.zone(a) == self.zone(b);
+        let linked = |(a, b): &(Coordinates, Coordinates)| grid.get(*a).is_ok_and(|cell| cell.linked.contains(b));
+
+        // each zone's own passages first, then other edges within the zone to join them into one tree
+        let mut candidates: Vec<_> = edges.iter().copied().filter(|edge| within(edge) && linked(edge)).collect();
+        let mut others: Vec<_> = edges.iter().copied().filter(|edge| within(edge) && !linked(edge)).collect();
+        others.shuffle(&mut rng);
+        candidates.extend(others);
+        let mut tree = DisjointSet::new();
+        for cell in grid.iter_cells() {
+            tree.make_set(cell.coords);
+        }
+        candidates.retain(|&(a, b)| tree.union(a, b));
+
+        // then a doorway, chosen at random along the zones' shared edge, for each passage between zones
+        let mut doorways: BTreeMap<(Coordinates, Coordinates), Vec<(Coordinates, Coordinates)>> = BTreeMap::new();
+        for &(a, b) in edges.iter().filter(|edge| !within(edge)) {
+            let (from, to) = (self.zone(a), self.zone(b));
+            if zones.get(from)?.linked.contains(&to) {
+                doorways.entry((from.min(to), from.max(to))).or_default().push((a, b));
+            }
+        }
+        for choices in doorways.values() {
+            if let Some(&(a, b)) = choices.choose(&mut rng) {
+                candidates.push((a, b));
+            }
+        }
+
+        let passages: Vec<(Coordinates, Coordinates)> = edges.into_iter().filter(linked).collect();
+        for (a, b) in passages {
+            grid.unlink(a, b)?;
+        }
+        for (a, b) in candidates {
+            grid.link(a, b)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlaid_mazes_follow_the_zones() {
+        for (width, height) in [(12usize, 12usize), (13, 10)] {
+            let json = format!(
+                r#"{{ "maze_type": "Orthogonal", "width": {}, "height": {}, "algorithm": "RecursiveBacktracker", "overlay": {{ "factor": 4, "algorithm": "Kruskals" }} }}"#,
+                width, height,
+            );
+            let grid = Grid::try_from(json).unwrap();
+            assert!(grid.is_perfect_maze().unwrap(), "{}x{}", width, height);
+            assert!(grid.iter_cells().all(|cell| cell.on_solution_path == grid.solution_path_order().contains(&cell.coords)));
+
+            // exactly one doorway joins each pair of neighboring zones that are joined at all
+            let overlay = Overlay { factor: 4, algorithm: MazeAlgorithm::Kruskals };
+            let mut doorways: BTreeMap<(Coordinates, Coordinates), usize> = BTreeMap::new();
+            for cell in grid.iter_cells() {
+                for linked in cell.linked.iter().filter(|&linked| cell.coords < linked) {
+                    let (a, b) = (overlay.zone(cell.coords), overlay.zone(linked));
+                    if a != b {
+                        *doorways.entry((a.min(b), a.max(b))).or_default() += 1;
+                    }
+                }
+            }
+            let zones = width.div_ceil(4) * height.div_ceil(4);
+            assert_eq!(doorways.len(), zones - 1, "{}x{}", width, height);
+            assert!(doorways.values().all(|&count| count == 1), "{}x{}", width, height);
+        }
+    }
+
+    #[test]
+    fn invalid_overlays_are_rejected() {
+        let overlay = |factor, algorithm| Overlay { factor, algorithm };
+        assert!(overlay(4, MazeAlgorithm::Kruskals).validate(MazeType::Orthogonal, 8, 8, 1).is_ok());
+        for (overlay, maze_type, layers) in [
+            (overlay(4, MazeAlgorithm::Kruskals), MazeType::Sigma, 1),
+            (overlay(4, MazeAlgorithm::Kruskals), MazeType::Orthogonal, 2),
+            (overlay(1, MazeAlgorithm::Kruskals), MazeType::Orthogonal, 1),
+            (overlay(8, MazeAlgorithm::Kruskals), MazeType::Orthogonal, 1),
+        ] {
+            assert!(matches!(overlay.validate(maze_type, 8, 8, layers), Err(Error::InvalidOverlay { .. })), "{:?}", overlay);
+        }
+        let json = r#"{ "maze_type": "Delta", "width": 8, "height": 8, "algorithm": "Prims", "overlay": { "factor": 4, "algorithm": "Prims" } }"#;
+        assert!(matches!(Grid::try_from(json), Err(Error::InvalidOverlay { .. })));
+    }
+
+    #[test]
+    fn overlays_cannot_be_combined_with_symmetry_or_constraints() {
+        let overlaid = r#""maze_type": "Orthogonal", "width": 12, "height": 12, "algorithm": "Prims", "overlay": { "factor": 4, "algorithm": "Kruskals" }"#;
+        let symmetric = format!(r#"{{ {}, "symmetry": "Horizontal" }}"#, overlaid);
+        assert!(matches!(Grid::try_from(symmetric), Err(Error::InvalidOverlay { .. })));
+
+        // two passages across the same zone edge would be two doorways between the zones
+        let constrained = format!(
+            r#"{{ {}, "constraints": {{ "passages": [[{{ "x": 3, "y": 0 }}, {{ "x": 4, "y": 0 }}], [{{ "x": 3, "y": 2 }}, {{ "x": 4, "y": 2 }}]] }} }}"#,
+            overlaid,
+        );
+        assert!(matches!(Grid::try_from(constrained), Err(Error::InvalidOverlay { .. })));
+    }
+}
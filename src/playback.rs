@@ -0,0 +1,188 @@
+use crate::cell::Cell;
+use crate::events::GenerationStep;
+use crate::grid::Grid;
+use crate::steps::GenerationSteps;
+
+/// How many generation steps a `StepPlayer` advances per second until its speed is set.
+pub const DEFAULT_STEPS_PER_SECOND: f64 = 30.0;
+
+/// Plays back the steps captured while generating a maze (see `Grid::generation_steps`), so that apps
+/// animating generation need only tell the player how much time has passed each frame and draw its current
+/// step, rather than keeping their own step index and timing.
+///
+/// The player starts paused on the first step. Once playing, `tick` advances it by as many steps as the time
+/// passed allows at its speed, stopping on the last step.
+#[derive(Debug, Clone)]
+pub struct StepPlayer<'a> {
+    steps: Option<&'a GenerationSteps>,
+    details: &'a [GenerationStep],
+    index: usize,
+    // the maze at the current step, rebuilt from the captured changes as the player moves
+    current: Option<Grid>,
+    playing: bool,
+    steps_per_second: f64,
+    // time passed since the player last advanced, short of a whole step
+    carried_millis: f64,
+}
+
+impl<'a> StepPlayer<'a> {
+    /// A player of the maze's generation steps, which has none unless the maze was generated with
+    /// `capture_steps`.
+    pub fn new(grid: &'a Grid) -> Self {
+        let steps = grid.generation_steps.as_ref();
+        StepPlayer {
+            steps,
+            details: grid.generation_step_details.as_deref().unwrap_or_default(),
+            index: 0,
+            current: steps.and_then(|steps| steps.get(0)),
+            playing: false,
+            steps_per_second: DEFAULT_STEPS_PER_SECOND,
+            carried_millis: 0.0,
+        }
+    }
+
+    /// The number of steps to play.
+    pub fn len(&self) -> usize {
+        self.steps.map_or(0, GenerationSteps::len)
+    }
+
+    /// Whether there are no steps to play.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The index of the current step.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Whether the player is advancing as time passes.
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Whether the current step is the last.
+    pub fn is_finished(&self) -> bool {
+        self.index + 1 >= self.len()
+    }
+
+    /// Start advancing as time passes, from the first step if the last has been reached.
+    pub fn play(&mut self) {
+        if self.is_finished() {
+            self.seek(0);
+        }
+        self.playing = !self.is_finished();
+    }
+
+    /// Stop advancing, staying on the current step.
+    pub fn pause(&mut self) {
+        self.playing = false;
+        self.carried_millis = 0.0;
+    }
+
+    /// Move to the step at `index`, or to the last step if there are fewer steps. Returns the new index.
+    /// Seeking forward replays just the steps passed over, while seeking back rebuilds the step from the first.
+    pub fn seek(&mut self, index: usize) -> usize {
+        let index = index.min(self.len().saturating_sub(1));
+        if let Some(steps) = self.steps {
+            match self.current.as_mut() {
+                Some(current) if index >= self.index => steps.replay(current, self.index, index),
+                _ => self.current = steps.get(index),
+            }
+        }
+        self.index = index;
+        self.carried_millis = 0.0;
+        if self.is_finished() {
+            self.playing = false;
+        }
+        self.index
+    }
+
+    /// How many steps the player advances per second.
+    pub fn speed(&self) -> f64 {
+        self.steps_per_second
+    }
+
+    /// Set how many steps the player advances per second. Speeds that are not positive are ignored.
+    pub fn set_speed(&mut self, steps_per_second: f64) {
+        if steps_per_second > 0.0 && steps_per_second.is_finite() {
+            self.steps_per_second = steps_per_second;
+        }
+    }
+
+    /// Advance by as many steps as `elapsed_millis` allows at the player's speed, if playing, pausing on
+    /// reaching the last step. Returns whether the current step changed.
+    pub fn tick(&mut self, elapsed_millis: u64) -> bool {
+        if !self.playing {
+            return false;
+        }
+        self.carried_millis += elapsed_millis as f64;
+        let millis_per_step = 1000.0 / self.steps_per_second;
+        let steps = (self.carried_millis / millis_per_step) as usize;
+        if steps == 0 {
+            return false;
+        }
+        self.carried_millis -= steps as f64 * millis_per_step;
+        let index = self.index;
+        self.seek(index.saturating_add(steps));
+        self.index != index
+    }
+
+    /// The maze as it was at the current step, or None if there are no steps.
+    pub fn current_step(&self) -> Option<&Grid> {
+        self.current.as_ref()
+    }
+
+    /// The cells of the maze as they were at the current step.
+    pub fn current_cells(&self) -> impl Iterator<Item = &Cell> + '_ {
+        self.current_step().into_iter().flat_map(|step| step.iter_cells())
+    }
+
+    /// What happened in the current step, or None if there are no steps.
+    pub fn current_details(&self) -> Option<&'a GenerationStep> {
+        self.details.get(self.index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playback_advances_with_time() {
+        let json = r#"{ "maze_type": "Orthogonal", "width": 6, "height": 6, "algorithm": "RecursiveBacktracker", "capture_steps": true }"#;
+        let grid = Grid::try_from(json).unwrap();
+        let mut player = StepPlayer::new(&grid);
+        assert_eq!(player.len(), grid.generation_steps.as_ref().unwrap().len());
+        assert!(!player.tick(1000));
+
+        player.set_speed(10.0);
+        player.play();
+        assert!(!player.tick(50));
+        assert!(player.tick(60));
+        assert_eq!(player.index(), 1);
+        assert!(player.tick(200));
+        assert_eq!(player.index(), 3);
+        assert_eq!(player.current_details().unwrap().linked.len(), 1);
+
+        player.pause();
+        assert!(!player.tick(1000));
+        assert_eq!(player.seek(usize::MAX), player.len() - 1);
+        assert_eq!(player.current_cells().count(), 36);
+        assert_eq!(player.current_step().unwrap().count_edges(), grid.count_edges());
+        // seeking back rebuilds the earlier step
+        player.seek(3);
+        assert_eq!(player.current_step().unwrap().cells, grid.generation_steps.as_ref().unwrap().get(3).unwrap().cells);
+        player.seek(usize::MAX);
+        // playing from the last step starts over, and playback pauses on reaching the end again
+        player.play();
+        assert_eq!(player.index(), 0);
+        player.tick(60_000);
+        assert!(player.is_finished() && !player.is_playing());
+
+        let grid = Grid::try_from(r#"{ "maze_type": "Orthogonal", "width": 6, "height": 6, "algorithm": "Prims" }"#).unwrap();
+        let mut player = StepPlayer::new(&grid);
+        player.play();
+        assert!(player.is_empty() && !player.is_playing() && player.current_step().is_none());
+    }
+}
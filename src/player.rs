@@ -0,0 +1,63 @@
+use alloc::collections::BTreeSet;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::cell::Coordinates;
+use crate::direction::Direction;
+use crate::history::MoveRecord;
+use crate::prelude::*;
+
+/// An additional token moving through the maze alongside the user's own active cell, for racing games where
+/// several players solve the same maze. Each player keeps its own position, visited trail and move history,
+/// so players never disturb each other's progress or the cells' own active and visited flags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Player {
+    /// Identifies the player in `Grid::make_move_for` and the other per-player methods.
+    pub id: u32,
+    /// The cell the player is on.
+    pub coords: Coordinates,
+    /// The cells of the player's current trail, as `Cell::is_visited` is for the user: entered moving forward
+    /// and left behind when backtracking.
+    pub visited: BTreeSet<Coordinates>,
+    /// Every cell the player has ever been on, as `Cell::has_been_visited` is for the user.
+    pub has_been_visited: BTreeSet<Coordinates>,
+    /// The player's moves so far, most recent last.
+    pub move_history: Vec<MoveRecord>,
+    /// Whether the player has completed the maze, by reaching the goal or, in "collect all" mode, by visiting
+    /// every target.
+    pub completed: bool,
+}
+
+impl Player {
+    /// A player standing on its start cell, which begins its trail.
+    pub fn new(id: u32, start: Coordinates) -> Self {
+        Player {
+            id,
+            coords: start,
+            visited: BTreeSet::from([start]),
+            has_been_visited: BTreeSet::from([start]),
+            move_history: Vec::new(),
+            completed: false,
+        }
+    }
+
+    /// Whether the cell is on the player's current trail.
+    pub fn is_visited(&self, coords: Coordinates) -> bool {
+        self.visited.contains(&coords)
+    }
+
+    // Move the player to a neighboring cell, extending its trail or, moving back onto it, retracting it
+    pub(crate) fn apply_move(&mut self, to: Coordinates, requested: Direction, direction: Direction) {
+        let from = self.coords;
+        let backtrack = self.visited.contains(&to);
+        if backtrack {
+            self.visited.remove(&from);
+        } else {
+            self.visited.insert(to);
+        }
+        let to_had_been_visited = !self.has_been_visited.insert(to);
+        self.coords = to;
+        self.move_history.push(MoveRecord { from, to, requested, direction, backtrack, to_had_been_visited });
+    }
+}
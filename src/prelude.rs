@@ -0,0 +1,55 @@
+//! Names the standard library prelude and `std::collections` provide, taken from `alloc` and `hashbrown` when
+//! building without `std`, so that modules compile the same way either way.
+
+#[cfg(feature = "std")]
+pub(crate) use std::collections::{HashMap, HashSet};
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::{HashMap, HashSet};
+
+pub(crate) use alloc::boxed::Box;
+pub(crate) use alloc::string::{String, ToString};
+pub(crate) use alloc::vec::Vec;
+pub(crate) use alloc::{format, vec};
+
+/// Floating point methods which `core` lacks, implemented with `libm` when building without `std`.
+#[cfg(not(feature = "std"))]
+pub(crate) trait FloatMath {
+    fn sqrt(self) -> f64;
+    fn round(self) -> f64;
+    fn ceil(self) -> f64;
+    fn ln(self) -> f64;
+    fn powi(self, n: i32) -> f64;
+    fn powf(self, n: f64) -> f64;
+    fn atan2(self, other: f64) -> f64;
+}
+
+#[cfg(not(feature = "std"))]
+impl FloatMath for f64 {
+    fn sqrt(self) -> f64 {
+        libm::sqrt(self)
+    }
+
+    fn round(self) -> f64 {
+        libm::round(self)
+    }
+
+    fn ceil(self) -> f64 {
+        libm::ceil(self)
+    }
+
+    fn ln(self) -> f64 {
+        libm::log(self)
+    }
+
+    fn powi(self, n: i32) -> f64 {
+        libm::pow(self, n as f64)
+    }
+
+    fn powf(self, n: f64) -> f64 {
+        libm::pow(self, n)
+    }
+
+    fn atan2(self, other: f64) -> f64 {
+        libm::atan2(self, other)
+    }
+}
@@ -0,0 +1,168 @@
+use alloc::collections::VecDeque;
+use rand::seq::SliceRandom;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::cell::Coordinates;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::rng::GridRng;
+use crate::prelude::*;
+
+/// A locked door across the passage between two neighboring cells, which opens once its key cell has been visited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Door {
+    /// The two cells on either side of the door.
+    pub between: (Coordinates, Coordinates),
+    /// The cell holding the door's key.
+    pub key: Coordinates,
+}
+
+impl Door {
+    /// Whether the door lies across the passage between the specified cells, in either order.
+    pub fn joins(&self, a: Coordinates, b: Coordinates) -> bool {
+        self.between == (a, b) || self.between == (b, a)
+    }
+
+    /// Whether the door is still locked, i.e. the user has not yet visited its key cell.
+    pub fn is_locked(&self, grid: &Grid) -> bool {
+        !grid.get(self.key).map(|cell| cell.has_been_visited).unwrap_or(false)
+    }
+}
+
+/// Place `count` locked doors along the solution path of a generated maze, replacing any existing doors, and
+/// hide each door's key where it can be reached before that door and every later door is opened. Doors and
+/// keys are chosen with the grid's seed, so the same maze always yields the same puzzle.
+pub fn add_doors(grid: &mut Grid, count: usize) -> Result<Vec<Door>, Error> {
+    grid.doors.clear();
+    let path = grid.solution_path_order();
+    // doors sit on the passages leaving path[1] through path[len - 2], so each door has a cell to hide its key before it
+    if count > path.len().saturating_sub(2) {
+        return Err(Error::InvalidPuzzle {
+            reason: format!("the solution path of {} cells has room for at most {} doors", path.len(), path.len().saturating_sub(2)),
+        });
+    }
+    let mut rng = GridRng::seed_from_u64(grid.seed);
+    let mut positions: Vec<usize> = (1..path.len() - 1).collect::<Vec<_>>().choose_multiple(&mut rng, count).copied().collect();
+    positions.sort();
+    let passages: Vec<(Coordinates, Coordinates)> = positions.iter().map(|&i| (path[i], path[i + 1])).collect();
+
+    let mut doors: Vec<Door> = Vec::new();
+    let mut previous_region: HashSet<Coordinates> = HashSet::new();
+    for (index, &between) in passages.iter().enumerate() {
+        // cells reachable with this door and every later door still closed
+        let region = reachable(grid, |a, b| passages[index..].iter().any(|&(x, y)| (x, y) == (a, b) || (x, y) == (b, a)));
+        let available = |coords: &&Coordinates| {
+            **coords != grid.start_coords && **coords != grid.goal_coords && doors.iter().all(|door| door.key != **coords)
+        };
+        // prefer hiding the key past the previous door, so doors must be opened in turn
+        let mut candidates: Vec<Coordinates> = region.difference(&previous_region).filter(available).copied().collect();
+        if candidates.is_empty() {
+            candidates = region.iter().filter(available).copied().collect();
+        }
+        candidates.sort();
+        let key = *candidates.choose(&mut rng).ok_or_else(|| Error::InvalidPuzzle {
+            reason: format!("no cell is available for the key to the door between {} and {}", between.0, between.1),
+        })?;
+        doors.push(Door { between, key });
+        previous_region = region;
+    }
+
+    grid.doors = doors.clone();
+    if !is_solvable(grid) {
+        grid.doors.clear();
+        return Err(Error::InvalidPuzzle { reason: "the doors would make the goal unreachable".to_string() });
+    }
+    Ok(doors)
+}
+
+/// Whether the goal can be reached from the start, collecting keys along the way to open doors.
+pub fn is_solvable(grid: &Grid) -> bool {
+    let mut keys: HashSet<Coordinates> = HashSet::new();
+    loop {
+        let region = reachable(grid, |a, b| grid.doors.iter().any(|door| door.joins(a, b) && !keys.contains(&door.key)));
+        if region.contains(&grid.goal_coords) {
+            return true;
+        }
+        let found: Vec<Coordinates> = grid.doors.iter().map(|door| door.key).filter(|key| region.contains(key) && !keys.contains(key)).collect();
+        if found.is_empty() {
+            return false;
+        }
+        keys.extend(found);
+    }
+}
+
+// Cells reachable from the start without crossing a blocked passage
+fn reachable(grid: &Grid, blocked: impl Fn(Coordinates, Coordinates) -> bool) -> HashSet<Coordinates> {
+    let mut seen: HashSet<Coordinates> = HashSet::from([grid.start_coords]);
+    let mut queue: VecDeque<Coordinates> = VecDeque::from([grid.start_coords]);
+    while let Some(coords) = queue.pop_front() {
+        if let Ok(cell) = grid.get(coords) {
+            for neighbor in &cell.linked {
+                if !blocked(coords, neighbor) && seen.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::behaviors::display::JsonDisplay;
+    use crate::cell::MazeType;
+    use crate::direction::Direction;
+
+    fn generated(maze_type: MazeType) -> Grid {
+        let json = format!(r#"{{ "maze_type": "{:?}", "width": 10, "height": 10, "algorithm": "Prims" }}"#, maze_type);
+        Grid::try_from(json).unwrap()
+    }
+
+    #[test]
+    fn doors_keep_the_maze_solvable() {
+        for maze_type in [MazeType::Orthogonal, MazeType::Delta, MazeType::Sigma, MazeType::Upsilon] {
+            let mut grid = generated(maze_type);
+            let doors = grid.add_doors(3).unwrap();
+            assert_eq!(doors.len(), 3);
+            assert!(is_solvable(&grid));
+            for door in &doors {
+                assert!(grid.get(door.between.0).unwrap().linked.contains(&door.between.1));
+                assert!(door.is_locked(&grid));
+            }
+            // without any keys the goal is out of reach
+            assert!(!reachable(&grid, |a, b| grid.doors.iter().any(|door| door.joins(a, b))).contains(&grid.goal_coords));
+        }
+    }
+
+    #[test]
+    fn locked_doors_block_moves_until_key_is_visited() {
+        let mut grid = Grid::new(MazeType::Orthogonal, 3, 2, Coordinates { x: 0, y: 0 }, Coordinates { x: 2, y: 0 }, false).unwrap();
+        for (a, b) in [((0, 0), (1, 0)), ((1, 0), (2, 0)), ((0, 0), (0, 1))] {
+            grid.link(Coordinates { x: a.0, y: a.1 }, Coordinates { x: b.0, y: b.1 }).unwrap();
+        }
+        grid.doors = vec![Door { between: (Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 0 }), key: Coordinates { x: 0, y: 1 } }];
+        assert!(is_solvable(&grid));
+
+        assert!(!grid.can_move(Direction::Right));
+        assert!(matches!(grid.make_move(Direction::Right), Err(Error::DoorLocked { .. })));
+        grid.make_move(Direction::Down).unwrap();
+        grid.make_move(Direction::Up).unwrap();
+        assert!(grid.can_move(Direction::Right));
+        grid.make_move(Direction::Right).unwrap();
+
+        let restored = Grid::from_saved_json(&grid.to_json().unwrap()).unwrap();
+        assert_eq!(restored.doors, grid.doors);
+    }
+
+    #[test]
+    fn too_many_doors() {
+        let mut grid = Grid::new(MazeType::Orthogonal, 3, 1, Coordinates { x: 0, y: 0 }, Coordinates { x: 2, y: 0 }, false).unwrap();
+        grid.link(Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 0 }).unwrap();
+        grid.link(Coordinates { x: 1, y: 0 }, Coordinates { x: 2, y: 0 }).unwrap();
+        assert!(matches!(grid.add_doors(2), Err(Error::InvalidPuzzle { .. })));
+        assert_eq!(grid.add_doors(1).unwrap().len(), 1);
+    }
+}
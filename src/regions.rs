@@ -0,0 +1,222 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::algorithms::kruskals::DisjointSet;
+use crate::cell::Coordinates;
+use crate::grid::Grid;
+use crate::prelude::*;
+
+/// The kind of structure a `Region` of the maze's passages forms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum RegionKind {
+    /// Cells joined by loops, so that any two of them are joined by at least two routes sharing no other cell.
+    Loop,
+    /// An open area, such as a 2x2 block of an Orthogonal maze without walls: cells joined by loops of at most
+    /// four passages. Delta mazes have no loops that short, and so no chambers.
+    Chamber,
+    /// A single cell which every route between some other cells passes through, a chokepoint of the maze.
+    ArticulationPoint,
+}
+
+/// Cells of the maze forming a loop, chamber or articulation point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Region {
+    pub kind: RegionKind,
+    /// The region's cells, sorted by coordinates.
+    pub cells: Vec<Coordinates>,
+}
+
+/// Label the loops, chambers and articulation points of the maze. Loops come first, then chambers, each
+/// largest first, then articulation points in order of their coordinates. A chamber lies within a loop, and
+/// articulation points may lie on loops; a perfect maze has no loops or chambers.
+pub fn regions(grid: &Grid) -> Vec<Region> {
+    let mut coords: Vec<Coordinates> = grid.iter_cells().map(|cell| cell.coords).collect();
+    coords.sort();
+    let index: HashMap<Coordinates, usize> = coords.iter().enumerate().map(|(i, &c)| (c, i)).collect();
+    let adjacency: Vec<Vec<usize>> = coords
+        .iter()
+        .map(|&c| {
+            let mut linked: Vec<usize> = grid.get(c).map(|cell| cell.linked.iter().filter_map(|n| index.get(&n).copied()).collect()).unwrap_or_default();
+            linked.sort();
+            linked
+        })
+        .collect();
+
+    let (blocks, cut) = biconnected_components(&adjacency);
+    let to_region = |kind: RegionKind, members: &mut dyn Iterator<Item = usize>| {
+        let mut cells: Vec<Coordinates> = members.map(|i| coords[i]).collect();
+        cells.sort();
+        cells.dedup();
+        Region { kind, cells }
+    };
+
+    let mut loops: Vec<Region> = blocks
+        .iter()
+        .filter(|block| block.len() > 1)
+        .map(|block| to_region(RegionKind::Loop, &mut block.iter().flat_map(|&(a, b)| [a, b])))
+        .collect();
+
+    // cells around each loop of at most four passages, joined wherever the loops share a cell
+    let mut joined = DisjointSet::new();
+    let mut in_chamber = BTreeSet::new();
+    for &cell in &coords {
+        joined.make_set(cell);
+    }
+    for a in 0..adjacency.len() {
+        for &b in &adjacency[a] {
+            for &c in adjacency[b].iter().filter(|&&c| c != a) {
+                let closes = adjacency[c].contains(&a);
+                let square = adjacency[c].iter().find(|&&d| d != b && d != a && adjacency[d].contains(&a));
+                let around: Vec<usize> = match (closes, square) {
+                    (true, _) => vec![a, b, c],
+                    (false, Some(&d)) => vec![a, b, c, d],
+                    _ => continue,
+                };
+                for &cell in &around {
+                    joined.union(coords[a], coords[cell]);
+                    in_chamber.insert(cell);
+                }
+            }
+        }
+    }
+    let mut chambers: BTreeMap<Coordinates, Vec<usize>> = BTreeMap::new();
+    for &cell in &in_chamber {
+        if let Some(root) = joined.find(coords[cell]) {
+            chambers.entry(root).or_default().push(cell);
+        }
+    }
+    let mut chambers: Vec<Region> = chambers
+        .into_values()
+        .map(|members| to_region(RegionKind::Chamber, &mut members.into_iter()))
+        .collect();
+
+    for regions in [&mut loops, &mut chambers] {
+        regions.sort_by(|a, b| b.cells.len().cmp(&a.cells.len()).then_with(|| a.cells.cmp(&b.cells)));
+    }
+    let points = cut.iter().enumerate().filter(|(_, &is_cut)| is_cut).map(|(i, _)| Region { kind: RegionKind::ArticulationPoint, cells: vec![coords[i]] });
+    loops.into_iter().chain(chambers).chain(points).collect()
+}
+
+// The passages of each biconnected component of the graph, and whether each node is a cut vertex (an
+// articulation point), found by Hopcroft and Tarjan's depth first search, kept iterative for large mazes
+fn biconnected_components(adjacency: &[Vec<usize>]) -> (Vec<Vec<(usize, usize)>>, Vec<bool>) {
+    let unvisited = usize::MAX;
+    let mut discovered = vec![unvisited; adjacency.len()];
+    let mut low = vec![0; adjacency.len()];
+    let mut parent = vec![unvisited; adjacency.len()];
+    let mut cut = vec![false; adjacency.len()];
+    let mut components = Vec::new();
+    let mut passages: Vec<(usize, usize)> = Vec::new();
+    let mut time = 0;
+    for root in 0..adjacency.len() {
+        if discovered[root] != unvisited {
+            continue;
+        }
+        discovered[root] = time;
+        low[root] = time;
+        time += 1;
+        let mut root_children = 0;
+        // each node on the search path, with the index of the next neighbor to search from it
+        let mut path = vec![(root, 0)];
+        while let Some((node, next)) = path.last_mut() {
+            let node = *node;
+            if let Some(&neighbor) = adjacency[node].get(*next) {
+                *next += 1;
+                if discovered[neighbor] == unvisited {
+                    parent[neighbor] = node;
+                    discovered[neighbor] = time;
+                    low[neighbor] = time;
+                    time += 1;
+                    passages.push((node, neighbor));
+                    path.push((neighbor, 0));
+                    if node == root {
+                        root_children += 1;
+                    }
+                } else if neighbor != parent[node] && discovered[neighbor] < discovered[node] {
+                    low[node] = low[node].min(discovered[neighbor]);
+                    passages.push((node, neighbor));
+                }
+                continue;
+            }
+            path.pop();
+            let Some(&(above, _)) = path.last() else {
+                continue;
+            };
+            low[above] = low[above].min(low[node]);
+            if low[node] >= discovered[above] {
+                if above != root {
+                    cut[above] = true;
+                }
+                let mut component = Vec::new();
+                while let Some(passage) = passages.pop() {
+                    component.push(passage);
+                    if passage == (above, node) {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+        if root_children > 1 {
+            cut[root] = true;
+        }
+    }
+    (components, cut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::MazeType;
+
+    fn at(x: usize, y: usize) -> Coordinates {
+        Coordinates { x, y }
+    }
+
+    #[test]
+    fn chamber_and_corridor() {
+        // an open 2x2 chamber on the left, leading into a corridor that doubles back along the top
+        let mut grid = Grid::new(MazeType::Orthogonal, 4, 2, at(0, 0), at(2, 0), false).unwrap();
+        for (a, b) in [
+            (at(0, 0), at(1, 0)), (at(0, 1), at(1, 1)), (at(0, 0), at(0, 1)), (at(1, 0), at(1, 1)),
+            (at(1, 1), at(2, 1)), (at(2, 1), at(3, 1)), (at(3, 1), at(3, 0)), (at(3, 0), at(2, 0)),
+        ] {
+            grid.link(a, b).unwrap();
+        }
+        let block = vec![at(0, 0), at(0, 1), at(1, 0), at(1, 1)];
+        assert_eq!(grid.regions(), vec![
+            Region { kind: RegionKind::Loop, cells: block.clone() },
+            Region { kind: RegionKind::Chamber, cells: block },
+            Region { kind: RegionKind::ArticulationPoint, cells: vec![at(1, 1)] },
+            Region { kind: RegionKind::ArticulationPoint, cells: vec![at(2, 1)] },
+            Region { kind: RegionKind::ArticulationPoint, cells: vec![at(3, 0)] },
+            Region { kind: RegionKind::ArticulationPoint, cells: vec![at(3, 1)] },
+        ]);
+    }
+
+    #[test]
+    fn ring_around_a_dead_end() {
+        // a ring of eight cells, with the middle cell a dead end off the top of the ring
+        let mut grid = Grid::new(MazeType::Orthogonal, 3, 3, at(0, 0), at(2, 2), false).unwrap();
+        let ring = [at(0, 0), at(1, 0), at(2, 0), at(2, 1), at(2, 2), at(1, 2), at(0, 2), at(0, 1)];
+        for i in 0..ring.len() {
+            grid.link(ring[i], ring[(i + 1) % ring.len()]).unwrap();
+        }
+        grid.link(at(1, 0), at(1, 1)).unwrap();
+        let mut cells = ring.to_vec();
+        cells.sort();
+        assert_eq!(grid.regions(), vec![
+            Region { kind: RegionKind::Loop, cells },
+            Region { kind: RegionKind::ArticulationPoint, cells: vec![at(1, 0)] },
+        ]);
+
+        let perfect = Grid::try_from(r#"{ "maze_type": "Sigma", "width": 8, "height": 8, "algorithm": "Wilsons" }"#).unwrap();
+        let regions = perfect.regions();
+        assert!(regions.iter().all(|region| region.kind == RegionKind::ArticulationPoint));
+        let branching = perfect.iter_cells().filter(|cell| cell.linked.len() > 1).count();
+        assert_eq!(regions.len(), branching);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::cell::{CellOrientation, Coordinates, MazeType};
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::render::{CellPolygon, Point};
+use crate::prelude::*;
+
+// Delta cells are equilateral triangles with sides `cell_size` long, alternating between upward-pointing
+// (Normal) and downward-pointing (Inverted) along each row, so neighboring triangles in a row overlap by half
+// a side horizontally.
+
+fn row_height(cell_size: f64) -> f64 {
+    cell_size * 3f64.sqrt() / 2.0
+}
+
+/// Center of the triangle at the specified coordinates, with the specified orientation.
+pub fn center(coords: Coordinates, orientation: CellOrientation, cell_size: f64) -> Point {
+    let h = row_height(cell_size);
+    let third = match orientation {
+        CellOrientation::Normal => 2.0 / 3.0,
+        CellOrientation::Inverted => 1.0 / 3.0,
+    };
+    Point {
+        x: (coords.x as f64 + 1.0) * cell_size / 2.0,
+        y: (coords.y as f64 + third) * h,
+    }
+}
+
+/// Vertices of the triangle at the specified coordinates, clockwise from its top-most (or top-left) vertex.
+pub fn polygon(coords: Coordinates, orientation: CellOrientation, cell_size: f64) -> Vec<Point> {
+    let h = row_height(cell_size);
+    let (left, top) = (coords.x as f64 * cell_size / 2.0, coords.y as f64 * h);
+    match orientation {
+        CellOrientation::Normal => vec![
+            Point { x: left + cell_size / 2.0, y: top },
+            Point { x: left + cell_size, y: top + h },
+            Point { x: left, y: top + h },
+        ],
+        CellOrientation::Inverted => vec![
+            Point { x: left, y: top },
+            Point { x: left + cell_size, y: top },
+            Point { x: left + cell_size / 2.0, y: top + h },
+        ],
+    }
+}
+
+/// Polygons for every cell of a Delta maze, in row-major order.
+pub fn polygons(grid: &Grid, cell_size: f64) -> Result<Vec<CellPolygon>, Error> {
+    if grid.maze_type != MazeType::Delta {
+        return Err(Error::GeometryUnavailableForMazeType { maze_type: grid.maze_type });
+    }
+    Ok(grid
+        .cells
+        .iter()
+        .flatten()
+        .map(|cell| CellPolygon { coords: cell.coords, vertices: polygon(cell.coords, cell.orientation, cell_size) })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighboring_cells_share_an_edge() {
+        let grid = Grid::try_from(r#"{ "maze_type": "Delta", "width": 7, "height": 5, "algorithm": "Prims" }"#).unwrap();
+        let polygons = polygons(&grid, 10.0).unwrap();
+        let polygon = |coords: Coordinates| polygons.iter().find(|p| p.coords == coords).unwrap();
+        for cell in grid.iter_cells() {
+            for neighbor in cell.neighbors() {
+                assert!(polygon(cell.coords).shared_edge(polygon(neighbor)).is_some(), "{:?} and {:?}", cell.coords, neighbor);
+            }
+        }
+        assert!(polygons.iter().flat_map(|p| p.vertices.iter()).all(|v| v.x >= 0.0 && v.y >= 0.0));
+    }
+}
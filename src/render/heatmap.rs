@@ -0,0 +1,40 @@
+use crate::cell::Coordinates;
+use crate::prelude::*;
+
+/// Normalize distances into heat intensities from 0.0 (the origin) to 1.0 (the farthest cell(s)),
+/// sorted by coordinates so that callers receive a stable ordering.
+pub fn normalize(distances: &HashMap<Coordinates, u32>) -> Vec<(Coordinates, f32)> {
+    let max_distance = distances.values().copied().max().unwrap_or(0);
+    let mut heat: Vec<(Coordinates, f32)> = distances
+        .iter()
+        .map(|(&coords, &distance)| {
+            let intensity = if max_distance == 0 { 0.0 } else { distance as f32 / max_distance as f32 };
+            (coords, intensity)
+        })
+        .collect();
+    heat.sort_by_key(|&(coords, _)| coords);
+    heat
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_to_unit_range() {
+        let distances: HashMap<Coordinates, u32> = (0..5)
+            .map(|x| (Coordinates { x, y: 0 }, x as u32))
+            .collect();
+        let heat = normalize(&distances);
+        assert_eq!(heat.len(), 5);
+        assert_eq!(heat[0], (Coordinates { x: 0, y: 0 }, 0.0));
+        assert_eq!(heat[2], (Coordinates { x: 2, y: 0 }, 0.5));
+        assert_eq!(heat[4], (Coordinates { x: 4, y: 0 }, 1.0));
+    }
+
+    #[test]
+    fn single_cell_has_no_heat() {
+        let distances: HashMap<Coordinates, u32> = [(Coordinates { x: 0, y: 0 }, 0)].into_iter().collect();
+        assert_eq!(normalize(&distances), vec![(Coordinates { x: 0, y: 0 }, 0.0)]);
+    }
+}
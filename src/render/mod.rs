@@ -0,0 +1,109 @@
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use crate::cell::{Coordinates, MazeType};
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::prelude::*;
+
+pub mod delta;
+pub mod heatmap;
+pub mod orthogonal;
+#[cfg(feature = "png")]
+pub mod png;
+pub mod rhombic;
+pub mod sigma;
+pub mod svg;
+pub mod upsilon;
+
+// Vertices closer than this are considered the same point
+const EPSILON: f64 = 1e-9;
+
+/// A point in render space, with y increasing downward.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// The outline of a single cell, with its vertices in clockwise order.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct CellPolygon {
+    pub coords: Coordinates,
+    pub vertices: Vec<Point>,
+}
+
+impl CellPolygon {
+    /// The area enclosed by the polygon.
+    pub fn area(&self) -> f64 {
+        let n = self.vertices.len();
+        let twice_area: f64 = (0..n)
+            .map(|i| {
+                let (a, b) = (self.vertices[i], self.vertices[(i + 1) % n]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum();
+        twice_area.abs() / 2.0
+    }
+
+    /// The polygon's edges, each from a vertex to the next in clockwise order.
+    pub fn edges(&self) -> Vec<(Point, Point)> {
+        let n = self.vertices.len();
+        (0..n).map(|i| (self.vertices[i], self.vertices[(i + 1) % n])).collect()
+    }
+
+    /// The edge this polygon shares with another, as traversed by this polygon, if they are adjacent.
+    pub fn shared_edge(&self, other: &CellPolygon) -> Option<(Point, Point)> {
+        let same = |a: &Point, b: &Point| (a.x - b.x).abs() < EPSILON && (a.y - b.y).abs() < EPSILON;
+        // adjacent polygons traverse their shared edge in opposite directions
+        self.edges().into_iter().find(|(a, b)| other.edges().iter().any(|(c, d)| same(a, d) && same(b, c)))
+    }
+
+    /// The mean of the polygon's vertices, which lies at the center of a regular polygon.
+    pub fn centroid(&self) -> Point {
+        let n = self.vertices.len() as f64;
+        Point {
+            x: self.vertices.iter().map(|v| v.x).sum::<f64>() / n,
+            y: self.vertices.iter().map(|v| v.y).sum::<f64>() / n,
+        }
+    }
+}
+
+/// Polygons for every cell of a maze of any type, in row-major order. `cell_size` is interpreted as by the
+/// module for the grid's maze type.
+pub fn polygons(grid: &Grid, cell_size: f64) -> Result<Vec<CellPolygon>, Error> {
+    match grid.maze_type {
+        MazeType::Orthogonal => orthogonal::polygons(grid, cell_size),
+        MazeType::Delta => delta::polygons(grid, cell_size),
+        MazeType::Sigma => sigma::polygons(grid, cell_size),
+        MazeType::Upsilon => upsilon::polygons(grid, cell_size),
+        MazeType::Rhombic => rhombic::polygons(grid, cell_size),
+    }
+}
+
+/// Center of the cell at the specified coordinates, as placed by `polygons`.
+pub fn center(grid: &Grid, coords: Coordinates, cell_size: f64) -> Result<Point, Error> {
+    let cell = grid.get(coords)?;
+    Ok(match grid.maze_type {
+        MazeType::Orthogonal => orthogonal::center(coords, cell_size),
+        MazeType::Delta => delta::center(coords, cell.orientation, cell_size),
+        MazeType::Sigma => sigma::center(coords, cell_size),
+        MazeType::Upsilon => upsilon::center(coords, cell_size),
+        MazeType::Rhombic => rhombic::center(coords, cell_size),
+    })
+}
+
+// The corners of the smallest axis-aligned rectangle containing every polygon, top left first
+pub(crate) fn bounds(polygons: &[CellPolygon]) -> (Point, Point) {
+    let vertices = || polygons.iter().flat_map(|polygon| polygon.vertices.iter());
+    let min = Point {
+        x: vertices().map(|v| v.x).fold(f64::INFINITY, f64::min),
+        y: vertices().map(|v| v.y).fold(f64::INFINITY, f64::min),
+    };
+    let max = Point {
+        x: vertices().map(|v| v.x).fold(f64::NEG_INFINITY, f64::max),
+        y: vertices().map(|v| v.y).fold(f64::NEG_INFINITY, f64::max),
+    };
+    (min, max)
+}
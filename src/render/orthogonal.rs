@@ -0,0 +1,58 @@
+use crate::cell::{Coordinates, MazeType};
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::render::{CellPolygon, Point};
+use crate::prelude::*;
+
+// Orthogonal cells are squares `cell_size` across, laid out in rows and columns. The layers of a multi-layer
+// maze are drawn one below the other, as they are stored.
+
+/// Center of the cell at the specified coordinates.
+pub fn center(coords: Coordinates, cell_size: f64) -> Point {
+    Point {
+        x: (coords.x as f64 + 0.5) * cell_size,
+        y: (coords.y as f64 + 0.5) * cell_size,
+    }
+}
+
+/// Vertices of the square at the specified coordinates, clockwise from the top-left.
+pub fn polygon(coords: Coordinates, cell_size: f64) -> Vec<Point> {
+    let (left, top) = (coords.x as f64 * cell_size, coords.y as f64 * cell_size);
+    vec![
+        Point { x: left, y: top },
+        Point { x: left + cell_size, y: top },
+        Point { x: left + cell_size, y: top + cell_size },
+        Point { x: left, y: top + cell_size },
+    ]
+}
+
+/// Polygons for every cell of an Orthogonal maze, in row-major order.
+pub fn polygons(grid: &Grid, cell_size: f64) -> Result<Vec<CellPolygon>, Error> {
+    if grid.maze_type != MazeType::Orthogonal {
+        return Err(Error::GeometryUnavailableForMazeType { maze_type: grid.maze_type });
+    }
+    Ok(grid
+        .cells
+        .iter()
+        .flatten()
+        .map(|cell| CellPolygon { coords: cell.coords, vertices: polygon(cell.coords, cell_size) })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighboring_cells_share_an_edge() {
+        let grid = Grid::try_from(r#"{ "maze_type": "Orthogonal", "width": 5, "height": 4, "algorithm": "Prims" }"#).unwrap();
+        let polygons = polygons(&grid, 10.0).unwrap();
+        let polygon = |coords: Coordinates| polygons.iter().find(|p| p.coords == coords).unwrap();
+        for cell in grid.iter_cells() {
+            for neighbor in cell.neighbors() {
+                assert!(polygon(cell.coords).shared_edge(polygon(neighbor)).is_some());
+            }
+        }
+        assert!((polygon(Coordinates { x: 0, y: 0 }).area() - 100.0).abs() < 1e-9);
+    }
+}
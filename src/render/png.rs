@@ -0,0 +1,134 @@
+use crate::cell::Coordinates;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::render::svg::{self, SvgOptions};
+use crate::render::{self, Point};
+use crate::prelude::*;
+
+type Rgb = [u8; 3];
+
+const WHITE: Rgb = [255, 255, 255];
+const BLACK: Rgb = [0, 0, 0];
+const RED: Rgb = [255, 0, 0];
+const GREEN: Rgb = [0, 128, 0];
+
+// An RGB image, drawn on with anti-aliased shapes
+struct Canvas {
+    width: usize,
+    height: usize,
+    // render space coordinates of the image's top left corner
+    origin: Point,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize, origin: Point) -> Self {
+        Canvas { width, height, origin, pixels: WHITE.repeat(width * height) }
+    }
+
+    // Blend a color into every pixel whose center lies within about half a pixel of the shape, as measured by
+    // `outside`, the distance from a point to the shape (zero or less inside it)
+    fn fill(&mut self, (min, max): (Point, Point), color: Rgb, outside: impl Fn(Point) -> f64) {
+        let column = |x: f64| ((x - self.origin.x).floor().max(0.0) as usize).min(self.width);
+        let row = |y: f64| ((y - self.origin.y).floor().max(0.0) as usize).min(self.height);
+        let (left, right) = (column(min.x - 1.0), column(max.x + 2.0));
+        let (top, bottom) = (row(min.y - 1.0), row(max.y + 2.0));
+        for py in top..bottom {
+            for px in left..right {
+                let center = Point { x: self.origin.x + px as f64 + 0.5, y: self.origin.y + py as f64 + 0.5 };
+                let coverage = (0.5 - outside(center)).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    let pixel = &mut self.pixels[(py * self.width + px) * 3..][..3];
+                    for (channel, &target) in pixel.iter_mut().zip(&color) {
+                        *channel = (*channel as f64 + (target as f64 - *channel as f64) * coverage).round() as u8;
+                    }
+                }
+            }
+        }
+    }
+
+    fn line(&mut self, a: Point, b: Point, width: f64, color: Rgb) {
+        let radius = width / 2.0;
+        let min = Point { x: a.x.min(b.x) - radius, y: a.y.min(b.y) - radius };
+        let max = Point { x: a.x.max(b.x) + radius, y: a.y.max(b.y) + radius };
+        self.fill((min, max), color, |p| distance_to_segment(p, a, b) - radius);
+    }
+
+    fn circle(&mut self, center: Point, radius: f64, color: Rgb) {
+        let min = Point { x: center.x - radius, y: center.y - radius };
+        let max = Point { x: center.x + radius, y: center.y + radius };
+        self.fill((min, max), color, |p| ((p.x - center.x).powi(2) + (p.y - center.y).powi(2)).sqrt() - radius);
+    }
+}
+
+fn distance_to_segment(p: Point, a: Point, b: Point) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0.0 { 0.0 } else { (((p.x - a.x) * dx + (p.y - a.y) * dy) / length_squared).clamp(0.0, 1.0) };
+    let (x, y) = (a.x + t * dx, a.y + t * dy);
+    ((p.x - x).powi(2) + (p.y - y).powi(2)).sqrt()
+}
+
+/// Render the maze as a PNG image, drawn as by `svg::to_svg` with one pixel per unit of render space. PNG
+/// images have no text, so the caption is left out.
+pub fn to_png(grid: &Grid, options: &SvgOptions) -> Result<Vec<u8>, Error> {
+    let polygons = render::polygons(grid, options.cell_size)?;
+    let (min, max) = render::bounds(&polygons);
+    let origin = Point { x: min.x - options.margin, y: min.y - options.margin };
+    let width = (max.x - min.x + 2.0 * options.margin).ceil() as usize;
+    let height = (max.y - min.y + 2.0 * options.margin).ceil() as usize;
+    let mut canvas = Canvas::new(width, height, origin);
+
+    for (a, b) in svg::walls(grid, &polygons) {
+        canvas.line(a, b, options.wall_width, BLACK);
+    }
+    let centers: HashMap<Coordinates, Point> = polygons.iter().map(|polygon| (polygon.coords, polygon.centroid())).collect();
+    if options.show_solution {
+        let points: Vec<Point> = grid.solution_path_order().iter().filter_map(|coords| centers.get(coords)).copied().collect();
+        for pair in points.windows(2) {
+            canvas.line(pair[0], pair[1], options.wall_width, RED);
+        }
+    }
+    for (coords, color) in [(grid.start_coords, GREEN), (grid.goal_coords, RED)] {
+        if let Some(&center) = centers.get(&coords) {
+            canvas.circle(center, options.cell_size / 4.0, color);
+        }
+    }
+
+    let mut bytes = Vec::new();
+    let mut encoder = ::png::Encoder::new(&mut bytes, width as u32, height as u32);
+    encoder.set_color(::png::ColorType::Rgb);
+    encoder.set_depth(::png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&canvas.pixels))
+        .map_err(|e| Error::ImageEncoding { reason: e.to_string() })?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::MazeType;
+
+    #[test]
+    fn every_maze_type_renders() {
+        for &maze_type in MazeType::all() {
+            let json = format!(r#"{{ "maze_type": "{:?}", "width": 6, "height": 6, "algorithm": "Wilsons" }}"#, maze_type);
+            let grid = Grid::try_from(json).unwrap();
+            let options = SvgOptions { show_solution: true, ..Default::default() };
+            let bytes = to_png(&grid, &options).unwrap();
+
+            let decoder = ::png::Decoder::new(bytes.as_slice());
+            let mut reader = decoder.read_info().unwrap();
+            let mut pixels = vec![0; reader.output_buffer_size()];
+            let info = reader.next_frame(&mut pixels).unwrap();
+            let polygons = render::polygons(&grid, options.cell_size).unwrap();
+            let (min, max) = render::bounds(&polygons);
+            assert_eq!(info.width, (max.x - min.x + 2.0 * options.margin).ceil() as u32);
+            // the margin is blank and the walls are drawn in black
+            assert_eq!(&pixels[..3], &WHITE);
+            assert!(pixels.chunks(3).any(|pixel| pixel == BLACK));
+        }
+    }
+}
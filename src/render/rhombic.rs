@@ -0,0 +1,113 @@
+use crate::cell::{Coordinates, MazeType};
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::render::{CellPolygon, Point};
+use crate::prelude::*;
+
+// Rhombic cells only exist where x + y is even, forming a checkerboard of diamonds which meet their four
+// diagonal neighbors edge to edge. With `cell_size` as the distance from a diamond's center to each of its
+// corners, neighboring centers sit `cell_size` apart both horizontally and vertically.
+
+/// Center of the cell at the specified coordinates. The grid is offset so that no polygon extends to negative
+/// coordinates.
+pub fn center(coords: Coordinates, cell_size: f64) -> Point {
+    Point {
+        x: (coords.x + 1) as f64 * cell_size,
+        y: (coords.y + 1) as f64 * cell_size,
+    }
+}
+
+/// Vertices of the diamond at the specified coordinates, clockwise from the top.
+pub fn polygon(coords: Coordinates, cell_size: f64) -> Vec<Point> {
+    let c = center(coords, cell_size);
+    vec![
+        Point { x: c.x, y: c.y - cell_size },
+        Point { x: c.x + cell_size, y: c.y },
+        Point { x: c.x, y: c.y + cell_size },
+        Point { x: c.x - cell_size, y: c.y },
+    ]
+}
+
+/// Polygons for every cell of a Rhombic maze, in row-major order.
+pub fn polygons(grid: &Grid, cell_size: f64) -> Result<Vec<CellPolygon>, Error> {
+    if grid.maze_type != MazeType::Rhombic {
+        return Err(Error::GeometryUnavailableForMazeType { maze_type: grid.maze_type });
+    }
+    Ok(grid
+        .cells
+        .iter()
+        .flatten()
+        .map(|cell| CellPolygon { coords: cell.coords, vertices: polygon(cell.coords, cell_size) })
+        .collect())
+}
+
+/// Text preview of a Rhombic maze's passages. Each cell is drawn as `o` (`S` for the start, `G` for the goal)
+/// and each passage between diagonal neighbors as `/` or `\`, so the maze reads like its own diagonal layout.
+pub fn to_ascii(grid: &Grid) -> Result<String, Error> {
+    if grid.maze_type != MazeType::Rhombic {
+        return Err(Error::GeometryUnavailableForMazeType { maze_type: grid.maze_type });
+    }
+    let (columns, rows) = (2 * grid.width - 1, 2 * grid.height - 1);
+    let mut canvas = vec![vec![' '; columns]; rows];
+    for cell in grid.iter_cells() {
+        let (x, y) = (cell.coords.x, cell.coords.y);
+        canvas[2 * y][2 * x] = match (cell.is_start, cell.is_goal) {
+            (true, _) => 'S',
+            (_, true) => 'G',
+            _ => 'o',
+        };
+        // draw each passage once, from its left-hand cell
+        for neighbor in cell.linked.iter().filter(|n| n.x > x) {
+            let (column, row) = (2 * x + 1, y + neighbor.y);
+            canvas[row][column] = if neighbor.y > y { '\\' } else { '/' };
+        }
+    }
+    Ok(canvas
+        .into_iter()
+        .map(|row| row.into_iter().collect::<String>().trim_end().to_string())
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighboring_diamonds_share_an_edge() {
+        let grid = Grid::try_from(r#"{ "maze_type": "Rhombic", "width": 7, "height": 7, "algorithm": "Kruskals" }"#).unwrap();
+        let polygons = polygons(&grid, 5.0).unwrap();
+        assert_eq!(polygons.len(), grid.iter_cells().count());
+        for cell in grid.iter_cells() {
+            let polygon = polygons.iter().find(|p| p.coords == cell.coords).unwrap();
+            assert_eq!(polygon.area(), 50.0);
+            for neighbor in cell.neighbors() {
+                let other = polygons.iter().find(|p| p.coords == neighbor).unwrap();
+                let shared = polygon.vertices.iter().filter(|v| other.vertices.contains(v)).count();
+                assert_eq!(shared, 2, "{:?} and {:?} should share an edge", cell.coords, neighbor);
+            }
+        }
+    }
+
+    #[test]
+    fn ascii_preview_draws_every_passage() {
+        let grid = Grid::try_from(r#"{ "maze_type": "Rhombic", "width": 9, "height": 9, "algorithm": "Wilsons" }"#).unwrap();
+        let preview = to_ascii(&grid).unwrap();
+        println!("\n\nRhombic\n\n{}\n\n", preview);
+        assert_eq!(preview.lines().count(), 2 * grid.height - 1);
+        assert_eq!(preview.matches(['/', '\\']).count(), grid.count_edges());
+        assert_eq!(preview.matches(['o', 'S', 'G']).count(), grid.iter_cells().count());
+        assert_eq!(preview.matches('S').count(), 1);
+        assert!(to_ascii(&Grid::try_from(r#"{ "maze_type": "Delta", "width": 4, "height": 4, "algorithm": "Prims" }"#).unwrap()).is_err());
+    }
+
+    #[test]
+    fn ascii_preview_of_small_maze() {
+        let mut grid = Grid::new(MazeType::Rhombic, 3, 3, Coordinates { x: 0, y: 0 }, Coordinates { x: 2, y: 2 }, false).unwrap();
+        grid.link(Coordinates { x: 0, y: 0 }, Coordinates { x: 1, y: 1 }).unwrap();
+        grid.link(Coordinates { x: 1, y: 1 }, Coordinates { x: 2, y: 2 }).unwrap();
+        grid.link(Coordinates { x: 1, y: 1 }, Coordinates { x: 0, y: 2 }).unwrap();
+        grid.link(Coordinates { x: 1, y: 1 }, Coordinates { x: 2, y: 0 }).unwrap();
+        assert_eq!(to_ascii(&grid).unwrap(), "S   o\n \\ /\n  o\n / \\\no   G");
+    }
+}
@@ -0,0 +1,65 @@
+use crate::cell::{Coordinates, MazeType};
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::render::{CellPolygon, Point};
+use crate::prelude::*;
+
+// Sigma cells are flat-topped hexagons laid out in columns, with odd columns shifted down by half a hexagon.
+// With `cell_size` as the distance from a hexagon's center to each of its corners, columns sit
+// 1.5 * cell_size apart and rows √3 * cell_size apart.
+
+fn hex_height(cell_size: f64) -> f64 {
+    cell_size * 3f64.sqrt()
+}
+
+/// Center of the cell at the specified coordinates. The grid is offset so that no polygon extends to negative
+/// coordinates.
+pub fn center(coords: Coordinates, cell_size: f64) -> Point {
+    let h = hex_height(cell_size);
+    let shift = if coords.x % 2 == 1 { h / 2.0 } else { 0.0 };
+    Point {
+        x: cell_size + 1.5 * cell_size * coords.x as f64,
+        y: h / 2.0 + h * coords.y as f64 + shift,
+    }
+}
+
+/// Vertices of the hexagon at the specified coordinates, clockwise from the top-left.
+pub fn polygon(coords: Coordinates, cell_size: f64) -> Vec<Point> {
+    let c = center(coords, cell_size);
+    let (half, half_height) = (cell_size / 2.0, hex_height(cell_size) / 2.0);
+    [(-half, -half_height), (half, -half_height), (cell_size, 0.0), (half, half_height), (-half, half_height), (-cell_size, 0.0)]
+        .into_iter()
+        .map(|(dx, dy)| Point { x: c.x + dx, y: c.y + dy })
+        .collect()
+}
+
+/// Polygons for every cell of a Sigma maze, in row-major order.
+pub fn polygons(grid: &Grid, cell_size: f64) -> Result<Vec<CellPolygon>, Error> {
+    if grid.maze_type != MazeType::Sigma {
+        return Err(Error::GeometryUnavailableForMazeType { maze_type: grid.maze_type });
+    }
+    Ok(grid
+        .cells
+        .iter()
+        .flatten()
+        .map(|cell| CellPolygon { coords: cell.coords, vertices: polygon(cell.coords, cell_size) })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighboring_cells_share_an_edge() {
+        let grid = Grid::try_from(r#"{ "maze_type": "Sigma", "width": 6, "height": 5, "algorithm": "Prims" }"#).unwrap();
+        let polygons = polygons(&grid, 10.0).unwrap();
+        let polygon = |coords: Coordinates| polygons.iter().find(|p| p.coords == coords).unwrap();
+        for cell in grid.iter_cells() {
+            for neighbor in cell.neighbors() {
+                assert!(polygon(cell.coords).shared_edge(polygon(neighbor)).is_some(), "{:?} and {:?}", cell.coords, neighbor);
+            }
+        }
+        assert!(polygons.iter().flat_map(|p| p.vertices.iter()).all(|v| v.x >= -1e-9 && v.y >= -1e-9));
+    }
+}
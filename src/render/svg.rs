@@ -0,0 +1,195 @@
+use core::fmt::Write;
+
+use crate::cell::Coordinates;
+use crate::direction::Direction;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::render::{self, CellPolygon, Point};
+use crate::prelude::*;
+
+/// Appearance of a maze rendered with `to_svg`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    /// Size of each cell, as interpreted by the render module for the maze's type.
+    pub cell_size: f64,
+    /// Blank space around the maze.
+    pub margin: f64,
+    pub wall_width: f64,
+    /// Draw the solution path from the start to the goal, e.g. for an answer key.
+    pub show_solution: bool,
+    /// Text drawn above the maze, such as a puzzle number.
+    pub caption: Option<String>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions { cell_size: 20.0, margin: 20.0, wall_width: 2.0, show_solution: false, caption: None }
+    }
+}
+
+// An edge identified independently of its direction and of floating point noise in its vertices
+type EdgeKey = ((i64, i64), (i64, i64));
+
+fn edge_key(a: Point, b: Point) -> EdgeKey {
+    let quantize = |p: Point| ((p.x * 1e6).round() as i64, (p.y * 1e6).round() as i64);
+    let (a, b) = (quantize(a), quantize(b));
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+// Unit vector pointing from a cell towards its side in the specified direction
+fn direction_vector(direction: Direction) -> Option<(f64, f64)> {
+    let diagonal = core::f64::consts::FRAC_1_SQRT_2;
+    match direction {
+        Direction::Up => Some((0.0, -1.0)),
+        Direction::Down => Some((0.0, 1.0)),
+        Direction::Left => Some((-1.0, 0.0)),
+        Direction::Right => Some((1.0, 0.0)),
+        Direction::UpperRight => Some((diagonal, -diagonal)),
+        Direction::LowerRight => Some((diagonal, diagonal)),
+        Direction::LowerLeft => Some((-diagonal, diagonal)),
+        Direction::UpperLeft => Some((-diagonal, -diagonal)),
+        Direction::Above | Direction::Below => None,
+        Direction::Inward | Direction::Outward | Direction::Clockwise | Direction::CounterClockwise => None,
+    }
+}
+
+/// Line segments for every wall of the maze: edges between neighboring cells without a passage between them,
+/// and edges on the border other than the entrance and exit openings.
+pub fn walls(grid: &Grid, polygons: &[CellPolygon]) -> Vec<(Point, Point)> {
+    let mut owners: HashMap<EdgeKey, Vec<Coordinates>> = HashMap::new();
+    for polygon in polygons {
+        for (a, b) in polygon.edges() {
+            owners.entry(edge_key(a, b)).or_default().push(polygon.coords);
+        }
+    }
+
+    // the border edge of each opening facing most nearly in the opening's direction
+    let mut openings: HashSet<EdgeKey> = HashSet::new();
+    for polygon in polygons {
+        let Some((dx, dy)) = grid.get(polygon.coords).ok().and_then(|cell| cell.border_opening).and_then(direction_vector) else {
+            continue;
+        };
+        let center = polygon.centroid();
+        let facing = |&(a, b): &(Point, Point)| {
+            let (mx, my) = ((a.x + b.x) / 2.0 - center.x, (a.y + b.y) / 2.0 - center.y);
+            (mx * dx + my * dy) / (mx * mx + my * my).sqrt()
+        };
+        let border = polygon.edges().into_iter().filter(|&(a, b)| owners[&edge_key(a, b)].len() == 1);
+        if let Some((a, b)) = border.max_by(|x, y| facing(x).total_cmp(&facing(y))) {
+            openings.insert(edge_key(a, b));
+        }
+    }
+
+    let linked = |a: Coordinates, b: Coordinates| grid.get(a).map(|cell| cell.linked.contains(&b)).unwrap_or(false);
+    let mut seen: HashSet<EdgeKey> = HashSet::new();
+    let mut walls = Vec::new();
+    for polygon in polygons {
+        for (a, b) in polygon.edges() {
+            let key = edge_key(a, b);
+            if !seen.insert(key) {
+                continue;
+            }
+            let is_wall = match owners[&key].as_slice() {
+                [first, second] => !linked(*first, *second),
+                _ => !openings.contains(&key),
+            };
+            if is_wall {
+                walls.push((a, b));
+            }
+        }
+    }
+    walls
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Render the maze as a standalone SVG document, with walls in black and the start and goal marked in green
+/// and red.
+pub fn to_svg(grid: &Grid, options: &SvgOptions) -> Result<String, Error> {
+    let polygons = render::polygons(grid, options.cell_size)?;
+    let (Point { x: min_x, y: min_y }, Point { x: max_x, y: max_y }) = render::bounds(&polygons);
+    let font_size = options.cell_size.max(12.0);
+    let caption_height = if options.caption.is_some() { font_size * 1.5 } else { 0.0 };
+    let (left, top) = (min_x - options.margin, min_y - options.margin - caption_height);
+    let width = max_x - min_x + 2.0 * options.margin;
+    let height = max_y - min_y + 2.0 * options.margin + caption_height;
+
+    let mut svg = String::new();
+    // writing to a String cannot fail
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.2}" height="{height:.2}" viewBox="{left:.2} {top:.2} {width:.2} {height:.2}">"#
+    );
+    let _ = writeln!(svg, r#"<rect x="{left:.2}" y="{top:.2}" width="{width:.2}" height="{height:.2}" fill="white"/>"#);
+    if let Some(caption) = &options.caption {
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.2}" y="{:.2}" font-family="sans-serif" font-size="{font_size:.2}">{}</text>"#,
+            min_x,
+            min_y - options.margin / 2.0 - caption_height / 3.0,
+            escape(caption)
+        );
+    }
+
+    let mut path = String::new();
+    for (a, b) in walls(grid, &polygons) {
+        let _ = write!(path, "M{:.2} {:.2}L{:.2} {:.2}", a.x, a.y, b.x, b.y);
+    }
+    let _ = writeln!(
+        svg,
+        r#"<path d="{path}" stroke="black" stroke-width="{:.2}" stroke-linecap="round" fill="none"/>"#,
+        options.wall_width
+    );
+
+    let centers: HashMap<Coordinates, Point> = polygons.iter().map(|polygon| (polygon.coords, polygon.centroid())).collect();
+    if options.show_solution {
+        let points: Vec<String> = grid
+            .solution_path_order()
+            .iter()
+            .filter_map(|coords| centers.get(coords))
+            .map(|p| format!("{:.2},{:.2}", p.x, p.y))
+            .collect();
+        let _ = writeln!(
+            svg,
+            r#"<polyline points="{}" stroke="red" stroke-width="{:.2}" stroke-linejoin="round" fill="none"/>"#,
+            points.join(" "),
+            options.wall_width
+        );
+    }
+    for (coords, color) in [(grid.start_coords, "green"), (grid.goal_coords, "red")] {
+        if let Some(p) = centers.get(&coords) {
+            let _ = writeln!(svg, r#"<circle cx="{:.2}" cy="{:.2}" r="{:.2}" fill="{color}"/>"#, p.x, p.y, options.cell_size / 4.0);
+        }
+    }
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::MazeType;
+
+    #[test]
+    fn walls_exclude_passages_and_openings() {
+        let grid = Grid::try_from(r#"{ "maze_type": "Orthogonal", "width": 4, "height": 4, "algorithm": "Prims", "open_exits": true }"#).unwrap();
+        let polygons = render::polygons(&grid, 10.0).unwrap();
+        // a perfect maze of n cells has n - 1 passages, out of 2 * 4 * 3 interior edges, plus 16 border edges
+        // less the entrance and exit
+        assert_eq!(walls(&grid, &polygons).len(), (24 - 15) + (16 - 2));
+    }
+
+    #[test]
+    fn every_maze_type_renders() {
+        for &maze_type in MazeType::all() {
+            let json = format!(r#"{{ "maze_type": "{:?}", "width": 8, "height": 8, "algorithm": "Wilsons" }}"#, maze_type);
+            let grid = Grid::try_from(json).unwrap();
+            let options = SvgOptions { show_solution: true, caption: Some("Maze <1>".to_string()), ..Default::default() };
+            let svg = to_svg(&grid, &options).unwrap();
+            assert!(svg.starts_with("<svg") && svg.trim_end().ends_with("</svg>"));
+            assert!(svg.contains("<polyline") && svg.contains("Maze &lt;1&gt;"));
+        }
+    }
+}
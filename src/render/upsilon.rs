@@ -0,0 +1,131 @@
+use core::f64::consts::SQRT_2;
+use crate::cell::{Coordinates, MazeType};
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::render::{CellPolygon, Point};
+use crate::prelude::*;
+
+// Upsilon mazes are a truncated square tiling laid out as a checkerboard: octagons alternate with squares
+// along each row and column, octagons meet each other across their diagonal edges, and squares sit in the
+// gaps between four octagons. With `cell_size` as the distance between the centers of horizontally (or
+// vertically) adjacent cells, every edge is (2 - √2) * cell_size long.
+
+/// Length of every polygon edge for the specified cell size.
+pub fn edge_length(cell_size: f64) -> f64 {
+    (2.0 - SQRT_2) * cell_size
+}
+
+/// Distance from an octagon's center to each of its edges.
+fn octagon_apothem(cell_size: f64) -> f64 {
+    cell_size / SQRT_2
+}
+
+/// Center of the cell at the specified coordinates. The grid is offset so that no polygon extends to negative
+/// coordinates.
+pub fn center(coords: Coordinates, cell_size: f64) -> Point {
+    let margin = octagon_apothem(cell_size);
+    Point {
+        x: margin + coords.x as f64 * cell_size,
+        y: margin + coords.y as f64 * cell_size,
+    }
+}
+
+/// Vertices of the cell at the specified coordinates, clockwise from the top-left. Octagons have eight vertices
+/// and squares four.
+pub fn polygon(coords: Coordinates, is_square: bool, cell_size: f64) -> Vec<Point> {
+    let c = center(coords, cell_size);
+    let half_edge = edge_length(cell_size) / 2.0;
+    let offsets: Vec<(f64, f64)> = if is_square {
+        vec![(-half_edge, -half_edge), (half_edge, -half_edge), (half_edge, half_edge), (-half_edge, half_edge)]
+    } else {
+        let apothem = octagon_apothem(cell_size);
+        vec![
+            (-half_edge, -apothem),
+            (half_edge, -apothem),
+            (apothem, -half_edge),
+            (apothem, half_edge),
+            (half_edge, apothem),
+            (-half_edge, apothem),
+            (-apothem, half_edge),
+            (-apothem, -half_edge),
+        ]
+    };
+    offsets.into_iter().map(|(dx, dy)| Point { x: c.x + dx, y: c.y + dy }).collect()
+}
+
+/// Polygons for every cell of an Upsilon maze, in row-major order.
+pub fn polygons(grid: &Grid, cell_size: f64) -> Result<Vec<CellPolygon>, Error> {
+    if grid.maze_type != MazeType::Upsilon {
+        return Err(Error::GeometryUnavailableForMazeType { maze_type: grid.maze_type });
+    }
+    Ok(grid
+        .cells
+        .iter()
+        .flatten()
+        .map(|cell| CellPolygon { coords: cell.coords, vertices: polygon(cell.coords, cell.is_square, cell_size) })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::HashMap;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn same_point(a: &Point, b: &Point) -> bool {
+        (a.x - b.x).abs() < EPSILON && (a.y - b.y).abs() < EPSILON
+    }
+
+    fn edges(polygon: &CellPolygon) -> Vec<(Point, Point)> {
+        let n = polygon.vertices.len();
+        (0..n).map(|i| (polygon.vertices[i], polygon.vertices[(i + 1) % n])).collect()
+    }
+
+    fn is_shared(edge: &(Point, Point), other: &CellPolygon) -> bool {
+        // neighbors traverse a shared edge in opposite directions
+        edges(other).iter().any(|(a, b)| same_point(a, &edge.1) && same_point(b, &edge.0))
+    }
+
+    fn maze() -> Grid {
+        Grid::try_from(r#"{ "maze_type": "Upsilon", "width": 7, "height": 6, "algorithm": "RecursiveBacktracker" }"#).unwrap()
+    }
+
+    #[test]
+    fn neighboring_cells_share_an_edge() {
+        let grid = maze();
+        let polygons: HashMap<Coordinates, CellPolygon> = polygons(&grid, 10.0).unwrap().into_iter().map(|p| (p.coords, p)).collect();
+        for cell in grid.iter_cells() {
+            let polygon = &polygons[&cell.coords];
+            assert_eq!(polygon.vertices.len(), if cell.is_square { 4 } else { 8 });
+            for neighbor in cell.neighbors() {
+                let shared = edges(polygon).iter().filter(|edge| is_shared(edge, &polygons[&neighbor])).count();
+                assert_eq!(shared, 1, "{:?} and {:?} should share exactly one edge", cell.coords, neighbor);
+            }
+        }
+    }
+
+    #[test]
+    fn polygons_tile_without_gaps() {
+        let grid = maze();
+        let cell_size = 10.0;
+        let polygons: Vec<CellPolygon> = polygons(&grid, cell_size).unwrap();
+        // every edge of a cell away from the border is shared with another cell, leaving no gaps around it
+        for polygon in polygons.iter().filter(|p| p.coords.x > 0 && p.coords.y > 0 && p.coords.x < grid.width - 1 && p.coords.y < grid.height - 1) {
+            for edge in edges(polygon) {
+                assert!(polygons.iter().any(|other| other.coords != polygon.coords && is_shared(&edge, other)), "gap beside {:?}", polygon.coords);
+            }
+        }
+        // an octagon and a square together cover exactly two cell_size squares, so the tiling has no overlaps
+        let octagon = polygons.iter().find(|p| p.vertices.len() == 8).unwrap();
+        let square = polygons.iter().find(|p| p.vertices.len() == 4).unwrap();
+        assert!((octagon.area() + square.area() - 2.0 * cell_size * cell_size).abs() < EPSILON);
+        assert!(polygons.iter().flat_map(|p| p.vertices.iter()).all(|v| v.x >= -EPSILON && v.y >= -EPSILON));
+    }
+
+    #[test]
+    fn rejects_other_maze_types() {
+        let grid = Grid::try_from(r#"{ "maze_type": "Orthogonal", "width": 4, "height": 4, "algorithm": "Prims" }"#).unwrap();
+        assert!(matches!(polygons(&grid, 10.0), Err(Error::GeometryUnavailableForMazeType { .. })));
+    }
+}
@@ -1,9 +1,54 @@
+use crate::cell::CellOrientation;
+use crate::cell::DeltaShape;
 use crate::cell::Coordinates;
 use crate::cell::MazeType;
-use crate::algorithms::MazeAlgorithm;
+use crate::cell::Rect;
+use crate::accessibility::Guardrails;
+use crate::constraints::Constraints;
+use crate::algorithms::{AlgorithmParams, MazeAlgorithm};
+use crate::direction::{Direction, MovePolicy};
+use crate::error::Error;
+use crate::mask::Mask;
+use crate::overlay::Overlay;
+use crate::rooms;
+use crate::symmetry::Symmetry;
+#[cfg(feature = "serde")]
 use serde::{ Serialize, Deserialize };
+use crate::prelude::*;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// How a maze's start and goal cells are chosen.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Endpoints {
+    /// Use the requested start and goal, or sensible defaults when they are not specified.
+    Default,
+    /// Place the start and goal at the two ends of the maze's longest path, overriding any requested start and goal.
+    LongestPath,
+}
+
+/// Cells to visit in "collect all" mode, where the maze is completed by visiting every target cell, Pac-Man
+/// style, rather than by reaching the goal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Collect {
+    /// Every cell of the maze is a target.
+    AllCells,
+    /// The specified cells are targets.
+    Cells(Vec<Coordinates>),
+}
+
+/// A place on the maze's outer border for an entrance or exit, e.g. the fourth row down the left side.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BorderEdge {
+    /// The side of the maze: `Up` for the top, `Down` for the bottom, `Left` or `Right`.
+    pub side: Direction,
+    /// The column along the top or bottom, or the row along the left or right side, counting from zero.
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MazeRequest {
     pub maze_type: MazeType,
     pub width: usize,
@@ -12,6 +57,460 @@ pub struct MazeRequest {
     pub start: Option<Coordinates>,
     pub goal: Option<Coordinates>,
     pub capture_steps: Option<bool>,
+    pub distance_bands: Option<usize>,
+    pub endpoints: Option<Endpoints>,
+    pub heatmap: Option<bool>,
+    pub algorithm_params: Option<AlgorithmParams>,
+    /// Number of stacked Orthogonal layers, each `width` by `height`, joined by stairs (defaults to 1).
+    /// Layers are stored one below the other, so a multi-layer maze's cells span `height * layers` rows.
+    pub layers: Option<usize>,
+    pub collect: Option<Collect>,
+    /// Enables fog of war, with the user seeing this many cells down each open corridor.
+    pub visibility_radius: Option<usize>,
+    /// Knocks out the outer wall of the start and goal cells, as entrance and exit openings on the border.
+    pub open_exits: Option<bool>,
+    /// How moves in a direction with no open passage are resolved (defaults to `MovePolicy::Fallback`).
+    pub move_policy: Option<MovePolicy>,
+    /// Seed recorded on the generated grid, from which seeded placement such as puzzles and features derives.
+    /// A random seed is chosen when omitted.
+    pub seed: Option<u64>,
+    /// Passages the maze must have and walls it must keep, whichever algorithm generates it.
+    pub constraints: Option<Constraints>,
+    /// Mirrors one sector of the maze onto the others (Orthogonal mazes of a single layer only, and not
+    /// together with `constraints`).
+    pub symmetry: Option<Symmetry>,
+    /// Places the start on the border, opening the outer wall there, instead of specifying `start`.
+    pub start_edge: Option<BorderEdge>,
+    /// Places the goal on the border, opening the outer wall there, instead of specifying `goal`.
+    pub goal_edge: Option<BorderEdge>,
+    /// Gives every cell a random weight from 1 up to this maximum, for weighted pathfinding.
+    pub weight_noise: Option<u32>,
+    /// Lays a coarse maze of zones over the maze, for district structure (Orthogonal mazes of a single layer only,
+    /// and not together with `symmetry` or `constraints`).
+    pub overlay: Option<Overlay>,
+    /// Rectangular blocks of cells merged into open chambers before the maze is generated, which generates each
+    /// room as a single cell whose neighbors are all the cells around the room (Orthogonal mazes of a single layer
+    /// only, with an algorithm which `supports_rooms`, and not together with `constraints`, `symmetry`, `overlay`
+    /// or `capture_steps`).
+    pub rooms: Option<Vec<Rect>>,
+    /// The orientation of a Delta maze's top-left triangle, `Inverted` to start row 0 with a triangle pointing
+    /// down (defaults to `Normal`, pointing up).
+    pub delta_orientation: Option<CellOrientation>,
+    /// The outline of a Delta maze, `Triangle` for a triangle pointing up (or down when `delta_orientation` is
+    /// `Inverted`), `2 * height - 1` triangles wide (defaults to `Rectangle`). A triangle leaves positions
+    /// without cells, so it needs an algorithm which can generate Rhombic mazes.
+    pub delta_shape: Option<DeltaShape>,
+    /// An outline leaving some positions without cells, such as a cave, for an organic-shaped maze (single layer
+    /// mazes only, generated by an algorithm which can generate Rhombic mazes, and not together with `symmetry`,
+    /// `overlay` or `rooms`). Default endpoints move to the nearest cells the outline keeps.
+    pub mask: Option<Mask>,
+    /// Limits on the maze's corridors, such as the longest straight corridor, kept by moving passages once the
+    /// maze is generated (not together with `constraints`, `rooms`, `symmetry` or `overlay`).
+    pub guardrails: Option<Guardrails>,
+    /// Generates a Kruskals maze as `ParallelKruskals`, carving strips of the maze on several threads when the
+    /// `rayon` feature is enabled. Other algorithms cannot be generated in parallel.
+    pub parallel: Option<bool>,
+}
+
+impl MazeRequest {
+    /// Start building a request in Rust rather than JSON.
+    pub fn builder() -> MazeRequestBuilder {
+        MazeRequestBuilder::default()
+    }
+
+    /// Check the request for problems which would otherwise only surface part way through maze generation,
+    /// such as an algorithm which cannot generate the requested maze type.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.width == 0 || self.height == 0 {
+            return Err(Error::InvalidGridDimensions { width: self.width, height: self.height });
+        }
+        if self.capture_steps.unwrap_or_default() && (self.width > 100 || self.rows() > 100) {
+            return Err(Error::GridDimensionsExceedLimitForCaptureSteps { width: self.width, height: self.rows() });
+        }
+        if self.parallel.unwrap_or_default() && !matches!(self.algorithm, MazeAlgorithm::Kruskals | MazeAlgorithm::ParallelKruskals) {
+            return Err(Error::ParallelGenerationUnavailable { algorithm: self.algorithm });
+        }
+        let algorithm = self.generation_algorithm();
+        if !algorithm.supports(self.maze_type) {
+            return Err(Error::AlgorithmUnavailableForMazeType { algorithm, maze_type: self.maze_type });
+        }
+        let cells = self.width.saturating_mul(self.rows());
+        let limits = algorithm.recommended_limits();
+        if !limits.allows(cells) {
+            return Err(Error::MazeTooLargeForAlgorithm {
+                algorithm,
+                cells,
+                max_cells: limits.max_cells,
+                alternatives: MazeAlgorithm::alternatives_for(self.maze_type, cells),
+            });
+        }
+        match self.layers {
+            Some(0) => return Err(Error::InvalidLayers { reason: "a maze needs at least 1 layer".to_string() }),
+            Some(layers) if layers > 1 && self.maze_type != MazeType::Orthogonal => {
+                return Err(Error::InvalidLayers { reason: format!("only Orthogonal mazes can have multiple layers, not {:?}", self.maze_type) });
+            }
+            Some(layers) if layers > 1 && !algorithm.supports_layers() => {
+                return Err(Error::InvalidLayers { reason: format!("{:?} cannot generate multi-layer mazes", algorithm) });
+            }
+            _ => {}
+        }
+        if self.symmetry.is_some() && (self.maze_type != MazeType::Orthogonal || self.layers.unwrap_or(1) > 1) {
+            return Err(Error::InvalidSymmetry {
+                reason: format!("only single layer Orthogonal mazes can be made symmetric, not {:?}", self.maze_type),
+            });
+        }
+        if self.symmetry.is_some() && self.constraints.is_some() {
+            // constraints rework the maze after it is mirrored, which would break the mirroring
+            return Err(Error::InvalidSymmetry { reason: "symmetry cannot be combined with constraints".to_string() });
+        }
+        if let Some(overlay) = self.overlay {
+            overlay.validate(self.maze_type, self.width, self.height, self.layers.unwrap_or(1))?;
+            // the maze is rebuilt under the overlay after it is mirrored, and constraints rework it afterwards,
+            // either of which would break the other's guarantee
+            if self.symmetry.is_some() || self.constraints.is_some() {
+                return Err(Error::InvalidOverlay { reason: "an overlay cannot be combined with symmetry or constraints".to_string() });
+            }
+        }
+        if let Some(rooms) = &self.rooms {
+            rooms::validate(rooms, self.maze_type, self.width, self.height, self.layers.unwrap_or(1))?;
+            if !rooms.is_empty() && (self.constraints.is_some() || self.symmetry.is_some() || self.overlay.is_some()) {
+                return Err(Error::InvalidRoom { reason: "rooms cannot be combined with constraints, symmetry or an overlay".to_string() });
+            }
+            // the maze is generated with each room contracted into a single cell, so the steps captured would not
+            // show the rooms
+            if !rooms.is_empty() && self.capture_steps.unwrap_or_default() {
+                return Err(Error::InvalidRoom { reason: "rooms cannot be combined with captured generation steps".to_string() });
+            }
+            if !rooms.is_empty() && !algorithm.supports_rooms() {
+                return Err(Error::InvalidRoom { reason: format!("{:?} cannot generate a maze around rooms", algorithm) });
+            }
+        }
+        if let Some(guardrails) = self.guardrails {
+            guardrails.validate()?;
+            // guardrails move passages, breaking the mirroring of symmetry and the single doorways of an overlay
+            let rooms = self.rooms.as_ref().is_some_and(|rooms| !rooms.is_empty());
+            if self.constraints.is_some() || rooms || self.symmetry.is_some() || self.overlay.is_some() {
+                return Err(Error::InvalidGuardrails {
+                    reason: "guardrails cannot be combined with constraints, rooms, symmetry or an overlay".to_string(),
+                });
+            }
+        }
+        if (self.delta_orientation.is_some() || self.delta_shape.is_some()) && self.maze_type != MazeType::Delta {
+            return Err(Error::InvalidCellForNonDeltaMaze { cell_maze_type: self.maze_type });
+        }
+        if self.delta_shape == Some(DeltaShape::Triangle) {
+            if self.width + 1 != 2 * self.height {
+                return Err(Error::InvalidDeltaShape {
+                    reason: format!("a triangle {} rows tall must be {} triangles wide, not {}", self.height, 2 * self.height - 1, self.width),
+                });
+            }
+            // the positions outside the triangle have no cells, as in a Rhombic maze
+            if !algorithm.supports(MazeType::Rhombic) {
+                return Err(Error::InvalidDeltaShape { reason: format!("{:?} cannot generate triangle-shaped mazes", algorithm) });
+            }
+        }
+        if let Some(mask) = self.mask {
+            mask.validate()?;
+            // as in a Rhombic maze, the positions outside the mask have no cells
+            if !algorithm.supports(MazeType::Rhombic) {
+                return Err(Error::InvalidMask { reason: format!("{:?} cannot generate masked mazes", algorithm) });
+            }
+            let rooms = self.rooms.as_ref().is_some_and(|rooms| !rooms.is_empty());
+            if self.layers.unwrap_or(1) > 1 || self.symmetry.is_some() || self.overlay.is_some() || rooms {
+                return Err(Error::InvalidMask {
+                    reason: "a mask cannot be combined with layers, symmetry, an overlay or rooms".to_string(),
+                });
+            }
+        }
+        if let Some(params) = &self.algorithm_params {
+            params.validate(self.algorithm, self.width, self.height)?;
+        }
+        if let Some(start) = self.start {
+            if !self.is_cell(start) {
+                return Err(Error::InvalidStartCoordinates { coordinates: start });
+            }
+        }
+        if let Some(goal) = self.goal {
+            if !self.is_cell(goal) {
+                return Err(Error::InvalidGoalCoordinates { coordinates: goal });
+            }
+        }
+        for (edge, conflicting) in [(self.start_edge, self.start.is_some()), (self.goal_edge, self.goal.is_some())] {
+            let Some(edge) = edge else {
+                continue;
+            };
+            if conflicting {
+                return Err(Error::InvalidBorderEdge { reason: "an endpoint cannot be given both as a cell and as a border edge".to_string() });
+            }
+            let length = match edge.side {
+                Direction::Up | Direction::Down => self.width,
+                Direction::Left | Direction::Right => self.rows(),
+                side => return Err(Error::InvalidBorderEdge { reason: format!("{} is not a side of the maze", side) }),
+            };
+            if edge.offset >= length {
+                return Err(Error::InvalidBorderEdge { reason: format!("offset {} lies beyond the {} side", edge.offset, edge.side) });
+            }
+        }
+        if let Some(bands) = self.distance_bands {
+            if bands == 0 {
+                return Err(Error::InvalidDistanceBandCount { bands });
+            }
+        }
+        if self.weight_noise == Some(0) {
+            return Err(Error::InvalidWeight { reason: "the maximum weight must be at least 1".to_string() });
+        }
+        if let Some(Collect::Cells(targets)) = &self.collect {
+            if let Some(&coordinates) = targets.iter().find(|coords| !self.is_cell(**coords)) {
+                return Err(Error::InvalidTargetCoordinates { coordinates });
+            }
+        }
+        Ok(())
+    }
+
+    /// The algorithm generating the maze: `ParallelKruskals` in place of `Kruskals` when `parallel` is set,
+    /// otherwise the requested algorithm.
+    pub fn generation_algorithm(&self) -> MazeAlgorithm {
+        match self.algorithm {
+            MazeAlgorithm::Kruskals if self.parallel.unwrap_or_default() => MazeAlgorithm::ParallelKruskals,
+            algorithm => algorithm,
+        }
+    }
+
+    /// Total number of rows in the requested grid, across all layers.
+    pub fn rows(&self) -> usize {
+        self.height * self.layers.unwrap_or(1).max(1)
+    }
+
+    // mirrors Grid::has_cell, without having to build the grid
+    fn is_cell(&self, coords: Coordinates) -> bool {
+        if coords.x >= self.width || coords.y >= self.rows() {
+            return false;
+        }
+        match self.maze_type {
+            MazeType::Rhombic => (coords.x + coords.y).is_multiple_of(2),
+            MazeType::Delta => self.delta_shape.unwrap_or_default().contains(self.delta_orientation.unwrap_or_default(), self.height, coords),
+            _ => true,
+        }
+    }
+}
+
+/// Fluent construction of a `MazeRequest`, e.g.
+/// `MazeRequest::builder().maze_type(MazeType::Sigma).size(20, 20).algorithm(MazeAlgorithm::Prims).build()`.
+/// The maze type, size and algorithm are required; every other option defaults as it does when omitted from JSON.
+#[derive(Debug, Clone, Default)]
+pub struct MazeRequestBuilder {
+    maze_type: Option<MazeType>,
+    size: Option<(usize, usize)>,
+    algorithm: Option<MazeAlgorithm>,
+    start: Option<Coordinates>,
+    goal: Option<Coordinates>,
+    capture_steps: Option<bool>,
+    distance_bands: Option<usize>,
+    endpoints: Option<Endpoints>,
+    heatmap: Option<bool>,
+    algorithm_params: Option<AlgorithmParams>,
+    layers: Option<usize>,
+    collect: Option<Collect>,
+    visibility_radius: Option<usize>,
+    open_exits: Option<bool>,
+    move_policy: Option<MovePolicy>,
+    seed: Option<u64>,
+    constraints: Option<Constraints>,
+    symmetry: Option<Symmetry>,
+    start_edge: Option<BorderEdge>,
+    goal_edge: Option<BorderEdge>,
+    weight_noise: Option<u32>,
+    overlay: Option<Overlay>,
+    rooms: Option<Vec<Rect>>,
+    delta_orientation: Option<CellOrientation>,
+    delta_shape: Option<DeltaShape>,
+    mask: Option<Mask>,
+    guardrails: Option<Guardrails>,
+    parallel: Option<bool>,
+}
+
+impl MazeRequestBuilder {
+    pub fn maze_type(mut self, maze_type: MazeType) -> Self {
+        self.maze_type = Some(maze_type);
+        self
+    }
+
+    /// Width and height of the maze, the height counting the rows of a single layer.
+    pub fn size(mut self, width: usize, height: usize) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    pub fn algorithm(mut self, algorithm: MazeAlgorithm) -> Self {
+        self.algorithm = Some(algorithm);
+        self
+    }
+
+    pub fn start(mut self, start: Coordinates) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    pub fn goal(mut self, goal: Coordinates) -> Self {
+        self.goal = Some(goal);
+        self
+    }
+
+    pub fn capture_steps(mut self, capture_steps: bool) -> Self {
+        self.capture_steps = Some(capture_steps);
+        self
+    }
+
+    pub fn distance_bands(mut self, bands: usize) -> Self {
+        self.distance_bands = Some(bands);
+        self
+    }
+
+    pub fn endpoints(mut self, endpoints: Endpoints) -> Self {
+        self.endpoints = Some(endpoints);
+        self
+    }
+
+    pub fn heatmap(mut self, heatmap: bool) -> Self {
+        self.heatmap = Some(heatmap);
+        self
+    }
+
+    pub fn algorithm_params(mut self, params: AlgorithmParams) -> Self {
+        self.algorithm_params = Some(params);
+        self
+    }
+
+    pub fn layers(mut self, layers: usize) -> Self {
+        self.layers = Some(layers);
+        self
+    }
+
+    pub fn collect(mut self, collect: Collect) -> Self {
+        self.collect = Some(collect);
+        self
+    }
+
+    pub fn visibility_radius(mut self, radius: usize) -> Self {
+        self.visibility_radius = Some(radius);
+        self
+    }
+
+    pub fn open_exits(mut self, open_exits: bool) -> Self {
+        self.open_exits = Some(open_exits);
+        self
+    }
+
+    pub fn move_policy(mut self, policy: MovePolicy) -> Self {
+        self.move_policy = Some(policy);
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn constraints(mut self, constraints: Constraints) -> Self {
+        self.constraints = Some(constraints);
+        self
+    }
+
+    pub fn symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = Some(symmetry);
+        self
+    }
+
+    pub fn start_edge(mut self, edge: BorderEdge) -> Self {
+        self.start_edge = Some(edge);
+        self
+    }
+
+    pub fn goal_edge(mut self, edge: BorderEdge) -> Self {
+        self.goal_edge = Some(edge);
+        self
+    }
+
+    pub fn weight_noise(mut self, max_weight: u32) -> Self {
+        self.weight_noise = Some(max_weight);
+        self
+    }
+
+    pub fn overlay(mut self, overlay: Overlay) -> Self {
+        self.overlay = Some(overlay);
+        self
+    }
+
+    pub fn rooms(mut self, rooms: Vec<Rect>) -> Self {
+        self.rooms = Some(rooms);
+        self
+    }
+
+    pub fn delta_orientation(mut self, orientation: CellOrientation) -> Self {
+        self.delta_orientation = Some(orientation);
+        self
+    }
+
+    pub fn delta_shape(mut self, shape: DeltaShape) -> Self {
+        self.delta_shape = Some(shape);
+        self
+    }
+
+    pub fn mask(mut self, mask: Mask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    pub fn guardrails(mut self, guardrails: Guardrails) -> Self {
+        self.guardrails = Some(guardrails);
+        self
+    }
+
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = Some(parallel);
+        self
+    }
+
+    /// The request, failing if a required option is missing or the request is invalid (see
+    /// `MazeRequest::validate`).
+    pub fn build(self) -> Result<MazeRequest, Error> {
+        let maze_type = self.maze_type.ok_or(Error::IncompleteRequest { field: "maze type" })?;
+        let (width, height) = self.size.ok_or(Error::IncompleteRequest { field: "size" })?;
+        let algorithm = self.algorithm.ok_or(Error::IncompleteRequest { field: "algorithm" })?;
+        let request = MazeRequest {
+            maze_type,
+            width,
+            height,
+            algorithm,
+            start: self.start,
+            goal: self.goal,
+            capture_steps: self.capture_steps,
+            distance_bands: self.distance_bands,
+            endpoints: self.endpoints,
+            heatmap: self.heatmap,
+            algorithm_params: self.algorithm_params,
+            layers: self.layers,
+            collect: self.collect,
+            visibility_radius: self.visibility_radius,
+            open_exits: self.open_exits,
+            move_policy: self.move_policy,
+            seed: self.seed,
+            constraints: self.constraints,
+            symmetry: self.symmetry,
+            start_edge: self.start_edge,
+            goal_edge: self.goal_edge,
+            weight_noise: self.weight_noise,
+            overlay: self.overlay,
+            rooms: self.rooms,
+            delta_orientation: self.delta_orientation,
+            delta_shape: self.delta_shape,
+            mask: self.mask,
+            guardrails: self.guardrails,
+            parallel: self.parallel,
+        };
+        request.validate()?;
+        Ok(request)
+    }
 }
 
 #[cfg(test)]
@@ -29,6 +528,28 @@ mod tests {
             start: Some(Coordinates { x: 0, y: 0 }),
             goal: Some(Coordinates { x: 9, y: 9 }),
             capture_steps: None,
+            distance_bands: None,
+            endpoints: None,
+            heatmap: None,
+            algorithm_params: None,
+            layers: None,
+            collect: None,
+            visibility_radius: None,
+            open_exits: None,
+            move_policy: None,
+            seed: None,
+            constraints: None,
+            symmetry: None,
+            start_edge: None,
+            goal_edge: None,
+            weight_noise: None,
+            overlay: None,
+            rooms: None,
+            delta_orientation: None,
+            delta_shape: None,
+            mask: None,
+            guardrails: None,
+            parallel: None,
         };
 
         let json = serde_json::to_string(&request).expect("Failed to serialize MazeRequest");
@@ -70,6 +591,28 @@ mod tests {
             start: Some(Coordinates { x: 0, y: 0 }),
             goal: Some(Coordinates { x: 9, y: 9 }),
             capture_steps: None,
+            distance_bands: None,
+            endpoints: None,
+            heatmap: None,
+            algorithm_params: None,
+            layers: None,
+            collect: None,
+            visibility_radius: None,
+            open_exits: None,
+            move_policy: None,
+            seed: None,
+            constraints: None,
+            symmetry: None,
+            start_edge: None,
+            goal_edge: None,
+            weight_noise: None,
+            overlay: None,
+            rooms: None,
+            delta_orientation: None,
+            delta_shape: None,
+            mask: None,
+            guardrails: None,
+            parallel: None,
         };
 
         let json = serde_json::to_string(&request).expect("Failed to serialize MazeRequest");
@@ -111,6 +654,28 @@ mod tests {
             start: Some(Coordinates { x: 0, y: 0 }),
             goal: Some(Coordinates { x: 9, y: 9 }),
             capture_steps: None,
+            distance_bands: None,
+            endpoints: None,
+            heatmap: None,
+            algorithm_params: None,
+            layers: None,
+            collect: None,
+            visibility_radius: None,
+            open_exits: None,
+            move_policy: None,
+            seed: None,
+            constraints: None,
+            symmetry: None,
+            start_edge: None,
+            goal_edge: None,
+            weight_noise: None,
+            overlay: None,
+            rooms: None,
+            delta_orientation: None,
+            delta_shape: None,
+            mask: None,
+            guardrails: None,
+            parallel: None,
         };
 
         let json = serde_json::to_string(&request).expect("Failed to serialize MazeRequest");
@@ -127,6 +692,28 @@ mod tests {
             start: Some(Coordinates { x: 0, y: 0 }),
             goal: Some(Coordinates { x: 9, y: 9 }),
             capture_steps: None,
+            distance_bands: None,
+            endpoints: None,
+            heatmap: None,
+            algorithm_params: None,
+            layers: None,
+            collect: None,
+            visibility_radius: None,
+            open_exits: None,
+            move_policy: None,
+            seed: None,
+            constraints: None,
+            symmetry: None,
+            start_edge: None,
+            goal_edge: None,
+            weight_noise: None,
+            overlay: None,
+            rooms: None,
+            delta_orientation: None,
+            delta_shape: None,
+            mask: None,
+            guardrails: None,
+            parallel: None,
         };
 
         let json = serde_json::to_string(&request).expect("Failed to serialize MazeRequest");
@@ -179,4 +766,85 @@ mod tests {
         assert_eq!(request.goal, Some(Coordinates { x: 9, y: 9 }));
     }
 
-}
\ No newline at end of file
+
+    #[test]
+    fn test_validate_rejects_problem_requests() {
+        let request = |json: &str| serde_json::from_str::<MazeRequest>(json).expect("Failed to deserialize MazeRequest");
+        let valid = request(r#"{ "maze_type": "Orthogonal", "width": 10, "height": 10, "algorithm": "BinaryTree" }"#);
+        assert!(valid.validate().is_ok());
+
+        let unsupported = request(r#"{ "maze_type": "Sigma", "width": 10, "height": 10, "algorithm": "Ellers" }"#);
+        assert!(matches!(unsupported.validate(), Err(Error::AlgorithmUnavailableForMazeType { .. })));
+
+        let empty = request(r#"{ "maze_type": "Delta", "width": 0, "height": 10, "algorithm": "Prims" }"#);
+        assert!(matches!(empty.validate(), Err(Error::InvalidGridDimensions { width: 0, height: 10 })));
+
+        let out_of_bounds = request(r#"{ "maze_type": "Orthogonal", "width": 10, "height": 10, "algorithm": "Prims", "goal": { "x": 10, "y": 9 } }"#);
+        assert!(matches!(out_of_bounds.validate(), Err(Error::InvalidGoalCoordinates { .. })));
+
+        let missing_rhombic_cell = request(r#"{ "maze_type": "Rhombic", "width": 10, "height": 10, "algorithm": "Prims", "start": { "x": 0, "y": 1 } }"#);
+        assert!(matches!(missing_rhombic_cell.validate(), Err(Error::InvalidStartCoordinates { .. })));
+
+        let no_bands = request(r#"{ "maze_type": "Orthogonal", "width": 10, "height": 10, "algorithm": "Prims", "distance_bands": 0 }"#);
+        assert!(matches!(no_bands.validate(), Err(Error::InvalidDistanceBandCount { bands: 0 })));
+
+        let layered = request(r#"{ "maze_type": "Orthogonal", "width": 10, "height": 10, "layers": 3, "algorithm": "Prims", "goal": { "x": 9, "y": 29 } }"#);
+        assert!(layered.validate().is_ok());
+
+        let layered_hexagons = request(r#"{ "maze_type": "Sigma", "width": 10, "height": 10, "layers": 3, "algorithm": "Prims" }"#);
+        assert!(matches!(layered_hexagons.validate(), Err(Error::InvalidLayers { .. })));
+
+        let layered_binary_tree = request(r#"{ "maze_type": "Orthogonal", "width": 10, "height": 10, "layers": 3, "algorithm": "BinaryTree" }"#);
+        assert!(matches!(layered_binary_tree.validate(), Err(Error::InvalidLayers { .. })));
+
+        let missing_target = request(r#"{ "maze_type": "Orthogonal", "width": 10, "height": 10, "algorithm": "Prims", "collect": { "Cells": [{ "x": 3, "y": 3 }, { "x": 10, "y": 3 }] } }"#);
+        assert!(matches!(missing_target.validate(), Err(Error::InvalidTargetCoordinates { .. })));
+
+        let parallel_prims = request(r#"{ "maze_type": "Orthogonal", "width": 10, "height": 10, "algorithm": "Prims", "parallel": true }"#);
+        assert!(matches!(parallel_prims.validate(), Err(Error::ParallelGenerationUnavailable { algorithm: MazeAlgorithm::Prims })));
+    }
+
+    #[test]
+    fn parallel_flag_generates_kruskals_in_parallel() {
+        let request = MazeRequest::builder()
+            .maze_type(MazeType::Orthogonal)
+            .size(40, 40)
+            .algorithm(MazeAlgorithm::Kruskals)
+            .parallel(true)
+            .build()
+            .unwrap();
+        assert_eq!(request.generation_algorithm(), MazeAlgorithm::ParallelKruskals);
+        let grid = crate::grid::Grid::try_from(request).unwrap();
+        assert!(grid.is_perfect_maze().unwrap());
+
+        let serial = MazeRequest::builder().maze_type(MazeType::Orthogonal).size(40, 40).algorithm(MazeAlgorithm::Kruskals).build().unwrap();
+        assert_eq!(serial.generation_algorithm(), MazeAlgorithm::Kruskals);
+    }
+
+    #[test]
+    fn builder_matches_json() {
+        let built = MazeRequest::builder()
+            .maze_type(MazeType::Sigma)
+            .size(20, 20)
+            .algorithm(MazeAlgorithm::Prims)
+            .seed(42)
+            .open_exits(true)
+            .build()
+            .unwrap();
+        let parsed: MazeRequest = serde_json::from_str(
+            r#"{ "maze_type": "Sigma", "width": 20, "height": 20, "algorithm": "Prims", "seed": 42, "open_exits": true }"#,
+        )
+        .unwrap();
+        assert_eq!(built, parsed);
+        assert_eq!(crate::grid::Grid::try_from(built).unwrap().seed, 42);
+    }
+
+    #[test]
+    fn builder_rejects_incomplete_and_invalid_requests() {
+        let missing = MazeRequest::builder().maze_type(MazeType::Orthogonal).algorithm(MazeAlgorithm::Prims).build();
+        assert!(matches!(missing, Err(Error::IncompleteRequest { field: "size" })));
+
+        let invalid = MazeRequest::builder().maze_type(MazeType::Delta).size(10, 10).algorithm(MazeAlgorithm::BinaryTree).build();
+        assert!(matches!(invalid, Err(Error::AlgorithmUnavailableForMazeType { .. })));
+    }
+}
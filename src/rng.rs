@@ -0,0 +1,63 @@
+//! The random numbers maze generation draws from.
+
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// The random number generator a grid owns, which every generation algorithm draws from rather than from
+/// its own source, so that the grid's seed determines every random choice made while generating.
+///
+/// The ChaCha8 algorithm is used rather than `rand::rngs::StdRng`, whose algorithm may change between
+/// versions of `rand`, so that a seed makes the same choices across versions of mazer and on every platform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridRng(ChaCha8Rng);
+
+impl GridRng {
+    /// A generator which always draws the same numbers from the same seed.
+    pub fn seed_from_u64(seed: u64) -> Self {
+        GridRng(ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// Random unsigned integer less than the upper bound, which must be greater than 0.
+    pub fn bounded_usize(&mut self, upper_bound: usize) -> usize {
+        // drawn as a u64, so that the choice doesn't depend on the platform's pointer width
+        self.0.gen_range(0..upper_bound as u64) as usize
+    }
+
+    /// An independent generator seeded from this one, for use while the grid which owns this one is borrowed.
+    pub fn fork(&mut self) -> GridRng {
+        GridRng::seed_from_u64(self.0.gen())
+    }
+}
+
+impl RngCore for GridRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_draws_same_numbers() {
+        let mut a = GridRng::seed_from_u64(42);
+        let mut b = GridRng::seed_from_u64(42);
+        let draws = |rng: &mut GridRng| (0..100).map(|_| rng.bounded_usize(1000)).collect::<Vec<_>>();
+        assert_eq!(draws(&mut a), draws(&mut b));
+        assert_eq!(a.fork(), b.fork());
+        assert_ne!(draws(&mut GridRng::seed_from_u64(43)), draws(&mut GridRng::seed_from_u64(42)));
+    }
+}
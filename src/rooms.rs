@@ -0,0 +1,299 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use rand::seq::SliceRandom;
+
+use crate::algorithms::kruskals::{self, DisjointSet};
+use crate::cell::{Coordinates, MazeType, Rect};
+use crate::direction::Direction;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::prelude::*;
+
+/// Check that rooms can be merged into a maze of the given type and dimensions: only single layer Orthogonal
+/// mazes can have rooms, and each room must lie within the maze, span at least two cells, and not overlap
+/// another room.
+pub fn validate(rooms: &[Rect], maze_type: MazeType, width: usize, height: usize, layers: usize) -> Result<(), Error> {
+    let invalid = |reason: String| Err(Error::InvalidRoom { reason });
+    if rooms.is_empty() {
+        return Ok(());
+    }
+    if maze_type != MazeType::Orthogonal || layers > 1 {
+        return invalid(format!("only single layer Orthogonal mazes can have rooms, not {:?}", maze_type));
+    }
+    for (index, room) in rooms.iter().enumerate() {
+        if room.width * room.height < 2 {
+            return invalid(format!("the room at {} must span at least 2 cells", Coordinates { x: room.x, y: room.y }));
+        }
+        if room.x + room.width > width || room.y + room.height > height {
+            return invalid(format!(
+                "the {}x{} room at {} lies beyond the {}x{} maze",
+                room.width, room.height, Coordinates { x: room.x, y: room.y }, width, height,
+            ));
+        }
+        let overlaps = |other: &Rect| {
+            room.x < other.x + other.width && other.x < room.x + room.width && room.y < other.y + other.height && other.y < room.y + room.height
+        };
+        if let Some(other) = rooms[..index].iter().find(|other| overlaps(other)) {
+            return invalid(format!(
+                "the rooms at {} and {} overlap",
+                Coordinates { x: other.x, y: other.y }, Coordinates { x: room.x, y: room.y },
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Merge each room of a generated maze into a single open chamber. Every cell of a room is linked to its
+/// neighbors within the room, and of the maze's passages into and out of the room, only as many are kept as
+/// join it to the rest of the maze without closing a loop, so the maze stays perfect with each room taken as
+/// one cell.
+pub fn merge(grid: &mut Grid, rooms: &[Rect]) -> Result<(), Error> {
+    validate(rooms, grid.maze_type, grid.width, grid.height, grid.layers)?;
+    let mut rng = grid.forked_rng();
+    let room_of = |coords: Coordinates| rooms.iter().position(|room| room.contains(coords));
+    let within = |&(a, b): &(Coordinates, Coordinates)| room_of(a).is_some() && room_of(a) == room_of(b);
+
+    // each room starts as a single set, so any passage leading back into a room already reached closes a loop
+    let mut tree = DisjointSet::new();
+    for cell in grid.iter_cells() {
+        tree.make_set(cell.coords);
+    }
+    let edges = kruskals::edges(grid);
+    for &(a, b) in edges.iter().filter(|edge| within(edge)) {
+        tree.union(a, b);
+    }
+
+    // passages are dropped at random, so that rooms are entered from every side
+    let mut passages: Vec<_> = edges
+        .iter()
+        .copied()
+        .filter(|edge| !within(edge))
+        .filter(|(a, b)| grid.get(*a).is_ok_and(|cell| cell.linked.contains(b)))
+        .collect();
+    passages.shuffle(&mut rng);
+    for (a, b) in passages {
+        if !tree.union(a, b) {
+            grid.unlink(a, b)?;
+        }
+    }
+    for (a, b) in edges.into_iter().filter(within) {
+        grid.link(a, b)?;
+    }
+    Ok(())
+}
+
+// The first cell of the room containing a cell, into which the room is contracted, or the cell itself when it lies
+// in no room
+fn first_cell(rooms: &[Rect], coords: Coordinates) -> Coordinates {
+    rooms.iter().find(|room| room.contains(coords)).map_or(coords, |room| Coordinates { x: room.x, y: room.y })
+}
+
+/// Contract each room into its first (top-left) cell before the maze is generated, so the maze is generated with
+/// each room as a single cell. The room's other cells are left out of the grid, as though masked, and its first
+/// cell takes every neighbor of the room as one of its `room_neighbors`, while cells beside the room lead into
+/// its first cell. A start or goal within a room moves to its first cell. Returns the start and goal, for
+/// `expand` to restore once the maze is generated.
+pub(crate) fn contract(grid: &mut Grid, rooms: &[Rect]) -> Result<(Coordinates, Coordinates), Error> {
+    validate(rooms, grid.maze_type, grid.width, grid.height, grid.layers)?;
+    let endpoints = (grid.start_coords, grid.goal_coords);
+    let mut directions: HashMap<Coordinates, Vec<(Direction, Coordinates)>> = HashMap::new();
+    let mut room_neighbors: BTreeMap<Coordinates, BTreeSet<Coordinates>> = BTreeMap::new();
+    for cell in grid.iter_cells() {
+        let first = first_cell(rooms, cell.coords);
+        if !rooms.iter().any(|room| room.contains(cell.coords)) {
+            directions.insert(first, cell.neighbors_by_direction.iter().map(|(direction, n)| (direction, first_cell(rooms, n))).collect());
+        } else {
+            let neighbors = room_neighbors.entry(first).or_default();
+            neighbors.extend(cell.neighbors().into_iter().map(|n| first_cell(rooms, n)).filter(|&n| n != first));
+        }
+    }
+
+    grid.mask = Some((0..grid.height).flat_map(|y| (0..grid.width).map(move |x| Coordinates { x, y })).map(|coords| first_cell(rooms, coords) == coords).collect());
+    grid.start_coords = first_cell(rooms, endpoints.0);
+    grid.goal_coords = first_cell(rooms, endpoints.1);
+    grid.rebuild_cells()?;
+    for cell in grid.cells.iter_mut().flatten() {
+        if let Some(neighbors) = room_neighbors.remove(&cell.coords) {
+            cell.neighbors_by_direction.clear();
+            cell.room_neighbors = neighbors.into_iter().collect();
+        } else if let Some(neighbors) = directions.remove(&cell.coords) {
+            cell.set_neighbors(neighbors.into_iter().collect());
+        }
+    }
+    Ok(endpoints)
+}
+
+/// Restore the rooms contracted by `contract` once the maze is generated, along with the start and goal. Every cell
+/// of a room is linked to its neighbors within the room, and each passage carved into a room's first cell opens
+/// instead between the room and the neighbor at a cell of the room chosen at random, so a perfect maze stays
+/// perfect with each room taken as one cell.
+pub(crate) fn expand(grid: &mut Grid, rooms: &[Rect], endpoints: (Coordinates, Coordinates)) -> Result<(), Error> {
+    let passages: BTreeSet<(Coordinates, Coordinates)> = grid
+        .iter_cells()
+        .flat_map(|cell| cell.linked.iter().map(move |n| (cell.coords.min(n), cell.coords.max(n))))
+        .collect();
+    grid.mask = None;
+    (grid.start_coords, grid.goal_coords) = endpoints;
+    grid.rebuild_cells()?;
+
+    // the cells a contracted cell stood for: a whole room for its first cell
+    let span = |coords: Coordinates| match rooms.iter().find(|room| (room.x, room.y) == (coords.x, coords.y)) {
+        Some(room) => (room.y..room.y + room.height).flat_map(|y| (room.x..room.x + room.width).map(move |x| Coordinates { x, y })).collect(),
+        None => vec![coords],
+    };
+    for (a, b) in passages {
+        let b_span = span(b);
+        let doorways: Vec<(Coordinates, Coordinates)> = span(a)
+            .into_iter()
+            .flat_map(|m| grid.get(m).map(|cell| cell.neighbors()).unwrap_or_default().into_iter().map(move |n| (m, n)))
+            .filter(|(_, n)| b_span.contains(n))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        if doorways.is_empty() {
+            return Err(Error::NoValidNeighbor { coordinates: a });
+        }
+        let (m, n) = doorways[grid.bounded_random_usize(doorways.len())];
+        grid.link(m, n)?;
+    }
+    let room_of = |coords: Coordinates| rooms.iter().position(|room| room.contains(coords));
+    for (a, b) in kruskals::edges(grid) {
+        if room_of(a).is_some() && room_of(a) == room_of(b) {
+            grid.link(a, b)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::MazeAlgorithm;
+
+    #[test]
+    fn rooms_are_open_chambers_of_a_perfect_maze() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 12,
+            "height": 10,
+            "algorithm": "RecursiveBacktracker",
+            "rooms": [
+                { "x": 2, "y": 2, "width": 3, "height": 3 },
+                { "x": 7, "y": 5, "width": 4, "height": 2 },
+                { "x": 0, "y": 9, "width": 5, "height": 1 }
+            ]
+        }
+        "#;
+        let grid = Grid::try_from(json).unwrap();
+        assert_eq!(grid.rooms.len(), 3);
+        assert_eq!(grid.room_at(Coordinates { x: 3, y: 3 }), Some(grid.rooms[0]));
+        assert_eq!(grid.room_at(Coordinates { x: 6, y: 6 }), None);
+
+        // no walls within a room
+        for (a, b) in kruskals::edges(&grid) {
+            if grid.room_at(a).is_some() && grid.room_at(a) == grid.room_at(b) {
+                assert!(grid.get(a).unwrap().linked.contains(&b), "{} and {}", a, b);
+            }
+        }
+
+        // taking each room as a single cell leaves a spanning tree
+        let nodes = grid.iter_cells().count() - grid.rooms.iter().map(|room| room.width * room.height - 1).sum::<usize>();
+        let passages = kruskals::edges(&grid)
+            .into_iter()
+            .filter(|(a, b)| grid.get(*a).unwrap().linked.contains(b))
+            .filter(|(a, b)| grid.room_at(*a).is_none() || grid.room_at(*a) != grid.room_at(*b))
+            .count();
+        assert_eq!(passages, nodes - 1);
+        assert_eq!(grid.all_connected_cells(grid.start_coords).len(), grid.iter_cells().count());
+        let (start, goal) = (grid.start_coords, grid.goal_coords);
+        assert!(grid.get_path_to(start.x, start.y, goal.x, goal.y).is_ok());
+
+        let restored: Grid = serde_json::from_str(&serde_json::to_string(&grid).unwrap()).unwrap();
+        assert_eq!(restored.rooms, grid.rooms);
+    }
+
+    #[test]
+    fn rooms_are_contracted_into_single_cells_for_generation() {
+        let mut grid = Grid::new(MazeType::Orthogonal, 6, 5, Coordinates { x: 2, y: 2 }, Coordinates { x: 5, y: 4 }, false).unwrap();
+        let room = Rect { x: 1, y: 1, width: 3, height: 2 };
+        let endpoints = contract(&mut grid, &[room]).unwrap();
+        assert_eq!(endpoints, (Coordinates { x: 2, y: 2 }, Coordinates { x: 5, y: 4 }));
+        assert_eq!(grid.start_coords, Coordinates { x: 1, y: 1 });
+        assert_eq!(grid.iter_cells().count(), 30 - 5);
+        assert!(grid.get(Coordinates { x: 2, y: 1 }).is_err());
+
+        // the first cell neighbors every cell around the room, each of which leads back into it
+        let first = grid.get(Coordinates { x: 1, y: 1 }).unwrap();
+        assert!(first.neighbors_by_direction.is_empty());
+        assert_eq!(first.neighbors().len(), 10);
+        for neighbor in first.neighbors() {
+            assert!(grid.get(neighbor).unwrap().neighbors().contains(&first.coords), "{}", neighbor);
+        }
+        assert_eq!(grid.get(Coordinates { x: 3, y: 3 }).unwrap().neighbors_by_direction.get(&Direction::Up), Some(first.coords));
+
+        MazeAlgorithm::Wilsons.generate(&mut grid).unwrap();
+        expand(&mut grid, &[room], endpoints).unwrap();
+        assert_eq!(grid.start_coords, Coordinates { x: 2, y: 2 });
+        assert_eq!(grid.iter_cells().count(), 30);
+        assert!(grid.iter_cells().all(|cell| cell.room_neighbors.is_empty()));
+        // the room's 6 cells have 7 passages between them, and the maze 24 others joining the room and 24 cells
+        assert_eq!(kruskals::edges(&grid).iter().filter(|(a, b)| grid.get(*a).unwrap().linked.contains(b)).count(), 7 + 24);
+        assert_eq!(grid.all_connected_cells(grid.start_coords).len(), 30);
+    }
+
+    #[test]
+    fn rooms_are_generated_by_algorithms_which_support_them() {
+        for algorithm in MazeAlgorithm::all() {
+            let json = format!(r#"{{
+                "maze_type": "Orthogonal", "width": 10, "height": 8, "algorithm": "{}",
+                "rooms": [{{ "x": 1, "y": 1, "width": 3, "height": 3 }}, {{ "x": 4, "y": 1, "width": 2, "height": 5 }}]
+            }}"#, algorithm.name());
+            match Grid::try_from(json.as_str()) {
+                Ok(grid) => {
+                    assert!(algorithm.supports_rooms(), "{:?}", algorithm);
+                    let inside = |(a, b): &(Coordinates, Coordinates)| grid.room_at(*a).is_some() && grid.room_at(*a) == grid.room_at(*b);
+                    let passages = kruskals::edges(&grid)
+                        .into_iter()
+                        .filter(|(a, b)| grid.get(*a).unwrap().linked.contains(b))
+                        .filter(|edge| !inside(edge))
+                        .count();
+                    // 80 cells, less the 8 and 9 merged into the two rooms, form a tree
+                    assert_eq!(passages, 80 - 8 - 9 - 1, "{:?}", algorithm);
+                    assert_eq!(grid.all_connected_cells(grid.start_coords).len(), 80);
+                    assert!(grid.get(grid.goal_coords).unwrap().on_solution_path);
+                }
+                Err(error) => {
+                    assert!(!algorithm.supports_rooms(), "{:?}: {}", algorithm, error);
+                    assert!(matches!(error, Error::InvalidRoom { .. }));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_rooms_are_rejected() {
+        let room = |x, y, width, height| Rect { x, y, width, height };
+        assert!(validate(&[room(0, 0, 2, 2), room(2, 0, 2, 2)], MazeType::Orthogonal, 8, 8, 1).is_ok());
+        for (rooms, maze_type, layers) in [
+            (vec![room(0, 0, 2, 2)], MazeType::Sigma, 1),
+            (vec![room(0, 0, 2, 2)], MazeType::Orthogonal, 2),
+            (vec![room(3, 3, 1, 1)], MazeType::Orthogonal, 1),
+            (vec![room(6, 6, 3, 2)], MazeType::Orthogonal, 1),
+            (vec![room(0, 0, 3, 3), room(2, 2, 2, 2)], MazeType::Orthogonal, 1),
+        ] {
+            assert!(matches!(validate(&rooms, maze_type, 8, 8, layers), Err(Error::InvalidRoom { .. })), "{:?}", rooms);
+        }
+        let json = r#"{ "maze_type": "Orthogonal", "width": 8, "height": 8, "algorithm": "Prims", "rooms": [{ "x": 7, "y": 7, "width": 2, "height": 2 }] }"#;
+        assert!(matches!(Grid::try_from(json), Err(Error::InvalidRoom { .. })));
+    }
+
+    #[test]
+    fn rooms_cannot_be_combined_with_symmetry_or_overlays() {
+        let with_room = r#""maze_type": "Orthogonal", "width": 12, "height": 12, "algorithm": "Prims", "rooms": [{ "x": 2, "y": 1, "width": 4, "height": 2 }]"#;
+        let symmetric = format!(r#"{{ {}, "symmetry": "Horizontal" }}"#, with_room);
+        assert!(matches!(Grid::try_from(symmetric), Err(Error::InvalidRoom { .. })));
+        // the room crosses the edge between two zones, which would give them several doorways
+        let overlaid = format!(r#"{{ {}, "overlay": {{ "factor": 4, "algorithm": "Kruskals" }} }}"#, with_room);
+        assert!(matches!(Grid::try_from(overlaid), Err(Error::InvalidRoom { .. })));
+    }
+}
@@ -0,0 +1,159 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+
+use crate::direction::Direction;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::handle::{Handle, HandleRegistry};
+use crate::request::MazeRequest;
+
+/// Mazes generated through a server, kept in memory for as long as it runs. A maze's id is its handle.
+type Mazes = Arc<Mutex<HandleRegistry>>;
+
+#[derive(Debug, Deserialize)]
+struct MoveRequest {
+    direction: Direction,
+}
+
+// An error reported to the client as `{"error": message}`
+struct ServerError(StatusCode, String);
+
+impl From<Error> for ServerError {
+    fn from(e: Error) -> Self {
+        let status = match e {
+            Error::InvalidHandle { .. } | Error::StaleHandle { .. } => StatusCode::NOT_FOUND,
+            Error::MoveUnavailable { .. } | Error::DoorLocked { .. } => StatusCode::CONFLICT,
+            Error::SerializationError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        ServerError(status, e.to_string())
+    }
+}
+
+impl IntoResponse for ServerError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // a panic while a maze was held leaves it as consistent as after any failed move
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The service's routes, with empty maze storage:
+///
+/// - `POST /generate` generates a maze from a JSON `MazeRequest`, responding `201 Created` with
+///   `{"id": ..., "maze": ...}`.
+/// - `GET /maze/{id}` responds with the maze.
+/// - `POST /maze/{id}/move` moves the maze's active cell, given `{"direction": "Up"}` or similar, responding
+///   with `{"direction": ..., "maze": ...}` where the direction is the one actually taken.
+///
+/// Failures respond with `{"error": message}`: `404 Not Found` for an unknown id, `409 Conflict` for a move
+/// which is blocked, and `400 Bad Request` for an invalid request.
+pub fn router() -> Router {
+    Router::new()
+        .route("/generate", post(generate))
+        .route("/maze/{id}", get(maze))
+        .route("/maze/{id}/move", post(make_move))
+        .with_state(Mazes::default())
+}
+
+/// Serve `router()` on the listener until the process ends.
+pub async fn serve(listener: TcpListener) -> std::io::Result<()> {
+    axum::serve(listener, router()).await
+}
+
+async fn generate(State(mazes): State<Mazes>, Json(request): Json<MazeRequest>) -> Result<(StatusCode, Json<Value>), ServerError> {
+    // generation is CPU bound, so it runs off the threads serving other requests
+    let grid = tokio::task::spawn_blocking(move || Grid::try_from(request))
+        .await
+        .map_err(|e| ServerError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))??;
+    let maze = serde_json::to_value(&grid).map_err(Error::from)?;
+    let id = lock(&mazes).insert(grid);
+    Ok((StatusCode::CREATED, Json(json!({ "id": id, "maze": maze }))))
+}
+
+async fn maze(State(mazes): State<Mazes>, Path(id): Path<Handle>) -> Result<Json<Value>, ServerError> {
+    let grid = lock(&mazes).get(id)?;
+    let maze = serde_json::to_value(&*lock(&grid)).map_err(Error::from)?;
+    Ok(Json(maze))
+}
+
+async fn make_move(
+    State(mazes): State<Mazes>,
+    Path(id): Path<Handle>,
+    Json(request): Json<MoveRequest>,
+) -> Result<Json<Value>, ServerError> {
+    let grid = lock(&mazes).get(id)?;
+    let mut grid = lock(&grid);
+    let direction = grid.make_move(request.direction)?;
+    let maze = serde_json::to_value(&*grid).map_err(Error::from)?;
+    Ok(Json(json!({ "direction": direction, "maze": maze })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{self, Body};
+    use axum::http::{Method, Request};
+    use tower::ServiceExt;
+
+    async fn call(app: &Router, method: Method, uri: &str, body: &str) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn generate_fetch_and_move() {
+        let app = router();
+        let request = r#"{ "maze_type": "Orthogonal", "width": 5, "height": 1, "algorithm": "RecursiveBacktracker" }"#;
+        let (status, created) = call(&app, Method::POST, "/generate", request).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = created["id"].as_u64().unwrap();
+
+        let (status, maze) = call(&app, Method::GET, &format!("/maze/{}", id), "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(maze["start"], created["maze"]["start"]);
+
+        let mut grid: Grid = serde_json::from_value(maze).unwrap();
+        let open = grid.get_active_cell().unwrap().get_user_facing_open_walls()[0];
+        // the maze is a single row, so there is never a passage up
+        let (status, body) = call(&app, Method::POST, &format!("/maze/{}/move", id), r#"{ "direction": "Up" }"#).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(body["error"].is_string());
+
+        let (status, moved) = call(&app, Method::POST, &format!("/maze/{}/move", id), &format!(r#"{{ "direction": "{}" }}"#, open)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(moved["direction"], open.to_string());
+        assert_eq!(moved["maze"]["move_history"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn errors() {
+        let app = router();
+        let (status, body) = call(&app, Method::GET, "/maze/12345", "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["error"].is_string());
+
+        let request = r#"{ "maze_type": "Orthogonal", "width": 0, "height": 5, "algorithm": "Prims" }"#;
+        let (status, _) = call(&app, Method::POST, "/generate", request).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,185 @@
+use std::time::{Duration, Instant};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::cell::Coordinates;
+use crate::history::{MoveRecord, Replay, ReplayMove};
+
+/// Telemetry for a user's attempt at solving a maze, tracking time spent and how the user moved.
+#[derive(Debug, Clone)]
+pub struct Session {
+    // when the clock was last started or resumed, or None while paused
+    resumed_at: Option<Instant>,
+    // time accumulated before the clock was last paused
+    elapsed_before_pause: Duration,
+    moves: usize,
+    wrong_turns: usize,
+    backtracks: usize,
+    // the path taken, when recording a replay
+    replay: Option<Replay>,
+}
+
+/// A snapshot of a session's telemetry.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SessionStats {
+    /// Time spent solving, excluding time spent paused.
+    pub elapsed: Duration,
+    /// Number of moves made, including backtracking moves and redone moves.
+    pub moves: usize,
+    /// Number of forward moves which led away from the goal.
+    pub wrong_turns: usize,
+    /// Number of moves retracing the path back into an already visited cell.
+    pub backtracks: usize,
+    /// Whether the session's clock is paused.
+    pub paused: bool,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Session {
+    /// Start a new session, with its clock running.
+    pub fn new() -> Self {
+        Session { resumed_at: Some(Instant::now()), elapsed_before_pause: Duration::ZERO, moves: 0, wrong_turns: 0, backtracks: 0, replay: None }
+    }
+
+    /// Start a new session, with its clock running, which also records a replay of the moves made from the
+    /// specified cell.
+    pub fn recording(start: Coordinates) -> Self {
+        Session { replay: Some(Replay::new(start)), ..Self::new() }
+    }
+
+    /// Stop the clock, until `resume` is called. Has no effect when already paused.
+    pub fn pause(&mut self) {
+        if let Some(resumed_at) = self.resumed_at.take() {
+            self.elapsed_before_pause += resumed_at.elapsed();
+        }
+    }
+
+    /// Restart the clock after a `pause`. Has no effect when the clock is running.
+    pub fn resume(&mut self) {
+        if self.resumed_at.is_none() {
+            self.resumed_at = Some(Instant::now());
+        }
+    }
+
+    /// Whether the session's clock is paused.
+    pub fn is_paused(&self) -> bool {
+        self.resumed_at.is_none()
+    }
+
+    /// Time spent solving, excluding time spent paused.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed_before_pause + self.resumed_at.map(|resumed_at| resumed_at.elapsed()).unwrap_or_default()
+    }
+
+    /// Count a move, noting whether it led away from the goal.
+    pub fn record(&mut self, record: &MoveRecord, away_from_goal: bool) {
+        self.moves += 1;
+        self.record_position(record.to);
+        if record.backtrack {
+            self.backtracks += 1;
+        } else if away_from_goal {
+            self.wrong_turns += 1;
+        }
+    }
+
+    /// Note the cell moved to in the replay, if one is being recorded, at the current session time.
+    pub fn record_position(&mut self, to: Coordinates) {
+        let tick = self.elapsed().as_millis() as u64;
+        if let Some(replay) = &mut self.replay {
+            replay.moves.push(ReplayMove { tick, to });
+        }
+    }
+
+    /// The replay recorded so far, if the session is recording one.
+    pub fn replay(&self) -> Option<&Replay> {
+        self.replay.as_ref()
+    }
+
+    /// A snapshot of the session's telemetry so far.
+    pub fn stats(&self) -> SessionStats {
+        SessionStats {
+            elapsed: self.elapsed(),
+            moves: self.moves,
+            wrong_turns: self.wrong_turns,
+            backtracks: self.backtracks,
+            paused: self.is_paused(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::{Coordinates, MazeType};
+    use crate::direction::Direction;
+    use crate::grid::Grid;
+
+    #[test]
+    fn pausing_stops_the_clock() {
+        let mut session = Session::new();
+        session.pause();
+        let paused_at = session.elapsed();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(session.elapsed(), paused_at);
+        assert!(session.stats().paused);
+        session.resume();
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(session.elapsed() > paused_at);
+    }
+
+    #[test]
+    fn moves_are_tracked() {
+        // a corridor from the start at (1, 0) to the goal at (2, 0), with a dead end at (0, 0)
+        let start = Coordinates { x: 1, y: 0 };
+        let mut grid = Grid::new(MazeType::Orthogonal, 3, 1, start, Coordinates { x: 2, y: 0 }, false).unwrap();
+        grid.link(Coordinates { x: 0, y: 0 }, start).unwrap();
+        grid.link(start, Coordinates { x: 2, y: 0 }).unwrap();
+        assert!(grid.stats().is_none());
+
+        grid.start_session();
+        grid.make_move(Direction::Left).unwrap();
+        grid.make_move(Direction::Right).unwrap();
+        grid.make_move(Direction::Right).unwrap();
+        let stats = grid.stats().unwrap();
+        assert_eq!((stats.moves, stats.wrong_turns, stats.backtracks), (3, 1, 1));
+
+        grid.pause();
+        assert!(grid.stats().unwrap().paused);
+        grid.resume();
+        assert!(!grid.stats().unwrap().paused);
+    }
+
+    #[test]
+    fn replays_are_recorded() {
+        let start = Coordinates { x: 0, y: 0 };
+        let mut grid = Grid::new(MazeType::Orthogonal, 3, 1, start, Coordinates { x: 2, y: 0 }, false).unwrap();
+        grid.link(start, Coordinates { x: 1, y: 0 }).unwrap();
+        grid.link(Coordinates { x: 1, y: 0 }, Coordinates { x: 2, y: 0 }).unwrap();
+        assert!(grid.recorded_replay().is_none());
+
+        grid.start_session();
+        grid.make_move(Direction::Right).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        grid.make_move(Direction::Right).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        grid.undo().unwrap();
+        let replay = grid.recorded_replay().unwrap().clone();
+        let path: Vec<Coordinates> = replay.moves.iter().map(|recorded| recorded.to).collect();
+        assert_eq!(path, [Coordinates { x: 1, y: 0 }, Coordinates { x: 2, y: 0 }, Coordinates { x: 1, y: 0 }]);
+        assert!(replay.moves[1].tick >= replay.moves[0].tick + 5);
+
+        let ghost = serde_json::from_str(&serde_json::to_string(&replay).unwrap()).unwrap();
+        assert_eq!(replay, ghost);
+        assert_eq!(grid.replay(&ghost, 0).unwrap(), if replay.moves[0].tick == 0 { path[0] } else { start });
+        assert_eq!(grid.replay(&ghost, replay.moves[1].tick).unwrap(), path[1]);
+        assert_eq!(grid.replay(&ghost, replay.duration() + 1000).unwrap(), path[2]);
+        let smaller = Grid::new(MazeType::Orthogonal, 2, 1, start, Coordinates { x: 1, y: 0 }, false).unwrap();
+        assert!(smaller.replay(&ghost, replay.moves[1].tick).is_err());
+    }
+}
@@ -0,0 +1,415 @@
+use alloc::collections::VecDeque;
+use core::f64::consts::{PI, TAU};
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::cell::{Cell, Coordinates};
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::render::{self, Point};
+use crate::trace::{trace_span, trace_timed};
+use crate::prelude::*;
+
+/// A classic strategy for solving a maze, which `Grid::simulate_solver` plays out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SolverStrategy {
+    /// Keep the left hand on the wall, turning left wherever possible.
+    LeftHand,
+    /// Keep the right hand on the wall, turning right wherever possible.
+    RightHand,
+    /// Head toward the goal until blocked, then follow the wall with the right hand, counting the turns made,
+    /// until facing toward the goal again with every turn undone.
+    Pledge,
+    /// Fill in every dead end, and then every cell left a dead end by the filling, until only the routes from
+    /// the start to the goal remain. Unlike the other strategies this surveys the whole maze rather than
+    /// walking it.
+    DeadEndFilling,
+    /// Walk the maze marking each passage on every traversal, never entering a passage marked twice, turning
+    /// back on reaching an already marked junction by a new passage, and otherwise preferring unmarked passages.
+    Tremaux,
+}
+
+/// The cells a solving strategy passed through, from the start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SolverRun {
+    /// Every cell entered in turn, beginning with the start and, when solved, ending with the goal. Dead-end
+    /// filling enters no cells, so its path is instead the shortest route through the cells left unfilled.
+    pub path: Vec<Coordinates>,
+    /// Number of moves made, one fewer than the cells on the path, or for dead-end filling the number of
+    /// cells filled in.
+    pub steps: usize,
+    /// Whether the goal was reached. A hand on the wall can wander forever in a maze with loops, so those runs
+    /// are cut short after a number of moves proportional to the size of the maze.
+    pub solved: bool,
+}
+
+// Copies of the maze taken after each step of a run, with the solver's progress marked on the cells
+struct Recorder {
+    base: Grid,
+    frames: Vec<Grid>,
+}
+
+impl Recorder {
+    fn new(grid: &Grid) -> Self {
+        let mut base = grid.clone();
+        base.capture_steps = false;
+        base.generation_steps = None;
+        base.generation_step_details = None;
+        base.solving_steps = None;
+        base.event_log = None;
+        for cell in base.cells.iter_mut().flatten() {
+            cell.is_active = false;
+            cell.is_visited = false;
+            cell.has_been_visited = false;
+            cell.on_solution_path = false;
+        }
+        Recorder { base, frames: Vec::new() }
+    }
+
+    fn capture(&mut self, mark: impl Fn(&mut Cell)) {
+        let mut frame = self.base.clone();
+        frame.cells.iter_mut().flatten().for_each(mark);
+        self.frames.push(frame);
+    }
+}
+
+// The heading from one point to another, counterclockwise from rightward as the maze is drawn
+fn heading(from: Point, to: Point) -> f64 {
+    // rendered y grows downward
+    (from.y - to.y).atan2(to.x - from.x)
+}
+
+// The angle turned counterclockwise from one heading to another, from just over zero through a full turn
+fn counterclockwise(from: f64, to: f64) -> f64 {
+    let mut angle = to - from;
+    while angle <= 1e-9 {
+        angle += TAU;
+    }
+    while angle > TAU + 1e-9 {
+        angle -= TAU;
+    }
+    angle
+}
+
+// The signed turn from one heading to another, from a half turn clockwise through a half turn counterclockwise
+fn turn(from: f64, to: f64) -> f64 {
+    let angle = counterclockwise(from, to);
+    if angle > PI { angle - TAU } else { angle }
+}
+
+/// Play out the strategy from the maze's start until it reaches the goal or gives up.
+pub fn simulate(grid: &Grid, strategy: SolverStrategy) -> Result<SolverRun, Error> {
+    run(grid, strategy, None)
+}
+
+/// Play out the strategy as `simulate` does, also returning a copy of the maze after every step. Each copy
+/// marks the walker's cell active, its current trail visited and every cell it has entered as having been
+/// visited; for dead-end filling, each filled cell is marked as having been visited, and a last copy marks the
+/// route found on the solution path.
+pub fn capture(grid: &Grid, strategy: SolverStrategy) -> Result<(SolverRun, Vec<Grid>), Error> {
+    let mut recorder = Recorder::new(grid);
+    let run = run(grid, strategy, Some(&mut recorder))?;
+    Ok((run, recorder.frames))
+}
+
+fn run(grid: &Grid, strategy: SolverStrategy, recorder: Option<&mut Recorder>) -> Result<SolverRun, Error> {
+    trace_span!("solve", strategy = ?strategy, recording = recorder.is_some());
+    trace_timed!(match strategy {
+        SolverStrategy::DeadEndFilling => fill_dead_ends(grid, recorder),
+        _ => walk(grid, strategy, recorder),
+    }, strategy = ?strategy, "maze solved")
+}
+
+// The passage between two cells, the same in either direction
+fn passage(a: Coordinates, b: Coordinates) -> (Coordinates, Coordinates) {
+    (a.min(b), a.max(b))
+}
+
+fn walk(grid: &Grid, strategy: SolverStrategy, mut recorder: Option<&mut Recorder>) -> Result<SolverRun, Error> {
+    let center = |coords| render::center(grid, coords, 1.0);
+    let cells = grid.iter_cells().count();
+    // Tremaux never walks a passage more than twice, so it always ends by itself
+    let limit = if strategy == SolverStrategy::Tremaux { usize::MAX } else { 4 * cells + 4 };
+    let goal = grid.goal_coords;
+    let mut current = grid.start_coords;
+    let mut path = vec![current];
+    // the Pledge algorithm's preferred heading, and the turns made since leaving it while following a wall
+    let preferred = heading(center(current)?, center(goal)?);
+    let mut following = false;
+    let mut turned = 0.0;
+    // the heading of the previous move; at the start, as if having just moved toward the goal
+    let mut moving = preferred;
+    // the number of times Tremaux has walked each passage
+    let mut marks: HashMap<(Coordinates, Coordinates), u8> = HashMap::new();
+    // the cells walked through and not since backtracked over, and every cell entered, for the recorded steps
+    let mut trail = vec![current];
+    let mut entered: HashSet<Coordinates> = HashSet::from([current]);
+    let mut record = |current: Coordinates, trail: &[Coordinates], entered: &HashSet<Coordinates>| {
+        if let Some(recorder) = recorder.as_mut() {
+            let trail: HashSet<Coordinates> = trail.iter().copied().collect();
+            recorder.capture(|cell| {
+                cell.is_active = cell.coords == current;
+                cell.is_visited = trail.contains(&cell.coords);
+                cell.has_been_visited = entered.contains(&cell.coords);
+            });
+        }
+    };
+    record(current, &trail, &entered);
+
+    while current != goal && path.len() <= limit {
+        let here = center(current)?;
+        let cell = grid.get(current)?;
+        let mut open: Vec<(Coordinates, f64)> = Vec::new();
+        for linked in &cell.linked {
+            open.push((linked, heading(here, center(linked)?)));
+        }
+        // neighbors in a fixed order, so that exact ties between headings resolve the same way every time
+        open.sort_by_key(|&(coords, _)| coords);
+        let back = moving + PI;
+        let right_hand = |from: f64| open.iter().min_by(|a, b| counterclockwise(from, a.1).total_cmp(&counterclockwise(from, b.1))).copied();
+        let next = match strategy {
+            SolverStrategy::RightHand => right_hand(back),
+            SolverStrategy::LeftHand => open.iter().min_by(|a, b| counterclockwise(a.1, back).total_cmp(&counterclockwise(b.1, back))).copied(),
+            SolverStrategy::Tremaux => {
+                let marked = |to: Coordinates| marks.get(&passage(current, to)).copied().unwrap_or(0);
+                let entry = path.len().checked_sub(2).map(|previous| path[previous]);
+                let others: Vec<(Coordinates, f64)> = open.iter().copied().filter(|&(coords, _)| Some(coords) != entry).collect();
+                let back = entry.and_then(|entry| open.iter().copied().find(|&(coords, _)| coords == entry));
+                match back {
+                    // arriving by a new passage at a junction already marked, so go back the way we came
+                    Some(back) if marked(back.0) == 1 && others.iter().any(|&(coords, _)| marked(coords) > 0) => Some(back),
+                    _ => others
+                        .iter()
+                        .copied()
+                        .filter(|&(coords, _)| marked(coords) < 2)
+                        .min_by_key(|&(coords, _)| marked(coords))
+                        .or(back.filter(|&(coords, _)| marked(coords) < 2)),
+                }
+            }
+            SolverStrategy::DeadEndFilling => None,
+            SolverStrategy::Pledge => {
+                if !following {
+                    // the side of the cell facing most nearly toward the goal, if it is open
+                    let mut facing: Option<(Coordinates, f64)> = None;
+                    for neighbor in cell.neighbors_by_direction.values() {
+                        if grid.get(neighbor).is_ok() {
+                            let direction = heading(here, center(neighbor)?);
+                            if facing.is_none_or(|(_, best)| turn(preferred, direction).abs() < turn(preferred, best).abs()) {
+                                facing = Some((neighbor, direction));
+                            }
+                        }
+                    }
+                    match facing.filter(|(neighbor, _)| cell.linked.contains(neighbor)) {
+                        Some(ahead) => Some(ahead),
+                        None => {
+                            // blocked, so turn away keeping the wall on the right
+                            following = true;
+                            turned = 0.0;
+                            right_hand(preferred)
+                        }
+                    }
+                } else {
+                    right_hand(back)
+                }
+            }
+        };
+        let Some((next, direction)) = next else {
+            break;
+        };
+        if following {
+            turned += turn(moving, direction);
+            // back to facing the goal, having undone every turn, so stop following the wall
+            if turned.abs() < PI / 4.0 {
+                following = false;
+            }
+        }
+        if strategy == SolverStrategy::Tremaux {
+            *marks.entry(passage(current, next)).or_default() += 1;
+        }
+        moving = direction;
+        current = next;
+        path.push(current);
+        if trail.len() > 1 && trail[trail.len() - 2] == current {
+            trail.pop();
+        } else {
+            trail.push(current);
+        }
+        entered.insert(current);
+        record(current, &trail, &entered);
+    }
+    let steps = path.len() - 1;
+    Ok(SolverRun { path, steps, solved: current == goal })
+}
+
+fn fill_dead_ends(grid: &Grid, mut recorder: Option<&mut Recorder>) -> Result<SolverRun, Error> {
+    let (start, goal) = (grid.start_coords, grid.goal_coords);
+    let mut filled: HashSet<Coordinates> = HashSet::new();
+    let fillable = |coords: Coordinates, filled: &HashSet<Coordinates>| {
+        coords != start
+            && coords != goal
+            && !filled.contains(&coords)
+            && grid.get(coords).is_ok_and(|cell| cell.linked.iter().filter(|linked| !filled.contains(linked)).count() <= 1)
+    };
+    let mut record = |filled: &HashSet<Coordinates>, solution: &[Coordinates]| {
+        if let Some(recorder) = recorder.as_mut() {
+            recorder.capture(|cell| {
+                cell.has_been_visited = filled.contains(&cell.coords);
+                cell.on_solution_path = solution.contains(&cell.coords);
+            });
+        }
+    };
+    record(&filled, &[]);
+
+    let mut queue: VecDeque<Coordinates> = grid.iter_cells().map(|cell| cell.coords).filter(|&coords| fillable(coords, &filled)).collect();
+    while let Some(coords) = queue.pop_front() {
+        // a cell can be queued again as its last open neighbor is filled in
+        if !fillable(coords, &filled) {
+            continue;
+        }
+        filled.insert(coords);
+        record(&filled, &[]);
+        for linked in &grid.get(coords)?.linked {
+            if fillable(linked, &filled) {
+                queue.push_back(linked);
+            }
+        }
+    }
+
+    // the shortest route left through the unfilled cells, which is the only one in a perfect maze
+    let mut previous: HashMap<Coordinates, Coordinates> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(coords) = queue.pop_front() {
+        if coords == goal {
+            break;
+        }
+        let mut next: Vec<Coordinates> = grid.get(coords)?.linked
+            .iter()
+            .filter(|linked| !filled.contains(linked) && *linked != start && !previous.contains_key(linked))
+            .collect();
+        next.sort();
+        for linked in next {
+            previous.insert(linked, coords);
+            queue.push_back(linked);
+        }
+    }
+    let solved = start == goal || previous.contains_key(&goal);
+    let mut path = vec![start];
+    if solved && start != goal {
+        path = vec![goal];
+        while let Some(&coords) = previous.get(path.last().unwrap_or(&start)) {
+            path.push(coords);
+        }
+        path.reverse();
+    }
+    if solved {
+        record(&filled, &path);
+    }
+    Ok(SolverRun { path, steps: filled.len(), solved })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::MazeType;
+
+    fn assert_valid(grid: &Grid, run: &SolverRun) {
+        assert_eq!(run.path[0], grid.start_coords);
+        assert_eq!(run.steps, run.path.len() - 1);
+        for pair in run.path.windows(2) {
+            assert!(grid.get(pair[0]).unwrap().linked.contains(&pair[1]), "{:?} does not lead to {:?}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn wall_followers_solve_perfect_mazes() {
+        for &maze_type in MazeType::all() {
+            let json = format!(r#"{{ "maze_type": "{:?}", "width": 10, "height": 10, "algorithm": "Wilsons" }}"#, maze_type);
+            let grid = Grid::try_from(json).unwrap();
+            let solution = grid.solution_path_order().len() - 1;
+            for strategy in [SolverStrategy::LeftHand, SolverStrategy::RightHand] {
+                let run = grid.simulate_solver(strategy).unwrap();
+                assert_valid(&grid, &run);
+                assert!(run.solved, "{:?} {:?}", maze_type, strategy);
+                assert_eq!(run.path.last(), Some(&grid.goal_coords));
+                // every passage is walked at most once in each direction
+                assert!(run.steps >= solution && run.steps <= 2 * (grid.iter_cells().count() - 1), "{:?} {:?}", maze_type, strategy);
+            }
+            let pledge = grid.simulate_solver(SolverStrategy::Pledge).unwrap();
+            assert_valid(&grid, &pledge);
+        }
+    }
+
+    #[test]
+    fn hands_turn_opposite_ways() {
+        // a junction at (1, 1) with passages left, right and up from the start below it
+        let at = |x, y| Coordinates { x, y };
+        let mut grid = Grid::new(MazeType::Orthogonal, 3, 3, at(1, 2), at(1, 0), false).unwrap();
+        for (a, b) in [(at(1, 2), at(1, 1)), (at(1, 1), at(0, 1)), (at(1, 1), at(2, 1)), (at(1, 1), at(1, 0))] {
+            grid.link(a, b).unwrap();
+        }
+        assert_eq!(grid.simulate_solver(SolverStrategy::LeftHand).unwrap().path[2], at(0, 1));
+        assert_eq!(grid.simulate_solver(SolverStrategy::RightHand).unwrap().path[2], at(2, 1));
+        // Pledge heads straight for the goal
+        let pledge = grid.simulate_solver(SolverStrategy::Pledge).unwrap();
+        assert_eq!(pledge.path, vec![at(1, 2), at(1, 1), at(1, 0)]);
+        assert!(pledge.solved);
+    }
+
+    #[test]
+    fn filling_and_tremaux_solve_and_record_steps() {
+        for &maze_type in MazeType::all() {
+            let json = format!(r#"{{ "maze_type": "{:?}", "width": 10, "height": 10, "algorithm": "Wilsons" }}"#, maze_type);
+            let mut grid = Grid::try_from(json).unwrap();
+            let solution = grid.solution_path_order();
+
+            // in a perfect maze, filling every dead end leaves exactly the solution
+            let filling = grid.capture_solver(SolverStrategy::DeadEndFilling).unwrap();
+            assert!(filling.solved, "{:?}", maze_type);
+            assert_eq!(filling.path, solution, "{:?}", maze_type);
+            assert_eq!(filling.steps, grid.iter_cells().count() - solution.len(), "{:?}", maze_type);
+            let steps = grid.solving_steps.as_ref().unwrap();
+            assert_eq!(steps.len(), filling.steps + 2);
+            assert!(steps[0].iter_cells().all(|cell| !cell.has_been_visited && !cell.on_solution_path));
+            let last = steps.last().unwrap();
+            assert!(last.iter_cells().all(|cell| cell.on_solution_path != cell.has_been_visited), "{:?}", maze_type);
+
+            let tremaux = grid.capture_solver(SolverStrategy::Tremaux).unwrap();
+            assert_valid(&grid, &tremaux);
+            assert!(tremaux.solved, "{:?}", maze_type);
+            let steps = grid.solving_steps.as_ref().unwrap();
+            assert_eq!(steps.len(), tremaux.path.len());
+            for (step, &coords) in steps.iter().zip(&tremaux.path) {
+                assert_eq!(step.iter_cells().filter(|cell| cell.is_active).map(|cell| cell.coords).collect::<Vec<_>>(), vec![coords]);
+            }
+            // the trail left at the goal, having backed out of every dead end, is the solution
+            let trail: HashSet<Coordinates> = steps.last().unwrap().iter_cells().filter(|cell| cell.is_visited).map(|cell| cell.coords).collect();
+            assert_eq!(trail, solution.iter().copied().collect(), "{:?}", maze_type);
+        }
+    }
+
+    #[test]
+    fn tremaux_and_filling_handle_loops() {
+        // a fully open 4x4 grid, full of loops
+        let at = |x, y| Coordinates { x, y };
+        let mut grid = Grid::new(MazeType::Orthogonal, 4, 4, at(0, 0), at(3, 3), false).unwrap();
+        for y in 0..4 {
+            for x in 0..4 {
+                if x < 3 {
+                    grid.link(at(x, y), at(x + 1, y)).unwrap();
+                }
+                if y < 3 {
+                    grid.link(at(x, y), at(x, y + 1)).unwrap();
+                }
+            }
+        }
+        let tremaux = grid.simulate_solver(SolverStrategy::Tremaux).unwrap();
+        assert_valid(&grid, &tremaux);
+        assert!(tremaux.solved);
+        let filling = grid.simulate_solver(SolverStrategy::DeadEndFilling).unwrap();
+        assert!(filling.solved);
+        assert_eq!(filling.steps, 0);
+        assert_eq!(filling.path.len(), 7);
+    }
+}
@@ -0,0 +1,158 @@
+use alloc::collections::BTreeSet;
+
+use crate::cell::{Cell, Coordinates};
+use crate::grid::Grid;
+use crate::prelude::*;
+
+/// The steps captured while generating a maze (see `Grid::generation_steps`), stored as a copy of the maze at
+/// the first step followed by just the cells each later step changed, rather than a copy of the whole maze per
+/// step. Capturing a 100x100 maze thus takes a few megabytes rather than the gigabytes its ten thousand
+/// copies would, while any step can still be rebuilt as a `Grid` with `get`, or all of them in turn with `iter`.
+#[derive(Debug, Clone, Default)]
+pub struct GenerationSteps {
+    // the maze at the first step, without steps of its own
+    first: Option<Box<Grid>>,
+    // the cells changed by each step after the first, as they were after the step
+    changes: Vec<Vec<Cell>>,
+    // the cells as they were at the latest step, in the order of the maze's cells
+    latest: Vec<Option<Cell>>,
+    // the maze's revision when the latest step was captured
+    revision: u64,
+}
+
+impl GenerationSteps {
+    /// No steps, to which steps are added as a maze is generated.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of steps captured.
+    pub fn len(&self) -> usize {
+        self.first.as_ref().map_or(0, |_| self.changes.len() + 1)
+    }
+
+    /// Whether no steps were captured.
+    pub fn is_empty(&self) -> bool {
+        self.first.is_none()
+    }
+
+    /// The maze as it was at the step at `index`, or None if there are fewer steps. Rebuilding a step replays
+    /// every step before it, so to visit the steps in order use `iter` instead.
+    pub fn get(&self, index: usize) -> Option<Grid> {
+        if index >= self.len() {
+            return None;
+        }
+        let mut grid = self.first.as_deref()?.clone();
+        self.replay(&mut grid, 0, index);
+        Some(grid)
+    }
+
+    /// The maze as it was at each step, in order.
+    pub fn iter(&self) -> impl Iterator<Item = Grid> + '_ {
+        let mut current = self.first.as_deref().cloned();
+        (0..self.len()).filter_map(move |index| {
+            let grid = current.as_mut()?;
+            self.replay(grid, index.saturating_sub(1), index);
+            Some(grid.clone())
+        })
+    }
+
+    /// Approximate number of bytes used by the steps, including the copy of the maze at the first step.
+    pub fn memory_footprint(&self) -> usize {
+        let first = self.first.as_ref().map_or(0, |grid| grid.memory_footprint());
+        let cells = |cells: &[Cell]| cells.iter().map(|cell| core::mem::size_of::<Cell>() + cell.heap_footprint()).sum::<usize>();
+        let latest: usize = self.latest.iter().flatten().map(|cell| cell.heap_footprint()).sum();
+        let changes: usize = self.changes.iter().map(|step| cells(step)).sum();
+        core::mem::size_of::<Self>()
+            + first
+            + self.latest.capacity() * core::mem::size_of::<Option<Cell>>()
+            + latest
+            + self.changes.capacity() * core::mem::size_of::<Vec<Cell>>()
+            + changes
+    }
+
+    // The maze at the first step
+    #[cfg(feature = "json")]
+    pub(crate) fn first(&self) -> Option<&Grid> {
+        self.first.as_deref()
+    }
+
+    // The cells changed by the step at `index`, as they were after it; none for the first step or past the last
+    #[cfg(feature = "json")]
+    pub(crate) fn changed_cells(&self, index: usize) -> &[Cell] {
+        index.checked_sub(1).and_then(|index| self.changes.get(index)).map_or(&[], Vec::as_slice)
+    }
+
+    // The cell as it was at the latest step
+    pub(crate) fn latest_cell(&self, grid: &Grid, coords: Coordinates) -> Option<&Cell> {
+        self.latest.get(grid.get_flattened_index(coords.x, coords.y))?.as_ref()
+    }
+
+    // Move a maze at the step at `from` on to the step at `to`
+    pub(crate) fn replay(&self, grid: &mut Grid, from: usize, to: usize) {
+        for step in self.changes.iter().take(to).skip(from) {
+            for cell in step {
+                if let Ok(target) = grid.get_mut(cell.coords) {
+                    target.clone_from(cell);
+                }
+            }
+        }
+    }
+
+    // Capture the maze as the next step, storing the cells changed since the latest step: those the algorithm
+    // reports changing and those whose passages were opened or closed
+    pub(crate) fn push(&mut self, grid: &Grid, changed_cells: &HashSet<Coordinates>) {
+        if self.first.is_none() {
+            let mut first = grid.clone();
+            first.capture_steps = false;
+            first.generation_steps = None;
+            first.generation_step_details = None;
+            self.first = Some(Box::new(first));
+            self.latest = grid.cells.clone();
+        } else {
+            let mut candidates: BTreeSet<Coordinates> = changed_cells.iter().copied().collect();
+            candidates.extend(grid.changed_cells_since(self.revision).iter().map(|cell| cell.coords));
+            let mut changes = Vec::new();
+            for coords in candidates {
+                let index = grid.get_flattened_index(coords.x, coords.y);
+                let (Ok(cell), Some(Some(latest))) = (grid.get(coords), self.latest.get_mut(index)) else {
+                    continue;
+                };
+                if cell != latest {
+                    latest.clone_from(cell);
+                    changes.push(cell.clone());
+                }
+            }
+            self.changes.push(changes);
+        }
+        self.revision = grid.revision;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_are_rebuilt_from_changed_cells() {
+        let json = r#"{ "maze_type": "Orthogonal", "width": 100, "height": 100, "algorithm": "RecursiveBacktracker", "capture_steps": true }"#;
+        let grid = Grid::try_from(json).unwrap();
+        let steps = grid.generation_steps.as_ref().unwrap();
+        assert!(steps.len() >= 10_000);
+        assert!(steps.memory_footprint() < 64 * 1024 * 1024, "steps take {} bytes", steps.memory_footprint());
+        // each step carves one more passage, or backtracks without carving
+        let edges: Vec<usize> = (0..steps.len()).step_by(997).map(|index| steps.get(index).unwrap().count_edges()).collect();
+        assert!(edges.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        let json = r#"{ "maze_type": "Sigma", "width": 8, "height": 7, "algorithm": "Wilsons", "capture_steps": true }"#;
+        let grid = Grid::try_from(json).unwrap();
+        let steps = grid.generation_steps.as_ref().unwrap();
+        for (index, step) in steps.iter().enumerate() {
+            let rebuilt = steps.get(index).unwrap();
+            assert!(step.cells == rebuilt.cells && step.generation_steps.is_none());
+        }
+        let last = steps.get(steps.len() - 1).unwrap();
+        assert_eq!(last.count_edges(), grid.count_edges());
+        assert!(steps.get(steps.len()).is_none());
+    }
+}
@@ -0,0 +1,178 @@
+use rand::seq::SliceRandom;
+#[cfg(feature = "serde")]
+use serde::{ Serialize, Deserialize };
+
+use crate::algorithms::kruskals::{self, DisjointSet};
+use crate::cell::{Coordinates, MazeType};
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::prelude::*;
+
+/// Symmetry imposed on a generated maze, so that every player faces the same maze from their own side, as in
+/// competitive play. One sector of the maze is mirrored onto the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Symmetry {
+    /// The right half mirrors the left half.
+    Horizontal,
+    /// The bottom half mirrors the top half.
+    Vertical,
+    /// The bottom half is the top half turned half way around.
+    Rotational180,
+    /// Each quarter mirrors the top left quarter, across both axes.
+    Quad,
+}
+
+impl Symmetry {
+    // Whether the cell lies in the sector copied to the rest of the maze. The middle column or row of a maze
+    // of odd width or height lies in no sector, so copies of the sector never overlap
+    fn in_sector(self, coords: Coordinates, width: usize, height: usize) -> bool {
+        let (half_width, half_height) = (width / 2, height / 2);
+        match self {
+            Symmetry::Horizontal => coords.x < half_width,
+            Symmetry::Vertical | Symmetry::Rotational180 => coords.y < half_height,
+            Symmetry::Quad => coords.x < half_width && coords.y < half_height,
+        }
+    }
+
+    // The maps from the sector onto each copy of it, starting with the sector itself
+    fn images(self, coords: Coordinates, width: usize, height: usize) -> Vec<Coordinates> {
+        let mirrored_x = Coordinates { x: width - 1 - coords.x, y: coords.y };
+        let mirrored_y = Coordinates { x: coords.x, y: height - 1 - coords.y };
+        let rotated = Coordinates { x: width - 1 - coords.x, y: height - 1 - coords.y };
+        match self {
+            Symmetry::Horizontal => vec![coords, mirrored_x],
+            Symmetry::Vertical => vec![coords, mirrored_y],
+            Symmetry::Rotational180 => vec![coords, rotated],
+            Symmetry::Quad => vec![coords, mirrored_x, mirrored_y, rotated],
+        }
+    }
+
+    /// Rebuild a generated maze with this symmetry. The passages of the maze's first sector (its left half,
+    /// top half or top left quarter) are joined into a single tree, copied exactly to every other sector, and
+    /// the sectors then joined by as few passages as keep the maze perfect, across the seams between sectors
+    /// or through the middle column or row of a maze of odd width or height. Only single layer Orthogonal
+    /// mazes can be made symmetric.
+    pub fn apply(self, grid: &mut Grid) -> Result<(), Error> {
+        if grid.maze_type != MazeType::Orthogonal || grid.layers > 1 {
+            return Err(Error::InvalidSymmetry {
+                reason: format!("only single layer Orthogonal mazes can be made symmetric, not {:?}", grid.maze_type),
+            });
+        }
+        let (width, height) = (grid.width, grid.height);
+        let mut rng = grid.forked_rng();
+        let mut edges = kruskals::edges(grid);
+        let in_sector = |(a, b): &(Coordinates, Coordinates)| self.in_sector(*a, width, height) && self.in_sector(*b, width, height);
+
+        // the sector's own passages first, then other edges within the sector to join them into one tree
+        let linked = |(a, b): &(Coordinates, Coordinates)| grid.get(*a).is_ok_and(|cell| cell.linked.contains(b));
+        let mut sector_edges: Vec<_> = edges.iter().copied().filter(|edge| in_sector(edge) && linked(edge)).collect();
+        let mut others: Vec<_> = edges.iter().copied().filter(|edge| in_sector(edge) && !linked(edge)).collect();
+        others.shuffle(&mut rng);
+        sector_edges.extend(others);
+        let mut sector = DisjointSet::new();
+        for cell in grid.iter_cells() {
+            sector.make_set(cell.coords);
+        }
+        sector_edges.retain(|&(a, b)| sector.union(a, b));
+
+        // then each copy of the sector tree, which never overlap so are kept whole, then whichever edges join the
+        // sectors, as a single spanning tree
+        let mut candidates: Vec<(Coordinates, Coordinates)> = Vec::new();
+        for image in 0..self.images(Coordinates { x: 0, y: 0 }, width, height).len() {
+            candidates.extend(sector_edges.iter().map(|&(a, b)| {
+                (self.images(a, width, height)[image], self.images(b, width, height)[image])
+            }));
+        }
+        edges.shuffle(&mut rng);
+        candidates.extend(edges);
+
+        let passages: Vec<(Coordinates, Coordinates)> = grid
+            .iter_cells()
+            .flat_map(|cell| cell.linked.iter().map(move |linked| (cell.coords, linked)))
+            .filter(|(a, b)| a < b)
+            .collect();
+        for (a, b) in passages {
+            grid.unlink(a, b)?;
+        }
+        let mut tree = DisjointSet::new();
+        for cell in grid.iter_cells() {
+            tree.make_set(cell.coords);
+        }
+        for (a, b) in candidates {
+            if tree.union(a, b) {
+                grid.link(a, b)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::MazeAlgorithm;
+
+    #[test]
+    fn symmetric_mazes_are_perfect_and_mirrored() {
+        for symmetry in [Symmetry::Horizontal, Symmetry::Vertical, Symmetry::Rotational180, Symmetry::Quad] {
+            for (width, height) in [(10, 8), (9, 7)] {
+                let json = format!(
+                    r#"{{ "maze_type": "Orthogonal", "width": {}, "height": {}, "algorithm": "Wilsons", "symmetry": "{:?}", "seed": 42 }}"#,
+                    width, height, symmetry,
+                );
+                let grid = Grid::try_from(json).unwrap();
+                assert!(grid.is_perfect_maze().unwrap(), "{:?} {}x{}", symmetry, width, height);
+
+                // which copy of the sector each cell lies in, if any
+                let copies = symmetry.images(Coordinates { x: 0, y: 0 }, width, height).len();
+                let copy_of = |coords: Coordinates| (0..copies).find(|&image| {
+                    grid.iter_cells()
+                        .filter(|cell| symmetry.in_sector(cell.coords, width, height))
+                        .any(|cell| symmetry.images(cell.coords, width, height)[image] == coords)
+                });
+                // within each copy, the passages are exactly those of the sector
+                for cell in grid.iter_cells().filter(|cell| symmetry.in_sector(cell.coords, width, height)) {
+                    for neighbor in cell.neighbors().into_iter().filter(|&neighbor| symmetry.in_sector(neighbor, width, height)) {
+                        let linked = cell.linked.contains(&neighbor);
+                        let images = symmetry.images(cell.coords, width, height).into_iter().zip(symmetry.images(neighbor, width, height));
+                        for (image, neighbor_image) in images {
+                            assert_eq!(grid.get(image).unwrap().linked.contains(&neighbor_image), linked, "{:?} {}x{}", symmetry, width, height);
+                        }
+                    }
+                }
+                // every other passage joins the copies, across a seam or through the middle column or row
+                let mut joins = 0;
+                for (a, b) in grid.iter_linked_pairs() {
+                    match (copy_of(a.coords), copy_of(b.coords)) {
+                        (Some(first), Some(second)) if first == second => {}
+                        _ => joins += 1,
+                    }
+                }
+                assert!(joins >= copies - 1, "{:?} {}x{}", symmetry, width, height);
+            }
+        }
+    }
+
+    #[test]
+    fn only_orthogonal_mazes_are_symmetric() {
+        let mut sigma = Grid::new(MazeType::Sigma, 6, 6, Coordinates { x: 0, y: 0 }, Coordinates { x: 5, y: 5 }, false).unwrap();
+        MazeAlgorithm::Prims.generate(&mut sigma).unwrap();
+        assert!(matches!(Symmetry::Horizontal.apply(&mut sigma), Err(Error::InvalidSymmetry { .. })));
+    }
+
+    #[test]
+    fn symmetry_cannot_be_combined_with_constraints() {
+        let json = r#"
+        {
+            "maze_type": "Orthogonal",
+            "width": 10,
+            "height": 8,
+            "algorithm": "Prims",
+            "symmetry": "Horizontal",
+            "constraints": { "passages": [[{ "x": 0, "y": 0 }, { "x": 1, "y": 0 }]] }
+        }
+        "#;
+        assert!(matches!(Grid::try_from(json), Err(Error::InvalidSymmetry { .. })));
+    }
+}
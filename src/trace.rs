@@ -0,0 +1,89 @@
+//! Instrumentation for the optional `tracing` feature. Without the feature the macros expand to nothing, so
+//! their arguments are never evaluated.
+
+/// Enter an info-level span, named and with fields as for `tracing::info_span!`, until the end of the
+/// enclosing block.
+macro_rules! trace_span {
+    ($($args:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!($($args)*).entered();
+    };
+}
+
+/// Evaluate a `Result`-valued expression, then emit an info-level event with how long it took in
+/// milliseconds and whether it succeeded, along with further fields and message as for `tracing::info!`.
+/// Without `std` there is no clock, so no event.
+macro_rules! trace_timed {
+    ($body:expr, $($args:tt)*) => {{
+        #[cfg(all(feature = "tracing", feature = "std"))]
+        let started = std::time::Instant::now();
+        let result = $body;
+        #[cfg(all(feature = "tracing", feature = "std"))]
+        tracing::info!(elapsed_ms = started.elapsed().as_secs_f64() * 1000.0, succeeded = result.is_ok(), $($args)*);
+        result
+    }};
+}
+
+pub(crate) use trace_span;
+pub(crate) use trace_timed;
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use crate::grid::Grid;
+    use crate::solver::SolverStrategy;
+
+    // Records the names of spans created and fields of events emitted
+    #[derive(Default, Clone)]
+    struct Recorder {
+        spans: Arc<Mutex<Vec<String>>>,
+        events: Arc<Mutex<Vec<String>>>,
+    }
+
+    struct Fields(String);
+
+    impl tracing::field::Visit for Fields {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.push_str(&format!("{}={:?} ", field.name(), value));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(span.metadata().name().to_string());
+            Id::from_u64(spans.len() as u64)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(String::new());
+            event.record(&mut fields);
+            self.events.lock().unwrap().push(fields.0);
+        }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn generation_and_solving_are_traced() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let grid = Grid::try_from(r#"{ "maze_type": "Orthogonal", "width": 6, "height": 6, "algorithm": "Kruskals" }"#).unwrap();
+            grid.simulate_solver(SolverStrategy::LeftHand).unwrap();
+        });
+        let spans = recorder.spans.lock().unwrap();
+        for name in ["generate", "algorithm", "carve", "finalize", "solve"] {
+            assert!(spans.iter().any(|span| span == name), "no {} span in {:?}", name, spans);
+        }
+        let events = recorder.events.lock().unwrap();
+        assert!(events.iter().any(|event| event.contains("algorithm=\"Kruskals\"") && event.contains("elapsed_ms=")), "{:?}", events);
+        assert!(events.iter().any(|event| event.contains("maze solved")), "{:?}", events);
+    }
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "json")]
+
 use mazer::{self, generate};
 
 #[test]